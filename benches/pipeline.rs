@@ -0,0 +1,149 @@
+//! Criterion benchmarks for the hot paths of a `wbi get`: page parsing, grouping and
+//! summaries, LOESS smoothing and SVG rendering.
+//!
+//! Every benchmark runs on the same synthetic 100k-row dataset (1000 series × 100 years).
+//! Run with `cargo bench`; pass a filter to run a single group, e.g. `cargo bench -- render`.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use wbi_rs::models::{DataPoint, Entry, Meta};
+use wbi_rs::viz::loess::loess_series;
+use wbi_rs::viz::{LegendMode, PlotKind, PlotOptions};
+use wbi_rs::{stats, viz};
+
+const COUNTRIES: usize = 200;
+const INDICATORS: usize = 5;
+const YEARS: i32 = 100;
+const FIRST_YEAR: i32 = 1925;
+
+/// Deterministic rows in API order (indicator, country, year descending), ~10% missing.
+fn synthetic_points() -> Vec<DataPoint> {
+    let mut out = Vec::with_capacity(COUNTRIES * INDICATORS * YEARS as usize);
+    for ind in 0..INDICATORS {
+        for c in 0..COUNTRIES {
+            for y in (0..YEARS).rev() {
+                let seed = (ind * 7919 + c * 104_729) as f64 + y as f64;
+                let value = if (c + y as usize).is_multiple_of(10) {
+                    None
+                } else {
+                    Some(1.0e6 * (1.0 + ind as f64) + 1.0e3 * (seed * 0.37).sin() + y as f64)
+                };
+                out.push(DataPoint {
+                    indicator_id: format!("IND.{ind}"),
+                    indicator_name: format!("Indicator {ind}"),
+                    country_id: format!("C{c}"),
+                    country_name: format!("Country {c:03}"),
+                    country_iso3: format!("C{c:02}"),
+                    year: FIRST_YEAR + y,
+                    value,
+                    unit: None,
+                    obs_status: None,
+                    decimal: Some(1),
+                });
+            }
+        }
+    }
+    out
+}
+
+/// The same rows as one data-endpoint page: `[meta, [entry, ...]]`.
+fn synthetic_page(points: &[DataPoint]) -> String {
+    let entries: Vec<serde_json::Value> = points
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "indicator": { "id": p.indicator_id, "value": p.indicator_name },
+                "country": { "id": p.country_id, "value": p.country_name },
+                "countryiso3code": p.country_iso3,
+                "date": p.year.to_string(),
+                "value": p.value,
+                "unit": "",
+                "obs_status": "",
+                "decimal": p.decimal,
+            })
+        })
+        .collect();
+    let meta = serde_json::json!({
+        "page": 1,
+        "pages": 1,
+        "per_page": "100000",
+        "total": points.len(),
+    });
+    serde_json::Value::Array(vec![meta, serde_json::Value::Array(entries)]).to_string()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let page = synthetic_page(&synthetic_points());
+    c.bench_function("parse/page_100k", |b| {
+        b.iter(|| {
+            let (meta, entries): (Meta, Vec<Entry>) =
+                serde_json::from_str(black_box(&page)).unwrap();
+            let points: Vec<DataPoint> = entries.into_iter().map(DataPoint::from).collect();
+            (meta, points)
+        })
+    });
+}
+
+fn bench_stats(c: &mut Criterion) {
+    let points = synthetic_points();
+    let mut group = c.benchmark_group("stats");
+    group.bench_function("grouped_summary_100k", |b| {
+        b.iter(|| stats::grouped_summary(black_box(&points)))
+    });
+    group.bench_function("summary_by_indicator_100k", |b| {
+        b.iter(|| stats::summary_by(black_box(&points), stats::GroupBy::Indicator))
+    });
+    group.bench_function("yearly_summary_100k", |b| {
+        b.iter(|| stats::yearly_summary(black_box(&points)))
+    });
+    group.finish();
+}
+
+fn bench_loess(c: &mut Criterion) {
+    let points = synthetic_points();
+    // One (xs, ys) pair per series, as the LOESS chart smooths them.
+    let series: Vec<(Vec<f64>, Vec<f64>)> = points
+        .chunks(YEARS as usize)
+        .map(|rows| {
+            rows.iter()
+                .rev()
+                .filter_map(|p| Some((p.year as f64, p.value?)))
+                .unzip()
+        })
+        .collect();
+    let mut group = c.benchmark_group("loess");
+    group.sample_size(10);
+    group.bench_function("series_100k", |b| {
+        b.iter(|| {
+            for (xs, ys) in &series {
+                black_box(loess_series(xs, ys, 0.3));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn bench_render(c: &mut Criterion) {
+    let points = synthetic_points();
+    let mut group = c.benchmark_group("render");
+    group.sample_size(10);
+    for (name, kind) in [
+        ("svg_line_100k", PlotKind::Line),
+        ("svg_area_100k", PlotKind::Area),
+        ("svg_loess_100k", PlotKind::Loess),
+    ] {
+        let options = PlotOptions {
+            kind,
+            legend: LegendMode::Right,
+            ..Default::default()
+        };
+        group.bench_function(name, |b| {
+            b.iter(|| viz::render_svg_string(black_box(&points), &options).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_stats, bench_loess, bench_render);
+criterion_main!(benches);
@@ -1,4 +1,6 @@
-/// Synchronous client for the **World Bank Indicators API (v2)**.
+/// Clients for the **World Bank Indicators API (v2)**: the synchronous [`Client`] (feature
+/// `blocking`, on by default) and [`AsyncClient`], which also builds for
+/// `wasm32-unknown-unknown` where it runs on the browser's `fetch`.
 ///
 /// This module focuses on the `country/{codes}/indicator/{codes}` endpoint and returns
 /// results as tidy `models::DataPoint` rows. Pagination is handled automatically.
@@ -8,6 +10,10 @@
 /// - When requesting **multiple indicators** at once, the API requires a `source` parameter
 ///   (e.g., `source=2` for WDI). Pass it via `Client::fetch(..., Some(2))`.
 /// - Network timeouts use a sane default (30s) and can be adjusted by editing the client builder.
+/// - Large queries are split into several requests of at most [`MAX_CODES_PER_REQUEST`] codes
+///   and about [`MAX_ROWS_PER_REQUEST`] rows each (see [`estimate_rows`]), by country, then
+///   by indicator and year range; the results are merged. [`Client::with_max_rows`] and
+///   [`AsyncClient::with_max_rows`] refuse queries that would return more rows in total.
 ///
 ///
 /// Typical usage:
@@ -22,12 +28,23 @@
 /// )?;
 /// # Ok::<(), anyhow::Error>(())
 /// ```
-use crate::models::{DataPoint, DateSpec, Entry, IndicatorMeta, Meta};
+#[cfg(feature = "blocking")]
+use crate::cache::{HttpCache, MetadataCache};
+#[cfg(feature = "blocking")]
+use crate::fetch_log::{FetchLog, FetchRecord};
+#[cfg(feature = "blocking")]
+use crate::fixtures::{FixtureMode, Fixtures};
+use crate::models::{DataPoint, DateSpec, Entry, IndicatorMeta, Meta, SeriesCountryMeta};
+use crate::telemetry;
 use anyhow::{Context, Result, bail};
 use percent_encoding::{AsciiSet, NON_ALPHANUMERIC};
+#[cfg(feature = "blocking")]
 use reqwest::blocking::Client as HttpClient;
+#[cfg(not(target_arch = "wasm32"))]
 use reqwest::redirect::Policy;
 use serde_json::Value;
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
 use std::time::Duration;
 
 /// Fetch indicator observations.
@@ -60,13 +77,57 @@ use std::time::Duration;
 /// )?;
 /// # Ok::<(), anyhow::Error>(())
 /// ```
-
+#[cfg(feature = "blocking")]
 #[derive(Debug, Clone)]
 pub struct Client {
     pub base_url: String,
     http: HttpClient,
+    cache: Option<HttpCache>,
+    metadata: Option<MetadataCache>,
+    log: Option<FetchLog>,
+    max_rows: Option<u64>,
+    fixtures: Option<(Fixtures, FixtureMode)>,
+}
+
+/// Why a request failed, for callers that react differently to each (retry later, fix the
+/// query, …). Errors returned by [`Client`] carry one of these in their chain; find it with
+/// `err.downcast_ref::<ApiError>()` or by walking `err.chain()`.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// No usable response after retries (DNS, connect, timeout, or repeated 5xx).
+    #[error("network error: {0}")]
+    Network(String),
+    /// The server answered with a non-success, non-retryable HTTP status.
+    #[error("request failed with HTTP {0}")]
+    Http(reqwest::StatusCode),
+    /// The API reported an error in its payload, or sent something that is not its format.
+    #[error("world bank api error: {0}")]
+    Api(String),
+    /// The query would return more rows than [`Client::with_max_rows`] allows; nothing past
+    /// the first page of a request was fetched.
+    #[error(
+        "the query would return about {rows} rows, more than the limit of {limit}; narrow the \
+         countries, indicators or date range"
+    )]
+    TooManyRows { rows: u64, limit: u64 },
+}
+
+/// Where a [`Client::fetch_with_progress`] call stands; reported after every page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchProgress {
+    /// 1-based request number. Several indicators without a `source` take one request each.
+    pub request: usize,
+    /// Number of requests the fetch needs.
+    pub requests: usize,
+    /// 1-based page just received within the current request.
+    pub page: u32,
+    /// Pages of the current request, as reported by the API.
+    pub pages: u32,
+    /// Observations received so far, across all requests.
+    pub rows: usize,
 }
 
+#[cfg(feature = "blocking")]
 impl Default for Client {
     fn default() -> Self {
         let http = HttpClient::builder()
@@ -79,6 +140,11 @@ impl Default for Client {
         Self {
             base_url: "https://api.worldbank.org/v2".into(),
             http,
+            cache: None,
+            metadata: None,
+            log: None,
+            max_rows: None,
+            fixtures: None,
         }
     }
 }
@@ -94,7 +160,484 @@ fn enc_join<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
         .join(";")
 }
 
+/// Most country or indicator codes in one request, which keeps URLs well within what the
+/// API accepts.
+pub const MAX_CODES_PER_REQUEST: usize = 50;
+
+/// Rows one request aims to stay under; larger queries are split by country, indicator and
+/// year range. The API is slow to page through bigger results and tends to time out on them.
+pub const MAX_ROWS_PER_REQUEST: u64 = 20_000;
+
+/// Countries and aggregates the API returns for the code `all`.
+const ALL_COUNTRIES: u64 = 266;
+
+/// Upper estimate of the rows a query returns: one per country, indicator and year (or
+/// most recent value). `all` counts as every country and aggregate; open date ranges and no
+/// date at all span from [`DateSpec::FIRST_YEAR`] to the current year.
+///
+/// ```
+/// use wbi_rs::DateSpec;
+/// let countries = ["DEU".to_string(), "FRA".to_string()];
+/// let rows = wbi_rs::api::estimate_rows(&countries, &["SP.POP.TOTL".into()], Some(DateSpec::Range { start: 2001, end: 2020 }));
+/// assert_eq!(rows, 40);
+/// ```
+pub fn estimate_rows(countries: &[String], indicators: &[String], date: Option<DateSpec>) -> u64 {
+    use chrono::Datelike;
+    let countries: u64 = countries
+        .iter()
+        .map(|c| {
+            if c.trim().eq_ignore_ascii_case("all") {
+                ALL_COUNTRIES
+            } else {
+                1
+            }
+        })
+        .sum();
+    let current_year = chrono::Utc::now().year();
+    let years = match date
+        .unwrap_or(DateSpec::From(DateSpec::FIRST_YEAR))
+        .resolve(current_year)
+    {
+        DateSpec::Year(_) => 1,
+        DateSpec::Range { start, end } => u64::try_from(end - start + 1).unwrap_or(0),
+        DateSpec::MostRecent(n) => u64::from(n),
+        open => unreachable!("resolve() leaves no open range: {open:?}"),
+    };
+    countries * indicators.len() as u64 * years
+}
+
+/// One request of a split query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PlannedRequest<'a> {
+    countries: &'a [String],
+    indicators: &'a [String],
+    date: Option<DateSpec>,
+}
+
+/// The requests a query is split into: one per indicator without a `source` (the API needs
+/// one to combine indicators), else indicators in groups of [`MAX_CODES_PER_REQUEST`];
+/// countries in groups small enough for [`MAX_ROWS_PER_REQUEST`]. A group still over the
+/// budget on its own (`all`, or one country with many indicators over many years) is split
+/// further by indicator and then by year range.
+fn plan_requests<'a>(
+    countries: &'a [String],
+    indicators: &'a [String],
+    date: Option<DateSpec>,
+    source: Option<u32>,
+) -> Vec<PlannedRequest<'a>> {
+    let per_request = if source.is_none() {
+        1
+    } else {
+        MAX_CODES_PER_REQUEST
+    };
+    let mut plan = Vec::new();
+    for indicators in indicators.chunks(per_request) {
+        let mut start = 0;
+        while start < countries.len() {
+            // Grow the group while it stays under the row budget; one country always fits.
+            let mut end = start + 1;
+            while end < countries.len()
+                && end - start < MAX_CODES_PER_REQUEST
+                && estimate_rows(&countries[start..=end], indicators, date) <= MAX_ROWS_PER_REQUEST
+            {
+                end += 1;
+            }
+            plan_group(&countries[start..end], indicators, date, &mut plan);
+            start = end;
+        }
+    }
+    plan
+}
+
+/// Split one country group by indicator, then by year range, until each request fits
+/// [`MAX_ROWS_PER_REQUEST`]; single years and most-recent-value queries are not split.
+fn plan_group<'a>(
+    countries: &'a [String],
+    indicators: &'a [String],
+    date: Option<DateSpec>,
+    plan: &mut Vec<PlannedRequest<'a>>,
+) {
+    if estimate_rows(countries, indicators, date) <= MAX_ROWS_PER_REQUEST {
+        plan.push(PlannedRequest {
+            countries,
+            indicators,
+            date,
+        });
+        return;
+    }
+    let per_indicator = estimate_rows(countries, &indicators[..1], date).max(1);
+    let fit = usize::try_from(MAX_ROWS_PER_REQUEST / per_indicator)
+        .unwrap_or(usize::MAX)
+        .max(1);
+    for indicators in indicators.chunks(fit) {
+        let dates = if estimate_rows(countries, indicators, date) <= MAX_ROWS_PER_REQUEST {
+            vec![date]
+        } else {
+            let per_year = estimate_rows(countries, indicators, Some(DateSpec::Year(0))).max(1);
+            split_years(date, (MAX_ROWS_PER_REQUEST / per_year).max(1))
+        };
+        plan.extend(dates.into_iter().map(|date| PlannedRequest {
+            countries,
+            indicators,
+            date,
+        }));
+    }
+}
+
+/// `date` as consecutive ranges of at most `years` years; no date spans from
+/// [`DateSpec::FIRST_YEAR`] to the current year. Single years and most recent values stay
+/// whole.
+fn split_years(date: Option<DateSpec>, years: u64) -> Vec<Option<DateSpec>> {
+    use chrono::Datelike;
+    let resolved = date
+        .unwrap_or(DateSpec::From(DateSpec::FIRST_YEAR))
+        .resolve(chrono::Utc::now().year());
+    let DateSpec::Range { start, end } = resolved else {
+        return vec![date];
+    };
+    let step = i32::try_from(years).unwrap_or(i32::MAX);
+    (start..=end)
+        .step_by(years as usize)
+        .map(|from| {
+            Some(DateSpec::Range {
+                start: from,
+                end: from.saturating_add(step - 1).min(end),
+            })
+        })
+        .collect()
+}
+
+/// Fail early on requests the API cannot answer.
+fn check_fetch_args(countries: &[String], indicators: &[String]) -> Result<()> {
+    if countries.is_empty() {
+        bail!("at least one country/region code required");
+    }
+    if indicators.is_empty() {
+        bail!("at least one indicator code required");
+    }
+    Ok(())
+}
+
+/// URL of the data endpoint for one request, without the `page` parameter.
+fn data_url(
+    base_url: &str,
+    countries: &[String],
+    indicators: &[String],
+    date: Option<DateSpec>,
+    source: Option<u32>,
+) -> String {
+    let country_spec = enc_join(countries.iter().map(|s| s.as_str()));
+    let indicator_spec = enc_join(indicators.iter().map(|s| s.as_str()));
+
+    let mut url = format!(
+        "{}/country/{}/indicator/{}?format=json&per_page=1000",
+        base_url, country_spec, indicator_spec
+    );
+    if let Some(d) = date {
+        url.push_str(&format!("&{}={}", d.query_key(), d.to_query_param()));
+    }
+    if let Some(s) = source {
+        url.push_str(&format!("&source={}", s));
+    }
+    url
+}
+
+/// URL of the indicator metadata endpoint for `indicators`.
+fn indicator_url(base_url: &str, indicators: &[String]) -> String {
+    let indicator_spec = enc_join(indicators.iter().map(|s| s.as_str()));
+    format!("{base_url}/indicator/{indicator_spec}?format=json&per_page=1000")
+}
+
+/// Source of the metadata endpoints: World Development Indicators, which most indicators
+/// belong to.
+const METADATA_SOURCE: u32 = 2;
+
+/// Metatype holding the methodology in series metadata.
+const METHODOLOGY_METATYPE: &str = "Statisticalconceptandmethodology";
+
+/// URLs of the series metadata and the series-country metadata of `indicator` in `country`.
+fn series_metadata_urls(base_url: &str, indicator: &str, country: &str) -> (String, String) {
+    let source = format!("{base_url}/sources/{METADATA_SOURCE}");
+    let (indicator, country) = (enc_join([indicator]), enc_join([country]));
+    (
+        format!("{source}/series/{indicator}/metadata?format=json"),
+        format!("{source}/country/{country}/series/{indicator}/metadata?format=json"),
+    )
+}
+
+/// `(metatype, text)` pairs of a metadata response, which nests them as
+/// `source[].concept[].variable[].metatype[]`; blank texts are left out. The API answers
+/// unknown codes with an "Invalid value" message, which yields no pairs.
+fn parse_metatypes(v: &Value) -> Result<Vec<(String, String)>> {
+    if v.is_array() {
+        return match response_array(v) {
+            Err(_) if is_invalid_value(&v[0]) => Ok(vec![]),
+            Err(e) => Err(e),
+            Ok(_) => Err(ApiError::Api("unexpected metadata response shape".into()).into()),
+        };
+    }
+    // A single source may come as an object rather than a one-element array.
+    let each = |v: &Value| -> Vec<Value> {
+        match v {
+            Value::Array(items) => items.clone(),
+            Value::Null => vec![],
+            other => vec![other.clone()],
+        }
+    };
+    let mut out = Vec::new();
+    for source in each(&v["source"]) {
+        for concept in each(&source["concept"]) {
+            for variable in each(&concept["variable"]) {
+                for metatype in each(&variable["metatype"]) {
+                    let (Some(id), Some(text)) =
+                        (metatype["id"].as_str(), metatype["value"].as_str())
+                    else {
+                        continue;
+                    };
+                    if !text.trim().is_empty() {
+                        out.push((id.to_string(), text.trim().to_string()));
+                    }
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Series-country metadata from the metatypes of the series and series-country responses.
+fn series_country_meta(
+    indicator: &str,
+    country: &str,
+    series: Vec<(String, String)>,
+    country_notes: Vec<(String, String)>,
+) -> SeriesCountryMeta {
+    SeriesCountryMeta {
+        indicator_id: indicator.to_string(),
+        country_id: country.to_string(),
+        methodology: series
+            .into_iter()
+            .find(|(id, _)| id == METHODOLOGY_METATYPE)
+            .map(|(_, text)| text),
+        notes: country_notes.into_iter().map(|(_, text)| text).collect(),
+    }
+}
+
+/// Whether an API error message rejects a code as invalid.
+fn is_invalid_value(error: &Value) -> bool {
+    error
+        .get("message")
+        .and_then(Value::as_array)
+        .is_some_and(|m| m.iter().any(|e| e["key"] == "Invalid value"))
+}
+
+/// Safety cap on pages per request, to avoid pathological jobs.
+const MAX_PAGES: u32 = 1000;
+
+/// The `[Meta, [record, ...]]` array of a response, surfacing API errors.
+fn response_array(v: &Value) -> Result<&[Value]> {
+    // The API returns an array: [Meta, [Entry, ...]] or a "message" object in position 0 on error.
+    let arr = v
+        .as_array()
+        .ok_or_else(|| ApiError::Api("unexpected response shape: not a top-level array".into()))?;
+    if arr.is_empty() {
+        return Err(ApiError::Api("unexpected response: empty array".into()).into());
+    }
+    // If first element has "message", surface API error.
+    if arr[0].get("message").is_some() {
+        return Err(ApiError::Api(arr[0].to_string()).into());
+    }
+    Ok(arr)
+}
+
+/// One page of the data endpoint: its metadata and observations.
+fn parse_page(v: &Value) -> Result<(Meta, Vec<DataPoint>)> {
+    let arr = response_array(v)?;
+    let meta: Meta = serde_json::from_value(arr[0].clone()).context("parse meta")?;
+    let entries: Vec<Entry> = if arr.len() > 1 {
+        serde_json::from_value(arr[1].clone()).context("parse entries")?
+    } else {
+        vec![]
+    };
+    Ok((meta, entries.into_iter().map(DataPoint::from).collect()))
+}
+
+/// Records of an indicator metadata response.
+fn parse_indicators(v: &Value) -> Result<Vec<IndicatorMeta>> {
+    // Same structure as data endpoint: [Meta, [IndicatorMeta, ...]]
+    let arr = response_array(v)?;
+    if arr.len() > 1 {
+        serde_json::from_value(arr[1].clone()).context("parse indicator metadata")
+    } else {
+        Ok(vec![])
+    }
+}
+
+/// Map from indicator ID to unit, skipping indicators without one.
+fn unit_map(metas: impl IntoIterator<Item = IndicatorMeta>) -> HashMap<String, String> {
+    metas
+        .into_iter()
+        .filter_map(|meta| {
+            let unit = meta.unit.filter(|u| !u.trim().is_empty())?;
+            Some((meta.id, unit))
+        })
+        .collect()
+}
+
+/// Whether `p` has no usable unit and should be enriched from indicator metadata.
+fn lacks_unit(p: &DataPoint) -> bool {
+    p.unit.as_ref().is_none_or(|u| u.trim().is_empty())
+}
+
+/// Fill in missing units from `units` (indicator ID to unit).
+fn enrich_units(points: &mut [DataPoint], units: &HashMap<String, String>) {
+    for point in points.iter_mut().filter(|p| lacks_unit(p)) {
+        if let Some(unit) = units.get(&point.indicator_id) {
+            point.unit = Some(unit.clone());
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
 impl Client {
+    /// Serve responses from `cache` while fresh, and store what is fetched.
+    pub fn with_cache(mut self, cache: HttpCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Append a [`FetchRecord`] to `log` for every API call, cached or not.
+    pub fn with_fetch_log(mut self, log: FetchLog) -> Self {
+        self.log = Some(log);
+        self
+    }
+
+    /// Record one call in the fetch log, if attached. Best effort, like the cache.
+    fn log_fetch(
+        &self,
+        url: &str,
+        status: Option<u16>,
+        body: Option<&Value>,
+        error: Option<String>,
+    ) {
+        let Some(log) = &self.log else {
+            return;
+        };
+        let record = FetchRecord {
+            url: url.to_string(),
+            timestamp: chrono::Utc::now(),
+            status,
+            rows: body.and_then(|v| Some(v.get(1)?.as_array()?.len())),
+            cached: status.is_none() && error.is_none(),
+            error,
+        };
+        if let Err(e) = log.append(&record) {
+            tracing::warn!("could not write fetch log: {e:#}");
+        }
+    }
+
+    /// Keep indicator metadata in `cache`: unit enrichment and [`Self::lookup_indicator`]
+    /// only request indicators without a fresh entry, and fall back to stale entries when
+    /// the API cannot be reached.
+    pub fn with_metadata_cache(mut self, cache: MetadataCache) -> Self {
+        self.metadata = Some(cache);
+        self
+    }
+
+    /// Record every response to `fixtures`, or answer only from them without going online;
+    /// see [`crate::fixtures`].
+    pub fn with_fixtures(mut self, fixtures: Fixtures, mode: FixtureMode) -> Self {
+        self.fixtures = Some((fixtures, mode));
+        self
+    }
+
+    /// GET `u` as JSON: from the fixtures when replaying, otherwise as [`Self::get_live`]
+    /// does, saving the response when recording.
+    fn get_json(&self, u: &str) -> Result<Value> {
+        let Some((fixtures, mode)) = &self.fixtures else {
+            return self.get_live(u);
+        };
+        let relative = u.strip_prefix(self.base_url.as_str()).unwrap_or(u);
+        match mode {
+            FixtureMode::Replay => {
+                let body = fixtures.get(relative)?;
+                self.log_fetch(u, None, Some(&body), None);
+                Ok(body)
+            }
+            FixtureMode::Record => {
+                let body = self.get_live(u)?;
+                let path = fixtures.put(relative, &body)?;
+                tracing::debug!("recorded {}", path.display());
+                Ok(body)
+            }
+        }
+    }
+
+    /// GET `u` as JSON, from the cache if attached and fresh. Transient failures
+    /// (5xx / network errors) are retried with a short backoff.
+    fn get_live(&self, u: &str) -> Result<Value> {
+        if let Some(cache) = &self.cache {
+            let cached = cache.get(u);
+            telemetry::cache(cached.is_some());
+            if let Some(body) = cached {
+                tracing::debug!("cache hit {u}");
+                self.log_fetch(u, None, Some(&body), None);
+                return Ok(body);
+            }
+        }
+        let mut last_err: Option<String> = None;
+        for (attempt, backoff_ms) in [100u64, 300, 700].into_iter().enumerate() {
+            if attempt > 0 {
+                telemetry::retry();
+            }
+            tracing::debug!("GET {u}");
+            let started = telemetry::start();
+            let response = self.http.get(u).send();
+            if let Err(e) = &response {
+                telemetry::request(e.status().map(|s| s.as_u16()), started);
+            }
+            match response {
+                Ok(r) if r.status().is_success() => {
+                    let status = r.status().as_u16();
+                    let body: Result<Value> = r.json().context("decode json");
+                    telemetry::request(Some(status), started);
+                    let body = match body {
+                        Ok(body) => body,
+                        Err(e) => {
+                            self.log_fetch(u, Some(status), None, Some(format!("{e:#}")));
+                            return Err(e);
+                        }
+                    };
+                    self.log_fetch(u, Some(status), Some(&body), None);
+                    if let Some(cache) = &self.cache
+                        && let Err(e) = cache.put(u, &body)
+                    {
+                        // A broken cache must not fail the fetch.
+                        tracing::warn!("could not cache response: {e:#}");
+                    }
+                    return Ok(body);
+                }
+                Ok(r) if r.status().is_server_error() => {
+                    telemetry::request(Some(r.status().as_u16()), started);
+                    tracing::warn!("HTTP {} from {u}; retrying in {backoff_ms} ms", r.status());
+                    last_err = Some(format!("HTTP {}", r.status()));
+                }
+                Ok(r) => {
+                    telemetry::request(Some(r.status().as_u16()), started);
+                    self.log_fetch(u, Some(r.status().as_u16()), None, None);
+                    return Err(ApiError::Http(r.status()).into());
+                }
+                Err(e) => {
+                    tracing::warn!("request to {u} failed ({e}); retrying in {backoff_ms} ms");
+                    last_err = Some(e.to_string());
+                }
+            }
+            std::thread::sleep(Duration::from_millis(backoff_ms));
+        }
+        let err = last_err.unwrap_or_default();
+        self.log_fetch(u, None, None, Some(err.clone()));
+        Err(ApiError::Network(err).into())
+    }
+
     /// Fetch units from the World Bank indicator endpoint for the given indicators.
     ///
     /// Returns a map from indicator ID to unit string. Missing indicators or those
@@ -110,71 +653,185 @@ impl Client {
     /// let units = cli.fetch_indicator_units(&["SP.POP.TOTL".into()])?;
     /// # Ok::<(), anyhow::Error>(())
     /// ```
-    pub fn fetch_indicator_units(
-        &self,
-        indicators: &[String],
-    ) -> Result<std::collections::HashMap<String, String>> {
-        use std::collections::HashMap;
-
-        if indicators.is_empty() {
-            return Ok(HashMap::new());
-        }
+    pub fn fetch_indicator_units(&self, indicators: &[String]) -> Result<HashMap<String, String>> {
+        Ok(unit_map(self.indicator_meta(indicators)?.into_values()))
+    }
 
-        let indicator_spec = enc_join(indicators.iter().map(|s| s.as_str()));
-        let url = format!(
-            "{}/indicator/{}?format=json&per_page=1000",
-            self.base_url, indicator_spec
-        );
+    /// Metadata (name, unit, source note and organization) of `indicators`, in the given
+    /// order. Indicators the API does not know are left out. Served from the metadata cache
+    /// where fresh, like [`Self::fetch_indicator_units`].
+    ///
+    /// ### Example
+    /// ```no_run
+    /// # use wbi_rs::Client;
+    /// for meta in Client::default().fetch_indicator_metadata(&["SP.POP.TOTL".into()])? {
+    ///     println!("{}: {}", meta.name, meta.source_note.unwrap_or_default());
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn fetch_indicator_metadata(&self, indicators: &[String]) -> Result<Vec<IndicatorMeta>> {
+        let mut known = self.indicator_meta(indicators)?;
+        Ok(indicators
+            .iter()
+            .filter_map(|id| known.remove(id))
+            .collect())
+    }
 
-        // Use the same retry logic as the main fetch method
-        let get_json = |u: &str| -> Result<Value> {
-            let mut last_err: Option<anyhow::Error> = None;
-            for backoff_ms in [100u64, 300, 700] {
-                match self.http.get(u).send() {
-                    Ok(r) if r.status().is_success() => {
-                        return r.json().context("decode json");
-                    }
-                    Ok(r) if r.status().is_server_error() => { /* retry */ }
-                    Ok(r) => bail!("request failed with HTTP {}", r.status()),
-                    Err(e) => last_err = Some(e.into()),
+    /// Metadata of `indicators`, from the metadata cache where fresh and the API otherwise.
+    fn indicator_meta(&self, indicators: &[String]) -> Result<HashMap<String, IndicatorMeta>> {
+        let mut known = match &self.metadata {
+            Some(cache) => cache.get(indicators),
+            None => HashMap::new(),
+        };
+        let missing: Vec<String> = indicators
+            .iter()
+            .filter(|id| !known.contains_key(*id))
+            .cloned()
+            .collect();
+        if missing.is_empty() {
+            return Ok(known);
+        }
+        let url = indicator_url(&self.base_url, &missing);
+        let fetched = self
+            .get_json(&url)
+            .with_context(|| format!("GET {}", url))
+            .and_then(|v| parse_indicators(&v));
+        match (fetched, &self.metadata) {
+            (Ok(metas), cache) => {
+                if let Some(cache) = cache
+                    && let Err(e) = cache.put(&metas)
+                {
+                    tracing::warn!("could not cache indicator metadata: {e:#}");
                 }
-                std::thread::sleep(Duration::from_millis(backoff_ms));
+                known.extend(metas.into_iter().map(|m| (m.id.clone(), m)));
             }
-            bail!("network error: {:?}", last_err);
-        };
+            (Err(e), Some(cache)) => {
+                let stale = cache.get_stale(&missing);
+                if stale.is_empty() {
+                    return Err(e);
+                }
+                tracing::warn!("using stale indicator metadata: {e:#}");
+                known.extend(stale);
+            }
+            (Err(e), None) => return Err(e),
+        }
+        Ok(known)
+    }
 
-        let v: Value = get_json(&url).with_context(|| format!("GET {}", url))?;
+    /// Name of the country or aggregate `code` (ISO3 or ISO2), or `None` if the API does
+    /// not know the code.
+    pub fn lookup_country(&self, code: &str) -> Result<Option<String>> {
+        let url = format!("{}/country/{}?format=json", self.base_url, enc_join([code]));
+        Ok(self
+            .lookup(&url)?
+            .and_then(|v| v.get("name")?.as_str().map(str::to_string)))
+    }
 
-        // Parse the response (same structure as data endpoint: [Meta, [IndicatorMeta, ...]])
-        let arr = v
-            .as_array()
-            .ok_or_else(|| anyhow::anyhow!("unexpected response shape: not a top-level array"))?;
-        if arr.is_empty() {
-            bail!("unexpected response: empty array");
+    /// Metadata of indicator `id`, or `None` if the API does not know the id.
+    pub fn lookup_indicator(&self, id: &str) -> Result<Option<IndicatorMeta>> {
+        if let Some(meta) = self
+            .metadata
+            .as_ref()
+            .and_then(|cache| cache.get(&[id.to_string()]).remove(id))
+        {
+            return Ok(Some(meta));
         }
-
-        // Check for API error
-        if arr[0].get("message").is_some() {
-            bail!("world bank api error: {}", arr[0]);
+        let url = format!("{}/indicator/{}?format=json", self.base_url, enc_join([id]));
+        let Some(v) = self.lookup(&url)? else {
+            return Ok(None);
+        };
+        let meta: IndicatorMeta = serde_json::from_value(v).context("parse indicator metadata")?;
+        if let Some(cache) = &self.metadata
+            && let Err(e) = cache.put(std::slice::from_ref(&meta))
+        {
+            tracing::warn!("could not cache indicator metadata: {e:#}");
         }
+        Ok(Some(meta))
+    }
 
-        let indicators_data: Vec<IndicatorMeta> = if arr.len() > 1 {
-            serde_json::from_value(arr[1].clone()).context("parse indicator metadata")?
-        } else {
-            vec![]
+    /// Indicators of World Bank source `source` (e.g. `2` for WDI) whose id or name contains
+    /// every word of `query` (case-insensitive), sorted by id.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// # use wbi_rs::Client;
+    /// let hits = Client::default().search_indicators("gdp per capita", 2)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn search_indicators(&self, query: &str, source: u32) -> Result<Vec<IndicatorMeta>> {
+        // One page holds a whole source (WDI has ~1,500 indicators).
+        let url = format!(
+            "{}/source/{source}/indicator?format=json&per_page=20000",
+            self.base_url
+        );
+        let v: Value = self
+            .get_json(&url)
+            .with_context(|| format!("GET {}", url))?;
+        let arr = response_array(&v)?;
+        let all: Vec<IndicatorMeta> = match arr.get(1) {
+            Some(records) if !records.is_null() => {
+                serde_json::from_value(records.clone()).context("parse indicator metadata")?
+            }
+            _ => vec![],
         };
+        let mut hits: Vec<IndicatorMeta> = all
+            .into_iter()
+            .filter(|m| crate::provider::matches_query(m, query))
+            .collect();
+        hits.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(hits)
+    }
 
-        // Build map from ID to unit
-        let mut result = HashMap::new();
-        for meta in indicators_data {
-            if let Some(unit) = meta.unit
-                && !unit.trim().is_empty()
-            {
-                result.insert(meta.id, unit);
+    /// Methodology and country-specific footnotes of `indicator` in `country` (ISO3), from
+    /// the World Bank metadata API: one request for the series and one for the series in
+    /// that country, both for the World Development Indicators source. Fields the API has no
+    /// text for stay empty, as they do for codes it does not know.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// # use wbi_rs::Client;
+    /// let meta = Client::default().fetch_series_country_metadata("SP.POP.TOTL", "DEU")?;
+    /// for note in &meta.notes {
+    ///     println!("{note}");
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn fetch_series_country_metadata(
+        &self,
+        indicator: &str,
+        country: &str,
+    ) -> Result<SeriesCountryMeta> {
+        let (series_url, country_url) = series_metadata_urls(&self.base_url, indicator, country);
+        let fetch = |url: &str| {
+            self.get_json(url)
+                .with_context(|| format!("GET {url}"))
+                .and_then(|v| parse_metatypes(&v))
+        };
+        Ok(series_country_meta(
+            indicator,
+            country,
+            fetch(&series_url)?,
+            fetch(&country_url)?,
+        ))
+    }
+
+    /// First record of a metadata response; `None` when the API rejects the code as invalid.
+    fn lookup(&self, url: &str) -> Result<Option<Value>> {
+        let v: Value = self.get_json(url).with_context(|| format!("GET {}", url))?;
+        let arr = v.as_array().ok_or_else(|| {
+            ApiError::Api("unexpected response shape: not a top-level array".into())
+        })?;
+        if let Some(error) = arr.first().filter(|m| m.get("message").is_some()) {
+            if is_invalid_value(error) {
+                return Ok(None);
             }
+            return Err(ApiError::Api(error.to_string()).into());
         }
-
-        Ok(result)
+        Ok(arr
+            .get(1)
+            .and_then(Value::as_array)
+            .and_then(|records| records.first())
+            .cloned())
     }
 
     /// Fetch indicator observations.
@@ -193,131 +850,315 @@ impl Client {
         date: Option<DateSpec>,
         source: Option<u32>,
     ) -> Result<Vec<DataPoint>> {
-        if countries.is_empty() {
-            bail!("at least one country/region code required");
-        }
-        if indicators.is_empty() {
-            bail!("at least one indicator code required");
-        }
+        self.fetch_with_progress(countries, indicators, date, source, |_| {})
+    }
 
-        // Multi-indicator fallback: if multiple indicators without source,
-        // fetch each indicator separately and merge results
-        if indicators.len() > 1 && source.is_none() {
-            let mut all_points = Vec::new();
-            for indicator in indicators {
-                let points = self.fetch(countries, std::slice::from_ref(indicator), date, None)?;
-                all_points.extend(points);
+    /// [`Client::fetch`], calling `on_progress` after every page received (e.g. to drive a
+    /// progress bar).
+    pub fn fetch_with_progress(
+        &self,
+        countries: &[String],
+        indicators: &[String],
+        date: Option<DateSpec>,
+        source: Option<u32>,
+        mut on_progress: impl FnMut(FetchProgress),
+    ) -> Result<Vec<DataPoint>> {
+        check_fetch_args(countries, indicators)?;
+        let limit = self.max_rows.unwrap_or(u64::MAX);
+        let estimate = estimate_rows(countries, indicators, date);
+        if estimate > limit {
+            return Err(ApiError::TooManyRows {
+                rows: estimate,
+                limit,
             }
-            return Ok(all_points);
+            .into());
         }
 
-        let country_spec = enc_join(countries.iter().map(|s| s.as_str()));
-        let indicator_spec = enc_join(indicators.iter().map(|s| s.as_str()));
-
-        let mut url = format!(
-            "{}/country/{}/indicator/{}?format=json&per_page=1000",
-            self.base_url, country_spec, indicator_spec
-        );
-        if let Some(d) = date {
-            url.push_str(&format!("&date={}", d.to_query_param()));
-        }
-        if let Some(s) = source {
-            url.push_str(&format!("&source={}", s));
+        // Split into requests the API answers comfortably (one per indicator without a
+        // source) and merge the results.
+        let plan = plan_requests(countries, indicators, date, source);
+        let mut out = Vec::new();
+        // Rows the API reported for the requests so far; the estimate may be off for
+        // aggregates.
+        let mut reported = 0;
+        for (i, request) in plan.iter().enumerate() {
+            let points = self.fetch_request(
+                request.countries,
+                request.indicators,
+                request.date,
+                source,
+                (i + 1, plan.len(), out.len()),
+                &mut |total| {
+                    reported += total;
+                    if reported > limit {
+                        return Err(ApiError::TooManyRows {
+                            rows: reported.max(estimate),
+                            limit,
+                        }
+                        .into());
+                    }
+                    Ok(())
+                },
+                &mut on_progress,
+            )?;
+            out.extend(points);
         }
 
-        // Small retry for transient failures (5xx / network errors)
-        let get_json = |u: &str| -> Result<Value> {
-            let mut last_err: Option<anyhow::Error> = None;
-            for backoff_ms in [100u64, 300, 700] {
-                match self.http.get(u).send() {
-                    Ok(r) if r.status().is_success() => {
-                        return r.json().context("decode json");
-                    }
-                    Ok(r) if r.status().is_server_error() => { /* retry */ }
-                    Ok(r) => bail!("request failed with HTTP {}", r.status()),
-                    Err(e) => last_err = Some(e.into()),
+        // Unit enrichment: if any DataPoints lack units, try to fetch from indicator metadata
+        if out.iter().any(lacks_unit) {
+            match self.fetch_indicator_units(indicators) {
+                Ok(indicator_units) => enrich_units(&mut out, &indicator_units),
+                Err(e) => {
+                    tracing::debug!("unit enrichment skipped: {e:#}");
+                    // If indicator metadata fetch fails, continue without enrichment
+                    // This ensures that the main data fetch doesn't fail due to metadata issues
                 }
-                std::thread::sleep(Duration::from_millis(backoff_ms));
             }
-            bail!("network error: {:?}", last_err);
-        };
+        }
+        Ok(out)
+    }
+
+    /// Refuse queries that would return more than `max_rows` rows, estimated up front with
+    /// [`estimate_rows`] and checked against the totals the API reports on the first page of
+    /// every request. Fails with [`ApiError::TooManyRows`] instead of a long fetch.
+    pub fn with_max_rows(mut self, max_rows: u64) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
 
-        // Safety cap to avoid pathological jobs
-        let max_pages = 1000u32;
+    /// One paginated request. `position` is (request, requests, rows before this request);
+    /// `on_total` gets the row count the API reports on the first page.
+    #[allow(clippy::too_many_arguments)]
+    fn fetch_request(
+        &self,
+        countries: &[String],
+        indicators: &[String],
+        date: Option<DateSpec>,
+        source: Option<u32>,
+        position: (usize, usize, usize),
+        on_total: &mut dyn FnMut(u64) -> Result<()>,
+        on_progress: &mut dyn FnMut(FetchProgress),
+    ) -> Result<Vec<DataPoint>> {
+        let url = data_url(&self.base_url, countries, indicators, date, source);
 
         // Paginate until we retrieved all pages.
         let mut page = 1u32;
         let mut out: Vec<DataPoint> = Vec::new();
         loop {
             let page_url = format!("{}&page={}", url, page);
-            if page > max_pages {
-                bail!("page limit exceeded ({})", max_pages);
+            if page > MAX_PAGES {
+                bail!("page limit exceeded ({})", MAX_PAGES);
             }
-            let v: Value = get_json(&page_url).with_context(|| format!("GET {}", page_url))?;
-
-            // The API returns an array: [Meta, [Entry, ...]] or a "message" object in position 0 on error.
-            let arr = v.as_array().ok_or_else(|| {
-                anyhow::anyhow!("unexpected response shape: not a top-level array")
-            })?;
-            if arr.is_empty() {
-                bail!("unexpected response: empty array");
+            let v: Value = self
+                .get_json(&page_url)
+                .with_context(|| format!("GET {}", page_url))?;
+            let (meta, points) = parse_page(&v)?;
+            if page == 1 {
+                on_total(meta.total.into())?;
             }
 
-            // If first element has "message", surface API error.
-            if arr[0].get("message").is_some() {
-                bail!("world bank api error: {}", arr[0]);
+            out.extend(points);
+            tracing::debug!(page, pages = meta.pages, rows = out.len(), "received page");
+            on_progress(FetchProgress {
+                request: position.0,
+                requests: position.1,
+                page,
+                pages: meta.pages,
+                rows: position.2 + out.len(),
+            });
+
+            if page >= meta.pages {
+                break;
             }
+            page += 1;
+        }
 
-            let meta: Meta = serde_json::from_value(arr[0].clone()).context("parse meta")?;
-            let entries: Vec<Entry> = if arr.len() > 1 {
-                serde_json::from_value(arr[1].clone()).context("parse entries")?
-            } else {
-                vec![]
-            };
+        telemetry::rows(out.len());
+        Ok(out)
+    }
+}
 
-            out.extend(entries.into_iter().map(DataPoint::from));
+/// Asynchronous client on reqwest's async HTTP client: tokio on native targets, the browser's
+/// `fetch` under `wasm32-unknown-unknown`. Same requests and results as [`Client::fetch`], but
+/// without retries or a [`crate::cache::HttpCache`] (a browser has its own).
+///
+/// ```no_run
+/// # async fn demo() -> anyhow::Result<()> {
+/// use wbi_rs::{AsyncClient, DateSpec};
+///
+/// let rows = AsyncClient::default()
+///     .fetch(&["DEU".into()], &["SP.POP.TOTL".into()], Some(DateSpec::Year(2020)), None)
+///     .await?;
+/// let summaries = wbi_rs::stats::grouped_summary(&rows);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AsyncClient {
+    pub base_url: String,
+    http: reqwest::Client,
+    max_rows: Option<u64>,
+}
 
-            let total_pages = meta.pages;
-            if page >= total_pages {
-                break;
+impl Default for AsyncClient {
+    fn default() -> Self {
+        let builder = reqwest::Client::builder();
+        // Browsers own timeouts, redirects and the user agent.
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = builder
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .redirect(Policy::limited(5))
+            .user_agent(concat!("wbi_rs/", env!("CARGO_PKG_VERSION")));
+        Self {
+            base_url: "https://api.worldbank.org/v2".into(),
+            http: builder.build().expect("reqwest client build"),
+            max_rows: None,
+        }
+    }
+}
+
+impl AsyncClient {
+    /// GET `u` as JSON.
+    async fn get_json(&self, u: &str) -> Result<Value> {
+        tracing::debug!("GET {u}");
+        let started = telemetry::start();
+        let r = match self.http.get(u).send().await {
+            Ok(r) => r,
+            Err(e) => {
+                telemetry::request(None, started);
+                return Err(ApiError::Network(e.to_string()).into());
             }
-            page += 1;
+        };
+        let status = r.status();
+        if !status.is_success() {
+            telemetry::request(Some(status.as_u16()), started);
+            return Err(ApiError::Http(status).into());
         }
+        let body = r.json().await.context("decode json");
+        telemetry::request(Some(status.as_u16()), started);
+        body
+    }
 
-        // Unit enrichment: if any DataPoints lack units, try to fetch from indicator metadata
-        let needs_enrichment = out.iter().any(|p| {
-            p.unit.is_none()
-                || p.unit
-                    .as_ref()
-                    .map(|u| u.trim().is_empty())
-                    .unwrap_or(false)
-        });
+    /// Async [`Client::fetch_indicator_units`].
+    pub async fn fetch_indicator_units(
+        &self,
+        indicators: &[String],
+    ) -> Result<HashMap<String, String>> {
+        if indicators.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let url = indicator_url(&self.base_url, indicators);
+        let v = self
+            .get_json(&url)
+            .await
+            .with_context(|| format!("GET {}", url))?;
+        Ok(unit_map(parse_indicators(&v)?))
+    }
 
-        if needs_enrichment {
-            // Fetch indicator metadata to get units
-            match self.fetch_indicator_units(indicators) {
-                Ok(indicator_units) => {
-                    // Enrich DataPoints that lack units
-                    for point in &mut out {
-                        if (point.unit.is_none()
-                            || point
-                                .unit
-                                .as_ref()
-                                .map(|u| u.trim().is_empty())
-                                .unwrap_or(false))
-                            && let Some(unit) = indicator_units.get(&point.indicator_id)
-                        {
-                            point.unit = Some(unit.clone());
-                        }
-                    }
-                }
-                Err(_) => {
-                    // If indicator metadata fetch fails, continue without enrichment
-                    // This ensures that the main data fetch doesn't fail due to metadata issues
-                }
+    /// Async [`Client::fetch_series_country_metadata`].
+    pub async fn fetch_series_country_metadata(
+        &self,
+        indicator: &str,
+        country: &str,
+    ) -> Result<SeriesCountryMeta> {
+        let (series_url, country_url) = series_metadata_urls(&self.base_url, indicator, country);
+        let mut metatypes = Vec::with_capacity(2);
+        for url in [&series_url, &country_url] {
+            let v = self
+                .get_json(url)
+                .await
+                .with_context(|| format!("GET {url}"))?;
+            metatypes.push(parse_metatypes(&v)?);
+        }
+        let country_notes = metatypes.pop().unwrap_or_default();
+        let series = metatypes.pop().unwrap_or_default();
+        Ok(series_country_meta(
+            indicator,
+            country,
+            series,
+            country_notes,
+        ))
+    }
+
+    /// Async [`Client::fetch`], including the per-indicator fallback without `source`, the
+    /// splitting of large queries and unit enrichment.
+    pub async fn fetch(
+        &self,
+        countries: &[String],
+        indicators: &[String],
+        date: Option<DateSpec>,
+        source: Option<u32>,
+    ) -> Result<Vec<DataPoint>> {
+        check_fetch_args(countries, indicators)?;
+        let limit = self.max_rows.unwrap_or(u64::MAX);
+        let estimate = estimate_rows(countries, indicators, date);
+        if estimate > limit {
+            return Err(ApiError::TooManyRows {
+                rows: estimate,
+                limit,
             }
+            .into());
         }
 
+        let mut out = Vec::new();
+        let mut reported = 0;
+        for request in plan_requests(countries, indicators, date, source) {
+            let (points, total) = self
+                .fetch_request(request.countries, request.indicators, request.date, source)
+                .await?;
+            // Rows the API reported so far; the estimate may be off for aggregates.
+            reported += total;
+            if reported > limit {
+                return Err(ApiError::TooManyRows {
+                    rows: reported.max(estimate),
+                    limit,
+                }
+                .into());
+            }
+            out.extend(points);
+        }
+        if out.iter().any(lacks_unit) {
+            match self.fetch_indicator_units(indicators).await {
+                Ok(units) => enrich_units(&mut out, &units),
+                Err(e) => tracing::debug!("unit enrichment skipped: {e:#}"),
+            }
+        }
         Ok(out)
     }
+
+    /// Async [`Client::with_max_rows`].
+    pub fn with_max_rows(mut self, max_rows: u64) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// One paginated request, with the row count the API reports on the first page. A
+    /// request over the row limit ends there.
+    async fn fetch_request(
+        &self,
+        countries: &[String],
+        indicators: &[String],
+        date: Option<DateSpec>,
+        source: Option<u32>,
+    ) -> Result<(Vec<DataPoint>, u64)> {
+        let url = data_url(&self.base_url, countries, indicators, date, source);
+        let limit = self.max_rows.unwrap_or(u64::MAX);
+        let mut out: Vec<DataPoint> = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let page_url = format!("{}&page={}", url, page);
+            let v = self
+                .get_json(&page_url)
+                .await
+                .with_context(|| format!("GET {}", page_url))?;
+            let (meta, points) = parse_page(&v)?;
+            let total = u64::from(meta.total);
+            out.extend(points);
+            if page >= meta.pages || total > limit {
+                telemetry::rows(out.len());
+                return Ok((out, total));
+            }
+        }
+        bail!("page limit exceeded ({})", MAX_PAGES)
+    }
 }
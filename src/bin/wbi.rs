@@ -1,17 +1,50 @@
-use anyhow::{Result, bail};
-use clap::{Args, Parser, Subcommand, ValueEnum};
-use num_format::{Locale, ToFormattedString};
+use anyhow::{Context, Result, bail};
+use clap::{ArgAction, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
+use wbi_rs::fetch_log::FetchLog;
+use wbi_rs::util::format;
 use wbi_rs::{Client, DateSpec};
 use wbi_rs::{stats, storage, viz};
 
+const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  success
+  1  other error
+  2  usage error (invalid flags or arguments)
+  3  network error (no response after retries)
+  4  API error (HTTP error status or error payload)
+  5  empty result (the query matched no observations)
+  6  I/O error (reading or writing files)
+  7  invalid data (an input file of `wbi diff` or `wbi chart-diff` could not be compared)
+`wbi diff` and `wbi chart-diff` exit 1 when the inputs differ; their errors use the codes
+above, never 1.";
+
 #[derive(Parser, Debug)]
 #[command(
     name = "wbi",
     version,
-    about = "Fetch, store, visualize & summarize World Bank indicators"
+    about = "Fetch, store, visualize & summarize World Bank indicators",
+    after_help = EXIT_CODES_HELP
 )]
 struct Cli {
+    /// Config file with defaults (default: ~/.config/wbi/config.toml, if present)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// More diagnostics on stderr (-v: debug, -vv: trace)
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+    /// Only print errors (no status lines, no progress bar)
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// How errors are printed to stderr: text, or one JSON object for orchestration tools
+    #[arg(long = "error-format", value_enum, global = true, default_value_t = ErrorFormat::Text)]
+    error_format: ErrorFormat,
+    /// Locale for numbers in console output and chart labels (e.g., en, de, fr). Default: en
+    #[arg(long, global = true, default_value = "en")]
+    locale: String,
     #[command(subcommand)]
     cmd: Command,
 }
@@ -20,6 +53,146 @@ struct Cli {
 enum Command {
     /// Fetch data (and optionally save, plot, and print stats).
     Get(GetArgs),
+    /// Plot a previously saved CSV/JSON export without fetching.
+    Plot(PlotCmdArgs),
+    /// Summarize a previously saved CSV/JSON export without fetching.
+    Stats(StatsCmdArgs),
+    /// Rank countries on an indicator in one year, with rank changes since a baseline year.
+    Rank(RankCmdArgs),
+    /// Convert a saved export to another format.
+    Convert(ConvertCmdArgs),
+    /// Run the `get` jobs listed in a TOML manifest.
+    Batch(BatchCmdArgs),
+    /// Compare two saved snapshots. Exit code: 0 identical, 1 differences, 2 error.
+    Diff(DiffCmdArgs),
+    /// Compare two rendered charts (PNG or SVG). Exit code: 0 similar enough, 1 not, 2 error.
+    ChartDiff(ChartDiffCmdArgs),
+    /// Inspect or clean the HTTP response cache used by `get --cache-ttl`.
+    Cache(CacheCmdArgs),
+    /// Fetch data and write a Markdown/HTML report with chart, stats, coverage and sources.
+    Report(ReportCmdArgs),
+    /// Check country and indicator codes (with suggestions for typos) before a long fetch.
+    Validate(ValidateCmdArgs),
+    /// Run a `get` preset from the config file, or list the presets.
+    Preset(PresetCmdArgs),
+    /// Show recent `get` and `preset` commands, or with --fetches the logged API calls.
+    History(HistoryCmdArgs),
+    /// Serve /data, /stats and /chart.svg over HTTP, e.g. for dashboards.
+    Serve(ServeCmdArgs),
+    /// Save the API responses of a query as JSON fixtures for offline tests.
+    Record(RecordCmdArgs),
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ErrorFormat {
+    /// `error: message: cause: …`
+    Text,
+    /// `{"error": {"kind", "exit_code", "message", "causes"}}` on one line
+    Json,
+}
+
+/// Class of a failure; decides the exit code and the `kind` of JSON errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    Other = 1,
+    Usage = 2,
+    Network = 3,
+    Api = 4,
+    Empty = 5,
+    Io = 6,
+    Data = 7,
+}
+
+impl Failure {
+    /// Classify by the most specific error in the chain: API errors first (they may wrap I/O
+    /// errors from the HTTP stack), then empty results and file errors.
+    fn of(err: &anyhow::Error) -> Self {
+        for cause in err.chain() {
+            if let Some(e) = cause.downcast_ref::<wbi_rs::ApiError>() {
+                return match e {
+                    wbi_rs::ApiError::Network(_) => Failure::Network,
+                    wbi_rs::ApiError::Http(_) | wbi_rs::ApiError::Api(_) => Failure::Api,
+                    wbi_rs::ApiError::TooManyRows { .. } => Failure::Usage,
+                };
+            }
+        }
+        if err.chain().any(|c| c.is::<UsageError>()) {
+            Failure::Usage
+        } else if err.chain().any(|c| c.is::<EmptyResult>()) {
+            Failure::Empty
+        } else if err.chain().any(|c| {
+            c.is::<std::io::Error>()
+                || c.downcast_ref::<csv::Error>()
+                    .is_some_and(|e| matches!(e.kind(), csv::ErrorKind::Io(_)))
+                || c.downcast_ref::<serde_json::Error>()
+                    .is_some_and(serde_json::Error::is_io)
+        }) {
+            Failure::Io
+        } else {
+            Failure::Other
+        }
+    }
+
+    fn kind(self) -> &'static str {
+        match self {
+            Failure::Other => "other",
+            Failure::Usage => "usage",
+            Failure::Network => "network",
+            Failure::Api => "api",
+            Failure::Empty => "empty_result",
+            Failure::Io => "io",
+            Failure::Data => "invalid_data",
+        }
+    }
+
+    /// For `diff` and `chart-diff`, where 1 means "the inputs differ": errors that fit no
+    /// other class concern the inputs being compared.
+    fn of_comparison(err: &anyhow::Error) -> Self {
+        match Self::of(err) {
+            Failure::Other => Failure::Data,
+            failure => failure,
+        }
+    }
+}
+
+/// A flag value or combination rejected after clap parsed the command line; exits like a
+/// clap error.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct UsageError(String);
+
+/// The query succeeded but matched no observations.
+#[derive(Debug, thiserror::Error)]
+#[error("no observations for this query (check codes and --date)")]
+struct EmptyResult;
+
+/// Print `err` to stderr in `format` and return its exit code.
+fn report_error(failure: Failure, err: &anyhow::Error, format: ErrorFormat) -> u8 {
+    match format {
+        ErrorFormat::Text => tracing::error!("{err:#}"),
+        ErrorFormat::Json => {
+            let causes: Vec<String> = err.chain().skip(1).map(|c| c.to_string()).collect();
+            // clap renders a whole usage block; its first line is the message.
+            let message = match err.downcast_ref::<clap::Error>() {
+                Some(e) => {
+                    let text = e.to_string();
+                    let first = text.lines().next().unwrap_or_default();
+                    first.strip_prefix("error: ").unwrap_or(first).to_string()
+                }
+                None => err.to_string(),
+            };
+            let body = serde_json::json!({
+                "error": {
+                    "kind": failure.kind(),
+                    "exit_code": failure as u8,
+                    "message": message,
+                    "causes": causes,
+                }
+            });
+            eprintln!("{body}");
+        }
+    }
+    failure as u8
 }
 
 #[derive(Clone, Copy, Debug, clap::ValueEnum, PartialEq, Eq)]
@@ -34,6 +207,8 @@ enum LegendPos {
     Right,
     Top,
     Bottom,
+    Hidden,
+    Direct,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -45,31 +220,845 @@ enum PlotKindArg {
     StackedArea,
     GroupedBar,
     Loess,
+    XyScatter,
+    Envelope,
+    FanChart,
+    Pareto,
+    Waterfall,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum CopyArg {
+    /// The chart as an image (the last one with --plot-per)
+    Chart,
+    /// The rows as CSV
+    Data,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum EmitArg {
+    /// A `wbi get …` command line
+    Command,
+    /// A `[[jobs]]` table for `wbi batch`
+    Manifest,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum PlotPerArg {
+    Indicator,
+    Country,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum BarGroupsArg {
+    /// One group per year, one bar per series
+    Years,
+    /// One group per country, one bar per indicator (latest year with data)
+    Countries,
+    /// One group per indicator, one bar per country (latest year with data)
+    Indicators,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum BarOrderArg {
+    /// Legend order in every group
+    Series,
+    /// Smallest value first, per group
+    Ascending,
+    /// Largest value first, per group
+    Descending,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum StackNegativesArg {
+    /// Draw negative values as zero, with a warning per affected series
+    Clamp,
+    /// Refuse to plot when any value is negative
+    Error,
+    /// Stack negative values below the zero line
+    Mirror,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum CornerArg {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum StyleModeArg {
+    /// One palette colour per series
+    Palette,
+    /// One base colour per country; indicators vary by shade, marker and dash
+    Country,
+    /// One base colour per indicator; countries vary by shade, marker and dash
+    Indicator,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ConvertFormatArg {
+    /// Long CSV, one observation per row (as `get --out`)
+    Csv,
+    /// Pretty JSON array (as `get --out`)
+    Json,
+    /// Wide CSV, one row per indicator and country, one column per year (write-only)
+    CsvWide,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum StatsFormatArg {
+    /// Aligned table with box-drawing borders
+    Table,
+    /// JSON array of summaries (raw numbers, no locale)
+    Json,
+    /// CSV with a header row (raw numbers, no locale)
+    Csv,
+    /// GitHub-flavoured markdown table
+    Markdown,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum GroupByArg {
+    /// One row per country and indicator
+    Country,
+    /// One row per indicator, pooling all countries and years
+    Indicator,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum YScaleArg {
+    Auto,
+    None,
+    Thousands,
+    Millions,
+    Billions,
+    Trillions,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum TransformArg {
+    /// Divide by total population (fetched automatically)
+    PerCapita,
+    /// Convert current to constant prices with the GDP deflator (fetched automatically)
+    Deflate,
+    /// Rebase each series to 100 in --index-year
+    Index,
+    /// Year-over-year growth in percent
+    YoyGrowth,
+    /// First --pair country minus the second, per indicator
+    Difference,
+    /// First --pair country divided by the second, per indicator
+    Ratio,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ErrorBarsArg {
+    /// ± half a unit in the last reported decimal place
+    Decimal,
+    /// ±5% on observations flagged with an obs_status (estimates)
+    Estimates,
+}
+
+#[derive(Args, Debug)]
+struct PlotCmdArgs {
+    /// Saved observations (.csv or .json, as written by `get --out`), or a chart drawn with
+    /// --plot-data-embed (.svg or .html).
+    #[arg(long = "in")]
+    input: PathBuf,
+    /// Input format (csv or json). If omitted, inferred from the --in extension.
+    #[arg(long, value_enum)]
+    format: Option<OutFormat>,
+    /// Chart output path (.svg, .pdf or .png).
+    #[arg(long)]
+    out: PathBuf,
+    /// One chart per indicator or country (path gets `_{indicator}`/`_{country}` if missing)
+    #[arg(long = "plot-per", value_enum)]
+    plot_per: Option<PlotPerArg>,
+    /// Open the chart(s) in the system default viewer afterwards
+    #[arg(long, default_value_t = false)]
+    open: bool,
+    /// Put the chart or the data on the clipboard (needs the `clipboard` feature)
+    #[arg(long, value_enum, value_name = "WHAT")]
+    copy: Option<CopyArg>,
+    #[command(flatten)]
+    select: SelectArgs,
+    #[command(flatten)]
+    chart: PlotArgs,
+}
+
+#[derive(Args, Debug)]
+struct RecordCmdArgs {
+    #[command(flatten)]
+    fetch: FetchArgs,
+    /// Directory for the fixtures, e.g. tests/fixtures; replay them with
+    /// `Client::with_fixtures(.., FixtureMode::Replay)`
+    #[arg(long = "fixtures-dir")]
+    fixtures_dir: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct CacheCmdArgs {
+    /// Cache directory (default: ~/.cache/wbi/http)
+    #[arg(long = "cache-dir")]
+    cache_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    action: CacheAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheAction {
+    /// Print location, entry count, size and fetch times, and the cached indicator count.
+    Info,
+    /// Delete all cached responses and cached indicator metadata.
+    Clear,
+    /// Delete responses fetched longer ago than --older-than.
+    Prune {
+        /// Age such as 30d, 12h, 90m or 2w
+        #[arg(long = "older-than", value_parser = parse_duration)]
+        older_than: std::time::Duration,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum DiffFormatArg {
+    /// One line per difference plus a summary
+    Text,
+    /// JSON object with `added`, `removed` and `changed` arrays
+    Json,
+}
+
+#[derive(Args, Debug)]
+struct DiffCmdArgs {
+    /// Older snapshot (.csv or .json)
+    old: PathBuf,
+    /// Newer snapshot (.csv or .json)
+    new: PathBuf,
+    /// Report layout.
+    #[arg(long, value_enum, default_value_t = DiffFormatArg::Text)]
+    format: DiffFormatArg,
+    /// Fill each snapshot's series with empty rows for the years it covers, so a year left
+    /// out by one snapshot and empty in the other is no difference (e.g. across providers)
+    #[arg(long = "align-years", default_value_t = false)]
+    align_years: bool,
+}
+
+#[derive(Args, Debug)]
+struct ChartDiffCmdArgs {
+    /// Reference chart (.png or .svg)
+    a: PathBuf,
+    /// Chart to compare with it, in the same format
+    b: PathBuf,
+    /// Write a diff image (PNG for PNG charts, SVG for SVG charts): what differs in red over
+    /// the faded second chart
+    #[arg(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+    /// Largest per-channel difference (0-255) at which two pixels still count as equal (PNG)
+    #[arg(long, default_value_t = 0)]
+    tolerance: u8,
+    /// Exit with 0 when the similarity is at least this, e.g. 0.99 (default: identical)
+    #[arg(long = "min-similarity", default_value_t = 1.0, value_parser = parse_fraction)]
+    min_similarity: f64,
+}
+
+#[derive(Args, Debug)]
+struct BatchCmdArgs {
+    /// Manifest with one `[[jobs]]` table per run; keys are `get` flags (e.g. plot-kind = "area").
+    manifest: PathBuf,
+    /// Number of jobs to run at the same time.
+    #[arg(short = 'j', long = "jobs", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=64))]
+    jobs: u32,
+}
+
+#[derive(Args, Debug)]
+struct ConvertCmdArgs {
+    /// Saved observations (.csv or .json, as written by `get --out`).
+    #[arg(long = "in")]
+    input: PathBuf,
+    /// Input format (csv or json). If omitted, inferred from the --in extension.
+    #[arg(long, value_enum)]
+    from: Option<OutFormat>,
+    /// Converted output path.
+    #[arg(long)]
+    out: PathBuf,
+    /// Output format. If omitted, inferred from the --out extension (.csv or .json).
+    #[arg(long, value_enum)]
+    to: Option<ConvertFormatArg>,
+    #[command(flatten)]
+    csv: CsvArgs,
+}
+
+/// Number formatting of CSV output, shared by `get` and `convert`.
+#[derive(Args, Debug, Clone)]
+struct CsvArgs {
+    /// Write CSV values with this locale's decimal separator (de, fr, es, it, pt, nl: 1234,5)
+    #[arg(long = "csv-locale", value_name = "TAG")]
+    csv_locale: Option<String>,
+    /// Group the thousands of CSV values (1.234,5 with --csv-locale de)
+    #[arg(long = "csv-grouping", default_value_t = false)]
+    csv_grouping: bool,
+    /// CSV field delimiter, e.g. ";" or "tab" (default: ";" when --csv-locale uses a decimal
+    /// comma, "," otherwise)
+    #[arg(long = "csv-delimiter", value_name = "CHAR", value_parser = parse_delimiter)]
+    csv_delimiter: Option<u8>,
+}
+
+impl CsvArgs {
+    fn options(&self) -> storage::CsvOptions {
+        storage::CsvOptions {
+            locale: self.csv_locale.clone(),
+            group_thousands: self.csv_grouping,
+            delimiter: self.csv_delimiter,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct StatsCmdArgs {
+    /// Saved observations (.csv or .json, as written by `get --out`).
+    #[arg(long = "in")]
+    input: PathBuf,
+    /// Input format (csv or json). If omitted, inferred from the --in extension.
+    #[arg(long, value_enum)]
+    format: Option<OutFormat>,
+    /// Output layout for the summaries.
+    #[arg(long, value_enum, default_value_t = StatsFormatArg::Table)]
+    stats_format: StatsFormatArg,
+    /// Grouping of the summaries.
+    #[arg(long, value_enum, default_value_t = GroupByArg::Country)]
+    group_by: GroupByArg,
+    /// Projected values (.csv or .json): print their divergence from --in instead of summaries
+    #[arg(long, value_name = "PATH", conflicts_with = "group_by")]
+    projection: Option<PathBuf>,
+    /// Print a data quality score per series (completeness, recency, estimates; revisions for
+    /// a SQLite store) instead of summaries
+    #[arg(long, default_value_t = false, conflicts_with_all = ["group_by", "projection"])]
+    quality: bool,
+}
+
+#[derive(Args, Debug)]
+struct RankCmdArgs {
+    /// Indicator to rank by (e.g., NY.GDP.PCAP.CD)
+    #[arg(short, long)]
+    indicator: String,
+    /// Year of the ranking
+    #[arg(long)]
+    year: i32,
+    /// Year to compare ranks with (↑ moved up, ↓ moved down)
+    #[arg(long)]
+    baseline: Option<i32>,
+    /// Countries to rank, separated by comma or semicolon (or @set); `all` skips regions and
+    /// income groups
+    #[arg(short, long, default_value = "all")]
+    countries: String,
+    /// Rank saved observations (.csv or .json) instead of fetching
+    #[arg(long = "in", conflicts_with = "countries")]
+    input: Option<PathBuf>,
+    /// Only list ranks 1 to N
+    #[arg(long)]
+    top: Option<usize>,
+    /// Output layout.
+    #[arg(long, value_enum, default_value_t = StatsFormatArg::Table)]
+    format: StatsFormatArg,
 }
 
 #[derive(Args, Debug)]
 struct GetArgs {
-    /// Country/region codes separated by comma or semicolon (e.g., DEU,USA or EUU)
+    #[command(flatten)]
+    fetch: FetchArgs,
+    /// Save results to file (format inferred by --format or extension).
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Output format (csv or json). If omitted, inferred from --out extension.
+    #[arg(long, value_enum)]
+    format: Option<OutFormat>,
+    #[command(flatten)]
+    csv: CsvArgs,
+    /// Create a chart at the given path (.svg, .pdf or .png).
+    #[arg(long)]
+    plot: Option<PathBuf>,
+    /// One chart per indicator or country (path gets `_{indicator}`/`_{country}` if missing)
+    #[arg(long = "plot-per", value_enum, requires = "plot")]
+    plot_per: Option<PlotPerArg>,
+    /// Open the chart(s) in the system default viewer afterwards
+    #[arg(
+        long,
+        default_value_t = false,
+        requires = "plot",
+        conflicts_with = "refresh"
+    )]
+    open: bool,
+    /// Keep running and update --out every interval (e.g. 24h), fetching only recent years
+    #[arg(long, value_parser = parse_duration, requires = "out")]
+    refresh: Option<std::time::Duration>,
+    /// Put the chart (needs --plot) or the data on the clipboard (needs the `clipboard` feature)
+    #[arg(long, value_enum, value_name = "WHAT", conflicts_with = "refresh")]
+    copy: Option<CopyArg>,
+    /// With --refresh: update once if --out is older than the interval, then exit (for cron)
+    #[arg(long = "once-if-stale", default_value_t = false, requires = "refresh")]
+    once_if_stale: bool,
+    /// Print grouped statistics to stdout, plus the divergence of --projection from the
+    /// fetched values.
+    #[arg(long, default_value_t = false)]
+    stats: bool,
+    /// Layout of the --stats output.
+    #[arg(long, value_enum, default_value_t = StatsFormatArg::Table, requires = "stats")]
+    stats_format: StatsFormatArg,
+    /// Print the flags given as a `wbi get` command or a batch manifest job instead of running
+    #[arg(long, value_enum, value_name = "FORM")]
+    emit: Option<EmitArg>,
+    #[command(flatten)]
+    select: SelectArgs,
+    #[command(flatten)]
+    chart: PlotArgs,
+}
+
+#[derive(Args, Debug)]
+struct ReportCmdArgs {
+    #[command(flatten)]
+    fetch: FetchArgs,
+    /// Report path: .md (chart saved next to it as .svg) or .html (chart inlined)
+    #[arg(long)]
+    out: PathBuf,
+    /// Template with {{placeholder}} slots replacing the built-in one
+    #[arg(long)]
+    template: Option<PathBuf>,
+    /// Append each indicator's definition and source organization (fills {{definitions}})
+    #[arg(long, default_value_t = false)]
+    definitions: bool,
+    /// Append each series' methodology and country footnotes from the metadata API (fills
+    /// {{appendix}}; two requests per indicator and country)
+    #[arg(long, default_value_t = false)]
+    appendix: bool,
+    /// Open the report in the system default viewer/browser afterwards
+    #[arg(long, default_value_t = false)]
+    open: bool,
+    #[command(flatten)]
+    select: SelectArgs,
+    #[command(flatten)]
+    chart: PlotArgs,
+}
+
+#[derive(Args, Debug)]
+#[command(group(clap::ArgGroup::new("codes").required(true).multiple(true)))]
+struct ValidateCmdArgs {
+    /// Country/region codes separated by comma or semicolon (e.g., DEU,USA or @set)
+    #[arg(short, long, group = "codes")]
+    countries: Option<String>,
+    /// Indicator codes separated by comma or semicolon (e.g., SP.POP.TOTL or @set)
+    #[arg(short, long, group = "codes")]
+    indicators: Option<String>,
+    /// Only check against the built-in country list; indicators are then only checked for
+    /// well-formedness
+    #[arg(long, default_value_t = false)]
+    offline: bool,
+}
+
+#[derive(Args, Debug)]
+struct PresetCmdArgs {
+    /// Preset name (a `[presets.NAME]` table in the config file); omit to list the presets.
+    name: Option<String>,
+    /// More `get` flags; they win over the preset's values.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, requires = "name")]
+    args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+struct HistoryCmdArgs {
+    /// Number of commands (or API calls with --fetches) to show, newest last.
+    #[arg(short = 'n', long, default_value_t = 10)]
+    limit: usize,
+    /// Summarize the API calls recorded by --fetch-log instead of listing commands
+    #[arg(long, default_value_t = false)]
+    fetches: bool,
+    /// Fetch log to read with --fetches (default: ~/.cache/wbi/http/fetch.log.jsonl)
+    #[arg(long, value_name = "PATH", requires = "fetches")]
+    log: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ServeCmdArgs {
+    /// Port to listen on
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+    /// Address to listen on; the default only accepts local connections
+    #[arg(long, default_value = "127.0.0.1")]
+    bind: std::net::IpAddr,
+    /// Reuse API responses younger than this (e.g. 1h, 10m)
+    #[arg(long = "cache-ttl", value_parser = parse_duration, default_value = "1h")]
+    cache_ttl: std::time::Duration,
+    /// Cache directory (default: ~/.cache/wbi/http)
+    #[arg(long = "cache-dir")]
+    cache_dir: Option<PathBuf>,
+}
+
+/// Query flags shared by `get` and `report`.
+#[derive(Args, Debug)]
+struct FetchArgs {
+    /// Country/region codes separated by comma or semicolon (e.g., DEU,USA, EUU or @G7)
     #[arg(short, long)]
     countries: String,
     /// Indicator codes separated by comma or semicolon (e.g., SP.POP.TOTL)
     #[arg(short, long)]
     indicators: String,
-    /// Year (YYYY) or range (YYYY:YYYY)
+    /// Year (YYYY), range (YYYY:YYYY), open range (YYYY: or :YYYY), last:N years, or mrv:N
+    /// most recent values
     #[arg(short = 'd', long)]
     date: Option<String>,
     /// Source id (e.g., 2 for WDI). Required by API when requesting multiple indicators.
     #[arg(long)]
     source: Option<u32>,
-    /// Save results to file (format inferred by --format or extension).
-    #[arg(long)]
-    out: Option<PathBuf>,
-    /// Output format (csv or json). If omitted, inferred from --out extension.
-    #[arg(long, value_enum)]
-    format: Option<OutFormat>,
-    /// Create a chart at the given path (.svg or .png).
+    /// Reuse cached API responses younger than this (e.g. 1d, 12h); off unless set
+    #[arg(long = "cache-ttl", value_parser = parse_duration)]
+    cache_ttl: Option<std::time::Duration>,
+    /// Cache directory for --cache-ttl (default: ~/.cache/wbi/http)
+    #[arg(long = "cache-dir")]
+    cache_dir: Option<PathBuf>,
+    /// Append one line per API call (url, time, status, rows) to fetch.log.jsonl in the cache
+    /// directory, or next to the report for `wbi report`; see `wbi history --fetches`
+    #[arg(long = "fetch-log", default_value_t = false)]
+    fetch_log: bool,
+    /// Fail before fetching when the query would return more than N rows (estimated from
+    /// countries × indicators × years); large queries are split into several requests anyway
+    #[arg(long = "max-rows", value_name = "N")]
+    max_rows: Option<u64>,
+}
+
+/// A query with `@set`s expanded, the date resolved and the client configured.
+struct Query {
+    client: Client,
+    countries: Vec<String>,
+    indicators: Vec<String>,
+    date: DateSpec,
+    source: Option<u32>,
+    /// Custom aggregates among `countries`, fetched as their members.
+    aggregates: Vec<stats::CustomAggregate>,
+}
+
+impl FetchArgs {
+    fn resolve(&self, config: &Config) -> Result<Query> {
+        self.resolve_with(config, new_client())
+    }
+
+    /// [`Self::resolve`] on top of `client` instead of the default one.
+    fn resolve_with(&self, config: &Config, mut client: Client) -> Result<Query> {
+        if let Some(ttl) = self.cache_ttl {
+            client = client.with_cache(open_cache(self.cache_dir.as_deref())?.with_ttl(ttl));
+        }
+        if self.fetch_log {
+            client =
+                client.with_fetch_log(FetchLog::new(fetch_log_path(self.cache_dir.as_deref())?));
+        }
+        if let Some(max_rows) = self.max_rows {
+            client = client.with_max_rows(max_rows);
+        }
+        let date = match &self.date {
+            Some(s) => s
+                .parse::<DateSpec>()
+                .map_err(|e| UsageError(format!("invalid --date: {e:#}")))?,
+            None => DateSpec::Range {
+                start: 2000,
+                end: 2020,
+            },
+        };
+        let countries = expand_list(&self.countries, &config.country_sets, "country")?;
+        Ok(Query {
+            client,
+            aggregates: config.aggregates_in(&countries),
+            countries,
+            indicators: expand_list(&self.indicators, &config.indicator_sets, "indicator")?,
+            date,
+            source: self.source,
+        })
+    }
+}
+
+impl Query {
+    /// Fetch the observations, with a progress bar.
+    fn fetch(&self) -> Result<Vec<wbi_rs::models::DataPoint>> {
+        self.fetch_date(self.date)
+    }
+
+    /// Fetch only what may have changed since `saved` was written and merge it in: the years
+    /// from the earliest "latest year with data" across saved series onwards. Falls back to a
+    /// full fetch when the date spec has no year range to narrow.
+    fn fetch_incremental(
+        &self,
+        saved: &[wbi_rs::models::DataPoint],
+    ) -> Result<Vec<wbi_rs::models::DataPoint>> {
+        use chrono::Datelike;
+
+        let mut latest: HashMap<(&str, &str), i32> = HashMap::new();
+        for p in saved.iter().filter(|p| p.value.is_some_and(f64::is_finite)) {
+            let year = latest
+                .entry((p.indicator_id.as_str(), p.country_iso3.as_str()))
+                .or_insert(p.year);
+            *year = (*year).max(p.year);
+        }
+        let since = latest.values().min().copied();
+        let date = match (self.date.resolve(chrono::Utc::now().year()), since) {
+            (DateSpec::Range { start, end }, Some(since)) if since > start && since <= end => {
+                DateSpec::Range { start: since, end }
+            }
+            (other, _) => other,
+        };
+        tracing::debug!("incremental fetch for {}", date.to_query_param());
+        Ok(wbi_rs::diff::merge(saved, &self.fetch_date(date)?))
+    }
+
+    fn fetch_date(&self, date: DateSpec) -> Result<Vec<wbi_rs::models::DataPoint>> {
+        let bar = fetch_progress_bar();
+        let points = self.fetch_rows(&self.indicators, date, |p| {
+            bar.set_length(u64::from(p.pages));
+            bar.set_position(u64::from(p.page));
+            bar.set_message(format!("{}/{} · {} rows", p.request, p.requests, p.rows));
+        })?;
+        bar.finish_and_clear();
+        Ok(points)
+    }
+
+    /// `indicators` for the query's countries in `date`. Custom aggregates are fetched as
+    /// their members (plus population, when they may weight by it) and combined; rows
+    /// fetched only for that are dropped.
+    fn fetch_rows(
+        &self,
+        indicators: &[String],
+        date: DateSpec,
+        on_progress: impl FnMut(wbi_rs::FetchProgress),
+    ) -> Result<Vec<wbi_rs::models::DataPoint>> {
+        if self.aggregates.is_empty() {
+            return self.client.fetch_with_progress(
+                &self.countries,
+                indicators,
+                Some(date),
+                self.source,
+                on_progress,
+            );
+        }
+        let mut countries: Vec<String> = Vec::new();
+        for c in &self.countries {
+            let members = match self
+                .aggregates
+                .iter()
+                .find(|a| a.code.eq_ignore_ascii_case(c))
+            {
+                Some(a) => a.members.as_slice(),
+                None => std::slice::from_ref(c),
+            };
+            for m in members {
+                if !countries.iter().any(|c| c.eq_ignore_ascii_case(m)) {
+                    countries.push(m.clone());
+                }
+            }
+        }
+        let mut points = self.client.fetch_with_progress(
+            &countries,
+            indicators,
+            Some(date),
+            self.source,
+            on_progress,
+        )?;
+        let weighted = self.aggregates.iter().any(|a| {
+            matches!(
+                a.method,
+                stats::AggregateMethod::Auto | stats::AggregateMethod::PopulationWeighted
+            )
+        });
+        if weighted
+            && !indicators
+                .iter()
+                .any(|i| i.eq_ignore_ascii_case(stats::POPULATION_INDICATOR))
+        {
+            points.extend(self.client.fetch(
+                &countries,
+                &[stats::POPULATION_INDICATOR.to_string()],
+                Some(date),
+                None,
+            )?);
+        }
+        let aggregated = stats::custom_aggregates(&points, &self.aggregates);
+        let requested = |p: &wbi_rs::models::DataPoint| {
+            indicators
+                .iter()
+                .any(|i| i.eq_ignore_ascii_case(&p.indicator_id))
+                && self.countries.iter().any(|c| {
+                    c.eq_ignore_ascii_case("all")
+                        || c.eq_ignore_ascii_case(&p.country_iso3)
+                        || c.eq_ignore_ascii_case(&p.country_id)
+                })
+        };
+        Ok(points
+            .into_iter()
+            .chain(aggregated)
+            .filter(requested)
+            .collect())
+    }
+
+    /// Rows the chart needs on top of the export: population for per-capita, the deflator
+    /// for deflate, fetched separately so exports stay as requested.
+    fn plot_extras(&self, options: &viz::PlotOptions) -> Result<Vec<wbi_rs::models::DataPoint>> {
+        let extra = match options.transform {
+            viz::PlotTransform::PerCapita => stats::POPULATION_INDICATOR,
+            viz::PlotTransform::Deflate => stats::DEFLATOR_INDICATOR,
+            _ => return Ok(Vec::new()),
+        };
+        if self
+            .indicators
+            .iter()
+            .any(|i| i.eq_ignore_ascii_case(extra))
+        {
+            return Ok(Vec::new());
+        }
+        self.fetch_rows(&[extra.to_string()], self.date, |_| {})
+    }
+}
+
+/// The chart path template for `--plot-per`: `path` itself if it already names the group's
+/// placeholder, otherwise with `_{indicator}` / `_{country}` added before the extension.
+fn per_chart_template(path: &Path, per: Option<PlotPerArg>) -> PathBuf {
+    let placeholder = match per {
+        None => return path.to_path_buf(),
+        Some(PlotPerArg::Indicator) => "{indicator}",
+        Some(PlotPerArg::Country) => "{country}",
+    };
+    if path.to_string_lossy().contains(placeholder) {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}_{placeholder}.{}", ext.to_string_lossy()),
+        None => format!("{stem}_{placeholder}"),
+    };
+    path.with_file_name(name)
+}
+
+/// Expand `{indicator}`, `{country}`, `{date}` and `{today}` in an output path.
+///
+/// A path naming `{indicator}` and/or `{country}` splits `points` into one output per value
+/// (ISO3 code for countries). `{date}` is the year span of each output's rows (`2000-2020`),
+/// `{today}` the current date. A path without placeholders yields one output with all rows.
+fn outputs_for(
+    template: &Path,
+    points: &[wbi_rs::models::DataPoint],
+) -> Vec<(PathBuf, Vec<wbi_rs::models::DataPoint>)> {
+    use std::collections::BTreeMap;
+
+    let t = template.to_string_lossy();
+    if !t.contains('{') {
+        return vec![(template.to_path_buf(), points.to_vec())];
+    }
+    let by_indicator = t.contains("{indicator}");
+    let by_country = t.contains("{country}");
+    let mut groups: BTreeMap<(&str, &str), Vec<wbi_rs::models::DataPoint>> = BTreeMap::new();
+    for p in points {
+        let key = (
+            if by_indicator {
+                p.indicator_id.as_str()
+            } else {
+                ""
+            },
+            if by_country {
+                p.country_iso3.as_str()
+            } else {
+                ""
+            },
+        );
+        groups.entry(key).or_default().push(p.clone());
+    }
+    if groups.is_empty() {
+        groups.insert(("", ""), Vec::new());
+    }
+
+    // Keep separators and other special characters out of file names.
+    let clean = |s: &str| s.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_");
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    groups
+        .into_iter()
+        .map(|((indicator, country), rows)| {
+            let years = (
+                rows.iter().map(|p| p.year).min(),
+                rows.iter().map(|p| p.year).max(),
+            );
+            let date = match years {
+                (Some(a), Some(b)) if a == b => a.to_string(),
+                (Some(a), Some(b)) => format!("{a}-{b}"),
+                _ => "nodata".to_string(),
+            };
+            let path = t
+                .replace("{indicator}", &clean(indicator))
+                .replace("{country}", &clean(country))
+                .replace("{date}", &date)
+                .replace("{today}", &today);
+            (PathBuf::from(path), rows)
+        })
+        .collect()
+}
+
+/// Row selection shared by `get`, `plot` and `report`, applied before export, stats and
+/// plotting.
+#[derive(Args, Debug)]
+struct SelectArgs {
+    /// Drop series: countries, indicator ids or COUNTRY:INDICATOR pairs, separated by ',' or ';'
+    #[arg(long, value_name = "SERIES")]
+    exclude: Option<String>,
+    /// Drop series with a value in fewer than PCT percent of the years (e.g. 80)
+    #[arg(long = "min-coverage", value_name = "PCT", value_parser = parse_percent)]
+    min_coverage: Option<f64>,
+    /// Drop series whose quality score (0-1, see `wbi stats --quality`) is below SCORE
+    #[arg(long = "min-quality", value_name = "SCORE", value_parser = parse_fraction)]
+    min_quality: Option<f64>,
+    /// Drop rows without a value
+    #[arg(long, default_value_t = false)]
+    dropna: bool,
+    /// Keep only the N highest-ranked countries on the first indicator; countries without a
+    /// ranking value are dropped
     #[arg(long)]
-    plot: Option<PathBuf>,
+    top: Option<usize>,
+    /// Ranking for --top: last (latest value), mean, or year=YYYY
+    #[arg(long, requires = "top", default_value = "last", value_parser = parse_rank_by)]
+    by: stats::RankBy,
+}
+
+impl SelectArgs {
+    /// Apply `--exclude`, `--min-coverage`, `--min-quality`, then `--top` (ranking on
+    /// `indicator`, default: the first indicator in `points`), then `--dropna`. Coverage and
+    /// quality come before `--dropna` so they still see the gaps.
+    fn apply(
+        &self,
+        mut points: Vec<wbi_rs::models::DataPoint>,
+        indicator: Option<&str>,
+    ) -> Result<Vec<wbi_rs::models::DataPoint>> {
+        if let Some(exclude) = &self.exclude {
+            points = stats::exclude_series(&points, &parse_list(exclude));
+        }
+        if let Some(pct) = self.min_coverage {
+            points = stats::filter_coverage(&points, pct / 100.0);
+        }
+        if let Some(score) = self.min_quality {
+            points = stats::filter_quality(&points, score);
+        }
+        if let Some(n) = self.top {
+            let indicator = indicator
+                .map(str::to_string)
+                .or_else(|| points.first().map(|p| p.indicator_id.clone()));
+            if let Some(indicator) = indicator {
+                points = stats::top_countries(&points, &indicator, n, self.by)?;
+            }
+        }
+        if self.dropna {
+            points = stats::drop_missing(&points);
+        }
+        Ok(points)
+    }
+}
+
+/// Chart options shared by `get --plot` and `plot`.
+#[derive(Args, Debug)]
+struct PlotArgs {
     /// Width of the plot (default 1000).
     #[arg(long, default_value_t = 1000)]
     width: u32,
@@ -79,25 +1068,198 @@ struct GetArgs {
     /// Title for the chart (defaults to "World Bank Indicator(s)")
     #[arg(long)]
     title: Option<String>,
-    /// Print grouped statistics to stdout.
-    #[arg(long, default_value_t = false)]
-    stats: bool,
-    /// Locale for number formatting in chart labels & stats (e.g., en, de, fr). Default: en
-    #[arg(long, default_value = "en")]
-    locale: String,
-    /// Legend placement: inside (overlay), right (panel), top (band), or bottom (band).
+    /// Legend placement: inside (overlay), right (panel), top (band), bottom (band), hidden, or
+    /// direct (labels at the line ends)
     /// Default: bottom
     #[arg(long, value_enum, default_value_t = LegendPos::Bottom)]
     legend: LegendPos,
+    /// Write the legend to its own SVG/PNG/PDF file and leave it off the chart(s); placeholders
+    /// as in the chart path, without them one legend for all charts
+    #[arg(long = "legend-out", value_name = "PATH")]
+    legend_out: Option<PathBuf>,
     /// Chart type: line, scatter, line-points, or area (default: line)
     #[arg(long = "plot-kind", value_enum, default_value = "line")]
     plot_kind: PlotKindArg,
     /// LOESS span in (0,1]; fraction of neighbors used (only for --plot-kind loess)
     #[arg(long = "loess-span", default_value_t = 0.3, value_parser = parse_loess_span)]
     loess_span: f64,
-    /// Enable country-consistent styling
-    #[arg(long = "country-styles", default_value_t = false)]
+    /// Enable country-consistent styling (same as --style-mode country)
+    #[arg(
+        long = "country-styles",
+        default_value_t = false,
+        conflicts_with = "style_mode"
+    )]
     country_styles: bool,
+    /// Series colouring: palette, country (colour per country) or indicator (colour per indicator)
+    #[arg(long = "style-mode", value_enum)]
+    style_mode: Option<StyleModeArg>,
+    /// TOML file with house style overrides (colours and dashes by ISO3 or indicator, line
+    /// width, marker size)
+    #[arg(long = "style-config")]
+    style_config: Option<PathBuf>,
+    /// JSON chart spec (as written by --save-plot-spec) to start from; chart flags given
+    /// alongside override its settings
+    #[arg(long = "plot-spec", value_name = "PATH")]
+    plot_spec: Option<PathBuf>,
+    /// Save the chart settings actually used as a JSON spec, to reuse with --plot-spec
+    #[arg(long = "save-plot-spec", value_name = "PATH")]
+    save_plot_spec: Option<PathBuf>,
+    /// Fill bars and areas with per-series patterns (hatch, dots, stripes) for print legibility
+    #[arg(long = "pattern-fills", default_value_t = false)]
+    pattern_fills: bool,
+    /// Indicator on the X axis (only for --plot-kind xy-scatter; default: first indicator)
+    #[arg(long = "x-indicator")]
+    x_indicator: Option<String>,
+    /// Indicator on the Y axis (only for --plot-kind xy-scatter; default: second indicator)
+    #[arg(long = "y-indicator")]
+    y_indicator: Option<String>,
+    /// Indicator scaling marker size (only for --plot-kind xy-scatter)
+    #[arg(long = "size-indicator")]
+    size_indicator: Option<String>,
+    /// Plot a single year instead of trails (only for --plot-kind xy-scatter)
+    #[arg(long = "xy-year")]
+    xy_year: Option<i32>,
+    /// Year to rank the countries in (only for --plot-kind pareto; default: latest year with
+    /// data)
+    #[arg(long = "pareto-year")]
+    pareto_year: Option<i32>,
+    /// Cumulative share in percent marked with a dashed line, or none (only for --plot-kind
+    /// pareto)
+    #[arg(
+        long = "pareto-reference",
+        value_name = "PCT|none",
+        value_parser = parse_pareto_reference,
+        default_value = "80"
+    )]
+    // Spelled out so clap keeps the parser's `None` (for `none`) instead of treating the
+    // flag as optional.
+    pareto_reference: std::option::Option<f64>,
+    /// Starting year (only for --plot-kind waterfall; default: earliest year with data)
+    #[arg(long = "waterfall-from")]
+    waterfall_from: Option<i32>,
+    /// Ending year (only for --plot-kind waterfall; default: latest year with data)
+    #[arg(long = "waterfall-to")]
+    waterfall_to: Option<i32>,
+    /// Groups along the X axis (only for --plot-kind grouped-bar)
+    #[arg(long = "bar-groups", value_enum, default_value_t = BarGroupsArg::Years)]
+    bar_groups: BarGroupsArg,
+    /// Order of the bars within each group (only for --plot-kind grouped-bar)
+    #[arg(long = "bar-order", value_enum, default_value_t = BarOrderArg::Series)]
+    bar_order: BarOrderArg,
+    /// Share of each group's slot left empty between groups, in [0, 1) (only for
+    /// --plot-kind grouped-bar)
+    #[arg(long = "bar-gap", value_name = "FRACTION", default_value_t = 0.2, value_parser = parse_bar_gap)]
+    bar_gap: f64,
+    /// Outline every bar (only for --plot-kind grouped-bar)
+    #[arg(long = "bar-borders", default_value_t = false)]
+    bar_borders: bool,
+    /// Widest a bar may get, in pixels (only for --plot-kind grouped-bar)
+    #[arg(long = "max-bar-width", value_name = "PX", value_parser = clap::value_parser!(u32).range(1..))]
+    max_bar_width: Option<u32>,
+    /// Negative values (only for --plot-kind stacked-area)
+    #[arg(long = "stack-negatives", value_enum, default_value_t = StackNegativesArg::Clamp)]
+    stack_negatives: StackNegativesArg,
+    /// Draw error bars derived from observation metadata (line, scatter, line-points, grouped-bar)
+    #[arg(long = "error-bars", value_enum)]
+    error_bars: Option<ErrorBarsArg>,
+    /// Error bar cap width in pixels
+    #[arg(long = "error-bar-cap", default_value_t = 6)]
+    error_bar_cap: u32,
+    /// Error bar opacity in [0, 1]
+    #[arg(long = "error-bar-opacity", default_value_t = 0.6)]
+    error_bar_opacity: f64,
+    /// Transform the data before plotting
+    #[arg(long = "transform", value_enum)]
+    transform: Option<TransformArg>,
+    /// Base year for --transform index
+    #[arg(long = "index-year")]
+    index_year: Option<i32>,
+    /// Countries compared by --transform difference|ratio, e.g. DEU,FRA
+    #[arg(long, value_name = "A,B", value_parser = parse_pair)]
+    pair: Option<(String, String)>,
+    /// Projected values (.csv or .json, same columns as `get --out`) drawn dashed over the
+    /// matching series
+    #[arg(long, value_name = "PATH", conflicts_with = "transform")]
+    projection: Option<PathBuf>,
+    /// Transparent background (SVG, or PNG with alpha)
+    #[arg(long = "transparent", default_value_t = false)]
+    transparent: bool,
+    /// Bitmap resolution multiplier, e.g. 2 for retina/print (layout unchanged; ignored for SVG)
+    #[arg(long = "scale", default_value_t = 1.0, value_parser = parse_scale)]
+    scale: f64,
+    /// TTF/OTF font file for all chart text (default: bundled DejaVu Sans)
+    #[arg(long = "font")]
+    font: Option<PathBuf>,
+    /// Family name to register --font under (default: the file name without extension)
+    #[arg(long = "font-family", requires = "font")]
+    font_family: Option<String>,
+    /// Chart title font size in pixels
+    #[arg(long = "title-size", default_value_t = 24)]
+    title_size: u32,
+    /// Axis title font size in pixels
+    #[arg(long = "axis-title-size", default_value_t = 16)]
+    axis_title_size: u32,
+    /// Tick label font size in pixels
+    #[arg(long = "tick-size", default_value_t = 12)]
+    tick_size: u32,
+    /// Legend font size in pixels
+    #[arg(long = "legend-size", default_value_t = 14)]
+    legend_size: u32,
+    /// Draw only every Nth marker (scatter, line-points; default: skip overlapping markers)
+    #[arg(long = "marker-every", conflicts_with = "marker_spacing")]
+    marker_every: Option<usize>,
+    /// Minimum pixel distance between markers; 0 draws every marker (scatter, line-points)
+    #[arg(long = "marker-spacing")]
+    marker_spacing: Option<u32>,
+    /// Y-axis unit shown in the axis title (default: derived from the data)
+    #[arg(long = "y-unit")]
+    y_unit: Option<String>,
+    /// Pin the Y-axis scale instead of choosing it from the data magnitude
+    #[arg(long = "y-scale", value_enum, default_value_t = YScaleArg::Auto)]
+    y_scale: YScaleArg,
+    /// Abbreviate tick labels (1.2M, 3,4 Mrd. with --locale de) instead of a scale word in the
+    /// axis title
+    #[arg(
+        long = "abbreviate-ticks",
+        default_value_t = false,
+        conflicts_with = "y_scale"
+    )]
+    abbreviate_ticks: bool,
+    /// Y-axis range for percentages: the data range, full (0–100, widened for values outside)
+    /// or the data range padded by a fraction of it, e.g. 0.05
+    #[arg(
+        long = "percent-axis",
+        value_name = "data|full|PAD",
+        value_parser = parse_percent_bounds,
+        default_value = "data"
+    )]
+    percent_axis: viz::PercentBounds,
+    /// Append % to the Y tick labels of percentages
+    #[arg(long = "percent-ticks", default_value_t = false)]
+    percent_ticks: bool,
+    /// Cut FROM..TO (in axis units, after --y-scale) out of the Y axis, e.g. 10:100 when one
+    /// country dwarfs the rest
+    #[arg(long = "y-break", value_name = "FROM:TO", value_parser = parse_axis_break)]
+    y_break: Option<viz::AxisBreak>,
+    /// Embed the plotted data as JSON in SVG charts and HTML reports, so `wbi plot --in` can
+    /// redraw them without the data file
+    #[arg(long = "plot-data-embed", default_value_t = false)]
+    plot_data_embed: bool,
+    /// Logo (.png, or .svg for SVG charts) drawn in the --brand-corner of every chart
+    #[arg(long, value_name = "PATH")]
+    logo: Option<PathBuf>,
+    /// Logo height in pixels; the width keeps the aspect ratio
+    #[arg(long = "logo-height", value_name = "PX", default_value_t = 32)]
+    logo_height: u32,
+    /// Watermark text next to the logo, e.g. the publisher's name
+    #[arg(long, value_name = "TEXT")]
+    watermark: Option<String>,
+    /// Chart corner for --logo and --watermark
+    #[arg(long = "brand-corner", value_enum, default_value_t = CornerArg::BottomRight)]
+    brand_corner: CornerArg,
+    /// Opacity of --logo and --watermark in [0, 1]
+    #[arg(long = "brand-opacity", default_value_t = 0.6)]
+    brand_opacity: f64,
 }
 
 fn parse_list(s: &str) -> Vec<String> {
@@ -107,64 +1269,6 @@ fn parse_list(s: &str) -> Vec<String> {
         .collect()
 }
 
-fn parse_date(s: &str) -> Option<DateSpec> {
-    if let Some((a, b)) = s.split_once(':') {
-        let start = a.parse::<i32>().ok()?;
-        let end = b.parse::<i32>().ok()?;
-        Some(DateSpec::Range { start, end })
-    } else {
-        s.parse::<i32>().ok().map(DateSpec::Year)
-    }
-}
-
-fn map_locale(tag: &str) -> (&'static Locale, char) {
-    match tag.to_lowercase().as_str() {
-        "de" | "de_de" | "german" => (&Locale::de, ','),
-        "fr" | "fr_fr" => (&Locale::fr, ','),
-        "es" | "es_es" => (&Locale::es, ','),
-        "it" | "it_it" => (&Locale::it, ','),
-        "pt" | "pt_pt" | "pt_br" => (&Locale::pt, ','),
-        "nl" | "nl_nl" => (&Locale::nl, ','),
-        _ => (&Locale::en, '.'),
-    }
-}
-
-fn fmt_float_with_locale(x: f64, loc: &Locale, dec_sep: char) -> String {
-    let mut s = format!("{:.4}", x);
-    if s.contains('.') {
-        while s.ends_with('0') {
-            s.pop();
-        }
-        if s.ends_with('.') {
-            s.pop();
-        }
-    }
-    if let Some((intp, fracp)) = s.split_once('.') {
-        let sign = if intp.starts_with('-') { "-" } else { "" };
-        let digits = intp.trim_start_matches('-');
-        let int_num: i64 = digits.parse().unwrap_or(0);
-        let grouped = int_num.to_formatted_string(loc);
-        if fracp.is_empty() {
-            format!("{}{}", sign, grouped)
-        } else {
-            format!("{}{}{}{}", sign, grouped, dec_sep, fracp)
-        }
-    } else {
-        let sign = if s.starts_with('-') { "-" } else { "" };
-        let digits = s.trim_start_matches('-');
-        let int_num: i64 = digits.parse().unwrap_or(0);
-        let grouped = int_num.to_formatted_string(loc);
-        format!("{}{}", sign, grouped)
-    }
-}
-
-fn fmt_opt_locale(v: Option<f64>, loc: &Locale, dec_sep: char) -> String {
-    match v {
-        Some(x) if x.is_finite() => fmt_float_with_locale(x, loc, dec_sep),
-        _ => "NA".to_string(),
-    }
-}
-
 // Keep using your existing enum OutFormat { Csv, Json }.
 // No need for PartialEq; we use pattern matching.
 fn decide_output_format(path: &Path, format_flag: Option<OutFormat>) -> Result<&'static str> {
@@ -201,92 +1305,2313 @@ fn decide_output_format(path: &Path, format_flag: Option<OutFormat>) -> Result<&
     Ok(fmt)
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
-    match cli.cmd {
-        Command::Get(args) => cmd_get(args),
-    }
+/// Defaults read from `~/.config/wbi/config.toml` or `--config`. Command-line flags win.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    locale: Option<String>,
+    legend: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    /// House style file; relative paths are resolved against the config file's directory.
+    style_config: Option<PathBuf>,
+    /// Chart logo (relative paths as for `style_config`).
+    logo: Option<PathBuf>,
+    /// Chart watermark text.
+    watermark: Option<String>,
+    /// Response cache directory (relative paths as for `style_config`).
+    cache_dir: Option<PathBuf>,
+    /// Enables the response cache for `get`, e.g. "1d".
+    cache_ttl: Option<String>,
+    /// Turns on `--fetch-log` for `get` and `report`.
+    fetch_log: Option<bool>,
+    /// Default `--max-rows` for `get` and `report`.
+    max_rows: Option<u64>,
+    /// Named country lists, used as `--countries @name`.
+    country_sets: HashMap<String, Vec<String>>,
+    /// Named indicator lists, used as `--indicators @name`.
+    indicator_sets: HashMap<String, Vec<String>>,
+    /// Named `get` runs for `wbi preset NAME`; keys as in batch manifest jobs.
+    presets: BTreeMap<String, toml::Table>,
+    /// User-defined aggregates by code, used like countries in `--countries`.
+    aggregates: BTreeMap<String, AggregateConfig>,
 }
 
-fn cmd_get(args: GetArgs) -> Result<()> {
-    let client = Client::default();
-    let countries = parse_list(&args.countries);
-    let indicators = parse_list(&args.indicators);
-    let date = match &args.date {
-        Some(s) => parse_date(s)
-            .ok_or_else(|| anyhow::anyhow!("invalid --date, expected YYYY or YYYY:YYYY"))?,
-        None => DateSpec::Range {
-            start: 2000,
-            end: 2020,
-        },
-    };
-
-    let points = client.fetch(&countries, &indicators, Some(date), args.source)?;
+/// An `[aggregates.CODE]` table of the config file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AggregateConfig {
+    #[serde(default)]
+    name: String,
+    members: Vec<String>,
+    #[serde(default)]
+    method: stats::AggregateMethod,
+}
 
-    if let Some(path) = args.out.as_ref() {
-        let fmt = decide_output_format(path, args.format)?;
-        match fmt {
-            "csv" => storage::save_csv(&points, path)?,
-            "json" => storage::save_json(&points, path)?,
-            other => anyhow::bail!("unsupported format: {}", other),
+impl Config {
+    /// Load `explicit` (which must exist) or the default location (skipped when absent).
+    fn load(explicit: Option<&Path>) -> Result<Self> {
+        let path = match explicit {
+            Some(p) => p.to_path_buf(),
+            None => match default_config_path() {
+                Some(p) if p.is_file() => p,
+                _ => return Ok(Self::default()),
+            },
+        };
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading config {}", path.display()))?;
+        let mut config: Self =
+            toml::from_str(&text).with_context(|| format!("parsing config {}", path.display()))?;
+        if let Some(dir) = path.parent() {
+            for p in [
+                config.style_config.as_mut(),
+                config.logo.as_mut(),
+                config.cache_dir.as_mut(),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                if p.is_relative() {
+                    *p = dir.join(&*p);
+                }
+            }
         }
-        eprintln!("Saved {} rows to {}", points.len(), path.display());
+        Ok(config)
     }
 
-    if let Some(plot_path) = args.plot.as_ref() {
-        let legend_mode = match args.legend {
-            LegendPos::Inside => viz::LegendMode::Inside,
-            LegendPos::Right => viz::LegendMode::Right,
-            LegendPos::Top => viz::LegendMode::Top,
-            LegendPos::Bottom => viz::LegendMode::Bottom,
-        };
-        let title = args.title.as_deref().unwrap_or("World Bank Indicator(s)"); //title will be overriden later
-        let plot_kind = match args.plot_kind {
-            PlotKindArg::Line => viz::PlotKind::Line,
-            PlotKindArg::Scatter => viz::PlotKind::Scatter,
-            PlotKindArg::LinePoints => viz::PlotKind::LinePoints,
-            PlotKindArg::Area => viz::PlotKind::Area,
-            PlotKindArg::StackedArea => viz::PlotKind::StackedArea,
-            PlotKindArg::GroupedBar => viz::PlotKind::GroupedBar,
-            PlotKindArg::Loess => viz::PlotKind::Loess,
-        };
-        viz::plot_chart(
-            &points,
-            plot_path,
-            args.width,
-            args.height,
-            &args.locale,
-            legend_mode,
-            title,
-            plot_kind,
-            args.loess_span,
-            Some(args.country_styles),
-        )?;
-        eprintln!("Wrote plot to {}", plot_path.display());
-    }
+    /// `(argument id, default)` pairs to install on every subcommand that has the argument.
+    fn arg_defaults(&self) -> Vec<(&'static str, String)> {
+        let mut out = Vec::new();
+        if let Some(v) = &self.locale {
+            out.push(("locale", v.clone()));
+        }
+        if let Some(v) = &self.legend {
+            out.push(("legend", v.clone()));
+        }
+        if let Some(v) = self.width {
+            out.push(("width", v.to_string()));
+        }
+        if let Some(v) = self.height {
+            out.push(("height", v.to_string()));
+        }
+        if let Some(v) = &self.style_config {
+            out.push(("style_config", v.display().to_string()));
+        }
+        if let Some(v) = &self.logo {
+            out.push(("logo", v.display().to_string()));
+        }
+        if let Some(v) = &self.watermark {
+            out.push(("watermark", v.clone()));
+        }
+        if let Some(v) = &self.cache_dir {
+            out.push(("cache_dir", v.display().to_string()));
+        }
+        if let Some(v) = &self.cache_ttl {
+            out.push(("cache_ttl", v.clone()));
+        }
+        if let Some(v) = self.fetch_log {
+            out.push(("fetch_log", v.to_string()));
+        }
+        if let Some(v) = self.max_rows {
+            out.push(("max_rows", v.to_string()));
+        }
+        out
+    }
+
+    /// The custom aggregates named in `countries`.
+    fn aggregates_in(&self, countries: &[String]) -> Vec<stats::CustomAggregate> {
+        self.aggregates
+            .iter()
+            .filter(|(code, _)| countries.iter().any(|c| c.eq_ignore_ascii_case(code)))
+            .map(|(code, a)| stats::CustomAggregate {
+                code: code.clone(),
+                name: a.name.clone(),
+                members: a.members.clone(),
+                method: a.method,
+            })
+            .collect()
+    }
+}
+
+/// `$XDG_CONFIG_HOME/wbi/config.toml`, falling back to `~/.config/wbi/config.toml`.
+fn default_config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("wbi").join("config.toml"))
+}
+
+/// Value of a global flag (`--name value` or `--name=value`) that has to be known before
+/// clap runs: `--config` supplies clap's defaults, `--error-format` shapes clap's own errors.
+fn early_flag(args: &[std::ffi::OsString], name: &str) -> Option<String> {
+    let mut it = args.iter().skip(1);
+    while let Some(arg) = it.next() {
+        let arg = arg.to_str()?;
+        if arg == "--" {
+            break;
+        }
+        if arg == name {
+            return it.next().and_then(|v| v.to_str()).map(str::to_string);
+        }
+        if let Some(v) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+            return Some(v.to_string());
+        }
+    }
+    None
+}
+
+/// Split a code list and replace `@name` entries with the named set from the config, or with
+/// the codes listed in file `name` when no set has that name.
+fn expand_list(s: &str, sets: &HashMap<String, Vec<String>>, what: &str) -> Result<Vec<String>> {
+    let mut out = Vec::new();
+    for item in parse_list(s) {
+        let Some(name) = item.strip_prefix('@') else {
+            out.push(item);
+            continue;
+        };
+        // Config sets first, so they can redefine a built-in country set.
+        let builtin = || {
+            (what == "country")
+                .then(|| wbi_rs::codes::country_group(name))
+                .flatten()
+        };
+        match sets.get(name).map(Vec::as_slice).or_else(builtin) {
+            Some(codes) => out.extend(codes.iter().cloned()),
+            None if Path::new(name).is_file() => out.extend(read_code_file(Path::new(name))?),
+            None if what == "country" => bail!(
+                "unknown country set '@{name}'; built-in sets are {}, or define it in the config \
+                 file or give a file path",
+                wbi_rs::codes::country_groups()
+                    .keys()
+                    .map(|k| format!("@{k}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => bail!(
+                "unknown {what} set '@{name}'; define it in the config file or give a file path"
+            ),
+        }
+    }
+    Ok(out)
+}
+
+/// Codes from a list file: separated by newlines, commas or semicolons; `#` starts a comment.
+fn read_code_file(path: &Path) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("reading code list {}", path.display()))?;
+    Ok(text
+        .lines()
+        .flat_map(|line| parse_list(line.split('#').next().unwrap_or_default()))
+        .collect())
+}
+
+/// Route `tracing` events to stderr: status lines at the default level, `-v`/`-vv` for
+/// debug/trace, `--quiet` for errors only.
+fn init_logging(verbose: u8, quiet: bool) {
+    use tracing::Level;
+    let level = match (quiet, verbose) {
+        (true, _) => Level::ERROR,
+        (false, 0) => Level::INFO,
+        (false, 1) => Level::DEBUG,
+        (false, _) => Level::TRACE,
+    };
+    // Only the first call takes effect, so `main` can fall back to defaults for errors
+    // raised before the flags were parsed.
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .event_format(StderrFormat)
+        .try_init();
+}
+
+/// Status lines print bare; other levels get a lowercase `level: ` prefix.
+struct StderrFormat;
+
+impl<S, N> tracing_subscriber::fmt::FormatEvent<S, N> for StderrFormat
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    N: for<'a> tracing_subscriber::fmt::FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &tracing_subscriber::fmt::FmtContext<'_, S, N>,
+        mut writer: tracing_subscriber::fmt::format::Writer<'_>,
+        event: &tracing::Event<'_>,
+    ) -> std::fmt::Result {
+        match *event.metadata().level() {
+            tracing::Level::INFO => {}
+            tracing::Level::WARN => write!(writer, "warning: ")?,
+            level => write!(writer, "{}: ", level.as_str().to_ascii_lowercase())?,
+        }
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+/// All fetch progress bars of the process, so parallel batch jobs get one line each instead
+/// of overwriting each other.
+static PROGRESS: std::sync::LazyLock<MultiProgress> = std::sync::LazyLock::new(MultiProgress::new);
+
+thread_local! {
+    /// Name of the batch job running on this thread; prefixes its progress bar.
+    static JOB_LABEL: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Run `f` with progress bars labelled `label`.
+fn with_job_label<T>(label: String, f: impl FnOnce() -> T) -> T {
+    JOB_LABEL.with(|l| *l.borrow_mut() = Some(label));
+    let out = f();
+    JOB_LABEL.with(|l| *l.borrow_mut() = None);
+    out
+}
+
+/// Progress bar for a fetch, hidden under `--quiet` (and by indicatif when stderr is no TTY).
+fn fetch_progress_bar() -> ProgressBar {
+    let bar = if tracing::level_filters::LevelFilter::current() < tracing::Level::INFO {
+        ProgressBar::hidden()
+    } else {
+        PROGRESS.add(ProgressBar::new(0))
+    };
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{prefix}{spinner} request {msg} [{bar:30}] page {pos}/{len} · {elapsed}",
+        )
+        .expect("static progress template")
+        .progress_chars("=> "),
+    );
+    if let Some(label) = JOB_LABEL.with(|l| l.borrow().clone()) {
+        bar.set_prefix(format!("{label}: "));
+    }
+    bar
+}
+
+/// The clap command with the config file's defaults installed.
+fn build_command(config: &Config) -> clap::Command {
+    let mut command = Cli::command();
+    for (id, value) in config.arg_defaults() {
+        if command.get_arguments().any(|a| a.get_id() == id) {
+            // Global flag: set on the root, clap propagates it to every subcommand.
+            command = command.mut_arg(id, |a| a.default_value(value.clone()));
+            continue;
+        }
+        let names: Vec<String> = command
+            .get_subcommands()
+            .filter(|sc| sc.get_arguments().any(|a| a.get_id() == id))
+            .map(|sc| sc.get_name().to_string())
+            .collect();
+        for name in names {
+            command = command.mut_subcommand(name, |sc| {
+                sc.mut_arg(id, |a| a.default_value(value.clone()))
+            });
+        }
+    }
+    command
+}
+
+fn main() -> std::process::ExitCode {
+    let argv: Vec<std::ffi::OsString> = std::env::args_os().collect();
+    let format = match early_flag(&argv, "--error-format").as_deref() {
+        Some("json") => ErrorFormat::Json,
+        _ => ErrorFormat::Text,
+    };
+    let code = match run(argv) {
+        Ok(code) => code,
+        Err(err) => {
+            init_logging(0, false);
+            let failure = match err.downcast_ref::<clap::Error>() {
+                Some(e) if !e.use_stderr() => {
+                    // --help / --version
+                    let _ = e.print();
+                    return std::process::ExitCode::SUCCESS;
+                }
+                Some(e) if format == ErrorFormat::Text => {
+                    let _ = e.print();
+                    return std::process::ExitCode::from(Failure::Usage as u8);
+                }
+                Some(_) => Failure::Usage,
+                None => Failure::of(&err),
+            };
+            report_error(failure, &err, format)
+        }
+    };
+    std::process::ExitCode::from(code)
+}
+
+fn run(argv: Vec<std::ffi::OsString>) -> Result<u8> {
+    let config = Config::load(early_flag(&argv, "--config").map(PathBuf::from).as_deref())?;
+    let command = build_command(&config);
+    let matches = command.clone().try_get_matches_from(&argv)?;
+    let cli = Cli::from_arg_matches(&matches)?;
+    init_logging(cli.verbose, cli.quiet);
+
+    if let Command::Get(GetArgs {
+        emit: Some(form), ..
+    }) = &cli.cmd
+    {
+        print!("{}", emit_get(&command, &matches, *form)?);
+        return Ok(0);
+    }
+
+    let record = matches!(
+        cli.cmd,
+        Command::Get(_) | Command::Preset(PresetCmdArgs { name: Some(_), .. })
+    );
+    let result = match cli.cmd {
+        Command::Get(args) => cmd_get(args, &config, &cli.locale),
+        Command::Plot(args) => cmd_plot(args, &cli.locale),
+        Command::Stats(args) => cmd_stats(args, &cli.locale),
+        Command::Rank(args) => cmd_rank(args, &config, &cli.locale),
+        Command::Convert(args) => cmd_convert(args),
+        Command::Batch(args) => cmd_batch(args, &command, &config),
+        Command::Cache(args) => cmd_cache(args),
+        Command::Report(args) => cmd_report(args, &config, &cli.locale),
+        Command::Validate(args) => cmd_validate(args, &config),
+        Command::Preset(args) => cmd_preset(args, &command, &config),
+        Command::History(args) => cmd_history(args),
+        Command::Serve(args) => cmd_serve(args, &command, &config),
+        Command::Record(args) => cmd_record(args, &config),
+        Command::Diff(args) => {
+            // diff(1) convention, so scripts can tell "changed" from "failed".
+            return Ok(match cmd_diff(args, &cli.locale) {
+                Ok(false) => 0,
+                Ok(true) => 1,
+                Err(e) => report_error(Failure::of_comparison(&e), &e, cli.error_format),
+            });
+        }
+        Command::ChartDiff(args) => {
+            return Ok(match cmd_chart_diff(args) {
+                Ok(true) => 0,
+                Ok(false) => 1,
+                Err(e) => report_error(Failure::of_comparison(&e), &e, cli.error_format),
+            });
+        }
+    };
+    if record && result.is_ok() {
+        record_history(&argv);
+    }
+    result.map(|()| 0)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    jobs: Vec<toml::Table>,
+}
+
+/// The `get` flags given on the command line of `matches` as a command or a manifest job,
+/// the inverse of [`job_args`]. Only `--locale` of the global flags carries over; values
+/// coming from the config file are not included.
+fn emit_get(command: &clap::Command, matches: &clap::ArgMatches, form: EmitArg) -> Result<String> {
+    use clap::parser::ValueSource;
+
+    let get = command.find_subcommand("get").expect("get is a subcommand");
+    let sub = matches
+        .subcommand_matches("get")
+        .expect("emit_get is called for get");
+    let args = get
+        .get_arguments()
+        .chain(command.get_arguments().filter(|a| a.get_id() == "locale"));
+
+    let mut flags: Vec<(String, Option<Vec<String>>)> = Vec::new();
+    for arg in args {
+        let id = arg.get_id().as_str();
+        let explicit = sub.try_contains_id(id).unwrap_or(false)
+            && sub.value_source(id) == Some(ValueSource::CommandLine);
+        let Some(long) = arg.get_long().filter(|_| explicit && id != "emit") else {
+            continue;
+        };
+        let values = match arg.get_action() {
+            ArgAction::SetTrue => None,
+            _ => Some(
+                sub.get_raw(id)
+                    .into_iter()
+                    .flatten()
+                    .map(|v| v.to_string_lossy().into_owned())
+                    .collect(),
+            ),
+        };
+        flags.push((long.to_string(), values));
+    }
+
+    Ok(match form {
+        EmitArg::Command => {
+            let mut argv = vec!["wbi".to_string(), "get".to_string()];
+            for (long, values) in flags {
+                argv.push(format!("--{long}"));
+                argv.extend(values.into_iter().flatten());
+            }
+            shell_line(&argv) + "\n"
+        }
+        EmitArg::Manifest => {
+            let mut job = toml::Table::new();
+            for (long, values) in flags {
+                let value = match values.as_deref() {
+                    None => toml::Value::Boolean(true),
+                    Some([one]) => one
+                        .parse::<i64>()
+                        .map_or_else(|_| toml::Value::String(one.clone()), toml::Value::Integer),
+                    Some(many) => {
+                        toml::Value::Array(many.iter().cloned().map(toml::Value::String).collect())
+                    }
+                };
+                job.insert(long, value);
+            }
+            format!("[[jobs]]\n{}", toml::to_string(&job)?)
+        }
+    })
+}
+
+/// Turn one manifest job into `get` arguments: `key = value` becomes `--key value`
+/// (underscores read as dashes), `true` becomes a bare flag, `false` is dropped and arrays
+/// are joined with commas. The optional `name` key only labels the job.
+fn job_args(job: &toml::Table) -> Result<Vec<String>> {
+    let mut args = vec!["wbi".to_string(), "get".to_string()];
+    for (key, value) in job {
+        if key == "name" {
+            continue;
+        }
+        let flag = format!("--{}", key.replace('_', "-"));
+        let scalar = |v: &toml::Value| -> Result<String> {
+            Ok(match v {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Integer(i) => i.to_string(),
+                toml::Value::Float(f) => f.to_string(),
+                other => bail!("unsupported value for '{key}': {other}"),
+            })
+        };
+        match value {
+            toml::Value::Boolean(true) => args.push(flag),
+            toml::Value::Boolean(false) => {}
+            toml::Value::Array(items) => {
+                let items = items.iter().map(scalar).collect::<Result<Vec<_>>>()?;
+                args.extend([flag, items.join(",")]);
+            }
+            other => args.extend([flag, scalar(other)?]),
+        }
+    }
+    Ok(args)
+}
+
+fn cmd_batch(args: BatchCmdArgs, command: &clap::Command, config: &Config) -> Result<()> {
+    let text = std::fs::read_to_string(&args.manifest)
+        .with_context(|| format!("reading manifest {}", args.manifest.display()))?;
+    let manifest: Manifest = toml::from_str(&text)
+        .with_context(|| format!("parsing manifest {}", args.manifest.display()))?;
+
+    let run = |i: usize, job: &toml::Table| -> Result<()> {
+        let argv = job_args(job)?;
+        let matches = command
+            .clone()
+            .try_get_matches_from(argv)
+            .map_err(|e| anyhow::anyhow!("{}", e.render().to_string().trim_end()))?;
+        let cli = Cli::from_arg_matches(&matches)?;
+        match cli.cmd {
+            Command::Get(get) => {
+                let name = job_name(i, job);
+                tracing::info!("[{}] {name}", i + 1);
+                with_job_label(name, || cmd_get(get, config, &cli.locale))
+            }
+            _ => unreachable!("job_args always builds a get command"),
+        }
+    };
+
+    // Jobs are dealt round-robin to the workers; results keep manifest order.
+    let workers = (args.jobs as usize).min(manifest.jobs.len()).max(1);
+    let mut results: Vec<Option<Result<()>>> = (0..manifest.jobs.len()).map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|w| {
+                let jobs = &manifest.jobs;
+                let run = &run;
+                scope.spawn(move || {
+                    (w..jobs.len())
+                        .step_by(workers)
+                        .map(|i| (i, run(i, &jobs[i])))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for handle in handles {
+            for (i, result) in handle.join().expect("batch worker panicked") {
+                results[i] = Some(result);
+            }
+        }
+    });
+
+    let mut failed = 0;
+    for (i, result) in results.into_iter().enumerate() {
+        let name = job_name(i, &manifest.jobs[i]);
+        match result.expect("every job ran") {
+            Ok(()) => tracing::info!("ok      {name}"),
+            Err(e) => {
+                failed += 1;
+                eprintln!("FAILED  {name}: {e:#}");
+            }
+        }
+    }
+    let total = manifest.jobs.len();
+    eprintln!("{} succeeded, {} failed", total - failed, failed);
+    if failed > 0 {
+        bail!("{failed} of {total} batch jobs failed");
+    }
+    Ok(())
+}
+
+/// Run preset `args.name` as `get`, with `args.args` appended; without a name, list the presets.
+fn cmd_preset(args: PresetCmdArgs, command: &clap::Command, config: &Config) -> Result<()> {
+    let Some(name) = args.name else {
+        if config.presets.is_empty() {
+            eprintln!("No presets; add [presets.NAME] tables to the config file.");
+        }
+        for (name, preset) in &config.presets {
+            println!("{name:<16} {}", shell_line(&job_args(preset)?));
+        }
+        return Ok(());
+    };
+    let Some(preset) = config.presets.get(&name) else {
+        bail!(
+            "no preset '{name}' in the config file; available: {}",
+            config
+                .presets
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    };
+    let mut argv = job_args(preset)?;
+    argv.extend(args.args);
+    tracing::debug!("preset {name}: {}", shell_line(&argv));
+    // Later flags replace the preset's, instead of clap's "used multiple times" error.
+    let matches = command
+        .clone()
+        .mut_subcommand("get", |sc| sc.args_override_self(true))
+        .try_get_matches_from(argv)?;
+    let cli = Cli::from_arg_matches(&matches)?;
+    match cli.cmd {
+        Command::Get(GetArgs {
+            emit: Some(form), ..
+        }) => {
+            print!("{}", emit_get(command, &matches, form)?);
+            Ok(())
+        }
+        Command::Get(get) => cmd_get(get, config, &cli.locale),
+        _ => unreachable!("job_args always builds a get command"),
+    }
+}
+
+/// Number of commands kept by [`record_history`].
+const HISTORY_LEN: usize = 100;
+
+/// `$XDG_STATE_HOME/wbi/history`, falling back to `~/.local/state/wbi/history`.
+fn history_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?)
+            .join(".local")
+            .join("state"),
+    };
+    Some(base.join("wbi").join("history"))
+}
+
+/// Append the command line to the history (moving a repeated command to the end). Best
+/// effort: a read-only home must not fail the command that just succeeded.
+fn record_history(argv: &[std::ffi::OsString]) {
+    let Some(path) = history_path() else {
+        return;
+    };
+    let args: Vec<String> = std::iter::once("wbi".to_string())
+        .chain(
+            argv.iter()
+                .skip(1)
+                .map(|a| a.to_string_lossy().into_owned()),
+        )
+        .collect();
+    let line = shell_line(&args);
+    let mut lines = read_history(&path);
+    lines.retain(|l| *l != line);
+    lines.push(line);
+    let start = lines.len().saturating_sub(HISTORY_LEN);
+    let write = || -> Result<()> {
+        std::fs::create_dir_all(path.parent().expect("history path has a parent"))?;
+        std::fs::write(&path, lines[start..].join("\n") + "\n")?;
+        Ok(())
+    };
+    if let Err(e) = write() {
+        tracing::debug!("not recording history in {}: {e:#}", path.display());
+    }
+}
+
+/// History lines, oldest first; a missing or unreadable file is an empty history.
+fn read_history(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .map(|text| text.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn cmd_history(args: HistoryCmdArgs) -> Result<()> {
+    if args.fetches {
+        return print_fetch_history(&args);
+    }
+    let path = history_path().ok_or_else(|| anyhow::anyhow!("cannot locate the home directory"))?;
+    let lines = read_history(&path);
+    let start = lines.len().saturating_sub(args.limit);
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        println!("{:>4}  {line}", i + 1);
+    }
+    Ok(())
+}
+
+/// The last `args.limit` logged API calls, then totals over the whole log.
+fn print_fetch_history(args: &HistoryCmdArgs) -> Result<()> {
+    let path = match &args.log {
+        Some(path) => path.clone(),
+        None => fetch_log_path(None)?,
+    };
+    let records = FetchLog::new(&path).read()?;
+    let (Some(first), Some(last)) = (records.first(), records.last()) else {
+        println!("no fetches logged in {}", path.display());
+        return Ok(());
+    };
+    let time = |t: &chrono::DateTime<chrono::Utc>| t.format("%Y-%m-%d %H:%M:%S").to_string();
+    for record in &records[records.len().saturating_sub(args.limit)..] {
+        let status = match (record.cached, record.status) {
+            (true, _) => "cache".to_string(),
+            (false, Some(status)) => status.to_string(),
+            (false, None) => "error".to_string(),
+        };
+        let rows = record
+            .rows
+            .map_or_else(|| "-".to_string(), |n| n.to_string());
+        println!(
+            "{}  {status:>5}  {rows:>6}  {}",
+            time(&record.timestamp),
+            record.url
+        );
+    }
+    let cached = records.iter().filter(|r| r.cached).count();
+    let failed = records.iter().filter(|r| !r.ok()).count();
+    let rows: usize = records.iter().filter_map(|r| r.rows).sum();
+    println!(
+        "{} calls from {} to {} UTC: {cached} from cache, {failed} failed, {rows} rows",
+        records.len(),
+        time(&first.timestamp),
+        time(&last.timestamp),
+    );
+    Ok(())
+}
+
+/// Arguments joined into a line that a POSIX shell splits back into the same arguments.
+fn shell_line(args: &[String]) -> String {
+    args.iter()
+        .map(|a| {
+            let plain = !a.is_empty()
+                && a.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./:,=@+%".contains(c));
+            if plain {
+                a.clone()
+            } else {
+                format!("'{}'", a.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The job's `name`, or its 1-based position in the manifest.
+fn job_name(i: usize, job: &toml::Table) -> String {
+    match job.get("name").and_then(|v| v.as_str()) {
+        Some(name) => name.to_string(),
+        None => format!("job {}", i + 1),
+    }
+}
+
+fn cmd_get(args: GetArgs, config: &Config, locale: &str) -> Result<()> {
+    let (Some(every), Some(out)) = (args.refresh, args.out.as_deref()) else {
+        return get_once(&args, config, locale);
+    };
+    if out.to_string_lossy().contains('{') {
+        return Err(
+            UsageError("--refresh needs a single --out file, not a path template".into()).into(),
+        );
+    }
+    if args.once_if_stale {
+        let age = std::fs::metadata(out)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok());
+        if let Some(age) = age
+            && age < every
+        {
+            tracing::info!(
+                "{} is up to date (written {} ago); nothing to do",
+                out.display(),
+                human_duration(std::time::Duration::from_secs(age.as_secs() / 60 * 60))
+            );
+            return Ok(());
+        }
+        return get_once(&args, config, locale);
+    }
+    loop {
+        // A daemon outlives transient failures; the next round tries again.
+        if let Err(e) = get_once(&args, config, locale) {
+            tracing::error!("refresh failed: {e:#}");
+        }
+        tracing::info!("Next refresh in {}", human_duration(every));
+        std::thread::sleep(every);
+    }
+}
+
+/// One `get` run. With --refresh and an existing --out, only recent years are fetched and
+/// merged into the saved rows.
+fn get_once(args: &GetArgs, config: &Config, locale: &str) -> Result<()> {
+    let query = args.fetch.resolve(config)?;
+
+    // Build chart options before fetching so a broken style config or flag fails fast.
+    let plot_options = match args.plot {
+        Some(_) => Some(plot_options(&args.chart, locale)?),
+        None => None,
+    };
+
+    let saved = match args.out.as_deref() {
+        Some(out) if args.refresh.is_some() && out.exists() => Some(load_saved(out, args.format)?),
+        _ => None,
+    };
+    let points = match &saved {
+        Some(saved) => {
+            let merged = query.fetch_incremental(saved)?;
+            let d = wbi_rs::diff::diff(saved, &merged);
+            tracing::info!(
+                "Refreshed: {} new, {} revised values",
+                d.added.len(),
+                d.changed.len()
+            );
+            merged
+        }
+        None => query.fetch()?,
+    };
+    if points.is_empty() {
+        return Err(EmptyResult.into());
+    }
+    let points = args
+        .select
+        .apply(points, query.indicators.first().map(String::as_str))?;
+
+    if let Some(template) = args.out.as_ref() {
+        let fmt = decide_output_format(template, args.format)?;
+        let provenance = storage::Provenance {
+            date: Some(query.date),
+            source: query.source,
+            ..storage::Provenance::now(query.countries.clone(), query.indicators.clone())
+        };
+        for (path, rows) in outputs_for(template, &points) {
+            match fmt {
+                "csv" => storage::save_csv_with_options(&rows, &path, &args.csv.options())?,
+                "json" => storage::save_json(&rows, &path)?,
+                other => anyhow::bail!("unsupported format: {}", other),
+            }
+            storage::save_provenance(&path, &provenance)?;
+            tracing::info!("Saved {} rows to {}", rows.len(), path.display());
+        }
+    }
+
+    if let (Some(template), Some(options)) = (args.plot.as_ref(), plot_options) {
+        let extras = query.plot_extras(&options)?;
+        let template = per_chart_template(template, args.plot_per);
+        let mut last = None;
+        for (path, mut rows) in outputs_for(&template, &points) {
+            rows.extend(extras.iter().cloned());
+            viz::plot_with_options(&rows, &path, &options)?;
+            tracing::info!("Wrote plot to {}", path.display());
+            if args.open {
+                open_in_viewer(&path);
+            }
+            last = Some(rows);
+        }
+        if let Some(legend) = &args.chart.legend_out {
+            write_legends(legend, &points, &extras, &options)?;
+        }
+        if let (Some(CopyArg::Chart), Some(rows)) = (args.copy, last) {
+            copy_chart(&rows, &options)?;
+        }
+    } else if args.copy == Some(CopyArg::Chart) {
+        return Err(UsageError("--copy chart needs --plot".into()).into());
+    }
+    if args.copy == Some(CopyArg::Data) {
+        copy_data(&points)?;
+    }
+
+    if args.stats {
+        print_summaries(
+            &stats::grouped_summary(&points),
+            stats::GroupBy::Country,
+            args.stats_format,
+            locale,
+        )?;
+        if let Some(path) = &args.chart.projection {
+            let projected = load_saved(path, None)?;
+            print_divergence(
+                &stats::divergence(&points, &projected),
+                args.stats_format,
+                locale,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_report(args: ReportCmdArgs, config: &Config, locale: &str) -> Result<()> {
+    use wbi_rs::report::{ChartEmbed, ReportFormat, render_report};
+
+    let format = ReportFormat::from_path(&args.out);
+    let template = match &args.template {
+        Some(path) => Some(
+            std::fs::read_to_string(path)
+                .with_context(|| format!("reading template {}", path.display()))?,
+        ),
+        None => None,
+    };
+    let options = plot_options(&args.chart, locale)?;
+    let mut query = args.fetch.resolve(config)?;
+    if args.fetch.fetch_log {
+        let dir = args.out.parent().unwrap_or(Path::new(""));
+        query.client = query
+            .client
+            .with_fetch_log(FetchLog::new(dir.join(FetchLog::FILE_NAME)));
+    }
+    let points = query.fetch()?;
+    if points.is_empty() {
+        return Err(EmptyResult.into());
+    }
+    let points = args
+        .select
+        .apply(points, query.indicators.first().map(String::as_str))?;
+    let title = args
+        .chart
+        .title
+        .clone()
+        .unwrap_or_else(|| "World Bank Indicator(s)".to_string());
+    let definitions = if args.definitions {
+        let ids: Vec<String> = points
+            .iter()
+            .map(|p| p.indicator_id.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        query
+            .client
+            .fetch_indicator_metadata(&ids)
+            .context("fetching indicator definitions")?
+    } else {
+        Vec::new()
+    };
+    let appendix = if args.appendix {
+        let series: BTreeSet<(&str, &str)> = points
+            .iter()
+            .map(|p| (p.indicator_id.as_str(), p.country_iso3.as_str()))
+            .collect();
+        series
+            .into_iter()
+            .map(|(indicator, country)| {
+                query
+                    .client
+                    .fetch_series_country_metadata(indicator, country)
+                    .with_context(|| format!("fetching metadata of {indicator} in {country}"))
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        Vec::new()
+    };
+
+    // Markdown links the chart as a sibling file; HTML inlines it so the report is one file.
+    let extras = query.plot_extras(&options)?;
+    if let Some(legend) = &args.chart.legend_out {
+        write_legends(legend, &points, &extras, &options)?;
+    }
+    let mut chart_points = points.clone();
+    chart_points.extend(extras);
+    let report = match format {
+        ReportFormat::Markdown => {
+            let chart_path = args.out.with_extension("svg");
+            viz::plot_with_options(&chart_points, &chart_path, &options)?;
+            tracing::info!("Wrote plot to {}", chart_path.display());
+            let link = chart_path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            render_report(
+                &points,
+                &title,
+                Some(ChartEmbed::Link(&link)),
+                format,
+                template.as_deref(),
+                &definitions,
+                &appendix,
+            )?
+        }
+        ReportFormat::Html => {
+            let svg = viz::render_svg_string(&chart_points, &options)?;
+            render_report(
+                &points,
+                &title,
+                Some(ChartEmbed::InlineSvg(&svg)),
+                format,
+                template.as_deref(),
+                &definitions,
+                &appendix,
+            )?
+        }
+    };
+    std::fs::write(&args.out, report)
+        .with_context(|| format!("writing report {}", args.out.display()))?;
+    tracing::info!("Wrote report to {}", args.out.display());
+    if args.open {
+        open_in_viewer(&args.out);
+    }
+    Ok(())
+}
+
+/// Translate the shared chart flags into [`viz::PlotOptions`], reading `--style-config` and
+/// layering them over `--plot-spec`, and check them before anything is fetched. Flags the
+/// chosen `--plot-kind` ignores get a warning.
+fn plot_options(args: &PlotArgs, locale: &str) -> Result<viz::PlotOptions> {
+    let mut options = chart_options(args, locale)?;
+    if let Some(path) = &args.plot_spec {
+        options = viz::PlotOptions::load(path)?.overlay(&options)?;
+    }
+    options.validate()?;
+    if let Some(path) = &args.save_plot_spec {
+        options.save(path)?;
+    }
+    for setting in options.ignored_settings() {
+        let flags = match setting {
+            "loess_span" => "--loess-span",
+            "xy" => "--x-indicator/--y-indicator/--size-indicator/--xy-year",
+            "pareto" => "--pareto-year/--pareto-reference",
+            "waterfall" => "--waterfall-from/--waterfall-to",
+            "bar_groups" => "--bar-groups",
+            "bars" => "--bar-order/--bar-gap/--bar-borders/--max-bar-width",
+            "stack_negatives" => "--stack-negatives",
+            "pattern_fills" => "--pattern-fills",
+            "markers" => "--marker-every/--marker-spacing",
+            "error_bars" => "--error-bars",
+            "projections" => "--projection",
+            "percent_bounds" => "--percent-axis",
+            "percent_ticks" => "--percent-ticks",
+            "y_break" => "--y-break",
+            other => other,
+        };
+        tracing::warn!(
+            "{flags} has no effect on --plot-kind {}",
+            args.plot_kind
+                .to_possible_value()
+                .expect("no skipped variants")
+                .get_name()
+        );
+    }
+    Ok(options)
+}
+
+fn chart_options(args: &PlotArgs, locale: &str) -> Result<viz::PlotOptions> {
+    let transform = match args.transform {
+        None => viz::PlotTransform::None,
+        Some(TransformArg::PerCapita) => viz::PlotTransform::PerCapita,
+        Some(TransformArg::Deflate) => viz::PlotTransform::Deflate,
+        Some(TransformArg::Index) => viz::PlotTransform::IndexToYear(
+            args.index_year
+                .ok_or_else(|| anyhow::anyhow!("--transform index requires --index-year"))?,
+        ),
+        Some(TransformArg::YoyGrowth) => viz::PlotTransform::YoYGrowth,
+        Some(kind @ (TransformArg::Difference | TransformArg::Ratio)) => {
+            let (a, b) = args.pair.clone().ok_or_else(|| {
+                anyhow::anyhow!("--transform difference/ratio requires --pair A,B")
+            })?;
+            match kind {
+                TransformArg::Difference => viz::PlotTransform::CountryDifference(a, b),
+                _ => viz::PlotTransform::CountryRatio(a, b),
+            }
+        }
+    };
+    let legend_mode = match args.legend {
+        _ if args.legend_out.is_some() => viz::LegendMode::Hidden,
+        LegendPos::Inside => viz::LegendMode::Inside,
+        LegendPos::Right => viz::LegendMode::Right,
+        LegendPos::Top => viz::LegendMode::Top,
+        LegendPos::Bottom => viz::LegendMode::Bottom,
+        LegendPos::Hidden => viz::LegendMode::Hidden,
+        LegendPos::Direct => viz::LegendMode::Direct,
+    };
+    let title = args.title.as_deref().unwrap_or("World Bank Indicator(s)"); //title will be overriden later
+    let plot_kind = match args.plot_kind {
+        PlotKindArg::Line => viz::PlotKind::Line,
+        PlotKindArg::Scatter => viz::PlotKind::Scatter,
+        PlotKindArg::LinePoints => viz::PlotKind::LinePoints,
+        PlotKindArg::Area => viz::PlotKind::Area,
+        PlotKindArg::StackedArea => viz::PlotKind::StackedArea,
+        PlotKindArg::GroupedBar => viz::PlotKind::GroupedBar,
+        PlotKindArg::Loess => viz::PlotKind::Loess,
+        PlotKindArg::XyScatter => viz::PlotKind::XYScatter,
+        PlotKindArg::Envelope => viz::PlotKind::Envelope,
+        PlotKindArg::FanChart => viz::PlotKind::FanChart,
+        PlotKindArg::Pareto => viz::PlotKind::Pareto,
+        PlotKindArg::Waterfall => viz::PlotKind::Waterfall,
+    };
+    Ok(viz::PlotOptions {
+        width: args.width,
+        height: args.height,
+        locale: locale.to_string(),
+        legend: legend_mode,
+        title: title.to_string(),
+        kind: plot_kind,
+        loess_span: args.loess_span,
+        country_styles: args.country_styles,
+        style_mode: match args.style_mode {
+            None | Some(StyleModeArg::Palette) => viz::StyleMode::Palette,
+            Some(StyleModeArg::Country) => viz::StyleMode::Country,
+            Some(StyleModeArg::Indicator) => viz::StyleMode::Indicator,
+        },
+        style_config: match &args.style_config {
+            Some(path) => viz::style::StyleConfig::load(path)?,
+            None => viz::style::StyleConfig::default(),
+        },
+        pattern_fills: args.pattern_fills,
+        xy: viz::XYScatterOptions {
+            x_indicator: args.x_indicator.clone(),
+            y_indicator: args.y_indicator.clone(),
+            size_indicator: args.size_indicator.clone(),
+            year: args.xy_year,
+        },
+        pareto: viz::ParetoOptions {
+            year: args.pareto_year,
+            reference_share: args.pareto_reference,
+        },
+        waterfall: viz::WaterfallOptions {
+            from: args.waterfall_from,
+            to: args.waterfall_to,
+        },
+        bar_groups: match args.bar_groups {
+            BarGroupsArg::Years => viz::BarGrouping::Years,
+            BarGroupsArg::Countries => viz::BarGrouping::Countries,
+            BarGroupsArg::Indicators => viz::BarGrouping::Indicators,
+        },
+        bars: viz::BarOptions {
+            order: match args.bar_order {
+                BarOrderArg::Series => viz::BarOrder::Series,
+                BarOrderArg::Ascending => viz::BarOrder::Ascending,
+                BarOrderArg::Descending => viz::BarOrder::Descending,
+            },
+            group_gap: args.bar_gap,
+            borders: args.bar_borders,
+            max_bar_px: args.max_bar_width,
+        },
+        stack_negatives: match args.stack_negatives {
+            StackNegativesArg::Clamp => viz::StackNegatives::Clamp,
+            StackNegativesArg::Error => viz::StackNegatives::Error,
+            StackNegativesArg::Mirror => viz::StackNegatives::Mirror,
+        },
+        error_bars: args.error_bars.map(|src| viz::ErrorBarOptions {
+            source: match src {
+                ErrorBarsArg::Decimal => viz::UncertaintySource::FromDecimal,
+                ErrorBarsArg::Estimates => viz::UncertaintySource::FromObsStatus { relative: 0.05 },
+            },
+            cap_width_px: args.error_bar_cap,
+            opacity: args.error_bar_opacity,
+        }),
+        transform,
+        projections: match &args.projection {
+            Some(path) => load_saved(path, None)?,
+            None => Vec::new(),
+        },
+        transparent: args.transparent,
+        scale: args.scale,
+        fonts: viz::FontOptions {
+            custom: args.font.clone().map(|path| viz::CustomFont {
+                family: args.font_family.clone().unwrap_or_else(|| {
+                    path.file_stem()
+                        .map(|s| s.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| "custom".into())
+                }),
+                path,
+            }),
+            title_px: args.title_size,
+            axis_title_px: args.axis_title_size,
+            tick_px: args.tick_size,
+            legend_px: args.legend_size,
+        },
+        markers: match (args.marker_every, args.marker_spacing) {
+            (Some(n), _) => viz::MarkerDecimation::EveryNth(n),
+            (None, Some(0)) => viz::MarkerDecimation::Off,
+            (None, Some(px)) => viz::MarkerDecimation::MinSpacingPx(px),
+            (None, None) => viz::MarkerDecimation::Auto,
+        },
+        y_unit: args.y_unit.clone(),
+        y_scale: match args.y_scale {
+            YScaleArg::Auto => viz::AxisScale::Auto,
+            YScaleArg::None => viz::AxisScale::None,
+            YScaleArg::Thousands => viz::AxisScale::Thousands,
+            YScaleArg::Millions => viz::AxisScale::Millions,
+            YScaleArg::Billions => viz::AxisScale::Billions,
+            YScaleArg::Trillions => viz::AxisScale::Trillions,
+        },
+        tick_abbreviation: args.abbreviate_ticks,
+        percent_bounds: args.percent_axis,
+        percent_ticks: args.percent_ticks,
+        y_break: args.y_break,
+        deterministic: false,
+        embed_data: args.plot_data_embed,
+        branding: viz::Branding {
+            logo: args.logo.clone(),
+            logo_height: args.logo_height,
+            watermark: args.watermark.clone(),
+            corner: match args.brand_corner {
+                CornerArg::TopLeft => viz::Corner::TopLeft,
+                CornerArg::TopRight => viz::Corner::TopRight,
+                CornerArg::BottomLeft => viz::Corner::BottomLeft,
+                CornerArg::BottomRight => viz::Corner::BottomRight,
+            },
+            opacity: args.brand_opacity,
+        },
+    })
+}
+
+/// Load a saved export, logging its provenance when a sidecar exists.
+fn load_saved(path: &Path, format: Option<OutFormat>) -> Result<Vec<wbi_rs::models::DataPoint>> {
+    log_provenance(path);
+    match format {
+        Some(OutFormat::Csv) => storage::load_csv(path),
+        Some(OutFormat::Json) => storage::load_json(path),
+        None => storage::load(path),
+    }
+}
+
+/// Write the legend of the charts of `points` to `--legend-out`, one file per placeholder value
+/// like the chart path; `extras` are the rows every chart gets (see [`Query::plot_extras`]).
+fn write_legends(
+    template: &Path,
+    points: &[wbi_rs::models::DataPoint],
+    extras: &[wbi_rs::models::DataPoint],
+    options: &viz::PlotOptions,
+) -> Result<()> {
+    for (path, mut rows) in outputs_for(template, points) {
+        rows.extend(extras.iter().cloned());
+        viz::render_legend(&rows, &path, options)?;
+        tracing::info!("Wrote legend to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Log where the saved file at `path` came from, if it records that.
+fn log_provenance(path: &Path) {
+    match storage::load_provenance(path) {
+        Ok(Some(p)) => tracing::info!(
+            "{}: fetched {} by {} ({} · {}{})",
+            path.display(),
+            p.fetched_at.format("%Y-%m-%d %H:%M UTC"),
+            p.generator,
+            p.countries.join(","),
+            p.indicators.join(","),
+            p.date
+                .map(|d| format!(" · {}={}", d.query_key(), d.to_query_param()))
+                .unwrap_or_default()
+        ),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("ignoring provenance: {e:#}"),
+    }
+}
+
+fn cmd_plot(args: PlotCmdArgs, locale: &str) -> Result<()> {
+    let options = plot_options(&args.chart, locale)?;
+    let points = args
+        .select
+        .apply(load_saved(&args.input, args.format)?, None)?;
+    let template = per_chart_template(&args.out, args.plot_per);
+    let mut last = None;
+    for (path, rows) in outputs_for(&template, &points) {
+        viz::plot_with_options(&rows, &path, &options)?;
+        tracing::info!("Wrote plot to {}", path.display());
+        if args.open {
+            open_in_viewer(&path);
+        }
+        last = Some(rows);
+    }
+    if let Some(legend) = &args.chart.legend_out {
+        write_legends(legend, &points, &[], &options)?;
+    }
+    match (args.copy, last) {
+        (Some(CopyArg::Chart), Some(rows)) => copy_chart(&rows, &options),
+        (Some(CopyArg::Data), _) => copy_data(&points),
+        _ => Ok(()),
+    }
+}
+
+/// Put the chart of `rows` on the clipboard as an image.
+fn copy_chart(rows: &[wbi_rs::models::DataPoint], options: &viz::PlotOptions) -> Result<()> {
+    let png = viz::render_png_bytes(rows, options)?;
+    set_clipboard(Clip::Png(png))?;
+    tracing::info!("Copied the chart to the clipboard");
+    Ok(())
+}
+
+/// Put `points` on the clipboard as CSV.
+fn copy_data(points: &[wbi_rs::models::DataPoint]) -> Result<()> {
+    let mut csv = Vec::new();
+    storage::write_csv(points, &mut csv)?;
+    set_clipboard(Clip::Text(String::from_utf8(csv)?))?;
+    tracing::info!("Copied {} rows to the clipboard", points.len());
+    Ok(())
+}
+
+/// Clipboard content; only read when built with the `clipboard` feature.
+#[cfg_attr(not(feature = "clipboard"), allow(dead_code))]
+enum Clip {
+    Png(Vec<u8>),
+    Text(String),
+}
+
+/// On Linux the content outlives `wbi` only when a clipboard manager takes it over.
+#[cfg(feature = "clipboard")]
+fn set_clipboard(clip: Clip) -> Result<()> {
+    let mut clipboard = arboard::Clipboard::new().context("opening the clipboard")?;
+    match clip {
+        Clip::Text(text) => clipboard.set_text(text)?,
+        Clip::Png(png) => {
+            let mut reader = png::Decoder::new(png.as_slice()).read_info()?;
+            let mut buf = vec![0; reader.output_buffer_size()];
+            let info = reader.next_frame(&mut buf)?;
+            buf.truncate(info.buffer_size());
+            let rgba = match info.color_type {
+                png::ColorType::Rgba => buf,
+                _ => buf
+                    .chunks(3)
+                    .flat_map(|px| [px[0], px[1], px[2], 255])
+                    .collect(),
+            };
+            clipboard.set_image(arboard::ImageData {
+                width: info.width as usize,
+                height: info.height as usize,
+                bytes: rgba.into(),
+            })?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn set_clipboard(_clip: Clip) -> Result<()> {
+    bail!("this wbi was built without clipboard support; reinstall with `--features clipboard`")
+}
+
+/// Command that opens `path` with the platform's default application.
+fn opener(path: &Path) -> std::process::Command {
+    let mut cmd;
+    if cfg!(target_os = "windows") {
+        // `start` is a cmd builtin; its first quoted argument is the window title.
+        cmd = std::process::Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+    } else if cfg!(target_os = "macos") {
+        cmd = std::process::Command::new("open");
+    } else {
+        cmd = std::process::Command::new("xdg-open");
+    }
+    cmd.arg(path);
+    cmd
+}
+
+/// Launch the default viewer for a written file. The file is already saved, so failing to
+/// open it is only a warning.
+fn open_in_viewer(path: &Path) {
+    let mut cmd = opener(path);
+    cmd.stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    if let Err(e) = cmd.spawn() {
+        tracing::warn!(
+            "could not open {} ({:?}): {e}",
+            path.display(),
+            cmd.get_program()
+        );
+    }
+}
+
+fn cmd_convert(args: ConvertCmdArgs) -> Result<()> {
+    let points = load_saved(&args.input, args.from)?;
+    let to = match args.to {
+        Some(to) => to,
+        None => match decide_output_format(&args.out, None)? {
+            "json" => ConvertFormatArg::Json,
+            _ => ConvertFormatArg::Csv,
+        },
+    };
+    match to {
+        ConvertFormatArg::Csv => {
+            storage::save_csv_with_options(&points, &args.out, &args.csv.options())?
+        }
+        ConvertFormatArg::Json => storage::save_json(&points, &args.out)?,
+        ConvertFormatArg::CsvWide => {
+            storage::save_csv_wide_with_options(&points, &args.out, &args.csv.options())?
+        }
+    }
+    tracing::info!("Wrote {} rows to {}", points.len(), args.out.display());
+    Ok(())
+}
 
-    if args.stats {
-        let (loc, dec_sep) = map_locale(&args.locale);
+/// The response cache at `dir`, or at the default location.
+/// A client that keeps indicator metadata in the default metadata cache, so units and names
+/// are not re-requested on every run.
+fn new_client() -> Client {
+    match wbi_rs::cache::MetadataCache::default_path() {
+        Some(path) => {
+            Client::default().with_metadata_cache(wbi_rs::cache::MetadataCache::new(path))
+        }
+        None => Client::default(),
+    }
+}
+
+/// `fetch.log.jsonl` in `cache_dir`, or in the default cache directory.
+fn fetch_log_path(cache_dir: Option<&Path>) -> Result<PathBuf> {
+    match cache_dir {
+        Some(dir) => Ok(dir.join(FetchLog::FILE_NAME)),
+        None => FetchLog::default_path()
+            .ok_or_else(|| anyhow::anyhow!("no home directory; pass --cache-dir")),
+    }
+}
+
+fn open_cache(dir: Option<&Path>) -> Result<wbi_rs::cache::HttpCache> {
+    let dir = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => wbi_rs::cache::HttpCache::default_dir()
+            .ok_or_else(|| anyhow::anyhow!("no home directory; pass --cache-dir"))?,
+    };
+    Ok(wbi_rs::cache::HttpCache::new(dir))
+}
+
+fn cmd_cache(args: CacheCmdArgs) -> Result<()> {
+    let cache = open_cache(args.cache_dir.as_deref())?;
+    let metadata =
+        wbi_rs::cache::MetadataCache::default_path().map(wbi_rs::cache::MetadataCache::new);
+    match args.action {
+        CacheAction::Info => {
+            let info = cache.info()?;
+            let when = |t: Option<std::time::SystemTime>| {
+                t.map_or_else(
+                    || "-".to_string(),
+                    |t| {
+                        chrono::DateTime::<chrono::Utc>::from(t)
+                            .format("%Y-%m-%d %H:%M:%S UTC")
+                            .to_string()
+                    },
+                )
+            };
+            println!("directory  {}", cache.dir().display());
+            println!("entries    {}", info.entries);
+            println!("size       {}", human_bytes(info.bytes));
+            println!("oldest     {}", when(info.oldest));
+            println!("newest     {}", when(info.newest));
+            if let Some(meta) = metadata {
+                println!("indicators {} ({})", meta.len(), meta.path().display());
+            }
+        }
+        CacheAction::Clear => {
+            let n = cache.clear()?;
+            tracing::info!("Removed {n} cached responses");
+            if let Some(meta) = metadata {
+                let n = meta.clear()?;
+                tracing::info!("Removed {n} cached indicators");
+            }
+        }
+        CacheAction::Prune { older_than } => {
+            let n = cache.prune(older_than)?;
+            tracing::info!("Removed {n} cached responses");
+        }
+    }
+    Ok(())
+}
+
+/// `wbi record`: fetch a query, saving every response as a fixture. Without the indicator
+/// metadata cache, so the unit lookups a replay makes are recorded too.
+fn cmd_record(args: RecordCmdArgs, config: &Config) -> Result<()> {
+    let fixtures = wbi_rs::fixtures::Fixtures::new(&args.fixtures_dir);
+    let client = Client::default().with_fixtures(fixtures, wbi_rs::fixtures::FixtureMode::Record);
+    let points = args.fetch.resolve_with(config, client)?.fetch()?;
+    tracing::info!(
+        "Recorded the responses for {} rows in {}",
+        points.len(),
+        args.fixtures_dir.display()
+    );
+    Ok(())
+}
+
+/// `get` flags a `serve` request may not set: they write or read local files, start
+/// long-running work, or replace the server's cache.
+const SERVE_DENIED: &[&str] = &[
+    "out",
+    "plot",
+    "plot-per",
+    "open",
+    "refresh",
+    "once-if-stale",
+    "copy",
+    "stats",
+    "stats-format",
+    "emit",
+    "cache-ttl",
+    "cache-dir",
+    "fetch-log",
+    "style-config",
+    "plot-spec",
+    "save-plot-spec",
+    "legend-out",
+    "font",
+    "font-family",
+];
+
+fn cmd_serve(args: ServeCmdArgs, command: &clap::Command, config: &Config) -> Result<()> {
+    let client =
+        new_client().with_cache(open_cache(args.cache_dir.as_deref())?.with_ttl(args.cache_ttl));
+    let listener = std::net::TcpListener::bind((args.bind, args.port))
+        .with_context(|| format!("listening on {}:{}", args.bind, args.port))?;
+    tracing::info!(
+        "Serving on http://{}/ (/data, /stats, /chart.svg)",
+        listener.local_addr()?
+    );
+    std::thread::scope(|s| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    s.spawn(|| serve_connection(stream, &client, command, config));
+                }
+                Err(e) => tracing::warn!("connection failed: {e}"),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Answer one HTTP/1.1 request and close the connection.
+fn serve_connection(
+    mut stream: std::net::TcpStream,
+    client: &Client,
+    command: &clap::Command,
+    config: &Config,
+) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // Headers are not needed; read them so the client sees a complete exchange.
+    let mut line = String::new();
+    while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+        line.clear();
+    }
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or("/"));
+    let (status, content_type, body) = if method == "GET" {
+        match serve_request(target, client, command, config) {
+            Ok((content_type, body)) => (200, content_type, body),
+            Err(e) => {
+                let status = if e.is::<BadRequest>() {
+                    400
+                } else {
+                    match Failure::of(&e) {
+                        Failure::Empty => 404,
+                        Failure::Network | Failure::Api => 502,
+                        Failure::Usage | Failure::Other | Failure::Io | Failure::Data => 500,
+                    }
+                };
+                (status, "text/plain; charset=utf-8", format!("{e:#}\n"))
+            }
+        }
+    } else {
+        (
+            405,
+            "text/plain; charset=utf-8",
+            "only GET is supported\n".into(),
+        )
+    };
+    tracing::info!("{method} {target} -> {status}");
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        502 => "Bad Gateway",
+        _ => "Internal Server Error",
+    };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+}
+
+/// A `serve` request that cannot run as asked: unknown endpoint, bad or denied parameters.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct BadRequest(String);
+
+/// Content type and body for `target` (path and query string). Query parameters are `get`
+/// flags without the dashes (`countries=DEU,USA&plot-kind=area`); a parameter without a value
+/// sets a switch (`dropna`).
+fn serve_request(
+    target: &str,
+    client: &Client,
+    command: &clap::Command,
+    config: &Config,
+) -> Result<(&'static str, String)> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if !matches!(path, "/data" | "/stats" | "/chart.svg") {
+        return Err(BadRequest(format!(
+            "no endpoint {path}; use /data, /stats or /chart.svg"
+        ))
+        .into());
+    }
+    let decode = |s: &str| {
+        percent_encoding::percent_decode_str(&s.replace('+', " "))
+            .decode_utf8_lossy()
+            .into_owned()
+    };
+    let mut argv = vec!["wbi".to_string(), "get".to_string()];
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let key = decode(key);
+        if SERVE_DENIED.contains(&key.as_str()) {
+            return Err(BadRequest(format!("parameter '{key}' is not available over HTTP")).into());
+        }
+        argv.push(format!("--{key}"));
+        if !value.is_empty() {
+            argv.push(decode(value));
+        }
+    }
+    let parse = || -> Result<_> {
+        let cli = Cli::from_arg_matches(&command.clone().try_get_matches_from(argv)?)?;
+        let Command::Get(args) = cli.cmd else {
+            unreachable!("the request is parsed as a get command")
+        };
+        let mut query = args.fetch.resolve(config)?;
+        query.client = client.clone();
+        let options = match path {
+            "/chart.svg" => Some(plot_options(&args.chart, &cli.locale)?),
+            _ => None,
+        };
+        Ok((args, query, options))
+    };
+    let (args, query, options) = parse().map_err(|e| BadRequest(format!("{e:#}")))?;
+
+    let points = query.fetch_rows(&query.indicators, query.date, |_| {})?;
+    if points.is_empty() {
+        return Err(EmptyResult.into());
+    }
+    let mut points = args
+        .select
+        .apply(points, query.indicators.first().map(String::as_str))?;
+    if let Some(options) = options {
+        points.extend(query.plot_extras(&options)?);
+        return Ok(("image/svg+xml", viz::render_svg_string(&points, &options)?));
+    }
+    if path == "/stats" {
         let summaries = stats::grouped_summary(&points);
-        for s in summaries {
+        return Ok(("application/json", serde_json::to_string(&summaries)?));
+    }
+    match args.format.unwrap_or(OutFormat::Json) {
+        OutFormat::Csv => {
+            let mut out = Vec::new();
+            storage::write_csv_with_options(&points, &mut out, &args.csv.options())?;
+            Ok(("text/csv; charset=utf-8", String::from_utf8(out)?))
+        }
+        OutFormat::Json => Ok(("application/json", serde_json::to_string(&points)?)),
+    }
+}
+
+/// `d` in the largest of w/d/h/m/s that keeps it a whole number ≥ 1, e.g. `1d` or `90m`.
+fn human_duration(d: std::time::Duration) -> String {
+    let secs = d.as_secs();
+    for (unit, size) in [("w", 7 * 86_400), ("d", 86_400), ("h", 3600), ("m", 60)] {
+        if secs >= size && secs.is_multiple_of(size) {
+            return format!("{}{unit}", secs / size);
+        }
+    }
+    format!("{secs}s")
+}
+
+/// `1536` -> `1.5 KiB`.
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut v = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while v >= 1024.0 && unit + 1 < UNITS.len() {
+        v /= 1024.0;
+        unit += 1;
+    }
+    format!("{v:.1} {}", UNITS[unit])
+}
+
+/// Print the differences between two snapshots; returns whether there were any.
+fn cmd_diff(args: DiffCmdArgs, locale: &str) -> Result<bool> {
+    let mut old = storage::load(&args.old)?;
+    let mut new = storage::load(&args.new)?;
+    if args.align_years {
+        old = wbi_rs::models::align_years(&old);
+        new = wbi_rs::models::align_years(&new);
+    }
+    let d = wbi_rs::diff::diff(&old, &new);
+
+    match args.format {
+        DiffFormatArg::Json => println!("{}", serde_json::to_string_pretty(&d)?),
+        DiffFormatArg::Text => {
+            let (loc, dec_sep) = format::map_locale(locale);
+            let value = |v: Option<f64>| format::format_opt(v, loc, dec_sep);
+            for p in &d.removed {
+                println!(
+                    "- {} {} {}: {}",
+                    p.country_iso3,
+                    p.indicator_id,
+                    p.year,
+                    value(p.value)
+                );
+            }
+            for p in &d.added {
+                println!(
+                    "+ {} {} {}: {}",
+                    p.country_iso3,
+                    p.indicator_id,
+                    p.year,
+                    value(p.value)
+                );
+            }
+            for c in &d.changed {
+                println!(
+                    "~ {} {} {}: {} -> {}",
+                    c.new.country_iso3,
+                    c.new.indicator_id,
+                    c.new.year,
+                    value(c.old.value),
+                    value(c.new.value)
+                );
+            }
             println!(
-                "{} • {}  count={} missing={}  min={} max={} mean={} median={}",
-                s.key.country_iso3,
-                s.key.indicator_id,
-                s.count,
-                s.missing,
-                fmt_opt_locale(s.min, loc, dec_sep),
-                fmt_opt_locale(s.max, loc, dec_sep),
-                fmt_opt_locale(s.mean, loc, dec_sep),
-                fmt_opt_locale(s.median, loc, dec_sep),
+                "{} added, {} removed, {} changed",
+                d.added.len(),
+                d.removed.len(),
+                d.changed.len()
             );
         }
     }
+    Ok(!d.is_empty())
+}
+
+/// Whether the charts are at least `--min-similarity` alike.
+fn cmd_chart_diff(args: ChartDiffCmdArgs) -> Result<bool> {
+    use viz::chart_diff::ChartFormat;
+
+    let d = viz::chart_diff::diff_files(&args.a, &args.b, args.tolerance)?;
+    let unit = match d.format {
+        ChartFormat::Png => "pixels",
+        ChartFormat::Svg => "elements",
+    };
+    println!(
+        "similarity {:.4}: {} of {} {unit} differ",
+        d.similarity, d.differing, d.total
+    );
+    if let Some(path) = &args.out {
+        d.save_image(path)?;
+        tracing::info!("Wrote diff image to {}", path.display());
+    }
+    Ok(d.similarity >= args.min_similarity)
+}
+
+fn cmd_rank(args: RankCmdArgs, config: &Config, locale: &str) -> Result<()> {
+    let points = match &args.input {
+        Some(path) => load_saved(path, None)?,
+        None => {
+            let (start, end) = args.baseline.map_or((args.year, args.year), |b| {
+                (b.min(args.year), b.max(args.year))
+            });
+            let countries = expand_list(&args.countries, &config.country_sets, "country")?;
+            let query = Query {
+                client: new_client(),
+                aggregates: config.aggregates_in(&countries),
+                countries,
+                indicators: vec![args.indicator.clone()],
+                date: DateSpec::Range { start, end },
+                source: None,
+            };
+            let points = query.fetch()?;
+            if query
+                .countries
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case("all"))
+            {
+                points
+                    .into_iter()
+                    .filter(|p| {
+                        !p.country_iso3.is_empty()
+                            && !wbi_rs::codes::find_country(&p.country_iso3)
+                                .is_some_and(|e| e.aggregate)
+                    })
+                    .collect()
+            } else {
+                points
+            }
+        }
+    };
+    let mut ranks = stats::rank_by_year(&points, &args.indicator, args.year, args.baseline);
+    if ranks.is_empty() {
+        return Err(anyhow::Error::new(EmptyResult)
+            .context(format!("no values of {} in {}", args.indicator, args.year)));
+    }
+    if let Some(n) = args.top {
+        ranks.retain(|r| r.rank <= n);
+    }
+    print_ranks(&ranks, args.baseline, args.format, locale)
+}
+
+/// Write a ranking to stdout in `format`. Table and markdown show rank changes as arrows
+/// (`↑3`, `↓1`, `=`); CSV and JSON keep the signed number.
+fn print_ranks(
+    ranks: &[stats::RankEntry],
+    baseline: Option<i32>,
+    format: StatsFormatArg,
+    locale: &str,
+) -> Result<()> {
+    if format == StatsFormatArg::Json {
+        println!("{}", serde_json::to_string_pretty(ranks)?);
+        return Ok(());
+    }
+    let mut header = vec![
+        "rank".to_string(),
+        "country".to_string(),
+        "name".to_string(),
+        "value".to_string(),
+    ];
+    if let Some(b) = baseline {
+        header.extend([format!("rank {b}"), format!("value {b}"), "change".into()]);
+    }
+    let (loc, dec_sep) = format::map_locale(locale);
+    let num = |v: Option<f64>| match format {
+        StatsFormatArg::Csv => v.map_or_else(String::new, |x| x.to_string()),
+        _ => format::format_opt(v, loc, dec_sep),
+    };
+    let arrow = |change: i64| match format {
+        StatsFormatArg::Csv => change.to_string(),
+        _ if change > 0 => format!("↑{change}"),
+        _ if change < 0 => format!("↓{}", -change),
+        _ => "=".to_string(),
+    };
+    let rows: Vec<Vec<String>> = ranks
+        .iter()
+        .map(|r| {
+            let mut row = vec![
+                r.rank.to_string(),
+                r.country_iso3.clone(),
+                r.country_name.clone(),
+                num(Some(r.value)),
+            ];
+            if baseline.is_some() {
+                row.extend([
+                    r.baseline_rank.map_or_else(String::new, |b| b.to_string()),
+                    num(r.baseline_value),
+                    r.change.map_or_else(String::new, arrow),
+                ]);
+            }
+            row
+        })
+        .collect();
+    let header: Vec<&str> = header.iter().map(String::as_str).collect();
+    print_table(&header, 3, &rows, format)
+}
+
+fn cmd_stats(args: StatsCmdArgs, locale: &str) -> Result<()> {
+    if args.quality {
+        let points = load_saved(&args.input, args.format)?;
+        // A SQLite store also has the earlier versions of each value.
+        #[cfg(feature = "sqlite")]
+        let history = if args
+            .input
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("sqlite") || e.eq_ignore_ascii_case("db"))
+        {
+            storage::load_sqlite_history(&args.input)?
+        } else {
+            Vec::new()
+        };
+        #[cfg(not(feature = "sqlite"))]
+        let history = Vec::new();
+        return print_quality(
+            &stats::quality_with_history(&points, &history),
+            args.stats_format,
+            locale,
+        );
+    }
+    if let Some(path) = &args.projection {
+        let points = load_saved(&args.input, args.format)?;
+        let projected = load_saved(path, None)?;
+        return print_divergence(
+            &stats::divergence(&points, &projected),
+            args.stats_format,
+            locale,
+        );
+    }
+    let group_by = match args.group_by {
+        GroupByArg::Country => stats::GroupBy::Country,
+        GroupByArg::Indicator => stats::GroupBy::Indicator,
+    };
+    let is_csv = match args.format {
+        Some(format) => format == OutFormat::Csv,
+        None => args
+            .input
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("csv")),
+    };
+    let summaries = if is_csv {
+        // One streaming pass, so multi-GB exports need not fit in memory.
+        log_provenance(&args.input);
+        let file = std::fs::File::open(&args.input)
+            .with_context(|| format!("reading CSV {}", args.input.display()))?;
+        stats::summary_by_from_reader(file, group_by)
+            .with_context(|| args.input.display().to_string())?
+    } else {
+        stats::summary_by(&load_saved(&args.input, args.format)?, group_by)
+    };
+    print_summaries(&summaries, group_by, args.stats_format, locale)
+}
+
+/// Write summaries to stdout in `format`. Table and markdown use `locale` for numbers; CSV
+/// and JSON keep raw values for further processing.
+fn print_summaries(
+    summaries: &[stats::Summary],
+    group_by: stats::GroupBy,
+    format: StatsFormatArg,
+    locale: &str,
+) -> Result<()> {
+    if format == StatsFormatArg::Json {
+        println!("{}", serde_json::to_string_pretty(summaries)?);
+        return Ok(());
+    }
+
+    let mut header = vec![
+        "indicator",
+        "count",
+        "missing",
+        "min",
+        "p25",
+        "median",
+        "p75",
+        "max",
+        "mean",
+        "std",
+    ];
+    let by_country = group_by == stats::GroupBy::Country;
+    if by_country {
+        header.insert(0, "country");
+        header.push("cagr");
+    }
+    // Label columns come first and are left-aligned; the rest are numbers.
+    let keys = if by_country { 2 } else { 1 };
+    let (loc, dec_sep) = format::map_locale(locale);
+    let num = |v: Option<f64>| match format {
+        StatsFormatArg::Csv => v
+            .filter(|x| x.is_finite())
+            .map_or_else(String::new, |x| x.to_string()),
+        _ => format::format_opt(v, loc, dec_sep),
+    };
+    let rows: Vec<Vec<String>> = summaries
+        .iter()
+        .map(|s| {
+            let mut row = vec![
+                s.key.indicator_id.clone(),
+                s.count.to_string(),
+                s.missing.to_string(),
+                num(s.min),
+                num(s.p25),
+                num(s.median),
+                num(s.p75),
+                num(s.max),
+                num(s.mean),
+                num(s.std_dev),
+            ];
+            if by_country {
+                row.insert(0, s.key.country_iso3.clone());
+                row.push(num(s.cagr));
+            }
+            row
+        })
+        .collect();
+    print_table(&header, keys, &rows, format)
+}
+
+/// Divergence of projections from actuals, laid out like [`print_summaries`].
+fn print_divergence(
+    rows: &[stats::Divergence],
+    format: StatsFormatArg,
+    locale: &str,
+) -> Result<()> {
+    if format == StatsFormatArg::Json {
+        println!("{}", serde_json::to_string_pretty(rows)?);
+        return Ok(());
+    }
+    let header = [
+        "country",
+        "indicator",
+        "n",
+        "bias",
+        "mae",
+        "rmse",
+        "mape %",
+        "max error",
+        "year",
+    ];
+    let (loc, dec_sep) = format::map_locale(locale);
+    let num = |v: Option<f64>| match format {
+        StatsFormatArg::Csv => v
+            .filter(|x| x.is_finite())
+            .map_or_else(String::new, |x| x.to_string()),
+        _ => format::format_opt(v, loc, dec_sep),
+    };
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|d| {
+            vec![
+                d.key.country_iso3.clone(),
+                d.key.indicator_id.clone(),
+                d.count.to_string(),
+                num(d.mean_error),
+                num(d.mae),
+                num(d.rmse),
+                num(d.mape),
+                num(d.max_abs_error.map(|(_, e)| e)),
+                d.max_abs_error
+                    .map_or_else(String::new, |(y, _)| y.to_string()),
+            ]
+        })
+        .collect();
+    print_table(&header, 2, &rows, format)
+}
+
+/// Quality scores per series, laid out like [`print_summaries`].
+fn print_quality(
+    rows: &[stats::SeriesQuality],
+    format: StatsFormatArg,
+    locale: &str,
+) -> Result<()> {
+    if format == StatsFormatArg::Json {
+        println!("{}", serde_json::to_string_pretty(rows)?);
+        return Ok(());
+    }
+    let header = [
+        "country",
+        "indicator",
+        "complete",
+        "last",
+        "lag",
+        "estimates",
+        "revisions",
+        "score",
+    ];
+    let (loc, dec_sep) = format::map_locale(locale);
+    let num = |v: Option<f64>| match format {
+        StatsFormatArg::Csv => v
+            .filter(|x| x.is_finite())
+            .map_or_else(String::new, |x| x.to_string()),
+        _ => v.map_or_else(String::new, |x| format::format_fixed(x, 2, loc, dec_sep)),
+    };
+    let int = |v: Option<i32>| v.map_or_else(String::new, |v| v.to_string());
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|q| {
+            vec![
+                q.key.country_iso3.clone(),
+                q.key.indicator_id.clone(),
+                num(Some(q.completeness)),
+                int(q.last_year),
+                int(q.years_since_last),
+                num(Some(q.estimate_share)),
+                num(q.revision_volatility),
+                num(Some(q.score)),
+            ]
+        })
+        .collect();
+    print_table(&header, 2, &rows, format)
+}
+
+/// Write `rows` under `header` as CSV, a markdown table or a box-drawing table; the first
+/// `keys` columns are labels (left-aligned), the rest numbers.
+fn print_table(
+    header: &[&str],
+    keys: usize,
+    rows: &[Vec<String>],
+    format: StatsFormatArg,
+) -> Result<()> {
+    match format {
+        StatsFormatArg::Csv => {
+            let mut w = csv::Writer::from_writer(std::io::stdout().lock());
+            w.write_record(header)?;
+            for row in rows {
+                w.write_record(row)?;
+            }
+            w.flush()?;
+        }
+        StatsFormatArg::Markdown => {
+            println!("| {} |", header.join(" | "));
+            let align: Vec<&str> = (0..header.len())
+                .map(|i| if i < keys { ":---" } else { "---:" })
+                .collect();
+            println!("| {} |", align.join(" | "));
+            for row in rows {
+                println!("| {} |", row.join(" | "));
+            }
+        }
+        _ => {
+            let widths: Vec<usize> = (0..header.len())
+                .map(|i| {
+                    rows.iter()
+                        .map(|r| r[i].chars().count())
+                        .chain([header[i].len()])
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+            let rule = |left: &str, mid: &str, right: &str| {
+                let bars: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+                format!("{left}{}{right}", bars.join(mid))
+            };
+            let line = |cells: &[&str]| {
+                let cells: Vec<String> = cells
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        if i < keys {
+                            format!(" {:<w$} ", c, w = widths[i])
+                        } else {
+                            format!(" {:>w$} ", c, w = widths[i])
+                        }
+                    })
+                    .collect();
+                format!("│{}│", cells.join("│"))
+            };
+            println!("{}", rule("┌", "┬", "┐"));
+            println!("{}", line(header));
+            println!("{}", rule("├", "┼", "┤"));
+            for row in rows {
+                println!(
+                    "{}",
+                    line(&row.iter().map(String::as_str).collect::<Vec<_>>())
+                );
+            }
+            println!("{}", rule("└", "┴", "┘"));
+        }
+    }
+    Ok(())
+}
+
+/// Print one line per code (ok / unknown / unchecked) and fail if any code is unknown.
+fn cmd_validate(args: ValidateCmdArgs, config: &Config) -> Result<()> {
+    use wbi_rs::codes;
+
+    let client = new_client();
+    let mut lines: Vec<(String, &str, String)> = Vec::new();
+    let mut unknown = 0;
+
+    let countries = match &args.countries {
+        Some(s) => expand_list(s, &config.country_sets, "country")?,
+        None => Vec::new(),
+    };
+    for code in countries {
+        if code.eq_ignore_ascii_case("all") {
+            lines.push((code, "ok", "all economies".to_string()));
+            continue;
+        }
+        if let Some((_, a)) = config
+            .aggregates
+            .iter()
+            .find(|(c, _)| c.eq_ignore_ascii_case(&code))
+        {
+            let what = format!("custom aggregate of {}", a.members.join(", "));
+            lines.push((code, "ok", what));
+            continue;
+        }
+        if let Some(e) = codes::find_country(&code) {
+            lines.push((code, "ok", e.name.to_string()));
+            continue;
+        }
+        // The API knows a few codes beyond the built-in list; ask it before calling a typo.
+        if !args.offline {
+            match client.lookup_country(&code) {
+                Ok(Some(name)) => {
+                    lines.push((code, "ok", name));
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("could not check {code} online: {e:#}");
+                }
+            }
+        }
+        let hint = codes::suggest_countries(&code)
+            .iter()
+            .map(|e| {
+                let suggested = if code.len() == 2 { e.iso2 } else { e.iso3 };
+                format!("{suggested} ({})", e.name)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let hint = if hint.is_empty() {
+            String::new()
+        } else {
+            format!("did you mean {hint}?")
+        };
+        lines.push((code, "unknown", hint));
+        unknown += 1;
+    }
+
+    let indicators = match &args.indicators {
+        Some(s) => expand_list(s, &config.indicator_sets, "indicator")?,
+        None => Vec::new(),
+    };
+    for id in indicators {
+        if !codes::is_indicator_id(&id) {
+            lines.push((id, "unknown", "not an indicator code".to_string()));
+            unknown += 1;
+        } else if args.offline {
+            lines.push((id, "unchecked", "offline".to_string()));
+        } else {
+            match client.lookup_indicator(&id) {
+                Ok(Some(meta)) => lines.push((id, "ok", meta.name)),
+                Ok(None) => {
+                    lines.push((id, "unknown", "not found in the API".to_string()));
+                    unknown += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("could not check {id} online: {e:#}");
+                    lines.push((id, "unchecked", "API unreachable".to_string()));
+                }
+            }
+        }
+    }
 
+    let width = lines.iter().map(|l| l.0.chars().count()).max().unwrap_or(0);
+    for (code, status, note) in &lines {
+        println!("{code:<width$}  {status:<9}  {note}");
+    }
+    if unknown > 0 {
+        bail!("{unknown} of {} codes are unknown", lines.len());
+    }
     Ok(())
 }
 
+/// Parse an age like `30d`: a whole number followed by s, m, h, d or w.
+fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: u64 = num
+        .parse()
+        .map_err(|_| format!("invalid duration '{s}', expected e.g. 30d or 12h"))?;
+    let secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => {
+            return Err(format!(
+                "invalid duration unit in '{s}', use s, m, h, d or w"
+            ));
+        }
+    };
+    Ok(std::time::Duration::from_secs(n * secs))
+}
+
+/// Parse a single-byte CSV delimiter; `tab` or `\t` for a tab.
+fn parse_delimiter(s: &str) -> Result<u8, String> {
+    match s {
+        "tab" | "\\t" | "\t" => Ok(b'\t'),
+        _ if s.len() == 1 && s.is_ascii() && s != "\"" && s != "\n" => Ok(s.as_bytes()[0]),
+        _ => Err(format!(
+            "expected a single ASCII character or 'tab' as delimiter, got '{s}'"
+        )),
+    }
+}
+
+/// Parse a percentage in [0, 100].
+fn parse_percent(s: &str) -> Result<f64, String> {
+    match s.trim().trim_end_matches('%').parse::<f64>() {
+        Ok(v) if (0.0..=100.0).contains(&v) => Ok(v),
+        _ => Err(format!(
+            "expected a percentage between 0 and 100, got '{s}'"
+        )),
+    }
+}
+
+/// Parse `--by`: `last`, `mean` or `year=YYYY`.
+fn parse_rank_by(s: &str) -> Result<stats::RankBy, String> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "last" => Ok(stats::RankBy::Last),
+        "mean" => Ok(stats::RankBy::Mean),
+        other => other
+            .strip_prefix("year=")
+            .and_then(|y| y.parse().ok())
+            .map(stats::RankBy::Year)
+            .ok_or_else(|| "expected last, mean or year=YYYY".to_string()),
+    }
+}
+
 /// Validate `--loess-span` ∈ (0, 1].
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if (0.0..=1.0).contains(&v) => Ok(v),
+        _ => Err(format!("expected a number between 0 and 1, got '{s}'")),
+    }
+}
+
+fn parse_bar_gap(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if (0.0..1.0).contains(&v) => Ok(v),
+        _ => Err(format!("expected a fraction in [0, 1), got '{s}'")),
+    }
+}
+
+fn parse_percent_bounds(s: &str) -> Result<viz::PercentBounds, String> {
+    match s.trim() {
+        "data" => Ok(viz::PercentBounds::Data),
+        "full" => Ok(viz::PercentBounds::Full),
+        pad => parse_fraction(pad)
+            .map(viz::PercentBounds::Padded)
+            .map_err(|_| format!("expected data, full or a padding fraction, got '{s}'")),
+    }
+}
+
+fn parse_pareto_reference(s: &str) -> Result<Option<f64>, String> {
+    if s.trim().eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    match s.trim().trim_end_matches('%').parse::<f64>() {
+        Ok(v) if v > 0.0 && v < 100.0 => Ok(Some(v)),
+        _ => Err(format!(
+            "expected a percentage between 0 and 100 or none, got '{s}'"
+        )),
+    }
+}
+
+fn parse_axis_break(s: &str) -> Result<viz::AxisBreak, String> {
+    let err = || format!("expected FROM:TO with FROM < TO, e.g. 10:100, got '{s}'");
+    let (from, to) = s.split_once(':').ok_or_else(err)?;
+    let from: f64 = from.trim().parse().map_err(|_| err())?;
+    let to: f64 = to.trim().parse().map_err(|_| err())?;
+    if !(from.is_finite() && to.is_finite() && from < to) {
+        return Err(err());
+    }
+    Ok(viz::AxisBreak { from, to })
+}
+
 fn parse_loess_span(s: &str) -> Result<f64, String> {
     let x: f64 = s
         .parse()
@@ -298,11 +3623,251 @@ fn parse_loess_span(s: &str) -> Result<f64, String> {
     }
 }
 
+fn parse_pair(s: &str) -> Result<(String, String), String> {
+    match parse_list(s).as_slice() {
+        [a, b] if !a.eq_ignore_ascii_case(b) => Ok((a.clone(), b.clone())),
+        _ => Err("expected two different country codes, e.g. DEU,FRA".into()),
+    }
+}
+
+fn parse_scale(s: &str) -> Result<f64, String> {
+    let x: f64 = s
+        .parse()
+        .map_err(|_| "invalid float for --scale".to_string())?;
+    if !(x > 0.0 && x <= 8.0) {
+        Err("scale must be in (0, 8]".into())
+    } else {
+        Ok(x)
+    }
+}
+
 #[cfg(test)]
 mod tests_out_format {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn batch_jobs_become_get_flags() {
+        let job: toml::Table = toml::from_str(
+            r#"
+            name = "population"
+            countries = ["DEU", "FRA"]
+            plot_kind = "line-points"
+            index-year = 2010
+            stats = true
+            country-styles = false
+            "#,
+        )
+        .unwrap();
+        let args = job_args(&job).unwrap();
+        assert_eq!(&args[..2], ["wbi", "get"]);
+        let rest = args[2..].join(" ");
+        assert!(rest.contains("--countries DEU,FRA"), "{rest}");
+        assert!(rest.contains("--plot-kind line-points"), "{rest}");
+        assert!(rest.contains("--index-year 2010"), "{rest}");
+        assert!(rest.contains("--stats"), "{rest}");
+        assert!(!rest.contains("country-styles") && !rest.contains("population"));
+    }
+
+    #[test]
+    fn chart_flag_defaults_match_plot_options_defaults() {
+        // --plot-spec only lets flags that differ from PlotOptions::default() override it.
+        let cli = Cli::try_parse_from(["wbi", "plot", "--in", "x.csv", "--out", "x.svg"]).unwrap();
+        let Command::Plot(args) = cli.cmd else {
+            unreachable!()
+        };
+        let options = chart_options(&args.chart, &cli.locale).unwrap();
+        assert_eq!(options, viz::PlotOptions::default());
+    }
+
+    #[test]
+    fn output_templates_split_rows_per_placeholder() {
+        let dp = |ind: &str, iso3: &str, year: i32| wbi_rs::models::DataPoint {
+            indicator_id: ind.into(),
+            indicator_name: "x".into(),
+            country_id: "XX".into(),
+            country_name: "x".into(),
+            country_iso3: iso3.into(),
+            year,
+            value: Some(1.0),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        };
+        let rows = vec![
+            dp("A.B", "DEU", 2000),
+            dp("A.B", "FRA", 2001),
+            dp("C/D", "DEU", 2005),
+        ];
+
+        let single = outputs_for(Path::new("out/all.csv"), &rows);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].1.len(), 3);
+
+        let per: Vec<(String, usize)> = outputs_for(Path::new("out/{indicator}_{date}.svg"), &rows)
+            .into_iter()
+            .map(|(p, r)| (p.display().to_string(), r.len()))
+            .collect();
+        assert_eq!(
+            per,
+            [
+                ("out/A.B_2000-2001.svg".to_string(), 2),
+                ("out/C_D_2005.svg".to_string(), 1)
+            ]
+        );
+
+        assert_eq!(
+            outputs_for(Path::new("{indicator}-{country}.csv"), &rows).len(),
+            3
+        );
+        let today = outputs_for(Path::new("{today}.csv"), &rows);
+        assert_eq!(today.len(), 1);
+        assert!(!today[0].0.display().to_string().contains('{'));
+    }
+
+    #[test]
+    fn plot_per_adds_the_group_placeholder_once() {
+        let t = |p: &str, per| per_chart_template(Path::new(p), per).display().to_string();
+        assert_eq!(t("charts/pop.svg", None), "charts/pop.svg");
+        assert_eq!(
+            t("charts/pop.svg", Some(PlotPerArg::Indicator)),
+            "charts/pop_{indicator}.svg"
+        );
+        assert_eq!(t("chart", Some(PlotPerArg::Country)), "chart_{country}");
+        assert_eq!(
+            t("{country}/gdp.png", Some(PlotPerArg::Country)),
+            "{country}/gdp.png"
+        );
+    }
+
+    #[test]
+    fn opener_passes_the_path_last() {
+        let cmd = opener(Path::new("out/chart.svg"));
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(
+            args.last().copied(),
+            Some(std::ffi::OsStr::new("out/chart.svg"))
+        );
+    }
+
+    #[test]
+    fn failures_are_classified_through_context() {
+        let api = |e: wbi_rs::ApiError| anyhow::Error::from(e).context("GET url");
+        assert_eq!(
+            Failure::of(&api(wbi_rs::ApiError::Network("timeout".into()))),
+            Failure::Network
+        );
+        assert_eq!(
+            Failure::of(&api(wbi_rs::ApiError::Api("bad".into()))),
+            Failure::Api
+        );
+        assert_eq!(
+            Failure::of(&anyhow::Error::from(EmptyResult)),
+            Failure::Empty
+        );
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        assert_eq!(
+            Failure::of(&anyhow::Error::from(io).context("reading x")),
+            Failure::Io
+        );
+        assert_eq!(Failure::of(&anyhow::anyhow!("other")), Failure::Other);
+        let usage = anyhow::Error::from(UsageError("invalid --date".into())).context("get");
+        assert_eq!(Failure::of(&usage), Failure::Usage);
+        assert_eq!(
+            Failure::of_comparison(&anyhow::anyhow!("other")),
+            Failure::Data
+        );
+    }
+
+    #[test]
+    fn durations_print_in_the_largest_whole_unit() {
+        let d = std::time::Duration::from_secs;
+        assert_eq!(human_duration(d(86_400)), "1d");
+        assert_eq!(human_duration(d(5400)), "90m");
+        assert_eq!(human_duration(d(14 * 86_400)), "2w");
+        assert_eq!(human_duration(d(45)), "45s");
+    }
+
+    #[test]
+    fn batch_jobs_label_their_progress_bars() {
+        assert_eq!(fetch_progress_bar().prefix(), "");
+        let bar = with_job_label("population".into(), fetch_progress_bar);
+        assert_eq!(bar.prefix(), "population: ");
+        assert_eq!(fetch_progress_bar().prefix(), "");
+    }
+
+    #[test]
+    fn shell_line_quotes_only_when_needed() {
+        let args = [
+            "wbi", "get", "-c", "DEU;FRA", "--title", "It's GDP", "--date", "",
+        ];
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        assert_eq!(
+            shell_line(&args),
+            r#"wbi get -c 'DEU;FRA' --title 'It'\''s GDP' --date ''"#
+        );
+    }
+
+    #[test]
+    fn durations_take_a_unit() {
+        assert_eq!(
+            parse_duration("30d"),
+            Ok(std::time::Duration::from_secs(30 * 86_400))
+        );
+        assert_eq!(
+            parse_duration("90m"),
+            Ok(std::time::Duration::from_secs(5400))
+        );
+        assert!(parse_duration("30").is_err());
+        assert!(parse_duration("d").is_err());
+        assert_eq!(human_bytes(1536), "1.5 KiB");
+    }
+
+    #[test]
+    fn rank_by_parses_last_mean_and_year() {
+        assert_eq!(parse_rank_by("last"), Ok(stats::RankBy::Last));
+        assert_eq!(parse_rank_by("Mean"), Ok(stats::RankBy::Mean));
+        assert_eq!(parse_rank_by("year=2015"), Ok(stats::RankBy::Year(2015)));
+        assert!(parse_rank_by("year=").is_err());
+        assert!(parse_rank_by("max").is_err());
+    }
+
+    #[test]
+    fn code_lists_expand_config_sets() {
+        let sets = HashMap::from([(
+            "dach".to_string(),
+            vec!["DEU".into(), "AUT".into(), "CHE".into()],
+        )]);
+        let got = expand_list("@dach;FRA", &sets, "country").unwrap();
+        assert_eq!(got, ["DEU", "AUT", "CHE", "FRA"]);
+        let err = expand_list("@nordics", &sets, "country").unwrap_err();
+        assert!(err.to_string().contains("unknown country set '@nordics'"));
+        assert!(err.to_string().contains("@G7"));
+    }
+
+    #[test]
+    fn code_lists_expand_built_in_country_groups_unless_redefined() {
+        let got = expand_list("@g7", &HashMap::new(), "country").unwrap();
+        assert_eq!(got, ["CAN", "FRA", "DEU", "ITA", "JPN", "GBR", "USA"]);
+        assert!(expand_list("@G7", &HashMap::new(), "indicator").is_err());
+
+        let sets = HashMap::from([("G7".to_string(), vec!["DEU".into()])]);
+        assert_eq!(expand_list("@G7", &sets, "country").unwrap(), ["DEU"]);
+    }
+
+    #[test]
+    fn code_lists_read_files_when_no_set_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("countries.txt");
+        std::fs::write(&file, "# G3\nDEU\nFRA, ITA # founders\n\n").unwrap();
+        let got = expand_list(
+            &format!("USA;@{}", file.display()),
+            &HashMap::new(),
+            "country",
+        );
+        assert_eq!(got.unwrap(), ["USA", "DEU", "FRA", "ITA"]);
+    }
+
     #[test]
     fn ext_csv_no_flag_yields_csv() {
         let p = PathBuf::from("pop.csv");
@@ -0,0 +1,328 @@
+/// On-disk cache of API responses, keyed by request URL.
+///
+/// Each response is stored as one small JSON file (`{"url": …, "body": …}`) in a cache
+/// directory; the file's modification time is its fetch time. Attach a cache to the client
+/// with [`Client::with_cache`](crate::Client::with_cache); responses younger than the TTL
+/// are then served from disk instead of the network.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::{Client, cache::HttpCache};
+/// # use std::time::Duration;
+/// let cache = HttpCache::new(HttpCache::default_dir().unwrap())
+///     .with_ttl(Duration::from_secs(24 * 3600));
+/// let client = Client::default().with_cache(cache);
+/// # let _ = client;
+/// ```
+///
+/// Indicator metadata (names, units, source notes) has its own longer-lived store,
+/// [`MetadataCache`]: a single file keyed by indicator id, so unit enrichment does not
+/// re-request indicators it has already seen, whatever other indicators a query combines
+/// them with.
+use crate::models::IndicatorMeta;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::NamedTempFile;
+
+/// Response cache rooted at a directory. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// Totals reported by [`HttpCache::info`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheInfo {
+    pub entries: usize,
+    pub bytes: u64,
+    /// Fetch times of the oldest and newest entry; `None` when empty.
+    pub oldest: Option<SystemTime>,
+    pub newest: Option<SystemTime>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    url: String,
+    body: Value,
+}
+
+impl HttpCache {
+    /// Default TTL: one day.
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 3600);
+
+    /// Cache in `dir` (created on first write) with [`Self::DEFAULT_TTL`].
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: Self::DEFAULT_TTL,
+        }
+    }
+
+    /// Serve entries younger than `ttl`; older ones are refetched and overwritten. A zero TTL
+    /// only writes the cache.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// `$XDG_CACHE_HOME/wbi/http`, falling back to `~/.cache/wbi/http`.
+    pub fn default_dir() -> Option<PathBuf> {
+        let base = match std::env::var_os("XDG_CACHE_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+        };
+        Some(base.join("wbi").join("http"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The cached body for `url`, if present and fresh. Unreadable entries count as misses.
+    pub fn get(&self, url: &str) -> Option<Value> {
+        let path = self.entry_path(url);
+        let age = path.metadata().ok()?.modified().ok()?.elapsed().ok()?;
+        if age >= self.ttl {
+            return None;
+        }
+        let text = std::fs::read_to_string(&path).ok()?;
+        let entry: Entry = serde_json::from_str(&text).ok()?;
+        // Guard against hash collisions.
+        (entry.url == url).then_some(entry.body)
+    }
+
+    /// Store `body` as the response for `url` (atomic write).
+    pub fn put(&self, url: &str, body: &Value) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating cache dir {}", self.dir.display()))?;
+        let mut tmp = NamedTempFile::new_in(&self.dir)?;
+        serde_json::to_writer(
+            tmp.as_file_mut(),
+            &Entry {
+                url: url.to_string(),
+                body: body.clone(),
+            },
+        )?;
+        tmp.persist(self.entry_path(url))?;
+        Ok(())
+    }
+
+    /// Entry count, total size and fetch-time range.
+    pub fn info(&self) -> Result<CacheInfo> {
+        let mut info = CacheInfo::default();
+        for (_, meta) in self.entries()? {
+            info.entries += 1;
+            info.bytes += meta.len();
+            if let Ok(t) = meta.modified() {
+                info.oldest = Some(info.oldest.map_or(t, |o| o.min(t)));
+                info.newest = Some(info.newest.map_or(t, |n| n.max(t)));
+            }
+        }
+        Ok(info)
+    }
+
+    /// Delete every entry; returns how many were removed.
+    pub fn clear(&self) -> Result<usize> {
+        self.prune(Duration::ZERO)
+    }
+
+    /// Delete entries fetched more than `older_than` ago; returns how many were removed.
+    pub fn prune(&self, older_than: Duration) -> Result<usize> {
+        let mut removed = 0;
+        for (path, meta) in self.entries()? {
+            let age = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.elapsed().ok())
+                .unwrap_or(Duration::MAX);
+            if age >= older_than {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("removing {}", path.display()))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Cache files with their metadata; a missing directory is an empty cache.
+    fn entries(&self) -> Result<Vec<(PathBuf, std::fs::Metadata)>> {
+        let read = match std::fs::read_dir(&self.dir) {
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("reading cache dir {}", self.dir.display()));
+            }
+        };
+        let mut out = Vec::new();
+        for item in read {
+            let item = item?;
+            let path = item.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                out.push((path, item.metadata()?));
+            }
+        }
+        Ok(out)
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.json", fnv1a(url.as_bytes())))
+    }
+}
+
+/// Indicator metadata keyed by indicator id, persisted in one JSON file. Attach it with
+/// [`Client::with_metadata_cache`](crate::Client::with_metadata_cache).
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::{Client, cache::MetadataCache};
+/// let cache = MetadataCache::new(MetadataCache::default_path().unwrap());
+/// let client = Client::default().with_metadata_cache(cache);
+/// # let _ = client;
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetadataCache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MetaEntry {
+    /// Fetch time in seconds since the Unix epoch.
+    fetched: u64,
+    meta: IndicatorMeta,
+}
+
+impl MetadataCache {
+    /// Default TTL: 30 days. Indicator names and units rarely change.
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(30 * 24 * 3600);
+
+    /// Cache stored in the file `path` (created on first write) with [`Self::DEFAULT_TTL`].
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ttl: Self::DEFAULT_TTL,
+        }
+    }
+
+    /// Treat entries older than `ttl` as stale; they are refetched when the API is reachable.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// `$XDG_CACHE_HOME/wbi/indicators.json`, falling back to `~/.cache/wbi/indicators.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(HttpCache::default_dir()?.with_file_name("indicators.json"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Fresh entries for `ids`; ids without one are absent from the map.
+    pub fn get(&self, ids: &[String]) -> HashMap<String, IndicatorMeta> {
+        let now = unix_now();
+        self.lookup(ids, |e| now.saturating_sub(e.fetched) < self.ttl.as_secs())
+    }
+
+    /// Entries for `ids` regardless of age, for when the API cannot be reached.
+    pub fn get_stale(&self, ids: &[String]) -> HashMap<String, IndicatorMeta> {
+        self.lookup(ids, |_| true)
+    }
+
+    /// Store `metas`, replacing older entries with the same id (atomic write).
+    pub fn put(&self, metas: &[IndicatorMeta]) -> Result<()> {
+        if metas.is_empty() {
+            return Ok(());
+        }
+        let mut entries = self.load();
+        let fetched = unix_now();
+        for meta in metas {
+            entries.insert(
+                meta.id.clone(),
+                MetaEntry {
+                    fetched,
+                    meta: meta.clone(),
+                },
+            );
+        }
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("creating cache dir {}", dir.display()))?;
+        let mut tmp = NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(tmp.as_file_mut(), &entries)?;
+        tmp.persist(&self.path)?;
+        Ok(())
+    }
+
+    /// Number of cached indicators.
+    pub fn len(&self) -> usize {
+        self.load().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Delete the cache file; returns how many entries it held.
+    pub fn clear(&self) -> Result<usize> {
+        let n = self.len();
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(n),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e).with_context(|| format!("removing {}", self.path.display())),
+        }
+    }
+
+    fn lookup(
+        &self,
+        ids: &[String],
+        keep: impl Fn(&MetaEntry) -> bool,
+    ) -> HashMap<String, IndicatorMeta> {
+        let mut entries = self.load();
+        ids.iter()
+            .filter_map(|id| {
+                let entry = entries.remove(id).filter(|e| keep(e))?;
+                Some((id.clone(), entry.meta))
+            })
+            .collect()
+    }
+
+    /// All entries; a missing or unreadable file is an empty cache.
+    fn load(&self) -> BTreeMap<String, MetaEntry> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// FNV-1a: a stable hash, so file names survive toolchain and dependency updates.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
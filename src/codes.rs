@@ -0,0 +1,452 @@
+//! Offline lists of country and aggregate codes, for checking user input before a fetch.
+//!
+//! [`ECONOMIES`] holds the ISO 3166-1 countries and territories (alpha-3 and alpha-2), the
+//! World Bank's own codes for Kosovo (`XKX`) and the Channel Islands (`CHI`), and the World
+//! Bank aggregates (`WLD`, `EUU`, income and lending groups, …). The API knows a few codes
+//! beyond these, so a miss here is a strong hint of a typo, not proof;
+//! [`Client::lookup_country`](crate::Client::lookup_country) gives the definitive answer.
+//!
+//! [`country_group`] resolves the built-in country sets (`G7`, `G20`, `EU27`, `OECD`,
+//! `BRICS`, …) that the CLI accepts as `--countries @G7`.
+//!
+//! ### Example
+//! ```
+//! use wbi_rs::codes::{country_group, find_country, suggest_countries};
+//!
+//! assert_eq!(find_country("de").map(|e| e.iso3), Some("DEU"));
+//! assert_eq!(suggest_countries("DUE")[0].iso3, "DEU");
+//! assert_eq!(country_group("g7").map(|g| g.len()), Some(7));
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// A country, territory or aggregate known to the World Bank API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Economy {
+    pub iso3: &'static str,
+    pub iso2: &'static str,
+    pub name: &'static str,
+    /// `true` for regions and groups such as `EUU` or `HIC`.
+    pub aggregate: bool,
+}
+
+const fn country(iso3: &'static str, iso2: &'static str, name: &'static str) -> Economy {
+    Economy {
+        iso3,
+        iso2,
+        name,
+        aggregate: false,
+    }
+}
+
+const fn aggregate(iso3: &'static str, iso2: &'static str, name: &'static str) -> Economy {
+    Economy {
+        iso3,
+        iso2,
+        name,
+        aggregate: true,
+    }
+}
+
+/// Countries and territories (by name), then aggregates (by name).
+pub static ECONOMIES: &[Economy] = &[
+    country("AFG", "AF", "Afghanistan"),
+    country("ALA", "AX", "Åland Islands"),
+    country("ALB", "AL", "Albania"),
+    country("DZA", "DZ", "Algeria"),
+    country("ASM", "AS", "American Samoa"),
+    country("AND", "AD", "Andorra"),
+    country("AGO", "AO", "Angola"),
+    country("AIA", "AI", "Anguilla"),
+    country("ATA", "AQ", "Antarctica"),
+    country("ATG", "AG", "Antigua and Barbuda"),
+    country("ARG", "AR", "Argentina"),
+    country("ARM", "AM", "Armenia"),
+    country("ABW", "AW", "Aruba"),
+    country("AUS", "AU", "Australia"),
+    country("AUT", "AT", "Austria"),
+    country("AZE", "AZ", "Azerbaijan"),
+    country("BHS", "BS", "Bahamas"),
+    country("BHR", "BH", "Bahrain"),
+    country("BGD", "BD", "Bangladesh"),
+    country("BRB", "BB", "Barbados"),
+    country("BLR", "BY", "Belarus"),
+    country("BEL", "BE", "Belgium"),
+    country("BLZ", "BZ", "Belize"),
+    country("BEN", "BJ", "Benin"),
+    country("BMU", "BM", "Bermuda"),
+    country("BTN", "BT", "Bhutan"),
+    country("BOL", "BO", "Bolivia"),
+    country("BES", "BQ", "Bonaire, Sint Eustatius and Saba"),
+    country("BIH", "BA", "Bosnia and Herzegovina"),
+    country("BWA", "BW", "Botswana"),
+    country("BVT", "BV", "Bouvet Island"),
+    country("BRA", "BR", "Brazil"),
+    country("IOT", "IO", "British Indian Ocean Territory"),
+    country("BRN", "BN", "Brunei Darussalam"),
+    country("BGR", "BG", "Bulgaria"),
+    country("BFA", "BF", "Burkina Faso"),
+    country("BDI", "BI", "Burundi"),
+    country("CPV", "CV", "Cabo Verde"),
+    country("KHM", "KH", "Cambodia"),
+    country("CMR", "CM", "Cameroon"),
+    country("CAN", "CA", "Canada"),
+    country("CYM", "KY", "Cayman Islands"),
+    country("CAF", "CF", "Central African Republic"),
+    country("TCD", "TD", "Chad"),
+    country("CHI", "JG", "Channel Islands"),
+    country("CHL", "CL", "Chile"),
+    country("CHN", "CN", "China"),
+    country("CXR", "CX", "Christmas Island"),
+    country("CCK", "CC", "Cocos (Keeling) Islands"),
+    country("COL", "CO", "Colombia"),
+    country("COM", "KM", "Comoros"),
+    country("COG", "CG", "Congo"),
+    country("COD", "CD", "Congo, Democratic Republic of the"),
+    country("COK", "CK", "Cook Islands"),
+    country("CRI", "CR", "Costa Rica"),
+    country("CIV", "CI", "Côte d'Ivoire"),
+    country("HRV", "HR", "Croatia"),
+    country("CUB", "CU", "Cuba"),
+    country("CUW", "CW", "Curaçao"),
+    country("CYP", "CY", "Cyprus"),
+    country("CZE", "CZ", "Czechia"),
+    country("DNK", "DK", "Denmark"),
+    country("DJI", "DJ", "Djibouti"),
+    country("DMA", "DM", "Dominica"),
+    country("DOM", "DO", "Dominican Republic"),
+    country("ECU", "EC", "Ecuador"),
+    country("EGY", "EG", "Egypt"),
+    country("SLV", "SV", "El Salvador"),
+    country("GNQ", "GQ", "Equatorial Guinea"),
+    country("ERI", "ER", "Eritrea"),
+    country("EST", "EE", "Estonia"),
+    country("SWZ", "SZ", "Eswatini"),
+    country("ETH", "ET", "Ethiopia"),
+    country("FLK", "FK", "Falkland Islands"),
+    country("FRO", "FO", "Faroe Islands"),
+    country("FJI", "FJ", "Fiji"),
+    country("FIN", "FI", "Finland"),
+    country("FRA", "FR", "France"),
+    country("GUF", "GF", "French Guiana"),
+    country("PYF", "PF", "French Polynesia"),
+    country("ATF", "TF", "French Southern Territories"),
+    country("GAB", "GA", "Gabon"),
+    country("GMB", "GM", "Gambia"),
+    country("GEO", "GE", "Georgia"),
+    country("DEU", "DE", "Germany"),
+    country("GHA", "GH", "Ghana"),
+    country("GIB", "GI", "Gibraltar"),
+    country("GRC", "GR", "Greece"),
+    country("GRL", "GL", "Greenland"),
+    country("GRD", "GD", "Grenada"),
+    country("GLP", "GP", "Guadeloupe"),
+    country("GUM", "GU", "Guam"),
+    country("GTM", "GT", "Guatemala"),
+    country("GGY", "GG", "Guernsey"),
+    country("GIN", "GN", "Guinea"),
+    country("GNB", "GW", "Guinea-Bissau"),
+    country("GUY", "GY", "Guyana"),
+    country("HTI", "HT", "Haiti"),
+    country("HMD", "HM", "Heard Island and McDonald Islands"),
+    country("VAT", "VA", "Holy See"),
+    country("HND", "HN", "Honduras"),
+    country("HKG", "HK", "Hong Kong"),
+    country("HUN", "HU", "Hungary"),
+    country("ISL", "IS", "Iceland"),
+    country("IND", "IN", "India"),
+    country("IDN", "ID", "Indonesia"),
+    country("IRN", "IR", "Iran"),
+    country("IRQ", "IQ", "Iraq"),
+    country("IRL", "IE", "Ireland"),
+    country("IMN", "IM", "Isle of Man"),
+    country("ISR", "IL", "Israel"),
+    country("ITA", "IT", "Italy"),
+    country("JAM", "JM", "Jamaica"),
+    country("JPN", "JP", "Japan"),
+    country("JEY", "JE", "Jersey"),
+    country("JOR", "JO", "Jordan"),
+    country("KAZ", "KZ", "Kazakhstan"),
+    country("KEN", "KE", "Kenya"),
+    country("KIR", "KI", "Kiribati"),
+    country("PRK", "KP", "Korea, Democratic People's Republic of"),
+    country("KOR", "KR", "Korea, Republic of"),
+    country("XKX", "XK", "Kosovo"),
+    country("KWT", "KW", "Kuwait"),
+    country("KGZ", "KG", "Kyrgyzstan"),
+    country("LAO", "LA", "Lao People's Democratic Republic"),
+    country("LVA", "LV", "Latvia"),
+    country("LBN", "LB", "Lebanon"),
+    country("LSO", "LS", "Lesotho"),
+    country("LBR", "LR", "Liberia"),
+    country("LBY", "LY", "Libya"),
+    country("LIE", "LI", "Liechtenstein"),
+    country("LTU", "LT", "Lithuania"),
+    country("LUX", "LU", "Luxembourg"),
+    country("MAC", "MO", "Macao"),
+    country("MDG", "MG", "Madagascar"),
+    country("MWI", "MW", "Malawi"),
+    country("MYS", "MY", "Malaysia"),
+    country("MDV", "MV", "Maldives"),
+    country("MLI", "ML", "Mali"),
+    country("MLT", "MT", "Malta"),
+    country("MHL", "MH", "Marshall Islands"),
+    country("MTQ", "MQ", "Martinique"),
+    country("MRT", "MR", "Mauritania"),
+    country("MUS", "MU", "Mauritius"),
+    country("MYT", "YT", "Mayotte"),
+    country("MEX", "MX", "Mexico"),
+    country("FSM", "FM", "Micronesia"),
+    country("MDA", "MD", "Moldova"),
+    country("MCO", "MC", "Monaco"),
+    country("MNG", "MN", "Mongolia"),
+    country("MNE", "ME", "Montenegro"),
+    country("MSR", "MS", "Montserrat"),
+    country("MAR", "MA", "Morocco"),
+    country("MOZ", "MZ", "Mozambique"),
+    country("MMR", "MM", "Myanmar"),
+    country("NAM", "NA", "Namibia"),
+    country("NRU", "NR", "Nauru"),
+    country("NPL", "NP", "Nepal"),
+    country("NLD", "NL", "Netherlands"),
+    country("NCL", "NC", "New Caledonia"),
+    country("NZL", "NZ", "New Zealand"),
+    country("NIC", "NI", "Nicaragua"),
+    country("NER", "NE", "Niger"),
+    country("NGA", "NG", "Nigeria"),
+    country("NIU", "NU", "Niue"),
+    country("NFK", "NF", "Norfolk Island"),
+    country("MKD", "MK", "North Macedonia"),
+    country("MNP", "MP", "Northern Mariana Islands"),
+    country("NOR", "NO", "Norway"),
+    country("OMN", "OM", "Oman"),
+    country("PAK", "PK", "Pakistan"),
+    country("PLW", "PW", "Palau"),
+    country("PSE", "PS", "Palestine, State of"),
+    country("PAN", "PA", "Panama"),
+    country("PNG", "PG", "Papua New Guinea"),
+    country("PRY", "PY", "Paraguay"),
+    country("PER", "PE", "Peru"),
+    country("PHL", "PH", "Philippines"),
+    country("PCN", "PN", "Pitcairn"),
+    country("POL", "PL", "Poland"),
+    country("PRT", "PT", "Portugal"),
+    country("PRI", "PR", "Puerto Rico"),
+    country("QAT", "QA", "Qatar"),
+    country("REU", "RE", "Réunion"),
+    country("ROU", "RO", "Romania"),
+    country("RUS", "RU", "Russian Federation"),
+    country("RWA", "RW", "Rwanda"),
+    country("BLM", "BL", "Saint Barthélemy"),
+    country("SHN", "SH", "Saint Helena, Ascension and Tristan da Cunha"),
+    country("KNA", "KN", "Saint Kitts and Nevis"),
+    country("LCA", "LC", "Saint Lucia"),
+    country("MAF", "MF", "Saint Martin (French part)"),
+    country("SPM", "PM", "Saint Pierre and Miquelon"),
+    country("VCT", "VC", "Saint Vincent and the Grenadines"),
+    country("WSM", "WS", "Samoa"),
+    country("SMR", "SM", "San Marino"),
+    country("STP", "ST", "Sao Tome and Principe"),
+    country("SAU", "SA", "Saudi Arabia"),
+    country("SEN", "SN", "Senegal"),
+    country("SRB", "RS", "Serbia"),
+    country("SYC", "SC", "Seychelles"),
+    country("SLE", "SL", "Sierra Leone"),
+    country("SGP", "SG", "Singapore"),
+    country("SXM", "SX", "Sint Maarten (Dutch part)"),
+    country("SVK", "SK", "Slovakia"),
+    country("SVN", "SI", "Slovenia"),
+    country("SLB", "SB", "Solomon Islands"),
+    country("SOM", "SO", "Somalia"),
+    country("ZAF", "ZA", "South Africa"),
+    country("SGS", "GS", "South Georgia and the South Sandwich Islands"),
+    country("SSD", "SS", "South Sudan"),
+    country("ESP", "ES", "Spain"),
+    country("LKA", "LK", "Sri Lanka"),
+    country("SDN", "SD", "Sudan"),
+    country("SUR", "SR", "Suriname"),
+    country("SJM", "SJ", "Svalbard and Jan Mayen"),
+    country("SWE", "SE", "Sweden"),
+    country("CHE", "CH", "Switzerland"),
+    country("SYR", "SY", "Syrian Arab Republic"),
+    country("TWN", "TW", "Taiwan"),
+    country("TJK", "TJ", "Tajikistan"),
+    country("TZA", "TZ", "Tanzania"),
+    country("THA", "TH", "Thailand"),
+    country("TLS", "TL", "Timor-Leste"),
+    country("TGO", "TG", "Togo"),
+    country("TKL", "TK", "Tokelau"),
+    country("TON", "TO", "Tonga"),
+    country("TTO", "TT", "Trinidad and Tobago"),
+    country("TUN", "TN", "Tunisia"),
+    country("TUR", "TR", "Türkiye"),
+    country("TKM", "TM", "Turkmenistan"),
+    country("TCA", "TC", "Turks and Caicos Islands"),
+    country("TUV", "TV", "Tuvalu"),
+    country("UGA", "UG", "Uganda"),
+    country("UKR", "UA", "Ukraine"),
+    country("ARE", "AE", "United Arab Emirates"),
+    country("GBR", "GB", "United Kingdom"),
+    country("USA", "US", "United States"),
+    country("UMI", "UM", "United States Minor Outlying Islands"),
+    country("URY", "UY", "Uruguay"),
+    country("UZB", "UZ", "Uzbekistan"),
+    country("VUT", "VU", "Vanuatu"),
+    country("VEN", "VE", "Venezuela"),
+    country("VNM", "VN", "Viet Nam"),
+    country("VGB", "VG", "Virgin Islands (British)"),
+    country("VIR", "VI", "Virgin Islands (U.S.)"),
+    country("WLF", "WF", "Wallis and Futuna"),
+    country("ESH", "EH", "Western Sahara"),
+    country("YEM", "YE", "Yemen"),
+    country("ZMB", "ZM", "Zambia"),
+    country("ZWE", "ZW", "Zimbabwe"),
+    aggregate("AFE", "ZH", "Africa Eastern and Southern"),
+    aggregate("AFW", "ZI", "Africa Western and Central"),
+    aggregate("ARB", "1A", "Arab World"),
+    aggregate("CSS", "S3", "Caribbean small states"),
+    aggregate("CEB", "B8", "Central Europe and the Baltics"),
+    aggregate("EAR", "V2", "Early-demographic dividend"),
+    aggregate("EAS", "Z4", "East Asia & Pacific"),
+    aggregate("EAP", "4E", "East Asia & Pacific (excluding high income)"),
+    aggregate("TEA", "T4", "East Asia & Pacific (IDA & IBRD countries)"),
+    aggregate("EMU", "XC", "Euro area"),
+    aggregate("ECS", "Z7", "Europe & Central Asia"),
+    aggregate("ECA", "7E", "Europe & Central Asia (excluding high income)"),
+    aggregate("TEC", "T7", "Europe & Central Asia (IDA & IBRD countries)"),
+    aggregate("EUU", "EU", "European Union"),
+    aggregate("FCS", "F1", "Fragile and conflict affected situations"),
+    aggregate("HPC", "XE", "Heavily indebted poor countries (HIPC)"),
+    aggregate("HIC", "XD", "High income"),
+    aggregate("IBD", "XF", "IBRD only"),
+    aggregate("IBT", "ZT", "IDA & IBRD total"),
+    aggregate("IDB", "XH", "IDA blend"),
+    aggregate("IDX", "XI", "IDA only"),
+    aggregate("IDA", "XG", "IDA total"),
+    aggregate("LTE", "V3", "Late-demographic dividend"),
+    aggregate("LCN", "ZJ", "Latin America & Caribbean"),
+    aggregate(
+        "LAC",
+        "XJ",
+        "Latin America & Caribbean (excluding high income)",
+    ),
+    aggregate(
+        "TLA",
+        "T2",
+        "Latin America & the Caribbean (IDA & IBRD countries)",
+    ),
+    aggregate("LDC", "XL", "Least developed countries: UN classification"),
+    aggregate("LIC", "XM", "Low income"),
+    aggregate("LMY", "XO", "Low & middle income"),
+    aggregate("LMC", "XN", "Lower middle income"),
+    aggregate("MEA", "ZQ", "Middle East & North Africa"),
+    aggregate(
+        "MNA",
+        "XQ",
+        "Middle East & North Africa (excluding high income)",
+    ),
+    aggregate(
+        "TMN",
+        "T3",
+        "Middle East & North Africa (IDA & IBRD countries)",
+    ),
+    aggregate("MIC", "XP", "Middle income"),
+    aggregate("NAC", "XU", "North America"),
+    aggregate("INX", "XY", "Not classified"),
+    aggregate("OED", "OE", "OECD members"),
+    aggregate("OSS", "S4", "Other small states"),
+    aggregate("PSS", "S2", "Pacific island small states"),
+    aggregate("PST", "V4", "Post-demographic dividend"),
+    aggregate("PRE", "V1", "Pre-demographic dividend"),
+    aggregate("SST", "S1", "Small states"),
+    aggregate("SAS", "8S", "South Asia"),
+    aggregate("TSA", "T5", "South Asia (IDA & IBRD)"),
+    aggregate("SSF", "ZG", "Sub-Saharan Africa"),
+    aggregate("SSA", "ZF", "Sub-Saharan Africa (excluding high income)"),
+    aggregate("TSS", "T6", "Sub-Saharan Africa (IDA & IBRD countries)"),
+    aggregate("UMC", "XT", "Upper middle income"),
+    aggregate("WLD", "1W", "World"),
+];
+
+/// The economy whose ISO3 or ISO2 code is `code` (case-insensitive).
+pub fn find_country(code: &str) -> Option<&'static Economy> {
+    let code = code.trim();
+    ECONOMIES
+        .iter()
+        .find(|e| e.iso3.eq_ignore_ascii_case(code) || e.iso2.eq_ignore_ascii_case(code))
+}
+
+/// Up to three likely meant economies for an unknown `code`: codes one edit or swap away
+/// (ISO2 codes only for two-letter input), then names starting with it (`germ` → `DEU`).
+pub fn suggest_countries(code: &str) -> Vec<&'static Economy> {
+    let code = code.trim().to_ascii_uppercase();
+    let mut hits: Vec<&'static Economy> = ECONOMIES
+        .iter()
+        .filter(|e| {
+            let candidate = if code.len() == 2 { e.iso2 } else { e.iso3 };
+            edit_distance(&code, candidate) <= 1
+        })
+        .collect();
+    if code.len() >= 3 {
+        let lower = code.to_lowercase();
+        let by_name: Vec<&'static Economy> = ECONOMIES
+            .iter()
+            .filter(|e| e.name.to_lowercase().starts_with(&lower) && !hits.contains(e))
+            .collect();
+        hits.extend(by_name);
+    }
+    hits.truncate(3);
+    hits
+}
+
+/// Built-in country sets by upper-case name, with ISO3 member codes. Maintained in
+/// `assets/country_groups.toml`, which documents what each set includes.
+pub fn country_groups() -> &'static BTreeMap<String, Vec<String>> {
+    static GROUPS: OnceLock<BTreeMap<String, Vec<String>>> = OnceLock::new();
+    GROUPS.get_or_init(|| {
+        toml::from_str(include_str!("../assets/country_groups.toml"))
+            .expect("assets/country_groups.toml is valid")
+    })
+}
+
+/// Members of the built-in country set `name` (case-insensitive), e.g. `G7` or `eu27`.
+pub fn country_group(name: &str) -> Option<&'static [String]> {
+    country_groups()
+        .get(&name.trim().to_ascii_uppercase())
+        .map(Vec::as_slice)
+}
+
+/// Whether `id` is shaped like an indicator code (`SP.POP.TOTL`, `per_si_allsi.cov_pop_tot`):
+/// ASCII letters, digits, `.`, `_` and `-`, starting with a letter or digit.
+pub fn is_indicator_id(id: &str) -> bool {
+    id.chars().next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Edit distance counting insertions, deletions, substitutions and swaps of adjacent
+/// characters (optimal string alignment).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
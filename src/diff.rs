@@ -0,0 +1,94 @@
+/// Compare two snapshots of the same query, e.g. to spot value revisions between fetches.
+///
+/// Observations are matched on `(indicator_id, country_iso3, year)`. A matched pair counts
+/// as **changed** when its value differs (a value appearing or disappearing included);
+/// names, units and flags are not compared.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::{diff, storage};
+/// let old = storage::load("pop_2024.csv")?;
+/// let new = storage::load("pop_2025.csv")?;
+/// let d = diff::diff(&old, &new);
+/// println!("{} revised values", d.changed.len());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+use crate::models::{self, ConflictPolicy, DataPoint, same_value};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Key that identifies one observation across snapshots.
+type ObsKey<'a> = (&'a str, &'a str, i32);
+
+/// A matched observation whose value differs between the snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub old: DataPoint,
+    pub new: DataPoint,
+}
+
+/// Differences from an old to a new snapshot; each list is sorted by
+/// `(indicator_id, country_iso3, year)`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SnapshotDiff {
+    /// Observations only in the new snapshot.
+    pub added: Vec<DataPoint>,
+    /// Observations only in the old snapshot.
+    pub removed: Vec<DataPoint>,
+    /// Observations in both whose value differs.
+    pub changed: Vec<Change>,
+}
+
+impl SnapshotDiff {
+    /// `true` when the snapshots hold the same observations with the same values.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare `old` with `new`. If a snapshot repeats a key, its last row wins.
+pub fn diff(old: &[DataPoint], new: &[DataPoint]) -> SnapshotDiff {
+    let old = index(old);
+    let new = index(new);
+
+    let mut out = SnapshotDiff::default();
+    for (key, o) in &old {
+        match new.get(key) {
+            None => out.removed.push((*o).clone()),
+            Some(n) if !same_value(o.value, n.value) => out.changed.push(Change {
+                old: (*o).clone(),
+                new: (*n).clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    out.added = new
+        .iter()
+        .filter(|(key, _)| !old.contains_key(*key))
+        .map(|(_, n)| (*n).clone())
+        .collect();
+    out
+}
+
+/// Overlay `update` on `base`: rows of `update` replace rows of `base` with the same
+/// `(indicator_id, country_iso3, year)`, other rows of both are kept. Sorted by that key.
+///
+/// This is how an incremental refresh folds a fetch of recent years into a saved snapshot;
+/// see [`crate::models::merge`] for more datasets, other policies and conflict reporting.
+pub fn merge(base: &[DataPoint], update: &[DataPoint]) -> Vec<DataPoint> {
+    models::merge([base, update], ConflictPolicy::KeepLast)
+        .map(|merged| merged.dataset.0)
+        .expect("KeepLast never fails")
+}
+
+fn index(points: &[DataPoint]) -> BTreeMap<ObsKey<'_>, &DataPoint> {
+    points
+        .iter()
+        .map(|p| {
+            (
+                (p.indicator_id.as_str(), p.country_iso3.as_str(), p.year),
+                p,
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,111 @@
+/// Append-only log of API calls, one JSON object per line, for reproducing where a dataset
+/// came from.
+///
+/// Attach a log to the client with [`Client::with_fetch_log`](crate::Client::with_fetch_log);
+/// every request then appends a [`FetchRecord`] with its URL, time, HTTP status and row count,
+/// including responses served from the [`HttpCache`](crate::cache::HttpCache) and requests
+/// that failed.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::{Client, fetch_log::FetchLog};
+/// let client = Client::default().with_fetch_log(FetchLog::new("fetch.log.jsonl"));
+/// // ... fetch ...
+/// for record in FetchLog::new("fetch.log.jsonl").read()? {
+///     println!("{} {:?} {}", record.timestamp, record.status, record.url);
+/// }
+/// # let _ = client;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// One API call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FetchRecord {
+    pub url: String,
+    /// When the call finished.
+    pub timestamp: DateTime<Utc>,
+    /// HTTP status of the final attempt; `None` when served from the cache or when no
+    /// response arrived.
+    pub status: Option<u16>,
+    /// Records in the response (observations, or indicators for metadata requests).
+    pub rows: Option<usize>,
+    /// Served from the response cache without a request.
+    #[serde(default)]
+    pub cached: bool,
+    /// Why the call failed, when no response arrived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FetchRecord {
+    /// Whether the call produced a usable response.
+    pub fn ok(&self) -> bool {
+        self.cached || self.status.is_some_and(|s| (200..300).contains(&s))
+    }
+}
+
+/// A fetch log file. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct FetchLog {
+    path: PathBuf,
+}
+
+impl FetchLog {
+    /// File name used in the cache or report directory.
+    pub const FILE_NAME: &str = "fetch.log.jsonl";
+
+    /// Log appending to `path` (created with its directory on first write).
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// [`Self::FILE_NAME`] in [`HttpCache::default_dir`](crate::cache::HttpCache::default_dir).
+    pub fn default_path() -> Option<PathBuf> {
+        Some(crate::cache::HttpCache::default_dir()?.join(Self::FILE_NAME))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `record` as one line.
+    pub fn append(&self, record: &FetchRecord) -> Result<()> {
+        if let Some(dir) = self.path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("creating log dir {}", dir.display()))?;
+        }
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut f| f.write_all(line.as_bytes()))
+            .with_context(|| format!("appending to {}", self.path.display()))
+    }
+
+    /// All records, oldest first. A missing file is an empty log; a malformed line is an
+    /// error naming its line number.
+    pub fn read(&self) -> Result<Vec<FetchRecord>> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", self.path.display())),
+        };
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("{}:{}: invalid record", self.path.display(), i + 1))
+            })
+            .collect()
+    }
+}
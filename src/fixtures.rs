@@ -0,0 +1,136 @@
+/// API responses saved as JSON files, for tests that run without the network.
+///
+/// Attach a fixture directory to the client with
+/// [`Client::with_fixtures`](crate::Client::with_fixtures). In [`FixtureMode::Record`] every
+/// response is also written to the directory; in [`FixtureMode::Replay`] requests are answered
+/// from it only, and a request without a fixture fails instead of going online. `wbi record`
+/// records the responses of one query.
+///
+/// Fixtures are keyed by the URL relative to the client's `base_url`, so they replay against
+/// any host, and sanitized: the volatile `lastupdated` dates are dropped and the JSON is
+/// pretty-printed, so re-recording only shows real changes in a diff.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::{Client, DateSpec, fixtures::{FixtureMode, Fixtures}};
+/// let client = Client::default()
+///     .with_fixtures(Fixtures::new("tests/fixtures"), FixtureMode::Replay);
+/// let rows = client.fetch(&["DEU".into()], &["SP.POP.TOTL".into()], Some(DateSpec::Year(2020)), None)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Longest file name stem taken from the URL; longer ones are cut and get a hash.
+const MAX_STEM_LEN: usize = 96;
+
+/// Whether a client writes fixtures or answers from them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Fetch as usual and save every response.
+    Record,
+    /// Serve responses from the fixtures only; never go online.
+    Replay,
+}
+
+/// A directory of fixtures. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct Fixtures {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Fixture {
+    url: String,
+    body: Value,
+}
+
+impl Fixtures {
+    /// Fixtures in `dir`, created on the first recording.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The recorded body for `url` (relative to the API base URL).
+    pub fn get(&self, url: &str) -> Result<Value> {
+        let path = self.path(url);
+        let text = std::fs::read_to_string(&path).map_err(|_| {
+            anyhow!(
+                "no fixture for {url} in {}; record it with `wbi record`",
+                self.dir.display()
+            )
+        })?;
+        let fixture: Fixture = serde_json::from_str(&text)
+            .with_context(|| format!("parsing fixture {}", path.display()))?;
+        if fixture.url != url {
+            return Err(anyhow!(
+                "fixture {} is for {}, not {url}",
+                path.display(),
+                fixture.url
+            ));
+        }
+        Ok(fixture.body)
+    }
+
+    /// Save the sanitized `body` as the response for `url` (relative to the API base URL) and
+    /// return the file written.
+    pub fn put(&self, url: &str, body: &Value) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating fixture dir {}", self.dir.display()))?;
+        let mut body = body.clone();
+        sanitize(&mut body);
+        let fixture = Fixture {
+            url: url.to_string(),
+            body,
+        };
+        let path = self.path(url);
+        let mut text = serde_json::to_string_pretty(&fixture)?;
+        text.push('\n');
+        std::fs::write(&path, text).with_context(|| format!("writing {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// A readable file name for `url`: its path and query with every other character than
+    /// letters, digits, `.`, `-` and `_` replaced by `_`.
+    fn path(&self, url: &str) -> PathBuf {
+        let stem: String = url
+            .trim_start_matches('/')
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let name = if stem.len() > MAX_STEM_LEN {
+            format!(
+                "{}-{:016x}.json",
+                &stem[..MAX_STEM_LEN],
+                crate::cache::fnv1a(url.as_bytes())
+            )
+        } else {
+            format!("{stem}.json")
+        };
+        self.dir.join(name)
+    }
+}
+
+/// Drop the `lastupdated` dates the API stamps on responses, at any depth.
+fn sanitize(body: &mut Value) {
+    match body {
+        Value::Object(map) => {
+            map.remove("lastupdated");
+            map.values_mut().for_each(sanitize);
+        }
+        Value::Array(items) => items.iter_mut().for_each(sanitize),
+        _ => {}
+    }
+}
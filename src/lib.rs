@@ -5,13 +5,35 @@
 //! data.
 //!
 //! ## Highlights
-//! - Synchronous API client (`api::Client`)
+//! - Synchronous API client (`api::Client`) and an async one (`api::AsyncClient`)
 //! - Tidy data model (`models::DataPoint`)
-//! - Summary stats (`stats::grouped_summary`)
+//! - Summary stats (`stats::grouped_summary`) and a data quality score per series (`stats::quality`)
+//! - Offline country code list with typo suggestions (`codes`)
 //! - CSV/JSON export (`storage`)
+//! - Snapshot comparison for value revisions (`diff`) and merging of overlapping fetches with
+//!   conflict reporting (`models::merge`, `models::align_years`)
+//! - A JSON-lines log of every API call for reproducibility (`fetch_log`)
+//! - Recorded API responses replayed offline in tests (`fixtures`)
+//! - Other data sources behind one `DataProvider` trait, e.g. SDMX-CSV files from the OECD or
+//!   IMF (`provider`)
+//! - Locale-aware number formatting shared by CLI and charts (`util::format`)
 //! - SVG/PNG charts (`viz`) with legend placement, locale formatting, and multiple plot types
+//! - Chart comparison with a similarity score and diff image (`viz::chart_diff`)
+//! - The common types in one import (`use wbi_rs::prelude::*`)
 //!
 //! ## Feature flags
+//! - `blocking` (default): the synchronous `Client` and the `wbi` binary. Without it the
+//!   crate builds for `wasm32-unknown-unknown`, with `AsyncClient` for fetching.
+//! - `evcxr`: inline HTML tables and SVG charts in Rust Jupyter notebooks (`notebook`).
+//! - `arrow`: observations as an Arrow `RecordBatch` (`storage::to_record_batch`), e.g. for
+//!   Polars or other Arrow-based tools.
+//! - `sqlite`: a SQLite store that keeps every fetched revision of a value
+//!   (`storage::save_sqlite`, `storage::revisions`).
+//! - `datafusion`: SQL queries over observations or the SQLite store in-process, through a
+//!   DataFusion `TableProvider` (`sql::WbiTable`).
+//! - `ndarray`: observations as a dense matrix for numerical work (`stats::to_ndarray`).
+//! - `metrics`: request, retry, latency, cache and row counts via the `metrics` facade
+//!   (`telemetry`).
 //! - `online`: enables live API tests/examples. (The library itself works without it.)
 //!
 //! Country-consistent styling is available as  option via `viz::plot_chart(.., Some(true))`.
@@ -53,15 +75,33 @@
 //! ```
 
 pub mod api;
+pub mod cache;
+pub mod codes;
+pub mod diff;
+pub mod fetch_log;
+pub mod fixtures;
 pub mod models;
+#[cfg(feature = "evcxr")]
+pub mod notebook;
+pub mod prelude;
+pub mod provider;
+pub mod report;
+#[cfg(feature = "datafusion")]
+pub mod sql;
 pub mod stats;
 pub mod storage;
+pub mod telemetry;
+pub mod util;
 pub mod viz;
+#[deprecated(note = "moved to `wbi_rs::viz::plotters_adapter`")]
 pub mod viz_plotters_adapter;
+#[deprecated(note = "use `wbi_rs::viz::style` (`SeriesStyle` is `viz::style::DrawStyle` there)")]
 pub mod viz_style;
 
-// Country-consistent styling module (always available)
-pub mod style;
+// Country-consistent styling, kept at the crate root for compatibility.
+pub use viz::style;
 
+#[cfg(feature = "blocking")]
 pub use api::Client;
-pub use models::{DataPoint, DateSpec, GroupKey};
+pub use api::{ApiError, AsyncClient, FetchProgress};
+pub use models::{DataPoint, Dataset, DateSpec, GroupKey, Period};
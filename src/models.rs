@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Data model and (de)serialization helpers for World Bank API responses.
 ///
@@ -9,11 +10,14 @@ use serde::{Deserialize, Serialize};
 ///
 /// * `Year(y)` becomes `"YYYY"`
 /// * `Range { start, end }` becomes `"YYYY:YYYY"`
+/// * `From`, `Until` and `Last` become ranges against the current year
+/// * `MostRecent(n)` becomes `mrv=n` instead of a `date`
 #[doc = "Convert to API query string (e.g., `2010:2020`)."]
 /// ```
 /// use wbi_rs::models::DateSpec;
 /// assert_eq!(DateSpec::Year(2020).to_query_param(), "2020");
 /// assert_eq!(DateSpec::Range{start: 2010, end: 2020}.to_query_param(), "2010:2020");
+/// assert_eq!("mrv:5".parse::<DateSpec>().unwrap(), DateSpec::MostRecent(5));
 /// ```
 ///
 /// Metadata returned in position **0** of the API response.
@@ -42,13 +46,206 @@ pub enum DateSpec {
     Year(i32),
     /// Inclusive range like 2000..=2020
     Range { start: i32, end: i32 },
+    /// From a year up to the current year (`2015:`)
+    From(i32),
+    /// From [`DateSpec::FIRST_YEAR`] up to a year (`:2020`)
+    Until(i32),
+    /// The last `n` calendar years, the current one included (`last:10`)
+    Last(u32),
+    /// The `n` most recent values of each series, whichever years they fall in (`mrv:5`)
+    MostRecent(u32),
 }
 
 impl DateSpec {
+    /// First year of the World Bank series; the start of an open `:YYYY` range.
+    pub const FIRST_YEAR: i32 = 1960;
+
+    /// Turn the relative forms into a `Range` as of `current_year`; other forms are returned
+    /// unchanged.
+    pub fn resolve(self, current_year: i32) -> DateSpec {
+        match self {
+            DateSpec::From(start) => DateSpec::Range {
+                start,
+                end: current_year,
+            },
+            DateSpec::Until(end) => DateSpec::Range {
+                start: Self::FIRST_YEAR,
+                end,
+            },
+            DateSpec::Last(n) => DateSpec::Range {
+                start: current_year
+                    .saturating_sub(i32::try_from(n.saturating_sub(1)).unwrap_or(i32::MAX)),
+                end: current_year,
+            },
+            other => other,
+        }
+    }
+
+    /// Name of the query parameter: `mrv` for [`DateSpec::MostRecent`], else `date`.
+    pub fn query_key(&self) -> &'static str {
+        match self {
+            DateSpec::MostRecent(_) => "mrv",
+            _ => "date",
+        }
+    }
+
+    /// Value of the [`query_key`](Self::query_key) parameter; relative forms are resolved
+    /// against the current year.
     pub fn to_query_param(&self) -> String {
-        match *self {
+        use chrono::Datelike;
+        match self.resolve(chrono::Utc::now().year()) {
             DateSpec::Year(y) => y.to_string(),
             DateSpec::Range { start, end } => format!("{}:{}", start, end),
+            DateSpec::MostRecent(n) => n.to_string(),
+            open => unreachable!("resolve() leaves no open range: {open:?}"),
+        }
+    }
+}
+
+impl std::str::FromStr for DateSpec {
+    type Err = anyhow::Error;
+
+    /// Parse `YYYY`, `YYYY:YYYY`, `YYYY:`, `:YYYY`, `last:N` or `mrv:N`.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        const FORMS: &str = "expected YYYY, YYYY:YYYY, YYYY: (to this year), :YYYY (from 1960), \
+                             last:N (last N years) or mrv:N (N most recent values)";
+        let s = s.trim();
+        let year = |t: &str| {
+            t.trim()
+                .parse::<i32>()
+                .map_err(|_| anyhow::anyhow!("invalid year '{t}' in date '{s}'; {FORMS}"))
+        };
+        let count = |t: &str| match t.trim().parse::<u32>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(anyhow::anyhow!(
+                "invalid count '{t}' in date '{s}', expected a whole number of at least 1"
+            )),
+        };
+        let Some((a, b)) = s.split_once(':') else {
+            return year(s).map(DateSpec::Year);
+        };
+        match (a.trim().to_ascii_lowercase().as_str(), b.trim()) {
+            ("last", n) => count(n).map(DateSpec::Last),
+            ("mrv", n) => count(n).map(DateSpec::MostRecent),
+            ("", "") => anyhow::bail!("empty date range '{s}'; {FORMS}"),
+            ("", end) => year(end).map(DateSpec::Until),
+            (start, "") => year(start).map(DateSpec::From),
+            (start, end) => {
+                let (start, end) = (year(start)?, year(end)?);
+                if start > end {
+                    anyhow::bail!("date range '{s}' starts after it ends");
+                }
+                Ok(DateSpec::Range { start, end })
+            }
+        }
+    }
+}
+
+/// The period an observation covers, as written in the API's `date` field: a year (`2020`),
+/// a quarter (`2020Q1`) or a month (`2020M03`).
+///
+/// Periods order chronologically by their start; a year sorts before the quarters and months
+/// that start with it.
+///
+/// ```
+/// use wbi_rs::models::Period;
+/// let q: Period = "2020Q2".parse().unwrap();
+/// assert_eq!(q, Period::Quarter(2020, 2));
+/// assert_eq!(q.year(), 2020);
+/// assert_eq!(q.to_string(), "2020Q2");
+/// assert!(Period::Year(2020) < q && q < Period::Month(2020, 5));
+/// ```
+///
+/// Quarters and months outside 1–4 and 1–12 (only possible when built directly, not parsed)
+/// are clamped into range for ordering and [`Period::as_f64`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Period {
+    Year(i32),
+    /// Year and quarter (1–4).
+    Quarter(i32, u8),
+    /// Year and month (1–12).
+    Month(i32, u8),
+}
+
+impl Period {
+    /// Calendar year the period falls in.
+    pub fn year(self) -> i32 {
+        match self {
+            Period::Year(y) | Period::Quarter(y, _) | Period::Month(y, _) => y,
+        }
+    }
+
+    /// First month of the period (1–12).
+    fn first_month(self) -> u8 {
+        match self {
+            Period::Year(_) => 1,
+            Period::Quarter(_, q) => q.clamp(1, 4) * 3 - 2,
+            Period::Month(_, m) => m.clamp(1, 12),
+        }
+    }
+
+    /// Start of the period in fractional years (`2020Q2` → 2020.25), for a time axis.
+    pub fn as_f64(self) -> f64 {
+        self.year() as f64 + (self.first_month() - 1) as f64 / 12.0
+    }
+
+    fn sort_key(self) -> (i32, u8, u8) {
+        let rank = match self {
+            Period::Year(_) => 0,
+            Period::Quarter(..) => 1,
+            Period::Month(..) => 2,
+        };
+        (self.year(), self.first_month(), rank)
+    }
+}
+
+impl From<i32> for Period {
+    fn from(year: i32) -> Self {
+        Period::Year(year)
+    }
+}
+
+impl Ord for Period {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for Period {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Period::Year(y) => write!(f, "{y}"),
+            Period::Quarter(y, q) => write!(f, "{y}Q{q}"),
+            Period::Month(y, m) => write!(f, "{y}M{m:02}"),
+        }
+    }
+}
+
+impl std::str::FromStr for Period {
+    type Err = anyhow::Error;
+
+    /// Parse `YYYY`, `YYYYQn` or `YYYYMnn`.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let s = s.trim();
+        let invalid = || anyhow::anyhow!("invalid period '{s}', expected YYYY, YYYYQn or YYYYMnn");
+        let split = s.find(['Q', 'q', 'M', 'm']);
+        let year: i32 = s[..split.unwrap_or(s.len())]
+            .parse()
+            .map_err(|_| invalid())?;
+        let Some(at) = split else {
+            return Ok(Period::Year(year));
+        };
+        let n: u8 = s[at + 1..].parse().map_err(|_| invalid())?;
+        match s.as_bytes()[at].to_ascii_uppercase() {
+            b'Q' if (1..=4).contains(&n) => Ok(Period::Quarter(year, n)),
+            b'M' if (1..=12).contains(&n) => Ok(Period::Month(year, n)),
+            _ => Err(invalid()),
         }
     }
 }
@@ -108,6 +305,104 @@ where
     deserializer.deserialize_any(U32Visitor)
 }
 
+/// Text the API and spreadsheet exports use for a missing number.
+fn is_missing_number(s: &str) -> bool {
+    s.is_empty() || s == ".." || s.eq_ignore_ascii_case("null")
+}
+
+/// Serde helper: an optional `f64` from a JSON number, a string such as `"1.5"` or
+/// `"3.2E+09"`, or null. Empty strings, `".."` and `"null"` are `None`, as are non-finite
+/// values, which JSON cannot carry anyway.
+fn de_opt_f64_lenient<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::{self, Visitor};
+    struct F64Visitor;
+
+    impl<'de> Visitor<'de> for F64Visitor {
+        type Value = Option<f64>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "a number, a string holding a number, or null")
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(Some(v).filter(|v| v.is_finite()))
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(Some(v as f64))
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(Some(v as f64))
+        }
+
+        fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            let s = s.trim();
+            if is_missing_number(s) {
+                return Ok(None);
+            }
+            let v: f64 = s
+                .parse()
+                .map_err(|_| E::custom(format!("invalid number {s:?}")))?;
+            Ok(Some(v).filter(|v| v.is_finite()))
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    deserializer.deserialize_any(F64Visitor)
+}
+
+/// Serde helper: an optional `i32` from a JSON number (`1` or `1.0`), a string holding one,
+/// or null; missing-number strings as in [`de_opt_f64_lenient`] are `None`.
+fn de_opt_i32_lenient<'de, D>(deserializer: D) -> Result<Option<i32>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    let Some(v) = de_opt_f64_lenient(deserializer)? else {
+        return Ok(None);
+    };
+    if v.fract() != 0.0 || v < f64::from(i32::MIN) || v > f64::from(i32::MAX) {
+        return Err(D::Error::custom(format!("{v} is not a whole number")));
+    }
+    Ok(Some(v as i32))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeName {
     pub id: String,
@@ -122,6 +417,33 @@ pub struct IndicatorMeta {
     #[serde(alias = "value")]
     pub name: String,
     pub unit: Option<String>,
+    /// The API's description of the indicator and its methodology.
+    #[serde(
+        default,
+        rename = "sourceNote",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub source_note: Option<String>,
+    /// Organization that compiles the indicator.
+    #[serde(
+        default,
+        rename = "sourceOrganization",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub source_organization: Option<String>,
+}
+
+/// Notes on one indicator for one country from the World Bank metadata API, see
+/// [`Client::fetch_series_country_metadata`](crate::Client::fetch_series_country_metadata).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeriesCountryMeta {
+    pub indicator_id: String,
+    pub country_id: String,
+    /// How the indicator is compiled ("Statistical concept and methodology"); the same for
+    /// every country.
+    pub methodology: Option<String>,
+    /// Country-specific footnotes on the series, such as its data sources or breaks in it.
+    pub notes: Vec<String>,
 }
 
 /// Raw entry from the API (position 1 array).
@@ -131,10 +453,14 @@ pub struct Entry {
     pub country: CodeName,
     pub countryiso3code: String,
     pub date: String,
+    /// A number, or a string holding one (`"1.5"`, `"3.2E+09"`); null or `""` when missing.
+    #[serde(default, deserialize_with = "de_opt_f64_lenient")]
     pub value: Option<f64>,
     pub unit: Option<String>,
     #[serde(rename = "obs_status")]
     pub obs_status: Option<String>,
+    /// Read like `value`; `1.0` counts as `1`.
+    #[serde(default, deserialize_with = "de_opt_i32_lenient")]
     pub decimal: Option<i32>,
 }
 
@@ -147,9 +473,12 @@ pub struct DataPoint {
     pub country_name: String,
     pub country_iso3: String,
     pub year: i32,
+    /// Read leniently like [`Entry::value`], so files written by other tools load too.
+    #[serde(default, deserialize_with = "de_opt_f64_lenient")]
     pub value: Option<f64>,
     pub unit: Option<String>,
     pub obs_status: Option<String>,
+    #[serde(default, deserialize_with = "de_opt_i32_lenient")]
     pub decimal: Option<i32>,
 }
 
@@ -171,9 +500,311 @@ impl From<Entry> for DataPoint {
     }
 }
 
-/// Grouping key used in stats and plotting.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Key of one series: `(indicator_id, country_iso3)`, plus optional context.
+///
+/// `region` and `unit` are optional context: [`crate::viz::group_series`] fills `unit` from the
+/// observations, `region` is left for callers that know it, and the stats summaries leave
+/// both empty. Build keys with [`GroupKey::new`]; more context may be added later.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub struct GroupKey {
     pub indicator_id: String,
     pub country_iso3: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+impl GroupKey {
+    /// Key for one (indicator, country) series without region or unit.
+    pub fn new(indicator_id: impl Into<String>, country_iso3: impl Into<String>) -> Self {
+        Self {
+            indicator_id: indicator_id.into(),
+            country_iso3: country_iso3.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Canonical category of a unit string, see [`Unit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitKind {
+    /// Money amounts: `current US$`, `constant 2015 LCU`, `PPP (current international $)`.
+    Currency,
+    /// Shares and growth rates: `% of GDP`, `annual %`, `percent`.
+    Percent,
+    /// Headcounts and other counts: `people`, `number`.
+    Count,
+    /// Index numbers: `2010 = 100`, `index`.
+    Index,
+    /// Anything else, including rates such as `per 1,000 people`.
+    Other,
+}
+
+/// A unit as reported by the API (or taken from an indicator name), with its category.
+///
+/// The API's units are free text; [`Unit::parse`] sorts them into a [`UnitKind`] so callers
+/// can decide e.g. whether values may be shown in thousands or millions.
+///
+/// ```
+/// use wbi_rs::models::{Unit, UnitKind};
+/// assert_eq!(Unit::parse("current US$").kind, UnitKind::Currency);
+/// assert_eq!(Unit::parse("% of GDP").kind, UnitKind::Percent);
+/// assert_eq!(Unit::parse("people").kind, UnitKind::Count);
+/// assert!(!Unit::parse("annual %").is_scalable());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Unit {
+    pub kind: UnitKind,
+    /// The unit text as given, trimmed.
+    pub raw: String,
+}
+
+impl Unit {
+    /// Categorize `raw`; percent wins over currency (`% of GDP`), rates (`per 1,000 people`)
+    /// are [`UnitKind::Other`].
+    pub fn parse(raw: &str) -> Self {
+        let raw = raw.trim();
+        let u = raw.to_ascii_lowercase();
+        let has_word = |w: &str| {
+            u.split(|c: char| !c.is_ascii_alphanumeric())
+                .any(|t| t == w)
+        };
+        let kind = if u.contains('%') || u.contains("percent") || u.contains("per cent") {
+            UnitKind::Percent
+        } else if u.contains("= 100") || u.contains("=100") || has_word("index") {
+            UnitKind::Index
+        } else if u.contains('$')
+            || u.contains('€')
+            || has_word("lcu")
+            || has_word("ppp")
+            || has_word("usd")
+            || has_word("eur")
+            || has_word("dollars")
+        {
+            UnitKind::Currency
+        } else if u.starts_with("per ") || u.contains(" per 1") {
+            UnitKind::Other
+        } else if ["people", "persons", "number", "count"]
+            .iter()
+            .any(|w| has_word(w))
+        {
+            UnitKind::Count
+        } else {
+            UnitKind::Other
+        };
+        Unit {
+            kind,
+            raw: raw.to_string(),
+        }
+    }
+
+    /// Whether values in this unit may be shown scaled (thousands, millions, …); percentages
+    /// and index numbers are not.
+    pub fn is_scalable(&self) -> bool {
+        !matches!(self.kind, UnitKind::Percent | UnitKind::Index)
+    }
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// The observations of one or more fetches as a single value, e.g. for notebook display
+/// (feature `evcxr`). Derefs to `Vec<DataPoint>`, so functions taking `&[DataPoint]` accept
+/// `&dataset` directly.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Dataset(pub Vec<DataPoint>);
+
+impl From<Vec<DataPoint>> for Dataset {
+    fn from(points: Vec<DataPoint>) -> Self {
+        Self(points)
+    }
+}
+
+impl From<Dataset> for Vec<DataPoint> {
+    fn from(dataset: Dataset) -> Self {
+        dataset.0
+    }
+}
+
+impl FromIterator<DataPoint> for Dataset {
+    fn from_iter<I: IntoIterator<Item = DataPoint>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl std::ops::Deref for Dataset {
+    type Target = Vec<DataPoint>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for Dataset {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// What [`merge`] does when datasets disagree on the value of an observation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    /// Keep the row from the earliest dataset.
+    KeepFirst,
+    /// Keep the row from the latest dataset, e.g. a newer fetch over an older one.
+    #[default]
+    KeepLast,
+    /// Fail on the first disagreement.
+    Error,
+}
+
+/// Rows of one `(indicator_id, country_iso3, year)` whose values differ between the merged
+/// datasets.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Conflict {
+    /// The row in the merged dataset.
+    pub kept: DataPoint,
+    /// The rows it won over whose value differs, in input order.
+    pub dropped: Vec<DataPoint>,
+}
+
+/// Result of [`merge`]: the combined observations and where the inputs disagreed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Merged {
+    /// One row per `(indicator_id, country_iso3, year)`, sorted by that key.
+    pub dataset: Dataset,
+    /// Observations with differing values, sorted like `dataset`.
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Combine several fetches (different runs, different providers) into one dataset with one
+/// row per `(indicator_id, country_iso3, year)`.
+///
+/// Rows with the same key and the same value (two missing values included) are duplicates
+/// and collapse silently; differing values, a value against a missing one included, are
+/// resolved by `policy` and reported in [`Merged::conflicts`]. Names, units and flags are
+/// taken from the kept row and not compared. A key repeated within one dataset is treated
+/// like one repeated across datasets.
+///
+/// ```
+/// use wbi_rs::models::{ConflictPolicy, DataPoint, merge};
+/// let row = |year, value| DataPoint {
+///     indicator_id: "SP.POP.TOTL".into(),
+///     indicator_name: "Population, total".into(),
+///     country_id: "DE".into(),
+///     country_name: "Germany".into(),
+///     country_iso3: "DEU".into(),
+///     year,
+///     value: Some(value),
+///     unit: None,
+///     obs_status: None,
+///     decimal: None,
+/// };
+/// let saved = vec![row(2020, 83.1e6), row(2021, 83.2e6)];
+/// let fetched = vec![row(2021, 83.3e6), row(2022, 83.8e6)];
+/// let merged = merge([&saved[..], &fetched[..]], ConflictPolicy::KeepLast)?;
+/// assert_eq!(merged.dataset.len(), 3);
+/// assert_eq!(merged.conflicts[0].kept.value, Some(83.3e6));
+/// assert!(merge([&saved[..], &fetched[..]], ConflictPolicy::Error).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn merge<'a, I>(datasets: I, policy: ConflictPolicy) -> anyhow::Result<Merged>
+where
+    I: IntoIterator<Item = &'a [DataPoint]>,
+{
+    type Key<'k> = (&'k str, &'k str, i32);
+    let mut rows: BTreeMap<Key<'a>, (&'a DataPoint, Vec<&'a DataPoint>)> = BTreeMap::new();
+    for p in datasets.into_iter().flatten() {
+        let key = (p.indicator_id.as_str(), p.country_iso3.as_str(), p.year);
+        let Some((kept, dropped)) = rows.get_mut(&key) else {
+            rows.insert(key, (p, Vec::new()));
+            continue;
+        };
+        let differs = !same_value(kept.value, p.value);
+        match policy {
+            ConflictPolicy::Error if differs => anyhow::bail!(
+                "conflicting values for {} {} {}: {} and {}",
+                p.indicator_id,
+                p.country_iso3,
+                p.year,
+                fmt_value(kept.value),
+                fmt_value(p.value)
+            ),
+            ConflictPolicy::KeepFirst | ConflictPolicy::Error => {
+                if differs {
+                    dropped.push(p);
+                }
+            }
+            ConflictPolicy::KeepLast => {
+                let previous = std::mem::replace(kept, p);
+                // Rows dropped earlier are compared against the new winner.
+                dropped.push(previous);
+                dropped.retain(|d| !same_value(d.value, p.value));
+            }
+        }
+    }
+
+    let mut out = Merged::default();
+    for (kept, dropped) in rows.into_values() {
+        if !dropped.is_empty() {
+            out.conflicts.push(Conflict {
+                kept: kept.clone(),
+                dropped: dropped.into_iter().cloned().collect(),
+            });
+        }
+        out.dataset.push(kept.clone());
+    }
+    Ok(out)
+}
+
+/// Give every `(indicator_id, country_iso3)` series a row for each year that any series in
+/// `points` has, adding rows without a value where a series has none. Useful before
+/// comparing or merging fetches where one lists missing years as empty rows (as the World
+/// Bank API does) and another leaves them out. Sorted by `(indicator_id, country_iso3,
+/// year)`; a repeated key keeps its last row.
+pub fn align_years(points: &[DataPoint]) -> Vec<DataPoint> {
+    let years: BTreeSet<i32> = points.iter().map(|p| p.year).collect();
+    let mut series: BTreeMap<(&str, &str), BTreeMap<i32, &DataPoint>> = BTreeMap::new();
+    for p in points {
+        series
+            .entry((p.indicator_id.as_str(), p.country_iso3.as_str()))
+            .or_default()
+            .insert(p.year, p);
+    }
+    let mut out = Vec::with_capacity(series.len() * years.len());
+    for rows in series.values() {
+        let Some(template) = rows.values().next() else {
+            continue;
+        };
+        for year in &years {
+            out.push(match rows.get(year) {
+                Some(p) => (*p).clone(),
+                None => DataPoint {
+                    year: *year,
+                    value: None,
+                    obs_status: None,
+                    decimal: None,
+                    ..(*template).clone()
+                },
+            });
+        }
+    }
+    out
+}
+
+/// Value equality where two missing (or non-finite) values are the same.
+pub(crate) fn same_value(a: Option<f64>, b: Option<f64>) -> bool {
+    let finite = |x: Option<f64>| x.filter(|v| v.is_finite());
+    finite(a) == finite(b)
+}
+
+fn fmt_value(v: Option<f64>) -> String {
+    v.map_or_else(|| "missing".to_string(), |v| v.to_string())
 }
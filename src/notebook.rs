@@ -0,0 +1,86 @@
+//! Inline display in Rust Jupyter notebooks through [evcxr](https://github.com/evcxr/evcxr)
+//! (feature `evcxr`). evcxr renders the last value of a cell richly when it has an
+//! `evcxr_display` method: [`Dataset`] as an HTML table, [`Chart`] as an inline SVG.
+//!
+//! ```no_run
+//! use wbi_rs::{Client, Dataset, DateSpec, notebook};
+//!
+//! let data: Dataset = Client::default()
+//!     .fetch(&["DEU".into()], &["SP.POP.TOTL".into()], Some(DateSpec::Year(2020)), None)?
+//!     .into();
+//! data.evcxr_display(); // what evcxr calls for `data` at the end of a cell
+//! notebook::chart(&data, &Default::default())?.evcxr_display();
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::models::{DataPoint, Dataset};
+use crate::report::escape_html;
+use crate::viz::{self, PlotOptions};
+use anyhow::Result;
+
+/// Rows shown by [`Dataset::to_html`]; the rest are counted in a last row.
+const MAX_ROWS: usize = 50;
+
+/// Print `body` in evcxr's rich output protocol.
+fn display(mime: &str, body: &str) {
+    println!("EVCXR_BEGIN_CONTENT {mime}\n{body}\nEVCXR_END_CONTENT");
+}
+
+impl Dataset {
+    /// HTML table of the first rows: country, indicator, year, value and unit.
+    pub fn to_html(&self) -> String {
+        let mut out = String::from(
+            "<table>\n<thead><tr><th>country</th><th>iso3</th><th>indicator</th>\
+             <th>year</th><th>value</th><th>unit</th></tr></thead>\n<tbody>\n",
+        );
+        for p in self.iter().take(MAX_ROWS) {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&p.country_name),
+                escape_html(&p.country_iso3),
+                escape_html(&p.indicator_id),
+                p.year,
+                p.value.map(|v| v.to_string()).unwrap_or_default(),
+                escape_html(p.unit.as_deref().unwrap_or("")),
+            ));
+        }
+        if self.len() > MAX_ROWS {
+            out.push_str(&format!(
+                "<tr><td colspan=\"6\">… {} more rows</td></tr>\n",
+                self.len() - MAX_ROWS
+            ));
+        }
+        out.push_str("</tbody>\n</table>");
+        out
+    }
+
+    /// Show the dataset as an HTML table in an evcxr notebook.
+    pub fn evcxr_display(&self) {
+        display("text/html", &self.to_html());
+    }
+}
+
+/// A rendered chart; shown inline by evcxr.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    svg: String,
+}
+
+impl Chart {
+    /// The chart as an SVG document.
+    pub fn svg(&self) -> &str {
+        &self.svg
+    }
+
+    /// Show the chart inline in an evcxr notebook.
+    pub fn evcxr_display(&self) {
+        display("text/html", &self.svg);
+    }
+}
+
+/// Render `points` as a [`Chart`], with the same options as [`viz::plot_with_options`].
+pub fn chart(points: &[DataPoint], options: &PlotOptions) -> Result<Chart> {
+    Ok(Chart {
+        svg: viz::render_svg_string(points, options)?,
+    })
+}
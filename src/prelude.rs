@@ -0,0 +1,23 @@
+//! The commonly used types in one import.
+//!
+//! ```no_run
+//! use wbi_rs::prelude::*;
+//!
+//! let data: Vec<DataPoint> = Client::default().fetch(
+//!     &["DEU".into()],
+//!     &["SP.POP.TOTL".into()],
+//!     Some(DateSpec::Range { start: 2010, end: 2020 }),
+//!     None,
+//! )?;
+//! let summaries: Vec<Summary> = wbi_rs::stats::grouped_summary(&data);
+//! let options = PlotOptions { kind: PlotKind::Area, legend: LegendMode::Right, ..Default::default() };
+//! wbi_rs::viz::plot_with_options(&data, "pop.svg", &options)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+#[cfg(feature = "blocking")]
+pub use crate::api::Client;
+pub use crate::api::{ApiError, AsyncClient, FetchProgress};
+pub use crate::models::{DataPoint, Dataset, DateSpec, GroupKey, Period};
+pub use crate::stats::{CorrelationMatrix, GroupBy, RankBy, Summary, YearlySummary};
+pub use crate::viz::{LegendMode, PlotKind, PlotOptions, PlotTransform};
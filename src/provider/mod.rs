@@ -0,0 +1,132 @@
+//! Data sources behind one interface, so the stats, viz and storage modules work on
+//! observations from any of them.
+//!
+//! [`DataProvider`] is implemented by the World Bank [`Client`](crate::Client) (feature
+//! `blocking`) and by [`SdmxCsvProvider`], which reads SDMX-CSV exports of the OECD, IMF,
+//! Eurostat and other SDMX services. Both return tidy [`DataPoint`] rows, so series from
+//! several providers can be concatenated and drawn in one chart:
+//!
+//! ```no_run
+//! use wbi_rs::provider::{DataProvider, SdmxCsvProvider};
+//! use wbi_rs::{Client, DateSpec};
+//!
+//! let oecd = SdmxCsvProvider::from_path("oecd_gdp.csv")?;
+//! let date = Some(DateSpec::Range { start: 2010, end: 2020 });
+//! let countries = ["DEU".to_string(), "FRA".to_string()];
+//! let mut rows = Client::default().fetch(&countries, &["NY.GDP.PCAP.CD".into()], date, None)?;
+//! let oecd_ids: Vec<String> = oecd.search("gdp")?.into_iter().map(|m| m.id).collect();
+//! rows.extend(oecd.fetch(&countries, &oecd_ids, date)?);
+//! wbi_rs::viz::plot_lines(&rows, "gdp.svg", 1000, 600)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+mod sdmx;
+
+pub use sdmx::SdmxCsvProvider;
+
+use crate::models::{DataPoint, DateSpec, IndicatorMeta};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// A source of indicator observations.
+pub trait DataProvider {
+    /// Short name of the source, e.g. `"World Bank"`.
+    fn name(&self) -> &str;
+
+    /// Observations of `indicators` for `countries` (ISO3 or ISO2 codes) in `date`, one
+    /// [`DataPoint`] per indicator, country and year.
+    fn fetch(
+        &self,
+        countries: &[String],
+        indicators: &[String],
+        date: Option<DateSpec>,
+    ) -> Result<Vec<DataPoint>>;
+
+    /// Indicators whose id or name contains every word of `query` (case-insensitive).
+    fn search(&self, query: &str) -> Result<Vec<IndicatorMeta>>;
+
+    /// Metadata of indicator `id`, or `None` if the provider does not know it.
+    fn metadata(&self, id: &str) -> Result<Option<IndicatorMeta>>;
+}
+
+/// The World Bank Indicators API. `search` covers the World Development Indicators
+/// (source 2); `fetch` requests each indicator separately, as [`Client::fetch`] does without
+/// a source.
+///
+/// [`Client::fetch`]: crate::Client::fetch
+#[cfg(feature = "blocking")]
+impl DataProvider for crate::Client {
+    fn name(&self) -> &str {
+        "World Bank"
+    }
+
+    fn fetch(
+        &self,
+        countries: &[String],
+        indicators: &[String],
+        date: Option<DateSpec>,
+    ) -> Result<Vec<DataPoint>> {
+        crate::Client::fetch(self, countries, indicators, date, None)
+    }
+
+    fn search(&self, query: &str) -> Result<Vec<IndicatorMeta>> {
+        self.search_indicators(query, 2)
+    }
+
+    fn metadata(&self, id: &str) -> Result<Option<IndicatorMeta>> {
+        self.lookup_indicator(id)
+    }
+}
+
+/// Whether every whitespace-separated word of `query` occurs in the id or name of `meta`.
+pub(crate) fn matches_query(meta: &IndicatorMeta, query: &str) -> bool {
+    let haystack = format!("{} {}", meta.id, meta.name).to_lowercase();
+    query
+        .split_whitespace()
+        .all(|word| haystack.contains(&word.to_lowercase()))
+}
+
+/// Keep the rows of `points` that fall in `date`, resolved against the current year; for
+/// [`DateSpec::MostRecent`], the `n` latest years with a value of each series.
+pub(crate) fn filter_date(points: Vec<DataPoint>, date: Option<DateSpec>) -> Vec<DataPoint> {
+    use chrono::Datelike;
+    let Some(date) = date else {
+        return points;
+    };
+    match date.resolve(chrono::Utc::now().year()) {
+        DateSpec::Year(y) => points.into_iter().filter(|p| p.year == y).collect(),
+        DateSpec::Range { start, end } => points
+            .into_iter()
+            .filter(|p| (start..=end).contains(&p.year))
+            .collect(),
+        DateSpec::MostRecent(n) => {
+            // Cut-off year per series: the n-th latest year with a value.
+            let mut years: HashMap<(&str, &str), Vec<i32>> = HashMap::new();
+            for p in points.iter().filter(|p| p.value.is_some()) {
+                years
+                    .entry((&p.indicator_id, &p.country_id))
+                    .or_default()
+                    .push(p.year);
+            }
+            let cutoff: HashMap<(String, String), i32> = years
+                .into_iter()
+                .filter_map(|((ind, country), mut ys)| {
+                    ys.sort_unstable_by(|a, b| b.cmp(a));
+                    let nth = *ys.get((n as usize).max(1) - 1).or(ys.last())?;
+                    Some(((ind.to_string(), country.to_string()), nth))
+                })
+                .collect();
+            points
+                .into_iter()
+                .filter(|p| {
+                    p.value.is_some()
+                        && cutoff
+                            .get(&(p.indicator_id.clone(), p.country_id.clone()))
+                            .is_some_and(|&first| p.year >= first)
+                })
+                .collect()
+        }
+        // `resolve` turns every other form into a range.
+        DateSpec::From(_) | DateSpec::Until(_) | DateSpec::Last(_) => points,
+    }
+}
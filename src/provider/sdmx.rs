@@ -0,0 +1,292 @@
+//! [`SdmxCsvProvider`]: annual observations from an SDMX-CSV file.
+
+use super::{DataProvider, filter_date, matches_query};
+use crate::codes;
+use crate::models::{DataPoint, DateSpec, IndicatorMeta};
+use anyhow::{Context, Result, anyhow};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Columns naming the dataflow a row belongs to (SDMX-CSV 1.0 and 2.0).
+const DATAFLOW_COLUMNS: [&str; 2] = ["DATAFLOW", "STRUCTURE_ID"];
+/// Columns holding the economy, by preference.
+const AREA_COLUMNS: [&str; 4] = ["REF_AREA", "COUNTRY", "LOCATION", "GEO"];
+/// Columns that are neither part of the series key nor read as attributes.
+const SKIPPED_COLUMNS: [&str; 14] = [
+    "STRUCTURE",
+    "STRUCTURE_NAME",
+    "ACTION",
+    "FREQ",
+    "TIME_PERIOD",
+    "OBS_VALUE",
+    "OBS_STATUS",
+    "OBS_CONF",
+    "OBS_FLAG",
+    "CONF_STATUS",
+    "UNIT_MULT",
+    "DECIMALS",
+    "TIME_FORMAT",
+    "BASE_PER",
+];
+
+/// Observations loaded from an SDMX-CSV file, as exported by the OECD Data Explorer, the IMF,
+/// Eurostat and other SDMX web services. Labels are read when present, either in the cells
+/// (`REF_AREA: Reference area` headers, `DEU: Germany` cells) or in a column titled in words
+/// right after each code column.
+///
+/// Each row becomes one [`DataPoint`]:
+/// - **Country**: `REF_AREA` (or `COUNTRY`, `LOCATION`, `GEO`), matched against the World Bank
+///   economies so ISO2 and ISO3 codes both end up as ISO3 with the usual name.
+/// - **Indicator**: the dataflow id followed by the other dimension codes, joined by `.`
+///   (`DF_QNA.B1GQ.USD_PPP`); its name is the dimension labels when the file has them.
+/// - **Value**: `OBS_VALUE` times 10^`UNIT_MULT`, so values are in base units like World Bank
+///   data. `UNIT_MEASURE`, `OBS_STATUS` (or `OBS_FLAG`) and `DECIMALS` fill `unit`,
+///   `obs_status` and `decimal`.
+///
+/// Only annual observations (`TIME_PERIOD` is a year) are kept, since a `DataPoint` is one
+/// year; quarterly and monthly rows are skipped.
+#[derive(Debug, Clone)]
+pub struct SdmxCsvProvider {
+    name: String,
+    points: Vec<DataPoint>,
+    indicators: Vec<IndicatorMeta>,
+}
+
+/// `("DEU", Some("Germany"))` from `DEU: Germany`; `("DEU", None)` from `DEU`.
+fn code_and_label(cell: &str) -> (&str, Option<&str>) {
+    match cell.split_once(": ") {
+        Some((code, label)) => (code.trim(), Some(label.trim())),
+        None => (cell.trim(), None),
+    }
+}
+
+/// `DF_QNA` from `OECD.SDD.NAD:DSD_NAMAIN1@DF_QNA(1.0)`.
+fn dataflow_short_id(id: &str) -> &str {
+    let id = id.split_once(':').map_or(id, |(_, rest)| rest);
+    let id = id.split_once('(').map_or(id, |(head, _)| head);
+    id.rsplit_once('@').map_or(id, |(_, tail)| tail)
+}
+
+impl SdmxCsvProvider {
+    /// Load the SDMX-CSV file at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("reading SDMX-CSV {}", path.display()))?;
+        Self::from_reader(file).with_context(|| format!("parsing SDMX-CSV {}", path.display()))
+    }
+
+    /// Load SDMX-CSV from `reader`.
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self> {
+        let mut rdr = csv::Reader::from_reader(reader);
+        let raw: Vec<String> = rdr
+            .headers()?
+            .iter()
+            .map(|h| {
+                code_and_label(h.trim_start_matches('\u{feff}'))
+                    .0
+                    .to_string()
+            })
+            .collect();
+        // Codes are identifiers in one case (`REF_AREA`, Eurostat's `geo`); a column titled in
+        // words (`Reference area`, `Measure`) holds the labels of the column before it.
+        let is_code: Vec<bool> = raw
+            .iter()
+            .map(|h| {
+                !h.is_empty()
+                    && h.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+                    && (*h == h.to_ascii_uppercase() || *h == h.to_ascii_lowercase())
+            })
+            .collect();
+        let label_column: Vec<Option<usize>> = (0..raw.len())
+            .map(|i| Some(i + 1).filter(|&n| is_code[i] && is_code.get(n) == Some(&false)))
+            .collect();
+        let headers: Vec<String> = raw.iter().map(|h| h.to_ascii_uppercase()).collect();
+        let column = |name: &str| headers.iter().position(|h| h == name);
+        let required = |name: &str| column(name).ok_or_else(|| anyhow!("no {name} column"));
+        let time = required("TIME_PERIOD")?;
+        let value = required("OBS_VALUE")?;
+        let area = AREA_COLUMNS
+            .iter()
+            .find_map(|c| column(c))
+            .ok_or_else(|| anyhow!("no REF_AREA column"))?;
+        let dataflow = DATAFLOW_COLUMNS.iter().find_map(|c| column(c));
+        let (unit, unit_mult) = (column("UNIT_MEASURE"), column("UNIT_MULT"));
+        let status = column("OBS_STATUS").or_else(|| column("OBS_FLAG"));
+        let decimals = column("DECIMALS");
+        let key: Vec<usize> = (0..headers.len())
+            .filter(|&i| {
+                i != area
+                    && Some(i) != dataflow
+                    && is_code[i]
+                    && !SKIPPED_COLUMNS.contains(&headers[i].as_str())
+                    && !DATAFLOW_COLUMNS.contains(&headers[i].as_str())
+            })
+            .collect();
+
+        let mut agency: Option<String> = None;
+        let mut indicators: BTreeMap<String, IndicatorMeta> = BTreeMap::new();
+        let mut points = Vec::new();
+        let mut skipped = 0usize;
+        for (i, record) in rdr.records().enumerate() {
+            // Header is line 1, so record i is on line i + 2.
+            let record = record.with_context(|| format!("line {}", i + 2))?;
+            let cell = |c: usize| record.get(c).unwrap_or_default();
+            let entry = |c: usize| {
+                let (code, label) = code_and_label(cell(c));
+                let label = label.or_else(|| label_column[c].map(cell).filter(|l| !l.is_empty()));
+                (code, label)
+            };
+            let Ok(year) = cell(time).trim().parse::<i32>() else {
+                skipped += 1;
+                continue;
+            };
+
+            let flow = dataflow.map(|c| code_and_label(cell(c)).0).unwrap_or("");
+            if agency.is_none() && !flow.is_empty() {
+                agency = flow.split_once(':').map(|(a, _)| a.to_string());
+            }
+            let mut ids: Vec<&str> = Vec::new();
+            let mut labels: Vec<&str> = Vec::new();
+            if !flow.is_empty() {
+                ids.push(dataflow_short_id(flow));
+            }
+            for &c in &key {
+                let (code, label) = entry(c);
+                if !code.is_empty() {
+                    ids.push(code);
+                    labels.extend(label);
+                }
+            }
+            let indicator_id = ids.join(".");
+            let indicator_name = if labels.is_empty() {
+                indicator_id.clone()
+            } else {
+                labels.join(", ")
+            };
+            let unit = unit
+                .map(entry)
+                .filter(|(code, _)| !code.is_empty())
+                .map(|(code, label)| label.unwrap_or(code).to_string());
+
+            let (area_code, area_label) = entry(area);
+            let (country_id, country_iso3, country_name) = match codes::find_country(area_code) {
+                Some(e) => (e.iso2.to_string(), e.iso3.to_string(), e.name.to_string()),
+                None => (
+                    area_code.to_string(),
+                    area_code.to_string(),
+                    area_label.unwrap_or(area_code).to_string(),
+                ),
+            };
+
+            let mult: i32 = unit_mult
+                .and_then(|c| code_and_label(cell(c)).0.parse().ok())
+                .unwrap_or(0);
+            let value = cell(value)
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .map(|v| v * 10f64.powi(mult));
+
+            indicators
+                .entry(indicator_id.clone())
+                .or_insert_with(|| IndicatorMeta {
+                    id: indicator_id.clone(),
+                    name: indicator_name.clone(),
+                    unit: unit.clone(),
+                    source_note: None,
+                    source_organization: None,
+                });
+            points.push(DataPoint {
+                indicator_id,
+                indicator_name,
+                country_id,
+                country_name,
+                country_iso3,
+                year,
+                value,
+                unit,
+                obs_status: status
+                    .map(|c| code_and_label(cell(c)).0.to_string())
+                    .filter(|s| !s.is_empty()),
+                decimal: decimals.and_then(|c| code_and_label(cell(c)).0.parse().ok()),
+            });
+        }
+        if points.is_empty() && skipped > 0 {
+            return Err(anyhow!(
+                "no annual observations ({skipped} rows with sub-annual TIME_PERIOD)"
+            ));
+        }
+        if skipped > 0 {
+            tracing::debug!("skipped {skipped} sub-annual SDMX observations");
+        }
+        Ok(Self {
+            name: agency.unwrap_or_else(|| "SDMX".into()),
+            points,
+            indicators: indicators.into_values().collect(),
+        })
+    }
+
+    /// All loaded observations, in file order.
+    pub fn points(&self) -> &[DataPoint] {
+        &self.points
+    }
+
+    /// All indicators in the file, sorted by id.
+    pub fn indicators(&self) -> &[IndicatorMeta] {
+        &self.indicators
+    }
+}
+
+/// The name is the agency of the dataflow (`OECD.SDD.NAD`), or `SDMX` when the file has none.
+/// `fetch` treats an empty `countries` or `indicators` slice as "all in the file".
+impl DataProvider for SdmxCsvProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fetch(
+        &self,
+        countries: &[String],
+        indicators: &[String],
+        date: Option<DateSpec>,
+    ) -> Result<Vec<DataPoint>> {
+        let wanted_country = |p: &DataPoint| {
+            countries.is_empty()
+                || countries.iter().any(|c| {
+                    c.eq_ignore_ascii_case(&p.country_iso3) || c.eq_ignore_ascii_case(&p.country_id)
+                })
+        };
+        let wanted_indicator = |p: &DataPoint| {
+            indicators.is_empty()
+                || indicators
+                    .iter()
+                    .any(|i| i.eq_ignore_ascii_case(&p.indicator_id))
+        };
+        let rows = self
+            .points
+            .iter()
+            .filter(|p| wanted_country(p) && wanted_indicator(p))
+            .cloned()
+            .collect();
+        Ok(filter_date(rows, date))
+    }
+
+    fn search(&self, query: &str) -> Result<Vec<IndicatorMeta>> {
+        Ok(self
+            .indicators
+            .iter()
+            .filter(|m| matches_query(m, query))
+            .cloned()
+            .collect())
+    }
+
+    fn metadata(&self, id: &str) -> Result<Option<IndicatorMeta>> {
+        Ok(self
+            .indicators
+            .iter()
+            .find(|m| m.id.eq_ignore_ascii_case(id))
+            .cloned())
+    }
+}
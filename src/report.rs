@@ -0,0 +1,475 @@
+/// Markdown/HTML reports: chart, summary statistics, data coverage and source attribution.
+///
+/// A report is a template with `{{placeholder}}` slots. The built-in templates
+/// ([`MARKDOWN_TEMPLATE`], [`HTML_TEMPLATE`]) can be replaced by user templates using the
+/// same placeholders:
+///
+/// | Placeholder | Content |
+/// |---|---|
+/// | `{{title}}` | report title |
+/// | `{{generated}}` | generation time (UTC) |
+/// | `{{countries}}`, `{{indicators}}`, `{{years}}` | what the data covers |
+/// | `{{chart}}` | the chart (image link, or inline SVG in HTML) |
+/// | `{{stats}}` | summary table per country and indicator |
+/// | `{{coverage}}` | years with data per country and indicator |
+/// | `{{source}}` | World Bank attribution with indicator names |
+/// | `{{definitions}}` | indicator definitions (source notes) under their own heading; empty without metadata |
+/// | `{{appendix}}` | methodology and country footnotes per indicator; empty without series-country metadata |
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::report::{ChartEmbed, ReportFormat, render_report};
+/// # let points = vec![];
+/// let md = render_report(&points, "Population", Some(ChartEmbed::Link("pop.svg")), ReportFormat::Markdown, None, &[], &[])?;
+/// std::fs::write("report.md", md)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+use crate::models::{DataPoint, IndicatorMeta, SeriesCountryMeta};
+use crate::stats::grouped_summary;
+use anyhow::{Result, bail};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Built-in Markdown template.
+pub const MARKDOWN_TEMPLATE: &str = "# {{title}}
+
+_Generated {{generated}} · {{countries}} · {{indicators}} · {{years}}_
+
+{{chart}}
+
+## Summary statistics
+
+{{stats}}
+
+## Coverage
+
+{{coverage}}
+
+## Source
+
+{{source}}
+{{definitions}}{{appendix}}";
+
+/// Built-in HTML template.
+pub const HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{{title}}</title>
+<style>
+body { font-family: "DejaVu Sans", Helvetica, Arial, sans-serif; max-width: 64rem; margin: 2rem auto; color: #222; }
+table { border-collapse: collapse; margin: 1rem 0; }
+th, td { border: 1px solid #ccc; padding: 0.25rem 0.6rem; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+.meta { color: #666; }
+</style>
+</head>
+<body>
+<h1>{{title}}</h1>
+<p class="meta">Generated {{generated}} · {{countries}} · {{indicators}} · {{years}}</p>
+{{chart}}
+<h2>Summary statistics</h2>
+{{stats}}
+<h2>Coverage</h2>
+{{coverage}}
+<h2>Source</h2>
+{{source}}
+{{definitions}}{{appendix}}</body>
+</html>
+"#;
+
+/// Output flavour of a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// `.html`/`.htm` → HTML, anything else → Markdown.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("html" | "htm") => Self::Html,
+            _ => Self::Markdown,
+        }
+    }
+}
+
+/// How `{{chart}}` shows the chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartEmbed<'a> {
+    /// Image reference to a file path or URL (relative to the report).
+    Link(&'a str),
+    /// SVG markup placed inline (HTML only; Markdown falls back to nothing).
+    InlineSvg(&'a str),
+}
+
+/// Fill `template` (or the built-in one for `format`) from `points`. `definitions` (e.g. from
+/// [`Client::fetch_indicator_metadata`](crate::Client::fetch_indicator_metadata)) fill
+/// `{{definitions}}` and `appendix` (e.g. from
+/// [`Client::fetch_series_country_metadata`](crate::Client::fetch_series_country_metadata))
+/// fills `{{appendix}}`; pass `&[]` to leave either empty.
+///
+/// Errors on unknown placeholders, so typos in user templates do not go unnoticed.
+pub fn render_report(
+    points: &[DataPoint],
+    title: &str,
+    chart: Option<ChartEmbed<'_>>,
+    format: ReportFormat,
+    template: Option<&str>,
+    definitions: &[IndicatorMeta],
+    appendix: &[SeriesCountryMeta],
+) -> Result<String> {
+    let template = template.unwrap_or(match format {
+        ReportFormat::Markdown => MARKDOWN_TEMPLATE,
+        ReportFormat::Html => HTML_TEMPLATE,
+    });
+    let text = |s: &str| match format {
+        ReportFormat::Markdown => s.to_string(),
+        ReportFormat::Html => escape_html(s),
+    };
+
+    let countries: BTreeSet<&str> = points.iter().map(|p| p.country_iso3.as_str()).collect();
+    let indicators: BTreeMap<&str, &str> = points
+        .iter()
+        .map(|p| (p.indicator_id.as_str(), p.indicator_name.as_str()))
+        .collect();
+    let years = match (
+        points.iter().map(|p| p.year).min(),
+        points.iter().map(|p| p.year).max(),
+    ) {
+        (Some(a), Some(b)) if a == b => a.to_string(),
+        (Some(a), Some(b)) => format!("{a}–{b}"),
+        _ => "no data".to_string(),
+    };
+
+    let chart = match (chart, format) {
+        (None, _) => String::new(),
+        (Some(ChartEmbed::Link(src)), ReportFormat::Markdown) => format!("![{title}]({src})"),
+        (Some(ChartEmbed::Link(src)), ReportFormat::Html) => format!(
+            r#"<img src="{}" alt="{}">"#,
+            escape_html(src),
+            escape_html(title)
+        ),
+        (Some(ChartEmbed::InlineSvg(svg)), ReportFormat::Html) => svg.to_string(),
+        (Some(ChartEmbed::InlineSvg(_)), ReportFormat::Markdown) => String::new(),
+    };
+
+    let stats_rows: Vec<Vec<String>> = grouped_summary(points)
+        .into_iter()
+        .map(|s| {
+            vec![
+                s.key.country_iso3,
+                s.key.indicator_id,
+                s.count.to_string(),
+                s.missing.to_string(),
+                fmt_num(s.min),
+                fmt_num(s.max),
+                fmt_num(s.mean),
+                fmt_num(s.median),
+            ]
+        })
+        .collect();
+    let stats = table(
+        format,
+        &[
+            "Country",
+            "Indicator",
+            "Count",
+            "Missing",
+            "Min",
+            "Max",
+            "Mean",
+            "Median",
+        ],
+        2,
+        &stats_rows,
+    );
+
+    let coverage = table(
+        format,
+        &["Country", "Indicator", "Years with data", "First", "Last"],
+        2,
+        &coverage_rows(points),
+    );
+
+    let attribution = "Data: World Bank, World Development Indicators \
+                       (https://data.worldbank.org), licensed CC BY 4.0.";
+    let listed: Vec<String> = indicators
+        .iter()
+        .map(|(id, name)| format!("{id}: {name}"))
+        .collect();
+    let source = match format {
+        ReportFormat::Markdown => std::iter::once(attribution.to_string())
+            .chain(listed.iter().map(|l| format!("- {l}")))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ReportFormat::Html => {
+            let items: String = listed
+                .iter()
+                .map(|l| format!("<li>{}</li>", escape_html(l)))
+                .collect();
+            format!("<p>{}</p>\n<ul>{items}</ul>", escape_html(attribution))
+        }
+    };
+
+    let vars: BTreeMap<&str, String> = BTreeMap::from([
+        ("title", text(title)),
+        (
+            "generated",
+            chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+        ),
+        (
+            "countries",
+            text(&countries.into_iter().collect::<Vec<_>>().join(", ")),
+        ),
+        (
+            "indicators",
+            text(&indicators.keys().copied().collect::<Vec<_>>().join(", ")),
+        ),
+        ("years", years),
+        ("chart", chart),
+        ("stats", stats),
+        ("coverage", coverage),
+        ("source", source),
+        ("definitions", definitions_block(format, definitions)),
+        ("appendix", appendix_block(format, appendix, &indicators)),
+    ]);
+    fill(template, &vars)
+}
+
+/// "Indicator definitions" section: name, id, source note and organization per indicator.
+/// Empty when no indicator has a note or organization.
+fn definitions_block(format: ReportFormat, definitions: &[IndicatorMeta]) -> String {
+    let described: Vec<&IndicatorMeta> = definitions
+        .iter()
+        .filter(|m| {
+            nonempty(&m.source_note).is_some() || nonempty(&m.source_organization).is_some()
+        })
+        .collect();
+    if described.is_empty() {
+        return String::new();
+    }
+    match format {
+        ReportFormat::Markdown => {
+            let mut out = String::from("\n## Indicator definitions\n");
+            for m in described {
+                out.push_str(&format!("\n**{}** (`{}`)\n", m.name, m.id));
+                if let Some(note) = nonempty(&m.source_note) {
+                    out.push_str(&format!("\n{note}\n"));
+                }
+                if let Some(org) = nonempty(&m.source_organization) {
+                    out.push_str(&format!("\n_Source: {org}_\n"));
+                }
+            }
+            out
+        }
+        ReportFormat::Html => {
+            let mut out = String::from("<h2>Indicator definitions</h2>\n<dl>");
+            for m in described {
+                out.push_str(&format!(
+                    "\n<dt>{} ({})</dt><dd>",
+                    escape_html(&m.name),
+                    escape_html(&m.id)
+                ));
+                if let Some(note) = nonempty(&m.source_note) {
+                    out.push_str(&format!("<p>{}</p>", escape_html(note)));
+                }
+                if let Some(org) = nonempty(&m.source_organization) {
+                    out.push_str(&format!("<p><em>Source: {}</em></p>", escape_html(org)));
+                }
+                out.push_str("</dd>");
+            }
+            out.push_str("\n</dl>\n");
+            out
+        }
+    }
+}
+
+/// "Appendix" section: per indicator its methodology, then the footnotes of each country.
+/// Indicators are named as in `names` (id to name). Empty when there is no text at all.
+fn appendix_block(
+    format: ReportFormat,
+    appendix: &[SeriesCountryMeta],
+    names: &BTreeMap<&str, &str>,
+) -> String {
+    let mut by_indicator: Vec<(&str, Vec<&SeriesCountryMeta>)> = Vec::new();
+    for m in appendix
+        .iter()
+        .filter(|m| nonempty(&m.methodology).is_some() || !m.notes.is_empty())
+    {
+        match by_indicator
+            .iter_mut()
+            .find(|(id, _)| *id == m.indicator_id)
+        {
+            Some((_, metas)) => metas.push(m),
+            None => by_indicator.push((&m.indicator_id, vec![m])),
+        }
+    }
+    if by_indicator.is_empty() {
+        return String::new();
+    }
+    let mut out = match format {
+        ReportFormat::Markdown => String::from("\n## Appendix: methodology and country notes\n"),
+        ReportFormat::Html => String::from("<h2>Appendix: methodology and country notes</h2>\n"),
+    };
+    for (id, metas) in by_indicator {
+        let name = names.get(id).copied().unwrap_or(id);
+        let methodology = metas.iter().find_map(|m| nonempty(&m.methodology));
+        let countries = metas.iter().filter(|m| !m.notes.is_empty());
+        match format {
+            ReportFormat::Markdown => {
+                out.push_str(&format!("\n### {name} (`{id}`)\n"));
+                if let Some(text) = methodology {
+                    out.push_str(&format!("\n{text}\n"));
+                }
+                for m in countries {
+                    out.push_str(&format!("\n**{}**\n\n", m.country_id));
+                    for note in &m.notes {
+                        out.push_str(&format!("- {note}\n"));
+                    }
+                }
+            }
+            ReportFormat::Html => {
+                out.push_str(&format!(
+                    "<h3>{} ({})</h3>\n",
+                    escape_html(name),
+                    escape_html(id)
+                ));
+                if let Some(text) = methodology {
+                    out.push_str(&format!("<p>{}</p>\n", escape_html(text)));
+                }
+                for m in countries {
+                    out.push_str(&format!(
+                        "<p><strong>{}</strong></p>\n<ul>",
+                        escape_html(&m.country_id)
+                    ));
+                    for note in &m.notes {
+                        out.push_str(&format!("<li>{}</li>", escape_html(note)));
+                    }
+                    out.push_str("</ul>\n");
+                }
+            }
+        }
+    }
+    out
+}
+
+/// `s` trimmed, or `None` when absent or blank.
+fn nonempty(s: &Option<String>) -> Option<&str> {
+    s.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// One row per (country, indicator): years with a finite value, and the first/last such year.
+fn coverage_rows(points: &[DataPoint]) -> Vec<Vec<String>> {
+    let span: BTreeSet<i32> = points.iter().map(|p| p.year).collect();
+    let mut seen: BTreeMap<(&str, &str), BTreeSet<i32>> = BTreeMap::new();
+    for p in points {
+        let years = seen
+            .entry((p.country_iso3.as_str(), p.indicator_id.as_str()))
+            .or_default();
+        if p.value.is_some_and(f64::is_finite) {
+            years.insert(p.year);
+        }
+    }
+    seen.into_iter()
+        .map(|((country, indicator), years)| {
+            let year = |y: Option<&i32>| y.map_or_else(|| "–".to_string(), i32::to_string);
+            vec![
+                country.to_string(),
+                indicator.to_string(),
+                format!("{} of {}", years.len(), span.len()),
+                year(years.first()),
+                year(years.last()),
+            ]
+        })
+        .collect()
+}
+
+/// A table whose first `keys` columns are labels and the rest numbers (right-aligned).
+fn table(format: ReportFormat, header: &[&str], keys: usize, rows: &[Vec<String>]) -> String {
+    match format {
+        ReportFormat::Markdown => {
+            let cell = |s: &str| s.replace('|', "\\|");
+            let mut out = format!("| {} |\n", header.join(" | "));
+            let align: Vec<&str> = (0..header.len())
+                .map(|i| if i < keys { ":---" } else { "---:" })
+                .collect();
+            out.push_str(&format!("| {} |", align.join(" | ")));
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                out.push_str(&format!("\n| {} |", cells.join(" | ")));
+            }
+            out
+        }
+        ReportFormat::Html => {
+            let mut out = String::from("<table>\n<tr>");
+            for h in header {
+                out.push_str(&format!("<th>{}</th>", escape_html(h)));
+            }
+            out.push_str("</tr>");
+            for row in rows {
+                out.push_str("\n<tr>");
+                for (i, c) in row.iter().enumerate() {
+                    let class = if i < keys { "" } else { r#" class="num""# };
+                    out.push_str(&format!("<td{class}>{}</td>", escape_html(c)));
+                }
+                out.push_str("</tr>");
+            }
+            out.push_str("\n</table>");
+            out
+        }
+    }
+}
+
+/// Replace every `{{name}}` in `template` with `vars[name]`.
+fn fill(template: &str, vars: &BTreeMap<&str, String>) -> Result<String> {
+    let mut out = String::with_capacity(template.len() * 2);
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            bail!("unclosed '{{{{' in report template");
+        };
+        let name = after[..end].trim();
+        match vars.get(name) {
+            Some(value) => out.push_str(value),
+            None => bail!(
+                "unknown placeholder '{{{{{name}}}}}' in report template; available: {}",
+                vars.keys().copied().collect::<Vec<_>>().join(", ")
+            ),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Up to four decimals, trailing zeros trimmed; `–` for missing.
+fn fmt_num(v: Option<f64>) -> String {
+    match v {
+        Some(x) if x.is_finite() => {
+            let s = format!("{x:.4}");
+            let s = s.trim_end_matches('0').trim_end_matches('.');
+            if s == "-0" {
+                "0".to_string()
+            } else {
+                s.to_string()
+            }
+        }
+        _ => "–".to_string(),
+    }
+}
+
+pub(crate) fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
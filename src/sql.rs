@@ -0,0 +1,168 @@
+//! SQL over observations in-process with [DataFusion](https://datafusion.apache.org) (feature
+//! `datafusion`). [`WbiTable`] is a DataFusion `TableProvider` over fetched observations, e.g.
+//! a [`Dataset`], or over the SQLite store of [`storage::save_sqlite`] (with the `sqlite`
+//! feature as well). Register it under a name and query it:
+//!
+//! ```
+//! use std::sync::Arc;
+//! use datafusion::prelude::SessionContext;
+//! use wbi_rs::models::{DataPoint, Dataset};
+//! use wbi_rs::sql::WbiTable;
+//!
+//! let mk = |iso3: &str, year: i32, v: f64| DataPoint { indicator_id: "SP.POP.TOTL".into(),
+//!     indicator_name: "Population, total".into(), country_id: iso3[..2].into(),
+//!     country_name: iso3.into(), country_iso3: iso3.into(), year, value: Some(v), unit: None,
+//!     obs_status: None, decimal: Some(0) };
+//! let data: Dataset = vec![mk("DEU", 2020, 83.2e6), mk("DEU", 2021, 83.1e6)].into();
+//!
+//! let ctx = SessionContext::new();
+//! ctx.register_table("wbi", Arc::new(WbiTable::from(data)))?;
+//! let batches = tokio::runtime::Builder::new_current_thread().build()?.block_on(async {
+//!     ctx.sql("SELECT country, value FROM wbi WHERE indicator='SP.POP.TOTL' AND year=2020")
+//!         .await?
+//!         .collect()
+//!         .await
+//! })?;
+//! assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! The table has the columns of [`storage::to_record_batch`], with `indicator_id` and
+//! `country_iso3` named `indicator` and `country`. There is no Parquet store in this crate;
+//! files written by other tools can be registered next to a `WbiTable` with DataFusion's own
+//! `SessionContext::register_parquet`.
+
+use std::any::Any;
+#[cfg(feature = "sqlite")]
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use arrow_array::RecordBatch;
+use arrow_schema::{Field, Schema, SchemaRef};
+use async_trait::async_trait;
+use datafusion::catalog::{Session, TableProvider};
+use datafusion::datasource::TableType;
+use datafusion::datasource::memory::MemorySourceConfig;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::ExecutionPlan;
+
+use crate::models::{DataPoint, Dataset};
+use crate::storage;
+
+/// Where a [`WbiTable`] reads its rows from.
+#[derive(Debug, Clone)]
+enum Source {
+    Points(Arc<Vec<DataPoint>>),
+    /// Read again on every scan, so queries see the latest fetches saved to the store.
+    #[cfg(feature = "sqlite")]
+    Sqlite {
+        path: PathBuf,
+        as_of: Option<chrono::DateTime<chrono::Utc>>,
+    },
+}
+
+/// A DataFusion table of observations, one row per indicator, country and year; see the
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct WbiTable {
+    source: Source,
+}
+
+impl WbiTable {
+    /// Table over `points`, e.g. the result of a fetch.
+    pub fn new(points: Vec<DataPoint>) -> Self {
+        Self {
+            source: Source::Points(Arc::new(points)),
+        }
+    }
+
+    /// Table over the SQLite store at `path` (features `datafusion` and `sqlite`): the values of
+    /// [`storage::load_sqlite`] as of `as_of`, or the latest ones when `as_of` is `None`.
+    #[cfg(feature = "sqlite")]
+    pub fn sqlite<P: Into<PathBuf>>(path: P, as_of: Option<chrono::DateTime<chrono::Utc>>) -> Self {
+        Self {
+            source: Source::Sqlite {
+                path: path.into(),
+                as_of,
+            },
+        }
+    }
+
+    /// Schema of the table: [`storage::record_batch_schema`] with `indicator_id` and
+    /// `country_iso3` renamed to `indicator` and `country`.
+    pub fn table_schema() -> SchemaRef {
+        let fields: Vec<Field> = storage::record_batch_schema()
+            .fields()
+            .iter()
+            .map(|f| match f.name().as_str() {
+                "indicator_id" => f.as_ref().clone().with_name("indicator"),
+                "country_iso3" => f.as_ref().clone().with_name("country"),
+                _ => f.as_ref().clone(),
+            })
+            .collect();
+        Arc::new(Schema::new(fields))
+    }
+
+    /// All rows of the source as one batch.
+    fn batch(&self) -> Result<RecordBatch> {
+        let batch = match &self.source {
+            Source::Points(points) => storage::to_record_batch(points)?,
+            #[cfg(feature = "sqlite")]
+            Source::Sqlite { path, as_of } => {
+                storage::to_record_batch(&storage::load_sqlite(path, *as_of)?)?
+            }
+        };
+        Ok(RecordBatch::try_new(
+            Self::table_schema(),
+            batch.columns().to_vec(),
+        )?)
+    }
+}
+
+impl From<Dataset> for WbiTable {
+    fn from(dataset: Dataset) -> Self {
+        Self::new(dataset.0)
+    }
+}
+
+impl From<Vec<DataPoint>> for WbiTable {
+    fn from(points: Vec<DataPoint>) -> Self {
+        Self::new(points)
+    }
+}
+
+#[async_trait]
+impl TableProvider for WbiTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Self::table_schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    /// Filters and limits are left to DataFusion; the rows are small enough to build in full.
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> datafusion::error::Result<Arc<dyn ExecutionPlan>> {
+        let batch = self
+            .batch()
+            .map_err(|e| DataFusionError::External(e.into()))?;
+        let exec = MemorySourceConfig::try_new_exec(
+            &[vec![batch]],
+            Self::table_schema(),
+            projection.cloned(),
+        )?;
+        Ok(exec)
+    }
+}
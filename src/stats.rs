@@ -9,6 +9,9 @@ use serde::{Deserialize, Serialize};
 #[doc = "- `min`/`max`: extremes over non-missing"]
 #[doc = "- `mean`: arithmetic mean"]
 #[doc = "- `median`: middle value (average of two middles for even length)"]
+#[doc = "- `std_dev`: sample standard deviation (needs at least two values)"]
+#[doc = "- `p25`/`p75`: quartiles, linearly interpolated between closest ranks"]
+#[doc = "- `cagr`: compound annual growth in percent from the first to the last year with a value (see [`cagr`])"]
 ///
 /// Compute grouped statistics by `(indicator_id, country_iso3)`.
 ///
@@ -42,27 +45,69 @@ pub struct Summary {
     pub max: Option<f64>,
     pub mean: Option<f64>,
     pub median: Option<f64>,
+    #[serde(default)]
+    pub std_dev: Option<f64>,
+    #[serde(default)]
+    pub p25: Option<f64>,
+    #[serde(default)]
+    pub p75: Option<f64>,
+    /// Only for per-country groups; pooling countries has no single first and last value.
+    #[serde(default)]
+    pub cagr: Option<f64>,
 }
 
 /// Compute grouped statistics by (indicator_id, country_iso3).
 /// This function aggregates `DataPoint` entries into summaries.
 /// With finite-value guard + safe sort
 pub fn grouped_summary(points: &[DataPoint]) -> Vec<Summary> {
+    summary_by(points, GroupBy::Country)
+}
+
+/// How [`summary_by`] groups observations. Indicators are never pooled, since their values
+/// are not comparable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    /// One summary per (indicator, country), as [`grouped_summary`].
+    #[default]
+    Country,
+    /// One summary per indicator over all countries and years; `key.country_iso3` is empty.
+    Indicator,
+}
+
+/// Grouped statistics with the grouping chosen by `group_by`; sorted by key.
+pub fn summary_by(points: &[DataPoint], group_by: GroupBy) -> Vec<Summary> {
     use std::cmp::Ordering;
     use std::collections::BTreeMap;
 
     let mut groups: BTreeMap<GroupKey, Vec<f64>> = BTreeMap::new();
     let mut missing: BTreeMap<GroupKey, usize> = BTreeMap::new();
+    // (year, value) of the earliest and latest observation per group, for the CAGR.
+    type Obs = (i32, f64);
+    let mut ends: BTreeMap<GroupKey, (Obs, Obs)> = BTreeMap::new();
 
     for p in points {
-        let key = GroupKey {
-            indicator_id: p.indicator_id.clone(),
-            country_iso3: p.country_iso3.clone(),
-        };
+        let key = GroupKey::new(
+            p.indicator_id.clone(),
+            match group_by {
+                GroupBy::Country => p.country_iso3.clone(),
+                GroupBy::Indicator => String::new(),
+            },
+        );
 
         match p.value {
             // Treat only finite numbers as valid observations
             Some(v) if v.is_finite() => {
+                let obs = (p.year, v);
+                ends.entry(key.clone())
+                    .and_modify(|(first, last)| {
+                        if obs.0 < first.0 {
+                            *first = obs;
+                        }
+                        if obs.0 > last.0 {
+                            *last = obs;
+                        }
+                    })
+                    .or_insert((obs, obs));
                 groups.entry(key).or_default().push(v);
             }
             // Count None or non-finite values as "missing"
@@ -96,7 +141,18 @@ pub fn grouped_summary(points: &[DataPoint]) -> Vec<Summary> {
             Some((vals[count / 2 - 1] + vals[count / 2]) / 2.0)
         };
 
+        let std_dev = match mean {
+            Some(m) if count > 1 => Some(
+                (vals.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (count - 1) as f64).sqrt(),
+            ),
+            _ => None,
+        };
+
         let miss = missing.get(&key).cloned().unwrap_or(0);
+        let growth = match group_by {
+            GroupBy::Country => ends.get(&key).and_then(|&(first, last)| cagr(first, last)),
+            GroupBy::Indicator => None,
+        };
 
         out.push(Summary {
             key,
@@ -106,8 +162,1516 @@ pub fn grouped_summary(points: &[DataPoint]) -> Vec<Summary> {
             max,
             mean,
             median,
+            std_dev,
+            p25: quantile_sorted(&vals, 0.25),
+            p75: quantile_sorted(&vals, 0.75),
+            cagr: growth,
         });
     }
 
     out
 }
+
+/// [`grouped_summary`] of the CSV in `reader` (as written by [`crate::storage::save_csv`]),
+/// computed in one streaming pass; see [`summary_by_from_reader`].
+pub fn grouped_summary_from_reader<R: std::io::Read>(reader: R) -> anyhow::Result<Vec<Summary>> {
+    summary_by_from_reader(reader, GroupBy::Country)
+}
+
+/// Values per group kept exactly by [`summary_by_from_reader`] before it estimates the median
+/// and quartiles instead.
+pub const STREAMING_EXACT_VALUES: usize = 512;
+
+/// [`summary_by`] of the CSV in `reader`, read one row at a time, for exports too large to
+/// load. Memory grows with the number of groups, not rows.
+///
+/// Count, missing, min, max, CAGR, mean and standard deviation (Welford's online algorithm)
+/// are exact. The median and quartiles are exact up to [`STREAMING_EXACT_VALUES`] values per
+/// group, the common case for per-country groups; beyond that they are P² estimates (Jain &
+/// Chlamtac, 1985), which converge on the true quantiles for large groups.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::stats::grouped_summary_from_reader;
+///
+/// let csv = "indicator_id,indicator_name,country_id,country_name,country_iso3,year,value,unit,obs_status,decimal
+/// X,Demo,DE,Germany,DEU,2020,1,,,
+/// X,Demo,DE,Germany,DEU,2021,3,,,
+/// X,Demo,DE,Germany,DEU,2022,,,,
+/// ";
+/// let s = grouped_summary_from_reader(csv.as_bytes())?;
+/// assert_eq!((s[0].count, s[0].missing), (2, 1));
+/// assert_eq!(s[0].median, Some(2.0));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn summary_by_from_reader<R: std::io::Read>(
+    reader: R,
+    group_by: GroupBy,
+) -> anyhow::Result<Vec<Summary>> {
+    use std::collections::BTreeMap;
+
+    let mut groups: BTreeMap<GroupKey, StreamingGroup> = BTreeMap::new();
+    for row in crate::storage::read_csv(reader) {
+        let p = row?;
+        let key = GroupKey::new(
+            p.indicator_id,
+            match group_by {
+                GroupBy::Country => p.country_iso3,
+                GroupBy::Indicator => String::new(),
+            },
+        );
+        groups.entry(key).or_default().push(p.year, p.value);
+    }
+
+    Ok(groups
+        .into_iter()
+        .filter(|(_, g)| g.count > 0)
+        .map(|(key, g)| {
+            let [p25, median, p75] = g.quantiles.estimates();
+            Summary {
+                count: g.count,
+                missing: g.missing,
+                min: Some(g.min),
+                max: Some(g.max),
+                mean: Some(g.mean),
+                median,
+                std_dev: (g.count > 1).then(|| (g.m2 / (g.count - 1) as f64).sqrt()),
+                p25,
+                p75,
+                cagr: match group_by {
+                    GroupBy::Country => g.ends.and_then(|(first, last)| cagr(first, last)),
+                    GroupBy::Indicator => None,
+                },
+                key,
+            }
+        })
+        .collect())
+}
+
+/// Running state of one group in [`summary_by_from_reader`].
+#[derive(Debug, Default)]
+struct StreamingGroup {
+    count: usize,
+    missing: usize,
+    min: f64,
+    max: f64,
+    mean: f64,
+    /// Sum of squared deviations from the running mean.
+    m2: f64,
+    /// Earliest and latest `(year, value)`, for the CAGR.
+    ends: Option<((i32, f64), (i32, f64))>,
+    quantiles: StreamingQuantiles,
+}
+
+impl StreamingGroup {
+    fn push(&mut self, year: i32, value: Option<f64>) {
+        let Some(v) = value.filter(|v| v.is_finite()) else {
+            self.missing += 1;
+            return;
+        };
+        if self.count == 0 {
+            (self.min, self.max) = (v, v);
+        }
+        self.count += 1;
+        self.min = self.min.min(v);
+        self.max = self.max.max(v);
+        let delta = v - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (v - self.mean);
+        let obs = (year, v);
+        self.ends = Some(match self.ends {
+            None => (obs, obs),
+            Some((first, last)) => (
+                if year < first.0 { obs } else { first },
+                if year > last.0 { obs } else { last },
+            ),
+        });
+        self.quantiles.push(v);
+    }
+}
+
+/// p25, median and p75 of a stream: exact while small, P² estimates after.
+#[derive(Debug)]
+enum StreamingQuantiles {
+    Exact(Vec<f64>),
+    Estimated(Box<[P2Quantile; 3]>),
+}
+
+impl Default for StreamingQuantiles {
+    fn default() -> Self {
+        Self::Exact(Vec::new())
+    }
+}
+
+impl StreamingQuantiles {
+    const PROBABILITIES: [f64; 3] = [0.25, 0.5, 0.75];
+
+    fn push(&mut self, v: f64) {
+        match self {
+            Self::Exact(vals) if vals.len() < STREAMING_EXACT_VALUES => vals.push(v),
+            Self::Exact(vals) => {
+                vals.push(v);
+                vals.sort_by(f64::total_cmp);
+                let markers = Self::PROBABILITIES.map(|p| P2Quantile::from_sorted(vals, p));
+                *self = Self::Estimated(Box::new(markers));
+            }
+            Self::Estimated(markers) => markers.iter_mut().for_each(|m| m.push(v)),
+        }
+    }
+
+    fn estimates(mut self) -> [Option<f64>; 3] {
+        match &mut self {
+            Self::Exact(vals) => {
+                vals.sort_by(f64::total_cmp);
+                Self::PROBABILITIES.map(|p| quantile_sorted(vals, p))
+            }
+            Self::Estimated(markers) => markers.each_ref().map(|m| Some(m.heights[2])),
+        }
+    }
+}
+
+/// P² estimator of one quantile: five markers whose heights track the minimum, the quantile
+/// `p`, the maximum and the points halfway between, adjusted by piecewise-parabolic
+/// interpolation as values arrive.
+#[derive(Debug)]
+struct P2Quantile {
+    heights: [f64; 5],
+    /// Actual marker positions (1-based ranks).
+    positions: [f64; 5],
+    /// Desired marker positions.
+    desired: [f64; 5],
+    /// Increments of `desired` per value.
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    /// Markers placed on ascending `vals` (at least five), as if they had been streamed.
+    fn from_sorted(vals: &[f64], p: f64) -> Self {
+        let increments = [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0];
+        let last = (vals.len() - 1) as f64;
+        let desired = increments.map(|d| 1.0 + last * d);
+        let positions = desired.map(f64::round);
+        Self {
+            heights: positions.map(|n| vals[n as usize - 1]),
+            positions,
+            desired,
+            increments,
+        }
+    }
+
+    fn push(&mut self, v: f64) {
+        let (q, n) = (&mut self.heights, &mut self.positions);
+        // Cell k holds v: heights[k] <= v < heights[k + 1], extremes widened as needed.
+        let k = if v < q[0] {
+            q[0] = v;
+            0
+        } else if v >= q[4] {
+            q[4] = v;
+            3
+        } else {
+            (0..4).rfind(|&i| q[i] <= v).unwrap_or(0)
+        };
+        for pos in &mut n[k + 1..] {
+            *pos += 1.0;
+        }
+        for (d, inc) in self.desired.iter_mut().zip(self.increments) {
+            *d += inc;
+        }
+        for i in 1..4 {
+            let off = self.desired[i] - n[i];
+            if (off >= 1.0 && n[i + 1] - n[i] > 1.0) || (off <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = off.signum();
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > 0.0 { i + 1 } else { i - 1 };
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                n[i] += d;
+            }
+        }
+    }
+}
+
+/// Compound annual growth rate in percent between two `(year, value)` observations:
+/// `((last / first)^(1 / years) - 1) * 100`.
+///
+/// `None` unless `last` is later than `first` and both values are positive (growth rates of
+/// sign changes are undefined).
+///
+/// ```
+/// use wbi_rs::stats::cagr;
+///
+/// let g = cagr((2000, 100.0), (2002, 121.0)).unwrap();
+/// assert!((g - 10.0).abs() < 1e-9);
+/// assert_eq!(cagr((2000, -1.0), (2002, 1.0)), None);
+/// ```
+pub fn cagr(first: (i32, f64), last: (i32, f64)) -> Option<f64> {
+    let years = last.0 - first.0;
+    if years <= 0 || first.1 <= 0.0 || last.1 <= 0.0 {
+        return None;
+    }
+    Some(((last.1 / first.1).powf(1.0 / years as f64) - 1.0) * 100.0)
+}
+
+/// Quantile `q` in [0, 1] of ascending `vals`, interpolating linearly between the closest
+/// ranks (so `q = 0.5` is the median); `None` when empty.
+fn quantile_sorted(vals: &[f64], q: f64) -> Option<f64> {
+    let last = vals.len().checked_sub(1)?;
+    let h = last as f64 * q;
+    let (lo, hi) = (h.floor() as usize, h.ceil() as usize);
+    Some(vals[lo] + (h - lo as f64) * (vals[hi] - vals[lo]))
+}
+
+/// Cross-country statistics of one indicator in one year.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct YearlySummary {
+    pub indicator_id: String,
+    pub year: i32,
+    /// Number of countries with a finite value.
+    pub count: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    /// 10th, 25th, 75th and 90th percentiles, interpolated like [`Summary::p25`].
+    #[serde(default)]
+    pub p10: Option<f64>,
+    #[serde(default)]
+    pub p25: Option<f64>,
+    #[serde(default)]
+    pub p75: Option<f64>,
+    #[serde(default)]
+    pub p90: Option<f64>,
+}
+
+/// Summarize each `(indicator_id, year)` across countries, sorted by indicator then year.
+///
+/// Like [`grouped_summary`], only finite values count; a year where every country is missing
+/// yields `count == 0` and `None` statistics.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::yearly_summary;
+///
+/// let rows: Vec<DataPoint> = (0..=10)
+///     .map(|i| DataPoint { indicator_id: "X".into(), indicator_name: "Demo".into(),
+///         country_id: format!("C{i}"), country_name: format!("Country {i}"),
+///         country_iso3: format!("C{i:02}"), year: 2020, value: Some(i as f64),
+///         unit: None, obs_status: None, decimal: None })
+///     .collect();
+/// let s = &yearly_summary(&rows)[0];
+/// assert_eq!((s.p10, s.p25, s.median, s.p90), (Some(1.0), Some(2.5), Some(5.0), Some(9.0)));
+/// ```
+pub fn yearly_summary(points: &[DataPoint]) -> Vec<YearlySummary> {
+    use std::cmp::Ordering;
+    use std::collections::BTreeMap;
+
+    let mut groups: BTreeMap<(String, i32), Vec<f64>> = BTreeMap::new();
+    for p in points {
+        let vals = groups.entry((p.indicator_id.clone(), p.year)).or_default();
+        if let Some(v) = p.value.filter(|v| v.is_finite()) {
+            vals.push(v);
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((indicator_id, year), mut vals)| {
+            vals.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            let count = vals.len();
+            let median = match count {
+                0 => None,
+                n if n % 2 == 1 => Some(vals[n / 2]),
+                n => Some((vals[n / 2 - 1] + vals[n / 2]) / 2.0),
+            };
+            YearlySummary {
+                indicator_id,
+                year,
+                count,
+                min: vals.first().copied(),
+                max: vals.last().copied(),
+                mean: (count > 0).then(|| vals.iter().sum::<f64>() / count as f64),
+                median,
+                p10: quantile_sorted(&vals, 0.10),
+                p25: quantile_sorted(&vals, 0.25),
+                p75: quantile_sorted(&vals, 0.75),
+                p90: quantile_sorted(&vals, 0.90),
+            }
+        })
+        .collect()
+}
+
+/// Indicator id of total population, used as the denominator for [`per_capita`].
+pub const POPULATION_INDICATOR: &str = "SP.POP.TOTL";
+
+/// Base unit of a point: the API-provided unit, else the unit embedded in the indicator name.
+fn base_unit(p: &DataPoint) -> Option<String> {
+    p.unit
+        .clone()
+        .filter(|u| !u.is_empty())
+        .or_else(|| crate::viz::util::extract_unit_from_indicator_name(&p.indicator_name))
+}
+
+/// Divide every indicator by total population (`SP.POP.TOTL`) of the same country and year.
+///
+/// The population series itself is dropped from the output. Points without a matching,
+/// non-zero population become missing (`value: None`). Units and names gain a "per capita"
+/// suffix so axis titles stay truthful.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::{per_capita, POPULATION_INDICATOR};
+///
+/// let mk = |id: &str, v: f64| DataPoint { indicator_id: id.into(), indicator_name: "GDP (current US$)".into(),
+///     country_id: "DE".into(), country_name: "Germany".into(), country_iso3: "DEU".into(),
+///     year: 2020, value: Some(v), unit: None, obs_status: None, decimal: None };
+/// let out = per_capita(&[mk("GDP", 1000.0), mk(POPULATION_INDICATOR, 10.0)]);
+/// assert_eq!(out.len(), 1);
+/// assert_eq!(out[0].value, Some(100.0));
+/// assert_eq!(out[0].unit.as_deref(), Some("current US$ per capita"));
+/// ```
+pub fn per_capita(points: &[DataPoint]) -> Vec<DataPoint> {
+    use std::collections::HashMap;
+
+    let population: HashMap<(&str, i32), f64> = points
+        .iter()
+        .filter(|p| p.indicator_id == POPULATION_INDICATOR)
+        .filter_map(|p| Some(((p.country_iso3.as_str(), p.year), p.value?)))
+        .collect();
+
+    points
+        .iter()
+        .filter(|p| p.indicator_id != POPULATION_INDICATOR)
+        .map(|p| {
+            let pop = population
+                .get(&(p.country_iso3.as_str(), p.year))
+                .copied()
+                .filter(|v| *v != 0.0 && v.is_finite());
+            let mut out = p.clone();
+            out.value = match (p.value, pop) {
+                (Some(v), Some(pop)) => Some(v / pop),
+                _ => None,
+            };
+            out.unit = Some(match base_unit(p) {
+                Some(u) => format!("{u} per capita"),
+                None => "per capita".to_string(),
+            });
+            out.indicator_name = format!("{} per capita", p.indicator_name);
+            out.decimal = None;
+            out
+        })
+        .collect()
+}
+
+/// Indicator id of the GDP deflator (price index, base year varies by country), the usual
+/// `deflator` for [`deflate`].
+pub const DEFLATOR_INDICATOR: &str = "NY.GDP.DEFL.ZS";
+
+/// Convert current-price values to constant prices: divide each value by the `deflator` index
+/// (e.g. [`DEFLATOR_INDICATOR`]) of the same country and year, times 100.
+///
+/// Values end up in prices of the year in which the index is 100. For the World Bank's GDP
+/// deflator that base year differs between countries, so compare real growth, not levels,
+/// across countries. Points without a matching positive index become missing. Ids get a
+/// `~deflated` suffix, units change from "current" to "constant" and names name the deflator,
+/// so the derived series is neither mistaken for nor grouped with a fetched one.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::{deflate, DEFLATOR_INDICATOR};
+///
+/// let mk = |id: &str, name: &str, v: f64| DataPoint { indicator_id: id.into(),
+///     indicator_name: name.into(), country_id: "DE".into(), country_name: "Germany".into(),
+///     country_iso3: "DEU".into(), year: 2020, value: Some(v), unit: None, obs_status: None,
+///     decimal: None };
+/// let gdp = [mk("NY.GDP.MKTP.CD", "GDP (current US$)", 3300.0)];
+/// let deflator = [mk(DEFLATOR_INDICATOR, "GDP deflator", 110.0)];
+/// let out = deflate(&gdp, &deflator);
+/// assert_eq!(out[0].value, Some(3000.0));
+/// assert_eq!(out[0].indicator_id, "NY.GDP.MKTP.CD~deflated");
+/// assert_eq!(out[0].unit.as_deref(), Some("constant US$, deflator base year"));
+/// assert_eq!(out[0].indicator_name, "GDP (current US$) — deflated by GDP deflator");
+/// ```
+pub fn deflate(points: &[DataPoint], deflator: &[DataPoint]) -> Vec<DataPoint> {
+    use std::collections::HashMap;
+
+    let index: HashMap<(&str, i32), f64> = deflator
+        .iter()
+        .filter_map(|p| {
+            let v = p.value.filter(|v| v.is_finite() && *v > 0.0)?;
+            Some(((p.country_iso3.as_str(), p.year), v))
+        })
+        .collect();
+    let deflator_name = deflator
+        .first()
+        .map_or("deflator", |p| p.indicator_name.as_str());
+
+    points
+        .iter()
+        .map(|p| {
+            let d = index.get(&(p.country_iso3.as_str(), p.year)).copied();
+            let mut out = p.clone();
+            out.value = match (p.value, d) {
+                (Some(v), Some(d)) => Some(v / d * 100.0),
+                _ => None,
+            };
+            out.unit = Some(match base_unit(p) {
+                Some(u) => match u.strip_prefix("current ") {
+                    Some(currency) => format!("constant {currency}, deflator base year"),
+                    None => format!("{u}, deflated"),
+                },
+                None => "deflated".to_string(),
+            });
+            out.indicator_id = format!("{}~deflated", p.indicator_id);
+            out.indicator_name = format!("{} — deflated by {deflator_name}", p.indicator_name);
+            out.decimal = None;
+            out
+        })
+        .collect()
+}
+
+/// Rebase every `(indicator_id, country_iso3)` series so that `base_year = 100`.
+///
+/// Series without a finite, non-zero value in `base_year` become entirely missing.
+pub fn index_to_year(points: &[DataPoint], base_year: i32) -> Vec<DataPoint> {
+    use std::collections::HashMap;
+
+    let base: HashMap<(&str, &str), f64> = points
+        .iter()
+        .filter(|p| p.year == base_year)
+        .filter_map(|p| {
+            let v = p.value.filter(|v| *v != 0.0 && v.is_finite())?;
+            Some(((p.indicator_id.as_str(), p.country_iso3.as_str()), v))
+        })
+        .collect();
+
+    points
+        .iter()
+        .map(|p| {
+            let b = base
+                .get(&(p.indicator_id.as_str(), p.country_iso3.as_str()))
+                .copied();
+            let mut out = p.clone();
+            out.value = match (p.value, b) {
+                (Some(v), Some(b)) => Some(v / b * 100.0),
+                _ => None,
+            };
+            out.unit = Some(format!("index, {base_year} = 100"));
+            out.indicator_name = format!("{} — index, {base_year} = 100", p.indicator_name);
+            out.decimal = None;
+            out
+        })
+        .collect()
+}
+
+/// Year-over-year growth in percent: `(v[t] / v[t-1] - 1) * 100` per series.
+///
+/// Only consecutive years are compared; the first year of a series (or one following a gap)
+/// becomes missing.
+pub fn yoy_growth(points: &[DataPoint]) -> Vec<DataPoint> {
+    use std::collections::HashMap;
+
+    let by_key: HashMap<(&str, &str, i32), f64> = points
+        .iter()
+        .filter_map(|p| {
+            Some((
+                (p.indicator_id.as_str(), p.country_iso3.as_str(), p.year),
+                p.value.filter(|v| v.is_finite())?,
+            ))
+        })
+        .collect();
+
+    points
+        .iter()
+        .map(|p| {
+            let prev = by_key
+                .get(&(p.indicator_id.as_str(), p.country_iso3.as_str(), p.year - 1))
+                .copied()
+                .filter(|v| *v != 0.0);
+            let mut out = p.clone();
+            out.value = match (p.value, prev) {
+                (Some(v), Some(prev)) => Some((v / prev - 1.0) * 100.0),
+                _ => None,
+            };
+            out.unit = Some("annual % change".to_string());
+            out.indicator_name = format!("{} — YoY growth", p.indicator_name);
+            out.decimal = None;
+            out
+        })
+        .collect()
+}
+
+/// How [`compare_countries`] combines the two countries' values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CountryComparison {
+    /// First country minus second, in the indicator's own unit.
+    #[default]
+    Difference,
+    /// First country divided by second.
+    Ratio,
+}
+
+/// Derive one series per indicator comparing country `a` with country `b` (ISO3 or ISO2 codes,
+/// case ignored) year by year; rows of other countries are dropped.
+///
+/// The output series is labelled `"A-B"` (difference) or `"A/B"` (ratio) in place of a
+/// country, and names and units say what was computed: a difference keeps the indicator's
+/// unit, a ratio is unitless. Years where either value is missing (or `b` is zero for a ratio)
+/// become missing.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::{compare_countries, CountryComparison};
+///
+/// let mk = |iso3: &str, name: &str, v: f64| DataPoint { indicator_id: "GDP".into(),
+///     indicator_name: "GDP (current US$)".into(), country_id: iso3[..2].into(),
+///     country_name: name.into(), country_iso3: iso3.into(), year: 2020, value: Some(v),
+///     unit: None, obs_status: None, decimal: None };
+/// let rows = [mk("DEU", "Germany", 30.0), mk("FRA", "France", 20.0)];
+/// let out = compare_countries(&rows, "DEU", "FRA", CountryComparison::Difference);
+/// assert_eq!(out[0].value, Some(10.0));
+/// assert_eq!(out[0].country_name, "Germany minus France");
+/// assert_eq!(out[0].unit.as_deref(), Some("current US$"));
+/// ```
+pub fn compare_countries(
+    points: &[DataPoint],
+    a: &str,
+    b: &str,
+    comparison: CountryComparison,
+) -> Vec<DataPoint> {
+    use std::collections::HashMap;
+
+    let is = |p: &DataPoint, code: &str| {
+        code.eq_ignore_ascii_case(&p.country_iso3) || code.eq_ignore_ascii_case(&p.country_id)
+    };
+    let second: HashMap<(&str, i32), &DataPoint> = points
+        .iter()
+        .filter(|p| is(p, b))
+        .map(|p| ((p.indicator_id.as_str(), p.year), p))
+        .collect();
+
+    let (b_code, b_name) = points
+        .iter()
+        .find(|p| is(p, b))
+        .map_or((b.to_ascii_uppercase(), b.to_ascii_uppercase()), |p| {
+            (p.country_iso3.clone(), p.country_name.clone())
+        });
+
+    points
+        .iter()
+        .filter(|p| is(p, a))
+        .map(|p| {
+            let a_code = &p.country_iso3;
+            let other = second
+                .get(&(p.indicator_id.as_str(), p.year))
+                .and_then(|o| o.value);
+            let mut out = p.clone();
+            out.value = match (p.value, other) {
+                (Some(x), Some(y)) => match comparison {
+                    CountryComparison::Difference => Some(x - y),
+                    CountryComparison::Ratio => (y != 0.0).then(|| x / y),
+                },
+                _ => None,
+            };
+            let (code, name, unit, what) = match comparison {
+                CountryComparison::Difference => (
+                    format!("{a_code}-{b_code}"),
+                    format!("{} minus {b_name}", p.country_name),
+                    base_unit(p).unwrap_or_else(|| "difference".to_string()),
+                    "difference",
+                ),
+                CountryComparison::Ratio => (
+                    format!("{a_code}/{b_code}"),
+                    format!("{} / {b_name}", p.country_name),
+                    format!("ratio, {a_code} / {b_code}"),
+                    "ratio",
+                ),
+            };
+            out.indicator_name = format!("{} — {what}", p.indicator_name);
+            out.country_id = code.clone();
+            out.country_iso3 = code;
+            out.country_name = name;
+            out.unit = Some(unit);
+            out.decimal = None;
+            out
+        })
+        .collect()
+}
+
+/// How [`custom_aggregates`] combines the members' values of an indicator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AggregateMethod {
+    /// By indicator, see [`AggregateMethod::for_indicator`].
+    #[default]
+    Auto,
+    /// Total of the members' values, for counts and amounts.
+    Sum,
+    /// Unweighted mean of the members' values.
+    Mean,
+    /// Mean weighted by the members' total population ([`POPULATION_INDICATOR`]) in the same
+    /// year, for percentages, rates and per-capita values.
+    PopulationWeighted,
+}
+
+impl AggregateMethod {
+    /// What [`AggregateMethod::Auto`] uses for the indicator of `p`: a population-weighted mean
+    /// when its name or unit speaks of a percentage, rate, ratio, index or deflator, years or a
+    /// "per" quantity (`GDP per capita`, `per 1,000 people`), else the sum.
+    pub fn for_indicator(p: &DataPoint) -> Self {
+        const INTENSIVE: [&str; 9] = [
+            "per", "rate", "ratio", "index", "deflator", "years", "average", "share", "growth",
+        ];
+        let text = format!(
+            "{} {}",
+            p.indicator_name,
+            p.unit.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+        let intensive = text.contains('%')
+            || text
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| INTENSIVE.contains(&word));
+        if intensive {
+            Self::PopulationWeighted
+        } else {
+            Self::Sum
+        }
+    }
+}
+
+/// A user-defined group of economies reported as one, like the Nordics.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomAggregate {
+    /// Code of the aggregate's rows (`country_iso3` and `country_id`), e.g. `NRD`.
+    pub code: String,
+    /// Display name (`country_name`); the code when empty.
+    #[serde(default)]
+    pub name: String,
+    /// Member codes, ISO3 or ISO2 (case-insensitive).
+    pub members: Vec<String>,
+    #[serde(default)]
+    pub method: AggregateMethod,
+}
+
+/// Rows of each aggregate in `aggregates`: one per indicator and year any member has a row,
+/// tagged with the aggregate's code and name. Add them to `points` to treat the aggregates
+/// like countries.
+///
+/// A year's value is missing unless every member has a finite value (and, for
+/// [`AggregateMethod::PopulationWeighted`], a population), so a partial total never passes
+/// for the group's. Names and units are those of the indicator.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::{custom_aggregates, CustomAggregate, POPULATION_INDICATOR};
+///
+/// let mk = |id: &str, name: &str, iso3: &str, v: f64| DataPoint { indicator_id: id.into(),
+///     indicator_name: name.into(), country_id: iso3[..2].into(), country_name: iso3.into(),
+///     country_iso3: iso3.into(), year: 2020, value: Some(v), unit: None, obs_status: None,
+///     decimal: None };
+/// let rows = [
+///     mk(POPULATION_INDICATOR, "Population, total", "DNK", 6.0),
+///     mk(POPULATION_INDICATOR, "Population, total", "NOR", 5.0),
+///     mk("SL.UEM.TOTL.ZS", "Unemployment (%)", "DNK", 5.0),
+///     mk("SL.UEM.TOTL.ZS", "Unemployment (%)", "NOR", 5.0 + 1.1),
+/// ];
+/// let nordics = CustomAggregate {
+///     code: "NRD".into(), name: "Nordics".into(), members: vec!["DNK".into(), "NO".into()],
+///     ..Default::default()
+/// };
+/// let out = custom_aggregates(&rows, &[nordics]);
+/// assert!((out[0].value.unwrap() - 5.5).abs() < 1e-9); // unemployment: weighted by population
+/// assert_eq!(out[1].value, Some(11.0)); // population: summed
+/// assert_eq!((out[0].country_iso3.as_str(), out[0].country_name.as_str()), ("NRD", "Nordics"));
+/// ```
+pub fn custom_aggregates(points: &[DataPoint], aggregates: &[CustomAggregate]) -> Vec<DataPoint> {
+    use std::collections::{BTreeMap, HashMap};
+
+    let mut out = Vec::new();
+    for agg in aggregates {
+        // The member each row belongs to, by its position in `members`.
+        let member = |p: &DataPoint| {
+            agg.members.iter().position(|m| {
+                m.eq_ignore_ascii_case(&p.country_iso3) || m.eq_ignore_ascii_case(&p.country_id)
+            })
+        };
+        let mut cells: BTreeMap<(&str, i32), Vec<Option<&DataPoint>>> = BTreeMap::new();
+        let mut population: HashMap<(usize, i32), f64> = HashMap::new();
+        for p in points {
+            let Some(m) = member(p) else { continue };
+            cells
+                .entry((p.indicator_id.as_str(), p.year))
+                .or_insert_with(|| vec![None; agg.members.len()])[m] = Some(p);
+            if p.indicator_id == POPULATION_INDICATOR
+                && let Some(v) = p.value.filter(|v| v.is_finite() && *v > 0.0)
+            {
+                population.insert((m, p.year), v);
+            }
+        }
+
+        for ((_, year), rows) in cells {
+            let first = rows.iter().flatten().next().expect("cells hold a row");
+            let values: Option<Vec<(usize, f64)>> = rows
+                .iter()
+                .enumerate()
+                .map(|(m, p)| Some((m, (*p)?.value.filter(|v| v.is_finite())?)))
+                .collect();
+            let method = match agg.method {
+                AggregateMethod::Auto => AggregateMethod::for_indicator(first),
+                method => method,
+            };
+            let value = values.and_then(|values| match method {
+                AggregateMethod::Sum | AggregateMethod::Auto => {
+                    Some(values.iter().map(|(_, v)| v).sum())
+                }
+                AggregateMethod::Mean => {
+                    Some(values.iter().map(|(_, v)| v).sum::<f64>() / values.len() as f64)
+                }
+                AggregateMethod::PopulationWeighted => {
+                    let (mut sum, mut weights) = (0.0, 0.0);
+                    for (m, v) in values {
+                        let w = population.get(&(m, year))?;
+                        sum += v * w;
+                        weights += w;
+                    }
+                    Some(sum / weights)
+                }
+            });
+            out.push(DataPoint {
+                country_id: agg.code.clone(),
+                country_iso3: agg.code.clone(),
+                country_name: if agg.name.is_empty() {
+                    agg.code.clone()
+                } else {
+                    agg.name.clone()
+                },
+                value,
+                obs_status: None,
+                decimal: None,
+                ..(*first).clone()
+            });
+        }
+    }
+    out
+}
+
+/// Drop rows without a finite value.
+pub fn drop_missing(points: &[DataPoint]) -> Vec<DataPoint> {
+    points
+        .iter()
+        .filter(|p| p.value.is_some_and(f64::is_finite))
+        .cloned()
+        .collect()
+}
+
+/// Drop the rows of every series matched by one of `patterns`. A pattern is a country (ISO3
+/// or ISO2 code), an indicator id, or `COUNTRY:INDICATOR` for a single series; case is ignored.
+pub fn exclude_series(points: &[DataPoint], patterns: &[String]) -> Vec<DataPoint> {
+    let matches = |p: &DataPoint, pattern: &str| {
+        let country = |c: &str| {
+            c.eq_ignore_ascii_case(&p.country_iso3) || c.eq_ignore_ascii_case(&p.country_id)
+        };
+        match pattern.split_once(':') {
+            Some((c, i)) => country(c) && i.eq_ignore_ascii_case(&p.indicator_id),
+            None => country(pattern) || pattern.eq_ignore_ascii_case(&p.indicator_id),
+        }
+    };
+    points
+        .iter()
+        .filter(|p| !patterns.iter().any(|pat| matches(p, pat.trim())))
+        .cloned()
+        .collect()
+}
+
+/// Keep only the `(indicator_id, country_iso3)` series that have a finite value in at least
+/// `min_fraction` (0–1) of the years present in `points`.
+///
+/// The API returns a row for every requested year, so "years present" is the requested span.
+pub fn filter_coverage(points: &[DataPoint], min_fraction: f64) -> Vec<DataPoint> {
+    use std::collections::{BTreeSet, HashMap};
+
+    let span = points.iter().map(|p| p.year).collect::<BTreeSet<_>>().len();
+    let mut covered: HashMap<(&str, &str), usize> = HashMap::new();
+    for p in points {
+        let n = covered
+            .entry((p.indicator_id.as_str(), p.country_iso3.as_str()))
+            .or_default();
+        if p.value.is_some_and(f64::is_finite) {
+            *n += 1;
+        }
+    }
+    points
+        .iter()
+        .filter(|p| {
+            let n = covered[&(p.indicator_id.as_str(), p.country_iso3.as_str())];
+            n as f64 >= min_fraction * span as f64
+        })
+        .cloned()
+        .collect()
+}
+
+/// Value used by [`top_countries`] to rank countries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RankBy {
+    /// Most recent finite value.
+    #[default]
+    Last,
+    /// Mean over all finite values.
+    Mean,
+    /// Value in the given year.
+    Year(i32),
+}
+
+/// Keep all rows of the `n` countries ranking highest on `indicator_id`.
+///
+/// Only countries with a finite ranking value are ranked; ties keep ISO3 order. When fewer
+/// than `n` have one, those are kept with a warning, and when none has one (e.g. `by` names
+/// a year without data) this is an error rather than an arbitrary pick. Rows of other
+/// indicators are kept for the selected countries, so the result can still be plotted or
+/// exported as a whole.
+pub fn top_countries(
+    points: &[DataPoint],
+    indicator_id: &str,
+    n: usize,
+    by: RankBy,
+) -> anyhow::Result<Vec<DataPoint>> {
+    use std::collections::{BTreeMap, HashSet};
+
+    // iso3 -> finite (year, value) pairs of the ranking indicator
+    let mut series: BTreeMap<&str, Vec<(i32, f64)>> = BTreeMap::new();
+    for p in points {
+        let vals = series.entry(p.country_iso3.as_str()).or_default();
+        if p.indicator_id == indicator_id
+            && let Some(v) = p.value.filter(|v| v.is_finite())
+        {
+            vals.push((p.year, v));
+        }
+    }
+
+    let mut ranked: Vec<(&str, f64)> = series
+        .into_iter()
+        .filter_map(|(iso3, vals)| {
+            let score = match by {
+                RankBy::Last => vals.iter().max_by_key(|(y, _)| *y).map(|&(_, v)| v),
+                RankBy::Mean if vals.is_empty() => None,
+                RankBy::Mean => Some(vals.iter().map(|(_, v)| v).sum::<f64>() / vals.len() as f64),
+                RankBy::Year(year) => vals.iter().find(|(y, _)| *y == year).map(|&(_, v)| v),
+            };
+            Some((iso3, score?))
+        })
+        .collect();
+    if ranked.is_empty() {
+        let of = match by {
+            RankBy::Year(year) => format!(" in {year}"),
+            _ => String::new(),
+        };
+        anyhow::bail!("no country has a value of {indicator_id}{of} to rank by");
+    }
+    if ranked.len() < n {
+        tracing::warn!(
+            "only {} countries have a value of {indicator_id} to rank by; keeping those instead of the top {n}",
+            ranked.len()
+        );
+    }
+    // Stable sort: descending score.
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let keep: HashSet<&str> = ranked.into_iter().take(n).map(|(iso3, _)| iso3).collect();
+    Ok(points
+        .iter()
+        .filter(|p| keep.contains(p.country_iso3.as_str()))
+        .cloned()
+        .collect())
+}
+
+/// Position of one country in [`rank_by_year`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RankEntry {
+    pub country_iso3: String,
+    pub country_name: String,
+    /// 1 for the highest value; ties share a rank and skip the following ones (1, 2, 2, 4).
+    pub rank: usize,
+    pub value: f64,
+    /// Rank in the baseline year; `None` without a baseline or a value in it.
+    pub baseline_rank: Option<usize>,
+    pub baseline_value: Option<f64>,
+    /// Places gained since the baseline (`baseline_rank - rank`): positive moved up.
+    pub change: Option<i64>,
+}
+
+/// Rank countries by their value of `indicator_id` in `year`, highest first, and compare
+/// with their rank in `baseline`.
+///
+/// Only countries with a finite value in `year` are listed; equal values keep ISO3 order.
+/// Baseline ranks are taken among all countries with a value in `baseline`, so a country
+/// missing in `year` still counts there.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::rank_by_year;
+///
+/// let mk = |iso3: &str, year: i32, v: f64| DataPoint { indicator_id: "X".into(),
+///     indicator_name: "Demo".into(), country_id: iso3[..2].into(), country_name: iso3.into(),
+///     country_iso3: iso3.into(), year, value: Some(v), unit: None, obs_status: None, decimal: None };
+/// let rows = vec![mk("AAA", 2010, 1.0), mk("BBB", 2010, 2.0), mk("AAA", 2020, 5.0), mk("BBB", 2020, 3.0)];
+/// let ranks = rank_by_year(&rows, "X", 2020, Some(2010));
+/// assert_eq!((ranks[0].country_iso3.as_str(), ranks[0].rank, ranks[0].change), ("AAA", 1, Some(1)));
+/// assert_eq!((ranks[1].country_iso3.as_str(), ranks[1].change), ("BBB", Some(-1)));
+/// ```
+pub fn rank_by_year(
+    points: &[DataPoint],
+    indicator_id: &str,
+    year: i32,
+    baseline: Option<i32>,
+) -> Vec<RankEntry> {
+    use std::collections::{BTreeMap, HashMap};
+
+    // (iso3, name, value, rank) of every country with a value in `year`, highest first.
+    let ranked = |year: i32| -> Vec<(&str, &str, f64, usize)> {
+        let mut vals: BTreeMap<&str, (&str, f64)> = BTreeMap::new();
+        for p in points {
+            if p.indicator_id == indicator_id
+                && p.year == year
+                && let Some(v) = p.value.filter(|v| v.is_finite())
+            {
+                vals.insert(&p.country_iso3, (&p.country_name, v));
+            }
+        }
+        let mut sorted: Vec<(&str, &str, f64)> = vals
+            .into_iter()
+            .map(|(iso3, (name, v))| (iso3, name, v))
+            .collect();
+        sorted.sort_by(|a, b| b.2.total_cmp(&a.2));
+        let mut out: Vec<(&str, &str, f64, usize)> = Vec::with_capacity(sorted.len());
+        for (i, (iso3, name, v)) in sorted.into_iter().enumerate() {
+            let rank = match out.last() {
+                Some(&(_, _, prev, r)) if prev == v => r,
+                _ => i + 1,
+            };
+            out.push((iso3, name, v, rank));
+        }
+        out
+    };
+
+    let base: HashMap<&str, (f64, usize)> = baseline
+        .map(|b| {
+            ranked(b)
+                .into_iter()
+                .map(|(iso3, _, v, rank)| (iso3, (v, rank)))
+                .collect()
+        })
+        .unwrap_or_default();
+    ranked(year)
+        .into_iter()
+        .map(|(iso3, name, value, rank)| {
+            let before = base.get(iso3).copied();
+            RankEntry {
+                country_iso3: iso3.to_string(),
+                country_name: name.to_string(),
+                rank,
+                value,
+                baseline_rank: before.map(|(_, r)| r),
+                baseline_value: before.map(|(v, _)| v),
+                change: before.map(|(_, r)| r as i64 - rank as i64),
+            }
+        })
+        .collect()
+}
+
+/// Pairwise Pearson correlations between indicators.
+///
+/// Observations are paired on `(country_iso3, year)`, so the matrix describes how indicators
+/// move together across countries and years.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CorrelationMatrix {
+    /// Indicator ids in row/column order (sorted).
+    pub indicators: Vec<String>,
+    /// `values[i][j]` correlates `indicators[i]` with `indicators[j]`; `None` when fewer than
+    /// three paired observations exist or either side is constant.
+    pub values: Vec<Vec<Option<f64>>>,
+    /// Number of paired observations behind each cell.
+    pub counts: Vec<Vec<usize>>,
+}
+
+/// Compute the [`CorrelationMatrix`] of all indicators in `points`.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::correlation_matrix;
+///
+/// let mk = |id: &str, year: i32, v: f64| DataPoint { indicator_id: id.into(), indicator_name: id.into(),
+///     country_id: "DE".into(), country_name: "Germany".into(), country_iso3: "DEU".into(),
+///     year, value: Some(v), unit: None, obs_status: None, decimal: None };
+/// let rows: Vec<DataPoint> = (0..4)
+///     .flat_map(|t| [mk("A", 2000 + t, t as f64), mk("B", 2000 + t, -2.0 * t as f64)])
+///     .collect();
+/// let m = correlation_matrix(&rows);
+/// assert_eq!(m.indicators, ["A", "B"]);
+/// assert!((m.values[0][1].unwrap() + 1.0).abs() < 1e-12);
+/// ```
+pub fn correlation_matrix(points: &[DataPoint]) -> CorrelationMatrix {
+    use std::collections::{BTreeMap, HashMap};
+
+    let mut by_indicator: BTreeMap<&str, HashMap<(&str, i32), f64>> = BTreeMap::new();
+    for p in points {
+        if let Some(v) = p.value.filter(|v| v.is_finite()) {
+            by_indicator
+                .entry(p.indicator_id.as_str())
+                .or_default()
+                .insert((p.country_iso3.as_str(), p.year), v);
+        }
+    }
+
+    let indicators: Vec<String> = by_indicator.keys().map(|s| s.to_string()).collect();
+    let series: Vec<&HashMap<(&str, i32), f64>> = by_indicator.values().collect();
+    let k = series.len();
+    let mut values = vec![vec![None; k]; k];
+    let mut counts = vec![vec![0; k]; k];
+    for i in 0..k {
+        for j in i..k {
+            let pairs: Vec<(f64, f64)> = series[i]
+                .iter()
+                .filter_map(|(key, a)| Some((*a, *series[j].get(key)?)))
+                .collect();
+            let r = pearson(&pairs);
+            values[i][j] = r;
+            values[j][i] = r;
+            counts[i][j] = pairs.len();
+            counts[j][i] = pairs.len();
+        }
+    }
+    CorrelationMatrix {
+        indicators,
+        values,
+        counts,
+    }
+}
+
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 3 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let (mx, my) = (
+        pairs.iter().map(|p| p.0).sum::<f64>() / n,
+        pairs.iter().map(|p| p.1).sum::<f64>() / n,
+    );
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        sxy += (x - mx) * (y - my);
+        sxx += (x - mx).powi(2);
+        syy += (y - my).powi(2);
+    }
+    if sxx == 0.0 || syy == 0.0 {
+        return None;
+    }
+    Some((sxy / (sxx * syy).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// How far projected values are from the actual observations of one `(indicator, country)`
+/// series, over the years where both have a finite value.
+///
+/// Errors are `projected - actual`, so a positive `mean_error` means the projection ran high.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Divergence {
+    pub key: GroupKey,
+    /// Number of years with both an actual and a projected value.
+    pub count: usize,
+    /// Mean signed error (bias).
+    pub mean_error: Option<f64>,
+    /// Mean absolute error.
+    pub mae: Option<f64>,
+    /// Root mean squared error.
+    pub rmse: Option<f64>,
+    /// Mean absolute percentage error, over the years whose actual value is not zero.
+    pub mape: Option<f64>,
+    /// Largest absolute error and the year it occurred in.
+    pub max_abs_error: Option<(i32, f64)>,
+}
+
+/// Compare `projected` against `actual` per `(indicator_id, country_iso3)`; sorted by key.
+///
+/// Every projected series gets a row; one that shares no year with the actuals has
+/// `count == 0` and `None` statistics.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::divergence;
+///
+/// let mk = |year: i32, v: f64| DataPoint { indicator_id: "X".into(), indicator_name: "Demo".into(),
+///     country_id: "DE".into(), country_name: "Germany".into(), country_iso3: "DEU".into(),
+///     year, value: Some(v), unit: None, obs_status: None, decimal: None };
+/// let actual = vec![mk(2020, 100.0), mk(2021, 110.0)];
+/// let projected = vec![mk(2020, 104.0), mk(2021, 108.0), mk(2022, 115.0)];
+/// let d = divergence(&actual, &projected);
+/// assert_eq!(d[0].count, 2);
+/// assert_eq!(d[0].mean_error, Some(1.0));
+/// assert_eq!(d[0].mae, Some(3.0));
+/// assert_eq!(d[0].max_abs_error, Some((2020, 4.0)));
+/// ```
+pub fn divergence(actual: &[DataPoint], projected: &[DataPoint]) -> Vec<Divergence> {
+    use std::collections::{BTreeMap, HashMap};
+
+    let observed: HashMap<(&str, &str, i32), f64> = actual
+        .iter()
+        .filter_map(|p| {
+            let v = p.value.filter(|v| v.is_finite())?;
+            Some((
+                (p.indicator_id.as_str(), p.country_iso3.as_str(), p.year),
+                v,
+            ))
+        })
+        .collect();
+
+    // (year, actual, projected) per projected series.
+    let mut pairs: BTreeMap<GroupKey, Vec<(i32, f64, f64)>> = BTreeMap::new();
+    for p in projected {
+        let key = GroupKey::new(p.indicator_id.clone(), p.country_iso3.clone());
+        let series = pairs.entry(key).or_default();
+        let Some(v) = p.value.filter(|v| v.is_finite()) else {
+            continue;
+        };
+        if let Some(&a) = observed.get(&(p.indicator_id.as_str(), p.country_iso3.as_str(), p.year))
+        {
+            series.push((p.year, a, v));
+        }
+    }
+
+    pairs
+        .into_iter()
+        .map(|(key, mut obs)| {
+            obs.sort_by_key(|o| o.0);
+            let count = obs.len();
+            let n = count as f64;
+            let errors: Vec<(i32, f64)> = obs.iter().map(|&(y, a, v)| (y, v - a)).collect();
+            let mean = |f: &dyn Fn(f64) -> f64| {
+                (count > 0).then(|| errors.iter().map(|&(_, e)| f(e)).sum::<f64>() / n)
+            };
+            let pct: Vec<f64> = obs
+                .iter()
+                .filter(|o| o.1 != 0.0)
+                .map(|&(_, a, v)| ((v - a) / a).abs() * 100.0)
+                .collect();
+            Divergence {
+                key,
+                count,
+                mean_error: mean(&|e| e),
+                mae: mean(&|e| e.abs()),
+                rmse: mean(&|e| e * e).map(f64::sqrt),
+                mape: (!pct.is_empty()).then(|| pct.iter().sum::<f64>() / pct.len() as f64),
+                // The earliest year wins a tie.
+                max_abs_error: errors.iter().fold(None, |best, &(y, e)| match best {
+                    Some((_, b)) if b >= e.abs() => best,
+                    _ => Some((y, e.abs())),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Data quality of one `(indicator, country)` series, see [`quality`]. Shares, volatility and
+/// the score are fractions (0.25 = 25%).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeriesQuality {
+    pub key: GroupKey,
+    /// Share of the years present in the input that have a finite value.
+    pub completeness: f64,
+    /// Latest year with a finite value.
+    pub last_year: Option<i32>,
+    /// Years from `last_year` to the latest year in the input; `None` without any value.
+    pub years_since_last: Option<i32>,
+    /// Share of the finite values flagged with an `obs_status` (estimates, forecasts, breaks).
+    pub estimate_share: f64,
+    /// Mean relative change between consecutive stored versions of a value; `None` without a
+    /// revision history (see [`quality_with_history`]).
+    pub revision_volatility: Option<f64>,
+    /// Mean of completeness, recency (1 for a value in the latest year, 0 after
+    /// [`QUALITY_RECENCY_YEARS`] years), `1 - estimate_share` and, with a history,
+    /// `1 - revision_volatility` (floored at 0). Series without values score 0.
+    pub score: f64,
+}
+
+/// Years without a new value after which a series counts as not recent at all in
+/// [`SeriesQuality::score`].
+pub const QUALITY_RECENCY_YEARS: i32 = 10;
+
+/// Completeness, recency and estimate share of every `(indicator_id, country_iso3)` series,
+/// with an overall score; sorted by key.
+///
+/// As in [`filter_coverage`], completeness is measured against the years present in `points`,
+/// which for API results is the requested span. Recency counts from the latest of those years.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::quality;
+///
+/// let mk = |year: i32, v: Option<f64>, status: Option<&str>| DataPoint {
+///     indicator_id: "X".into(), indicator_name: "Demo".into(), country_id: "DE".into(),
+///     country_name: "Germany".into(), country_iso3: "DEU".into(), year, value: v,
+///     unit: None, obs_status: status.map(Into::into), decimal: None };
+/// let rows = vec![mk(2020, Some(1.0), None), mk(2021, Some(2.0), Some("E")), mk(2022, None, None)];
+/// let q = &quality(&rows)[0];
+/// assert_eq!(q.last_year, Some(2021));
+/// assert_eq!(q.years_since_last, Some(1));
+/// assert_eq!(q.estimate_share, 0.5);
+/// assert!((q.completeness - 2.0 / 3.0).abs() < 1e-12);
+/// ```
+pub fn quality(points: &[DataPoint]) -> Vec<SeriesQuality> {
+    quality_with_history(points, &[])
+}
+
+/// [`quality`] with the revision volatility of each series taken from `history`: every stored
+/// version of its observations in retrieval order, e.g. from
+/// `storage::load_sqlite_history` (feature `sqlite`).
+///
+/// A value's versions are compared pairwise in order; the series' volatility is the mean
+/// `|new - old| / |old|` over all such pairs (old values of zero are skipped), and 0 when its
+/// observations were never revised. Series absent from `history` keep `None`.
+pub fn quality_with_history(points: &[DataPoint], history: &[DataPoint]) -> Vec<SeriesQuality> {
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+    let years: BTreeSet<i32> = points.iter().map(|p| p.year).collect();
+    let latest = years.last().copied();
+
+    #[derive(Default)]
+    struct Tally {
+        finite: usize,
+        flagged: usize,
+        last_year: Option<i32>,
+    }
+    let mut series: BTreeMap<GroupKey, Tally> = BTreeMap::new();
+    for p in points {
+        let tally = series
+            .entry(GroupKey::new(&p.indicator_id, &p.country_iso3))
+            .or_default();
+        if p.value.is_some_and(f64::is_finite) {
+            tally.finite += 1;
+            if p.obs_status
+                .as_deref()
+                .is_some_and(|s| !s.trim().is_empty())
+            {
+                tally.flagged += 1;
+            }
+            tally.last_year = tally.last_year.max(Some(p.year));
+        }
+    }
+
+    // Relative revisions per series, from consecutive versions of each observation.
+    let mut previous: HashMap<(&str, &str, i32), f64> = HashMap::new();
+    let mut revisions: HashMap<(&str, &str), Vec<f64>> = HashMap::new();
+    for p in history {
+        let key = (p.indicator_id.as_str(), p.country_iso3.as_str());
+        let changes = revisions.entry(key).or_default();
+        let Some(v) = p.value.filter(|v| v.is_finite()) else {
+            continue;
+        };
+        if let Some(old) = previous.insert((key.0, key.1, p.year), v)
+            && old != 0.0
+        {
+            changes.push((v - old).abs() / old.abs());
+        }
+    }
+
+    series
+        .into_iter()
+        .map(|(key, t)| {
+            let completeness = if years.is_empty() {
+                0.0
+            } else {
+                t.finite as f64 / years.len() as f64
+            };
+            let years_since_last = t.last_year.zip(latest).map(|(last, end)| end - last);
+            let estimate_share = if t.finite == 0 {
+                0.0
+            } else {
+                t.flagged as f64 / t.finite as f64
+            };
+            let revision_volatility = revisions
+                .get(&(key.indicator_id.as_str(), key.country_iso3.as_str()))
+                .map(|changes| {
+                    if changes.is_empty() {
+                        0.0
+                    } else {
+                        changes.iter().sum::<f64>() / changes.len() as f64
+                    }
+                });
+            let score = match years_since_last {
+                None => 0.0,
+                Some(lag) => {
+                    let recency =
+                        1.0 - lag.min(QUALITY_RECENCY_YEARS) as f64 / QUALITY_RECENCY_YEARS as f64;
+                    let mut parts = vec![completeness, recency, 1.0 - estimate_share];
+                    parts.extend(revision_volatility.map(|v| (1.0 - v).max(0.0)));
+                    parts.iter().sum::<f64>() / parts.len() as f64
+                }
+            };
+            SeriesQuality {
+                key,
+                completeness,
+                last_year: t.last_year,
+                years_since_last,
+                estimate_share,
+                revision_volatility,
+                score,
+            }
+        })
+        .collect()
+}
+
+/// Keep only the series whose [`quality`] score is at least `min_score` (0–1).
+pub fn filter_quality(points: &[DataPoint], min_score: f64) -> Vec<DataPoint> {
+    use std::collections::HashSet;
+
+    let keep: HashSet<GroupKey> = quality(points)
+        .into_iter()
+        .filter(|q| q.score >= min_score)
+        .map(|q| q.key)
+        .collect();
+    points
+        .iter()
+        .filter(|p| keep.contains(&GroupKey::new(&p.indicator_id, &p.country_iso3)))
+        .cloned()
+        .collect()
+}
+
+/// What the rows or the columns of [`to_ndarray`] stand for.
+#[cfg(feature = "ndarray")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PivotKey {
+    /// One per country (ISO3 code).
+    Country,
+    /// One per indicator id.
+    Indicator,
+    /// One per year.
+    Year,
+    /// One per country and year (`DEU:2020`): the observations of a panel.
+    CountryYear,
+    /// One per indicator and country (`SP.POP.TOTL:DEU`): the series of a time-series matrix.
+    Series,
+}
+
+#[cfg(feature = "ndarray")]
+impl PivotKey {
+    fn label(self, p: &DataPoint) -> (String, Option<i32>) {
+        match self {
+            PivotKey::Country => (p.country_iso3.clone(), None),
+            PivotKey::Indicator => (p.indicator_id.clone(), None),
+            PivotKey::Year => (String::new(), Some(p.year)),
+            PivotKey::CountryYear => (p.country_iso3.clone(), Some(p.year)),
+            PivotKey::Series => (format!("{}:{}", p.indicator_id, p.country_iso3), None),
+        }
+    }
+}
+
+/// Layout of [`to_ndarray`], e.g. `rows: CountryYear, cols: Indicator` for a regression design
+/// matrix or `rows: Series, cols: Year` for time series.
+#[cfg(feature = "ndarray")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PivotSpec {
+    pub rows: PivotKey,
+    pub cols: PivotKey,
+}
+
+/// Row and column labels of a [`to_ndarray`] matrix, in matrix order.
+#[cfg(feature = "ndarray")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PivotAxes {
+    pub rows: Vec<String>,
+    pub cols: Vec<String>,
+}
+
+/// Observations as a dense matrix (feature `ndarray`), for PCA or regression with
+/// ndarray/linfa. Rows and columns are sorted (years numerically); gaps and missing values are
+/// `NaN`.
+///
+/// Errors when two observations land in the same cell, i.e. the spec leaves out a dimension
+/// that varies in the data (`rows: Country, cols: Year` with several indicators).
+///
+/// ```
+/// # use wbi_rs::models::DataPoint;
+/// use wbi_rs::stats::{PivotKey, PivotSpec, to_ndarray};
+///
+/// # let p = |iso3: &str, year: i32, value: f64| DataPoint {
+/// #     indicator_id: "X".into(), indicator_name: "Demo".into(), country_id: "".into(),
+/// #     country_name: "".into(), country_iso3: iso3.into(), year, value: Some(value),
+/// #     unit: None, obs_status: None, decimal: None };
+/// let rows = vec![p("DEU", 2020, 1.0), p("FRA", 2021, 2.0)];
+/// let spec = PivotSpec { rows: PivotKey::Country, cols: PivotKey::Year };
+/// let (m, axes) = to_ndarray(&rows, spec)?;
+/// assert_eq!(axes.rows, ["DEU", "FRA"]);
+/// assert_eq!(axes.cols, ["2020", "2021"]);
+/// assert_eq!(m[[0, 0]], 1.0);
+/// assert!(m[[0, 1]].is_nan());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[cfg(feature = "ndarray")]
+pub fn to_ndarray(
+    points: &[DataPoint],
+    spec: PivotSpec,
+) -> anyhow::Result<(ndarray::Array2<f64>, PivotAxes)> {
+    use std::collections::BTreeMap;
+
+    // Sort by (text, year) so years order numerically, then number the labels.
+    let index = |key: PivotKey| {
+        let labels: BTreeMap<(String, Option<i32>), usize> = points
+            .iter()
+            .map(|p| (key.label(p), 0))
+            .collect::<BTreeMap<_, _>>()
+            .into_keys()
+            .enumerate()
+            .map(|(i, label)| (label, i))
+            .collect();
+        labels
+    };
+    let (rows, cols) = (index(spec.rows), index(spec.cols));
+    let mut matrix = ndarray::Array2::from_elem((rows.len(), cols.len()), f64::NAN);
+    let mut filled = ndarray::Array2::from_elem((rows.len(), cols.len()), false);
+    for p in points {
+        let (r, c) = (rows[&spec.rows.label(p)], cols[&spec.cols.label(p)]);
+        if std::mem::replace(&mut filled[[r, c]], true) {
+            anyhow::bail!(
+                "several observations for one cell ({} {}, {}); {:?} by {:?} leaves out a \
+                 dimension that varies in the data",
+                p.indicator_id,
+                p.country_iso3,
+                p.year,
+                spec.rows,
+                spec.cols
+            );
+        }
+        matrix[[r, c]] = p.value.filter(|v| v.is_finite()).unwrap_or(f64::NAN);
+    }
+
+    let names = |labels: BTreeMap<(String, Option<i32>), usize>| {
+        labels
+            .into_keys()
+            .map(|(text, year)| match (text.is_empty(), year) {
+                (_, None) => text,
+                (true, Some(y)) => y.to_string(),
+                (false, Some(y)) => format!("{text}:{y}"),
+            })
+            .collect()
+    };
+    Ok((
+        matrix,
+        PivotAxes {
+            rows: names(rows),
+            cols: names(cols),
+        },
+    ))
+}
@@ -23,12 +23,82 @@
 /// storage::save_json(&rows, "out.json")?;
 /// # Ok::<(), anyhow::Error>(())
 /// ```
-use crate::models::DataPoint;
-use anyhow::Result;
+///
+/// Save observations as a wide CSV: one row per indicator and country, one column per year.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::storage;
+/// # use wbi_rs::models::DataPoint;
+/// let rows: Vec<DataPoint> = vec![];
+/// storage::save_csv_wide(&rows, "wide.csv")?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Write CSV for a spreadsheet in a comma-decimal locale: `1.234,5` values, `;` between fields.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::storage::{self, CsvOptions};
+/// # let rows: Vec<wbi_rs::models::DataPoint> = vec![];
+/// let opts = CsvOptions { locale: Some("de".into()), group_thousands: true, ..Default::default() };
+/// storage::save_csv_with_options(&rows, "out.csv", &opts)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Load observations saved by `save_csv` or `save_json` (e.g. to plot offline).
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::storage;
+/// let rows = storage::load("out.csv")?; // format from the extension
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Keep every fetched revision of the values in a SQLite file (feature `sqlite`) and see how one
+/// changed between fetches.
+///
+/// ### Example
+/// ```ignore
+/// # use wbi_rs::storage;
+/// # let rows: Vec<wbi_rs::models::DataPoint> = vec![];
+/// storage::save_sqlite(&rows, "wbi.sqlite", chrono::Utc::now())?;
+/// for r in storage::revisions("wbi.sqlite", "DEU", "NY.GDP.MKTP.CD", 2020)? {
+///     println!("{}: {:?}", r.retrieved_at, r.value);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Hand observations to Arrow-based tools (feature `arrow`); for SQL see
+/// [`crate::sql`] (feature `datafusion`).
+///
+/// ### Example
+/// ```ignore
+/// # use wbi_rs::storage;
+/// # let rows: Vec<wbi_rs::models::DataPoint> = vec![];
+/// let batch = storage::to_record_batch(&rows)?;
+/// assert_eq!(batch.schema(), storage::record_batch_schema());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Record where a saved file came from in a `<file>.meta.json` sidecar.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::storage::{self, Provenance};
+/// storage::save_provenance("out.csv", &Provenance::now(vec!["DEU".into()], vec!["SP.POP.TOTL".into()]))?;
+/// if let Some(p) = storage::load_provenance("out.csv")? {
+///     println!("fetched {}", p.fetched_at);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+use crate::models::{DataPoint, DateSpec};
+use crate::util::format::{format_exact, map_locale};
+use anyhow::{Context, Result, anyhow};
 use csv::WriterBuilder;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
 
 /// Return a view of `s` that will not be interpreted as a formula by Excel/Calc.
@@ -46,6 +116,58 @@ fn csv_safe_cell(s: &str) -> Cow<'_, str> {
     }
 }
 
+/// Undo [`csv_safe_cell`]: drop the quote it put in front of a formula starter.
+fn csv_unguard_cell(s: &mut String) {
+    if s.starts_with('\'') && matches!(s.as_bytes().get(1), Some(b'=' | b'+' | b'-' | b'@')) {
+        s.remove(0);
+    }
+}
+
+/// Number and field formatting of CSV exports, for spreadsheets that expect their locale's
+/// separators.
+///
+/// The default is the portable format [`load_csv`] reads back: `,` between fields and plain
+/// `1234.5` values. Localized files are meant for spreadsheets; `load_csv` does not read them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Locale tag whose decimal separator the values use (see
+    /// [`map_locale`](crate::util::format::map_locale)), e.g. `de` for `1234,5`.
+    pub locale: Option<String>,
+    /// Group the thousands of values with the locale's separator (`1.234,5`; `1,234.5`
+    /// without a locale).
+    pub group_thousands: bool,
+    /// Field delimiter; `None` uses `;` when the decimal separator is a comma, `,` otherwise.
+    pub delimiter: Option<u8>,
+}
+
+impl CsvOptions {
+    /// The delimiter to write, after the locale default.
+    pub fn field_delimiter(&self) -> u8 {
+        self.delimiter
+            .unwrap_or(match self.locale.as_deref().map(map_locale) {
+                Some((_, ',')) => b';',
+                _ => b',',
+            })
+    }
+
+    /// `v` as cell text, or `None` when it is written as a plain number.
+    fn localized(&self, v: f64) -> Option<String> {
+        if self.locale.is_none() && !self.group_thousands {
+            return None;
+        }
+        let (loc, dec_sep) = map_locale(self.locale.as_deref().unwrap_or("en"));
+        Some(format_exact(v, loc, dec_sep, self.group_thousands))
+    }
+}
+
+/// A value cell: the number itself, or its localized text.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum ValueCell {
+    Number(f64),
+    Text(String),
+}
+
 /// Convert `NaN`/`±inf` to `None` so the JSON is always valid and portable.
 /// JSON has no representation for non-finite floats; serializing them would error.
 fn finite_or_none(x: Option<f64>) -> Option<f64> {
@@ -64,60 +186,172 @@ fn finite_or_none(x: Option<f64>) -> Option<f64> {
 /// Numeric fields are written as numbers; `None` becomes an empty cell.
 /// The final rename is atomic on the same filesystem, avoiding partial/corrupt files.
 pub fn save_csv<P: AsRef<Path>>(points: &[DataPoint], path: P) -> Result<()> {
+    save_csv_with_options(points, path, &CsvOptions::default())
+}
+
+/// [`save_csv`] with localized numbers and delimiter (see [`CsvOptions`]).
+pub fn save_csv_with_options<P: AsRef<Path>>(
+    points: &[DataPoint],
+    path: P,
+    options: &CsvOptions,
+) -> Result<()> {
     let path = path.as_ref();
     let parent = path.parent().unwrap_or_else(|| Path::new("."));
     let mut tmp = NamedTempFile::new_in(parent)?;
+    write_csv_with_options(points, tmp.as_file_mut(), options)?;
 
-    {
-        let mut wtr = WriterBuilder::new().from_writer(tmp.as_file_mut());
+    // All bytes are on disk; atomically move the tempfile into place.
+    tmp.persist(path)?;
+    Ok(())
+}
+
+/// The CSV of [`save_csv`], written to `out` (e.g. stdout or a buffer for the clipboard).
+pub fn write_csv<W: std::io::Write>(points: &[DataPoint], out: W) -> Result<()> {
+    write_csv_with_options(points, out, &CsvOptions::default())
+}
+
+/// [`write_csv`] with localized numbers and delimiter (see [`CsvOptions`]). Only `value` is
+/// localized; `year` and `decimal` are whole numbers and stay as they are.
+pub fn write_csv_with_options<W: std::io::Write>(
+    points: &[DataPoint],
+    out: W,
+    options: &CsvOptions,
+) -> Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .delimiter(options.field_delimiter())
+        .from_writer(out);
+
+    // Fixed header order for stable downstream processing
+    wtr.serialize((
+        "indicator_id",
+        "indicator_name",
+        "country_id",
+        "country_name",
+        "country_iso3",
+        "year",
+        "value",
+        "unit",
+        "obs_status",
+        "decimal",
+    ))?;
+
+    // Sanitize string-like fields; pass numeric fields as-is
+    for p in points {
+        let indicator_id = csv_safe_cell(&p.indicator_id);
+        let indicator_name = csv_safe_cell(&p.indicator_name);
+        let country_id = csv_safe_cell(&p.country_id);
+        let country_name = csv_safe_cell(&p.country_name);
+        let country_iso3 = csv_safe_cell(&p.country_iso3);
+
+        // Option<String> fields need sanitized owned strings if present
+        let unit: Option<String> = p.unit.as_deref().map(|s| csv_safe_cell(s).into_owned());
+        let obs_status: Option<String> = p
+            .obs_status
+            .as_deref()
+            .map(|s| csv_safe_cell(s).into_owned());
+        let value = p.value.map(|v| {
+            options
+                .localized(v)
+                .map_or(ValueCell::Number(v), ValueCell::Text)
+        });
 
-        // Fixed header order for stable downstream processing
         wtr.serialize((
+            indicator_id.as_ref(),
+            indicator_name.as_ref(),
+            country_id.as_ref(),
+            country_name.as_ref(),
+            country_iso3.as_ref(),
+            p.year,      // i32
+            &value,      // Option<f64 or localized text>
+            &unit,       // Option<String>
+            &obs_status, // Option<String>
+            &p.decimal,  // Option<…>
+        ))?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Write observations to CSV in **wide** layout, for spreadsheets and pivot-style tools:
+/// - Header `indicator_id, indicator_name, country_id, country_name, country_iso3, unit`,
+///   followed by every year present in `points`, ascending
+/// - One row per (indicator_id, country_iso3), sorted; names and unit come from the first
+///   observation of the pair
+/// - Missing or non-finite values are empty cells
+///
+/// Spreadsheet safety and the atomic write match [`save_csv`]. `obs_status` and `decimal`
+/// have no place in this layout and are dropped; use [`save_csv`] to keep them.
+pub fn save_csv_wide<P: AsRef<Path>>(points: &[DataPoint], path: P) -> Result<()> {
+    save_csv_wide_with_options(points, path, &CsvOptions::default())
+}
+
+/// [`save_csv_wide`] with localized numbers and delimiter (see [`CsvOptions`]).
+pub fn save_csv_wide_with_options<P: AsRef<Path>>(
+    points: &[DataPoint],
+    path: P,
+    options: &CsvOptions,
+) -> Result<()> {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    let path = path.as_ref();
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut tmp = NamedTempFile::new_in(parent)?;
+
+    let years: BTreeSet<i32> = points.iter().map(|p| p.year).collect();
+    // (indicator_id, country_iso3) -> (first observation, finite values by year)
+    type WideRow<'a> = (&'a DataPoint, BTreeMap<i32, f64>);
+    let mut rows: BTreeMap<(&str, &str), WideRow<'_>> = BTreeMap::new();
+    for p in points {
+        let (_, values) = rows
+            .entry((p.indicator_id.as_str(), p.country_iso3.as_str()))
+            .or_insert_with(|| (p, BTreeMap::new()));
+        if let Some(v) = finite_or_none(p.value) {
+            values.insert(p.year, v);
+        }
+    }
+
+    {
+        let mut wtr = WriterBuilder::new()
+            .delimiter(options.field_delimiter())
+            .from_writer(tmp.as_file_mut());
+
+        let mut header: Vec<String> = [
             "indicator_id",
             "indicator_name",
             "country_id",
             "country_name",
             "country_iso3",
-            "year",
-            "value",
             "unit",
-            "obs_status",
-            "decimal",
-        ))?;
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        header.extend(years.iter().map(|y| y.to_string()));
+        wtr.write_record(&header)?;
 
-        // Sanitize string-like fields; pass numeric fields as-is
-        for p in points {
-            let indicator_id = csv_safe_cell(&p.indicator_id);
-            let indicator_name = csv_safe_cell(&p.indicator_name);
-            let country_id = csv_safe_cell(&p.country_id);
-            let country_name = csv_safe_cell(&p.country_name);
-            let country_iso3 = csv_safe_cell(&p.country_iso3);
-
-            // Option<String> fields need sanitized owned strings if present
-            let unit: Option<String> = p.unit.as_deref().map(|s| csv_safe_cell(s).into_owned());
-            let obs_status: Option<String> = p
-                .obs_status
-                .as_deref()
-                .map(|s| csv_safe_cell(s).into_owned());
-
-            wtr.serialize((
-                indicator_id.as_ref(),
-                indicator_name.as_ref(),
-                country_id.as_ref(),
-                country_name.as_ref(),
-                country_iso3.as_ref(),
-                p.year,      // i32
-                p.value,     // Option<f64>
-                &unit,       // Option<String>
-                &obs_status, // Option<String>
-                &p.decimal,  // Option<…>
-            ))?;
+        for (first, values) in rows.values() {
+            let mut record: Vec<String> = [
+                first.indicator_id.as_str(),
+                first.indicator_name.as_str(),
+                first.country_id.as_str(),
+                first.country_name.as_str(),
+                first.country_iso3.as_str(),
+                first.unit.as_deref().unwrap_or(""),
+            ]
+            .iter()
+            .map(|s| csv_safe_cell(s).into_owned())
+            .collect();
+            record.extend(years.iter().map(|y| match values.get(y) {
+                Some(&v) => options.localized(v).unwrap_or_else(|| v.to_string()),
+                None => String::new(),
+            }));
+            wtr.write_record(&record)?;
         }
 
         wtr.flush()?;
     }
 
-    // All bytes are on disk; atomically move the tempfile into place.
     tmp.persist(path)?;
     Ok(())
 }
@@ -174,3 +408,429 @@ pub fn save_json<P: AsRef<Path>>(points: &[DataPoint], path: P) -> Result<()> {
     tmp.persist(path)?;
     Ok(())
 }
+
+/// Read observations from a CSV file written by [`save_csv`].
+///
+/// Columns are matched by header name, so reordered files load too. The formula guard added
+/// on save (a leading `'` before `=`, `+`, `-` or `@`) is removed again.
+pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Vec<DataPoint>> {
+    let path = path.as_ref();
+    let file =
+        std::fs::File::open(path).with_context(|| format!("reading CSV {}", path.display()))?;
+    read_csv(file)
+        .map(|row| row.with_context(|| path.display().to_string()))
+        .collect()
+}
+
+/// Observations of the CSV in `reader`, one row at a time, as [`load_csv`] reads them; for
+/// files too large to load at once. Errors name the line.
+pub fn read_csv<R: std::io::Read>(reader: R) -> impl Iterator<Item = Result<DataPoint>> {
+    csv::Reader::from_reader(reader)
+        .into_deserialize::<DataPoint>()
+        .enumerate()
+        .map(|(i, row)| {
+            // Header is line 1, so record i is on line i + 2.
+            let mut p = row.with_context(|| format!("line {}", i + 2))?;
+            for s in [
+                &mut p.indicator_id,
+                &mut p.indicator_name,
+                &mut p.country_id,
+                &mut p.country_name,
+                &mut p.country_iso3,
+            ] {
+                csv_unguard_cell(s);
+            }
+            for s in [p.unit.as_mut(), p.obs_status.as_mut()]
+                .into_iter()
+                .flatten()
+            {
+                csv_unguard_cell(s);
+            }
+            Ok(p)
+        })
+}
+
+/// Read observations from a JSON array written by [`save_json`].
+pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Vec<DataPoint>> {
+    let path = path.as_ref();
+    let file =
+        std::fs::File::open(path).with_context(|| format!("reading JSON {}", path.display()))?;
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .with_context(|| format!("parsing JSON {}", path.display()))
+}
+
+/// Read observations with [`load_csv`] or [`load_json`], chosen by the file extension; with the
+/// `sqlite` feature, `.sqlite` and `.db` files load the latest values via [`load_sqlite`].
+/// `.svg` and `.html` files load the rows embedded in a chart (see
+/// [`crate::viz::embedded_data`]).
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<DataPoint>> {
+    let path = path.as_ref();
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("csv") => load_csv(path),
+        Some("json") => load_json(path),
+        Some("svg" | "html" | "htm") => {
+            let document = std::fs::read_to_string(path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            crate::viz::embedded_data(&document)
+                .with_context(|| format!("loading chart data from {}", path.display()))
+        }
+        #[cfg(feature = "sqlite")]
+        Some("sqlite" | "db") => load_sqlite(path, None),
+        _ => Err(anyhow!(
+            "cannot infer the format of {}; expected a .csv or .json file",
+            path.display()
+        )),
+    }
+}
+
+/// Where a saved file came from: the query and when it was fetched.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Provenance {
+    /// Program that wrote the file, e.g. `wbi-rs 0.1.4`.
+    pub generator: String,
+    pub fetched_at: chrono::DateTime<chrono::Utc>,
+    pub countries: Vec<String>,
+    pub indicators: Vec<String>,
+    pub date: Option<DateSpec>,
+    /// World Bank source database id, if one was requested.
+    pub source: Option<u32>,
+}
+
+impl Provenance {
+    /// Provenance of a fetch happening now by this crate.
+    pub fn now(countries: Vec<String>, indicators: Vec<String>) -> Self {
+        Self {
+            generator: concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION")).to_string(),
+            fetched_at: chrono::Utc::now(),
+            countries,
+            indicators,
+            date: None,
+            source: None,
+        }
+    }
+
+    /// `data.csv` → `data.csv.meta.json`.
+    pub fn sidecar_path(data_path: &Path) -> PathBuf {
+        let mut name = data_path.as_os_str().to_owned();
+        name.push(".meta.json");
+        PathBuf::from(name)
+    }
+}
+
+/// Write `provenance` as the sidecar of `data_path` (atomic write).
+pub fn save_provenance<P: AsRef<Path>>(data_path: P, provenance: &Provenance) -> Result<()> {
+    let path = Provenance::sidecar_path(data_path.as_ref());
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut tmp = NamedTempFile::new_in(parent)?;
+    serde_json::to_writer_pretty(tmp.as_file_mut(), provenance)?;
+    tmp.persist(&path)?;
+    Ok(())
+}
+
+/// The sidecar of `data_path`, or `None` when there is none.
+pub fn load_provenance<P: AsRef<Path>>(data_path: P) -> Result<Option<Provenance>> {
+    let path = Provenance::sidecar_path(data_path.as_ref());
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .with_context(|| format!("parsing {}", path.display()))
+}
+
+/// Schema of the [`save_sqlite`] store: one row per observation and retrieval, keyed by
+/// indicator, country, year and `retrieved_at` (RFC 3339 in UTC, so text order is time order).
+#[cfg(feature = "sqlite")]
+const SQLITE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS observations (
+    indicator_id   TEXT    NOT NULL,
+    indicator_name TEXT    NOT NULL,
+    country_id     TEXT    NOT NULL,
+    country_name   TEXT    NOT NULL,
+    country_iso3   TEXT    NOT NULL,
+    year           INTEGER NOT NULL,
+    value          REAL,
+    unit           TEXT,
+    obs_status     TEXT,
+    decimal        INTEGER,
+    retrieved_at   TEXT    NOT NULL,
+    PRIMARY KEY (indicator_id, country_id, country_iso3, year, retrieved_at)
+);";
+
+/// One stored version of an observation (feature `sqlite`), see [`revisions`].
+#[cfg(feature = "sqlite")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    /// When the fetch that first returned this version ran.
+    pub retrieved_at: chrono::DateTime<chrono::Utc>,
+    pub value: Option<f64>,
+    pub obs_status: Option<String>,
+    pub decimal: Option<i32>,
+}
+
+#[cfg(feature = "sqlite")]
+fn open_sqlite(path: &Path) -> Result<rusqlite::Connection> {
+    let conn = rusqlite::Connection::open(path)
+        .with_context(|| format!("opening SQLite store {}", path.display()))?;
+    conn.execute_batch(SQLITE_SCHEMA)
+        .with_context(|| format!("creating tables in {}", path.display()))?;
+    Ok(conn)
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_timestamp(at: chrono::DateTime<chrono::Utc>) -> String {
+    at.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+#[cfg(feature = "sqlite")]
+fn parse_sqlite_timestamp(text: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+    Ok(chrono::DateTime::parse_from_rfc3339(text)
+        .with_context(|| format!("invalid retrieved_at {text:?}"))?
+        .with_timezone(&chrono::Utc))
+}
+
+/// Record `points` as retrieved at `retrieved_at` in the SQLite store at `path` (feature
+/// `sqlite`), creating it if needed, and return how many observations were new or revised.
+///
+/// An observation whose value, `obs_status` and `decimal` equal its latest stored version is
+/// not stored again, so the store grows only with actual revisions. Non-finite values are stored
+/// as missing, as in [`save_json`]. All rows are written in one transaction.
+#[cfg(feature = "sqlite")]
+pub fn save_sqlite<P: AsRef<Path>>(
+    points: &[DataPoint],
+    path: P,
+    retrieved_at: chrono::DateTime<chrono::Utc>,
+) -> Result<usize> {
+    use rusqlite::{OptionalExtension, params};
+
+    let path = path.as_ref();
+    let mut conn = open_sqlite(path)?;
+    let at = sqlite_timestamp(retrieved_at);
+    let tx = conn.transaction()?;
+    let mut written = 0;
+    {
+        let mut latest = tx.prepare(
+            "SELECT value, obs_status, decimal FROM observations
+             WHERE indicator_id = ?1 AND country_id = ?2 AND country_iso3 = ?3 AND year = ?4
+             ORDER BY retrieved_at DESC LIMIT 1",
+        )?;
+        let mut insert = tx.prepare(
+            "INSERT OR REPLACE INTO observations VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )?;
+        for p in points {
+            let value = finite_or_none(p.value);
+            let current = (value, p.obs_status.clone(), p.decimal);
+            let stored: Option<(Option<f64>, Option<String>, Option<i32>)> = latest
+                .query_row(
+                    params![p.indicator_id, p.country_id, p.country_iso3, p.year],
+                    |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+                )
+                .optional()?;
+            if stored.as_ref() == Some(&current) {
+                continue;
+            }
+            insert.execute(params![
+                p.indicator_id,
+                p.indicator_name,
+                p.country_id,
+                p.country_name,
+                p.country_iso3,
+                p.year,
+                value,
+                p.unit,
+                p.obs_status,
+                p.decimal,
+                at,
+            ])?;
+            written += 1;
+        }
+    }
+    tx.commit()
+        .with_context(|| format!("writing {}", path.display()))?;
+    Ok(written)
+}
+
+/// The observations of the SQLite store at `path` as of `as_of` (feature `sqlite`): per
+/// indicator, country and year, the latest version retrieved at or before `as_of`, or the
+/// latest overall when `as_of` is `None`. Rows come sorted by indicator, country and year.
+#[cfg(feature = "sqlite")]
+pub fn load_sqlite<P: AsRef<Path>>(
+    path: P,
+    as_of: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<Vec<DataPoint>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(anyhow!("no SQLite store at {}", path.display()));
+    }
+    let conn = open_sqlite(path)?;
+    // Far-future bound when no snapshot is requested; timestamps compare as text.
+    let bound = as_of.map_or_else(|| "9999".to_string(), sqlite_timestamp);
+    let mut stmt = conn.prepare(
+        "SELECT indicator_id, indicator_name, country_id, country_name, country_iso3, year,
+                value, unit, obs_status, decimal
+         FROM observations o
+         WHERE retrieved_at = (
+             SELECT MAX(retrieved_at) FROM observations
+             WHERE indicator_id = o.indicator_id AND country_id = o.country_id
+               AND country_iso3 = o.country_iso3 AND year = o.year AND retrieved_at <= ?1)
+         ORDER BY indicator_id, country_iso3, country_id, year",
+    )?;
+    let rows = stmt.query_map([bound], |r| {
+        Ok(DataPoint {
+            indicator_id: r.get(0)?,
+            indicator_name: r.get(1)?,
+            country_id: r.get(2)?,
+            country_name: r.get(3)?,
+            country_iso3: r.get(4)?,
+            year: r.get(5)?,
+            value: r.get(6)?,
+            unit: r.get(7)?,
+            obs_status: r.get(8)?,
+            decimal: r.get(9)?,
+        })
+    })?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .with_context(|| format!("reading {}", path.display()))
+}
+
+/// Every stored version of every observation in the SQLite store at `path` (feature `sqlite`),
+/// sorted by indicator, country and year, then oldest version first, e.g. for
+/// [`quality_with_history`](crate::stats::quality_with_history).
+#[cfg(feature = "sqlite")]
+pub fn load_sqlite_history<P: AsRef<Path>>(path: P) -> Result<Vec<DataPoint>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(anyhow!("no SQLite store at {}", path.display()));
+    }
+    let conn = open_sqlite(path)?;
+    let mut stmt = conn.prepare(
+        "SELECT indicator_id, indicator_name, country_id, country_name, country_iso3, year,
+                value, unit, obs_status, decimal
+         FROM observations
+         ORDER BY indicator_id, country_iso3, country_id, year, retrieved_at",
+    )?;
+    let rows = stmt.query_map([], |r| {
+        Ok(DataPoint {
+            indicator_id: r.get(0)?,
+            indicator_name: r.get(1)?,
+            country_id: r.get(2)?,
+            country_name: r.get(3)?,
+            country_iso3: r.get(4)?,
+            year: r.get(5)?,
+            value: r.get(6)?,
+            unit: r.get(7)?,
+            obs_status: r.get(8)?,
+            decimal: r.get(9)?,
+        })
+    })?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .with_context(|| format!("reading {}", path.display()))
+}
+
+/// How the value of `indicator_id` for `country` (ISO3 or ISO2/aggregate id) in `year` changed
+/// across the fetches recorded in the SQLite store at `path` (feature `sqlite`), oldest first.
+/// Empty when the store has no such observation.
+#[cfg(feature = "sqlite")]
+pub fn revisions<P: AsRef<Path>>(
+    path: P,
+    country: &str,
+    indicator_id: &str,
+    year: i32,
+) -> Result<Vec<Revision>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(anyhow!("no SQLite store at {}", path.display()));
+    }
+    let conn = open_sqlite(path)?;
+    let mut stmt = conn.prepare(
+        "SELECT retrieved_at, value, obs_status, decimal FROM observations
+         WHERE indicator_id = ?1 AND (country_iso3 = ?2 OR country_id = ?2) AND year = ?3
+         ORDER BY retrieved_at",
+    )?;
+    let rows = stmt.query_map(
+        rusqlite::params![indicator_id, country.to_ascii_uppercase(), year],
+        |r| Ok((r.get::<_, String>(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+    )?;
+    let mut out = Vec::new();
+    for row in rows {
+        let (at, value, obs_status, decimal) =
+            row.with_context(|| format!("reading {}", path.display()))?;
+        out.push(Revision {
+            retrieved_at: parse_sqlite_timestamp(&at)?,
+            value,
+            obs_status,
+            decimal,
+        });
+    }
+    Ok(out)
+}
+
+/// Arrow schema of [`to_record_batch`]: the CSV columns, `year` as Int32, `value` as Float64 and
+/// `decimal` as Int64; `value`, `unit`, `obs_status` and `decimal` are nullable.
+#[cfg(feature = "arrow")]
+pub fn record_batch_schema() -> arrow_schema::SchemaRef {
+    use arrow_schema::{DataType, Field, Schema};
+    let text = |name: &str, nullable: bool| Field::new(name, DataType::Utf8, nullable);
+    std::sync::Arc::new(Schema::new(vec![
+        text("indicator_id", false),
+        text("indicator_name", false),
+        text("country_id", false),
+        text("country_name", false),
+        text("country_iso3", false),
+        Field::new("year", DataType::Int32, false),
+        Field::new("value", DataType::Float64, true),
+        text("unit", true),
+        text("obs_status", true),
+        Field::new("decimal", DataType::Int64, true),
+    ]))
+}
+
+/// Observations as one Arrow `RecordBatch` (feature `arrow`), e.g. for Polars; the `datafusion`
+/// feature's [`crate::sql::WbiTable`] builds on it for SQL. Non-finite values become nulls, as
+/// in [`save_json`].
+#[cfg(feature = "arrow")]
+pub fn to_record_batch(points: &[DataPoint]) -> Result<arrow_array::RecordBatch> {
+    use arrow_array::{ArrayRef, Float64Array, Int32Array, Int64Array, StringArray};
+    use std::sync::Arc;
+
+    let text = |f: fn(&DataPoint) -> &str| -> ArrayRef {
+        Arc::new(points.iter().map(f).map(Some).collect::<StringArray>())
+    };
+    let opt_text = |f: fn(&DataPoint) -> Option<&str>| -> ArrayRef {
+        Arc::new(points.iter().map(f).collect::<StringArray>())
+    };
+    let columns: Vec<ArrayRef> = vec![
+        text(|p| &p.indicator_id),
+        text(|p| &p.indicator_name),
+        text(|p| &p.country_id),
+        text(|p| &p.country_name),
+        text(|p| &p.country_iso3),
+        Arc::new(points.iter().map(|p| p.year).collect::<Int32Array>()),
+        Arc::new(
+            points
+                .iter()
+                .map(|p| finite_or_none(p.value))
+                .collect::<Float64Array>(),
+        ),
+        opt_text(|p| p.unit.as_deref()),
+        opt_text(|p| p.obs_status.as_deref()),
+        Arc::new(
+            points
+                .iter()
+                .map(|p| p.decimal.map(i64::from))
+                .collect::<Int64Array>(),
+        ),
+    ];
+    Ok(arrow_array::RecordBatch::try_new(
+        record_batch_schema(),
+        columns,
+    )?)
+}
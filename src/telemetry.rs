@@ -0,0 +1,75 @@
+//! API client metrics, recorded through the [`metrics`](https://docs.rs/metrics) facade with
+//! the `metrics` feature. Install any recorder (e.g. `metrics-exporter-prometheus`) in the
+//! embedding service; without the feature, or without a recorder, nothing is recorded.
+//!
+//! | Name | Type | Labels |
+//! |---|---|---|
+//! | [`REQUESTS`] | counter | `status`: HTTP status code, or `error` without a response |
+//! | [`RETRIES`] | counter | |
+//! | [`REQUEST_SECONDS`] | histogram | |
+//! | [`CACHE_HITS`] / [`CACHE_MISSES`] | counter | |
+//! | [`ROWS`] | counter | |
+//!
+//! The cache counters only move for a [`Client`](crate::Client) with an attached cache.
+
+/// HTTP requests sent to the API, by `status`.
+pub const REQUESTS: &str = "wbi_http_requests_total";
+/// Requests repeated after a server error or a failed connection.
+pub const RETRIES: &str = "wbi_http_retries_total";
+/// Time from sending a request to having its response body, in seconds.
+pub const REQUEST_SECONDS: &str = "wbi_http_request_duration_seconds";
+/// Responses served from the [`HttpCache`](crate::cache::HttpCache).
+pub const CACHE_HITS: &str = "wbi_cache_hits_total";
+/// Requests that found no fresh entry in the cache.
+pub const CACHE_MISSES: &str = "wbi_cache_misses_total";
+/// Observations returned by fetches.
+pub const ROWS: &str = "wbi_rows_fetched_total";
+
+/// Start timing a request; `None` where no clock is available (`wasm32`) or nothing is recorded.
+#[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+pub(crate) fn start() -> Option<std::time::Instant> {
+    Some(std::time::Instant::now())
+}
+
+#[cfg(not(all(feature = "metrics", not(target_arch = "wasm32"))))]
+pub(crate) fn start() -> Option<std::time::Instant> {
+    None
+}
+
+/// One request that ended with `status` (`None`: no response), timed from `started`.
+pub(crate) fn request(status: Option<u16>, started: Option<std::time::Instant>) {
+    #[cfg(feature = "metrics")]
+    {
+        let status = status.map_or_else(|| "error".to_string(), |s| s.to_string());
+        metrics::counter!(REQUESTS, "status" => status).increment(1);
+        if let Some(started) = started {
+            metrics::histogram!(REQUEST_SECONDS).record(started.elapsed().as_secs_f64());
+        }
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (status, started);
+}
+
+/// A request about to be repeated.
+#[cfg(feature = "blocking")]
+pub(crate) fn retry() {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(RETRIES).increment(1);
+}
+
+/// A cache lookup, hit or miss.
+#[cfg(feature = "blocking")]
+pub(crate) fn cache(hit: bool) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(if hit { CACHE_HITS } else { CACHE_MISSES }).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = hit;
+}
+
+/// `n` observations returned by a fetch.
+pub(crate) fn rows(n: usize) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(ROWS).increment(n as u64);
+    #[cfg(not(feature = "metrics"))]
+    let _ = n;
+}
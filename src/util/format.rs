@@ -0,0 +1,96 @@
+//! Locale-aware number formatting for console output and chart labels.
+//!
+//! ### Example
+//! ```
+//! use wbi_rs::util::format::{format_float, map_locale};
+//!
+//! let (loc, dec_sep) = map_locale("de");
+//! assert_eq!(format_float(1234.5, loc, dec_sep), "1.234,5");
+//! ```
+
+use num_format::{Locale, ToFormattedString};
+
+/// Map a user-provided locale tag to a `num_format::Locale` and its decimal separator char.
+///
+/// Supported tags (case-insensitive): `en`, `us`, `en_US`, `de`, `de_DE`, `german`,
+/// `fr`, `es`, `it`, `pt`, `nl`. Defaults to English.
+pub fn map_locale(tag: &str) -> (&'static Locale, char) {
+    match tag.to_lowercase().as_str() {
+        "de" | "de_de" | "german" => (&Locale::de, ','),
+        "fr" | "fr_fr" => (&Locale::fr, ','),
+        "es" | "es_es" => (&Locale::es, ','),
+        "it" | "it_it" => (&Locale::it, ','),
+        "pt" | "pt_pt" | "pt_br" => (&Locale::pt, ','),
+        "nl" | "nl_nl" => (&Locale::nl, ','),
+        _ => (&Locale::en, '.'), // default
+    }
+}
+
+/// `x` rounded to `decimals` places, with grouped thousands (`1,234.50`).
+pub fn format_fixed(x: f64, decimals: usize, loc: &Locale, dec_sep: char) -> String {
+    group(&format!("{:.*}", decimals, x), loc, dec_sep)
+}
+
+/// `x` with up to four decimals, trailing zeros trimmed (`1,234.5`).
+pub fn format_float(x: f64, loc: &Locale, dec_sep: char) -> String {
+    let s = format!("{x:.4}");
+    group(s.trim_end_matches('0').trim_end_matches('.'), loc, dec_sep)
+}
+
+/// [`format_float`] for finite values, `NA` for missing or non-finite ones.
+pub fn format_opt(v: Option<f64>, loc: &Locale, dec_sep: char) -> String {
+    match v {
+        Some(x) if x.is_finite() => format_float(x, loc, dec_sep),
+        _ => "NA".to_string(),
+    }
+}
+
+/// `x` with all its digits (the shortest form that reads back as `x`), the locale's decimal
+/// separator and, when `grouped`, its thousands separator: `1234.5678` → `1.234,5678` in `de`.
+/// Non-finite values are written as `NaN`, `inf` or `-inf`.
+pub fn format_exact(x: f64, loc: &Locale, dec_sep: char, grouped: bool) -> String {
+    let s = x.to_string();
+    if !x.is_finite() {
+        s
+    } else if grouped {
+        group(&s, loc, dec_sep)
+    } else {
+        s.replacen('.', &dec_sep.to_string(), 1)
+    }
+}
+
+/// Axis tick label: 0, 1 or 2 decimals for magnitudes of ≥ 100, ≥ 10 and below.
+pub fn format_tick(x: f64, loc: &Locale, dec_sep: char) -> String {
+    let a = x.abs();
+    let decimals = if a >= 100.0 {
+        0
+    } else if a >= 10.0 {
+        1
+    } else {
+        2
+    };
+    format_fixed(x, decimals, loc, dec_sep)
+}
+
+/// Regroup a plain `-1234.5` rendering with the locale's separators.
+fn group(s: &str, loc: &Locale, dec_sep: char) -> String {
+    let (int_part, frac) = s.split_once('.').unwrap_or((s, ""));
+    let (sign, digits) = match int_part.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", int_part),
+    };
+    let grouped = digits
+        .parse::<u128>()
+        .map_or_else(|_| digits.to_string(), |n| n.to_formatted_string(loc));
+    // Rounding can leave a negative zero ("-0", "-0.00"); print it unsigned.
+    let sign = if digits.chars().all(|c| c == '0') && frac.chars().all(|c| c == '0') {
+        ""
+    } else {
+        sign
+    };
+    if frac.is_empty() {
+        format!("{sign}{grouped}")
+    } else {
+        format!("{sign}{grouped}{dec_sep}{frac}")
+    }
+}
@@ -0,0 +1,3 @@
+//! Helpers shared by the CLI and the library modules.
+
+pub mod format;
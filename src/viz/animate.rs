@@ -0,0 +1,462 @@
+//! Animated chart export: one frame per year, encoded as **GIF** or **APNG**.
+//!
+//! Two animation styles are supported:
+//! - `RankedBar`: horizontal bars sorted by value (a "bar chart race"); gaps carry the last
+//!   observed value forward so series don't flicker in and out.
+//! - `Scatter`: observations accumulate on fixed year/value axes; the current year is highlighted.
+//!
+//! The output format is chosen from the file extension: `.gif` uses the Plotters GIF backend,
+//! `.png`/`.apng` renders frames in memory and encodes an animated PNG.
+
+use anyhow::{Result, anyhow, bail};
+use plotters::backend::DrawingBackend;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::FontFamily;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use plotters_bitmap::BitMapBackend;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::models::{DataPoint, Unit};
+
+use super::util::{choose_axis_scale, derive_axis_unit, office_color};
+
+/// Frame layout used by [`animate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationKind {
+    /// Horizontal bars ranked by value, re-sorted every year.
+    RankedBar,
+    /// Cumulative scatter over year/value axes, current year highlighted.
+    Scatter,
+}
+
+/// Options for [`animate`].
+#[derive(Debug, Clone)]
+pub struct AnimationOptions {
+    pub kind: AnimationKind,
+    pub width: u32,
+    pub height: u32,
+    /// Display time of each frame in milliseconds.
+    pub frame_delay_ms: u32,
+    /// Extra frames appended at the end holding the last year (a short pause before looping).
+    pub hold_last_frames: u32,
+    /// Maximum number of bars shown per frame (only for `RankedBar`).
+    pub top_n: usize,
+    /// Chart title; the current year is shown separately on every frame.
+    pub title: String,
+    /// Locale tag for value labels (e.g. `en`, `de`).
+    pub locale: String,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self {
+            kind: AnimationKind::RankedBar,
+            width: 1000,
+            height: 600,
+            frame_delay_ms: 500,
+            hold_last_frames: 3,
+            top_n: 10,
+            title: String::new(),
+            locale: "en".into(),
+        }
+    }
+}
+
+/// One labelled series with its observations sorted by year.
+struct AnimSeries {
+    label: String,
+    color: RGBAColor,
+    values: Vec<(i32, f64)>,
+}
+
+impl AnimSeries {
+    /// Value observed in `year`, if any.
+    fn value_at(&self, year: i32) -> Option<f64> {
+        self.values
+            .iter()
+            .find(|(y, _)| *y == year)
+            .map(|(_, v)| *v)
+    }
+
+    /// Most recent value observed at or before `year` (carry-forward for gaps).
+    fn value_carried(&self, year: i32) -> Option<f64> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(y, _)| *y <= year)
+            .map(|(_, v)| *v)
+    }
+}
+
+/// Render an animated chart that advances year by year.
+///
+/// The path extension selects the encoder: `.gif` or `.png`/`.apng` (animated PNG).
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::models::DataPoint;
+/// use wbi_rs::viz::{self, AnimationKind, AnimationOptions};
+/// # let points: Vec<DataPoint> = vec![];
+/// viz::animate(
+///     &points,
+///     "race.gif",
+///     &AnimationOptions { kind: AnimationKind::RankedBar, top_n: 8, ..Default::default() },
+/// )?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn animate<P: AsRef<Path>>(
+    points: &[DataPoint],
+    out_path: P,
+    options: &AnimationOptions,
+) -> Result<()> {
+    let out_path = out_path.as_ref();
+    let ext = out_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    if !matches!(ext.as_str(), "gif" | "png" | "apng") {
+        bail!(
+            "unsupported animation format '.{}': use .gif, .png or .apng",
+            ext
+        );
+    }
+    if options.width == 0 || options.height == 0 {
+        bail!("animation size must be non-zero");
+    }
+
+    let series = build_series(points);
+    if series.is_empty() {
+        return Err(anyhow!("no data to animate"));
+    }
+    let years: BTreeSet<i32> = series
+        .iter()
+        .flat_map(|s| s.values.iter().map(|(y, _)| *y))
+        .collect();
+    let mut frames: Vec<i32> = years.into_iter().collect();
+    if let Some(&last) = frames.last() {
+        frames.extend(std::iter::repeat_n(last, options.hold_last_frames as usize));
+    }
+
+    super::ensure_fonts_registered();
+    let ctx = FrameContext::new(points, &series, options);
+    let (w, h) = (options.width, options.height);
+
+    if ext == "gif" {
+        let root = super::gif_file(out_path, (w, h), options.frame_delay_ms)?.into_drawing_area();
+        for &year in &frames {
+            ctx.draw_frame(&root, year)?;
+            root.present().map_err(|e| anyhow!("{:?}", e))?;
+        }
+        return Ok(());
+    }
+
+    // APNG: render each frame into an RGB buffer, then encode all frames at once.
+    let frame_len = (w as usize) * (h as usize) * 3;
+    let mut encoded: Vec<Vec<u8>> = Vec::with_capacity(frames.len());
+    for &year in &frames {
+        let mut buf = vec![255u8; frame_len];
+        {
+            let root = BitMapBackend::with_buffer(&mut buf, (w, h)).into_drawing_area();
+            ctx.draw_frame(&root, year)?;
+            root.present().map_err(|e| anyhow!("{:?}", e))?;
+        }
+        encoded.push(buf);
+    }
+
+    let file = File::create(out_path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), w, h);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(encoded.len() as u32, 0)?;
+    encoder.set_frame_delay(options.frame_delay_ms.min(u16::MAX as u32) as u16, 1000)?;
+    let mut writer = encoder.write_header()?;
+    for frame in &encoded {
+        writer.write_image_data(frame)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Group finite observations into labelled series with stable colors.
+fn build_series(points: &[DataPoint]) -> Vec<AnimSeries> {
+    let unique_indicators: BTreeSet<&str> =
+        points.iter().map(|p| p.indicator_id.as_str()).collect();
+    let unique_countries: BTreeSet<&str> = points.iter().map(|p| p.country_iso3.as_str()).collect();
+    let one_indicator = unique_indicators.len() == 1;
+    let one_country = unique_countries.len() == 1;
+
+    let mut groups: BTreeMap<String, Vec<(i32, f64)>> = BTreeMap::new();
+    for p in points {
+        if let Some(v) = p.value
+            && v.is_finite()
+            && p.year != 0
+        {
+            let label = if one_indicator && !one_country {
+                p.country_name.clone()
+            } else if one_country && !one_indicator {
+                p.indicator_name.clone()
+            } else {
+                format!("{} — {}", p.country_name, p.indicator_name)
+            };
+            groups.entry(label).or_default().push((p.year, v));
+        }
+    }
+
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (label, mut values))| {
+            values.sort_by_key(|(y, _)| *y);
+            AnimSeries {
+                label,
+                color: office_color(idx),
+                values,
+            }
+        })
+        .collect()
+}
+
+/// Everything that stays constant across frames (axes, scaling, labels).
+struct FrameContext<'a> {
+    series: &'a [AnimSeries],
+    options: &'a AnimationOptions,
+    min_year: i32,
+    max_year: i32,
+    min_val: f64,
+    max_val: f64,
+    yscale: f64,
+    value_title: String,
+    locale: &'static num_format::Locale,
+    dec_sep: char,
+}
+
+impl<'a> FrameContext<'a> {
+    fn new(points: &[DataPoint], series: &'a [AnimSeries], options: &'a AnimationOptions) -> Self {
+        let all = series.iter().flat_map(|s| s.values.iter());
+        let (mut min_year, mut max_year) = (i32::MAX, i32::MIN);
+        let (mut min_val, mut max_val) = (f64::INFINITY, f64::NEG_INFINITY);
+        for (y, v) in all {
+            min_year = min_year.min(*y);
+            max_year = max_year.max(*y);
+            min_val = min_val.min(*v);
+            max_val = max_val.max(*v);
+        }
+        if min_year == max_year {
+            min_year -= 1;
+            max_year += 1;
+        }
+        if (max_val - min_val).abs() < f64::EPSILON {
+            min_val -= 1.0;
+            max_val += 1.0;
+        }
+
+        let unit = derive_axis_unit(points);
+        let max_abs = min_val.abs().max(max_val.abs());
+        let (yscale, scale_word) = match unit.as_deref() {
+            Some(u) if !Unit::parse(u).is_scalable() => (1.0, ""),
+            _ => choose_axis_scale(max_abs),
+        };
+        let scale_word = super::i18n::scale_word(&options.locale, scale_word);
+        let value = super::i18n::tr(&options.locale, super::i18n::Term::Value);
+        let value_title = match (unit.as_deref(), scale_word.as_str()) {
+            (Some(u), "") => u.to_string(),
+            (Some(u), sw) => format!("{u} ({sw})"),
+            (None, "") => value.to_string(),
+            (None, sw) => format!("{value} ({sw})"),
+        };
+        let (locale, dec_sep) = super::util::map_locale(&options.locale);
+
+        Self {
+            series,
+            options,
+            min_year,
+            max_year,
+            min_val,
+            max_val,
+            yscale,
+            value_title,
+            locale,
+            dec_sep,
+        }
+    }
+
+    fn draw_frame<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        year: i32,
+    ) -> Result<()> {
+        root.fill(&WHITE).map_err(|e| anyhow!("{:?}", e))?;
+        match self.options.kind {
+            AnimationKind::RankedBar => self.draw_ranked_bars(root, year)?,
+            AnimationKind::Scatter => self.draw_scatter(root, year)?,
+        }
+
+        // Large year stamp in the lower-right corner.
+        let (w, h) = root.dim_in_pixel();
+        let stamp_style = TextStyle::from((FontFamily::SansSerif, 48))
+            .color(&RGBColor(120, 120, 120))
+            .pos(Pos::new(HPos::Right, VPos::Bottom));
+        root.draw(&Text::new(
+            year.to_string(),
+            (w as i32 - 24, h as i32 - 64),
+            stamp_style,
+        ))
+        .map_err(|e| anyhow!("{:?}", e))?;
+        Ok(())
+    }
+
+    fn caption(&self) -> &str {
+        let t = self.options.title.trim();
+        if t.is_empty() {
+            "World Bank Indicator(s)"
+        } else {
+            t
+        }
+    }
+
+    fn fmt_value(&self, v: f64) -> String {
+        crate::util::format::format_tick(v / self.yscale, self.locale, self.dec_sep)
+    }
+
+    fn draw_ranked_bars<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        year: i32,
+    ) -> Result<()> {
+        let mut ranked: Vec<(&AnimSeries, f64)> = self
+            .series
+            .iter()
+            .filter_map(|s| s.value_carried(year).map(|v| (s, v)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(self.options.top_n.max(1));
+
+        let n_slots = self.options.top_n.clamp(1, self.series.len().max(1)) as f64;
+        let x_lo = (0.0f64.min(self.min_val) / self.yscale) * 1.05;
+        let x_hi = (0.0f64.max(self.max_val) / self.yscale) * 1.15;
+
+        let label_w = (self.options.width as f64 * 0.25).clamp(80.0, 260.0) as u32;
+        let mut chart = ChartBuilder::on(root)
+            .margin(16)
+            .caption(self.caption(), (FontFamily::SansSerif, 24))
+            .set_label_area_size(LabelAreaPosition::Left, label_w)
+            .set_label_area_size(LabelAreaPosition::Bottom, 48)
+            .build_cartesian_2d(x_lo..x_hi, 0.0..n_slots)
+            .map_err(|e| anyhow!("{:?}", e))?;
+
+        chart
+            .configure_mesh()
+            .disable_y_mesh()
+            .disable_y_axis()
+            .x_desc(self.value_title.as_str())
+            .x_labels(8)
+            .label_style((FontFamily::SansSerif, 12))
+            .axis_desc_style((FontFamily::SansSerif, 16))
+            .draw()
+            .map_err(|e| anyhow!("{:?}", e))?;
+
+        let label_style =
+            TextStyle::from((FontFamily::SansSerif, 14)).pos(Pos::new(HPos::Right, VPos::Center));
+        let value_style =
+            TextStyle::from((FontFamily::SansSerif, 12)).pos(Pos::new(HPos::Left, VPos::Center));
+
+        for (rank, (s, v)) in ranked.iter().enumerate() {
+            // Rank 0 at the top of the chart.
+            let top = n_slots - rank as f64 - 0.1;
+            let bottom = n_slots - rank as f64 - 0.9;
+            let scaled = *v / self.yscale;
+            let (x0, x1) = (0.0f64.min(scaled), 0.0f64.max(scaled));
+            chart
+                .draw_series(std::iter::once(Rectangle::new(
+                    [(x0, bottom), (x1, top)],
+                    s.color.filled(),
+                )))
+                .map_err(|e| anyhow!("{:?}", e))?;
+
+            let center = (bottom + top) / 2.0;
+            let (lx, ly) = chart.backend_coord(&(x_lo, center));
+            let text = super::text::truncate_to_width(&s.label, 14, label_w.saturating_sub(12));
+            root.draw(&Text::new(text, (lx - 8, ly), label_style.clone()))
+                .map_err(|e| anyhow!("{:?}", e))?;
+            let (vx, vy) = chart.backend_coord(&(x1, center));
+            root.draw(&Text::new(
+                self.fmt_value(*v),
+                (vx + 6, vy),
+                value_style.clone(),
+            ))
+            .map_err(|e| anyhow!("{:?}", e))?;
+        }
+        Ok(())
+    }
+
+    fn draw_scatter<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        year: i32,
+    ) -> Result<()> {
+        let x_min = self.min_year as f64 - 0.5;
+        let x_max = self.max_year as f64 + 0.5;
+        let pad = (self.max_val - self.min_val) * 0.05;
+        let y_lo = (self.min_val - pad) / self.yscale;
+        let y_hi = (self.max_val + pad) / self.yscale;
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(16)
+            .caption(self.caption(), (FontFamily::SansSerif, 24))
+            .set_label_area_size(LabelAreaPosition::Left, 72)
+            .set_label_area_size(LabelAreaPosition::Bottom, 48)
+            .build_cartesian_2d(x_min..x_max, y_lo..y_hi)
+            .map_err(|e| anyhow!("{:?}", e))?;
+
+        let x_label_fmt = |x: &f64| (x.round() as i32).to_string();
+        chart
+            .configure_mesh()
+            .x_desc(super::i18n::tr(
+                &self.options.locale,
+                super::i18n::Term::Year,
+            ))
+            .y_desc(self.value_title.as_str())
+            .x_labels(((self.max_year - self.min_year + 1) as usize).min(12))
+            .x_label_formatter(&x_label_fmt)
+            .label_style((FontFamily::SansSerif, 12))
+            .axis_desc_style((FontFamily::SansSerif, 16))
+            .draw()
+            .map_err(|e| anyhow!("{:?}", e))?;
+
+        for s in self.series {
+            let past = s.values.iter().filter(|(y, _)| *y < year).map(|(y, v)| {
+                Circle::new((*y as f64, *v / self.yscale), 3, s.color.mix(0.35).filled())
+            });
+            chart.draw_series(past).map_err(|e| anyhow!("{:?}", e))?;
+
+            if let Some(v) = s.value_at(year) {
+                let elem = chart
+                    .draw_series(std::iter::once(Circle::new(
+                        (year as f64, v / self.yscale),
+                        6,
+                        s.color.filled(),
+                    )))
+                    .map_err(|e| anyhow!("{:?}", e))?;
+                let legend_color = s.color;
+                elem.label(s.label.clone())
+                    .legend(move |(x, y)| Circle::new((x + 8, y), 4, legend_color.filled()));
+            }
+        }
+
+        chart
+            .configure_series_labels()
+            .border_style(BLACK)
+            .position(SeriesLabelPosition::UpperLeft)
+            .background_style(WHITE.mix(0.85))
+            .label_font((FontFamily::SansSerif, 14))
+            .draw()
+            .map_err(|e| anyhow!("{:?}", e))?;
+        Ok(())
+    }
+}
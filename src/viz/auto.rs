@@ -0,0 +1,164 @@
+//! [`auto_chart`]: a chart layout picked from the data, for callers who want a decent default
+//! without going through [`PlotOptions`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+
+use super::types::{LegendMode, PlotKind, PlotOptions, PlotTransform};
+use super::util::derive_axis_unit;
+use super::{Facet, Prepared, plot_with_options, prepare_points, render};
+use crate::models::DataPoint;
+
+/// Most series labelled at their line ends; more get a legend below the chart.
+const MAX_DIRECT_LABELS: usize = 8;
+
+/// Height of one row of facets, in pixels.
+const FACET_ROW_PX: u32 = 360;
+
+/// How [`auto_chart`] lays out a dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoLayout {
+    /// One line chart: a single indicator, or indicators sharing a unit.
+    Single,
+    /// One line chart of every series rebased to 100 in the given year, the earliest with a
+    /// value for all of them: a few series of indicators in different units.
+    Indexed(i32),
+    /// One panel per indicator, each on its own axis: indicators in different units with too
+    /// many series, or no common year, for one indexed chart.
+    Facets,
+}
+
+/// Pick the layout [`auto_chart`] uses for `points`. Units come from the `unit` field, or
+/// the indicator name as on the chart axis; an indicator without one counts as a unit of its
+/// own.
+pub fn auto_layout(points: &[DataPoint]) -> Result<AutoLayout> {
+    let by_indicator = by_indicator(points);
+    if by_indicator.is_empty() {
+        return Err(anyhow!("no numeric values to plot"));
+    }
+    let units: BTreeSet<Option<String>> = by_indicator
+        .values()
+        .map(|points| derive_axis_unit(points))
+        .collect();
+    if by_indicator.len() == 1 || (units.len() == 1 && !units.contains(&None)) {
+        return Ok(AutoLayout::Single);
+    }
+    let series: BTreeSet<(&str, &str)> = points
+        .iter()
+        .filter(|p| p.value.is_some())
+        .map(|p| (p.indicator_id.as_str(), p.country_iso3.as_str()))
+        .collect();
+    if series.len() <= MAX_DIRECT_LABELS
+        && let Some(year) = common_year(points, series.len())
+    {
+        return Ok(AutoLayout::Indexed(year));
+    }
+    Ok(AutoLayout::Facets)
+}
+
+/// Plot `points` with a layout picked by [`auto_layout`] and return it. Line charts, labelled
+/// at the line ends when there are few series; the backend follows the extension of
+/// `out_path` as in [`plot_with_options`]. The crate has no dual-axis chart, so two
+/// indicators in different units are indexed or faceted like any other mix.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::models::DataPoint;
+/// use wbi_rs::viz::{self, AutoLayout};
+/// # let points: Vec<DataPoint> = vec![];
+/// let layout = viz::auto_chart(&points, "overview.svg")?;
+/// println!("drawn as {layout:?}");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn auto_chart<P: AsRef<Path>>(points: &[DataPoint], out_path: P) -> Result<AutoLayout> {
+    let layout = auto_layout(points)?;
+    match layout {
+        AutoLayout::Single => plot_with_options(points, out_path, &line_options(points))?,
+        AutoLayout::Indexed(year) => {
+            let options = PlotOptions {
+                transform: PlotTransform::IndexToYear(year),
+                ..line_options(points)
+            };
+            plot_with_options(points, out_path, &options)?
+        }
+        AutoLayout::Facets => {
+            let panels = by_indicator(points);
+            let cols = facet_columns(panels.len());
+            let rows = panels.len().div_ceil(cols) as u32;
+            let options = PlotOptions {
+                height: (rows * FACET_ROW_PX).max(PlotOptions::default().height),
+                ..PlotOptions::default()
+            };
+            let facets = panels
+                .into_values()
+                .map(|points| {
+                    let options = PlotOptions {
+                        width: options.width / cols as u32,
+                        height: options.height / rows,
+                        ..line_options(&points)
+                    };
+                    let (points, prepared) = prepare_points(&points, &options)?;
+                    Ok(Facet {
+                        points: points.into_owned(),
+                        prepared,
+                        options,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            render(out_path.as_ref(), &[], &Prepared::Facets(facets), &options)?
+        }
+    }
+    Ok(layout)
+}
+
+/// Columns of a near-square grid of `n` facets.
+pub(crate) fn facet_columns(n: usize) -> usize {
+    (n as f64).sqrt().ceil().max(1.0) as usize
+}
+
+/// Points with a value, per indicator, ordered by indicator name.
+fn by_indicator(points: &[DataPoint]) -> BTreeMap<(String, String), Vec<DataPoint>> {
+    let mut out: BTreeMap<(String, String), Vec<DataPoint>> = BTreeMap::new();
+    for p in points.iter().filter(|p| p.value.is_some()) {
+        out.entry((p.indicator_name.clone(), p.indicator_id.clone()))
+            .or_default()
+            .push(p.clone());
+    }
+    out
+}
+
+/// Earliest year in which all `n_series` series have a non-zero value.
+fn common_year(points: &[DataPoint], n_series: usize) -> Option<i32> {
+    let mut per_year: BTreeMap<i32, BTreeSet<(&str, &str)>> = BTreeMap::new();
+    for p in points {
+        if p.value.is_some_and(|v| v != 0.0 && v.is_finite()) {
+            per_year
+                .entry(p.year)
+                .or_default()
+                .insert((&p.indicator_id, &p.country_iso3));
+        }
+    }
+    per_year
+        .into_iter()
+        .find(|(_, series)| series.len() == n_series)
+        .map(|(year, _)| year)
+}
+
+/// A line chart, labelled directly when the series are few enough.
+fn line_options(points: &[DataPoint]) -> PlotOptions {
+    let series: BTreeSet<(&str, &str)> = points
+        .iter()
+        .map(|p| (p.indicator_id.as_str(), p.country_iso3.as_str()))
+        .collect();
+    PlotOptions {
+        kind: PlotKind::Line,
+        legend: if series.len() <= MAX_DIRECT_LABELS {
+            LegendMode::Direct
+        } else {
+            LegendMode::Bottom
+        },
+        ..PlotOptions::default()
+    }
+}
@@ -0,0 +1,188 @@
+//! Broken value axes ([`AxisBreak`]): a cut-out stretch of the Y axis, drawn as a narrow band
+//! with the conventional zigzag marks on the axis line.
+
+use anyhow::{Result, anyhow};
+use plotters::coord::Shift;
+use plotters::coord::types::RangedCoordf64;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use super::fonts::FontOptions;
+use super::types::AxisBreak;
+
+/// Height of the break band as a fraction of the visible value span.
+const GAP_FRACTION: f64 = 0.05;
+
+/// Least share of the axis height either side of the break gets, however short its span;
+/// otherwise the series the break is meant to reveal would stay flat.
+const MIN_PART_SHARE: f64 = 1.0 / 3.0;
+
+/// Half the width of the zigzag marks, in pixels.
+const MARK_HALF_WIDTH_PX: i32 = 7;
+
+/// An [`AxisBreak`] resolved against the value range of a chart, in axis units. Chart
+/// coordinates start at `lo` and span the visible values, plus the gap.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BrokenAxis {
+    lo: f64,
+    hi: f64,
+    from: f64,
+    to: f64,
+    /// Share of the height below the break.
+    below_share: f64,
+    /// Chart units per value unit below and above the break.
+    below_k: f64,
+    above_k: f64,
+    gap: f64,
+}
+
+impl BrokenAxis {
+    /// The break cutting `lo..hi`, or `None` when it does not lie inside that range.
+    pub(crate) fn new(brk: &AxisBreak, (lo, hi): (f64, f64)) -> Option<Self> {
+        if !(lo < brk.from && brk.to < hi) {
+            return None;
+        }
+        let (below, above) = (brk.from - lo, hi - brk.to);
+        let span = below + above;
+        let below_share = (below / span).clamp(MIN_PART_SHARE, 1.0 - MIN_PART_SHARE);
+        Some(Self {
+            lo,
+            hi,
+            from: brk.from,
+            to: brk.to,
+            below_share,
+            below_k: below_share * span / below,
+            above_k: (1.0 - below_share) * span / above,
+            gap: span * GAP_FRACTION,
+        })
+    }
+
+    /// Chart coordinate of the value `v`: each side of the break stretched to its share of
+    /// the height, and values inside the break squeezed into the gap.
+    pub(crate) fn map(&self, v: f64) -> f64 {
+        let gap_start = self.lo + (self.from - self.lo) * self.below_k;
+        if v <= self.from {
+            self.lo + (v - self.lo) * self.below_k
+        } else if v >= self.to {
+            gap_start + self.gap + (v - self.to) * self.above_k
+        } else {
+            gap_start + self.gap * (v - self.from) / (self.to - self.from)
+        }
+    }
+
+    /// Chart coordinates of the whole axis.
+    pub(crate) fn range(&self) -> std::ops::Range<f64> {
+        self.lo..self.map(self.hi)
+    }
+
+    /// Tick values below and above the break, about `count` in all, shared by the height
+    /// of each part.
+    fn ticks(&self, count: usize) -> Vec<f64> {
+        let share = |part: f64| ((count as f64 * part).round() as usize).max(2);
+        let mut ticks = nice_ticks(self.lo, self.from, share(self.below_share));
+        ticks.extend(nice_ticks(self.to, self.hi, share(1.0 - self.below_share)));
+        ticks
+    }
+}
+
+/// Multiples of a 1-2-5 step inside `lo..=hi`, about `count` of them.
+fn nice_ticks(lo: f64, hi: f64, count: usize) -> Vec<f64> {
+    let raw = (hi - lo) / count.max(1) as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&s| s >= raw)
+        .unwrap_or(10.0 * magnitude);
+    let eps = step * 1e-9;
+    let first = ((lo - eps) / step).ceil() as i64;
+    let last = ((hi + eps) / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// Grid lines, tick marks and labels of a broken axis, which the mesh leaves out, then the
+/// break marks: the axis line is cut at the break and capped with two zigzags. `area` is the
+/// area the chart was built on.
+pub(crate) fn draw_axis<DB: DrawingBackend>(
+    chart: &ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    area: &DrawingArea<DB, Shift>,
+    axis: &BrokenAxis,
+    label: &dyn Fn(f64) -> String,
+    fonts: &FontOptions,
+    background: RGBAColor,
+) -> Result<()> {
+    let err = |e| anyhow!("{:?}", e);
+    let (x_px, _) = chart.plotting_area().get_pixel_range();
+    let (bx, by) = area.get_base_pixel();
+    let x0 = chart.x_range().start;
+    let y_px = |v: f64| chart.backend_coord(&(x0, axis.map(v))).1 - by;
+    let (left, right) = (x_px.start - bx, x_px.end - bx);
+
+    let grid = BLACK.mix(0.2);
+    let label_style = TextStyle::from((fonts.family(), fonts.tick_px).into_font())
+        .pos(Pos::new(HPos::Right, VPos::Center));
+    for v in axis.ticks(10) {
+        let y = y_px(v);
+        area.draw(&PathElement::new([(left, y), (right, y)], grid))
+            .map_err(err)?;
+        area.draw(&PathElement::new([(left - 5, y), (left, y)], BLACK))
+            .map_err(err)?;
+        area.draw(&Text::new(label(v), (left - 10, y), label_style.clone()))
+            .map_err(err)?;
+    }
+
+    // Chart y grows downwards: the band's top edge is where the upper part starts.
+    let (top, bottom) = (y_px(axis.to), y_px(axis.from));
+    let w = MARK_HALF_WIDTH_PX;
+    area.draw(&Rectangle::new(
+        [(left - w, top + 1), (left + w, bottom - 1)],
+        background.filled(),
+    ))
+    .map_err(err)?;
+    for y in [top, bottom] {
+        let zigzag = [
+            (left - w, y + 2),
+            (left - w / 2, y - 2),
+            (left, y + 2),
+            (left + w / 2, y - 2),
+            (left + w, y + 2),
+        ];
+        area.draw(&PathElement::new(zigzag, BLACK.stroke_width(1)))
+            .map_err(err)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn parts_keep_their_share_of_the_height_around_the_gap() {
+        let brk = AxisBreak {
+            from: 10.0,
+            to: 100.0,
+        };
+        let axis = BrokenAxis::new(&brk, (0.0, 120.0)).unwrap();
+        // Visible span 10 + 20 = 30: a third of it below the break, two above, and a gap
+        // of 1.5 between them.
+        assert!(close(axis.map(5.0), 5.0));
+        assert!(close(axis.map(55.0), 10.75));
+        assert!(close(axis.map(100.0), 11.5));
+        assert!(close(axis.range().end, 31.5));
+        assert_eq!(
+            axis.ticks(10),
+            [0.0, 5.0, 10.0, 100.0, 105.0, 110.0, 115.0, 120.0]
+        );
+
+        // A short span below the break still gets a third of the height.
+        let outlier = BrokenAxis::new(&brk, (0.0, 1000.0)).unwrap();
+        assert!(close(outlier.map(10.0), 910.0 / 3.0));
+        assert!(close(outlier.range().end, 910.0 * 1.05));
+        assert!(BrokenAxis::new(&brk, (0.0, 90.0)).is_none());
+    }
+}
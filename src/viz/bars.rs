@@ -0,0 +1,405 @@
+//! Grouped bars with countries or indicators along the X axis
+//! ([`BarGrouping::Countries`] / [`BarGrouping::Indicators`]).
+//!
+//! Every bar is the latest observation of one `(indicator, country)` series; the bars of a
+//! group are the other dimension, coloured consistently across groups.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Result, anyhow};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use super::errorbars::{self, Whiskers};
+use super::i18n::{self, Term};
+use super::layout::Layout;
+use super::legend::{LegendEntries, draw_legend_panel_with_patterns};
+use super::text::truncate_to_width;
+use super::types::{BarGrouping, BarOptions, BarOrder, LegendMode, PlotOptions};
+use super::util::{derive_axis_unit, office_color, suffixed_tick_label};
+use super::{plotters_adapter, style};
+use crate::models::DataPoint;
+
+/// One bar: the latest observation of a series.
+struct Bar {
+    category: usize,
+    series: usize,
+    year: i32,
+    value: f64,
+    whisker: Option<(f64, f64)>,
+}
+
+/// Horizontal placement of the bars of a group, in X-axis units (one unit per group).
+pub(crate) struct BarSlots {
+    bar_w: f64,
+}
+
+impl BarSlots {
+    /// Groups of up to `n_series` bars, `unit_px` pixels apart.
+    pub(crate) fn new(options: &BarOptions, n_series: usize, unit_px: f64) -> Self {
+        let mut bar_w = (1.0 - options.group_gap) / n_series.max(1) as f64;
+        if let Some(max_px) = options.max_bar_px
+            && unit_px > 0.0
+        {
+            bar_w = bar_w.min(f64::from(max_px) / unit_px);
+        }
+        Self { bar_w }
+    }
+
+    /// Left and right edge of bar `slot` of a group of `slots` bars centred on `center`.
+    pub(crate) fn span(&self, center: f64, slot: usize, slots: usize) -> (f64, f64) {
+        let x0 = center - self.bar_w * slots as f64 / 2.0 + slot as f64 * self.bar_w;
+        (x0, x0 + self.bar_w)
+    }
+}
+
+/// Slot of each series with a bar in a group, given as `(series, value)`, and the number of
+/// slots: every series keeps its own in `Series` order, otherwise the bars are ranked by value.
+pub(crate) fn group_slots(
+    order: BarOrder,
+    n_series: usize,
+    bars: &[(usize, f64)],
+) -> (HashMap<usize, usize>, usize) {
+    let mut ranked = bars.to_vec();
+    match order {
+        BarOrder::Series => {
+            return (bars.iter().map(|&(s, _)| (s, s)).collect(), n_series);
+        }
+        BarOrder::Ascending => ranked.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0))),
+        BarOrder::Descending => ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0))),
+    }
+    let slots = ranked
+        .iter()
+        .enumerate()
+        .map(|(slot, &(s, _))| (s, slot))
+        .collect();
+    (slots, ranked.len())
+}
+
+/// Outline drawn around every bar with `BarOptions::borders`.
+pub(crate) fn border_style() -> ShapeStyle {
+    BLACK.mix(0.7).stroke_width(1)
+}
+
+/// Validated bars plus the category (X) and series (legend) ids and labels, sorted by label.
+pub(crate) struct CategoryBars {
+    grouping: BarGrouping,
+    categories: Vec<(String, String)>,
+    series: Vec<(String, String)>,
+    bars: Vec<Bar>,
+    range: (f64, f64),
+}
+
+/// `(id, label)` pairs sorted by label, and the index of each id.
+fn sorted_ids(labels: BTreeMap<&str, &str>) -> (Vec<(String, String)>, HashMap<String, usize>) {
+    let mut ids: Vec<(String, String)> = labels
+        .into_iter()
+        .map(|(id, label)| (id.to_string(), label.to_string()))
+        .collect();
+    ids.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+    let index = ids
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| (id.clone(), i))
+        .collect();
+    (ids, index)
+}
+
+impl CategoryBars {
+    /// One bar per `(indicator, country)` series with data; errors when there is none.
+    pub(crate) fn from_points(
+        points: &[DataPoint],
+        grouping: BarGrouping,
+        whiskers: Option<&Whiskers>,
+    ) -> Result<Self> {
+        let mut countries: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut indicators: BTreeMap<&str, &str> = BTreeMap::new();
+        for p in points {
+            countries.entry(&p.country_iso3).or_insert(&p.country_name);
+            indicators
+                .entry(&p.indicator_id)
+                .or_insert(&p.indicator_name);
+        }
+        let (countries, country_idx) = sorted_ids(countries);
+        let (indicators, indicator_idx) = sorted_ids(indicators);
+
+        let mut bars = Vec::new();
+        for (key, series) in super::group_series(points) {
+            let Some(&(period, value)) = series.last() else {
+                continue;
+            };
+            let (c, i) = (
+                country_idx[&key.country_iso3],
+                indicator_idx[&key.indicator_id],
+            );
+            let (category, series) = match grouping {
+                BarGrouping::Indicators => (i, c),
+                _ => (c, i),
+            };
+            let year = period.year();
+            bars.push(Bar {
+                category,
+                series,
+                year,
+                value,
+                whisker: whiskers
+                    .and_then(|w| w.get(&(key.country_iso3, key.indicator_id, year)).copied()),
+            });
+        }
+        if bars.is_empty() {
+            return Err(anyhow!("no numeric values to plot"));
+        }
+
+        // Bars start at zero, so the range always includes it.
+        let (mut lo, mut hi) = bars.iter().fold((0.0f64, 0.0f64), |(lo, hi), b| {
+            let (wlo, whi) = b.whisker.unwrap_or((b.value, b.value));
+            (lo.min(b.value).min(wlo), hi.max(b.value).max(whi))
+        });
+        if (hi - lo).abs() < f64::EPSILON {
+            lo -= 1.0;
+            hi += 1.0;
+        }
+        let (categories, series) = match grouping {
+            BarGrouping::Indicators => (indicators, countries),
+            _ => (countries, indicators),
+        };
+        Ok(Self {
+            grouping,
+            categories,
+            series,
+            bars,
+            range: (lo, hi),
+        })
+    }
+
+    /// X-axis title: the category dimension and the year(s) of the bars.
+    fn x_title(&self, locale: &str) -> String {
+        let term = match self.grouping {
+            BarGrouping::Indicators => Term::Indicator,
+            _ => Term::Country,
+        };
+        let first = self.bars.iter().map(|b| b.year).min().unwrap_or_default();
+        let last = self.bars.iter().map(|b| b.year).max().unwrap_or_default();
+        if first == last {
+            format!("{} ({first})", i18n::tr(locale, term))
+        } else {
+            format!("{} ({first}–{last})", i18n::tr(locale, term))
+        }
+    }
+}
+
+pub(crate) fn draw_category_bars<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    points: &[DataPoint],
+    data: &CategoryBars,
+    options: &PlotOptions,
+    background: RGBAColor,
+) -> Result<LegendEntries> {
+    let fonts = &options.fonts;
+    let family = fonts.family();
+
+    let unit = options.y_unit.clone().or_else(|| derive_axis_unit(points));
+    let (yscale, scale_word) = options.value_scale(
+        &options.y_scale,
+        data.range.0.abs().max(data.range.1.abs()),
+        unit.as_deref(),
+    );
+    let abbreviate = options.tick_abbreviation_for(unit.as_deref());
+    let tick_suffix = options.tick_suffix_for(unit.as_deref());
+    let scale_word = i18n::scale_word(&options.locale, &scale_word);
+    let value = i18n::tr(&options.locale, Term::Value);
+    let range = options.value_range(data.range, unit.as_deref());
+    let (y_lo, y_hi) = (range.0 / yscale, range.1 / yscale);
+    let y_title = match (unit.as_deref(), scale_word.as_str()) {
+        (Some(u), "") => u.to_string(),
+        (Some(u), sw) => format!("{u} ({sw})"),
+        (None, "") => value.to_string(),
+        (None, sw) => format!("{value} ({sw})"),
+    };
+
+    // House style and patterns are looked up by the series id (an ISO3 code or indicator id).
+    let house = &options.style_config;
+    let colors: Vec<RGBAColor> = data
+        .series
+        .iter()
+        .enumerate()
+        .map(|(idx, (id, _))| {
+            house
+                .color_for(id, id)
+                .map_or_else(|| office_color(idx), plotters_adapter::rgba_color)
+        })
+        .collect();
+    let patterns: Vec<style::FillPattern> = data
+        .series
+        .iter()
+        .enumerate()
+        .map(|(idx, (id, _))| {
+            house
+                .pattern_for(id, id)
+                .unwrap_or(if options.pattern_fills {
+                    style::FillPattern::nth(idx)
+                } else {
+                    style::FillPattern::Solid
+                })
+        })
+        .collect();
+    let legend_texts: Vec<String> = data.series.iter().map(|(_, l)| l.clone()).collect();
+
+    let layout = Layout::new(fonts, options.width, (y_lo, y_hi), 10, abbreviate, true)
+        .with_tick_suffix(tick_suffix, options.width);
+    let axis_x_start_px = layout.axis_x_start_px();
+    let (plot_area, legend_area_opt) =
+        layout.split_legend_area(root, options.legend.panel(), &legend_texts, background)?;
+
+    let caption = super::chart_caption(&options.title, points);
+    let chart_area = layout.draw_title(&plot_area, &caption)?;
+    let y_title = layout.fit_y_title(&y_title, &chart_area);
+    let n = data.categories.len();
+    let mut chart = layout
+        .chart_builder(&chart_area)
+        .build_cartesian_2d(-0.5..(n as f64 - 0.5), y_lo..y_hi)
+        .map_err(|e| anyhow!("{:?}", e))?;
+
+    let tick_fmt = |v: &f64| suffixed_tick_label(*v, abbreviate, tick_suffix);
+    // Category labels are drawn below; plotters would place numeric ticks between groups.
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_labels(0)
+        .y_labels(10)
+        .y_label_formatter(&tick_fmt)
+        .x_desc(data.x_title(&options.locale))
+        .y_desc(y_title)
+        .label_style((family, fonts.tick_px))
+        .axis_desc_style((family, fonts.axis_title_px))
+        .draw()
+        .map_err(|e| anyhow!("{:?}", e))?;
+
+    let (plot_w, _) = chart.plotting_area().dim_in_pixel();
+    let unit_px = f64::from(plot_w) / n as f64;
+    let bar_slots = BarSlots::new(&options.bars, data.series.len(), unit_px);
+    let mut slot_of: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    for category in 0..n {
+        let group: Vec<(usize, f64)> = data
+            .bars
+            .iter()
+            .filter(|b| b.category == category)
+            .map(|b| (b.series, b.value))
+            .collect();
+        let (slots, count) = group_slots(options.bars.order, data.series.len(), &group);
+        for (series, slot) in slots {
+            slot_of.insert((category, series), (slot, count));
+        }
+    }
+    let whisker_opts = options.error_bars.clone().unwrap_or_default();
+    let inside_mode = matches!(options.legend, LegendMode::Inside);
+    let mut legend_items: Vec<(String, RGBAColor)> = Vec::new();
+
+    for (idx, (_, label)) in data.series.iter().enumerate() {
+        let (color, pattern) = (colors[idx], patterns[idx]);
+        let rects = |b: &Bar| {
+            let (slot, count) = slot_of[&(b.category, b.series)];
+            let (x0, x1) = bar_slots.span(b.category as f64, slot, count);
+            let (y0, y1) = (0.0f64.min(b.value) / yscale, 0.0f64.max(b.value) / yscale);
+            [(x0, y0), (x1, y1)]
+        };
+        let series_bars: Vec<&Bar> = data.bars.iter().filter(|b| b.series == idx).collect();
+        let elem = if pattern == style::FillPattern::Solid {
+            chart
+                .draw_series(
+                    series_bars
+                        .iter()
+                        .map(|b| Rectangle::new(rects(b), color.filled())),
+                )
+                .map_err(|e| anyhow!("{:?}", e))?
+        } else {
+            // Tint + pattern + outline keeps the bar readable in greyscale.
+            for b in &series_bars {
+                let [(x0, y0), (x1, y1)] = rects(b);
+                let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+                chart
+                    .draw_series(std::iter::once(Rectangle::new(
+                        [(x0, y0), (x1, y1)],
+                        color.mix(0.15).filled(),
+                    )))
+                    .map_err(|e| anyhow!("{:?}", e))?;
+                super::fill_pattern(&chart, &corners, pattern, color)?;
+            }
+            chart
+                .draw_series(
+                    series_bars
+                        .iter()
+                        .map(|b| Rectangle::new(rects(b), color.stroke_width(1))),
+                )
+                .map_err(|e| anyhow!("{:?}", e))?
+        };
+        if inside_mode {
+            elem.label(label.clone())
+                .legend(move |(x, y)| Circle::new((x + 8, y), 4, color.filled()));
+        } else {
+            legend_items.push((label.clone(), color));
+        }
+        if options.bars.borders {
+            chart
+                .draw_series(
+                    series_bars
+                        .iter()
+                        .map(|b| Rectangle::new(rects(b), border_style())),
+                )
+                .map_err(|e| anyhow!("{:?}", e))?;
+        }
+
+        if options.error_bars.is_some() {
+            for b in &series_bars {
+                if let Some((lo, hi)) = b.whisker {
+                    let [(x0, _), (x1, _)] = rects(b);
+                    errorbars::draw_whisker(
+                        &mut chart,
+                        (x0 + x1) / 2.0,
+                        lo / yscale,
+                        hi / yscale,
+                        BLACK.into(),
+                        &whisker_opts,
+                    )?;
+                }
+            }
+        }
+    }
+
+    let slot_px = (plot_w / n as u32).saturating_sub(4);
+    let label_style =
+        TextStyle::from((family, fonts.tick_px)).pos(Pos::new(HPos::Center, VPos::Top));
+    chart
+        .draw_series(data.categories.iter().enumerate().map(|(i, (_, label))| {
+            let label = truncate_to_width(label, fonts.tick_px, slot_px);
+            EmptyElement::at((i as f64, y_lo)) + Text::new(label, (0, 6), label_style.clone())
+        }))
+        .map_err(|e| anyhow!("{:?}", e))?;
+
+    if inside_mode {
+        chart
+            .configure_series_labels()
+            .border_style(BLACK)
+            .position(SeriesLabelPosition::UpperRight)
+            .background_style(WHITE.mix(0.85))
+            .label_font((family, fonts.legend_px))
+            .draw()
+            .map_err(|e| anyhow!("{:?}", e))?;
+    } else if let Some(ref legend_area) = legend_area_opt {
+        draw_legend_panel_with_patterns(
+            legend_area,
+            &legend_items,
+            &patterns,
+            "",
+            options.legend.panel(),
+            axis_x_start_px,
+            fonts,
+        )?;
+    }
+
+    Ok(LegendEntries {
+        items: legend_items,
+        patterns,
+    })
+}
@@ -0,0 +1,461 @@
+//! Branding for published charts ([`Branding`]): a logo and/or a watermark text in one
+//! corner of the chart, drawn over it with some transparency.
+//!
+//! PNG logos work with every output format; SVG logos are inlined into SVG charts only, as
+//! the crate has no SVG rasterizer.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::types::PlotOptions;
+
+/// Distance of the branding from the chart edges, in pixels.
+const MARGIN_PX: i32 = 10;
+
+/// Space between the logo and the watermark text, in pixels.
+const GAP_PX: i32 = 6;
+
+/// Chart corner the branding sits in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Logo and watermark for [`crate::viz::PlotOptions::branding`]; nothing is drawn while both
+/// are unset.
+///
+/// ```
+/// use std::path::PathBuf;
+/// use wbi_rs::viz::{Branding, Corner, PlotOptions};
+/// let opts = PlotOptions {
+///     branding: Branding {
+///         logo: Some(PathBuf::from("logo.png")),
+///         watermark: Some("Example Institute".into()),
+///         corner: Corner::TopRight,
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+/// assert_eq!(opts.branding.opacity, 0.6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Branding {
+    /// PNG or SVG logo file. SVG logos need SVG output.
+    pub logo: Option<PathBuf>,
+    /// Logo height in pixels; the width follows its aspect ratio.
+    pub logo_height: u32,
+    /// Text next to the logo, e.g. the publisher or "Draft"; set in the legend font size.
+    pub watermark: Option<String>,
+    pub corner: Corner,
+    /// Opacity of logo and text in [0, 1].
+    pub opacity: f64,
+}
+
+impl Default for Branding {
+    fn default() -> Self {
+        Self {
+            logo: None,
+            logo_height: 32,
+            watermark: None,
+            corner: Corner::BottomRight,
+            opacity: 0.6,
+        }
+    }
+}
+
+impl Branding {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.opacity) {
+            return Err(anyhow!(
+                "branding opacity must be in [0, 1], got {}",
+                self.opacity
+            ));
+        }
+        if !(4..=1000).contains(&self.logo_height) {
+            return Err(anyhow!(
+                "logo height must be in 4..=1000 px, got {}",
+                self.logo_height
+            ));
+        }
+        Ok(())
+    }
+
+    /// The watermark, unless it is blank.
+    fn text(&self) -> Option<&str> {
+        self.watermark.as_deref().filter(|t| !t.trim().is_empty())
+    }
+}
+
+/// A logo file, decoded as far as drawing it needs.
+enum Logo {
+    Png {
+        bytes: Vec<u8>,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    },
+    Svg {
+        markup: String,
+        width: f64,
+        height: f64,
+    },
+}
+
+impl Logo {
+    fn load(path: &Path) -> Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let bytes =
+            std::fs::read(path).with_context(|| format!("reading logo {}", path.display()))?;
+        match ext.as_deref() {
+            Some("png") => {
+                decode_png(bytes).with_context(|| format!("decoding logo {}", path.display()))
+            }
+            Some("svg") => {
+                let markup = String::from_utf8(bytes)
+                    .map_err(|_| anyhow!("logo {} is not UTF-8 text", path.display()))?;
+                svg_logo(markup).with_context(|| format!("reading logo {}", path.display()))
+            }
+            _ => Err(anyhow!(
+                "logo {} must be a .png or .svg file",
+                path.display()
+            )),
+        }
+    }
+
+    /// Width over height.
+    fn aspect(&self) -> f64 {
+        match self {
+            Logo::Png { width, height, .. } => *width as f64 / *height as f64,
+            Logo::Svg { width, height, .. } => width / height,
+        }
+    }
+}
+
+fn decode_png(bytes: Vec<u8>) -> Result<Logo> {
+    let mut decoder = png::Decoder::new(std::io::Cursor::new(&bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+    let data = &buf[..info.buffer_size()];
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => data.to_vec(),
+        png::ColorType::Rgb => data
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => data
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => data.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => return Err(anyhow!("unexpanded palette image")),
+    };
+    if info.width == 0 || info.height == 0 {
+        return Err(anyhow!("empty image"));
+    }
+    Ok(Logo::Png {
+        width: info.width,
+        height: info.height,
+        rgba,
+        bytes,
+    })
+}
+
+/// An SVG logo from its file contents: the root `<svg>` element onwards, sized by its
+/// `width`/`height` in pixels or else its `viewBox`.
+fn svg_logo(text: String) -> Result<Logo> {
+    let start = text
+        .find("<svg")
+        .ok_or_else(|| anyhow!("no <svg> element"))?;
+    let markup = text[start..].trim_end().to_string();
+    let tag_end = markup
+        .find('>')
+        .ok_or_else(|| anyhow!("unterminated <svg> tag"))?;
+    let tag = &markup[..tag_end];
+    let attr = |name: &str| {
+        let re = Regex::new(&format!(r#"\s{name}\s*=\s*["']([^"']*)["']"#)).expect("valid regex");
+        re.captures(tag).map(|c| c[1].trim().to_string())
+    };
+    let px = |v: Option<String>| -> Option<f64> {
+        v?.trim_end_matches("px")
+            .parse()
+            .ok()
+            .filter(|v: &f64| *v > 0.0)
+    };
+    let (width, height) = match (px(attr("width")), px(attr("height"))) {
+        (Some(w), Some(h)) => (w, h),
+        _ => {
+            let view_box: Vec<f64> = attr("viewBox")
+                .unwrap_or_default()
+                .split([' ', ','])
+                .filter(|s| !s.is_empty())
+                .filter_map(|s| s.parse().ok())
+                .collect();
+            match view_box[..] {
+                [_, _, w, h] if w > 0.0 && h > 0.0 => (w, h),
+                _ => {
+                    return Err(anyhow!(
+                        "the <svg> element needs a width and height or a viewBox"
+                    ));
+                }
+            }
+        }
+    };
+    Ok(Logo::Svg {
+        markup,
+        width,
+        height,
+    })
+}
+
+/// Where the branding goes on a chart: the logo's box and the text's left middle.
+struct Placement {
+    logo: Option<PlacedLogo>,
+    text: Option<(i32, i32)>,
+}
+
+/// A logo with the top-left corner and size it is drawn at.
+struct PlacedLogo {
+    logo: Logo,
+    at: (i32, i32),
+    size: (u32, u32),
+}
+
+impl Placement {
+    /// `None` when `options` has no branding.
+    fn new(options: &PlotOptions, (width, height): (u32, u32)) -> Result<Option<Self>> {
+        let branding = &options.branding;
+        let logo = branding.logo.as_deref().map(Logo::load).transpose()?;
+        let text = branding.text();
+        if logo.is_none() && text.is_none() {
+            return Ok(None);
+        }
+        let logo_size = logo.as_ref().map(|logo| {
+            let h = branding.logo_height;
+            (((h as f64 * logo.aspect()).round() as u32).max(1), h)
+        });
+        let font_px = options.fonts.legend_px;
+        let text_w = text.map(|t| super::text::estimate_text_width_px(t, font_px) as i32);
+
+        let (logo_w, logo_h) = logo_size.map_or((0, 0), |(w, h)| (w as i32, h as i32));
+        let gap = if logo.is_some() && text.is_some() {
+            GAP_PX
+        } else {
+            0
+        };
+        let row_w = logo_w + gap + text_w.unwrap_or(0);
+        let row_h = logo_h.max(if text.is_some() { font_px as i32 } else { 0 });
+        let left = matches!(branding.corner, Corner::TopLeft | Corner::BottomLeft);
+        let top = matches!(branding.corner, Corner::TopLeft | Corner::TopRight);
+        let x0 = if left {
+            MARGIN_PX
+        } else {
+            width as i32 - MARGIN_PX - row_w
+        };
+        let y0 = if top {
+            MARGIN_PX
+        } else {
+            height as i32 - MARGIN_PX - row_h
+        };
+        // The logo sits nearest the chart edge, the text inwards of it.
+        let (logo_x, text_x) = if left {
+            (x0, x0 + logo_w + gap)
+        } else {
+            (x0 + row_w - logo_w, x0)
+        };
+        Ok(Some(Self {
+            logo: logo.zip(logo_size).map(|(logo, size)| PlacedLogo {
+                logo,
+                at: (logo_x, y0 + (row_h - logo_h) / 2),
+                size,
+            }),
+            text: text.map(|_| (text_x, y0 + row_h / 2)),
+        }))
+    }
+}
+
+/// Draw the branding of `options` on `root`. With `raster_logo` the logo is drawn pixel by
+/// pixel; SVG output leaves it to [`insert_svg_logo`] instead.
+pub(crate) fn draw<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    options: &PlotOptions,
+    raster_logo: bool,
+) -> Result<()> {
+    let Some(placement) = Placement::new(options, root.dim_in_pixel())? else {
+        return Ok(());
+    };
+    let err = |e| anyhow!("{:?}", e);
+    let opacity = options.branding.opacity;
+    if let (Some(text), Some(pos)) = (options.branding.text(), placement.text) {
+        let color = BLACK.mix(opacity);
+        let style = TextStyle::from((options.fonts.family(), options.fonts.legend_px).into_font())
+            .color(&color)
+            .pos(Pos::new(HPos::Left, VPos::Center));
+        root.draw(&Text::new(text.to_string(), pos, style))
+            .map_err(err)?;
+    }
+    match placement.logo {
+        Some(PlacedLogo {
+            logo:
+                Logo::Png {
+                    width,
+                    height,
+                    rgba,
+                    ..
+                },
+            at: (x, y),
+            size,
+        }) if raster_logo => {
+            for (dx, dy, [r, g, b, a]) in resample(&rgba, (width, height), size) {
+                if a > 0 {
+                    let alpha = a as f64 / 255.0 * opacity;
+                    root.draw_pixel((x + dx, y + dy), &RGBAColor(r, g, b, alpha))
+                        .map_err(err)?;
+                }
+            }
+        }
+        Some(PlacedLogo {
+            logo: Logo::Svg { .. },
+            ..
+        }) if raster_logo => {
+            return Err(anyhow!(
+                "SVG logos can only be placed on SVG charts; use a PNG logo for PNG and PDF output"
+            ));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Inline the branding logo of `options`, if any, into the SVG chart `svg`, just before the
+/// closing `</svg>` tag: PNG logos as a data URL, SVG logos as a nested `<svg>` element.
+pub(crate) fn insert_svg_logo(svg: &mut String, options: &PlotOptions) -> Result<()> {
+    if options.branding.logo.is_none() {
+        return Ok(());
+    }
+    let Some(Placement {
+        logo:
+            Some(PlacedLogo {
+                logo,
+                at: (x, y),
+                size: (w, h),
+            }),
+        ..
+    }) = Placement::new(options, (options.width, options.height))?
+    else {
+        return Ok(());
+    };
+    let opacity = options.branding.opacity;
+    let element = match logo {
+        Logo::Png { bytes, .. } => format!(
+            r#"<image x="{x}" y="{y}" width="{w}" height="{h}" opacity="{opacity}" href="data:image/png;base64,{}"/>"#,
+            base64(&bytes)
+        ),
+        Logo::Svg {
+            markup,
+            width,
+            height,
+        } => format!(
+            r#"<g opacity="{opacity}"><svg x="{x}" y="{y}" width="{w}" height="{h}" viewBox="0 0 {width} {height}">{markup}</svg></g>"#
+        ),
+    };
+    let at = svg
+        .rfind("</svg>")
+        .ok_or_else(|| anyhow!("no closing </svg> tag to place the logo before"))?;
+    svg.insert_str(at, &format!("{element}\n"));
+    Ok(())
+}
+
+/// `rgba` of `src` size scaled to `dst` size, each target pixel the alpha-weighted mean of the
+/// source pixels it covers, as `(x, y, rgba)`.
+fn resample(
+    rgba: &[u8],
+    (sw, sh): (u32, u32),
+    (dw, dh): (u32, u32),
+) -> impl Iterator<Item = (i32, i32, [u8; 4])> + '_ {
+    let span = |t: u32, src: u32, dst: u32| {
+        let from = (t as u64 * src as u64 / dst as u64) as u32;
+        let to = ((t as u64 + 1) * src as u64 / dst as u64) as u32;
+        from..to.max(from + 1).min(src)
+    };
+    (0..dh).flat_map(move |ty| {
+        (0..dw).map(move |tx| {
+            let (mut sum, mut alpha, mut n) = ([0u64; 3], 0u64, 0u64);
+            for sy in span(ty, sh, dh) {
+                for sx in span(tx, sw, dw) {
+                    let p = &rgba[(sy * sw + sx) as usize * 4..][..4];
+                    let a = p[3] as u64;
+                    for (s, &c) in sum.iter_mut().zip(p) {
+                        *s += c as u64 * a;
+                    }
+                    alpha += a;
+                    n += 1;
+                }
+            }
+            let rgb = sum.map(|s| s.checked_div(alpha).unwrap_or(0) as u8);
+            let a = (alpha / n.max(1)) as u8;
+            (tx as i32, ty as i32, [rgb[0], rgb[1], rgb[2], a])
+        })
+    })
+}
+
+/// Standard base64 with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn resampling_averages_covered_pixels() {
+        // 2×1 image, opaque red and fully transparent: halving it keeps red at half alpha.
+        let rgba = [255, 0, 0, 255, 0, 0, 255, 0];
+        let px: Vec<_> = resample(&rgba, (2, 1), (1, 1)).collect();
+        assert_eq!(px, [(0, 0, [255, 0, 0, 127])]);
+        // Enlarging repeats source pixels.
+        let px: Vec<_> = resample(&rgba, (2, 1), (4, 1)).map(|p| p.2[3]).collect();
+        assert_eq!(px, [255, 255, 0, 0]);
+    }
+}
@@ -0,0 +1,413 @@
+//! Compare two rendered charts, for visual regression tests of report pipelines.
+//!
+//! PNGs are compared pixel by pixel. SVGs are compared element by element (one `<line>`,
+//! `<text>`, `<polyline>` … with its attributes and text), in drawing order, which catches a
+//! moved tick or a changed label without rasterizing. Either way the result carries a
+//! similarity score in [0, 1] and a diff image in the format of the inputs.
+//!
+//! ### Example
+//! ```no_run
+//! use wbi_rs::viz::chart_diff;
+//! let d = chart_diff::diff_files("expected.svg".as_ref(), "actual.svg".as_ref(), 0)?;
+//! if d.similarity < 0.99 {
+//!     d.save_image("chart-diff.svg".as_ref())?;
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+
+/// Format of the compared charts and of [`ChartDiff::image`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    Png,
+    Svg,
+}
+
+/// Result of comparing two charts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartDiff {
+    pub format: ChartFormat,
+    /// Share of matching pixels (PNG) or elements (SVG), in [0, 1]; `1.0` means identical.
+    pub similarity: f64,
+    /// Pixels that differ (PNG), or elements found in only one of the charts (SVG).
+    pub differing: usize,
+    /// Pixels of the larger canvas (PNG), or elements of both charts together (SVG).
+    pub total: usize,
+    /// Diff image in `format`: the second chart faded, with what differs in red. SVG diffs
+    /// also draw elements only the first chart has, in blue.
+    pub image: Vec<u8>,
+}
+
+impl ChartDiff {
+    pub fn is_identical(&self) -> bool {
+        self.differing == 0
+    }
+
+    /// Write [`Self::image`] to `path`.
+    pub fn save_image(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, &self.image)
+            .with_context(|| format!("writing diff image {}", path.display()))
+    }
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Compare the charts in files `a` and `b`, which must both be PNG or both SVG (detected from
+/// their contents). `tolerance` is the largest per-channel difference (0–255) at which two
+/// pixels still count as equal; SVGs ignore it.
+pub fn diff_files(a: &Path, b: &Path, tolerance: u8) -> Result<ChartDiff> {
+    let read = |p: &Path| std::fs::read(p).with_context(|| format!("reading {}", p.display()));
+    let (bytes_a, bytes_b) = (read(a)?, read(b)?);
+    match (sniff(&bytes_a, a)?, sniff(&bytes_b, b)?) {
+        (ChartFormat::Png, ChartFormat::Png) => diff_png(&bytes_a, &bytes_b, tolerance),
+        (ChartFormat::Svg, ChartFormat::Svg) => Ok(diff_svg(
+            &String::from_utf8_lossy(&bytes_a),
+            &String::from_utf8_lossy(&bytes_b),
+        )),
+        _ => Err(anyhow!(
+            "cannot compare {} with {}: render both charts as PNG or both as SVG",
+            a.display(),
+            b.display()
+        )),
+    }
+}
+
+fn sniff(bytes: &[u8], path: &Path) -> Result<ChartFormat> {
+    if bytes.starts_with(PNG_SIGNATURE) {
+        return Ok(ChartFormat::Png);
+    }
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]);
+    if head.contains("<svg") {
+        return Ok(ChartFormat::Svg);
+    }
+    Err(anyhow!("{} is not a PNG or SVG chart", path.display()))
+}
+
+/// Compare two PNG images pixel by pixel. Images of different sizes are compared on the
+/// larger canvas, where pixels outside either image differ.
+pub fn diff_png(a: &[u8], b: &[u8], tolerance: u8) -> Result<ChartDiff> {
+    let a = decode_png(a).context("decoding the first PNG")?;
+    let b = decode_png(b).context("decoding the second PNG")?;
+    let (w, h) = (a.width.max(b.width), a.height.max(b.height));
+
+    let mut image = Vec::with_capacity(w as usize * h as usize * 3);
+    let mut differing = 0;
+    for y in 0..h {
+        for x in 0..w {
+            let (pa, pb) = (a.pixel(x, y), b.pixel(x, y));
+            let same = match (pa, pb) {
+                (Some(pa), Some(pb)) => pa
+                    .iter()
+                    .zip(pb)
+                    .all(|(ca, cb)| ca.abs_diff(cb) <= tolerance),
+                _ => false,
+            };
+            match (same, pb) {
+                (true, Some([r, g, b, alpha])) => {
+                    // Luminance over white, faded to a light grey.
+                    let luma = (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64)
+                        * (alpha as f64 / 255.0)
+                        + 255.0 * (1.0 - alpha as f64 / 255.0);
+                    let faded = (255.0 - (255.0 - luma) / 4.0).round() as u8;
+                    image.extend([faded; 3]);
+                }
+                _ => {
+                    differing += 1;
+                    image.extend([230, 0, 0]);
+                }
+            }
+        }
+    }
+
+    let mut png = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png, w, h);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&image)?;
+        writer.finish()?;
+    }
+    let total = w as usize * h as usize;
+    Ok(ChartDiff {
+        format: ChartFormat::Png,
+        similarity: similarity(total - differing, total),
+        differing,
+        total,
+        image: png,
+    })
+}
+
+/// A decoded image as 8-bit RGBA.
+struct Rgba8 {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 4]>,
+}
+
+impl Rgba8 {
+    fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        (x < self.width && y < self.height).then(|| self.pixels[(y * self.width + x) as usize])
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Rgba8> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+    let buf = &buf[..info.buffer_size()];
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf
+            .chunks_exact(4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+            .collect(),
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => return Err(anyhow!("unexpanded palette image")),
+    };
+    Ok(Rgba8 {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
+
+/// Compare two SVG documents element by element, in drawing order. Whitespace between
+/// elements is ignored; attribute order is not, which holds for charts rendered by the same
+/// code.
+pub fn diff_svg(a: &str, b: &str) -> ChartDiff {
+    let (ea, eb) = (elements(a), elements(b));
+    let (in_a, in_b) = matches(&ea, &eb);
+    let matched = in_b.iter().filter(|&&m| m).count();
+
+    // The second chart with what is new marked, and what was dropped from the first appended,
+    // as insertions at byte offsets of `b`.
+    let mut inserts: Vec<(usize, String)> = eb
+        .iter()
+        .zip(&in_b)
+        .filter(|(e, m)| !**m && e.name != "svg")
+        .map(|(e, _)| (e.name_end, r#" data-diff="added""#.to_string()))
+        .collect();
+    if let Some(root) = eb.iter().find(|e| e.name == "svg") {
+        inserts.push((root.start + root.tag.len(), DIFF_STYLE.to_string()));
+    }
+    let removed: String = ea
+        .iter()
+        .zip(&in_a)
+        .filter(|(e, m)| !**m && e.name != "svg")
+        .filter_map(|(e, _)| e.standalone(a, r#" data-diff="removed""#))
+        .collect();
+    inserts.push((b.rfind("</svg>").unwrap_or(b.len()), removed));
+    inserts.sort_by_key(|(at, _)| *at);
+
+    let mut out = String::with_capacity(b.len() + 1024);
+    let mut pos = 0;
+    for (at, text) in inserts {
+        out.push_str(&b[pos..at]);
+        out.push_str(&text);
+        pos = at;
+    }
+    out.push_str(&b[pos..]);
+
+    let total = ea.len() + eb.len();
+    ChartDiff {
+        format: ChartFormat::Svg,
+        similarity: similarity(2 * matched, total),
+        differing: total - 2 * matched,
+        total,
+        image: out.into_bytes(),
+    }
+}
+
+/// Styles for the SVG diff image; `!important` beats the presentation attributes charts use.
+const DIFF_STYLE: &str = r#"
+<style>
+*:not(svg):not(style):not([data-diff]) { opacity: 0.25; }
+[data-diff] { opacity: 1 !important; stroke-opacity: 1 !important; }
+[data-diff="added"] { stroke: #E00000 !important; }
+text[data-diff="added"], [data-diff="added"]:not([fill="none"]) { fill: #E00000 !important; }
+[data-diff="removed"] { stroke: #0050E0 !important; }
+text[data-diff="removed"], [data-diff="removed"]:not([fill="none"]) { fill: #0050E0 !important; }
+</style>"#;
+
+fn similarity(matching: usize, total: usize) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        matching as f64 / total as f64
+    }
+}
+
+/// One start tag with the text that follows it, e.g. a `<text …>` and its label.
+#[derive(Debug)]
+struct Element<'a> {
+    /// Byte offset of the `<`.
+    start: usize,
+    /// Byte offset just past the element name, where attributes can be inserted.
+    name_end: usize,
+    name: &'a str,
+    /// The tag itself, `<` to `>`.
+    tag: &'a str,
+    /// Text content up to the next tag, trimmed.
+    text: &'a str,
+}
+
+impl Element<'_> {
+    fn key(&self) -> (&str, &str) {
+        (self.tag, self.text)
+    }
+
+    /// The element on its own with `attr` added, or `None` for containers (`<g>`), whose
+    /// children are listed separately.
+    fn standalone(&self, doc: &str, attr: &str) -> Option<String> {
+        let head = format!(
+            "{}{attr}{}",
+            &doc[self.start..self.name_end],
+            &doc[self.name_end..self.start + self.tag.len()]
+        );
+        if self.tag.ends_with("/>") {
+            Some(head)
+        } else if !self.text.is_empty() {
+            Some(format!("{head}{}</{}>", self.text, self.name))
+        } else {
+            None
+        }
+    }
+}
+
+/// Start tags of `doc` in document order; comments, declarations and end tags are skipped.
+fn elements(doc: &str) -> Vec<Element<'_>> {
+    let bytes = doc.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while let Some(off) = doc[i..].find('<') {
+        let start = i + off;
+        let rest = &doc[start..];
+        if rest.starts_with("<!--") {
+            i = start + rest.find("-->").map_or(rest.len(), |e| e + 3);
+            continue;
+        }
+        // Find the closing `>` outside quoted attribute values.
+        let mut quote = None;
+        let mut end = None;
+        for (j, &c) in bytes[start..].iter().enumerate() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, b'"' | b'\'') => quote = Some(c),
+                (None, b'>') => {
+                    end = Some(start + j + 1);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let Some(end) = end else { break };
+        i = end;
+        if rest.starts_with("</") || rest.starts_with("<?") || rest.starts_with("<!") {
+            continue;
+        }
+        let name_len = rest[1..]
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+            .unwrap_or(0);
+        let text_end = doc[end..].find('<').map_or(doc.len(), |t| end + t);
+        out.push(Element {
+            start,
+            name_end: start + 1 + name_len,
+            name: &rest[1..1 + name_len],
+            tag: &doc[start..end],
+            text: doc[end..text_end].trim(),
+        });
+    }
+    out
+}
+
+/// Largest `len(a) × len(b)` compared in order; beyond it elements are matched as multisets.
+const MAX_ORDERED_CELLS: usize = 4_000_000;
+
+/// Which elements of `a` and of `b` have a counterpart in the other chart: a longest common
+/// subsequence, so an inserted element does not shift everything after it.
+fn matches(a: &[Element], b: &[Element]) -> (Vec<bool>, Vec<bool>) {
+    let (mut in_a, mut in_b) = (vec![false; a.len()], vec![false; b.len()]);
+    let prefix = a
+        .iter()
+        .zip(b)
+        .take_while(|(x, y)| x.key() == y.key())
+        .count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x.key() == y.key())
+        .count();
+    for k in 0..prefix {
+        (in_a[k], in_b[k]) = (true, true);
+    }
+    for k in 0..suffix {
+        (in_a[a.len() - 1 - k], in_b[b.len() - 1 - k]) = (true, true);
+    }
+    let (ma, mb) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    let (n, m) = (ma.len(), mb.len());
+
+    if n * m > MAX_ORDERED_CELLS {
+        let mut pool: HashMap<(&str, &str), usize> = HashMap::new();
+        for e in ma {
+            *pool.entry(e.key()).or_default() += 1;
+        }
+        for (j, e) in mb.iter().enumerate() {
+            if let Some(left) = pool.get_mut(&e.key()).filter(|left| **left > 0) {
+                *left -= 1;
+                in_b[prefix + j] = true;
+            }
+        }
+        let mut pool: HashMap<(&str, &str), usize> = HashMap::new();
+        for e in mb {
+            *pool.entry(e.key()).or_default() += 1;
+        }
+        for (i, e) in ma.iter().enumerate() {
+            if let Some(left) = pool.get_mut(&e.key()).filter(|left| **left > 0) {
+                *left -= 1;
+                in_a[prefix + i] = true;
+            }
+        }
+        return (in_a, in_b);
+    }
+
+    // lcs[i][j]: length of the LCS of ma[i..] and mb[j..].
+    let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[at(i, j)] = if ma[i].key() == mb[j].key() {
+                lcs[at(i + 1, j + 1)] + 1
+            } else {
+                lcs[at(i + 1, j)].max(lcs[at(i, j + 1)])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if ma[i].key() == mb[j].key() {
+            (in_a[prefix + i], in_b[prefix + j]) = (true, true);
+            (i, j) = (i + 1, j + 1);
+        } else if lcs[at(i + 1, j)] >= lcs[at(i, j + 1)] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (in_a, in_b)
+}
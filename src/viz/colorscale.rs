@@ -0,0 +1,126 @@
+//! Interpolated colour scales for continuous data, and a vertical colour bar to explain them.
+//!
+//! Used by the correlation heatmap; public so custom charts can colour values the same way.
+//!
+//! ```
+//! use wbi_rs::viz::colorscale::ColorScale;
+//! // Map a correlation in [-1, 1]: negative is blue, positive red, zero near white.
+//! let c = ColorScale::RdBu.map(0.0, (-1.0, 1.0));
+//! assert_eq!((c.0, c.1, c.2), (247, 247, 247));
+//! ```
+
+use anyhow::{Result, anyhow};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use super::fonts::FontOptions;
+
+/// Built-in colour scales, each a list of evenly spaced stops interpolated in RGB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScale {
+    /// Sequential dark purple → teal → yellow (Viridis-like); perceptually ordered and
+    /// colour-blind friendly. For magnitudes.
+    Viridis,
+    /// Diverging blue → near-white → red (ColorBrewer RdBu, reversed so high is red). For
+    /// values around a meaningful midpoint such as 0.
+    RdBu,
+}
+
+const VIRIDIS: [(u8, u8, u8); 9] = [
+    (68, 1, 84),
+    (71, 45, 123),
+    (59, 82, 139),
+    (44, 114, 142),
+    (33, 145, 140),
+    (40, 174, 128),
+    (94, 201, 98),
+    (173, 220, 48),
+    (253, 231, 37),
+];
+
+const RD_BU: [(u8, u8, u8); 7] = [
+    (33, 102, 172),
+    (103, 169, 207),
+    (209, 229, 240),
+    (247, 247, 247),
+    (253, 219, 199),
+    (239, 138, 98),
+    (178, 24, 43),
+];
+
+impl ColorScale {
+    fn stops(self) -> &'static [(u8, u8, u8)] {
+        match self {
+            ColorScale::Viridis => &VIRIDIS,
+            ColorScale::RdBu => &RD_BU,
+        }
+    }
+
+    /// Colour at position `t` in [0, 1] (clamped; NaN maps to 0).
+    pub fn at(self, t: f64) -> RGBColor {
+        let stops = self.stops();
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let pos = t * (stops.len() - 1) as f64;
+        let i = (pos.floor() as usize).min(stops.len() - 2);
+        let f = pos - i as f64;
+        let (a, b) = (stops[i], stops[i + 1]);
+        let mix = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * f).round() as u8;
+        RGBColor(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+    }
+
+    /// Colour of `value` within `domain` (`(low, high)`); an empty domain maps to the middle.
+    pub fn map(self, value: f64, domain: (f64, f64)) -> RGBColor {
+        let (lo, hi) = domain;
+        let t = if (hi - lo).abs() < f64::EPSILON {
+            0.5
+        } else {
+            (value - lo) / (hi - lo)
+        };
+        self.at(t)
+    }
+}
+
+/// Draw a vertical colour bar for `scale` over `domain` on `area`: `domain.1` at pixel row
+/// `rows.0`, `domain.0` at `rows.1`, `width` pixels wide from `x0`, with `ticks`
+/// (value, label) written to its right.
+pub fn draw_color_bar<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    scale: ColorScale,
+    domain: (f64, f64),
+    ticks: &[(f64, &str)],
+    (x0, width): (i32, i32),
+    rows: (i32, i32),
+    fonts: &FontOptions,
+) -> Result<()> {
+    let (top, bottom) = rows;
+    let span = (bottom - top - 1).max(1) as f64;
+    for y in top..bottom {
+        let t = 1.0 - (y - top) as f64 / span;
+        area.draw(&Rectangle::new(
+            [(x0, y), (x0 + width, y + 1)],
+            scale.at(t).filled(),
+        ))
+        .map_err(|e| anyhow!("{:?}", e))?;
+    }
+    area.draw(&Rectangle::new(
+        [(x0, top), (x0 + width, bottom)],
+        BLACK.stroke_width(1),
+    ))
+    .map_err(|e| anyhow!("{:?}", e))?;
+
+    let tick_style =
+        TextStyle::from((fonts.family(), fonts.tick_px)).pos(Pos::new(HPos::Left, VPos::Center));
+    let (lo, hi) = domain;
+    for (value, label) in ticks {
+        let t = if (hi - lo).abs() < f64::EPSILON {
+            0.5
+        } else {
+            ((value - lo) / (hi - lo)).clamp(0.0, 1.0)
+        };
+        let y = top + ((1.0 - t) * span).round() as i32;
+        area.draw(&Text::new(*label, (x0 + width + 6, y), tick_style.clone()))
+            .map_err(|e| anyhow!("{:?}", e))?;
+    }
+    Ok(())
+}
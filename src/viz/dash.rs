@@ -0,0 +1,72 @@
+//! Dashed, dotted and dash-dot strokes for line series.
+//!
+//! Plotters only draws solid paths, so dashed lines are cut into short solid segments here.
+//! The pattern is measured in screen pixels (so dashes look the same regardless of the data
+//! range) but the cut points are interpolated in data space, which lets every backend draw the
+//! pieces with ordinary `PathElement`s.
+
+use super::style::LineDash;
+
+/// On/off lengths in pixels for `dash`, proportional to the stroke width. Empty for solid.
+pub(crate) fn dash_pattern(dash: LineDash, stroke_width: u32) -> Vec<f64> {
+    let w = stroke_width.max(1) as f64;
+    match dash {
+        LineDash::Solid => Vec::new(),
+        LineDash::Dash => vec![4.0 * w, 2.5 * w],
+        LineDash::Dot => vec![w, 2.0 * w],
+        LineDash::DashDot => vec![4.0 * w, 2.0 * w, w, 2.0 * w],
+    }
+}
+
+/// Split a polyline into its visible dash segments.
+///
+/// `data` are the points in data coordinates and `px` the same points mapped to pixels; the
+/// pattern (from [`dash_pattern`]) is walked along the pixel length and each cut point is
+/// interpolated back into data coordinates. A solid pattern returns the polyline unchanged.
+pub(crate) fn dash_segments(
+    data: &[(f64, f64)],
+    px: &[(i32, i32)],
+    pattern: &[f64],
+) -> Vec<Vec<(f64, f64)>> {
+    if pattern.is_empty() || data.len() < 2 || pattern.iter().all(|l| *l <= 0.0) {
+        return vec![data.to_vec()];
+    }
+
+    let mut out = Vec::new();
+    let mut idx = 0usize;
+    let mut remaining = pattern[0];
+    let mut on = true;
+    let mut current = vec![data[0]];
+
+    for k in 0..data.len() - 1 {
+        let (d0, d1) = (data[k], data[k + 1]);
+        let dx = (px[k + 1].0 - px[k].0) as f64;
+        let dy = (px[k + 1].1 - px[k].1) as f64;
+        let len = dx.hypot(dy);
+        if len == 0.0 {
+            continue;
+        }
+        let mut t = 0.0;
+        while len * (1.0 - t) > remaining {
+            t += remaining / len;
+            let p = (d0.0 + (d1.0 - d0.0) * t, d0.1 + (d1.1 - d0.1) * t);
+            if on {
+                current.push(p);
+                out.push(std::mem::take(&mut current));
+            } else {
+                current = vec![p];
+            }
+            on = !on;
+            idx = (idx + 1) % pattern.len();
+            remaining = pattern[idx];
+        }
+        remaining -= len * (1.0 - t);
+        if on {
+            current.push(d1);
+        }
+    }
+    if on && current.len() > 1 {
+        out.push(current);
+    }
+    out
+}
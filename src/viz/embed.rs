@@ -0,0 +1,49 @@
+//! The data behind a chart, embedded in its SVG as a JSON island
+//! ([`PlotOptions::embed_data`](super::PlotOptions::embed_data)) and read back by
+//! [`embedded_data`].
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::models::DataPoint;
+
+/// Opening tag of the island; its `id` is how [`embedded_data`] finds it.
+const OPEN: &str = r#"<script type="application/json" id="wbi-data">"#;
+const CLOSE: &str = "</script>";
+
+/// Insert `points` as a JSON island just before the closing `</svg>` tag.
+pub(crate) fn insert(svg: &mut String, points: &[DataPoint]) -> Result<()> {
+    let at = svg
+        .rfind("</svg>")
+        .ok_or_else(|| anyhow!("no closing </svg> tag to embed the data before"))?;
+    // `<`, `>` and `&` only occur inside JSON strings, where the escapes mean the same; this
+    // keeps the island valid XML and stops a value from closing the script in HTML.
+    let json = serde_json::to_string(points)?
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026");
+    svg.insert_str(at, &format!("{OPEN}{json}{CLOSE}\n"));
+    Ok(())
+}
+
+/// Read the rows embedded in a chart drawn with
+/// [`PlotOptions::embed_data`](super::PlotOptions::embed_data): the SVG itself, or an HTML
+/// page the SVG is inlined in, such as an HTML report. The first island wins.
+///
+/// ### Example
+/// ```no_run
+/// use wbi_rs::viz::{self, PlotOptions};
+/// let svg = std::fs::read_to_string("chart.svg")?;
+/// let points = viz::embedded_data(&svg)?;
+/// viz::plot_with_options(&points, "again.png", &PlotOptions::default())?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn embedded_data(document: &str) -> Result<Vec<DataPoint>> {
+    let start = document
+        .find(OPEN)
+        .map(|i| i + OPEN.len())
+        .ok_or_else(|| anyhow!("no embedded chart data; draw the chart with embed_data"))?;
+    let len = document[start..]
+        .find(CLOSE)
+        .ok_or_else(|| anyhow!("embedded chart data is not terminated"))?;
+    serde_json::from_str(&document[start..start + len]).context("parsing embedded chart data")
+}
@@ -0,0 +1,138 @@
+//! Per-point uncertainty rendered as vertical error bars ("whiskers").
+//!
+//! Uncertainty either comes from an explicit table supplied by the caller or is derived from
+//! the metadata the World Bank ships with each observation (`decimal`, `obs_status`).
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Result, anyhow};
+use plotters::coord::types::RangedCoordf64;
+use plotters::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::DataPoint;
+
+/// Key of an explicit uncertainty entry: `(country_iso3, indicator_id, year)`.
+pub type UncertaintyKey = (String, String, i32);
+
+/// Where the error-bar extents come from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UncertaintySource {
+    /// Absolute `(lower, upper)` bounds per observation; points without an entry get no bar.
+    /// Data rather than a setting, so it cannot be saved in a chart spec.
+    #[serde(skip)]
+    Explicit(BTreeMap<UncertaintyKey, (f64, f64)>),
+    /// Rounding uncertainty: ± half a unit in the last reported decimal place (`decimal`).
+    FromDecimal,
+    /// ± `relative` × |value| for observations that carry a non-empty `obs_status`
+    /// (typically `"E"` for estimates).
+    FromObsStatus { relative: f64 },
+}
+
+/// Error-bar configuration for [`crate::viz::PlotOptions::error_bars`].
+///
+/// Bars are drawn for `Line`, `Scatter`, `LinePoints` and `GroupedBar`; other kinds ignore them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ErrorBarOptions {
+    pub source: UncertaintySource,
+    /// Total width of the horizontal caps in pixels (0 = no caps).
+    pub cap_width_px: u32,
+    /// Opacity of the bars in [0, 1].
+    pub opacity: f64,
+}
+
+impl Default for ErrorBarOptions {
+    fn default() -> Self {
+        Self {
+            source: UncertaintySource::FromDecimal,
+            cap_width_px: 6,
+            opacity: 0.6,
+        }
+    }
+}
+
+/// Resolved `(lower, upper)` bounds per `(iso3, indicator_id, year)`, in data units.
+pub(crate) type Whiskers = HashMap<(String, String, i32), (f64, f64)>;
+
+impl ErrorBarOptions {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.opacity) {
+            return Err(anyhow!(
+                "error bar opacity must be in [0, 1], got {}",
+                self.opacity
+            ));
+        }
+        if let UncertaintySource::FromObsStatus { relative } = self.source
+            && !(relative.is_finite() && relative >= 0.0)
+        {
+            return Err(anyhow!(
+                "relative uncertainty must be a non-negative number, got {relative}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Compute the whisker extents for every point that has both a value and an uncertainty.
+    pub(crate) fn resolve(&self, points: &[DataPoint]) -> Whiskers {
+        let mut out = Whiskers::new();
+        for p in points {
+            let Some(v) = p.value.filter(|v| v.is_finite()) else {
+                continue;
+            };
+            let bounds = match &self.source {
+                UncertaintySource::Explicit(table) => table
+                    .get(&(p.country_iso3.clone(), p.indicator_id.clone(), p.year))
+                    .copied(),
+                UncertaintySource::FromDecimal => p.decimal.map(|d| {
+                    let half = 0.5 * 10f64.powi(-d);
+                    (v - half, v + half)
+                }),
+                UncertaintySource::FromObsStatus { relative } => p
+                    .obs_status
+                    .as_deref()
+                    .filter(|s| !s.trim().is_empty())
+                    .map(|_| (v - v.abs() * relative, v + v.abs() * relative)),
+            };
+            if let Some((lo, hi)) = bounds
+                && lo.is_finite()
+                && hi.is_finite()
+            {
+                out.insert(
+                    (p.country_iso3.clone(), p.indicator_id.clone(), p.year),
+                    (lo.min(hi), lo.max(hi)),
+                );
+            }
+        }
+        out
+    }
+}
+
+/// Draw one vertical whisker at data coordinate `x` spanning `lo..=hi` (already scaled).
+pub(crate) fn draw_whisker<DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    x: f64,
+    lo: f64,
+    hi: f64,
+    color: RGBAColor,
+    options: &ErrorBarOptions,
+) -> Result<()> {
+    let style = color.mix(options.opacity).stroke_width(1);
+    let half = (options.cap_width_px / 2) as i32;
+    chart
+        .draw_series(std::iter::once(PathElement::new(
+            vec![(x, lo), (x, hi)],
+            style,
+        )))
+        .map_err(|e| anyhow!("{:?}", e))?;
+    if half > 0 {
+        chart
+            .draw_series([lo, hi].into_iter().map(|y| {
+                EmptyElement::at((x, y)) + PathElement::new(vec![(-half, 0), (half, 0)], style)
+            }))
+            .map_err(|e| anyhow!("{:?}", e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,133 @@
+//! Chart fonts: an optional user-supplied TTF and per-element font sizes.
+//!
+//! The bundled DejaVu Sans is registered as `sans-serif` and used unless
+//! [`FontOptions::custom`] names another font. Custom fonts are loaded from disk once per
+//! family and stay registered for the lifetime of the process; a family cannot be
+//! re-registered from a different file.
+//!
+//! PDF output always uses the standard Helvetica font (no font embedding); only the sizes apply.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::Context;
+use anyhow::{Result, anyhow};
+use plotters::style::FontFamily;
+#[cfg(not(target_arch = "wasm32"))]
+use plotters::style::FontStyle;
+use serde::{Deserialize, Serialize};
+
+/// A TTF/OTF font file registered under `family`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomFont {
+    /// Family name the font is registered (and written into SVG output) as.
+    pub family: String,
+    pub path: PathBuf,
+}
+
+/// Font family and sizes (in pixels) for [`crate::viz::PlotOptions::fonts`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FontOptions {
+    /// Font used for all chart text; `None` = bundled DejaVu Sans.
+    pub custom: Option<CustomFont>,
+    /// Chart title.
+    pub title_px: u32,
+    /// Axis titles ("Year", the unit on the Y axis).
+    pub axis_title_px: u32,
+    /// Tick labels.
+    pub tick_px: u32,
+    /// Legend entries (an external legend's title uses `legend_px + 2`).
+    pub legend_px: u32,
+}
+
+impl Default for FontOptions {
+    fn default() -> Self {
+        Self {
+            custom: None,
+            title_px: 24,
+            axis_title_px: 16,
+            tick_px: 12,
+            legend_px: 14,
+        }
+    }
+}
+
+/// Families already registered with plotters, with the file each was loaded from.
+static REGISTERED: Mutex<Option<HashMap<String, PathBuf>>> = Mutex::new(None);
+
+/// Generic family names that would shadow the bundled font for every later chart.
+const RESERVED_FAMILIES: &[&str] = &["sans-serif", "serif", "monospace"];
+
+impl FontOptions {
+    /// Font family to draw chart text with.
+    pub(crate) fn family(&self) -> FontFamily<'_> {
+        match &self.custom {
+            Some(font) => FontFamily::Name(font.family.trim()),
+            None => FontFamily::SansSerif,
+        }
+    }
+
+    /// Check sizes and load/register the custom font (if any).
+    pub(crate) fn prepare(&self) -> Result<()> {
+        for (name, px) in [
+            ("title", self.title_px),
+            ("axis title", self.axis_title_px),
+            ("tick", self.tick_px),
+            ("legend", self.legend_px),
+        ] {
+            if !(4..=200).contains(&px) {
+                return Err(anyhow!("{name} font size must be in 4..=200 px, got {px}"));
+            }
+        }
+        let Some(font) = &self.custom else {
+            return Ok(());
+        };
+        let family = font.family.trim();
+        if family.is_empty() {
+            return Err(anyhow!("custom font needs a family name"));
+        }
+        if RESERVED_FAMILIES.contains(&family.to_ascii_lowercase().as_str()) {
+            return Err(anyhow!(
+                "custom font family '{family}' is reserved; pick a specific name"
+            ));
+        }
+
+        let mut guard = REGISTERED.lock().unwrap_or_else(|e| e.into_inner());
+        let registered = guard.get_or_insert_with(HashMap::new);
+        match registered.get(family) {
+            Some(path) if *path == font.path => return Ok(()),
+            Some(path) => {
+                return Err(anyhow!(
+                    "font family '{family}' is already registered from {}; pick another name",
+                    path.display()
+                ));
+            }
+            None => {}
+        }
+        load_font(family, &font.path)?;
+        registered.insert(family.to_string(), font.path.clone());
+        Ok(())
+    }
+}
+
+/// Read the font at `path` and register it with plotters as `family`.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_font(family: &str, path: &Path) -> Result<()> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("reading font file {}", path.display()))?;
+    // plotters keeps registered fonts for the whole process and wants `'static` data.
+    let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+    plotters::style::register_font(family, FontStyle::Normal, bytes)
+        .map_err(|_| anyhow!("{} is not a valid TTF/OTF font", path.display()))
+}
+
+/// On wasm32 plotters draws text with the page's fonts and cannot register font files.
+#[cfg(target_arch = "wasm32")]
+fn load_font(family: &str, _path: &Path) -> Result<()> {
+    Err(anyhow!(
+        "custom font '{family}' cannot be registered on wasm32; the page's fonts are used"
+    ))
+}
@@ -0,0 +1,147 @@
+//! Correlation heatmap ([`crate::viz::plot_correlation`]).
+
+use anyhow::{Result, anyhow};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use super::colorscale::{ColorScale, draw_color_bar};
+use super::layout::{MARGIN, draw_wrapped_title};
+use super::legend::LegendEntries;
+use super::text::{estimate_text_width_px, truncate_to_width};
+use super::types::PlotOptions;
+use crate::stats::CorrelationMatrix;
+
+/// Width reserved for the colour bar and its tick labels.
+const COLORBAR_AREA_PX: u32 = 90;
+const COLORBAR_WIDTH_PX: i32 = 16;
+
+/// Colour for missing cells.
+const MISSING: RGBColor = RGBColor(220, 220, 220);
+
+pub(crate) fn validate(matrix: &CorrelationMatrix) -> Result<()> {
+    let k = matrix.indicators.len();
+    if k == 0 {
+        return Err(anyhow!("correlation matrix is empty"));
+    }
+    if matrix.values.len() != k || matrix.values.iter().any(|row| row.len() != k) {
+        return Err(anyhow!(
+            "correlation matrix must be {k}×{k} to match its indicators"
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn draw_correlation<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    matrix: &CorrelationMatrix,
+    options: &PlotOptions,
+    background: RGBAColor,
+) -> Result<LegendEntries> {
+    let fonts = &options.fonts;
+    let family = fonts.family();
+    let k = matrix.indicators.len();
+
+    root.fill(&background).map_err(|e| anyhow!("{:?}", e))?;
+    let caption = {
+        let t = options.title.trim();
+        if t.is_empty() || t == "World Bank Indicator(s)" {
+            "Correlation between indicators (Pearson r)".to_string()
+        } else {
+            t.to_string()
+        }
+    };
+    let titled = draw_wrapped_title(&root, &caption, fonts, MARGIN)?;
+    let (titled_w, _) = titled.dim_in_pixel();
+    let (chart_area, colorbar_area) =
+        titled.split_horizontally(titled_w.saturating_sub(COLORBAR_AREA_PX));
+
+    let max_label_px = matrix
+        .indicators
+        .iter()
+        .map(|s| estimate_text_width_px(s, fonts.tick_px))
+        .max()
+        .unwrap_or(0);
+    let left_label_px = (max_label_px + 12).min(titled_w * 2 / 5);
+    let bottom_label_px = fonts.tick_px + 12;
+    let mut chart = ChartBuilder::on(&chart_area)
+        .margin_bottom(MARGIN as u32)
+        .set_label_area_size(LabelAreaPosition::Left, left_label_px)
+        .set_label_area_size(LabelAreaPosition::Bottom, bottom_label_px)
+        .build_cartesian_2d(0.0..k as f64, 0.0..k as f64)
+        .map_err(|e| anyhow!("{:?}", e))?;
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .x_labels(0)
+        .y_labels(0)
+        .draw()
+        .map_err(|e| anyhow!("{:?}", e))?;
+
+    let (plot_w, plot_h) = chart.plotting_area().dim_in_pixel();
+    let cell_w = plot_w / k as u32;
+    let cell_h = plot_h / k as u32;
+    let show_values = cell_w >= fonts.tick_px * 3 && cell_h >= fonts.tick_px + 4;
+    let value_style = |r: f64| {
+        let color = if r.abs() > 0.6 { &WHITE } else { &BLACK };
+        TextStyle::from((family, fonts.tick_px))
+            .color(color)
+            .pos(Pos::new(HPos::Center, VPos::Center))
+    };
+
+    // Row 0 is drawn at the top so the matrix reads like a table.
+    for (i, row) in matrix.values.iter().enumerate() {
+        let y = (k - i - 1) as f64;
+        for (j, r) in row.iter().enumerate() {
+            let x = j as f64;
+            let fill = r.map_or(MISSING, |r| ColorScale::RdBu.map(r, (-1.0, 1.0)));
+            chart
+                .draw_series(std::iter::once(Rectangle::new(
+                    [(x, y), (x + 1.0, y + 1.0)],
+                    fill.filled(),
+                )))
+                .map_err(|e| anyhow!("{:?}", e))?;
+            if show_values && let Some(r) = r {
+                chart
+                    .draw_series(std::iter::once(Text::new(
+                        format!("{r:.2}"),
+                        (x + 0.5, y + 0.5),
+                        value_style(*r),
+                    )))
+                    .map_err(|e| anyhow!("{:?}", e))?;
+            }
+        }
+    }
+
+    let row_style =
+        TextStyle::from((family, fonts.tick_px)).pos(Pos::new(HPos::Right, VPos::Center));
+    let col_style = TextStyle::from((family, fonts.tick_px)).pos(Pos::new(HPos::Center, VPos::Top));
+    for (i, name) in matrix.indicators.iter().enumerate() {
+        let row_label = truncate_to_width(name, fonts.tick_px, left_label_px.saturating_sub(12));
+        let col_label = truncate_to_width(name, fonts.tick_px, cell_w.saturating_sub(4));
+        chart
+            .draw_series([
+                EmptyElement::at((0.0, (k - i) as f64 - 0.5))
+                    + Text::new(row_label, (-6, 0), row_style.clone()),
+                EmptyElement::at((i as f64 + 0.5, 0.0))
+                    + Text::new(col_label, (0, 6), col_style.clone()),
+            ])
+            .map_err(|e| anyhow!("{:?}", e))?;
+    }
+
+    // Colour bar aligned with the plotting area.
+    let (_, area_h) = colorbar_area.dim_in_pixel();
+    let bottom = area_h as i32 - MARGIN - bottom_label_px as i32;
+    draw_color_bar(
+        &colorbar_area,
+        ColorScale::RdBu,
+        (-1.0, 1.0),
+        &[(1.0, "+1"), (0.0, "0"), (-1.0, "-1")],
+        (16, COLORBAR_WIDTH_PX),
+        (0, bottom),
+        fonts,
+    )?;
+
+    // The colour bar is part of the chart; there are no legend entries.
+    Ok(LegendEntries::default())
+}
@@ -0,0 +1,156 @@
+//! Fixed chart wording ("Year", scale words, default axis titles) in the language of
+//! [`PlotOptions::locale`](super::types::PlotOptions::locale).
+//!
+//! Languages follow the tags accepted by [`map_locale`](crate::util::format::map_locale);
+//! anything else falls back to English. Units, indicator names and custom scale words
+//! (`--y-scale 1e3:kt`) are drawn as given.
+
+/// A fixed word drawn on charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Term {
+    Year,
+    Value,
+    Mean,
+    Country,
+    Indicator,
+    Projection,
+    CumulativeShare,
+    Increase,
+    Decrease,
+    Total,
+}
+
+/// Language index into the translation tables: en, de, fr, es, it, pt, nl.
+fn language(locale: &str) -> usize {
+    match locale.to_lowercase().as_str() {
+        "de" | "de_de" | "german" => 1,
+        "fr" | "fr_fr" => 2,
+        "es" | "es_es" => 3,
+        "it" | "it_it" => 4,
+        "pt" | "pt_pt" | "pt_br" => 5,
+        "nl" | "nl_nl" => 6,
+        _ => 0,
+    }
+}
+
+/// `term` in the language of `locale`.
+pub(crate) fn tr(locale: &str, term: Term) -> &'static str {
+    let words: [&str; 7] = match term {
+        Term::Year => ["Year", "Jahr", "Année", "Año", "Anno", "Ano", "Jaar"],
+        Term::Value => [
+            "Value", "Wert", "Valeur", "Valor", "Valore", "Valor", "Waarde",
+        ],
+        Term::Mean => [
+            "Mean",
+            "Mittelwert",
+            "Moyenne",
+            "Media",
+            "Media",
+            "Média",
+            "Gemiddelde",
+        ],
+        Term::Country => ["Country", "Land", "Pays", "País", "Paese", "País", "Land"],
+        Term::Indicator => [
+            "Indicator",
+            "Indikator",
+            "Indicateur",
+            "Indicador",
+            "Indicatore",
+            "Indicador",
+            "Indicator",
+        ],
+        Term::Projection => [
+            "projection",
+            "Projektion",
+            "projection",
+            "proyección",
+            "proiezione",
+            "projeção",
+            "projectie",
+        ],
+        Term::CumulativeShare => [
+            "Cumulative share",
+            "Kumulierter Anteil",
+            "Part cumulée",
+            "Participación acumulada",
+            "Quota cumulata",
+            "Participação acumulada",
+            "Cumulatief aandeel",
+        ],
+        Term::Increase => [
+            "Increase", "Zunahme", "Hausse", "Aumento", "Aumento", "Aumento", "Toename",
+        ],
+        Term::Decrease => [
+            "Decrease",
+            "Abnahme",
+            "Baisse",
+            "Disminución",
+            "Diminuzione",
+            "Diminuição",
+            "Afname",
+        ],
+        Term::Total => [
+            "Total", "Gesamt", "Total", "Total", "Totale", "Total", "Totaal",
+        ],
+    };
+    words[language(locale)]
+}
+
+/// Suffixes for thousands, millions, billions and trillions in abbreviated numbers
+/// (`1.2M`, `3,4 Mrd.`), and whether a space goes before them.
+pub(crate) fn magnitude_suffixes(locale: &str) -> ([&'static str; 4], bool) {
+    match language(locale) {
+        1 => (["Tsd.", "Mio.", "Mrd.", "Bio."], true),
+        2 => (["k", "M", "Md", "Bn"], true),
+        3 => (["mil", "M", "mil M", "B"], true),
+        4 => (["mila", "Mln", "Mld", "Bln"], true),
+        5 => (["mil", "mi", "bi", "tri"], true),
+        6 => (["k", "mln", "mld", "bln"], true),
+        _ => (["k", "M", "B", "T"], false),
+    }
+}
+
+/// A built-in scale word (`thousands` … `trillions`) in the language of `locale`;
+/// any other word (empty or custom) is returned unchanged.
+pub(crate) fn scale_word(locale: &str, word: &str) -> String {
+    let words: [&str; 7] = match word {
+        "thousands" => [
+            "thousands",
+            "Tausend",
+            "milliers",
+            "miles",
+            "migliaia",
+            "milhares",
+            "duizenden",
+        ],
+        "millions" => [
+            "millions",
+            "Millionen",
+            "millions",
+            "millones",
+            "milioni",
+            "milhões",
+            "miljoenen",
+        ],
+        "billions" => [
+            "billions",
+            "Milliarden",
+            "milliards",
+            "miles de millones",
+            "miliardi",
+            "mil milhões",
+            "miljarden",
+        ],
+        "trillions" => [
+            "trillions",
+            "Billionen",
+            "billions",
+            "billones",
+            "bilioni",
+            "biliões",
+            "biljoenen",
+        ],
+        _ => return word.to_string(),
+    };
+    words[language(locale)].to_string()
+}
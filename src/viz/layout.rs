@@ -0,0 +1,223 @@
+//! Chart layout: the outer margin, the label gutters around the plotting area and the
+//! legend panels, computed once per chart from measured text.
+//!
+//! The line, summary and XY charts all build their plotting area through [`Layout`], so
+//! the title, the axis labels and an external legend always get the room their text needs.
+
+use anyhow::{Result, anyhow};
+use plotters::backend::DrawingBackend;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use super::fonts::FontOptions;
+use super::legend::estimate_top_bottom_legend_height_px;
+use super::text;
+use super::types::LegendMode;
+use super::util::y_tick_labels_width_px;
+
+/// Outer margin around the chart, in pixels.
+pub(crate) const MARGIN: i32 = 16;
+
+/// Space between the Y tick labels and the axis: the tick mark plus breathing room.
+const TICK_GUTTER_PX: u32 = 18;
+/// Space between the rotated Y-axis title and the tick labels.
+const AXIS_TITLE_GAP_PX: u32 = 6;
+/// Right legend panel: swatch column plus padding around the text (see `legend.rs`).
+const RIGHT_LEGEND_CHROME_PX: u32 = 36;
+/// Direct labels: gap between the plotting area and the label text, and after it.
+const DIRECT_LABEL_CHROME_PX: u32 = 12;
+/// Narrowest text column of the right legend panel, matching the wrap width floor in
+/// `legend.rs`.
+const RIGHT_LEGEND_MIN_TEXT_PX: u32 = 40;
+
+/// Gutters of one chart, shared by the plotting area, the axis titles and the legend.
+pub(crate) struct Layout<'a> {
+    fonts: &'a FontOptions,
+    /// Left label area: rotated Y-axis title, tick labels and tick marks.
+    pub left_label_px: u32,
+    /// Bottom label area: tick labels and, if drawn, the X-axis title.
+    pub bottom_label_px: u32,
+}
+
+impl<'a> Layout<'a> {
+    /// Layout for a chart `width` pixels wide whose scaled Y axis spans `y_range` with
+    /// `y_ticks` labels, abbreviated for the `y_abbreviate` locale if given (see
+    /// [`tick_label`](super::util::tick_label)). `x_title` is whether an X-axis title is drawn
+    /// below the tick labels.
+    pub(crate) fn new(
+        fonts: &'a FontOptions,
+        width: u32,
+        y_range: (f64, f64),
+        y_ticks: usize,
+        y_abbreviate: Option<&str>,
+        x_title: bool,
+    ) -> Self {
+        let ticks_px =
+            y_tick_labels_width_px(y_range.0, y_range.1, y_ticks, fonts.tick_px, y_abbreviate);
+        // Never more than 2/5 of the chart, so the plotting area keeps most of the width.
+        let left_label_px = (ticks_px + TICK_GUTTER_PX + fonts.axis_title_px + AXIS_TITLE_GAP_PX)
+            .clamp(48, (width * 2 / 5).max(48));
+        let bottom_label_px = if x_title {
+            fonts.tick_px + fonts.axis_title_px + 28
+        } else {
+            fonts.tick_px + 16
+        };
+        Self {
+            fonts,
+            left_label_px,
+            bottom_label_px,
+        }
+    }
+
+    /// Widen the tick label column of a chart `width` pixels wide for `suffix` (e.g. `%`)
+    /// after every Y tick label.
+    pub(crate) fn with_tick_suffix(mut self, suffix: &str, width: u32) -> Self {
+        if !suffix.is_empty() {
+            self.left_label_px = (self.left_label_px
+                + text::estimate_text_width_px(suffix, self.fonts.tick_px))
+            .min((width * 2 / 5).max(48));
+        }
+        self
+    }
+
+    /// Width of a label area right of the plotting area for a secondary Y axis with tick
+    /// labels up to `widest_tick` and a rotated axis title.
+    pub(crate) fn right_label_px(&self, widest_tick: &str) -> u32 {
+        text::estimate_text_width_px(widest_tick, self.fonts.tick_px)
+            + TICK_GUTTER_PX
+            + self.fonts.axis_title_px
+            + AXIS_TITLE_GAP_PX
+    }
+
+    /// Where the plotting area starts horizontally; Top/Bottom legends align with it.
+    pub(crate) fn axis_x_start_px(&self) -> i32 {
+        MARGIN + self.left_label_px as i32
+    }
+
+    /// Split `root` into the plot area and, for external legends, a legend area sized to
+    /// fit `legend_texts`. Both areas are filled with `background`.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn split_legend_area<DB: DrawingBackend>(
+        &self,
+        root: DrawingArea<DB, Shift>,
+        legend: LegendMode,
+        legend_texts: &[String],
+        background: RGBAColor,
+    ) -> Result<(DrawingArea<DB, Shift>, Option<DrawingArea<DB, Shift>>)> {
+        let fonts = self.fonts;
+        let (root_w, root_h) = root.dim_in_pixel();
+        let band_h = || {
+            estimate_top_bottom_legend_height_px(
+                legend_texts,
+                self.axis_x_start_px(),
+                root_w as i32,
+                /* has_title: */ false, // we render without a legend title by default
+                /* title_font_px: */ fonts.legend_px + 2,
+                /* font_px: */ fonts.legend_px,
+            )
+            .max(40)
+        };
+
+        let (plot_area, legend_area_opt) = match legend {
+            LegendMode::Right => {
+                // As wide as the longest label needs, up to 35% of the chart; longer labels
+                // wrap inside the panel, and short ones leave the rest to the plot.
+                let text_px = legend_texts
+                    .iter()
+                    .map(|t| text::estimate_text_width_px(t, fonts.legend_px))
+                    .max()
+                    .unwrap_or(0);
+                let max_w = root_w * 35 / 100;
+                let panel_w =
+                    (text_px.max(RIGHT_LEGEND_MIN_TEXT_PX) + RIGHT_LEGEND_CHROME_PX).min(max_w);
+                let (plot, legend) = root.split_horizontally(root_w - panel_w);
+                (plot, Some(legend))
+            }
+            LegendMode::Direct => {
+                // Labels sit right of the line ends, in a strip as wide as the longest one.
+                let text_px = legend_texts
+                    .iter()
+                    .map(|t| text::estimate_text_width_px(t, fonts.legend_px))
+                    .max()
+                    .unwrap_or(0);
+                let strip_w = (text_px + DIRECT_LABEL_CHROME_PX).min(root_w * 35 / 100);
+                let (plot, labels) = root.split_horizontally(root_w - strip_w);
+                (plot, Some(labels))
+            }
+            LegendMode::Top => {
+                let (legend, plot) = root.split_vertically(band_h());
+                (plot, Some(legend))
+            }
+            LegendMode::Bottom => {
+                // keep at least 40px for plot area
+                let (plot, legend) = root.split_vertically((root_h as i32 - band_h()).max(40));
+                (plot, Some(legend))
+            }
+            LegendMode::Inside | LegendMode::Hidden => (root, None),
+        };
+
+        plot_area
+            .fill(&background)
+            .map_err(|e| anyhow!("{:?}", e))?;
+        if let Some(ref legend_area) = legend_area_opt {
+            legend_area
+                .fill(&background)
+                .map_err(|e| anyhow!("{:?}", e))?;
+        }
+        Ok((plot_area, legend_area_opt))
+    }
+
+    /// Draw the chart title on `plot_area` and return the area left for the chart.
+    pub(crate) fn draw_title<DB: DrawingBackend>(
+        &self,
+        plot_area: &DrawingArea<DB, Shift>,
+        title: &str,
+    ) -> Result<DrawingArea<DB, Shift>> {
+        draw_wrapped_title(plot_area, title, self.fonts, MARGIN)
+    }
+
+    /// Truncate the (rotated) Y-axis title to the height of the plotting area in `chart_area`.
+    pub(crate) fn fit_y_title<DB: DrawingBackend>(
+        &self,
+        y_title: &str,
+        chart_area: &DrawingArea<DB, Shift>,
+    ) -> String {
+        let (_, h) = chart_area.dim_in_pixel();
+        let available = h.saturating_sub(MARGIN as u32 + self.bottom_label_px);
+        text::truncate_to_width(y_title, self.fonts.axis_title_px, available)
+    }
+
+    /// A chart builder on `chart_area` with this layout's margin and label areas.
+    pub(crate) fn chart_builder<'b, DB: DrawingBackend>(
+        &self,
+        chart_area: &'b DrawingArea<DB, Shift>,
+    ) -> ChartBuilder<'b, 'b, DB> {
+        let mut builder = ChartBuilder::on(chart_area);
+        builder
+            .margin_bottom(MARGIN as u32)
+            .set_label_area_size(LabelAreaPosition::Left, self.left_label_px)
+            .set_label_area_size(LabelAreaPosition::Bottom, self.bottom_label_px);
+        builder
+    }
+}
+
+/// Draw `title` centred at the top of `area`, inside a `margin` on the top, left and right,
+/// and return the area left for the chart (build it with only a bottom margin).
+///
+/// Titles wider than the area wrap onto a second line and are truncated with an ellipsis
+/// beyond that. A single line is laid out exactly like `ChartBuilder::caption`.
+pub(crate) fn draw_wrapped_title<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    title: &str,
+    fonts: &FontOptions,
+    margin: i32,
+) -> Result<DrawingArea<DB, Shift>> {
+    let mut out = area.margin(margin, 0, margin, margin);
+    let (w, _) = out.dim_in_pixel();
+    for line in text::wrap_text_to_lines(title, fonts.title_px, w, 2) {
+        out = out
+            .titled(&line, (fonts.family(), fonts.title_px))
+            .map_err(|e| anyhow!("{:?}", e))?;
+    }
+    Ok(out)
+}
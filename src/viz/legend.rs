@@ -4,12 +4,22 @@ use anyhow::Result;
 use plotters::backend::DrawingBackend;
 use plotters::coord::Shift;
 use plotters::prelude::*;
-use plotters::style::FontFamily;
 use plotters::style::text_anchor::{HPos, Pos, VPos};
 
+use super::fonts::FontOptions;
+use super::pattern;
+use super::style::FillPattern;
 use super::text::{estimate_text_width_px, wrap_text_to_width};
 use super::types::LegendMode;
 
+/// What a chart puts in its external legend: one label and colour per entry, and the fill
+/// patterns of the kinds that draw them (empty otherwise).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LegendEntries {
+    pub(crate) items: Vec<(String, RGBAColor)>,
+    pub(crate) patterns: Vec<FillPattern>,
+}
+
 /// Estimate how tall the TOP/BOTTOM legend band must be to fit all items,
 /// honoring wrapping and multi-row flow. Returns pixels.
 ///
@@ -146,22 +156,44 @@ pub fn estimate_top_bottom_legend_height_px(
 /// - If the sum fits the band width, use these widths so single-line labels do not wrap unnecessarily.
 /// - Otherwise, fall back to uniform column widths and wrap as needed.
 /// - Column x-positions are consistent across all rows, so entries align like a table.
+///
+/// The panel background is left to the caller so transparent charts stay transparent.
 pub fn draw_legend_panel<DB: DrawingBackend>(
     legend_area: &DrawingArea<DB, Shift>,
     items: &[(String, RGBAColor)],
     title: &str, // pass "" to omit (recommended)
     placement: LegendMode,
     axis_x_start_px: i32, // plot's X-axis start (from root's left edge)
+    fonts: &FontOptions,
 ) -> Result<()> {
-    legend_area
-        .fill(&WHITE)
-        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    draw_legend_panel_with_patterns(
+        legend_area,
+        items,
+        &[],
+        title,
+        placement,
+        axis_x_start_px,
+        fonts,
+    )
+}
 
+/// [`draw_legend_panel`] with a fill pattern per item (missing entries are solid); patterned
+/// items get a small square swatch instead of the round marker.
+pub fn draw_legend_panel_with_patterns<DB: DrawingBackend>(
+    legend_area: &DrawingArea<DB, Shift>,
+    items: &[(String, RGBAColor)],
+    patterns: &[FillPattern],
+    title: &str,
+    placement: LegendMode,
+    axis_x_start_px: i32,
+    fonts: &FontOptions,
+) -> Result<()> {
+    let pattern_of = |i: usize| patterns.get(i).copied().unwrap_or_default();
     let (w_u32, _) = legend_area.dim_in_pixel();
     let w = w_u32 as i32;
 
     // Layout constants (must match estimator)
-    let font_px: u32 = 14;
+    let font_px: u32 = fonts.legend_px;
     let line_h: i32 = font_px as i32 + 2;
     let row_gap: i32 = 4;
     let pad_small: i32 = 6;
@@ -172,11 +204,11 @@ pub fn draw_legend_panel<DB: DrawingBackend>(
 
     // Styles
     let has_title = !title.trim().is_empty();
-    let title_font_px: u32 = 16;
-    let title_style: TextStyle = TextStyle::from((FontFamily::SansSerif, title_font_px))
-        .pos(Pos::new(HPos::Left, VPos::Top));
+    let title_font_px: u32 = fonts.legend_px + 2;
+    let title_style: TextStyle =
+        TextStyle::from((fonts.family(), title_font_px)).pos(Pos::new(HPos::Left, VPos::Top));
     let label_style_center: TextStyle =
-        TextStyle::from((FontFamily::SansSerif, font_px)).pos(Pos::new(HPos::Left, VPos::Center));
+        TextStyle::from((fonts.family(), font_px)).pos(Pos::new(HPos::Left, VPos::Center));
 
     match placement {
         LegendMode::Right => {
@@ -196,20 +228,20 @@ pub fn draw_legend_panel<DB: DrawingBackend>(
             let text_x = pad_x + 24;
             let max_text_w = (w - text_x - pad_x).max(40) as u32;
 
-            for (label, color) in items {
+            for (i, (label, color)) in items.iter().enumerate() {
                 let lines = wrap_text_to_width(label, font_px, max_text_w);
                 let block_h = (lines.len().max(1) as i32) * line_h;
 
                 let marker_x = pad_x + 12;
                 let block_center_y = y + block_h / 2;
 
-                legend_area
-                    .draw(&Circle::new(
-                        (marker_x, block_center_y),
-                        marker_radius,
-                        color.clone().filled(),
-                    ))
-                    .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                draw_swatch(
+                    legend_area,
+                    (marker_x, block_center_y),
+                    marker_radius,
+                    *color,
+                    pattern_of(i),
+                )?;
 
                 for (i, line) in lines.iter().enumerate() {
                     let line_center_y = y + (i as i32) * line_h + line_h / 2;
@@ -248,6 +280,7 @@ pub fn draw_legend_panel<DB: DrawingBackend>(
             struct ItemRef {
                 label: String,
                 color: RGBAColor,
+                pattern: FillPattern,
             }
             let usable_row_w = w - pad_small;
             let per_item_cap_px: i32 = ((usable_row_w - start_x) as f32 * 0.35).max(140.0) as i32;
@@ -268,7 +301,7 @@ pub fn draw_legend_panel<DB: DrawingBackend>(
                 marker_to_text_gap + marker_radius + max_line_w + trailing_gap
             };
 
-            for (label, color) in items.iter() {
+            for (i, (label, color)) in items.iter().enumerate() {
                 let remaining_line_px = (usable_row_w - x).max(40);
                 let text_cap_now =
                     remaining_line_px - (marker_to_text_gap + marker_radius + trailing_gap);
@@ -291,6 +324,7 @@ pub fn draw_legend_panel<DB: DrawingBackend>(
                 cur.push(ItemRef {
                     label: label.clone(),
                     color: *color,
+                    pattern: pattern_of(i),
                 });
             }
             if !cur.is_empty() {
@@ -355,13 +389,13 @@ pub fn draw_legend_panel<DB: DrawingBackend>(
                     let text_x = col_x[ci];
                     let dot_x = (text_x - marker_to_text_gap).max(0);
 
-                    legend_area
-                        .draw(&Circle::new(
-                            (dot_x, y_center),
-                            marker_radius,
-                            it.color.clone().filled(),
-                        ))
-                        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                    draw_swatch(
+                        legend_area,
+                        (dot_x, y_center),
+                        marker_radius,
+                        it.color,
+                        it.pattern,
+                    )?;
 
                     let lines = &blocks_lines[ci];
                     let block_h = (lines.len().max(1) as i32) * line_h;
@@ -383,10 +417,91 @@ pub fn draw_legend_panel<DB: DrawingBackend>(
             }
         }
 
-        LegendMode::Inside => {
+        LegendMode::Inside | LegendMode::Hidden | LegendMode::Direct => {
             // Not used for external panel layout
         }
     }
 
     Ok(())
 }
+
+/// Direct labels: each `(y, label, colour)` drawn in the label strip `area` left of the
+/// plot, at the pixel row `y` of its line end (in backend coordinates), moved apart just
+/// enough not to overlap and kept within `rows` (the plotting area's pixel rows).
+pub(crate) fn draw_direct_labels<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    labels: &[(i32, String, RGBAColor)],
+    rows: std::ops::Range<i32>,
+    fonts: &FontOptions,
+) -> Result<()> {
+    let (_, base_y) = area.get_base_pixel();
+    let (w, _) = area.dim_in_pixel();
+    let line_h = fonts.legend_px as i32 + 2;
+    let mut order: Vec<usize> = (0..labels.len()).collect();
+    order.sort_by_key(|&i| labels[i].0);
+    let wanted: Vec<i32> = order.iter().map(|&i| labels[i].0 - base_y).collect();
+    let ys = spread(
+        &wanted,
+        line_h,
+        (rows.start - base_y + line_h / 2)..(rows.end - base_y - line_h / 2),
+    );
+    let pad_x = 6;
+    for (&i, y) in order.iter().zip(ys) {
+        let (_, label, color) = &labels[i];
+        let text = super::text::truncate_to_width(label, fonts.legend_px, w.saturating_sub(12));
+        let style = TextStyle::from((fonts.family(), fonts.legend_px))
+            .color(color)
+            .pos(Pos::new(HPos::Left, VPos::Center));
+        area.draw(&Text::new(text, (pad_x, y), style))
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    }
+    Ok(())
+}
+
+/// Positions for the sorted rows `wanted`, at least `gap` apart and inside `bounds` where
+/// there is room: pushed down from the top bound past each predecessor, then up from the
+/// bottom bound.
+fn spread(wanted: &[i32], gap: i32, bounds: std::ops::Range<i32>) -> Vec<i32> {
+    let mut ys = wanted.to_vec();
+    let mut floor = bounds.start;
+    for y in ys.iter_mut() {
+        *y = (*y).max(floor);
+        floor = *y + gap;
+    }
+    let mut ceiling = bounds.end;
+    for y in ys.iter_mut().rev() {
+        *y = (*y).min(ceiling);
+        ceiling = *y - gap;
+    }
+    ys
+}
+
+/// Legend marker: a dot for solid fills, a patterned square otherwise.
+fn draw_swatch<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    center: (i32, i32),
+    radius: i32,
+    color: RGBAColor,
+    fill: FillPattern,
+) -> Result<()> {
+    if fill == FillPattern::Solid {
+        return area
+            .draw(&Circle::new(center, radius, color.filled()))
+            .map_err(|e| anyhow::anyhow!("{:?}", e));
+    }
+    let h = radius + 2;
+    let (x0, y0, x1, y1) = (center.0 - h, center.1 - h, center.0 + h, center.1 + h);
+    area.draw(&Rectangle::new(
+        [(x0, y0), (x1, y1)],
+        color.mix(0.15).filled(),
+    ))
+    .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    let corners = [x0, x1, x1, x0]
+        .into_iter()
+        .zip([y0, y0, y1, y1])
+        .map(|(x, y)| (x as f64, y as f64))
+        .collect::<Vec<_>>();
+    pattern::draw_pattern(area, &corners, fill, color)?;
+    area.draw(&Rectangle::new([(x0, y0), (x1, y1)], color.stroke_width(1)))
+        .map_err(|e| anyhow::anyhow!("{:?}", e))
+}
@@ -1,61 +1,108 @@
-//! Visualization utilities: render multi-series charts to **SVG** or **PNG**.
+//! Visualization utilities: render multi-series charts to **SVG**, **PDF** or **PNG**.
 //!
 //! - Distinct series colors (Microsoft Office palette)
 //! - Locale-aware tick labels (`30,000` vs `30.000`), whole numbers
-//! - Legend placement: `Inside`, `Right`, `Top`, `Bottom` (non-overlapping for external legends)
-//! - Plot kinds: `Line`, `Scatter`, `LinePoints`, `Area`, `StackedArea`, `GroupedBar`, `Loess`
+//! - Legend placement: `Inside`, `Right`, `Top`, `Bottom` (non-overlapping for external legends),
+//!   `Hidden` with the legend rendered on its own by [`render_legend`], or `Direct` labels at
+//!   the line ends
+//! - Plot kinds: `Line`, `Scatter`, `LinePoints`, `Area`, `StackedArea`, `GroupedBar`, `Loess`,
+//!   `XYScatter`, `Envelope`, `FanChart`, `Pareto`, `Waterfall`
+//! - Grouped bars by year, country or indicator, with categorical X-axis labels
+//! - Per-capita, indexed (base year = 100) and year-over-year growth views
+//! - Optional error bars from explicit bounds or observation metadata
+//! - Transparent backgrounds and high-DPI (2×/3×) bitmap output
 //! - Custom chart title and legend handling for long labels
+//! - Custom fonts and per-element font sizes
+//! - Summary bar charts and correlation heatmaps for [`crate::stats`] outputs
+//! - Sequential and diverging colour scales with a colour bar ([`colorscale`])
+//! - Logo and watermark branding in a chart corner ([`Branding`])
+//! - Animated year-by-year exports (GIF/APNG) via [`animate`]
+//! - [`auto_chart`]: one chart, an indexed chart or one panel per indicator, picked from the
+//!   units and number of series
 
+pub mod animate;
+mod auto;
+mod axis_break;
+mod bars;
+mod branding;
+pub mod chart_diff;
+pub mod colorscale;
+mod dash;
+mod embed;
+pub mod errorbars;
+pub mod fonts;
+mod heatmap;
+mod i18n;
+mod layout;
 pub mod legend;
 pub mod loess;
+mod pareto;
+mod pattern;
+mod pdf;
+pub mod plotters_adapter;
+mod scaled;
+pub mod style;
+mod summary;
 pub mod text;
 pub mod types;
 pub mod util;
+mod waterfall;
+mod xy;
 
 // Re-export types for public API
-pub use types::{DEFAULT_LEGEND_MODE, LegendMode, PlotKind};
+pub use types::{
+    AxisBreak, AxisScale, BarGrouping, BarOptions, BarOrder, DEFAULT_LEGEND_MODE, LegendMode,
+    MarkerDecimation, ParetoOptions, PercentBounds, PlotKind, PlotOptions, PlotTransform,
+    StackNegatives, StyleMode, WaterfallOptions, XYScatterOptions,
+};
 
-// Re-export style modules (transitional)
-pub use crate::viz_style as style;
+pub use animate::{AnimationKind, AnimationOptions, animate};
+pub use auto::{AutoLayout, auto_chart, auto_layout};
+pub use branding::{Branding, Corner};
+pub use embed::embedded_data;
+pub use errorbars::{ErrorBarOptions, UncertaintyKey, UncertaintySource};
+pub use fonts::{CustomFont, FontOptions};
 
-use crate::models::DataPoint;
-use anyhow::{Result, anyhow};
-use num_format::Locale;
+use crate::models::{DataPoint, GroupKey, Period};
+use crate::stats::{CorrelationMatrix, Summary};
+use anyhow::{Context, Result, anyhow};
 
 use plotters::backend::DrawingBackend;
+use plotters::chart::SeriesAnno;
 use plotters::coord::Shift;
+use plotters::coord::types::RangedCoordf64;
 use plotters::prelude::*;
 use plotters::series::{AreaSeries, LineSeries};
 
-use plotters::style::FontFamily;
-
 use plotters_bitmap::BitMapBackend;
 use plotters_svg::SVGBackend;
 
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
 use std::sync::Once;
 
-use legend::{draw_legend_panel, estimate_top_bottom_legend_height_px};
-use util::{
-    choose_axis_scale, compute_left_label_area_px, derive_axis_unit, is_percentage_like,
-    map_locale, office_color,
-};
+use layout::Layout;
+use legend::{LegendEntries, draw_legend_panel_with_patterns};
+use util::{derive_axis_unit, office_color};
 
 use loess::loess_series;
 
 /// One-time registration for a fallback "sans-serif" font when using the `ab_glyph` text path.
-/// Required because `ab_glyph` doesn't discover OS fonts.
+/// Required because `ab_glyph` doesn't discover OS fonts. Not on wasm32, where plotters
+/// measures text with the page's fonts.
+#[cfg(not(target_arch = "wasm32"))]
 static INIT_FONTS: Once = Once::new();
 
 fn ensure_fonts_registered() {
     // Safe to call many times; only runs once.
+    #[cfg(not(target_arch = "wasm32"))]
     INIT_FONTS.call_once(|| {
-        // Updated path for new module location: from `src/viz/mod.rs` → project root → `assets/DejaVuSans.ttf`
         let _ = plotters::style::register_font(
             "sans-serif",
             plotters::style::FontStyle::Normal,
-            include_bytes!("../../assets/DejaVuSans.ttf"),
+            text::BUNDLED_FONT,
         );
     });
 }
@@ -151,6 +198,8 @@ pub fn plot_lines_locale_with_legend_title<P: AsRef<Path>>(
 }
 
 /// Fully-configurable entry point: choose locale, legend placement, custom title, plot kind, and LOESS span.
+///
+/// Equivalent to [`plot_with_options`] with the corresponding [`PlotOptions`] fields set.
 #[allow(clippy::too_many_arguments)]
 pub fn plot_chart<P: AsRef<Path>>(
     points: &[DataPoint],
@@ -164,195 +213,1126 @@ pub fn plot_chart<P: AsRef<Path>>(
     loess_span: f64, // fraction of neighbors (0,1], used only for PlotKind::Loess
     country_styles: Option<bool>, // None when feature disabled, Some(bool) when enabled
 ) -> Result<()> {
+    let options = PlotOptions {
+        width,
+        height,
+        locale: locale_tag.to_string(),
+        legend,
+        title: title.to_string(),
+        kind,
+        loess_span,
+        country_styles: country_styles.unwrap_or(false),
+        ..Default::default()
+    };
+    plot_with_options(points, out_path, &options)
+}
+
+/// Render a chart described by [`PlotOptions`].
+///
+/// The backend is selected from the output extension: `.svg` → SVG, `.pdf` → vector PDF,
+/// anything else → bitmap.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::models::DataPoint;
+/// use wbi_rs::viz::{self, PlotKind, PlotOptions};
+/// # let points: Vec<DataPoint> = vec![];
+/// let opts = PlotOptions { kind: PlotKind::Area, title: "Population".into(), ..Default::default() };
+/// viz::plot_with_options(&points, "pop.svg", &opts)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn plot_with_options<P: AsRef<Path>>(
+    points: &[DataPoint],
+    out_path: P,
+    options: &PlotOptions,
+) -> Result<()> {
+    let out_path = out_path.as_ref();
+    if options.embed_data
+        && out_path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("svg"))
+    {
+        let svg = render_svg_string(points, options)?;
+        return std::fs::write(out_path, svg)
+            .with_context(|| format!("writing {}", out_path.display()));
+    }
+    // Validate before creating a backend so no empty file is left behind on error.
+    let (points, prepared) = prepare_points(points, options)?;
+    render(out_path, &points, &prepared, options)
+}
+
+/// Render a chart as PNG bytes in memory, e.g. for a preview pane that re-renders whenever the
+/// options change. Same output as [`plot_with_options`] with a `.png` path (`scale` and
+/// `transparent` included), but nothing is written to disk.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::models::DataPoint;
+/// use wbi_rs::viz::{self, PlotOptions};
+/// # let points: Vec<DataPoint> = vec![];
+/// let png = viz::render_png_bytes(&points, &PlotOptions::default())?;
+/// assert!(png.starts_with(b"\x89PNG"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn render_png_bytes(points: &[DataPoint], options: &PlotOptions) -> Result<Vec<u8>> {
+    let (points, prepared) = prepare_points(points, options)?;
+    check_render_options(options)?;
+    let mut out = Vec::new();
+    write_png(&mut out, options, bitmap_size(options)?, |root, bg| {
+        draw_prepared(root, &points, &prepared, options, bg, true).map(drop)
+    })?;
+    Ok(out)
+}
+
+/// Render a chart as an SVG document in memory, e.g. to inline it in HTML or answer an HTTP
+/// request. Same output as [`plot_with_options`] with a `.svg` path.
+pub fn render_svg_string(points: &[DataPoint], options: &PlotOptions) -> Result<String> {
+    let (prepared_points, prepared) = prepare_points(points, options)?;
+    check_render_options(options)?;
+    let mut svg = svg_document(&prepared_points, &prepared, options)?;
+    if options.embed_data {
+        // The rows as given, so that re-plotting them applies the transform afresh.
+        embed::insert(&mut svg, points)?;
+    }
+    Ok(svg)
+}
+
+/// Render only the legend of the chart `options` describes, e.g. to share one legend between
+/// several small charts drawn with [`LegendMode::Hidden`]. The entries, colours and fill
+/// patterns are those the chart would show; the file is `options.width` wide and as tall as
+/// the entries need. The backend follows the extension as in [`plot_with_options`].
+///
+/// Errors for charts without legend entries, such as a correlation heatmap.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::models::DataPoint;
+/// use wbi_rs::viz::{self, LegendMode, PlotOptions};
+/// # let points: Vec<DataPoint> = vec![];
+/// let opts = PlotOptions { legend: LegendMode::Hidden, ..Default::default() };
+/// viz::plot_with_options(&points, "chart.svg", &opts)?;
+/// viz::render_legend(&points, "legend.svg", &opts)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn render_legend<P: AsRef<Path>>(
+    points: &[DataPoint],
+    out_path: P,
+    options: &PlotOptions,
+) -> Result<()> {
+    let (points, prepared) = prepare_points(points, options)?;
+    check_render_options(options)?;
+    // Draw the chart off-screen without a legend to learn its entries.
+    let hidden = PlotOptions {
+        legend: LegendMode::Hidden,
+        ..options.clone()
+    };
+    let mut scratch = String::new();
+    let entries = {
+        let root = SVGBackend::with_string(&mut scratch, (options.width, options.height))
+            .into_drawing_area();
+        draw_prepared(
+            root,
+            &points,
+            &prepared,
+            &hidden,
+            background(options),
+            false,
+        )?
+    };
+    if entries.items.is_empty() {
+        return Err(anyhow!("this chart has no legend entries"));
+    }
+
+    let labels: Vec<String> = entries.items.iter().map(|(l, _)| l.clone()).collect();
+    let fonts = &options.fonts;
+    let height = legend::estimate_top_bottom_legend_height_px(
+        &labels,
+        LEGEND_ONLY_INSET_PX,
+        options.width as i32,
+        false,
+        fonts.legend_px + 2,
+        fonts.legend_px,
+    )
+    .max(40) as u32;
+    // Same font pinning as the chart itself (see `draw_prepared`).
+    let fonts = if options.deterministic {
+        FontOptions {
+            custom: None,
+            ..options.fonts.clone()
+        }
+    } else {
+        options.fonts.clone()
+    };
+    let options = &PlotOptions {
+        height,
+        fonts,
+        ..options.clone()
+    };
+    let size = (options.width, height);
+    let background = background(options);
+
+    let out_path = out_path.as_ref();
+    let ext = out_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("svg") => {
+            let root = SVGBackend::new(out_path, size).into_drawing_area();
+            draw_legend_only(root, &entries, options, background)
+        }
+        Some("pdf") => {
+            let root = pdf::PdfBackend::new(out_path, size).into_drawing_area();
+            draw_legend_only(root, &entries, options, background)
+        }
+        _ if options.transparent => {
+            if ext.as_deref() != Some("png") {
+                return Err(anyhow!("transparent bitmaps require a .png output path"));
+            }
+            let file = std::fs::File::create(out_path)?;
+            write_png(
+                std::io::BufWriter::new(file),
+                options,
+                bitmap_size(options)?,
+                |root, bg| draw_legend_only(root, &entries, options, bg),
+            )
+        }
+        _ => {
+            let backend = bitmap_file(out_path, bitmap_size(options)?)?;
+            let root = scaled::ScaledBackend::new(backend, options.scale).into_drawing_area();
+            draw_legend_only(root, &entries, options, background)
+        }
+    }
+}
+
+/// Left inset of the first legend column in a [`render_legend`] file.
+const LEGEND_ONLY_INSET_PX: i32 = 12;
+
+/// Draw `entries` as a legend band filling `root`.
+fn draw_legend_only<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    entries: &LegendEntries,
+    options: &PlotOptions,
+    background: RGBAColor,
+) -> Result<()> {
+    root.fill(&background).map_err(|e| anyhow!("{:?}", e))?;
+    draw_legend_panel_with_patterns(
+        &root,
+        &entries.items,
+        &entries.patterns,
+        "",
+        LegendMode::Top,
+        LEGEND_ONLY_INSET_PX,
+        &options.fonts,
+    )?;
+    root.present().map_err(|e| anyhow!("{:?}", e))?;
+    Ok(())
+}
+
+/// A way a chart shows the data differently from its values. Listed by [`plot_warnings`] and
+/// logged as `tracing` warnings when the chart is drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlotWarning {
+    /// Negative values drawn as zero on a [`PlotKind::StackedArea`] chart
+    /// ([`StackNegatives::Clamp`]).
+    NegativesClamped {
+        country_iso3: String,
+        indicator_id: String,
+        /// Years with a negative value, ascending.
+        years: Vec<i32>,
+    },
+}
+
+impl std::fmt::Display for PlotWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlotWarning::NegativesClamped {
+                country_iso3,
+                indicator_id,
+                years,
+            } => write!(
+                f,
+                "{country_iso3} {indicator_id}: negative values in {} drawn as zero in the stack",
+                year_list(years)
+            ),
+        }
+    }
+}
+
+/// `2001, 2003, 2004`, shortened to the first five and a count.
+fn year_list(years: &[i32]) -> String {
+    let shown: Vec<String> = years.iter().take(5).map(i32::to_string).collect();
+    match years.len() {
+        n if n > 5 => format!("{} and {} more", shown.join(", "), n - 5),
+        _ => shown.join(", "),
+    }
+}
+
+/// Warnings about how the chart of `points` under `options` would misrepresent the data, e.g.
+/// negative values clamped on a stacked area chart. Empty when the chart shows the data as
+/// is. `options.transform` is applied first, as when plotting.
+///
+/// ### Example
+/// ```no_run
+/// # use wbi_rs::models::DataPoint;
+/// use wbi_rs::viz::{self, PlotKind, PlotOptions};
+/// # let points: Vec<DataPoint> = vec![];
+/// let opts = PlotOptions { kind: PlotKind::StackedArea, ..Default::default() };
+/// for warning in viz::plot_warnings(&points, &opts)? {
+///     eprintln!("warning: {warning}");
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn plot_warnings(points: &[DataPoint], options: &PlotOptions) -> Result<Vec<PlotWarning>> {
+    let transformed = apply_transform(points, &options.transform)?;
+    let points = transformed.as_deref().unwrap_or(points);
+    if options.kind != PlotKind::StackedArea || options.stack_negatives != StackNegatives::Clamp {
+        return Ok(Vec::new());
+    }
+    Ok(clamp_warnings(points))
+}
+
+/// One [`PlotWarning::NegativesClamped`] per series with negative values.
+fn clamp_warnings(points: &[DataPoint]) -> Vec<PlotWarning> {
+    negative_years(points)
+        .into_iter()
+        .map(|(key, years)| PlotWarning::NegativesClamped {
+            country_iso3: key.country_iso3,
+            indicator_id: key.indicator_id,
+            years,
+        })
+        .collect()
+}
+
+/// Series with negative values, and the years they occur in.
+fn negative_years(points: &[DataPoint]) -> Vec<(GroupKey, Vec<i32>)> {
+    group_series(points)
+        .into_iter()
+        .filter_map(|(key, series)| {
+            let years: Vec<i32> = series
+                .iter()
+                .filter(|(_, v)| *v < 0.0)
+                .map(|(p, _)| p.year())
+                .collect();
+            (!years.is_empty()).then_some((key, years))
+        })
+        .collect()
+}
+
+/// Observations grouped into one series per `(indicator, country)` and sorted by period — the
+/// grouping behind the line, area and bar charts. Rows without a value or a year are skipped;
+/// each key's `unit` is the first non-empty unit among its rows, and `region` is left empty.
+///
+/// ### Example
+/// ```
+/// use wbi_rs::models::{DataPoint, GroupKey, Period};
+/// use wbi_rs::viz::group_series;
+/// let dp = |year, value| DataPoint {
+///     indicator_id: "SP.POP.TOTL".into(), indicator_name: "Population".into(),
+///     country_id: "DE".into(), country_name: "Germany".into(), country_iso3: "DEU".into(),
+///     year, value, unit: None, obs_status: None, decimal: None,
+/// };
+/// let groups = group_series(&[dp(2021, Some(2.0)), dp(2020, Some(1.0)), dp(2022, None)]);
+/// let series = &groups[&GroupKey::new("SP.POP.TOTL", "DEU")];
+/// assert_eq!(series, &[(Period::Year(2020), 1.0), (Period::Year(2021), 2.0)]);
+/// ```
+pub fn group_series(points: &[DataPoint]) -> BTreeMap<GroupKey, Vec<(Period, f64)>> {
+    // (first non-empty unit, observations) per (indicator, country)
+    type Group<'a> = (Option<&'a str>, Vec<(Period, f64)>);
+    let mut groups: BTreeMap<(&str, &str), Group> = BTreeMap::new();
+    for p in points {
+        let (unit, series) = groups
+            .entry((&p.indicator_id, &p.country_iso3))
+            .or_default();
+        if unit.is_none() {
+            *unit = p.unit.as_deref().filter(|u| !u.trim().is_empty());
+        }
+        if let (y, Some(v)) = (p.year, p.value)
+            && y != 0
+        {
+            series.push((Period::Year(y), v));
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|(_, (_, series))| !series.is_empty())
+        .map(|((indicator_id, iso3), (unit, mut series))| {
+            series.sort_by_key(|(period, _)| *period);
+            let key = GroupKey {
+                unit: unit.map(str::to_string),
+                ..GroupKey::new(indicator_id, iso3)
+            };
+            (key, series)
+        })
+        .collect()
+}
+
+/// Bar chart of group means with min–max whiskers, one bar per `(indicator, country)` group of
+/// [`crate::stats::grouped_summary`]. Bars are coloured by indicator.
+///
+/// Uses `width`, `height`, `title`, `legend`, `fonts`, `y_unit`, `y_scale`, `transparent` and
+/// `scale` from `options`.
+pub fn plot_summary<P: AsRef<Path>>(
+    summaries: &[Summary],
+    out_path: P,
+    options: &PlotOptions,
+) -> Result<()> {
+    let prepared = Prepared::Summary(summary::SummaryData::new(summaries)?);
+    render(out_path.as_ref(), &[], &prepared, options)
+}
+
+/// Heatmap of a [`CorrelationMatrix`] on a diverging blue–white–red scale (−1 … +1).
+///
+/// Uses `width`, `height`, `title`, `fonts`, `transparent` and `scale` from `options`.
+pub fn plot_correlation<P: AsRef<Path>>(
+    matrix: &CorrelationMatrix,
+    out_path: P,
+    options: &PlotOptions,
+) -> Result<()> {
+    heatmap::validate(matrix)?;
+    let prepared = Prepared::Correlation(matrix.clone());
+    render(out_path.as_ref(), &[], &prepared, options)
+}
+
+/// Apply `options.transform` to `points` and prepare the chart data; shared by every
+/// point-based entry point.
+fn prepare_points<'a>(
+    points: &'a [DataPoint],
+    options: &PlotOptions,
+) -> Result<(Cow<'a, [DataPoint]>, Prepared)> {
     if points.is_empty() {
         return Err(anyhow!("no data to plot"));
     }
+    let points = match apply_transform(points, &options.transform)? {
+        Some(transformed) => Cow::Owned(transformed),
+        None => Cow::Borrowed(points),
+    };
+    let prepared = Prepared::new(&points, options)?;
+    Ok((points, prepared))
+}
+
+/// Page background: white, or nothing for `transparent`.
+fn background(options: &PlotOptions) -> RGBAColor {
+    if options.transparent {
+        TRANSPARENT
+    } else {
+        WHITE.to_rgba()
+    }
+}
+
+/// Create the backend for `out_path` and draw `prepared` on it.
+fn render(
+    out_path: &Path,
+    points: &[DataPoint],
+    prepared: &Prepared,
+    options: &PlotOptions,
+) -> Result<()> {
+    check_render_options(options)?;
+    let size = (options.width, options.height);
+    let background = background(options);
+
+    let ext = out_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase());
+    if ext.as_deref() == Some("svg") {
+        let svg = svg_document(points, prepared, options)?;
+        return std::fs::write(out_path, svg)
+            .with_context(|| format!("writing {}", out_path.display()));
+    }
+    if ext.as_deref() == Some("pdf") {
+        let root = pdf::PdfBackend::new(out_path, size).into_drawing_area();
+        draw_prepared(root, points, prepared, options, background, true)?;
+        return Ok(());
+    }
+
+    let px = bitmap_size(options)?;
+    if options.transparent {
+        if ext.as_deref() != Some("png") {
+            return Err(anyhow!("transparent bitmaps require a .png output path"));
+        }
+        let file = std::fs::File::create(out_path)?;
+        write_png(std::io::BufWriter::new(file), options, px, |root, bg| {
+            draw_prepared(root, points, prepared, options, bg, true).map(drop)
+        })?;
+    } else {
+        let backend = bitmap_file(out_path, px)?;
+        let root = scaled::ScaledBackend::new(backend, options.scale).into_drawing_area();
+        draw_prepared(root, points, prepared, options, background, true)?;
+    }
+    Ok(())
+}
+
+/// Draw `prepared` as an SVG document, with the branding logo (if any) inlined.
+fn svg_document(
+    points: &[DataPoint],
+    prepared: &Prepared,
+    options: &PlotOptions,
+) -> Result<String> {
+    let mut svg = String::new();
+    {
+        let root =
+            SVGBackend::with_string(&mut svg, (options.width, options.height)).into_drawing_area();
+        draw_prepared(root, points, prepared, options, background(options), false)?;
+    }
+    branding::insert_svg_logo(&mut svg, options)?;
+    Ok(svg)
+}
+
+/// Checks shared by every backend; also registers the bundled fonts.
+fn check_render_options(options: &PlotOptions) -> Result<()> {
     ensure_fonts_registered();
-    let out_path = out_path.as_ref();
-    let path_string = out_path.to_string_lossy().into_owned();
+    if !options.deterministic {
+        options.fonts.prepare()?;
+    }
+    options.y_scale.validate()?;
+    options.percent_bounds.validate()?;
+    options.bars.validate()?;
+    options.branding.validate()?;
+    options.pareto.validate()?;
+    options.waterfall.validate()?;
+    if let Some(brk) = &options.y_break {
+        brk.validate()?;
+    }
+    if options.tick_abbreviation && options.y_scale != AxisScale::Auto {
+        return Err(anyhow!(
+            "abbreviated ticks replace the axis scale; leave the Y scale on auto"
+        ));
+    }
+    if !options.projections.is_empty() && options.transform != PlotTransform::None {
+        return Err(anyhow!(
+            "projections are drawn as given and cannot be combined with a {:?} transform",
+            options.transform
+        ));
+    }
+    options.style_config.validate()
+}
 
-    let years: Vec<i32> = points.iter().map(|p| p.year).filter(|y| *y != 0).collect();
-    let (mut min_year, mut max_year) = (
-        *years
-            .iter()
-            .min()
-            .ok_or_else(|| anyhow!("no valid years"))?,
-        *years
-            .iter()
-            .max()
-            .ok_or_else(|| anyhow!("no valid years"))?,
-    );
-    if min_year == max_year {
-        min_year -= 1;
-        max_year += 1;
+/// Whether `kind` draws [`PlotOptions::projections`].
+fn draws_projections(kind: PlotKind) -> bool {
+    matches!(
+        kind,
+        PlotKind::Line | PlotKind::LinePoints | PlotKind::Scatter | PlotKind::Area
+    )
+}
+
+/// Bitmap size in pixels: the logical size times `options.scale`.
+fn bitmap_size(options: &PlotOptions) -> Result<(u32, u32)> {
+    if !(options.scale.is_finite() && options.scale > 0.0) {
+        return Err(anyhow!(
+            "scale must be a positive number, got {}",
+            options.scale
+        ));
     }
+    Ok((
+        (options.width as f64 * options.scale).round() as u32,
+        (options.height as f64 * options.scale).round() as u32,
+    ))
+}
 
-    let values: Vec<f64> = points.iter().filter_map(|p| p.value).collect();
-    if values.is_empty() {
-        return Err(anyhow!("no numeric values to plot"));
+/// Draw into memory with `draw` and encode as PNG: RGBA when `options.transparent`, RGB
+/// otherwise.
+fn write_png<W, F>(out: W, options: &PlotOptions, px: (u32, u32), draw: F) -> Result<()>
+where
+    W: std::io::Write,
+    F: Fn(DrawingArea<scaled::ScaledBackend<BitMapBackend<'_>>, Shift>, RGBAColor) -> Result<()>,
+{
+    let layer = |bg: RGBAColor| -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; px.0 as usize * px.1 as usize * 3];
+        {
+            let backend = BitMapBackend::with_buffer(&mut buf, px);
+            let root = scaled::ScaledBackend::new(backend, options.scale).into_drawing_area();
+            draw(root, bg)?;
+        }
+        Ok(buf)
+    };
+    let (data, color) = if options.transparent {
+        // Render on white and on black, then recover alpha from the difference.
+        let rgba = scaled::unblend_rgba(&layer(WHITE.to_rgba())?, &layer(BLACK.to_rgba())?);
+        (rgba, png::ColorType::Rgba)
+    } else {
+        (layer(WHITE.to_rgba())?, png::ColorType::Rgb)
+    };
+    let mut encoder = png::Encoder::new(out, px.0, px.1);
+    encoder.set_color(color);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Bitmap backend that writes `out_path` (format from the extension) when presented.
+#[cfg(not(target_arch = "wasm32"))]
+fn bitmap_file(out_path: &Path, px: (u32, u32)) -> Result<BitMapBackend<'_>> {
+    Ok(BitMapBackend::new(out_path, px))
+}
+
+/// plotters has no file-writing bitmap backend on wasm32; SVG strings work there.
+#[cfg(target_arch = "wasm32")]
+fn bitmap_file(out_path: &Path, _px: (u32, u32)) -> Result<BitMapBackend<'_>> {
+    Err(anyhow!(
+        "cannot write {} on wasm32: render an SVG string instead",
+        out_path.display()
+    ))
+}
+
+/// Animated GIF backend writing `out_path`, one frame per `present()`.
+#[cfg(not(target_arch = "wasm32"))]
+fn gif_file(out_path: &Path, px: (u32, u32), delay_ms: u32) -> Result<BitMapBackend<'_>> {
+    BitMapBackend::gif(out_path, px, delay_ms).map_err(|e| anyhow!("{:?}", e))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn gif_file(out_path: &Path, _px: (u32, u32), _delay_ms: u32) -> Result<BitMapBackend<'_>> {
+    Err(anyhow!("cannot write {} on wasm32", out_path.display()))
+}
+
+/// Apply `transform`, returning `None` when the data is plotted as-is.
+fn apply_transform(
+    points: &[DataPoint],
+    transform: &PlotTransform,
+) -> Result<Option<Vec<DataPoint>>> {
+    use crate::stats;
+    let pair = |a: &str, b: &str, comparison| {
+        for code in [a, b] {
+            if !points.iter().any(|p| {
+                code.eq_ignore_ascii_case(&p.country_iso3)
+                    || code.eq_ignore_ascii_case(&p.country_id)
+            }) {
+                return Err(anyhow!("country {code} is not in the data"));
+            }
+        }
+        Ok(stats::compare_countries(points, a, b, comparison))
+    };
+    let out = match transform {
+        PlotTransform::None => return Ok(None),
+        PlotTransform::PerCapita => {
+            if !points
+                .iter()
+                .any(|p| p.indicator_id == stats::POPULATION_INDICATOR)
+            {
+                return Err(anyhow!(
+                    "per-capita plots need population ({}) in the data",
+                    stats::POPULATION_INDICATOR
+                ));
+            }
+            stats::per_capita(points)
+        }
+        PlotTransform::Deflate => {
+            let (deflator, rest): (Vec<_>, Vec<_>) = points
+                .iter()
+                .cloned()
+                .partition(|p| p.indicator_id == stats::DEFLATOR_INDICATOR);
+            if deflator.is_empty() {
+                return Err(anyhow!(
+                    "deflated plots need the GDP deflator ({}) in the data",
+                    stats::DEFLATOR_INDICATOR
+                ));
+            }
+            stats::deflate(&rest, &deflator)
+        }
+        PlotTransform::IndexToYear(year) => stats::index_to_year(points, *year),
+        PlotTransform::YoYGrowth => stats::yoy_growth(points),
+        PlotTransform::CountryDifference(a, b) => pair(a, b, stats::CountryComparison::Difference)?,
+        PlotTransform::CountryRatio(a, b) => pair(a, b, stats::CountryComparison::Ratio)?,
+    };
+    if out.is_empty() {
+        return Err(anyhow!(
+            "no data left to plot after {:?} transform",
+            transform
+        ));
     }
-    let (mut min_val, mut max_val) = (
-        values.iter().cloned().fold(f64::INFINITY, f64::min),
-        values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
-    );
-    if (max_val - min_val).abs() < f64::EPSILON {
-        min_val -= 1.0;
-        max_val += 1.0;
+    Ok(Some(out))
+}
+
+/// Year and value extents of the plotted data (degenerate ranges already widened).
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    min_year: i32,
+    max_year: i32,
+    min_val: f64,
+    max_val: f64,
+}
+
+impl Bounds {
+    /// Extents of a [`PlotKind::StackedArea`] chart: the years of `points`, and values from the
+    /// zero line to the tallest stack (and the deepest one when negatives are `mirror`ed).
+    fn stacked(points: &[DataPoint], mirror: bool) -> Result<Self> {
+        let mut bounds = Self::from_points(points, None)?;
+        // (positive total, negative total) per year
+        let mut totals: BTreeMap<i32, (f64, f64)> = BTreeMap::new();
+        for series in group_series(points).into_values() {
+            for (period, v) in series {
+                let (pos, neg) = totals.entry(period.year()).or_default();
+                if v >= 0.0 {
+                    *pos += v;
+                } else if mirror {
+                    *neg += v;
+                }
+            }
+        }
+        bounds.min_val = totals.values().map(|t| t.1).fold(0.0, f64::min);
+        bounds.max_val = totals.values().map(|t| t.0).fold(0.0, f64::max);
+        if (bounds.max_val - bounds.min_val).abs() < f64::EPSILON {
+            bounds.max_val += 1.0;
+        }
+        Ok(bounds)
     }
 
-    let (num_locale, _dec_sep) = map_locale(locale_tag);
+    /// Extents of `points`, widened to include any error-bar `whiskers`.
+    fn from_points(points: &[DataPoint], whiskers: Option<&errorbars::Whiskers>) -> Result<Self> {
+        let years: Vec<i32> = points.iter().map(|p| p.year).filter(|y| *y != 0).collect();
+        let (mut min_year, mut max_year) = (
+            *years
+                .iter()
+                .min()
+                .ok_or_else(|| anyhow!("no valid years"))?,
+            *years
+                .iter()
+                .max()
+                .ok_or_else(|| anyhow!("no valid years"))?,
+        );
+        if min_year == max_year {
+            min_year -= 1;
+            max_year += 1;
+        }
 
-    if out_path.extension().and_then(|s| s.to_str()) == Some("svg") {
-        let root = SVGBackend::new(path_string.as_str(), (width, height)).into_drawing_area();
-        draw_chart(
-            root,
-            points,
+        let values: Vec<f64> = points.iter().filter_map(|p| p.value).collect();
+        if values.is_empty() {
+            return Err(anyhow!("no numeric values to plot"));
+        }
+        let (mut min_val, mut max_val) = (
+            values.iter().cloned().fold(f64::INFINITY, f64::min),
+            values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        );
+        for (lo, hi) in whiskers.into_iter().flat_map(|w| w.values()) {
+            min_val = min_val.min(*lo);
+            max_val = max_val.max(*hi);
+        }
+        if (max_val - min_val).abs() < f64::EPSILON {
+            min_val -= 1.0;
+            max_val += 1.0;
+        }
+        Ok(Self {
             min_year,
             max_year,
             min_val,
             max_val,
-            num_locale,
-            legend,
-            title,
-            kind,
-            loess_span,
-            country_styles,
-        )?;
+        })
+    }
+}
+
+/// Validated chart input, computed once before any backend is created.
+enum Prepared {
+    /// Kinds with years on the X axis.
+    Series(Bounds),
+    /// `PlotKind::XYScatter`: paired per-country observations.
+    XY(xy::XYData),
+    /// `PlotKind::GroupedBar` grouped by country or indicator: one bar per series.
+    Bars(bars::CategoryBars),
+    /// `PlotKind::Pareto`: countries ranked by one indicator in one year.
+    Pareto(pareto::ParetoData),
+    /// `PlotKind::Waterfall`: the change of a total split into contributions.
+    Waterfall(waterfall::WaterfallData),
+    /// [`plot_summary`]: one bar per summary group.
+    Summary(summary::SummaryData),
+    /// [`plot_correlation`]: a validated correlation matrix.
+    Correlation(CorrelationMatrix),
+    /// [`auto_chart`]: small multiples, drawn row by row in a near-square grid.
+    Facets(Vec<Facet>),
+}
+
+/// One panel of [`Prepared::Facets`], with the options it is drawn with.
+struct Facet {
+    points: Vec<DataPoint>,
+    prepared: Prepared,
+    options: PlotOptions,
+}
+
+impl Prepared {
+    fn new(points: &[DataPoint], options: &PlotOptions) -> Result<Self> {
+        match options.kind {
+            PlotKind::XYScatter => Ok(Prepared::XY(xy::XYData::from_points(points, &options.xy)?)),
+            PlotKind::Pareto => Ok(Prepared::Pareto(pareto::ParetoData::from_points(
+                points,
+                &options.pareto,
+            )?)),
+            PlotKind::Waterfall => Ok(Prepared::Waterfall(waterfall::WaterfallData::from_points(
+                points,
+                &options.waterfall,
+            )?)),
+            kind => {
+                let whiskers = match &options.error_bars {
+                    Some(eb) => {
+                        eb.validate()?;
+                        Some(eb.resolve(points))
+                    }
+                    None => None,
+                };
+                if kind == PlotKind::StackedArea {
+                    return Ok(Prepared::Series(stacked_bounds(points, options)?));
+                }
+                if kind == PlotKind::GroupedBar && options.bar_groups != BarGrouping::Years {
+                    return Ok(Prepared::Bars(bars::CategoryBars::from_points(
+                        points,
+                        options.bar_groups,
+                        whiskers.as_ref(),
+                    )?));
+                }
+                // Axes span the projections too, which usually run past the last actual year.
+                let with_projections;
+                let points = if draws_projections(kind) && !options.projections.is_empty() {
+                    with_projections = [points, options.projections.as_slice()].concat();
+                    with_projections.as_slice()
+                } else {
+                    points
+                };
+                Ok(Prepared::Series(Bounds::from_points(
+                    points,
+                    whiskers.as_ref(),
+                )?))
+            }
+        }
+    }
+}
+
+/// Apply `options.stack_negatives` to a stacked area chart of `points`: fail, or log what is
+/// clamped; then its bounds.
+fn stacked_bounds(points: &[DataPoint], options: &PlotOptions) -> Result<Bounds> {
+    match options.stack_negatives {
+        StackNegatives::Error => {
+            let negative: Vec<String> = negative_years(points)
+                .into_iter()
+                .map(|(key, years)| {
+                    format!(
+                        "{} {} ({})",
+                        key.country_iso3,
+                        key.indicator_id,
+                        year_list(&years)
+                    )
+                })
+                .collect();
+            if !negative.is_empty() {
+                return Err(anyhow!(
+                    "stacked area charts cannot stack negative values: {}; clamp them to zero \
+                     or stack them below the zero line instead",
+                    negative.join("; ")
+                ));
+            }
+        }
+        StackNegatives::Clamp => {
+            for warning in clamp_warnings(points) {
+                tracing::warn!("{warning}");
+            }
+        }
+        StackNegatives::Mirror => {}
+    }
+    Bounds::stacked(points, options.stack_negatives == StackNegatives::Mirror)
+}
+
+/// Draw `prepared` with the branding on top and present it. `raster_logo` as in
+/// [`branding::draw`].
+fn draw_prepared<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    points: &[DataPoint],
+    prepared: &Prepared,
+    options: &PlotOptions,
+    background: RGBAColor,
+    raster_logo: bool,
+) -> Result<LegendEntries> {
+    // Deterministic output never depends on a font file of the host.
+    let pinned;
+    let options = if options.deterministic && options.fonts.custom.is_some() {
+        pinned = PlotOptions {
+            fonts: FontOptions {
+                custom: None,
+                ..options.fonts.clone()
+            },
+            ..options.clone()
+        };
+        &pinned
     } else {
-        let root = BitMapBackend::new(path_string.as_str(), (width, height)).into_drawing_area();
-        draw_chart(
-            root,
-            points,
-            min_year,
-            max_year,
-            min_val,
-            max_val,
-            num_locale,
-            legend,
-            title,
-            kind,
-            loess_span,
-            country_styles,
-        )?;
+        options
+    };
+    let entries = draw_panel(root.clone(), points, prepared, options, background)?;
+    branding::draw(&root, options, raster_logo)?;
+    root.present().map_err(|e| anyhow!("{:?}", e))?;
+    Ok(entries)
+}
+
+/// Draw `prepared` on `root` without presenting it, so that facets share one backend.
+fn draw_panel<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    points: &[DataPoint],
+    prepared: &Prepared,
+    options: &PlotOptions,
+    background: RGBAColor,
+) -> Result<LegendEntries> {
+    match prepared {
+        Prepared::Series(bounds) => draw_chart(root, points, *bounds, options, background),
+        Prepared::XY(data) => xy::draw_xy_scatter(root, data, options, background),
+        Prepared::Bars(data) => bars::draw_category_bars(root, points, data, options, background),
+        Prepared::Pareto(data) => pareto::draw_pareto(root, points, data, options, background),
+        Prepared::Waterfall(data) => {
+            waterfall::draw_waterfall(root, points, data, options, background)
+        }
+        Prepared::Summary(data) => summary::draw_summary(root, data, options, background),
+        Prepared::Correlation(matrix) => {
+            heatmap::draw_correlation(root, matrix, options, background)
+        }
+        Prepared::Facets(facets) => {
+            root.fill(&background).map_err(|e| anyhow!("{:?}", e))?;
+            let cols = auto::facet_columns(facets.len());
+            let rows = facets.len().div_ceil(cols);
+            // Panels share their series, so the legend lists each entry once.
+            let mut entries = LegendEntries::default();
+            for (area, facet) in root.split_evenly((rows, cols)).into_iter().zip(facets) {
+                let drawn = draw_panel(
+                    area,
+                    &facet.points,
+                    &facet.prepared,
+                    &facet.options,
+                    background,
+                )?;
+                for item in drawn.items {
+                    if !entries.items.iter().any(|(label, _)| *label == item.0) {
+                        entries.items.push(item);
+                    }
+                }
+            }
+            Ok(entries)
+        }
     }
-    Ok(())
 }
 
-// This is the main chart drawing function - copied from original viz.rs
-#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+/// `title`, or one derived from the indicator names when it is empty or the default.
+fn chart_caption(title: &str, points: &[DataPoint]) -> String {
+    let t = title.trim();
+    if !(t.is_empty() || t == "World Bank Indicator(s)") {
+        return t.to_string();
+    }
+    let names: BTreeSet<&str> = points.iter().map(|p| p.indicator_name.as_str()).collect();
+    if names.is_empty() {
+        "World Bank Series".to_string()
+    } else if names.len() == 1 {
+        names.iter().next().unwrap().to_string()
+    } else if names.len() <= 3 {
+        names.into_iter().collect::<Vec<_>>().join(", ")
+    } else {
+        let first = names.iter().next().unwrap();
+        let more = names.len() - 1;
+        format!("{first} + {more} more")
+    }
+}
+
+/// Legend entry of an envelope band over `countries` countries.
+fn envelope_label(indicator_label: &str, countries: usize) -> String {
+    format!("{indicator_label} — mean, median (dashed), range of {countries} countries")
+}
+
+/// Legend entry of a fan chart over `countries` countries.
+fn fan_label(indicator_label: &str, countries: usize) -> String {
+    format!("{indicator_label} — median, 25–75th and 10–90th percentiles of {countries} countries")
+}
+
+/// Markers of `pts` that survive `mode`, for markers of `radius` pixels.
+fn thin_markers<DB: DrawingBackend>(
+    chart: &ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    pts: &[(f64, f64)],
+    mode: MarkerDecimation,
+    radius: u32,
+) -> Vec<(f64, f64)> {
+    let min_px = match mode {
+        MarkerDecimation::Off => return pts.to_vec(),
+        MarkerDecimation::EveryNth(n) => {
+            let n = n.max(1);
+            let last = pts.len().saturating_sub(1);
+            return pts
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| i % n == 0 || *i == last)
+                .map(|(_, p)| *p)
+                .collect();
+        }
+        MarkerDecimation::Auto => 2 * radius + 1,
+        MarkerDecimation::MinSpacingPx(px) => px,
+    } as f64;
+
+    let dist = |a: (i32, i32), b: (i32, i32)| ((a.0 - b.0) as f64).hypot((a.1 - b.1) as f64);
+    let mut out: Vec<((f64, f64), (i32, i32))> = Vec::with_capacity(pts.len());
+    for (i, p) in pts.iter().enumerate() {
+        let px = chart.backend_coord(p);
+        match out.last() {
+            Some((_, prev)) if dist(*prev, px) < min_px => {
+                // Keep the series end visible: the last marker replaces a too-close neighbour.
+                if i + 1 == pts.len() && out.len() > 1 {
+                    out.pop();
+                    out.push((*p, px));
+                }
+            }
+            _ => out.push((*p, px)),
+        }
+    }
+    out.into_iter().map(|(p, _)| p).collect()
+}
+
+/// Draw `pts` as a line, cut into dashes unless `dash` is solid.
+fn draw_line_series<'a, 'b, DB: DrawingBackend>(
+    chart: &'b mut ChartContext<'a, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    pts: Vec<(f64, f64)>,
+    style: ShapeStyle,
+    dash: style::LineDash,
+) -> Result<&'b mut SeriesAnno<'a, DB>> {
+    let pattern = dash::dash_pattern(dash, style.stroke_width);
+    if pattern.is_empty() {
+        return chart
+            .draw_series(LineSeries::new(pts, style))
+            .map_err(|e| anyhow!("{:?}", e));
+    }
+    let px: Vec<(i32, i32)> = pts.iter().map(|p| chart.backend_coord(p)).collect();
+    let segments = dash::dash_segments(&pts, &px, &pattern);
+    chart
+        .draw_series(
+            segments
+                .into_iter()
+                .map(move |seg| PathElement::new(seg, style)),
+        )
+        .map_err(|e| anyhow!("{:?}", e))
+}
+
+/// Draw `pattern` inside the data-space polygon `poly` of `chart`.
+fn fill_pattern<DB: DrawingBackend>(
+    chart: &ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    poly: &[(f64, f64)],
+    pattern: style::FillPattern,
+    color: RGBAColor,
+) -> Result<()> {
+    if pattern == style::FillPattern::Solid {
+        return Ok(());
+    }
+    let area = chart.plotting_area().strip_coord_spec();
+    let (bx, by) = area.get_base_pixel();
+    let px: Vec<(f64, f64)> = poly
+        .iter()
+        .map(|p| {
+            let (x, y) = chart.backend_coord(p);
+            ((x - bx) as f64, (y - by) as f64)
+        })
+        .collect();
+    pattern::draw_pattern(&area, &px, pattern, color)
+}
+
+/// Main drawing routine for all kinds with years on the X axis.
+#[allow(clippy::type_complexity)]
 fn draw_chart<DB>(
     root: DrawingArea<DB, Shift>,
     points: &[DataPoint],
-    min_year: i32,
-    max_year: i32,
-    min_val: f64,
-    max_val: f64,
-    _num_locale: &Locale,
-    legend: LegendMode,
-    title: &str,
-    kind: PlotKind,
-    loess_span: f64,
-    country_styles: Option<bool>,
-) -> Result<()>
+    bounds: Bounds,
+    options: &PlotOptions,
+    background: RGBAColor,
+) -> Result<LegendEntries>
 where
     DB: DrawingBackend,
 {
+    let Bounds {
+        min_year,
+        max_year,
+        min_val,
+        max_val,
+    } = bounds;
+    let title = options.title.as_str();
+    let kind = options.kind;
+    // Only kinds with one line end per legend entry can label them directly.
+    let legend = match kind {
+        PlotKind::Line
+        | PlotKind::Scatter
+        | PlotKind::LinePoints
+        | PlotKind::Area
+        | PlotKind::Loess => options.legend,
+        _ => options.legend.panel(),
+    };
+    let loess_span = options.loess_span;
+    let fonts = &options.fonts;
+    let family = fonts.family();
+
     // ----------------------------
     // 0) Common constants
     // ----------------------------
-    const MARGIN: i32 = 16; // matches .margin(16) below
     let x_min = min_year as f64;
     let x_max = max_year as f64;
 
     // Axis scaling for large magnitudes (thousands/millions/billions/…)
-    // Derive a unit from the indicator metadata/name, then decide scaling.
+    // Derive a unit from the indicator metadata/name (unless overridden), then decide scaling.
     // Percent-like units are NOT scaled; currencies/counts can be scaled to thousands/millions/…
-    let base_unit = derive_axis_unit(points); // e.g., "current US$" or "annual %"
+    let base_unit = options.y_unit.clone().or_else(|| derive_axis_unit(points)); // e.g., "current US$" or "annual %"
+    let (min_val, max_val) = options.value_range((min_val, max_val), base_unit.as_deref());
     let max_abs = min_val.abs().max(max_val.abs());
-
-    let (yscale, scale_word) = if let Some(ref unit) = base_unit {
-        if is_percentage_like(unit) {
-            (1.0, "") // do not scale percentages
-        } else {
-            choose_axis_scale(max_abs) // e.g., (1e6, "millions")
-        }
-    } else {
-        // Mixed indicators or no unit => fall back to generic scaling
-        choose_axis_scale(max_abs)
-    };
+    let (yscale, scale_word) = options.value_scale(&options.y_scale, max_abs, base_unit.as_deref());
+    let abbreviate = options.tick_abbreviation_for(base_unit.as_deref());
+    let tick_suffix = options.tick_suffix_for(base_unit.as_deref());
+    let scale_word = i18n::scale_word(&options.locale, &scale_word);
 
     // This is the final Y-axis title
-    let y_axis_title = match (base_unit.as_deref(), scale_word) {
+    let y_axis_title = match (base_unit.as_deref(), scale_word.as_str()) {
         (Some(u), "") => u.to_string(),         // e.g., "annual %"
         (Some(u), sw) => format!("{u} ({sw})"), // e.g., "current US$ (millions)"
-        (None, "") => "Value".to_string(),
-        (None, sw) => format!("Value ({sw})"),
+        (None, "") => i18n::tr(&options.locale, i18n::Term::Value).to_string(),
+        (None, sw) => format!("{} ({sw})", i18n::tr(&options.locale, i18n::Term::Value)),
     };
 
     // X/Y tick formatters
     let x_label_fmt = |x: &f64| (x.round() as i32).to_string();
-    let y_label_fmt_scaled = |v: &f64| {
-        let a = v.abs();
-        let prec = if a >= 100.0 {
-            0
-        } else if a >= 10.0 {
-            1
-        } else {
-            2
-        };
-        format!("{:.*}", prec, *v)
-    };
+    let y_label_fmt_scaled = |v: &f64| util::suffixed_tick_label(*v, abbreviate, tick_suffix);
     let x_label_count = ((max_year - min_year + 1) as usize).min(12);
     let y_label_count = 10usize;
 
+    // Values → chart Y: scaled, then moved past the axis break, if any.
+    let broken = options
+        .y_break
+        .and_then(|b| axis_break::BrokenAxis::new(&b, (min_val / yscale, max_val / yscale)));
+    let to_y = |v: f64| {
+        let scaled = v / yscale;
+        broken.map_or(scaled, |b| b.map(scaled))
+    };
+    let y_range = broken.map_or((min_val / yscale)..(max_val / yscale), |b| b.range());
+
     // ----------------------------
     // 1) Build name maps & groups
     // ----------------------------
-    let mut indicator_name_by_id: HashMap<String, String> = HashMap::new();
-    let mut country_name_by_iso3: HashMap<String, String> = HashMap::new();
-    for p in points {
+    let mut indicator_name_by_id: HashMap<&str, &str> = HashMap::new();
+    let mut country_name_by_iso3: HashMap<&str, &str> = HashMap::new();
+    let projected: &[DataPoint] = if draws_projections(kind) {
+        &options.projections
+    } else {
+        &[]
+    };
+    // Names from the actuals win; projections only name series the actuals lack.
+    for p in points.iter().chain(projected) {
         indicator_name_by_id
-            .entry(p.indicator_id.clone())
-            .or_insert_with(|| p.indicator_name.clone());
+            .entry(&p.indicator_id)
+            .or_insert(&p.indicator_name);
         country_name_by_iso3
-            .entry(p.country_iso3.clone())
-            .or_insert_with(|| p.country_name.clone());
-    }
-
-    // Group as (ISO3, indicator_id) -> Vec<(year, value)>
-    let mut groups: BTreeMap<(String, String), Vec<(i32, f64)>> = BTreeMap::new();
-    for p in points {
-        if let (y, Some(v)) = (p.year, p.value)
-            && y != 0
-        {
-            groups
-                .entry((p.country_iso3.clone(), p.indicator_id.clone()))
-                .or_default()
-                .push((y, v));
-        }
-    }
-    for ((_country, _indicator), series) in groups.iter_mut() {
-        series.sort_by_key(|(y, _)| *y);
+            .entry(&p.country_iso3)
+            .or_insert(&p.country_name);
     }
 
     // Sorted list by *country name* then *indicator name*
     let mut series_list: Vec<(String, String, String, String, Vec<(i32, f64)>)> = Vec::new();
-    for ((iso3, indicator_id), series) in groups.iter() {
+    for (key, series) in group_series(points) {
+        let GroupKey {
+            indicator_id,
+            country_iso3: iso3,
+            ..
+        } = key;
         let country_label = country_name_by_iso3
-            .get(iso3)
-            .cloned()
-            .unwrap_or_else(|| iso3.clone());
+            .get(iso3.as_str())
+            .map_or_else(|| iso3.clone(), |name| name.to_string());
         let indicator_label = indicator_name_by_id
-            .get(indicator_id)
-            .cloned()
-            .unwrap_or_else(|| indicator_id.clone());
-        series_list.push((
-            iso3.clone(),
-            indicator_id.clone(),
-            country_label,
-            indicator_label,
-            series.clone(),
-        ));
+            .get(indicator_id.as_str())
+            .map_or_else(|| indicator_id.clone(), |name| name.to_string());
+        let series = series.into_iter().map(|(p, v)| (p.year(), v)).collect();
+        series_list.push((iso3, indicator_id, country_label, indicator_label, series));
     }
     series_list.sort_by(|a, b| a.2.cmp(&b.2).then(a.3.cmp(&b.3)));
 
@@ -376,181 +1356,170 @@ where
         }
     };
 
+    // Projected series: (iso3, indicator id, legend label, series), drawn dashed after the actuals.
+    let projection_word = i18n::tr(&options.locale, i18n::Term::Projection);
+    let mut projection_list: Vec<(String, String, String, Vec<(i32, f64)>)> = Vec::new();
+    for (key, series) in group_series(projected) {
+        let label = make_label(
+            country_name_by_iso3
+                .get(key.country_iso3.as_str())
+                .unwrap_or(&key.country_iso3.as_str()),
+            indicator_name_by_id
+                .get(key.indicator_id.as_str())
+                .unwrap_or(&key.indicator_id.as_str()),
+        );
+        let series = series.into_iter().map(|(p, v)| (p.year(), v)).collect();
+        projection_list.push((
+            key.country_iso3,
+            key.indicator_id,
+            format!("{label} ({projection_word})"),
+            series,
+        ));
+    }
+    projection_list.sort_by(|a, b| a.2.cmp(&b.2));
+
     // ----------------------------
     // 2) Compute dynamic gutters before splitting
     // ----------------------------
-    // Left label area depends on *scaled* Y range & tick font size (12)
-    let left_label_width_px =
-        compute_left_label_area_px(min_val / yscale, max_val / yscale, y_label_count, 12);
+    // Left label area depends on *scaled* Y range & tick font size
+    let layout = Layout::new(
+        fonts,
+        options.width,
+        (min_val / yscale, max_val / yscale),
+        y_label_count,
+        abbreviate,
+        true,
+    )
+    .with_tick_suffix(tick_suffix, options.width);
     // X-axis text column starts at margin + left label area
-    let axis_x_start_px: i32 = MARGIN + left_label_width_px as i32;
+    let axis_x_start_px = layout.axis_x_start_px();
 
     // Legend height for Top/Bottom: pre-measure how much vertical space we need.
     // Build the list of final legend texts in drawing order (matches series_list).
-    let legend_texts: Vec<String> = series_list
-        .iter()
-        .map(|(_iso3, _ind, country_label, indicator_label, _s)| {
-            make_label(country_label, indicator_label)
-        })
-        .collect();
-
-    let (root_w_u32, root_h_u32) = root.dim_in_pixel();
-    let root_w = root_w_u32 as i32;
-    let root_h = root_h_u32 as i32;
-
-    // Title is generally omitted (best practice). We pass "" later.
-    let _has_title = false;
-    let _title_font_px: u32 = 16;
-    let _font_px: u32 = 14;
-
-    // Estimator to avoid missing-symbol issues:
-    let legend_needed_h = if matches!(legend, LegendMode::Top | LegendMode::Bottom) {
-        estimate_top_bottom_legend_height_px(
-            &legend_texts,
-            axis_x_start_px,
-            root_w,
-            /* has_title: */ false, // we render without a legend title by default
-            /* title_font_px: */ 16,
-            /* font_px: */ 14,
-        )
+    // Envelope and fan charts have one legend entry per indicator instead of per series.
+    let mut envelope_indicators: Vec<(&str, &str)> = indicator_name_by_id.into_iter().collect();
+    envelope_indicators.sort_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(b.0)));
+    let legend_texts: Vec<String> = if matches!(kind, PlotKind::Envelope | PlotKind::FanChart) {
+        let label = if kind == PlotKind::FanChart {
+            fan_label
+        } else {
+            envelope_label
+        };
+        envelope_indicators
+            .iter()
+            .map(|(_, name)| label(name, unique_countries.len()))
+            .collect()
     } else {
-        0
+        series_list
+            .iter()
+            .map(|(_iso3, _ind, country_label, indicator_label, _s)| {
+                make_label(country_label, indicator_label)
+            })
+            .chain(projection_list.iter().map(|(_, _, label, _)| label.clone()))
+            .collect()
     };
 
     // ----------------------------
     // 3) Split drawing areas
     // ----------------------------
-    let (plot_area, legend_area_opt): (DrawingArea<DB, Shift>, Option<DrawingArea<DB, Shift>>) =
-        match legend {
-            LegendMode::Right => {
-                let (plot, legend) = root.split_horizontally((85).percent_width());
-                (plot, Some(legend))
-            }
-            LegendMode::Top => {
-                let h = legend_needed_h.max(40);
-                let (legend, plot) = root.split_vertically(h);
-                (plot, Some(legend))
-            }
-            LegendMode::Bottom => {
-                let h = legend_needed_h.max(40);
-                // keep at least 40px for plot area
-                let (plot, legend) = root.split_vertically((root_h - h).max(40));
-                (plot, Some(legend))
-            }
-            LegendMode::Inside => (root, None),
-        };
-
-    plot_area
-        .fill(&WHITE)
-        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-    if let Some(ref legend_area) = legend_area_opt {
-        legend_area
-            .fill(&WHITE)
-            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-    }
+    let (plot_area, legend_area_opt) =
+        layout.split_legend_area(root, legend, &legend_texts, background)?;
 
     // ----------------------------
     // 4) Build chart (scaled Y range)
     // ----------------------------
-    let mut chart = ChartBuilder::on(&plot_area)
-        .margin(MARGIN as u32)
-        .caption(
-            {
-                let t = title.trim();
-                if t.is_empty() || t == "World Bank Indicator(s)" {
-                    // derive from indicator names
-                    let names: BTreeSet<&str> =
-                        points.iter().map(|p| p.indicator_name.as_str()).collect();
-                    if names.is_empty() {
-                        "World Bank Series".to_string()
-                    } else if names.len() == 1 {
-                        names.iter().next().unwrap().to_string()
-                    } else if names.len() <= 3 {
-                        names.into_iter().collect::<Vec<_>>().join(", ")
-                    } else {
-                        let first = names.iter().next().unwrap();
-                        let more = names.len() - 1;
-                        format!("{first} + {more} more")
-                    }
-                } else {
-                    t.to_string()
-                }
-            },
-            (FontFamily::SansSerif, 24),
-        )
-        .set_label_area_size(LabelAreaPosition::Left, left_label_width_px)
-        .set_label_area_size(LabelAreaPosition::Bottom, 56)
-        .build_cartesian_2d(x_min..x_max, (min_val / yscale)..(max_val / yscale))
+    let caption = chart_caption(title, points);
+    let chart_area = layout.draw_title(&plot_area, &caption)?;
+    let y_axis_title = layout.fit_y_title(&y_axis_title, &chart_area);
+    let mut chart = layout
+        .chart_builder(&chart_area)
+        .build_cartesian_2d(x_min..x_max, y_range)
         .map_err(|e| anyhow::anyhow!("{:?}", e))?;
 
     chart
         .configure_mesh()
-        .x_desc("Year")
+        .x_desc(i18n::tr(&options.locale, i18n::Term::Year))
         .y_desc(y_axis_title)
         .x_labels(x_label_count)
-        .y_labels(y_label_count)
+        // A broken axis draws its own value ticks and grid lines below.
+        .y_labels(if broken.is_some() { 0 } else { y_label_count })
         .x_label_formatter(&x_label_fmt)
         .y_label_formatter(&y_label_fmt_scaled)
-        .label_style((FontFamily::SansSerif, 12))
-        .axis_desc_style((FontFamily::SansSerif, 16))
+        .label_style((family, fonts.tick_px))
+        .axis_desc_style((family, fonts.axis_title_px))
         .draw()
         .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    if let Some(axis) = &broken {
+        let label = |v: f64| y_label_fmt_scaled(&v);
+        axis_break::draw_axis(&chart, &chart_area, axis, &label, fonts, background)?;
+    }
 
     // ----------------------------
     // 5) Draw series & collect legend items
     // ----------------------------
     let mut legend_items: Vec<(String, RGBAColor)> = Vec::new();
     let inside_mode = matches!(legend, LegendMode::Inside);
+    // Pixel row of each line end, with its label and colour, for `LegendMode::Direct`.
+    let mut direct_labels: Vec<(i32, String, RGBAColor)> = Vec::new();
 
-    // Create a flag for easier handling
-    let use_country_styles = country_styles.unwrap_or(false);
+    // Uncertainty whiskers, drawn on top of line/scatter/bar marks
+    let whiskers = options
+        .error_bars
+        .as_ref()
+        .map(|eb| (eb, eb.resolve(points)));
 
-    // Pre-compute unique countries for consistent ordering (if using country styles)
-    let country_list: Vec<String> = if use_country_styles {
-        let unique_countries: std::collections::BTreeSet<String> = series_list
+    // Country/indicator styles assign colour and dash per series; palette order and solid otherwise.
+    let keys = || -> Vec<style::SeriesKey> {
+        series_list
             .iter()
-            .map(|(iso3, _, _, _, _)| iso3.clone())
-            .collect();
-        unique_countries.into_iter().collect()
-    } else {
-        Vec::new()
+            .map(|(iso3, ind, _, _, _)| style::SeriesKey::new(iso3.clone(), ind.clone()))
+            .collect()
+    };
+    let series_styles: HashMap<style::SeriesKey, style::SeriesStyle> =
+        match options.effective_style_mode() {
+            StyleMode::Palette => HashMap::new(),
+            StyleMode::Country => style::assign_country_styles(&keys(), 255),
+            StyleMode::Indicator => style::assign_indicator_styles(&keys(), 255),
+        };
+    let style_of = |iso3: &str, indicator_id: &str| {
+        series_styles.get(&style::SeriesKey::new(
+            iso3.to_string(),
+            indicator_id.to_string(),
+        ))
+    };
+
+    let house = &options.style_config;
+    let line_width = house.line_width.unwrap_or(2);
+    let marker_px = house.marker_size.unwrap_or(3);
+
+    // Bar/area fill pattern: house style first, then the automatic cycle.
+    let get_series_pattern = |idx: usize, iso3: &str, indicator_id: &str| {
+        house
+            .pattern_for(iso3, indicator_id)
+            .unwrap_or(if options.pattern_fills {
+                style::FillPattern::nth(idx)
+            } else {
+                style::FillPattern::Solid
+            })
+    };
+
+    // Pattern swatches only for the kinds that draw patterns, one per series.
+    let patterns: Vec<style::FillPattern> = match kind {
+        PlotKind::Area | PlotKind::StackedArea | PlotKind::GroupedBar => series_list
+            .iter()
+            .enumerate()
+            .map(|(idx, (iso3, ind, _, _, _))| get_series_pattern(idx, iso3, ind))
+            .collect(),
+        _ => Vec::new(),
     };
 
     // Helper function to get the appropriate color for a series
     let get_series_color = |idx: usize, iso3: &str, indicator_id: &str| -> RGBAColor {
-        // Use country-consistent styling if enabled
-        if use_country_styles
-            && let Some(country_index) = country_list.iter().position(|c| c == iso3)
-        {
-            // Use the MS Office palette for base colors
-            let base_colors = [
-                (68, 114, 196),  // blue
-                (237, 125, 49),  // orange
-                (165, 165, 165), // gray
-                (255, 192, 0),   // gold
-                (91, 155, 213),  // light blue
-                (112, 173, 71),  // green
-                (38, 68, 120),   // dark blue
-                (158, 72, 14),   // dark orange
-                (99, 99, 99),    // dark gray
-                (153, 115, 0),   // brownish
-            ];
-
-            let base_color = base_colors[country_index % base_colors.len()];
-
-            // Create brightness variation based on indicator
-            use std::collections::hash_map::DefaultHasher;
-            use std::hash::{Hash, Hasher};
-            let mut hasher = DefaultHasher::new();
-            indicator_id.hash(&mut hasher);
-            let indicator_hash = hasher.finish();
-
-            let brightness_factor = 0.7 + 0.6 * ((indicator_hash % 100) as f64 / 100.0);
-            let adjusted_r = (base_color.0 as f64 * brightness_factor).clamp(0.0, 255.0) as u8;
-            let adjusted_g = (base_color.1 as f64 * brightness_factor).clamp(0.0, 255.0) as u8;
-            let adjusted_b = (base_color.2 as f64 * brightness_factor).clamp(0.0, 255.0) as u8;
-
-            return RGBAColor(adjusted_r, adjusted_g, adjusted_b, 1.0);
+        if let Some(shade) = house.color_for(iso3, indicator_id) {
+            return plotters_adapter::rgba_color(shade);
+        }
+        if let Some(st) = style_of(iso3, indicator_id) {
+            return plotters_adapter::rgba_color(st.shade);
         }
 
         // Default fallback: use index-based coloring
@@ -567,6 +1536,9 @@ where
                 series_list.iter().enumerate()
             {
                 let color = get_series_color(idx, iso3, indicator_id);
+                let line_dash = house.dash_for(iso3, indicator_id).unwrap_or_else(|| {
+                    style_of(iso3, indicator_id).map_or(style::LineDash::Solid, |st| st.dash)
+                });
                 let base_label = make_label(country_label, indicator_label);
                 let legend_label = if matches!(kind, PlotKind::Loess) {
                     format!("{base_label} (LOESS)")
@@ -575,226 +1547,365 @@ where
                 };
 
                 // Convert to f64 X and **scale Y**
-                let series_f: Vec<(f64, f64)> = series
-                    .iter()
-                    .map(|(x, y)| (*x as f64, *y / yscale))
-                    .collect();
+                let series_f: Vec<(f64, f64)> =
+                    series.iter().map(|(x, y)| (*x as f64, to_y(*y))).collect();
+                let mut line_end = series_f.last().map(|p| chart.backend_coord(p).1);
 
-                match kind {
+                let elem = match kind {
                     PlotKind::Line => {
                         let style = ShapeStyle {
                             color,
                             filled: false,
-                            stroke_width: 2,
+                            stroke_width: line_width,
                         };
-                        let elem = chart
-                            .draw_series(LineSeries::new(series_f.clone(), style))
-                            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-                        if inside_mode {
-                            let legend_color = color;
-                            let legend_text = legend_label.clone();
-                            elem.label(legend_text.clone()).legend(move |(x, y)| {
-                                EmptyElement::at((x, y))
-                                    + Circle::new((x + 8, y), 4, legend_color.clone().filled())
-                                    + Text::new(
-                                        legend_text.clone(),
-                                        (x + 20, y),
-                                        (FontFamily::SansSerif, 14),
-                                    )
-                            });
-                        } else {
-                            legend_items.push((legend_label, color));
-                        }
+                        Some(draw_line_series(&mut chart, series_f, style, line_dash)?)
                     }
                     PlotKind::Scatter => {
+                        let markers = thin_markers(&chart, &series_f, options.markers, marker_px);
                         let elem = chart
-                            .draw_series(
-                                series_f
-                                    .iter()
-                                    .map(|(x, y)| Circle::new((*x, *y), 3, color.clone().filled())),
-                            )
+                            .draw_series(markers.iter().map(|(x, y)| {
+                                Circle::new((*x, *y), marker_px, color.clone().filled())
+                            }))
                             .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-                        if inside_mode {
-                            let legend_color = color;
-                            let legend_text = legend_label.clone();
-                            elem.label(legend_text.clone()).legend(move |(x, y)| {
-                                EmptyElement::at((x, y))
-                                    + Circle::new((x + 8, y), 4, legend_color.clone().filled())
-                                    + Text::new(
-                                        legend_text.clone(),
-                                        (x + 20, y),
-                                        (FontFamily::SansSerif, 14),
-                                    )
-                            });
-                        } else {
-                            legend_items.push((legend_label, color));
-                        }
+                        Some(elem)
                     }
                     PlotKind::LinePoints => {
                         let style = ShapeStyle {
                             color,
                             filled: false,
-                            stroke_width: 2,
+                            stroke_width: line_width,
                         };
-                        chart
-                            .draw_series(LineSeries::new(series_f.clone(), style))
-                            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                        let markers = thin_markers(&chart, &series_f, options.markers, marker_px);
+                        draw_line_series(&mut chart, series_f, style, line_dash)?;
                         let elem = chart
-                            .draw_series(
-                                series_f
-                                    .iter()
-                                    .map(|(x, y)| Circle::new((*x, *y), 3, color.clone().filled())),
-                            )
+                            .draw_series(markers.iter().map(|(x, y)| {
+                                Circle::new((*x, *y), marker_px, color.clone().filled())
+                            }))
                             .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-                        if inside_mode {
-                            let legend_color = color;
-                            let legend_text = legend_label.clone();
-                            elem.label(legend_text.clone()).legend(move |(x, y)| {
-                                EmptyElement::at((x, y))
-                                    + Circle::new((x + 8, y), 4, legend_color.clone().filled())
-                                    + Text::new(
-                                        legend_text.clone(),
-                                        (x + 20, y),
-                                        (FontFamily::SansSerif, 14),
-                                    )
-                            });
-                        } else {
-                            legend_items.push((legend_label, color));
-                        }
+                        Some(elem)
                     }
                     PlotKind::Area => {
-                        let baseline_scaled = 0.0f64.min(min_val) / yscale;
+                        let baseline_scaled = to_y(0.0f64.min(min_val));
+                        // The pattern goes first; the translucent fill keeps it visible.
+                        if let (Some(first), Some(last)) = (series_f.first(), series_f.last()) {
+                            let poly: Vec<(f64, f64)> = series_f
+                                .iter()
+                                .copied()
+                                .chain([(last.0, baseline_scaled), (first.0, baseline_scaled)])
+                                .collect();
+                            let pattern = get_series_pattern(idx, iso3, indicator_id);
+                            fill_pattern(&chart, &poly, pattern, color)?;
+                        }
                         let fill = color.clone().mix(0.20).filled();
                         let border = color.clone().stroke_width(1);
                         let elem = chart
                             .draw_series(
-                                AreaSeries::new(series_f.clone(), baseline_scaled, fill)
+                                AreaSeries::new(series_f, baseline_scaled, fill)
                                     .border_style(border),
                             )
                             .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-                        if inside_mode {
-                            let legend_color = color;
-                            let legend_text = legend_label.clone();
-                            elem.label(legend_text.clone()).legend(move |(x, y)| {
-                                EmptyElement::at((x, y))
-                                    + Circle::new((x + 8, y), 4, legend_color.clone().filled())
-                                    + Text::new(
-                                        legend_text.clone(),
-                                        (x + 20, y),
-                                        (FontFamily::SansSerif, 14),
-                                    )
-                            });
-                        } else {
-                            legend_items.push((legend_label, color));
-                        }
+                        Some(elem)
                     }
                     PlotKind::Loess => {
                         // Smooth on original values, then **scale** the result for plotting
                         let xs: Vec<f64> = series.iter().map(|(x, _)| *x as f64).collect();
                         let ys: Vec<f64> = series.iter().map(|(_, y)| *y).collect();
                         let yhat = loess_series(&xs, &ys, loess_span);
-                        let smoothed: Vec<(f64, f64)> = xs
-                            .into_iter()
-                            .zip(yhat.into_iter().map(|v| v / yscale))
-                            .collect();
+                        let smoothed: Vec<(f64, f64)> =
+                            xs.into_iter().zip(yhat.into_iter().map(to_y)).collect();
+                        line_end = smoothed.last().map(|p| chart.backend_coord(p).1);
                         let style = ShapeStyle {
                             color,
                             filled: false,
-                            stroke_width: 3,
+                            stroke_width: line_width + 1,
                         };
-                        let elem = chart
-                            .draw_series(LineSeries::new(smoothed, style))
-                            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-                        if inside_mode {
-                            let legend_color = color;
-                            let legend_text = legend_label.clone();
-                            elem.label(legend_text.clone()).legend(move |(x, y)| {
-                                EmptyElement::at((x, y))
-                                    + Circle::new((x + 8, y), 4, legend_color.clone().filled())
-                                    + Text::new(
-                                        legend_text.clone(),
-                                        (x + 20, y),
-                                        (FontFamily::SansSerif, 14),
-                                    )
-                            });
-                        } else {
-                            legend_items.push((legend_label, color));
+                        Some(draw_line_series(&mut chart, smoothed, style, line_dash)?)
+                    }
+                    _ => None,
+                };
+                if let Some(y) = line_end {
+                    direct_labels.push((y, legend_label.clone(), color));
+                }
+                if let Some(elem) = elem {
+                    if inside_mode {
+                        let legend_text = legend_label.clone();
+                        elem.label(legend_label).legend(move |(x, y)| {
+                            EmptyElement::at((x, y))
+                                + Circle::new((x + 8, y), 4, color.filled())
+                                + Text::new(
+                                    legend_text.clone(),
+                                    (x + 20, y),
+                                    (family, fonts.legend_px),
+                                )
+                        });
+                    } else {
+                        legend_items.push((legend_label, color));
+                    }
+                }
+
+                if let Some((eb, w)) = &whiskers
+                    && matches!(
+                        kind,
+                        PlotKind::Line | PlotKind::Scatter | PlotKind::LinePoints
+                    )
+                {
+                    // One key per series; only the year changes per point.
+                    let mut key = (iso3.clone(), indicator_id.clone(), 0);
+                    for (year, _) in series.iter() {
+                        key.2 = *year;
+                        if let Some((lo, hi)) = w.get(&key) {
+                            errorbars::draw_whisker(
+                                &mut chart,
+                                *year as f64,
+                                to_y(*lo),
+                                to_y(*hi),
+                                color,
+                                eb,
+                            )?;
                         }
                     }
-                    _ => {}
+                }
+            }
+
+            // Projections: dashed, in the colour of the actual series they extend.
+            for (k, (iso3, indicator_id, legend_label, series)) in
+                projection_list.iter().enumerate()
+            {
+                let idx = series_list
+                    .iter()
+                    .position(|s| &s.0 == iso3 && &s.1 == indicator_id)
+                    .unwrap_or(series_list.len() + k);
+                let color = get_series_color(idx, iso3, indicator_id);
+                let series_f: Vec<(f64, f64)> =
+                    series.iter().map(|(x, y)| (*x as f64, to_y(*y))).collect();
+                let style = ShapeStyle {
+                    color,
+                    filled: false,
+                    stroke_width: line_width,
+                };
+                let markers = match kind {
+                    PlotKind::Scatter | PlotKind::LinePoints => {
+                        thin_markers(&chart, &series_f, options.markers, marker_px)
+                    }
+                    _ => Vec::new(),
+                };
+                if let Some(p) = series_f.last() {
+                    direct_labels.push((chart.backend_coord(p).1, legend_label.clone(), color));
+                }
+                let elem = draw_line_series(&mut chart, series_f, style, style::LineDash::Dash)?;
+                if inside_mode {
+                    elem.label(legend_label.clone()).legend(move |(x, y)| {
+                        EmptyElement::at((x, y))
+                            + PathElement::new(vec![(1, 0), (6, 0)], color.stroke_width(2))
+                            + PathElement::new(vec![(10, 0), (15, 0)], color.stroke_width(2))
+                    });
+                } else {
+                    legend_items.push((legend_label.clone(), color));
+                }
+                // Hollow markers tell projected points from the filled actual ones.
+                chart
+                    .draw_series(
+                        markers
+                            .iter()
+                            .map(|p| Circle::new(*p, marker_px, color.stroke_width(1))),
+                    )
+                    .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            }
+        }
+        PlotKind::XYScatter => unreachable!("XYScatter is drawn by xy::draw_xy_scatter"),
+        PlotKind::Pareto => unreachable!("Pareto is drawn by pareto::draw_pareto"),
+        PlotKind::Waterfall => unreachable!("Waterfall is drawn by waterfall::draw_waterfall"),
+        PlotKind::Envelope | PlotKind::FanChart => {
+            let yearly = crate::stats::yearly_summary(points);
+            for (idx, ((indicator_id, _), legend_label)) in envelope_indicators
+                .iter()
+                .zip(legend_texts.iter())
+                .enumerate()
+            {
+                let color = office_color(idx);
+                let rows: Vec<_> = yearly
+                    .iter()
+                    .filter(|s| s.indicator_id == *indicator_id && s.count > 0)
+                    .collect();
+                let line = |stat: fn(&crate::stats::YearlySummary) -> Option<f64>| {
+                    rows.iter()
+                        .filter_map(|s| Some((s.year as f64, to_y(stat(s)?))))
+                        .collect::<Vec<_>>()
+                };
+
+                // Band: lower curve forward, upper curve back.
+                type Stat = fn(&crate::stats::YearlySummary) -> Option<f64>;
+                let mut band = |lo: Stat, hi: Stat, opacity: f64| {
+                    let mut poly = line(lo);
+                    poly.extend(line(hi).into_iter().rev());
+                    chart
+                        .draw_series(std::iter::once(Polygon::new(
+                            poly,
+                            color.mix(opacity).filled(),
+                        )))
+                        .map(|_| ())
+                        .map_err(|e| anyhow::anyhow!("{:?}", e))
+                };
+                let elem = if kind == PlotKind::FanChart {
+                    // Nested bands: the outer one shows through the inner one, darkening it.
+                    band(|s| s.p10, |s| s.p90, 0.18)?;
+                    band(|s| s.p25, |s| s.p75, 0.25)?;
+                    draw_line_series(
+                        &mut chart,
+                        line(|s| s.median),
+                        color.stroke_width(2),
+                        style::LineDash::Solid,
+                    )?
+                } else {
+                    band(|s| s.min, |s| s.max, 0.25)?;
+                    draw_line_series(
+                        &mut chart,
+                        line(|s| s.median),
+                        color.stroke_width(1),
+                        style::LineDash::Dash,
+                    )?;
+                    draw_line_series(
+                        &mut chart,
+                        line(|s| s.mean),
+                        color.stroke_width(2),
+                        style::LineDash::Solid,
+                    )?
+                };
+
+                if inside_mode {
+                    elem.label(legend_label.clone())
+                        .legend(move |(x, y)| Circle::new((x + 8, y), 4, color.filled()));
+                } else {
+                    legend_items.push((legend_label.clone(), color));
                 }
             }
         }
         PlotKind::StackedArea => {
             let years_all: Vec<i32> = (min_year..=max_year).collect();
-            let mut cum: Vec<f64> = vec![0.0; years_all.len()];
+            let mirror = options.stack_negatives == StackNegatives::Mirror;
+            // Running totals above and (when mirrored) below the zero line.
+            let mut cum_pos: Vec<f64> = vec![0.0; years_all.len()];
+            let mut cum_neg: Vec<f64> = vec![0.0; years_all.len()];
 
             for (idx, (iso3, indicator_id, country_label, indicator_label, series)) in
                 series_list.iter().enumerate()
             {
                 let color = get_series_color(idx, iso3, indicator_id);
                 let legend_label = make_label(country_label, indicator_label);
+                let pattern = get_series_pattern(idx, iso3, indicator_id);
 
                 // Map series to full year grid, missing -> 0.0
                 let mut vals: Vec<f64> = vec![0.0; years_all.len()];
                 for (y, v) in series.iter() {
                     if *y >= min_year && *y <= max_year {
-                        vals[(*y - min_year) as usize] = (*v).max(0.0);
+                        vals[(*y - min_year) as usize] = *v;
                     }
                 }
-                // Build upper curve by adding to cumulative
-                let mut upper: Vec<(f64, f64)> = Vec::with_capacity(vals.len());
-                let mut lower: Vec<(f64, f64)> = Vec::with_capacity(vals.len());
-                for (i, v) in vals.iter().enumerate() {
-                    let x = (min_year + i as i32) as f64;
-                    lower.push((x, cum[i]));
-                    cum[i] += *v;
-                    upper.push((x, cum[i]));
+                let has_negative = vals.iter().any(|v| *v < 0.0);
+                let has_positive = vals.iter().any(|v| *v > 0.0);
+
+                // The positive part stacks up from the positive totals; mirrored negatives stack
+                // down from the negative totals, with the outline on the far edge of each band.
+                let mut bands: Vec<(&mut Vec<f64>, fn(f64) -> f64)> = Vec::new();
+                if !(mirror && has_negative && !has_positive) {
+                    bands.push((&mut cum_pos, |v: f64| v.max(0.0)));
+                }
+                if mirror && has_negative {
+                    bands.push((&mut cum_neg, |v: f64| v.min(0.0)));
                 }
-                // polygon: lower (forward) + upper (reverse), scaled
-                let mut poly: Vec<(f64, f64)> = Vec::with_capacity(upper.len() * 2);
-                poly.extend(lower.iter().map(|(x, y)| (*x, *y / yscale)));
-                poly.extend(upper.iter().rev().map(|(x, y)| (*x, *y / yscale)));
+                for (cum, part) in bands {
+                    let mut inner: Vec<(f64, f64)> = Vec::with_capacity(vals.len());
+                    let mut outer: Vec<(f64, f64)> = Vec::with_capacity(vals.len());
+                    for (i, v) in vals.iter().enumerate() {
+                        let x = (min_year + i as i32) as f64;
+                        inner.push((x, to_y(cum[i])));
+                        cum[i] += part(*v);
+                        outer.push((x, to_y(cum[i])));
+                    }
+                    // polygon: inner edge (forward) + outer edge (reverse)
+                    let mut poly: Vec<(f64, f64)> = Vec::with_capacity(outer.len() * 2);
+                    poly.extend(inner.iter().copied());
+                    poly.extend(outer.iter().rev().copied());
 
-                let fill = color.clone().mix(0.30).filled();
-                let border = color.clone().stroke_width(1);
-                chart
-                    .draw_series(std::iter::once(Polygon::new(poly, fill)))
-                    .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-                chart
-                    .draw_series(std::iter::once(PathElement::new(
-                        upper
-                            .iter()
-                            .map(|(x, y)| (*x, *y / yscale))
-                            .collect::<Vec<_>>(),
-                        border,
-                    )))
-                    .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                    let fill = color.clone().mix(0.30).filled();
+                    let border = color.clone().stroke_width(1);
+                    chart
+                        .draw_series(std::iter::once(Polygon::new(poly.clone(), fill)))
+                        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                    fill_pattern(&chart, &poly, pattern, color)?;
+                    chart
+                        .draw_series(std::iter::once(PathElement::new(outer, border)))
+                        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                }
 
                 legend_items.push((legend_label, color));
             }
         }
         PlotKind::GroupedBar => {
-            let n_series = series_list.len().max(1);
-            let group_width = 0.8f64;
-            let bar_w = group_width / n_series as f64;
+            let n_series = series_list.len();
+            let (plot_w, _) = chart.plotting_area().dim_in_pixel();
+            let unit_px = f64::from(plot_w) / (x_max - x_min).max(1.0);
+            let bar_slots = bars::BarSlots::new(&options.bars, n_series, unit_px);
+            let mut groups: BTreeMap<i32, Vec<(usize, f64)>> = BTreeMap::new();
+            for (idx, (_, _, _, _, series)) in series_list.iter().enumerate() {
+                for (year, v) in series {
+                    groups.entry(*year).or_default().push((idx, *v));
+                }
+            }
+            let mut slot_of: HashMap<(i32, usize), (usize, usize)> = HashMap::new();
+            for (year, group) in &groups {
+                let (slots, count) = bars::group_slots(options.bars.order, n_series, group);
+                for (idx, slot) in slots {
+                    slot_of.insert((*year, idx), (slot, count));
+                }
+            }
 
             for (idx, (iso3, indicator_id, country_label, indicator_label, series)) in
                 series_list.iter().enumerate()
             {
                 let color = get_series_color(idx, iso3, indicator_id);
                 let legend_label = make_label(country_label, indicator_label);
+                let pattern = get_series_pattern(idx, iso3, indicator_id);
 
                 for (y, v) in series.iter() {
-                    let x_center = *y as f64;
-                    let x0 = x_center - group_width / 2.0 + idx as f64 * bar_w;
-                    let x1 = x0 + bar_w;
-                    let y0 = 0.0f64.min(*v) / yscale;
-                    let y1 = 0.0f64.max(*v) / yscale;
-                    let rect = Rectangle::new([(x0, y0), (x1, y1)], color.clone().filled());
-                    chart
-                        .draw_series(std::iter::once(rect))
-                        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                    let (slot, count) = slot_of[&(*y, idx)];
+                    let (x0, x1) = bar_slots.span(*y as f64, slot, count);
+                    let y0 = to_y(0.0f64.min(*v));
+                    let y1 = to_y(0.0f64.max(*v));
+                    if pattern == style::FillPattern::Solid {
+                        let rect = Rectangle::new([(x0, y0), (x1, y1)], color.clone().filled());
+                        chart
+                            .draw_series(std::iter::once(rect))
+                            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                    } else {
+                        // Tint + pattern + outline keeps the bar readable in greyscale.
+                        chart
+                            .draw_series([
+                                Rectangle::new([(x0, y0), (x1, y1)], color.mix(0.15).filled()),
+                                Rectangle::new([(x0, y0), (x1, y1)], color.stroke_width(1)),
+                            ])
+                            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                        let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+                        fill_pattern(&chart, &corners, pattern, color)?;
+                    }
+                    if options.bars.borders {
+                        let outline = Rectangle::new([(x0, y0), (x1, y1)], bars::border_style());
+                        chart
+                            .draw_series(std::iter::once(outline))
+                            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                    }
+                    if let Some((eb, w)) = &whiskers
+                        && let Some((lo, hi)) = w.get(&(iso3.clone(), indicator_id.clone(), *y))
+                    {
+                        errorbars::draw_whisker(
+                            &mut chart,
+                            (x0 + x1) / 2.0,
+                            to_y(*lo),
+                            to_y(*hi),
+                            BLACK.into(),
+                            eb,
+                        )?;
+                    }
                 }
 
                 legend_items.push((legend_label, color));
@@ -811,24 +1922,27 @@ where
             .border_style(BLACK)
             .position(SeriesLabelPosition::UpperLeft)
             .background_style(WHITE.mix(0.85))
-            .label_font((FontFamily::SansSerif, 14))
+            .label_font((family, fonts.legend_px))
             .draw()
             .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    } else if let (LegendMode::Direct, Some(label_area)) = (legend, &legend_area_opt) {
+        let (_, rows) = chart.plotting_area().get_pixel_range();
+        legend::draw_direct_labels(label_area, &direct_labels, rows, fonts)?;
     } else if let Some(ref legend_area) = legend_area_opt {
         // Best practice: no explicit "Legend" title
-        draw_legend_panel(legend_area, &legend_items, "", legend, axis_x_start_px)?;
+        draw_legend_panel_with_patterns(
+            legend_area,
+            &legend_items,
+            &patterns,
+            "",
+            legend,
+            axis_x_start_px,
+            fonts,
+        )?;
     }
 
-    // ----------------------------
-    // 7) Present
-    // ----------------------------
-    plot_area
-        .present()
-        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-    if let Some(ref legend_area) = legend_area_opt {
-        legend_area
-            .present()
-            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
-    }
-    Ok(())
+    Ok(LegendEntries {
+        items: legend_items,
+        patterns,
+    })
 }
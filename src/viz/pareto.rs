@@ -0,0 +1,302 @@
+//! Pareto charts ([`PlotKind::Pareto`](super::PlotKind::Pareto)): one indicator in one year,
+//! a bar per country from the largest value down, and the cumulative share of the total as a
+//! line against a percent axis on the right.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Result, anyhow};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use super::i18n::{self, Term};
+use super::layout::Layout;
+use super::legend::{LegendEntries, draw_legend_panel};
+use super::text::{estimate_text_width_px, truncate_to_width};
+use super::types::{LegendMode, ParetoOptions, PlotOptions};
+use super::util::{derive_axis_unit, office_color, suffixed_tick_label};
+use super::{dash, plotters_adapter};
+use crate::models::DataPoint;
+
+/// One bar: a country's value in the ranked year.
+struct Bar {
+    iso3: String,
+    name: String,
+    value: f64,
+}
+
+/// The ranked bars of a Pareto chart, largest first.
+pub(crate) struct ParetoData {
+    indicator_id: String,
+    year: i32,
+    bars: Vec<Bar>,
+    /// Cumulative share of the total after each bar, in percent.
+    cumulative: Vec<f64>,
+}
+
+impl ParetoData {
+    /// Rank the countries of `points`, which must hold a single indicator, by their values in
+    /// `options.year` (or the latest year with data). Errors on negative values, which have
+    /// no share of a total.
+    pub(crate) fn from_points(points: &[DataPoint], options: &ParetoOptions) -> Result<Self> {
+        options.validate()?;
+        let valued: Vec<&DataPoint> = points
+            .iter()
+            .filter(|p| p.value.is_some_and(f64::is_finite))
+            .collect();
+        let indicators: BTreeSet<&str> = valued.iter().map(|p| p.indicator_id.as_str()).collect();
+        let indicator_id = match indicators.len() {
+            0 => return Err(anyhow!("no numeric values to plot")),
+            1 => indicators.into_iter().collect::<String>(),
+            n => {
+                let ids: Vec<&str> = indicators.into_iter().collect();
+                return Err(anyhow!(
+                    "Pareto charts rank one indicator, the data has {n} ({})",
+                    ids.join(", ")
+                ));
+            }
+        };
+        let year = match options.year {
+            Some(year) => year,
+            None => valued.iter().map(|p| p.year).max().unwrap_or_default(),
+        };
+
+        // First value per country, as `group_series` keeps it.
+        let mut by_country: BTreeMap<&str, (&str, f64)> = BTreeMap::new();
+        for p in valued.iter().filter(|p| p.year == year) {
+            by_country
+                .entry(&p.country_iso3)
+                .or_insert((&p.country_name, p.value.unwrap_or_default()));
+        }
+        if by_country.is_empty() {
+            return Err(anyhow!("no values in {year} to rank"));
+        }
+        let negative: Vec<&str> = by_country
+            .iter()
+            .filter(|(_, (_, v))| *v < 0.0)
+            .map(|(iso3, _)| *iso3)
+            .collect();
+        if !negative.is_empty() {
+            return Err(anyhow!(
+                "Pareto charts need non-negative values; negative in {year}: {}",
+                negative.join(", ")
+            ));
+        }
+        let total: f64 = by_country.values().map(|(_, v)| v).sum();
+        if total <= 0.0 {
+            return Err(anyhow!("values in {year} sum to zero; nothing to rank"));
+        }
+
+        let mut bars: Vec<Bar> = by_country
+            .into_iter()
+            .map(|(iso3, (name, value))| Bar {
+                iso3: iso3.to_string(),
+                name: name.to_string(),
+                value,
+            })
+            .collect();
+        bars.sort_by(|a, b| b.value.total_cmp(&a.value).then(a.name.cmp(&b.name)));
+        let cumulative = bars
+            .iter()
+            .scan(0.0, |sum, b| {
+                *sum += b.value;
+                Some(*sum / total * 100.0)
+            })
+            .collect();
+        Ok(Self {
+            indicator_id,
+            year,
+            bars,
+            cumulative,
+        })
+    }
+}
+
+pub(crate) fn draw_pareto<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    points: &[DataPoint],
+    data: &ParetoData,
+    options: &PlotOptions,
+    background: RGBAColor,
+) -> Result<LegendEntries> {
+    let fonts = &options.fonts;
+    let family = fonts.family();
+    let err = |e| anyhow!("{:?}", e);
+
+    let unit = options.y_unit.clone().or_else(|| derive_axis_unit(points));
+    let max_val = data.bars.first().map_or(1.0, |b| b.value).max(f64::EPSILON);
+    let (yscale, scale_word) = options.value_scale(&options.y_scale, max_val, unit.as_deref());
+    let abbreviate = options.tick_abbreviation_for(unit.as_deref());
+    let tick_suffix = options.tick_suffix_for(unit.as_deref());
+    let scale_word = i18n::scale_word(&options.locale, &scale_word);
+    let value = i18n::tr(&options.locale, Term::Value);
+    let range = options.value_range((0.0, max_val), unit.as_deref());
+    let (y_lo, y_hi) = (range.0 / yscale, range.1 / yscale);
+    let y_title = match (unit.as_deref(), scale_word.as_str()) {
+        (Some(u), "") => u.to_string(),
+        (Some(u), sw) => format!("{u} ({sw})"),
+        (None, "") => value.to_string(),
+        (None, sw) => format!("{value} ({sw})"),
+    };
+    let share_label = i18n::tr(&options.locale, Term::CumulativeShare);
+
+    let indicator_name = points
+        .iter()
+        .find(|p| p.indicator_id == data.indicator_id)
+        .map_or(data.indicator_id.as_str(), |p| p.indicator_name.as_str());
+    let house = &options.style_config;
+    let bar_color = house
+        .color_for(&data.indicator_id, &data.indicator_id)
+        .map_or_else(|| office_color(0), plotters_adapter::rgba_color);
+    let line_color = office_color(1);
+    let legend_texts = vec![indicator_name.to_string(), share_label.to_string()];
+
+    let layout = Layout::new(fonts, options.width, (y_lo, y_hi), 10, abbreviate, true)
+        .with_tick_suffix(tick_suffix, options.width);
+    let axis_x_start_px = layout.axis_x_start_px();
+    let (plot_area, legend_area_opt) =
+        layout.split_legend_area(root, options.legend.panel(), &legend_texts, background)?;
+
+    let caption = super::chart_caption(&options.title, points);
+    let chart_area = layout.draw_title(&plot_area, &caption)?;
+    let y_title = layout.fit_y_title(&y_title, &chart_area);
+    let share_title = layout.fit_y_title(&format!("{share_label} (%)"), &chart_area);
+    let n = data.bars.len();
+    let x_range = -0.5..(n as f64 - 0.5);
+    let mut builder = layout.chart_builder(&chart_area);
+    builder.set_label_area_size(LabelAreaPosition::Right, layout.right_label_px("100%"));
+    let mut chart = builder
+        .build_cartesian_2d(x_range.clone(), y_lo..y_hi)
+        .map_err(err)?
+        .set_secondary_coord(x_range, 0.0..100.0);
+
+    let tick_fmt = |v: &f64| suffixed_tick_label(*v, abbreviate, tick_suffix);
+    // Country labels are drawn below; plotters would place numeric ticks between bars.
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_labels(0)
+        .y_labels(10)
+        .y_label_formatter(&tick_fmt)
+        .x_desc(format!(
+            "{} ({})",
+            i18n::tr(&options.locale, Term::Country),
+            data.year
+        ))
+        .y_desc(y_title)
+        .label_style((family, fonts.tick_px))
+        .axis_desc_style((family, fonts.axis_title_px))
+        .draw()
+        .map_err(err)?;
+    let share_fmt = |v: &f64| format!("{v:.0}%");
+    chart
+        .configure_secondary_axes()
+        .y_labels(6)
+        .y_label_formatter(&share_fmt)
+        .y_desc(share_title)
+        .label_style((family, fonts.tick_px))
+        .axis_desc_style((family, fonts.axis_title_px))
+        .draw()
+        .map_err(err)?;
+
+    let inside_mode = matches!(options.legend, LegendMode::Inside);
+    let bars = chart
+        .draw_series(data.bars.iter().enumerate().map(|(i, b)| {
+            let x = i as f64;
+            Rectangle::new(
+                [(x - 0.4, 0.0), (x + 0.4, b.value / yscale)],
+                bar_color.filled(),
+            )
+        }))
+        .map_err(err)?;
+    if inside_mode {
+        bars.label(indicator_name).legend(move |(x, y)| {
+            Rectangle::new([(x + 4, y - 4), (x + 12, y + 4)], bar_color.filled())
+        });
+    }
+
+    if let Some(share) = options.pareto.reference_share {
+        let line = [(-0.5, share), (n as f64 - 0.5, share)];
+        let secondary = chart.borrow_secondary();
+        let px: Vec<(i32, i32)> = line.iter().map(|p| secondary.backend_coord(p)).collect();
+        let pattern = dash::dash_pattern(super::style::LineDash::Dash, 1);
+        let reference = BLACK.mix(0.5).stroke_width(1);
+        chart
+            .draw_secondary_series(
+                dash::dash_segments(&line, &px, &pattern)
+                    .into_iter()
+                    .map(|segment| PathElement::new(segment, reference)),
+            )
+            .map_err(err)?;
+    }
+
+    let cumulative: Vec<(f64, f64)> = data
+        .cumulative
+        .iter()
+        .enumerate()
+        .map(|(i, share)| (i as f64, *share))
+        .collect();
+    let line = chart
+        .draw_secondary_series(LineSeries::new(
+            cumulative.iter().copied(),
+            line_color.stroke_width(2),
+        ))
+        .map_err(err)?;
+    if inside_mode {
+        line.label(share_label).legend(move |(x, y)| {
+            PathElement::new([(x + 2, y), (x + 14, y)], line_color.stroke_width(2))
+        });
+    }
+    chart
+        .draw_secondary_series(
+            cumulative
+                .iter()
+                .map(|p| Circle::new(*p, 3, line_color.filled())),
+        )
+        .map_err(err)?;
+
+    // Country names where they fit under their bar, ISO3 codes otherwise.
+    let (plot_w, _) = chart.plotting_area().dim_in_pixel();
+    let slot_px = (plot_w / n as u32).saturating_sub(4);
+    let label_style =
+        TextStyle::from((family, fonts.tick_px)).pos(Pos::new(HPos::Center, VPos::Top));
+    chart
+        .draw_series(data.bars.iter().enumerate().map(|(i, b)| {
+            let label = if estimate_text_width_px(&b.name, fonts.tick_px) <= slot_px {
+                b.name.clone()
+            } else {
+                truncate_to_width(&b.iso3, fonts.tick_px, slot_px)
+            };
+            EmptyElement::at((i as f64, y_lo)) + Text::new(label, (0, 6), label_style.clone())
+        }))
+        .map_err(err)?;
+
+    let legend_items = vec![
+        (indicator_name.to_string(), bar_color),
+        (share_label.to_string(), line_color),
+    ];
+    if inside_mode {
+        chart
+            .configure_series_labels()
+            .border_style(BLACK)
+            .position(SeriesLabelPosition::UpperRight)
+            .background_style(WHITE.mix(0.85))
+            .label_font((family, fonts.legend_px))
+            .draw()
+            .map_err(err)?;
+    } else if let Some(ref legend_area) = legend_area_opt {
+        draw_legend_panel(
+            legend_area,
+            &legend_items,
+            "",
+            options.legend.panel(),
+            axis_x_start_px,
+            fonts,
+        )?;
+    }
+
+    Ok(LegendEntries {
+        items: legend_items,
+        patterns: Vec::new(),
+    })
+}
@@ -0,0 +1,159 @@
+//! Hatch, stripe and dot fills for bars and areas.
+//!
+//! Plotters only fills polygons with a flat colour, so pattern lines are clipped to the shape
+//! here and drawn as ordinary `PathElement`s / `Circle`s. Everything is in screen pixels so the
+//! pattern density does not depend on the data range or on the size of the shape.
+
+use anyhow::{Result, anyhow};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use super::style::FillPattern;
+
+/// Distance between pattern lines (and dot grid pitch) in pixels.
+const SPACING_PX: f64 = 7.0;
+const DOT_RADIUS_PX: u32 = 1;
+
+/// Draw `pattern` inside the pixel polygon `poly` (even–odd rule) on `area`.
+/// A solid pattern draws nothing; the caller fills the shape itself.
+pub(crate) fn draw_pattern<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    poly: &[(f64, f64)],
+    pattern: FillPattern,
+    color: RGBAColor,
+) -> Result<()> {
+    // Plotters clamps off-area points, which would bend the pattern lines; clip first.
+    let (w, h) = area.dim_in_pixel();
+    let poly = clip_to_rect(poly, w as f64, h as f64);
+    let poly = poly.as_slice();
+    let stroke = color.stroke_width(1);
+    match pattern {
+        FillPattern::Solid => Ok(()),
+        FillPattern::Hatch => draw_lines(area, poly, (1.0, 1.0), stroke),
+        FillPattern::Stripes => draw_lines(area, poly, (0.0, 1.0), stroke),
+        FillPattern::Dots => {
+            for (x, y) in dot_grid(poly) {
+                area.draw(&Circle::new((x, y), DOT_RADIUS_PX, color.filled()))
+                    .map_err(|e| anyhow!("{:?}", e))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn draw_lines<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    poly: &[(f64, f64)],
+    normal: (f64, f64),
+    stroke: ShapeStyle,
+) -> Result<()> {
+    for [a, b] in line_segments(poly, normal) {
+        area.draw(&PathElement::new(vec![a, b], stroke))
+            .map_err(|e| anyhow!("{:?}", e))?;
+    }
+    Ok(())
+}
+
+/// Pieces of the parallel lines `normal · p = k · SPACING_PX` that lie inside `poly`.
+fn line_segments(poly: &[(f64, f64)], normal: (f64, f64)) -> Vec<[(i32, i32); 2]> {
+    if poly.len() < 3 {
+        return Vec::new();
+    }
+    let len = normal.0.hypot(normal.1);
+    let n = (normal.0 / len, normal.1 / len);
+    let along = (-n.1, n.0);
+    let dot = |p: (f64, f64), v: (f64, f64)| p.0 * v.0 + p.1 * v.1;
+
+    let (lo, hi) = poly
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+            (lo.min(dot(*p, n)), hi.max(dot(*p, n)))
+        });
+    let mut out = Vec::new();
+    let mut c = (lo / SPACING_PX).ceil() * SPACING_PX;
+    while c <= hi {
+        // Crossings of this line with every polygon edge, ordered along the line.
+        let mut hits: Vec<(f64, f64)> = Vec::new();
+        for (i, a) in poly.iter().enumerate() {
+            let b = poly[(i + 1) % poly.len()];
+            let (da, db) = (dot(*a, n) - c, dot(b, n) - c);
+            if (da < 0.0) != (db < 0.0) {
+                let t = da / (da - db);
+                hits.push((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+            }
+        }
+        hits.sort_by(|p, q| dot(*p, along).total_cmp(&dot(*q, along)));
+        for pair in hits.chunks_exact(2) {
+            let round = |p: (f64, f64)| (p.0.round() as i32, p.1.round() as i32);
+            out.push([round(pair[0]), round(pair[1])]);
+        }
+        c += SPACING_PX;
+    }
+    out
+}
+
+/// Centres of a staggered dot grid that fall inside `poly`.
+fn dot_grid(poly: &[(f64, f64)]) -> Vec<(i32, i32)> {
+    if poly.len() < 3 {
+        return Vec::new();
+    }
+    let (x0, y0, x1, y1) = poly.iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(x0, y0, x1, y1), p| (x0.min(p.0), y0.min(p.1), x1.max(p.0), y1.max(p.1)),
+    );
+    let mut out = Vec::new();
+    let mut row = 0usize;
+    let mut y = (y0 / SPACING_PX).ceil() * SPACING_PX;
+    while y <= y1 {
+        let offset = if row % 2 == 1 { SPACING_PX / 2.0 } else { 0.0 };
+        let mut x = (x0 / SPACING_PX).floor() * SPACING_PX + offset;
+        while x <= x1 {
+            if x >= x0 && contains(poly, (x, y)) {
+                out.push((x.round() as i32, y.round() as i32));
+            }
+            x += SPACING_PX;
+        }
+        y += SPACING_PX;
+        row += 1;
+    }
+    out
+}
+
+/// Even–odd point-in-polygon test.
+fn contains(poly: &[(f64, f64)], p: (f64, f64)) -> bool {
+    let mut inside = false;
+    for (i, a) in poly.iter().enumerate() {
+        let b = poly[(i + 1) % poly.len()];
+        if (a.1 > p.1) != (b.1 > p.1) && p.0 < a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Sutherland–Hodgman clip of `poly` to the rectangle `[0, w] × [0, h]`.
+fn clip_to_rect(poly: &[(f64, f64)], w: f64, h: f64) -> Vec<(f64, f64)> {
+    // Each edge: signed distance inside (>= 0 keeps the point).
+    let edges: [&dyn Fn((f64, f64)) -> f64; 4] = [&|p| p.0, &|p| w - p.0, &|p| p.1, &|p| h - p.1];
+    let mut out = poly.to_vec();
+    for inside in edges {
+        let input = std::mem::take(&mut out);
+        for (i, a) in input.iter().enumerate() {
+            let b = input[(i + 1) % input.len()];
+            let (da, db) = (inside(*a), inside(b));
+            if da >= 0.0 {
+                out.push(*a);
+            }
+            if (da >= 0.0) != (db >= 0.0) {
+                let t = da / (da - db);
+                out.push((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+            }
+        }
+    }
+    out
+}
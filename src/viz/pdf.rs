@@ -0,0 +1,400 @@
+//! Minimal vector PDF backend for plotters (single page, no external dependencies).
+//!
+//! Shapes map one-to-one to PDF path operators; text uses the standard Type 1 fonts
+//! (Helvetica / Helvetica-Bold, WinAnsi encoding), which every PDF viewer ships, so no font
+//! needs to be embedded. One pixel of the logical chart size becomes one PostScript point, so a
+//! 1000×600 chart is a 1000×600 pt page that LaTeX can scale with `\includegraphics`.
+//!
+//! Text anchoring mirrors the SVG backend (`dy` of 0.76em / ±0.5ex and a font size of
+//! `size / 1.24`), so PDF and SVG exports of the same chart line up.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use plotters::backend::DrawingBackend;
+use plotters_backend::text_anchor::{HPos, VPos};
+use plotters_backend::{
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingErrorKind, FontStyle,
+    FontTransform,
+};
+
+/// Helvetica advance widths (1/1000 em) for ASCII 32..=126, from the standard AFM metrics.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, // ' '..'\''
+    333, 333, 389, 584, 278, 333, 278, 278, // '('..'/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // '0'..'9'
+    278, 278, 584, 584, 584, 556, 1015, // ':'..'@'
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // 'A'..'M'
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // 'N'..'Z'
+    278, 278, 278, 469, 556, 333, // '['..'`'
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // 'a'..'m'
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // 'n'..'z'
+    334, 260, 334, 584, // '{'..'~'
+];
+
+/// Map a character to its WinAnsiEncoding byte (`?` when not representable).
+fn win_ansi(c: char) -> u8 {
+    match c as u32 {
+        0x20..=0x7E | 0xA0..=0xFF => c as u32 as u8,
+        _ => match c {
+            '€' => 0x80,
+            '‚' => 0x82,
+            'ƒ' => 0x83,
+            '„' => 0x84,
+            '…' => 0x85,
+            '†' => 0x86,
+            '‡' => 0x87,
+            'ˆ' => 0x88,
+            '‰' => 0x89,
+            'Š' => 0x8A,
+            '‹' => 0x8B,
+            'Œ' => 0x8C,
+            'Ž' => 0x8E,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '˜' => 0x98,
+            '™' => 0x99,
+            'š' => 0x9A,
+            '›' => 0x9B,
+            'œ' => 0x9C,
+            'ž' => 0x9E,
+            'Ÿ' => 0x9F,
+            _ => b'?',
+        },
+    }
+}
+
+/// Approximate Helvetica advance width of an encoded byte in 1/1000 em.
+fn glyph_width(b: u8) -> u16 {
+    match b {
+        0x20..=0x7E => HELVETICA_WIDTHS[(b - 0x20) as usize],
+        0x97 => 1000,
+        _ => 556,
+    }
+}
+
+/// Vector PDF drawing backend. The file is written on [`DrawingBackend::present`] (or on drop).
+pub(crate) struct PdfBackend {
+    path: PathBuf,
+    size: (u32, u32),
+    content: String,
+    /// Opacity (in thousandths) -> ExtGState resource name.
+    alphas: BTreeMap<u16, String>,
+    saved: bool,
+}
+
+impl PdfBackend {
+    pub(crate) fn new<P: AsRef<Path>>(path: P, size: (u32, u32)) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            size,
+            content: String::new(),
+            alphas: BTreeMap::new(),
+            saved: false,
+        }
+    }
+
+    /// Flip a backend (y-down) coordinate into PDF user space (y-up).
+    fn xy(&self, (x, y): BackendCoord) -> (f64, f64) {
+        (x as f64, self.size.1 as f64 - y as f64)
+    }
+
+    /// Emit the graphics state for `color` (fill or stroke); `false` if fully transparent.
+    fn set_color(&mut self, color: BackendColor, stroke: bool) -> bool {
+        if color.alpha <= 0.0 {
+            return false;
+        }
+        let (r, g, b) = color.rgb;
+        let op = if stroke { "RG" } else { "rg" };
+        let _ = writeln!(
+            self.content,
+            "{:.3} {:.3} {:.3} {op}",
+            r as f64 / 255.0,
+            g as f64 / 255.0,
+            b as f64 / 255.0
+        );
+        let key = (color.alpha.clamp(0.0, 1.0) * 1000.0).round() as u16;
+        let next = self.alphas.len();
+        let name = self
+            .alphas
+            .entry(key)
+            .or_insert_with(|| format!("GS{next}"))
+            .clone();
+        let _ = writeln!(self.content, "/{name} gs");
+        true
+    }
+
+    fn stroke_path(&mut self, pts: &[BackendCoord], style: &impl BackendStyle, close: bool) {
+        if pts.len() < 2 || !self.set_color(style.color(), true) {
+            return;
+        }
+        let _ = writeln!(self.content, "{} w 1 j", style.stroke_width());
+        for (i, p) in pts.iter().enumerate() {
+            let (x, y) = self.xy(*p);
+            let op = if i == 0 { "m" } else { "l" };
+            let _ = writeln!(self.content, "{x} {y} {op}");
+        }
+        self.content.push_str(if close { "s\n" } else { "S\n" });
+    }
+
+    fn write_file(&mut self) -> io::Result<()> {
+        let (w, h) = self.size;
+        let mut gs = String::new();
+        for (key, name) in &self.alphas {
+            let a = *key as f64 / 1000.0;
+            let _ = write!(gs, "/{name} << /ca {a} /CA {a} >> ");
+        }
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {w} {h}] /Contents 4 0 R \
+                 /Resources << /Font << /F1 5 0 R /F2 6 0 R >> /ExtGState << {gs}>> >> >>"
+            ),
+            format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                self.content.len(),
+                self.content
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+
+        let mut out: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n{body}\nendobj\n", i + 1).as_bytes());
+        }
+        let xref_at = out.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for off in offsets {
+            let _ = writeln!(xref, "{off:010} 00000 n ");
+        }
+        let _ = write!(
+            xref,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_at}\n%%EOF\n",
+            objects.len() + 1
+        );
+        out.extend_from_slice(xref.as_bytes());
+        fs::write(&self.path, out)
+    }
+}
+
+impl DrawingBackend for PdfBackend {
+    type ErrorType = io::Error;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        if !self.saved {
+            self.write_file().map_err(DrawingErrorKind::DrawingError)?;
+            self.saved = true;
+        }
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if self.set_color(color, false) {
+            let (x, y) = self.xy(point);
+            let _ = writeln!(self.content, "{x} {} 1 1 re f", y - 1.0);
+        }
+        Ok(())
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.stroke_path(&[from, to], style, false);
+        Ok(())
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if !fill {
+            let (l, t, r, b) = (upper_left.0, upper_left.1, bottom_right.0, bottom_right.1);
+            self.stroke_path(&[(l, t), (r, t), (r, b), (l, b)], style, true);
+            return Ok(());
+        }
+        if self.set_color(style.color(), false) {
+            let (x0, y0) = self.xy(upper_left);
+            let (x1, y1) = self.xy(bottom_right);
+            let _ = writeln!(self.content, "{x0} {y1} {} {} re f", x1 - x0, y0 - y1);
+        }
+        Ok(())
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let pts: Vec<BackendCoord> = path.into_iter().collect();
+        self.stroke_path(&pts, style, false);
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if !self.set_color(style.color(), !fill) {
+            return Ok(());
+        }
+        // Four cubic Béziers; k is the standard quarter-circle control distance.
+        let (cx, cy) = self.xy(center);
+        let r = radius as f64;
+        let k = 0.552_284_75 * r;
+        if !fill {
+            let _ = writeln!(self.content, "{} w", style.stroke_width());
+        }
+        let _ = writeln!(
+            self.content,
+            "{} {cy} m\n\
+             {} {} {} {} {cx} {} c\n\
+             {} {} {} {} {} {cy} c\n\
+             {} {} {} {} {cx} {} c\n\
+             {} {} {} {} {} {cy} c\n{}",
+            cx + r,
+            cx + r,
+            cy + k,
+            cx + k,
+            cy + r,
+            cy + r,
+            cx - k,
+            cy + r,
+            cx - r,
+            cy + k,
+            cx - r,
+            cx - r,
+            cy - k,
+            cx - k,
+            cy - r,
+            cy - r,
+            cx + k,
+            cy - r,
+            cx + r,
+            cy - k,
+            cx + r,
+            if fill { "f" } else { "s" }
+        );
+        Ok(())
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let pts: Vec<BackendCoord> = vert.into_iter().collect();
+        if pts.len() < 3 || !self.set_color(style.color(), false) {
+            return Ok(());
+        }
+        for (i, p) in pts.iter().enumerate() {
+            let (x, y) = self.xy(*p);
+            let op = if i == 0 { "m" } else { "l" };
+            let _ = writeln!(self.content, "{x} {y} {op}");
+        }
+        self.content.push_str("h f\n");
+        Ok(())
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if !self.set_color(style.color(), false) {
+            return Ok(());
+        }
+        let font_size = style.size() / 1.24;
+        let bytes: Vec<u8> = text.chars().map(win_ansi).collect();
+        let width = bytes.iter().map(|b| glyph_width(*b) as f64).sum::<f64>() / 1000.0 * font_size;
+
+        let anchor = style.anchor();
+        let dx = match anchor.h_pos {
+            HPos::Left => 0.0,
+            HPos::Center => -width / 2.0,
+            HPos::Right => -width,
+        };
+        // Baseline offset below the anchor (screen space), matching the SVG backend.
+        let dy = match anchor.v_pos {
+            VPos::Top => 0.76 * font_size,
+            VPos::Center => 0.26 * font_size,
+            VPos::Bottom => -0.26 * font_size,
+        };
+        // Text matrix for a clockwise on-screen rotation in y-up PDF space.
+        let (a, b, c, d) = match style.transform() {
+            FontTransform::Rotate90 => (0, -1, 1, 0),
+            FontTransform::Rotate180 => (-1, 0, 0, -1),
+            FontTransform::Rotate270 => (0, 1, -1, 0),
+            _ => (1, 0, 0, 1),
+        };
+        let font = match style.style() {
+            FontStyle::Bold => "F2",
+            _ => "F1",
+        };
+        let (x, y) = self.xy(pos);
+
+        let mut literal = String::with_capacity(bytes.len() + 2);
+        for b in bytes {
+            match b {
+                b'(' | b')' | b'\\' => {
+                    literal.push('\\');
+                    literal.push(b as char);
+                }
+                0x20..=0x7E => literal.push(b as char),
+                _ => {
+                    let _ = write!(literal, "\\{b:03o}");
+                }
+            }
+        }
+        let _ = writeln!(
+            self.content,
+            "BT /{font} {font_size:.2} Tf {a} {b} {c} {d} {x} {y} Tm {dx:.2} {:.2} Td ({literal}) Tj ET",
+            -dy
+        );
+        Ok(())
+    }
+}
+
+impl Drop for PdfBackend {
+    fn drop(&mut self) {
+        if !self.saved {
+            // Best effort, like the SVG backend: errors surface through `present` instead.
+            let _ = self.write_file();
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! High-DPI and transparent bitmap output.
+//!
+//! [`ScaledBackend`] wraps any plotters backend and multiplies every coordinate, stroke width,
+//! radius and font size by a fixed factor. The chart code keeps laying out in *logical* pixels
+//! (`get_size` reports the unscaled size), so a 2× render is the same picture with twice the
+//! resolution rather than a chart with smaller text.
+//!
+//! Transparent PNGs are produced by rendering twice, on white and on black, and recovering
+//! per-pixel alpha from the difference (see [`unblend_rgba`]).
+
+use plotters::prelude::*;
+use plotters::style::text_anchor::Pos;
+use plotters::style::{FontDesc, TextStyle};
+use plotters_backend::{
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingErrorKind,
+};
+
+/// Plain style carrying an already-scaled stroke width.
+struct ScaledStyle {
+    color: BackendColor,
+    width: u32,
+}
+
+impl BackendStyle for ScaledStyle {
+    fn color(&self) -> BackendColor {
+        self.color
+    }
+    fn stroke_width(&self) -> u32 {
+        self.width
+    }
+}
+
+/// Drawing backend adapter that renders at `scale`× resolution.
+pub(crate) struct ScaledBackend<DB> {
+    inner: DB,
+    scale: f64,
+}
+
+impl<DB: DrawingBackend> ScaledBackend<DB> {
+    /// Wrap `inner`, whose size must already be the logical size multiplied by `scale`.
+    pub(crate) fn new(inner: DB, scale: f64) -> Self {
+        Self { inner, scale }
+    }
+
+    fn pt(&self, (x, y): BackendCoord) -> BackendCoord {
+        (
+            (x as f64 * self.scale).round() as i32,
+            (y as f64 * self.scale).round() as i32,
+        )
+    }
+
+    fn len(&self, v: u32) -> u32 {
+        ((v as f64 * self.scale).round() as u32).max(1)
+    }
+
+    fn style<S: BackendStyle>(&self, style: &S) -> ScaledStyle {
+        ScaledStyle {
+            color: style.color(),
+            width: self.len(style.stroke_width()),
+        }
+    }
+}
+
+impl<DB: DrawingBackend> DrawingBackend for ScaledBackend<DB> {
+    type ErrorType = DB::ErrorType;
+
+    fn get_size(&self) -> (u32, u32) {
+        let (w, h) = self.inner.get_size();
+        (
+            (w as f64 / self.scale).round() as u32,
+            (h as f64 / self.scale).round() as u32,
+        )
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.inner.ensure_prepared()
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.inner.present()
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        // Filled rectangles exclude their bottom-right corner.
+        let (x0, y0) = self.pt(point);
+        let (x1, y1) = self.pt((point.0 + 1, point.1 + 1));
+        self.inner.draw_rect((x0, y0), (x1, y1), &color, true)
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let s = self.style(style);
+        let (from, to) = (self.pt(from), self.pt(to));
+        self.inner.draw_line(from, to, &s)
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let s = self.style(style);
+        let (ul, br) = (self.pt(upper_left), self.pt(bottom_right));
+        self.inner.draw_rect(ul, br, &s, fill)
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let s = self.style(style);
+        let path: Vec<BackendCoord> = path.into_iter().map(|p| self.pt(p)).collect();
+        self.inner.draw_path(path, &s)
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let s = self.style(style);
+        let (c, r) = (self.pt(center), self.len(radius));
+        self.inner.draw_circle(c, r, &s, fill)
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let s = self.style(style);
+        let vert: Vec<BackendCoord> = vert.into_iter().map(|p| self.pt(p)).collect();
+        self.inner.fill_polygon(vert, &s)
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let family = style.family();
+        let font = FontDesc::new(family, style.size() * self.scale, style.style())
+            .transform(style.transform());
+        let scaled = TextStyle {
+            font,
+            color: style.color(),
+            pos: Pos::new(style.anchor().h_pos, style.anchor().v_pos),
+        };
+        let pos = self.pt(pos);
+        self.inner.draw_text(text, &scaled, pos)
+    }
+
+    fn estimate_text_size<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
+        self.inner.estimate_text_size(text, style)
+    }
+}
+
+/// Recover an RGBA image from two renders of the same chart on white and on black.
+///
+/// For a pixel of colour `c` with coverage `a` composited over background `b`, the result is
+/// `a·c + (1−a)·b`; the white/black difference therefore equals `255·(1−a)`.
+pub(crate) fn unblend_rgba(on_white: &[u8], on_black: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(on_white.len() / 3 * 4);
+    for (w, b) in on_white.chunks_exact(3).zip(on_black.chunks_exact(3)) {
+        let diff: u32 = (0..3)
+            .map(|i| w[i].saturating_sub(b[i]) as u32)
+            .sum::<u32>();
+        let alpha = 255.0 - diff as f64 / 3.0;
+        if alpha <= 0.5 {
+            out.extend_from_slice(&[0, 0, 0, 0]);
+            continue;
+        }
+        for &c in b {
+            out.push((c as f64 * 255.0 / alpha).round().min(255.0) as u8);
+        }
+        out.push(alpha.round() as u8);
+    }
+    out
+}
@@ -0,0 +1,761 @@
+//! Series styling: map (country, indicator) pairs to colours, marker shapes and line dashes.
+//!
+//! All series for the same country share one base colour from the MS Office palette, while
+//! indicators within that country are differentiated by shade, marker shape and line dash.
+//! [`assign_indicator_styles`] is the inverse: one colour per indicator, countries vary.
+//! [`PlotOptions::style_mode`](crate::viz::PlotOptions::style_mode) picks between them.
+//!
+//! # Example
+//!
+//! ```rust
+//! use wbi_rs::viz::style::{SeriesKey, assign_country_styles};
+//!
+//! let series = vec![
+//!     SeriesKey::new("USA".to_string(), "GDP".to_string()),
+//!     SeriesKey::new("USA".to_string(), "Population".to_string()),
+//!     SeriesKey::new("DEU".to_string(), "GDP".to_string()),
+//! ];
+//!
+//! let styles = assign_country_styles(&series, 255);
+//!
+//! // All USA series share the same base hue.
+//! assert_eq!(styles[&series[0]].base_hue, styles[&series[1]].base_hue);
+//! assert_ne!(styles[&series[0]].base_hue, styles[&series[2]].base_hue);
+//! ```
+//!
+//! # Design Principles
+//!
+//! - **Country consistency**: All series for the same country use the same base hue
+//! - **Indicator differentiation**: Different indicators use brightness variations,
+//!   unique marker shapes, and line dash patterns for redundant visual encoding
+//! - **Deterministic**: Identical inputs always produce identical outputs
+//! - **MS Office compatibility**: Uses the standard MS Office color palette
+//!
+//! [`DrawStyle::for_series`] styles a single pair without knowing the other series (a hashed
+//! hue instead of a palette slot); `crate::style` re-exports this module.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Microsoft Office (2013+) chart series palette, the single palette used by all charts.
+/// Order: Blue, Orange, Gray, Gold, Light Blue, Green, Dark Blue, Dark Orange, Dark Gray, Brownish Gold.
+pub const OFFICE_PALETTE: [(u8, u8, u8); 10] = [
+    (68, 114, 196),  // blue      (#4472C4)
+    (237, 125, 49),  // orange    (#ED7D31)
+    (165, 165, 165), // gray      (#A5A5A5)
+    (255, 192, 0),   // gold      (#FFC000)
+    (91, 155, 213),  // light blue(#5B9BD5)
+    (112, 173, 71),  // green     (#70AD47)
+    (38, 68, 120),   // dark blue (#264478)
+    (158, 72, 14),   // dark org. (#9E480E)
+    (99, 99, 99),    // dark gray (#636363)
+    (153, 115, 0),   // brownish  (#997300)
+];
+
+/// RGBA color representation. Deserializes from `"#RRGGBB"` or `"#RRGGBBAA"` and serializes
+/// back the same way (`#RRGGBB` when opaque).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Rgba {
+    /// Create a new RGBA color.
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Create an opaque RGB color.
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::new(r, g, b, 255)
+    }
+
+    /// Parse `#RRGGBB` or `#RRGGBBAA` (the `#` is optional).
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let digits = hex.trim().trim_start_matches('#');
+        let byte = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16);
+        let parsed = match digits.len() {
+            6 if digits.is_ascii() => (byte(0), byte(2), byte(4), Ok(255)),
+            8 if digits.is_ascii() => (byte(0), byte(2), byte(4), byte(6)),
+            _ => {
+                return Err(anyhow!(
+                    "invalid color '{hex}', expected #RRGGBB or #RRGGBBAA"
+                ));
+            }
+        };
+        match parsed {
+            (Ok(r), Ok(g), Ok(b), Ok(a)) => Ok(Self::new(r, g, b, a)),
+            _ => Err(anyhow!(
+                "invalid color '{hex}', expected #RRGGBB or #RRGGBBAA"
+            )),
+        }
+    }
+}
+
+impl From<Rgba> for String {
+    fn from(c: Rgba) -> Self {
+        match c.a {
+            255 => format!("#{:02X}{:02X}{:02X}", c.r, c.g, c.b),
+            a => format!("#{:02X}{:02X}{:02X}{a:02X}", c.r, c.g, c.b),
+        }
+    }
+}
+
+impl TryFrom<String> for Rgba {
+    type Error = anyhow::Error;
+
+    fn try_from(hex: String) -> Result<Self> {
+        Self::from_hex(&hex)
+    }
+}
+
+/// Opaque 8-bit RGB color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb8 {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// Hue/saturation/lightness color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsl {
+    pub h_deg: f64, // 0..360
+    pub s: f64,     // 0..1
+    pub l: f64,     // 0..1
+}
+
+/// Marker shape for data points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkerShape {
+    Circle,
+    Square,
+    Triangle,
+    Diamond,
+    Cross,
+    X,
+}
+
+/// Line dash pattern. Deserializes from `solid`, `dash`, `dot` or `dash-dot`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LineDash {
+    Solid,
+    Dash,
+    Dot,
+    DashDot,
+}
+
+/// Fill pattern for bars and areas, drawn in the series colour over a light tint of it.
+/// Deserializes from `solid`, `hatch`, `stripes` or `dots`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FillPattern {
+    /// Plain colour fill.
+    #[default]
+    Solid,
+    /// Diagonal lines.
+    Hatch,
+    /// Horizontal lines.
+    Stripes,
+    /// Dot grid.
+    Dots,
+}
+
+impl FillPattern {
+    /// Pattern of the `index`-th series when patterns are assigned automatically.
+    pub fn nth(index: usize) -> Self {
+        const CYCLE: [FillPattern; 4] = [
+            FillPattern::Solid,
+            FillPattern::Hatch,
+            FillPattern::Dots,
+            FillPattern::Stripes,
+        ];
+        CYCLE[index % CYCLE.len()]
+    }
+}
+
+/// Key identifying a unique series (country, indicator pair).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SeriesKey {
+    pub country: String,
+    pub indicator: String,
+}
+
+impl SeriesKey {
+    /// Create a new series key.
+    pub fn new(country: String, indicator: String) -> Self {
+        Self { country, indicator }
+    }
+}
+
+/// Complete style specification for a series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeriesStyle {
+    pub base_hue: f64, // 0..360 degrees, shared by the series' group
+    pub shade: Rgba,
+    pub marker: MarkerShape,
+    pub dash: LineDash,
+}
+
+impl SeriesStyle {
+    /// Create a new series style.
+    pub fn new(base_hue: f64, shade: Rgba, marker: MarkerShape, dash: LineDash) -> Self {
+        Self {
+            base_hue,
+            shade,
+            marker,
+            dash,
+        }
+    }
+}
+
+/// Fully resolved drawing attributes of one series, for use with
+/// [`crate::viz::plotters_adapter`].
+#[derive(Clone, Debug)]
+pub struct DrawStyle {
+    pub country: String,
+    pub indicator: String,
+    pub hsl: Hsl,
+    pub rgb: Rgb8,
+    pub hex: String,
+    pub marker: MarkerShape,
+    pub line_dash: LineDash,
+    pub marker_size: u32,
+    pub line_width: u32,
+}
+
+impl DrawStyle {
+    /// Build a consistent style for a (country, indicator) pair.
+    ///
+    /// Without the other series there is no palette slot to pick, so the country's hue is
+    /// hashed; marker and dash follow the same indicator mapping as [`assign_country_styles`].
+    pub fn for_series(country: &str, indicator: &str) -> Self {
+        let base = Hsl {
+            h_deg: (stable_hash(country) % 360) as f64,
+            s: 0.60,
+            l: 0.55,
+        };
+
+        // Indicator variation: deterministic lightness and slight saturation offset.
+        // Lightness in [-0.18, +0.18], Saturation in [-0.10, +0.10]
+        let h = stable_hash(indicator);
+        let dl = map_u64_to_range(h.rotate_left(13), -0.18, 0.18);
+        let ds = map_u64_to_range(h.rotate_left(29), -0.10, 0.10);
+        let varied = Hsl {
+            h_deg: base.h_deg,
+            s: (base.s + ds).clamp(0.0, 1.0),
+            l: (base.l + dl).clamp(0.0, 1.0),
+        };
+
+        let rgb = hsl_to_rgb8(varied);
+        DrawStyle {
+            country: country.to_string(),
+            indicator: indicator.to_string(),
+            hsl: varied,
+            rgb,
+            hex: format!("#{:02X}{:02X}{:02X}", rgb.r, rgb.g, rgb.b),
+            marker: variation_marker(h),
+            line_dash: variation_dash(h),
+            marker_size: 6,
+            line_width: 2,
+        }
+    }
+}
+
+/// House style overrides, usually loaded from a TOML file with [`StyleConfig::load`]:
+///
+/// ```toml
+/// line_width = 3
+/// marker_size = 4
+///
+/// [palette]          # keyed by ISO3 country code or indicator id
+/// DEU = "#000000"
+/// "SP.POP.TOTL" = "#C00000"
+///
+/// [dashes]           # solid, dash, dot or dash-dot
+/// "NY.GDP.MKTP.CD" = "dash"
+///
+/// [patterns]         # bar/area fills: solid, hatch, stripes or dots
+/// USA = "hatch"
+/// ```
+///
+/// For a series, a country key wins over an indicator key; series without an entry keep the
+/// colour and dash of the active [`StyleMode`](crate::viz::StyleMode).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StyleConfig {
+    /// Line width in pixels (line and line-points charts, LOESS one pixel thicker; default 2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_width: Option<u32>,
+    /// Marker radius in pixels (scatter and line-points charts; default 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker_size: Option<u32>,
+    /// Fixed colours by ISO3 country code or indicator id.
+    #[serde(serialize_with = "sorted")]
+    pub palette: HashMap<String, Rgba>,
+    /// Fixed dash patterns by ISO3 country code or indicator id.
+    #[serde(serialize_with = "sorted")]
+    pub dashes: HashMap<String, LineDash>,
+    /// Fixed bar/area fill patterns by ISO3 country code or indicator id.
+    #[serde(serialize_with = "sorted")]
+    pub patterns: HashMap<String, FillPattern>,
+}
+
+/// Serialize a map with its keys in order, so saved configs diff cleanly.
+fn sorted<S: Serializer, V: Serialize>(map: &HashMap<String, V>, s: S) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(s)
+}
+
+impl StyleConfig {
+    /// Read and validate a TOML style config.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading style config {}", path.display()))?;
+        Self::from_toml_str(&text)
+            .with_context(|| format!("parsing style config {}", path.display()))
+    }
+
+    /// Parse and validate a TOML style config.
+    pub fn from_toml_str(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        for (name, px) in [
+            ("line_width", self.line_width),
+            ("marker_size", self.marker_size),
+        ] {
+            if let Some(px) = px
+                && !(1..=20).contains(&px)
+            {
+                return Err(anyhow!("{name} must be in 1..=20 px, got {px}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Colour override for a series, if any.
+    pub(crate) fn color_for(&self, country: &str, indicator: &str) -> Option<Rgba> {
+        self.palette
+            .get(country)
+            .or_else(|| self.palette.get(indicator))
+            .copied()
+    }
+
+    /// Dash override for a series, if any.
+    pub(crate) fn dash_for(&self, country: &str, indicator: &str) -> Option<LineDash> {
+        self.dashes
+            .get(country)
+            .or_else(|| self.dashes.get(indicator))
+            .copied()
+    }
+
+    /// Fill pattern override for a series, if any.
+    pub(crate) fn pattern_for(&self, country: &str, indicator: &str) -> Option<FillPattern> {
+        self.patterns
+            .get(country)
+            .or_else(|| self.patterns.get(indicator))
+            .copied()
+    }
+}
+
+/// Assign country-consistent styles from `palette`.
+///
+/// Countries take palette slots in sorted order, so up to `palette.len()` countries never
+/// share a colour; further countries reuse the palette in darker and lighter rounds. Within a
+/// country, indicators differ by brightness (by sorted rank), marker shape and line dash.
+///
+/// # Arguments
+/// * `series` - List of series keys (country, indicator pairs)
+/// * `palette` - Color palette to use (typically [`OFFICE_PALETTE`])
+/// * `alpha` - Alpha channel value (0-255)
+///
+/// # Returns
+/// HashMap mapping each series key to its assigned style
+pub fn assign_country_styles_with_palette(
+    series: &[SeriesKey],
+    palette: &[(u8, u8, u8)],
+    alpha: u8,
+) -> HashMap<SeriesKey, SeriesStyle> {
+    assign_grouped(series, palette, alpha, |k| (&k.country, &k.indicator))
+}
+
+/// Convenience function using [`OFFICE_PALETTE`].
+pub fn assign_country_styles(series: &[SeriesKey], alpha: u8) -> HashMap<SeriesKey, SeriesStyle> {
+    assign_country_styles_with_palette(series, &OFFICE_PALETTE, alpha)
+}
+
+/// Assign indicator-consistent styles from `palette`: the inverse of
+/// [`assign_country_styles_with_palette`]. Indicators take palette slots in sorted order and
+/// countries within an indicator differ by brightness, marker shape and line dash.
+pub fn assign_indicator_styles_with_palette(
+    series: &[SeriesKey],
+    palette: &[(u8, u8, u8)],
+    alpha: u8,
+) -> HashMap<SeriesKey, SeriesStyle> {
+    assign_grouped(series, palette, alpha, |k| (&k.indicator, &k.country))
+}
+
+/// Convenience function using [`OFFICE_PALETTE`].
+pub fn assign_indicator_styles(series: &[SeriesKey], alpha: u8) -> HashMap<SeriesKey, SeriesStyle> {
+    assign_indicator_styles_with_palette(series, &OFFICE_PALETTE, alpha)
+}
+
+/// Colour for the `index`-th key in sorted order: palette slots first, then the palette again
+/// in darker and lighter rounds so keys beyond `palette.len()` stay distinguishable.
+pub fn palette_color(palette: &[(u8, u8, u8)], index: usize) -> (u8, u8, u8) {
+    let (r, g, b) = palette[index % palette.len()];
+    let round = index / palette.len();
+    if round == 0 {
+        return (r, g, b);
+    }
+    // Rounds 1, 2, 3, 4, … mix 30%, 30%, 60%, 60%, … toward black (odd) or white (even).
+    let t = (0.3 * round.div_ceil(2) as f64).min(0.8);
+    let mix = |c: u8| {
+        let c = c as f64;
+        let target = if round % 2 == 1 { 0.0 } else { 255.0 };
+        (c + (target - c) * t).round() as u8
+    };
+    (mix(r), mix(g), mix(b))
+}
+
+/// Shared assignment: `split` returns `(group, member)`. Groups take palette colours by sorted
+/// rank ([`palette_color`]); members within a group get evenly spaced brightness by sorted
+/// rank, so no two series in a group share a shade. Markers and dashes follow a hash of the
+/// member so they do not depend on which other series are plotted.
+fn assign_grouped(
+    series: &[SeriesKey],
+    palette: &[(u8, u8, u8)],
+    alpha: u8,
+    split: impl Fn(&SeriesKey) -> (&String, &String),
+) -> HashMap<SeriesKey, SeriesStyle> {
+    let mut members: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for key in series {
+        let (group, member) = split(key);
+        members.entry(group).or_default().insert(member);
+    }
+    let group_rank: HashMap<&str, usize> =
+        members.keys().enumerate().map(|(i, g)| (*g, i)).collect();
+
+    series
+        .iter()
+        .map(|key| {
+            let (group, member) = split(key);
+            let base_color = palette_color(palette, group_rank[group.as_str()]);
+            let in_group = &members[group.as_str()];
+            let rank = in_group.iter().position(|m| *m == member).unwrap_or(0);
+            let brightness_factor = if in_group.len() > 1 {
+                0.7 + 0.6 * rank as f64 / (in_group.len() - 1) as f64
+            } else {
+                1.0
+            };
+            let (r, g, b) = adjust_brightness(base_color, brightness_factor);
+            let member_hash = stable_hash(member);
+            let style = SeriesStyle::new(
+                rgb_to_hue(base_color),
+                Rgba::new(r, g, b, alpha),
+                variation_marker(member_hash),
+                variation_dash(member_hash),
+            );
+            (key.clone(), style)
+        })
+        .collect()
+}
+
+// ------------------------ Utilities ------------------------
+
+fn stable_hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn variation_marker(hash: u64) -> MarkerShape {
+    match hash % 6 {
+        0 => MarkerShape::Circle,
+        1 => MarkerShape::Square,
+        2 => MarkerShape::Triangle,
+        3 => MarkerShape::Diamond,
+        4 => MarkerShape::Cross,
+        _ => MarkerShape::X,
+    }
+}
+
+fn variation_dash(hash: u64) -> LineDash {
+    // Rotated so dash and marker vary independently.
+    match hash.rotate_left(16) % 4 {
+        0 => LineDash::Solid,
+        1 => LineDash::Dash,
+        2 => LineDash::Dot,
+        _ => LineDash::DashDot,
+    }
+}
+
+fn adjust_brightness(color: (u8, u8, u8), factor: f64) -> (u8, u8, u8) {
+    let scale = |c: u8| (c as f64 * factor).clamp(0.0, 255.0) as u8;
+    (scale(color.0), scale(color.1), scale(color.2))
+}
+
+fn map_u64_to_range(x: u64, min: f64, max: f64) -> f64 {
+    let t = (x as f64) / (u64::MAX as f64); // 0..1
+    min + t * (max - min)
+}
+
+/// Convert RGB to approximate hue (in degrees 0-360).
+fn rgb_to_hue(rgb: (u8, u8, u8)) -> f64 {
+    let (r, g, b) = (
+        rgb.0 as f64 / 255.0,
+        rgb.1 as f64 / 255.0,
+        rgb.2 as f64 / 255.0,
+    );
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return 0.0;
+    }
+
+    let hue = if max == r {
+        60.0 * (((g - b) / delta) % 6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    if hue < 0.0 { hue + 360.0 } else { hue }
+}
+
+// HSL -> RGB conversion (linear; sufficient for chart colors)
+fn hsl_to_rgb8(hsl: Hsl) -> Rgb8 {
+    let h = (hsl.h_deg % 360.0) / 360.0;
+    let s = hsl.s.clamp(0.0, 1.0);
+    let l = hsl.l.clamp(0.0, 1.0);
+
+    if s == 0.0 {
+        let v = (l * 255.0).round() as u8;
+        return Rgb8 { r: v, g: v, b: v };
+    }
+
+    let q = if l < 0.5 {
+        l * (1.0 + s)
+    } else {
+        l + s - l * s
+    };
+    let p = 2.0 * l - q;
+
+    fn hue_to_rgb(p: f64, q: f64, mut t: f64) -> f64 {
+        if t < 0.0 {
+            t += 1.0;
+        }
+        if t > 1.0 {
+            t -= 1.0;
+        }
+        if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 1.0 / 2.0 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        }
+    }
+
+    let r = hue_to_rgb(p, q, h + 1.0 / 3.0);
+    let g = hue_to_rgb(p, q, h);
+    let b = hue_to_rgb(p, q, h - 1.0 / 3.0);
+
+    Rgb8 {
+        r: (r * 255.0).round() as u8,
+        g: (g * 255.0).round() as u8,
+        b: (b * 255.0).round() as u8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_key_creation() {
+        let key = SeriesKey::new("USA".to_string(), "GDP".to_string());
+        assert_eq!(key.country, "USA");
+        assert_eq!(key.indicator, "GDP");
+    }
+
+    #[test]
+    fn test_rgba_creation() {
+        let color = Rgba::rgb(255, 128, 64);
+        assert_eq!(color.r, 255);
+        assert_eq!(color.g, 128);
+        assert_eq!(color.b, 64);
+        assert_eq!(color.a, 255);
+    }
+
+    #[test]
+    fn test_country_consistent_styles() {
+        let series = vec![
+            SeriesKey::new("USA".to_string(), "GDP".to_string()),
+            SeriesKey::new("USA".to_string(), "Population".to_string()),
+            SeriesKey::new("DEU".to_string(), "GDP".to_string()),
+            SeriesKey::new("DEU".to_string(), "Population".to_string()),
+        ];
+
+        let styles = assign_country_styles(&series, 255);
+
+        // Same country should have same base hue
+        let usa_gdp_hue = styles[&series[0]].base_hue;
+        let usa_pop_hue = styles[&series[1]].base_hue;
+        assert_eq!(usa_gdp_hue, usa_pop_hue);
+
+        let deu_gdp_hue = styles[&series[2]].base_hue;
+        let deu_pop_hue = styles[&series[3]].base_hue;
+        assert_eq!(deu_gdp_hue, deu_pop_hue);
+
+        // Different countries should have different base hues
+        assert_ne!(usa_gdp_hue, deu_gdp_hue);
+
+        // Test determinism - running again should give same results
+        let styles2 = assign_country_styles(&series, 255);
+        assert_eq!(styles, styles2);
+    }
+
+    #[test]
+    fn test_deterministic_assignment() {
+        let series = vec![
+            SeriesKey::new("FRA".to_string(), "inflation".to_string()),
+            SeriesKey::new("GBR".to_string(), "inflation".to_string()),
+        ];
+
+        let styles1 = assign_country_styles(&series, 200);
+        let styles2 = assign_country_styles(&series, 200);
+
+        // Results should be identical across multiple calls
+        assert_eq!(styles1, styles2);
+
+        // Should have assigned styles for all series
+        assert_eq!(styles1.len(), 2);
+        assert!(styles1.contains_key(&series[0]));
+        assert!(styles1.contains_key(&series[1]));
+    }
+
+    #[test]
+    fn test_indicator_consistent_styles() {
+        let series = vec![
+            SeriesKey::new("USA".to_string(), "GDP".to_string()),
+            SeriesKey::new("DEU".to_string(), "GDP".to_string()),
+            SeriesKey::new("USA".to_string(), "Population".to_string()),
+        ];
+
+        let styles = assign_indicator_styles(&series, 255);
+        assert_eq!(styles[&series[0]].base_hue, styles[&series[1]].base_hue);
+        assert_ne!(styles[&series[0]].base_hue, styles[&series[2]].base_hue);
+        // Countries within the indicator are told apart by shade.
+        assert_ne!(styles[&series[0]].shade, styles[&series[1]].shade);
+    }
+
+    #[test]
+    fn test_style_config_parses_overrides() {
+        let config = StyleConfig::from_toml_str(
+            r##"
+            line_width = 3
+            [palette]
+            DEU = "#000000"
+            "SP.POP.TOTL" = "#c00000"
+            [dashes]
+            "SP.POP.TOTL" = "dash-dot"
+            [patterns]
+            FRA = "dots"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.line_width, Some(3));
+        assert_eq!(
+            config.color_for("DEU", "SP.POP.TOTL"),
+            Some(Rgba::rgb(0, 0, 0))
+        );
+        assert_eq!(
+            config.color_for("FRA", "SP.POP.TOTL"),
+            Some(Rgba::rgb(192, 0, 0))
+        );
+        assert_eq!(
+            config.dash_for("FRA", "SP.POP.TOTL"),
+            Some(LineDash::DashDot)
+        );
+        assert_eq!(config.dash_for("FRA", "OTHER"), None);
+        assert_eq!(config.pattern_for("FRA", "OTHER"), Some(FillPattern::Dots));
+    }
+
+    #[test]
+    fn test_style_config_rejects_bad_values() {
+        assert!(StyleConfig::from_toml_str("[palette]\nDEU = \"red\"").is_err());
+        assert!(StyleConfig::from_toml_str("line_width = 0").is_err());
+        assert!(StyleConfig::from_toml_str("colour = 1").is_err());
+    }
+
+    #[test]
+    fn test_three_countries_get_three_palette_colors() {
+        // Regression: hash-modulo assignment could give two of three countries one colour.
+        let series: Vec<SeriesKey> = ["USA", "CHN", "IND"]
+            .iter()
+            .map(|c| SeriesKey::new(c.to_string(), "GDP".to_string()))
+            .collect();
+        let styles = assign_country_styles(&series, 255);
+        let shades: Vec<Rgba> = series.iter().map(|k| styles[k].shade).collect();
+        // Sorted: CHN, IND, USA take the first three palette slots unshaded.
+        assert_eq!(shades[1], Rgba::rgb(68, 114, 196));
+        assert_eq!(shades[2], Rgba::rgb(237, 125, 49));
+        assert_eq!(shades[0], Rgba::rgb(165, 165, 165));
+    }
+
+    #[test]
+    fn test_no_shared_colors_beyond_palette_size() {
+        let series: Vec<SeriesKey> = (0..25)
+            .map(|i| SeriesKey::new(format!("C{i:02}"), "GDP".to_string()))
+            .collect();
+        let styles = assign_country_styles(&series, 255);
+        let shades: BTreeSet<(u8, u8, u8)> = styles
+            .values()
+            .map(|s| (s.shade.r, s.shade.g, s.shade.b))
+            .collect();
+        assert_eq!(shades.len(), series.len());
+    }
+
+    #[test]
+    fn test_indicators_within_country_get_distinct_shades() {
+        let series: Vec<SeriesKey> = (0..6)
+            .map(|i| SeriesKey::new("DEU".to_string(), format!("IND.{i}")))
+            .collect();
+        let styles = assign_country_styles(&series, 255);
+        let shades: BTreeSet<(u8, u8, u8)> = styles
+            .values()
+            .map(|s| (s.shade.r, s.shade.g, s.shade.b))
+            .collect();
+        assert_eq!(shades.len(), series.len());
+    }
+
+    #[test]
+    fn test_draw_style_shares_indicator_mapping() {
+        let key = SeriesKey::new("USA".to_string(), "GDP".to_string());
+        let assigned = &assign_country_styles(std::slice::from_ref(&key), 255)[&key];
+        let drawn = DrawStyle::for_series("USA", "GDP");
+        assert_eq!(drawn.marker, assigned.marker);
+        assert_eq!(drawn.line_dash, assigned.dash);
+        assert_eq!(drawn.hex.len(), 7);
+    }
+}
@@ -0,0 +1,210 @@
+//! Bar chart of grouped summary statistics ([`crate::viz::plot_summary`]).
+
+use anyhow::{Result, anyhow};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use super::errorbars::{self, ErrorBarOptions};
+use super::layout::Layout;
+use super::legend::{LegendEntries, draw_legend_panel};
+use super::text::truncate_to_width;
+use super::types::{LegendMode, PlotOptions};
+use super::util::{office_color, tick_label};
+use crate::stats::Summary;
+
+/// One bar: a group's mean with its min–max range.
+struct Bar {
+    country_iso3: String,
+    indicator: usize,
+    mean: f64,
+    min: f64,
+    max: f64,
+}
+
+/// Validated bars, in summary order, plus the indicator ids used for colouring.
+pub(crate) struct SummaryData {
+    bars: Vec<Bar>,
+    indicators: Vec<String>,
+    range: (f64, f64),
+}
+
+impl SummaryData {
+    /// Keep groups with a mean; errors when none is left.
+    pub(crate) fn new(summaries: &[Summary]) -> Result<Self> {
+        let mut indicators: Vec<String> = Vec::new();
+        let mut bars = Vec::new();
+        for s in summaries {
+            let Some(mean) = s.mean.filter(|v| v.is_finite()) else {
+                continue;
+            };
+            let indicator = match indicators.iter().position(|i| *i == s.key.indicator_id) {
+                Some(i) => i,
+                None => {
+                    indicators.push(s.key.indicator_id.clone());
+                    indicators.len() - 1
+                }
+            };
+            bars.push(Bar {
+                country_iso3: s.key.country_iso3.clone(),
+                indicator,
+                mean,
+                min: s.min.unwrap_or(mean),
+                max: s.max.unwrap_or(mean),
+            });
+        }
+        if bars.is_empty() {
+            return Err(anyhow!("no summary groups with a mean to plot"));
+        }
+        // Bars start at zero, so the range always includes it.
+        let (mut lo, mut hi) = bars.iter().fold((0.0f64, 0.0f64), |(lo, hi), b| {
+            (lo.min(b.min).min(b.mean), hi.max(b.max).max(b.mean))
+        });
+        if (hi - lo).abs() < f64::EPSILON {
+            lo -= 1.0;
+            hi += 1.0;
+        }
+        Ok(Self {
+            bars,
+            indicators,
+            range: (lo, hi),
+        })
+    }
+}
+
+pub(crate) fn draw_summary<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    data: &SummaryData,
+    options: &PlotOptions,
+    background: RGBAColor,
+) -> Result<LegendEntries> {
+    let fonts = &options.fonts;
+    let family = fonts.family();
+
+    let unit = options.y_unit.as_deref();
+    let (yscale, scale_word) = options.value_scale(
+        &options.y_scale,
+        data.range.0.abs().max(data.range.1.abs()),
+        unit,
+    );
+    let abbreviate = options.tick_abbreviation_for(unit);
+    let scale_word = super::i18n::scale_word(&options.locale, &scale_word);
+    let mean = super::i18n::tr(&options.locale, super::i18n::Term::Mean);
+    let (y_lo, y_hi) = (data.range.0 / yscale, data.range.1 / yscale);
+    let y_title = match (unit, scale_word.as_str()) {
+        (Some(u), "") => u.to_string(),
+        (Some(u), sw) => format!("{u} ({sw})"),
+        (None, "") => mean.to_string(),
+        (None, sw) => format!("{mean} ({sw})"),
+    };
+
+    let layout = Layout::new(fonts, options.width, (y_lo, y_hi), 10, abbreviate, false);
+    let axis_x_start_px = layout.axis_x_start_px();
+    let (plot_area, legend_area_opt) =
+        layout.split_legend_area(root, options.legend.panel(), &data.indicators, background)?;
+
+    let caption = {
+        let t = options.title.trim();
+        if t.is_empty() || t == "World Bank Indicator(s)" {
+            "Group means (whiskers: min–max)".to_string()
+        } else {
+            t.to_string()
+        }
+    };
+    let chart_area = layout.draw_title(&plot_area, &caption)?;
+    let y_title = layout.fit_y_title(&y_title, &chart_area);
+    let n = data.bars.len();
+    let mut chart = layout
+        .chart_builder(&chart_area)
+        .build_cartesian_2d(-0.5..(n as f64 - 0.5), y_lo..y_hi)
+        .map_err(|e| anyhow!("{:?}", e))?;
+
+    let tick_fmt = |v: &f64| tick_label(*v, abbreviate);
+    // Category labels are drawn below; plotters would place numeric ticks between bars.
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_labels(0)
+        .y_labels(10)
+        .y_label_formatter(&tick_fmt)
+        .y_desc(y_title)
+        .label_style((family, fonts.tick_px))
+        .axis_desc_style((family, fonts.axis_title_px))
+        .draw()
+        .map_err(|e| anyhow!("{:?}", e))?;
+
+    let (plot_w, _) = chart.plotting_area().dim_in_pixel();
+    let slot_px = (plot_w / n as u32).saturating_sub(4);
+    let label_style =
+        TextStyle::from((family, fonts.tick_px)).pos(Pos::new(HPos::Center, VPos::Top));
+    let whisker_opts = ErrorBarOptions::default();
+    let inside_mode = matches!(options.legend, LegendMode::Inside);
+    let mut legend_items: Vec<(String, RGBAColor)> = Vec::new();
+
+    for (idx, indicator) in data.indicators.iter().enumerate() {
+        let color = office_color(idx);
+        let elem = chart
+            .draw_series(
+                data.bars
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| b.indicator == idx)
+                    .map(|(i, b)| {
+                        let x = i as f64;
+                        Rectangle::new([(x - 0.4, 0.0), (x + 0.4, b.mean / yscale)], color.filled())
+                    }),
+            )
+            .map_err(|e| anyhow!("{:?}", e))?;
+        if inside_mode {
+            elem.label(indicator.clone())
+                .legend(move |(x, y)| Circle::new((x + 8, y), 4, color.filled()));
+        } else {
+            legend_items.push((indicator.clone(), color));
+        }
+    }
+
+    for (i, b) in data.bars.iter().enumerate() {
+        let x = i as f64;
+        if b.max > b.min {
+            errorbars::draw_whisker(
+                &mut chart,
+                x,
+                b.min / yscale,
+                b.max / yscale,
+                BLACK.into(),
+                &whisker_opts,
+            )?;
+        }
+        let label = truncate_to_width(&b.country_iso3, fonts.tick_px, slot_px);
+        chart
+            .draw_series(std::iter::once(
+                EmptyElement::at((x, y_lo)) + Text::new(label, (0, 6), label_style.clone()),
+            ))
+            .map_err(|e| anyhow!("{:?}", e))?;
+    }
+
+    if inside_mode {
+        chart
+            .configure_series_labels()
+            .border_style(BLACK)
+            .position(SeriesLabelPosition::UpperRight)
+            .background_style(WHITE.mix(0.85))
+            .label_font((family, fonts.legend_px))
+            .draw()
+            .map_err(|e| anyhow!("{:?}", e))?;
+    } else if let Some(ref legend_area) = legend_area_opt {
+        draw_legend_panel(
+            legend_area,
+            &legend_items,
+            "",
+            options.legend.panel(),
+            axis_x_start_px,
+            fonts,
+        )?;
+    }
+
+    Ok(LegendEntries {
+        items: legend_items,
+        patterns: Vec::new(),
+    })
+}
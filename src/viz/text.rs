@@ -1,8 +1,33 @@
 //! Text measurement, truncation, and wrapping utilities.
 
-/// Heuristic: estimate pixel width of text (Plotters has no built-in text measuring).
+use std::sync::OnceLock;
+
+use ab_glyph::{Font, FontRef, ScaleFont};
+
+/// DejaVu Sans, bundled and registered as the charts' `sans-serif` font.
+pub(crate) const BUNDLED_FONT: &[u8] = include_bytes!("../../assets/DejaVuSans.ttf");
+
+fn bundled_font() -> &'static FontRef<'static> {
+    static FONT: OnceLock<FontRef<'static>> = OnceLock::new();
+    FONT.get_or_init(|| FontRef::try_from_slice(BUNDLED_FONT).expect("bundled font parses"))
+}
+
+/// Pixel width of `text` at `font_px`, from the glyph advances and kerning of the bundled
+/// font; plotters lays out chart text with the same metrics. Text in a custom font is
+/// measured with the bundled metrics too.
 pub fn estimate_text_width_px(text: &str, font_px: u32) -> u32 {
-    ((text.chars().count() as f32) * (font_px as f32) * 0.60).ceil() as u32
+    let font = bundled_font().as_scaled(font_px as f32);
+    let mut width = 0.0f32;
+    let mut prev = None;
+    for ch in text.chars() {
+        let glyph = font.glyph_id(ch);
+        if let Some(prev) = prev {
+            width += font.kern(prev, glyph);
+        }
+        width += font.h_advance(glyph);
+        prev = Some(glyph);
+    }
+    width.ceil() as u32
 }
 
 /// Truncate to fit `max_px` and add a single ellipsis if needed.
@@ -72,3 +97,15 @@ pub fn wrap_text_to_width(text: &str, font_px: u32, max_px: u32) -> Vec<String>
     }
     lines
 }
+
+/// Wrap text to at most `max_lines` lines of `max_px`; overflow is folded into the last line
+/// and truncated with an ellipsis.
+pub fn wrap_text_to_lines(text: &str, font_px: u32, max_px: u32, max_lines: usize) -> Vec<String> {
+    let mut lines = wrap_text_to_width(text, font_px, max_px);
+    let max_lines = max_lines.max(1);
+    if lines.len() > max_lines {
+        let rest = lines.split_off(max_lines - 1).join(" ");
+        lines.push(truncate_to_width(&rest, font_px, max_px));
+    }
+    lines
+}
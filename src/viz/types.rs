@@ -1,7 +1,17 @@
 //! Public types and constants for the visualization module.
 
+use super::branding::Branding;
+use super::errorbars::ErrorBarOptions;
+use super::fonts::FontOptions;
+use super::style::StyleConfig;
+use crate::models::{DataPoint, Unit, UnitKind};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
 /// Legend placement options.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum LegendMode {
     /// Overlay legend inside the plotting area (may overlap data).
     Inside,
@@ -11,10 +21,28 @@ pub enum LegendMode {
     Top,
     /// Separate, non-overlapping legend band at the bottom.
     Bottom,
+    /// No legend on the chart, e.g. when it is rendered on its own with
+    /// [`render_legend`](super::render_legend) and shared by several charts.
+    Hidden,
+    /// No legend: each series is labelled in its colour at the end of its line, labels
+    /// nudged apart where they would overlap. Suits line, line-points, scatter, area and LOESS
+    /// charts of up to about 8 series; other kinds fall back to `Right`.
+    Direct,
+}
+
+impl LegendMode {
+    /// Placement for charts without line ends to label: `Direct` becomes `Right`.
+    pub(crate) fn panel(self) -> Self {
+        match self {
+            LegendMode::Direct => LegendMode::Right,
+            other => other,
+        }
+    }
 }
 
 /// Plot types supported by this module.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum PlotKind {
     /// Multi-series line chart (default).
     Line,
@@ -24,12 +52,34 @@ pub enum PlotKind {
     LinePoints,
     /// Area chart (filled area from baseline to values).
     Area,
-    /// Stacked area chart (positive values stacked upward).
+    /// Stacked area chart (positive values stacked upward; see [`StackNegatives`] for
+    /// negative ones).
     StackedArea,
-    /// Grouped bar chart (per year, bars per series).
+    /// Grouped bar chart: one group per year (or per country/indicator, see
+    /// [`BarGrouping`]), one bar per series.
     GroupedBar,
     /// LOESS smoothed line (span parameter controls smoothness).
     Loess,
+    /// One indicator on X against another on Y, one marker (or trail) per country.
+    #[serde(rename = "xy-scatter")]
+    XYScatter,
+    /// Per indicator and year, the min–max range across countries as a shaded band, with the
+    /// mean (solid) and median (dashed) on top.
+    Envelope,
+    /// Fan chart: per indicator and year, the 10th–90th and 25th–75th percentile ranges
+    /// across countries as nested shaded bands, with the median line on top. Shows where
+    /// countries sit in the distribution without the outliers an `Envelope` spans.
+    FanChart,
+    /// Pareto chart of one indicator in one year: a bar per country from the largest value
+    /// down, with the cumulative share of the total as a line on a percent axis on the right.
+    /// Shows how concentrated a quantity is, e.g. how few countries emit most of the CO₂. See
+    /// [`ParetoOptions`].
+    Pareto,
+    /// Waterfall chart of how a total changed between two years: the starting total, one
+    /// floating bar per contribution (a country of one indicator, or a component indicator of
+    /// one country) and the ending total, joined by connector lines. See
+    /// [`WaterfallOptions`].
+    Waterfall,
 }
 
 /// Default legend placement following mainstream design guidance:
@@ -38,3 +88,677 @@ pub enum PlotKind {
 ///   References: IBM Carbon (bottom/top as default), U.S. Gov Data Viz Standards.
 ///   (You can still override per call.)
 pub const DEFAULT_LEGEND_MODE: LegendMode = LegendMode::Bottom;
+
+/// Data transform applied before drawing; see the matching functions in [`crate::stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlotTransform {
+    /// Plot values as fetched.
+    #[default]
+    None,
+    /// Divide by total population (`SP.POP.TOTL`), which must be present in the data.
+    PerCapita,
+    /// Convert to constant prices with the GDP deflator (`NY.GDP.DEFL.ZS`), which must be
+    /// present in the data; see [`crate::stats::deflate`].
+    Deflate,
+    /// Rebase each series so the given year equals 100.
+    IndexToYear(i32),
+    /// Year-over-year growth in percent.
+    #[serde(rename = "yoy-growth")]
+    YoYGrowth,
+    /// First country minus second (ISO3 or ISO2 codes), one series per indicator; see
+    /// [`crate::stats::compare_countries`].
+    CountryDifference(String, String),
+    /// First country divided by second (ISO3 or ISO2 codes), one series per indicator.
+    CountryRatio(String, String),
+}
+
+/// Y-axis magnitude scaling; the scale word is appended to the axis title.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AxisScale {
+    /// Pick thousands/millions/… from the data magnitude; percentages and index numbers are
+    /// never scaled.
+    #[default]
+    Auto,
+    /// Plot raw values.
+    None,
+    Thousands,
+    Millions,
+    Billions,
+    Trillions,
+    /// Divide by `factor` and label the axis with `word` (e.g. `1e9`, `"bn"`).
+    Custom {
+        factor: f64,
+        word: String,
+    },
+}
+
+impl AxisScale {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if let AxisScale::Custom { factor, .. } = self
+            && !(factor.is_finite() && *factor > 0.0)
+        {
+            return Err(anyhow::anyhow!(
+                "axis scale factor must be a positive number, got {factor}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// `(factor, word)` for data reaching `max_abs` in `unit`.
+    pub(crate) fn resolve(&self, max_abs: f64, unit: Option<&str>) -> (f64, String) {
+        let (factor, word) = match self {
+            AxisScale::Auto if unit.is_some_and(|u| !Unit::parse(u).is_scalable()) => (1.0, ""),
+            AxisScale::Auto => super::util::choose_axis_scale(max_abs),
+            AxisScale::None => (1.0, ""),
+            AxisScale::Thousands => (1.0e3, "thousands"),
+            AxisScale::Millions => (1.0e6, "millions"),
+            AxisScale::Billions => (1.0e9, "billions"),
+            AxisScale::Trillions => (1.0e12, "trillions"),
+            AxisScale::Custom { factor, word } => return (*factor, word.clone()),
+        };
+        (factor, word.to_string())
+    }
+}
+
+/// Value-axis range when the unit is a percentage (`% of GDP`, `annual %`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PercentBounds {
+    /// The data range, as for any other unit.
+    #[default]
+    Data,
+    /// 0 to 100, widened for values outside it (negative or above-100 % rates).
+    Full,
+    /// The data range padded by this fraction of its span on both sides, but not past 0 or
+    /// 100 unless the data is.
+    Padded(f64),
+}
+
+impl PercentBounds {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if let PercentBounds::Padded(pad) = self
+            && !(0.0..=1.0).contains(pad)
+        {
+            return Err(anyhow::anyhow!(
+                "percent axis padding must be a fraction in [0, 1], got {pad}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// The axis range for percentages spanning `lo..hi`.
+    pub(crate) fn apply(&self, (lo, hi): (f64, f64)) -> (f64, f64) {
+        match *self {
+            PercentBounds::Data => (lo, hi),
+            PercentBounds::Full => (lo.min(0.0), hi.max(100.0)),
+            PercentBounds::Padded(pad) => {
+                let pad = (hi - lo) * pad;
+                let lo = if lo >= 0.0 {
+                    (lo - pad).max(0.0)
+                } else {
+                    lo - pad
+                };
+                let hi = if hi <= 100.0 {
+                    (hi + pad).min(100.0)
+                } else {
+                    hi + pad
+                };
+                (lo, hi)
+            }
+        }
+    }
+}
+
+/// A stretch of the value axis cut out of the chart, so one outlier need not squash the
+/// other series into a flat line: values run up to `from`, then on from `to`. In axis units,
+/// i.e. after `y_scale` (`100` on an axis "in billions" is 100 billion).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AxisBreak {
+    pub from: f64,
+    pub to: f64,
+}
+
+impl AxisBreak {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if !(self.from.is_finite() && self.to.is_finite() && self.from < self.to) {
+            return Err(anyhow::anyhow!(
+                "axis break must run from a lower to a higher value, got {}..{}",
+                self.from,
+                self.to
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How series colours are assigned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StyleMode {
+    /// One palette colour per series, in drawing order.
+    #[default]
+    Palette,
+    /// One base colour per country; indicators differ by shade, marker and dash.
+    Country,
+    /// One base colour per indicator; countries differ by shade, marker and dash.
+    Indicator,
+}
+
+/// Marker thinning for dense `Scatter` / `LinePoints` series. Lines are always drawn through
+/// every point; the first and last marker of a series are always kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarkerDecimation {
+    /// Skip markers that would overlap the previously drawn one.
+    #[default]
+    Auto,
+    /// Draw every marker.
+    Off,
+    /// Draw every `n`-th marker (`0` and `1` draw all).
+    EveryNth(usize),
+    /// Skip markers closer than this many pixels to the previously drawn one.
+    MinSpacingPx(u32),
+}
+
+/// What the groups along the X axis of a [`PlotKind::GroupedBar`] chart are.
+///
+/// With `Countries` or `Indicators`, the bars within a group are the other dimension and each
+/// bar shows the latest year with data of its series; the X-axis title names the year(s).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BarGrouping {
+    /// One group per year, one bar per series.
+    #[default]
+    Years,
+    /// One group per country, one bar per indicator.
+    Countries,
+    /// One group per indicator, one bar per country.
+    Indicators,
+}
+
+/// Order of the bars within each group of a [`PlotKind::GroupedBar`] chart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BarOrder {
+    /// Legend order in every group, leaving a gap where a series has no value.
+    #[default]
+    Series,
+    /// Smallest value first; each group orders its own bars.
+    Ascending,
+    /// Largest value first; each group orders its own bars.
+    Descending,
+}
+
+/// Bar layout of [`PlotKind::GroupedBar`] charts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BarOptions {
+    pub order: BarOrder,
+    /// Share of each group's slot left empty between neighbouring groups, in `[0, 1)`.
+    pub group_gap: f64,
+    /// Outline every bar, which keeps neighbours of similar colour apart.
+    pub borders: bool,
+    /// Widest a bar gets, in pixels; groups with few bars shrink around their centre.
+    pub max_bar_px: Option<u32>,
+}
+
+impl Default for BarOptions {
+    fn default() -> Self {
+        Self {
+            order: BarOrder::Series,
+            group_gap: 0.2,
+            borders: false,
+            max_bar_px: None,
+        }
+    }
+}
+
+impl BarOptions {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if !(0.0..1.0).contains(&self.group_gap) {
+            anyhow::bail!("bar group gap must be in [0, 1), got {}", self.group_gap);
+        }
+        if self.max_bar_px == Some(0) {
+            anyhow::bail!("maximum bar width must be positive");
+        }
+        Ok(())
+    }
+}
+
+/// What a [`PlotKind::StackedArea`] chart does with negative values, which cannot be stacked
+/// on top of positive ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StackNegatives {
+    /// Draw negative values as zero and report each affected series as a
+    /// [`PlotWarning`](super::PlotWarning).
+    #[default]
+    Clamp,
+    /// Refuse to draw the chart, naming the series and years with negative values.
+    Error,
+    /// Stack negative values downward from the zero line, mirroring the positive stack.
+    Mirror,
+}
+
+/// Indicator selection for [`PlotKind::XYScatter`].
+///
+/// When `x_indicator`/`y_indicator` are `None`, the first two indicator ids (sorted) present in
+/// the data are used.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct XYScatterOptions {
+    /// Indicator id plotted on the X axis.
+    pub x_indicator: Option<String>,
+    /// Indicator id plotted on the Y axis.
+    pub y_indicator: Option<String>,
+    /// Optional third indicator controlling marker area (e.g. population).
+    pub size_indicator: Option<String>,
+    /// Plot a single year; `None` draws each country as a connected trail over all years.
+    pub year: Option<i32>,
+}
+
+/// Settings of [`PlotKind::Pareto`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParetoOptions {
+    /// Year to rank the countries in; `None` takes the latest year with data. Countries
+    /// without a value in that year are left out.
+    pub year: Option<i32>,
+    /// Cumulative share in percent marked with a dashed line, e.g. `80` for the 80/20 rule;
+    /// `None` draws no line.
+    pub reference_share: Option<f64>,
+}
+
+impl Default for ParetoOptions {
+    fn default() -> Self {
+        Self {
+            year: None,
+            reference_share: Some(80.0),
+        }
+    }
+}
+
+impl ParetoOptions {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if let Some(share) = self.reference_share
+            && !(share > 0.0 && share < 100.0)
+        {
+            anyhow::bail!("Pareto reference share must be in (0, 100) percent, got {share}");
+        }
+        Ok(())
+    }
+}
+
+/// Settings of [`PlotKind::Waterfall`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaterfallOptions {
+    /// Starting year; `None` takes the earliest year with data.
+    pub from: Option<i32>,
+    /// Ending year; `None` takes the latest year with data. Contributions without a value in
+    /// both years are left out of the steps and the totals alike.
+    pub to: Option<i32>,
+}
+
+impl WaterfallOptions {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if let (Some(from), Some(to)) = (self.from, self.to)
+            && from >= to
+        {
+            anyhow::bail!("waterfall years must run forward, got {from} to {to}");
+        }
+        Ok(())
+    }
+}
+
+/// Full set of chart options accepted by [`crate::viz::plot_with_options`].
+///
+/// Construct with struct-update syntax to override only what you need:
+/// ```
+/// use wbi_rs::viz::{PlotKind, PlotOptions};
+/// let opts = PlotOptions { kind: PlotKind::LinePoints, width: 1200, ..Default::default() };
+/// assert_eq!(opts.height, 600);
+/// ```
+///
+/// Options serialize to a JSON chart spec (see [`Self::save`]) in which omitted settings
+/// keep their defaults, so a spec only needs what differs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlotOptions {
+    pub width: u32,
+    pub height: u32,
+    /// Locale tag for number formatting (e.g. `en`, `de`).
+    pub locale: String,
+    pub legend: LegendMode,
+    /// Chart title; empty or `"World Bank Indicator(s)"` derives one from the indicator names.
+    pub title: String,
+    pub kind: PlotKind,
+    /// Fraction of neighbors in (0, 1], used only for `PlotKind::Loess`.
+    pub loess_span: f64,
+    /// Country-consistent styling (same base hue per country); shorthand for
+    /// `style_mode: StyleMode::Country`, used only while `style_mode` is `Palette`.
+    pub country_styles: bool,
+    /// How series colours are assigned.
+    pub style_mode: StyleMode,
+    /// House style overrides (fixed colours/dashes/patterns, line width, marker size).
+    pub style_config: StyleConfig,
+    /// Cycle fill patterns (solid, hatch, dots, stripes) per series on `Area`, `StackedArea`
+    /// and `GroupedBar` charts, for print and colour-blind legibility.
+    pub pattern_fills: bool,
+    /// Axis/indicator selection for `PlotKind::XYScatter`.
+    pub xy: XYScatterOptions,
+    /// Year and reference line of `PlotKind::Pareto`.
+    pub pareto: ParetoOptions,
+    /// Years compared by `PlotKind::Waterfall`.
+    pub waterfall: WaterfallOptions,
+    /// X-axis categories of `PlotKind::GroupedBar`: years, countries or indicators.
+    pub bar_groups: BarGrouping,
+    /// Bar order, spacing, outlines and width of `PlotKind::GroupedBar`.
+    pub bars: BarOptions,
+    /// Negative values on `PlotKind::StackedArea` charts: clamp to zero (with a warning),
+    /// error, or stack below the zero line.
+    pub stack_negatives: StackNegatives,
+    /// Optional per-point uncertainty whiskers (line/scatter/bar kinds).
+    pub error_bars: Option<ErrorBarOptions>,
+    /// Transform applied to the data before drawing; the Y-axis unit follows it.
+    pub transform: PlotTransform,
+    /// Projected values drawn as dashed lines over the matching actual series on `Line`,
+    /// `LinePoints`, `Scatter` and `Area` charts, labelled "(projection)". Same schema as the
+    /// plotted data; see [`crate::stats::divergence`] for how far they are off. Cannot be
+    /// combined with a `transform`. Data rather than a setting, so not part of a chart spec.
+    #[serde(skip)]
+    pub projections: Vec<DataPoint>,
+    /// Leave the background transparent (SVG, and PNG with an alpha channel).
+    pub transparent: bool,
+    /// Bitmap resolution multiplier (e.g. 2.0 for retina/print). The layout is computed at
+    /// `width`×`height`; the PNG has `scale` times as many pixels. Ignored for SVG.
+    pub scale: f64,
+    /// Custom font and per-element font sizes.
+    pub fonts: FontOptions,
+    /// Marker thinning for `Scatter` / `LinePoints`.
+    pub markers: MarkerDecimation,
+    /// Y-axis unit shown in the axis title; `None` derives it from the data.
+    pub y_unit: Option<String>,
+    /// Y-axis magnitude scaling (e.g. always "billions" to compare charts).
+    pub y_scale: AxisScale,
+    /// Abbreviate value ticks (`1.2M`, `3,4 Mrd.` in German) instead of dividing the axis by
+    /// a scale word; percentages and index numbers keep plain ticks. Requires `y_scale` to
+    /// be `Auto`.
+    pub tick_abbreviation: bool,
+    /// Value-axis range when the unit is a percentage; other units always span the data.
+    pub percent_bounds: PercentBounds,
+    /// Append `%` to value ticks when the unit is a percentage.
+    pub percent_ticks: bool,
+    /// Cut this stretch out of the value axis, as an alternative to a log scale when one
+    /// series dwarfs the rest. Ignored unless the break lies inside the plotted range.
+    pub y_break: Option<AxisBreak>,
+    /// Byte-for-byte reproducible output for golden-file tests: text is always set in the
+    /// bundled font (`fonts.custom` is ignored), so layout never depends on the host's fonts.
+    /// Chart SVGs carry no timestamps or generated ids either way.
+    pub deterministic: bool,
+    /// Embed the plotted rows in SVG output as a JSON island (`<script type="application/json"
+    /// id="wbi-data">`), so the chart stays re-plottable on its own; see
+    /// [`super::embedded_data`]. Ignored for PNG and PDF.
+    pub embed_data: bool,
+    /// Logo and/or watermark text in a corner of the chart.
+    pub branding: Branding,
+}
+
+impl PlotOptions {
+    /// Check every setting that does not depend on the data (sizes, scale, LOESS span, error
+    /// bars, fonts, axis scale, house style), so a front-end can reject a bad value when it is
+    /// entered rather than when the chart is rendered.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.width == 0 || self.height == 0 {
+            anyhow::bail!(
+                "chart size must be positive, got {}x{}",
+                self.width,
+                self.height
+            );
+        }
+        super::bitmap_size(self)?;
+        if self.kind == PlotKind::Loess && !(self.loess_span > 0.0 && self.loess_span <= 1.0) {
+            anyhow::bail!("LOESS span must be in (0, 1], got {}", self.loess_span);
+        }
+        if let Some(eb) = &self.error_bars {
+            eb.validate()?;
+        }
+        super::check_render_options(self)
+    }
+
+    /// Read a JSON chart spec written by [`Self::save`] (or by hand). Settings the spec omits
+    /// keep their defaults; unknown ones are an error. Not validated; see [`Self::validate`].
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading chart spec {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("parsing chart spec {}", path.display()))
+    }
+
+    /// Write the options as a pretty-printed JSON chart spec. `projections` are data and are
+    /// not saved; error bars from an explicit uncertainty table cannot be saved.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let mut json = serde_json::to_string_pretty(self).context("serializing chart spec")?;
+        json.push('\n');
+        std::fs::write(path, json).with_context(|| format!("writing chart spec {}", path.display()))
+    }
+
+    /// These options with every setting of `overrides` that differs from the default applied
+    /// on top, nested settings (fonts, style, …) field by field; `projections` are taken from
+    /// `overrides` when it has any. Meant for flags given alongside a saved spec.
+    ///
+    /// ```
+    /// use wbi_rs::viz::{PlotKind, PlotOptions};
+    /// let spec = PlotOptions { kind: PlotKind::Area, width: 1200, ..Default::default() };
+    /// let flags = PlotOptions { width: 800, ..Default::default() };
+    /// let merged = spec.overlay(&flags).unwrap();
+    /// assert_eq!((merged.kind, merged.width), (PlotKind::Area, 800));
+    /// ```
+    pub fn overlay(&self, overrides: &PlotOptions) -> anyhow::Result<Self> {
+        use serde_json::Value;
+        fn merge(base: &mut Value, over: Value, default: &Value) {
+            match (base, over) {
+                (Value::Object(base), Value::Object(over)) => {
+                    for (key, value) in over {
+                        let default = default.get(&key).unwrap_or(&Value::Null);
+                        match base.get_mut(&key) {
+                            // Structs and maps merge field by field; enums are replaced.
+                            Some(slot) if default.is_object() && value.is_object() => {
+                                merge(slot, value, default)
+                            }
+                            _ if value == *default => {}
+                            _ => {
+                                base.insert(key, value);
+                            }
+                        }
+                    }
+                }
+                (base, over) => *base = over,
+            }
+        }
+        let mut merged = serde_json::to_value(self)?;
+        merge(
+            &mut merged,
+            serde_json::to_value(overrides)?,
+            &serde_json::to_value(Self::default())?,
+        );
+        let mut out: Self = serde_json::from_value(merged)?;
+        out.projections = if overrides.projections.is_empty() {
+            self.projections.clone()
+        } else {
+            overrides.projections.clone()
+        };
+        Ok(out)
+    }
+
+    /// Settings changed from their defaults that `kind` does not use, by field name:
+    /// `loess_span`, `xy`, `pareto`, `waterfall`, `bar_groups`, `bars`, `stack_negatives`,
+    /// `pattern_fills`, `markers`, `error_bars`, `projections`, `percent_bounds`,
+    /// `percent_ticks` and `y_break`.
+    ///
+    /// ```
+    /// use wbi_rs::viz::{PlotKind, PlotOptions};
+    /// let opts = PlotOptions { loess_span: 0.5, ..Default::default() };
+    /// assert_eq!(opts.ignored_settings(), ["loess_span"]);
+    /// let loess = PlotOptions { kind: PlotKind::Loess, ..opts };
+    /// assert!(loess.ignored_settings().is_empty());
+    /// ```
+    pub fn ignored_settings(&self) -> Vec<&'static str> {
+        use PlotKind::*;
+        let defaults = Self::default();
+        let kind = self.kind;
+        [
+            (
+                "loess_span",
+                self.loess_span != defaults.loess_span,
+                kind == Loess,
+            ),
+            ("xy", self.xy != defaults.xy, kind == XYScatter),
+            ("pareto", self.pareto != defaults.pareto, kind == Pareto),
+            (
+                "waterfall",
+                self.waterfall != defaults.waterfall,
+                kind == Waterfall,
+            ),
+            (
+                "bar_groups",
+                self.bar_groups != defaults.bar_groups,
+                kind == GroupedBar,
+            ),
+            ("bars", self.bars != defaults.bars, kind == GroupedBar),
+            (
+                "stack_negatives",
+                self.stack_negatives != defaults.stack_negatives,
+                kind == StackedArea,
+            ),
+            (
+                "pattern_fills",
+                self.pattern_fills,
+                matches!(kind, Area | StackedArea | GroupedBar),
+            ),
+            (
+                "markers",
+                self.markers != defaults.markers,
+                matches!(kind, Scatter | LinePoints),
+            ),
+            (
+                "error_bars",
+                self.error_bars.is_some(),
+                matches!(kind, Line | Scatter | LinePoints | GroupedBar),
+            ),
+            (
+                "projections",
+                !self.projections.is_empty(),
+                super::draws_projections(kind),
+            ),
+            (
+                "percent_bounds",
+                self.percent_bounds != defaults.percent_bounds,
+                kind != XYScatter,
+            ),
+            ("percent_ticks", self.percent_ticks, kind != XYScatter),
+            (
+                "y_break",
+                self.y_break.is_some(),
+                kind != XYScatter
+                    && kind != Pareto
+                    && kind != Waterfall
+                    && !(kind == GroupedBar && self.bar_groups != BarGrouping::Years),
+            ),
+        ]
+        .into_iter()
+        .filter(|&(_, set, used)| set && !used)
+        .map(|(name, _, _)| name)
+        .collect()
+    }
+
+    /// The value-axis range for data spanning `range` in `unit`: widened per
+    /// `percent_bounds` for percentages, as is otherwise.
+    pub(crate) fn value_range(&self, range: (f64, f64), unit: Option<&str>) -> (f64, f64) {
+        match unit {
+            Some(u) if Unit::parse(u).kind == UnitKind::Percent => self.percent_bounds.apply(range),
+            _ => range,
+        }
+    }
+
+    /// Text after every value tick in `unit`: `%` for percentages with `percent_ticks`.
+    pub(crate) fn tick_suffix_for(&self, unit: Option<&str>) -> &'static str {
+        let percent = unit.is_some_and(|u| Unit::parse(u).kind == UnitKind::Percent);
+        if self.percent_ticks && percent {
+            "%"
+        } else {
+            ""
+        }
+    }
+
+    /// Locale to abbreviate value ticks in `unit` with, or `None` for plain ticks.
+    pub(crate) fn tick_abbreviation_for(&self, unit: Option<&str>) -> Option<&str> {
+        let scalable = unit.is_none_or(|u| Unit::parse(u).is_scalable());
+        (self.tick_abbreviation && scalable).then_some(self.locale.as_str())
+    }
+
+    /// `(factor, word)` dividing a value axis in `unit` that reaches `max_abs`: `scale`
+    /// resolved, or unscaled when its ticks are abbreviated.
+    pub(crate) fn value_scale(
+        &self,
+        scale: &AxisScale,
+        max_abs: f64,
+        unit: Option<&str>,
+    ) -> (f64, String) {
+        if self.tick_abbreviation_for(unit).is_some() {
+            (1.0, String::new())
+        } else {
+            scale.resolve(max_abs, unit)
+        }
+    }
+
+    /// `style_mode`, with `country_styles` applied.
+    pub(crate) fn effective_style_mode(&self) -> StyleMode {
+        match self.style_mode {
+            StyleMode::Palette if self.country_styles => StyleMode::Country,
+            mode => mode,
+        }
+    }
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        Self {
+            width: 1000,
+            height: 600,
+            locale: "en".into(),
+            legend: DEFAULT_LEGEND_MODE,
+            title: "World Bank Indicator(s)".into(),
+            kind: PlotKind::Line,
+            loess_span: 0.3,
+            country_styles: false,
+            style_mode: StyleMode::Palette,
+            style_config: StyleConfig::default(),
+            pattern_fills: false,
+            xy: XYScatterOptions::default(),
+            pareto: ParetoOptions::default(),
+            waterfall: WaterfallOptions::default(),
+            bar_groups: BarGrouping::Years,
+            bars: BarOptions::default(),
+            stack_negatives: StackNegatives::Clamp,
+            error_bars: None,
+            transform: PlotTransform::None,
+            projections: Vec::new(),
+            transparent: false,
+            scale: 1.0,
+            fonts: FontOptions::default(),
+            markers: MarkerDecimation::Auto,
+            y_unit: None,
+            y_scale: AxisScale::Auto,
+            tick_abbreviation: false,
+            percent_bounds: PercentBounds::Data,
+            percent_ticks: false,
+            y_break: None,
+            deterministic: false,
+            embed_data: false,
+            branding: Branding::default(),
+        }
+    }
+}
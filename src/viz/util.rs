@@ -1,31 +1,18 @@
 //! Utility functions for visualization: colors, scaling, locale mapping, unit detection.
 
-use crate::models::DataPoint;
-use num_format::Locale;
+use crate::models::{DataPoint, Unit, UnitKind};
 use plotters::prelude::*;
 use std::collections::BTreeSet;
 
+use super::style::{OFFICE_PALETTE, palette_color};
 use super::text::estimate_text_width_px;
 
-/// Microsoft Office (2013+) chart series palette.
-/// Order: Blue, Orange, Gray, Gold, Light Blue, Green, Dark Blue, Dark Orange, Dark Gray, Brownish Gold.
-const OFFICE10: [RGBColor; 10] = [
-    RGBColor(68, 114, 196),  // blue      (#4472C4)
-    RGBColor(237, 125, 49),  // orange    (#ED7D31)
-    RGBColor(165, 165, 165), // gray      (#A5A5A5)
-    RGBColor(255, 192, 0),   // gold      (#FFC000)
-    RGBColor(91, 155, 213),  // light blue(#5B9BD5)
-    RGBColor(112, 173, 71),  // green     (#70AD47)
-    RGBColor(38, 68, 120),   // dark blue (#264478)
-    RGBColor(158, 72, 14),   // dark org. (#9E480E)
-    RGBColor(99, 99, 99),    // dark gray (#636363)
-    RGBColor(153, 115, 0),   // brownish  (#997300)
-];
-
-/// Get a color from the Office palette.
+/// Get a color from the Office palette ([`OFFICE_PALETTE`]); past ten series the palette
+/// repeats in darker and lighter rounds.
 #[inline]
 pub fn office_color(idx: usize) -> RGBAColor {
-    OFFICE10[idx % OFFICE10.len()].to_rgba()
+    let (r, g, b) = palette_color(&OFFICE_PALETTE, idx);
+    RGBColor(r, g, b).to_rgba()
 }
 
 /// Pick a single Y-axis scale and its human label based on the overall magnitude.
@@ -87,27 +74,13 @@ pub fn derive_axis_unit(points: &[DataPoint]) -> Option<String> {
     }
 }
 
-/// Heuristic: treat percent-like units as non-scalable (no thousands/millions/billions).
+/// Whether `unit` is a percentage; see [`Unit::parse`].
 pub fn is_percentage_like(unit: &str) -> bool {
-    let u = unit.to_ascii_lowercase();
-    u.contains('%') || u.contains("percent") || u.contains("percentage") || u.contains("per cent")
+    Unit::parse(unit).kind == UnitKind::Percent
 }
 
-/// Map a user-provided locale tag to a `num_format::Locale` and its decimal separator char.
-///
-/// Supported tags (case-insensitive): `en`, `us`, `en_US`, `de`, `de_DE`, `german`,
-/// `fr`, `es`, `it`, `pt`, `nl`. Defaults to English.
-pub fn map_locale(tag: &str) -> (&'static Locale, char) {
-    match tag.to_lowercase().as_str() {
-        "de" | "de_de" | "german" => (&Locale::de, ','),
-        "fr" | "fr_fr" => (&Locale::fr, ','),
-        "es" | "es_es" => (&Locale::es, ','),
-        "it" | "it_it" => (&Locale::it, ','),
-        "pt" | "pt_pt" | "pt_br" => (&Locale::pt, ','),
-        "nl" | "nl_nl" => (&Locale::nl, ','),
-        _ => (&Locale::en, '.'), // default
-    }
-}
+/// Locale mapping lives in [`crate::util::format`]; re-exported for existing callers.
+pub use crate::util::format::map_locale;
 
 /// Compute a tight left label area width for the Y axis (in pixels),
 /// based on the formatted tick labels that will appear.
@@ -122,19 +95,84 @@ pub fn compute_left_label_area_px(
     ticks: usize,
     font_px: u32,
 ) -> u32 {
-    // This must match the formatter you use in .configure_mesh().y_label_formatter(...)
-    let y_label_fmt = |v: f64| {
-        let a = v.abs();
-        let prec = if a >= 100.0 {
+    // Add padding for tick marks & a little breathing room.
+    // Clamp to avoid silly extremes; tune these if you like.
+    let with_padding =
+        y_tick_labels_width_px(ymin_scaled, ymax_scaled, ticks, font_px, None).saturating_add(18);
+    with_padding.clamp(48, 140)
+}
+
+/// Label of a value-axis tick: 0, 1 or 2 decimals for magnitudes of ≥ 100, ≥ 10 and below.
+///
+/// With `abbreviate` set to a locale, large values are shortened with its suffixes instead
+/// (`1.2M`, `3,4 Mrd.` for `de`) and the locale's decimal separator is used.
+pub(crate) fn tick_label(v: f64, abbreviate: Option<&str>) -> String {
+    let decimals = |x: f64| {
+        let a = x.abs();
+        if a >= 100.0 {
             0
         } else if a >= 10.0 {
             1
         } else {
             2
-        };
-        format!("{:.*}", prec, v)
+        }
+    };
+    let Some(locale) = abbreviate else {
+        return format!("{:.*}", decimals(v), v);
     };
 
+    let (suffixes, spaced) = super::i18n::magnitude_suffixes(locale);
+    // Largest magnitude reached; the tolerance keeps 999999.9999 from reading "1000k".
+    let tier = [1.0e3, 1.0e6, 1.0e9, 1.0e12]
+        .iter()
+        .rposition(|f| v.abs() >= f * (1.0 - 1e-9));
+    let (x, suffix) = match tier {
+        Some(i) => (v / 10f64.powi(3 * (i as i32 + 1)), suffixes[i]),
+        None => (v, ""),
+    };
+    let text = format!("{:.*}", decimals(x), x);
+    let mut text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    };
+    if text == "-0" {
+        text = "0".into();
+    }
+    let text = text.replace('.', &map_locale(locale).1.to_string());
+    match (suffix, spaced) {
+        ("", _) => text,
+        (s, true) => format!("{text} {s}"),
+        (s, false) => format!("{text}{s}"),
+    }
+}
+
+/// [`tick_label`] followed by `suffix`; suffixed ticks drop trailing zeros as abbreviated
+/// ones do (`20%`, not `20.0%`).
+pub(crate) fn suffixed_tick_label(v: f64, abbreviate: Option<&str>, suffix: &str) -> String {
+    let text = tick_label(v, abbreviate);
+    if suffix.is_empty() {
+        return text;
+    }
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    format!("{text}{suffix}")
+}
+
+/// Width of the widest Y tick label over `ymin_scaled..ymax_scaled`, without padding.
+/// `abbreviate` is passed on to [`tick_label`].
+pub(crate) fn y_tick_labels_width_px(
+    ymin_scaled: f64,
+    ymax_scaled: f64,
+    ticks: usize,
+    font_px: u32,
+    abbreviate: Option<&str>,
+) -> u32 {
+    let y_label_fmt = |v: f64| tick_label(v, abbreviate);
+
     let mut max_px = 0u32;
     // Sample the same number of tick positions as you request from Plotters.
     for i in 0..=ticks {
@@ -147,9 +185,5 @@ pub fn compute_left_label_area_px(
         let s = y_label_fmt(v);
         max_px = max_px.max(estimate_text_width_px(&s, font_px));
     }
-
-    // Add padding for tick marks & a little breathing room.
-    // Clamp to avoid silly extremes; tune these if you like.
-    let with_padding = max_px.saturating_add(18);
-    with_padding.clamp(48, 140)
+    max_px
 }
@@ -0,0 +1,311 @@
+//! Waterfall charts ([`PlotKind::Waterfall`](super::PlotKind::Waterfall)): a total in one year,
+//! one floating bar per contribution to its change, and the total in a later year.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Result, anyhow};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use super::i18n::{self, Term};
+use super::layout::Layout;
+use super::legend::{LegendEntries, draw_legend_panel};
+use super::text::{estimate_text_width_px, truncate_to_width};
+use super::types::{LegendMode, PlotOptions, WaterfallOptions};
+use super::util::{derive_axis_unit, office_color, suffixed_tick_label};
+use crate::models::DataPoint;
+
+/// One contribution: a country or component indicator and its change between the years.
+struct Step {
+    name: String,
+    /// ISO3 code or indicator id, shown when the name does not fit under the bar.
+    code: String,
+    change: f64,
+}
+
+/// The totals and contributions of a waterfall chart, largest increase first.
+pub(crate) struct WaterfallData {
+    from: i32,
+    to: i32,
+    /// `Term::Country` or `Term::Indicator`: what the steps are.
+    steps_are: Term,
+    start: f64,
+    end: f64,
+    steps: Vec<Step>,
+}
+
+impl WaterfallData {
+    /// Split the change of `points` between `options.from` and `options.to` (default: the
+    /// first and last year with data) into per-country steps for a single indicator, or
+    /// per-indicator steps for a single country.
+    pub(crate) fn from_points(points: &[DataPoint], options: &WaterfallOptions) -> Result<Self> {
+        options.validate()?;
+        let valued: Vec<&DataPoint> = points
+            .iter()
+            .filter(|p| p.value.is_some_and(f64::is_finite))
+            .collect();
+        let indicators: BTreeSet<&str> = valued.iter().map(|p| p.indicator_id.as_str()).collect();
+        let countries: BTreeSet<&str> = valued.iter().map(|p| p.country_iso3.as_str()).collect();
+        let steps_are = match (indicators.len(), countries.len()) {
+            (0, _) => return Err(anyhow!("no numeric values to plot")),
+            (1, _) => Term::Country,
+            (_, 1) => Term::Indicator,
+            (i, c) => {
+                return Err(anyhow!(
+                    "waterfall charts split one indicator by country or one country by \
+                     indicator; the data has {i} indicators and {c} countries"
+                ));
+            }
+        };
+        let first = valued.iter().map(|p| p.year).min().unwrap_or_default();
+        let last = valued.iter().map(|p| p.year).max().unwrap_or_default();
+        let (from, to) = (options.from.unwrap_or(first), options.to.unwrap_or(last));
+        if from >= to {
+            return Err(anyhow!(
+                "waterfall charts compare two years; the data only has {from}"
+            ));
+        }
+
+        // Name and first value per contribution and year, as `group_series` keeps them.
+        let mut values: BTreeMap<&str, (&str, Option<f64>, Option<f64>)> = BTreeMap::new();
+        for p in valued.iter().filter(|p| p.year == from || p.year == to) {
+            let (code, name) = match steps_are {
+                Term::Country => (p.country_iso3.as_str(), p.country_name.as_str()),
+                _ => (p.indicator_id.as_str(), p.indicator_name.as_str()),
+            };
+            let entry = values.entry(code).or_insert((name, None, None));
+            let slot = if p.year == from {
+                &mut entry.1
+            } else {
+                &mut entry.2
+            };
+            slot.get_or_insert(p.value.unwrap_or_default());
+        }
+        let paired: Vec<(&str, &str, f64, f64)> = values
+            .into_iter()
+            .filter_map(|(code, (name, a, b))| Some((code, name, a?, b?)))
+            .collect();
+        if paired.is_empty() {
+            return Err(anyhow!("no series has values in both {from} and {to}"));
+        }
+
+        let start = paired.iter().map(|s| s.2).sum();
+        let end = paired.iter().map(|s| s.3).sum();
+        let mut steps: Vec<Step> = paired
+            .into_iter()
+            .map(|(code, name, a, b)| Step {
+                name: name.to_string(),
+                code: code.to_string(),
+                change: b - a,
+            })
+            .collect();
+        steps.sort_by(|a, b| b.change.total_cmp(&a.change).then(a.name.cmp(&b.name)));
+        Ok(Self {
+            from,
+            to,
+            steps_are,
+            start,
+            end,
+            steps,
+        })
+    }
+
+    /// Running total before and after each bar: the two totals stand on zero, each step
+    /// floats from the total so far.
+    fn spans(&self) -> Vec<(f64, f64)> {
+        let mut spans = vec![(0.0, self.start)];
+        let mut level = self.start;
+        for step in &self.steps {
+            spans.push((level, level + step.change));
+            level += step.change;
+        }
+        spans.push((0.0, self.end));
+        spans
+    }
+}
+
+pub(crate) fn draw_waterfall<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    points: &[DataPoint],
+    data: &WaterfallData,
+    options: &PlotOptions,
+    background: RGBAColor,
+) -> Result<LegendEntries> {
+    let fonts = &options.fonts;
+    let family = fonts.family();
+    let err = |e| anyhow!("{:?}", e);
+
+    let spans = data.spans();
+    let lo = spans.iter().map(|s| s.0.min(s.1)).fold(0.0, f64::min);
+    let hi = spans.iter().map(|s| s.0.max(s.1)).fold(0.0, f64::max);
+    let unit = options.y_unit.clone().or_else(|| derive_axis_unit(points));
+    let max_abs = lo.abs().max(hi.abs()).max(f64::EPSILON);
+    let (yscale, scale_word) = options.value_scale(&options.y_scale, max_abs, unit.as_deref());
+    let abbreviate = options.tick_abbreviation_for(unit.as_deref());
+    let tick_suffix = options.tick_suffix_for(unit.as_deref());
+    let scale_word = i18n::scale_word(&options.locale, &scale_word);
+    let value = i18n::tr(&options.locale, Term::Value);
+    // Headroom away from zero so the outermost step does not touch the plot edge.
+    let pad = (hi - lo) * 0.05;
+    let padded = (
+        if lo < 0.0 { lo - pad } else { lo },
+        if hi > 0.0 { hi + pad } else { hi },
+    );
+    let range = options.value_range(padded, unit.as_deref());
+    let (y_lo, y_hi) = (range.0 / yscale, range.1 / yscale);
+    let y_title = match (unit.as_deref(), scale_word.as_str()) {
+        (Some(u), "") => u.to_string(),
+        (Some(u), sw) => format!("{u} ({sw})"),
+        (None, "") => value.to_string(),
+        (None, sw) => format!("{value} ({sw})"),
+    };
+
+    let total = i18n::tr(&options.locale, Term::Total);
+    let total_color = office_color(0);
+    let increase_color = office_color(5);
+    let decrease_color = office_color(1);
+    let mut legend_items = vec![(total.to_string(), total_color)];
+    if data.steps.iter().any(|s| s.change >= 0.0) {
+        let label = i18n::tr(&options.locale, Term::Increase);
+        legend_items.push((label.to_string(), increase_color));
+    }
+    if data.steps.iter().any(|s| s.change < 0.0) {
+        let label = i18n::tr(&options.locale, Term::Decrease);
+        legend_items.push((label.to_string(), decrease_color));
+    }
+    let legend_texts: Vec<String> = legend_items.iter().map(|(t, _)| t.clone()).collect();
+
+    let layout = Layout::new(fonts, options.width, (y_lo, y_hi), 10, abbreviate, true)
+        .with_tick_suffix(tick_suffix, options.width);
+    let axis_x_start_px = layout.axis_x_start_px();
+    let (plot_area, legend_area_opt) =
+        layout.split_legend_area(root, options.legend.panel(), &legend_texts, background)?;
+
+    let caption = super::chart_caption(&options.title, points);
+    let chart_area = layout.draw_title(&plot_area, &caption)?;
+    let y_title = layout.fit_y_title(&y_title, &chart_area);
+    let n = spans.len();
+    let mut chart = layout
+        .chart_builder(&chart_area)
+        .build_cartesian_2d(-0.5..(n as f64 - 0.5), y_lo..y_hi)
+        .map_err(err)?;
+
+    let tick_fmt = |v: &f64| suffixed_tick_label(*v, abbreviate, tick_suffix);
+    // Bar labels are drawn below; plotters would place numeric ticks between bars.
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_labels(0)
+        .y_labels(10)
+        .y_label_formatter(&tick_fmt)
+        .x_desc(format!(
+            "{} ({}–{})",
+            i18n::tr(&options.locale, data.steps_are),
+            data.from,
+            data.to
+        ))
+        .y_desc(y_title)
+        .label_style((family, fonts.tick_px))
+        .axis_desc_style((family, fonts.axis_title_px))
+        .draw()
+        .map_err(err)?;
+
+    if y_lo < 0.0 && y_hi > 0.0 {
+        chart
+            .draw_series(std::iter::once(PathElement::new(
+                [(-0.5, 0.0), (n as f64 - 0.5, 0.0)],
+                BLACK.stroke_width(1),
+            )))
+            .map_err(err)?;
+    }
+
+    let inside_mode = matches!(options.legend, LegendMode::Inside);
+    let bar = |i: usize, color: RGBAColor| {
+        let (a, b) = spans[i];
+        let x = i as f64;
+        Rectangle::new(
+            [(x - 0.4, a / yscale), (x + 0.4, b / yscale)],
+            color.filled(),
+        )
+    };
+    let groups: [(Vec<usize>, RGBAColor); 3] = [
+        (vec![0, n - 1], total_color),
+        (
+            (1..n - 1).filter(|i| spans[*i].1 >= spans[*i].0).collect(),
+            increase_color,
+        ),
+        (
+            (1..n - 1).filter(|i| spans[*i].1 < spans[*i].0).collect(),
+            decrease_color,
+        ),
+    ];
+    for (indices, color) in groups {
+        if indices.is_empty() {
+            continue;
+        }
+        let drawn = chart
+            .draw_series(indices.into_iter().map(|i| bar(i, color)))
+            .map_err(err)?;
+        if inside_mode && let Some((label, _)) = legend_items.iter().find(|(_, c)| *c == color) {
+            drawn.label(label.as_str()).legend(move |(x, y)| {
+                Rectangle::new([(x + 4, y - 4), (x + 12, y + 4)], color.filled())
+            });
+        }
+    }
+
+    // Connectors carry each running total over to the next bar.
+    let connector = BLACK.mix(0.5).stroke_width(1);
+    chart
+        .draw_series(spans.windows(2).enumerate().map(|(i, pair)| {
+            let level = pair[0].1 / yscale;
+            let x = i as f64;
+            PathElement::new([(x + 0.4, level), (x + 0.6, level)], connector)
+        }))
+        .map_err(err)?;
+
+    // Names where they fit under their bar, ISO3 codes or indicator ids otherwise.
+    let (plot_w, _) = chart.plotting_area().dim_in_pixel();
+    let slot_px = (plot_w / n as u32).saturating_sub(4);
+    let label_style =
+        TextStyle::from((family, fonts.tick_px)).pos(Pos::new(HPos::Center, VPos::Top));
+    let labels = std::iter::once(format!("{total} {}", data.from))
+        .chain(data.steps.iter().map(|s| {
+            if estimate_text_width_px(&s.name, fonts.tick_px) <= slot_px {
+                s.name.clone()
+            } else {
+                truncate_to_width(&s.code, fonts.tick_px, slot_px)
+            }
+        }))
+        .chain(std::iter::once(format!("{total} {}", data.to)));
+    chart
+        .draw_series(labels.enumerate().map(|(i, label)| {
+            EmptyElement::at((i as f64, y_lo)) + Text::new(label, (0, 6), label_style.clone())
+        }))
+        .map_err(err)?;
+
+    if inside_mode {
+        chart
+            .configure_series_labels()
+            .border_style(BLACK)
+            .position(SeriesLabelPosition::UpperRight)
+            .background_style(WHITE.mix(0.85))
+            .label_font((family, fonts.legend_px))
+            .draw()
+            .map_err(err)?;
+    } else if let Some(ref legend_area) = legend_area_opt {
+        draw_legend_panel(
+            legend_area,
+            &legend_items,
+            "",
+            options.legend.panel(),
+            axis_x_start_px,
+            fonts,
+        )?;
+    }
+
+    Ok(LegendEntries {
+        items: legend_items,
+        patterns: Vec::new(),
+    })
+}
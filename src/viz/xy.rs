@@ -0,0 +1,336 @@
+//! Indicator-vs-indicator scatter (`PlotKind::XYScatter`), the Gapminder-style view.
+//!
+//! Each country contributes one marker per year where both the X and the Y indicator are
+//! observed. With a fixed `year` only that year is drawn; otherwise each country becomes a
+//! connected trail through time. An optional third indicator scales marker **area**.
+
+use anyhow::{Result, anyhow};
+use plotters::backend::DrawingBackend;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::models::DataPoint;
+
+use super::layout::Layout;
+use super::legend::{LegendEntries, draw_legend_panel};
+use super::types::{AxisScale, LegendMode, PlotOptions, XYScatterOptions};
+use super::util::{extract_unit_from_indicator_name, office_color, tick_label};
+
+const MIN_RADIUS: f64 = 3.0;
+const MAX_RADIUS: f64 = 20.0;
+
+/// One paired observation: `(year, x, y, size)`.
+type XYObs = (i32, f64, f64, Option<f64>);
+
+/// One country's paired observations.
+pub(crate) struct XYSeries {
+    label: String,
+    obs: Vec<XYObs>,
+}
+
+/// Validated, paired data for an XY scatter.
+pub(crate) struct XYData {
+    series: Vec<XYSeries>,
+    x_name: String,
+    y_name: String,
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+    size_max: Option<f64>,
+    year: Option<i32>,
+}
+
+impl XYData {
+    /// Pair X/Y (and optional size) observations per country and year.
+    pub(crate) fn from_points(points: &[DataPoint], spec: &XYScatterOptions) -> Result<Self> {
+        let indicator_ids: BTreeSet<&str> =
+            points.iter().map(|p| p.indicator_id.as_str()).collect();
+        let mut defaults = indicator_ids.iter();
+        let x_id = match spec.x_indicator.as_deref() {
+            Some(id) => id.to_string(),
+            None => defaults
+                .next()
+                .ok_or_else(|| anyhow!("no indicators to plot"))?
+                .to_string(),
+        };
+        let y_id = match spec.y_indicator.as_deref() {
+            Some(id) => id.to_string(),
+            None => indicator_ids
+                .iter()
+                .find(|id| **id != x_id)
+                .ok_or_else(|| anyhow!("XY scatter needs two indicators (X and Y)"))?
+                .to_string(),
+        };
+        for id in [Some(&x_id), Some(&y_id), spec.size_indicator.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            if !indicator_ids.contains(id.as_str()) {
+                return Err(anyhow!("indicator '{}' not present in data", id));
+            }
+        }
+
+        // (iso3, year) -> indicator_id -> value
+        let mut cells: BTreeMap<(String, i32), HashMap<&str, f64>> = BTreeMap::new();
+        let mut country_names: HashMap<&str, &str> = HashMap::new();
+        let mut indicator_names: HashMap<&str, &str> = HashMap::new();
+        for p in points {
+            indicator_names
+                .entry(p.indicator_id.as_str())
+                .or_insert(p.indicator_name.as_str());
+            country_names
+                .entry(p.country_iso3.as_str())
+                .or_insert(p.country_name.as_str());
+            if let Some(v) = p.value
+                && v.is_finite()
+                && p.year != 0
+                && spec.year.is_none_or(|y| y == p.year)
+            {
+                cells
+                    .entry((p.country_iso3.clone(), p.year))
+                    .or_default()
+                    .insert(p.indicator_id.as_str(), v);
+            }
+        }
+
+        let mut by_country: BTreeMap<String, Vec<XYObs>> = BTreeMap::new();
+        for ((iso3, year), vals) in &cells {
+            if let (Some(x), Some(y)) = (vals.get(x_id.as_str()), vals.get(y_id.as_str())) {
+                let size = spec
+                    .size_indicator
+                    .as_deref()
+                    .and_then(|id| vals.get(id).copied());
+                by_country
+                    .entry(iso3.clone())
+                    .or_default()
+                    .push((*year, *x, *y, size));
+            }
+        }
+        if by_country.is_empty() {
+            return Err(anyhow!(
+                "no country/year has values for both '{}' and '{}'",
+                x_id,
+                y_id
+            ));
+        }
+
+        let mut series: Vec<XYSeries> = by_country
+            .into_iter()
+            .map(|(iso3, obs)| XYSeries {
+                label: country_names
+                    .get(iso3.as_str())
+                    .map(|s| s.to_string())
+                    .unwrap_or(iso3),
+                obs,
+            })
+            .collect();
+        series.sort_by(|a, b| a.label.cmp(&b.label));
+
+        let all = series.iter().flat_map(|s| s.obs.iter());
+        let mut x_range = (f64::INFINITY, f64::NEG_INFINITY);
+        let mut y_range = (f64::INFINITY, f64::NEG_INFINITY);
+        let mut size_max: Option<f64> = None;
+        for (_, x, y, size) in all {
+            x_range = (x_range.0.min(*x), x_range.1.max(*x));
+            y_range = (y_range.0.min(*y), y_range.1.max(*y));
+            if let Some(s) = size {
+                size_max = Some(size_max.map_or(s.abs(), |m: f64| m.max(s.abs())));
+            }
+        }
+
+        let name_of = |id: &str| {
+            indicator_names
+                .get(id)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| id.to_string())
+        };
+        Ok(Self {
+            series,
+            x_name: name_of(&x_id),
+            y_name: name_of(&y_id),
+            x_range: padded(x_range),
+            y_range: padded(y_range),
+            size_max,
+            year: spec.year,
+        })
+    }
+
+    /// Marker radius in pixels; area is proportional to the size value.
+    fn radius(&self, size: Option<f64>) -> i32 {
+        match (size, self.size_max) {
+            (Some(s), Some(max)) if max > 0.0 => {
+                (MIN_RADIUS + (MAX_RADIUS - MIN_RADIUS) * (s.abs() / max).sqrt()).round() as i32
+            }
+            _ => 5,
+        }
+    }
+}
+
+/// Widen a range by 5% on each side (or ±1 when degenerate).
+fn padded((lo, hi): (f64, f64)) -> (f64, f64) {
+    if (hi - lo).abs() < f64::EPSILON {
+        (lo - 1.0, hi + 1.0)
+    } else {
+        let pad = (hi - lo) * 0.05;
+        (lo - pad, hi + pad)
+    }
+}
+
+/// Axis scale, title and tick abbreviation locale for one indicator, honoring percent-like
+/// units.
+///
+/// `unit` replaces the indicator name in the title when given.
+fn axis_scale_and_title<'a>(
+    name: &str,
+    range: (f64, f64),
+    scale: &AxisScale,
+    unit: Option<&str>,
+    options: &'a PlotOptions,
+) -> (f64, String, Option<&'a str>) {
+    let derived = extract_unit_from_indicator_name(name);
+    let unit_or_derived = unit.or(derived.as_deref());
+    let (factor, word) =
+        options.value_scale(scale, range.0.abs().max(range.1.abs()), unit_or_derived);
+    let abbreviate = options.tick_abbreviation_for(unit_or_derived);
+    let word = super::i18n::scale_word(&options.locale, &word);
+    let base = unit.unwrap_or(name);
+    let title = if word.is_empty() {
+        base.to_string()
+    } else {
+        format!("{base} ({word})")
+    };
+    (factor, title, abbreviate)
+}
+
+pub(crate) fn draw_xy_scatter<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    data: &XYData,
+    options: &PlotOptions,
+    background: RGBAColor,
+) -> Result<LegendEntries> {
+    let fonts = &options.fonts;
+    let family = fonts.family();
+    let (xscale, x_title, x_abbreviate) =
+        axis_scale_and_title(&data.x_name, data.x_range, &AxisScale::Auto, None, options);
+    let (yscale, y_title, y_abbreviate) = axis_scale_and_title(
+        &data.y_name,
+        data.y_range,
+        &options.y_scale,
+        options.y_unit.as_deref(),
+        options,
+    );
+    let (x_lo, x_hi) = (data.x_range.0 / xscale, data.x_range.1 / xscale);
+    let (y_lo, y_hi) = (data.y_range.0 / yscale, data.y_range.1 / yscale);
+
+    let layout = Layout::new(fonts, options.width, (y_lo, y_hi), 10, y_abbreviate, true);
+    let axis_x_start_px = layout.axis_x_start_px();
+    let legend_texts: Vec<String> = data.series.iter().map(|s| s.label.clone()).collect();
+    let (plot_area, legend_area_opt) =
+        layout.split_legend_area(root, options.legend.panel(), &legend_texts, background)?;
+
+    let caption = {
+        let t = options.title.trim();
+        if t.is_empty() || t == "World Bank Indicator(s)" {
+            match data.year {
+                Some(y) => format!("{} vs {} ({y})", data.y_name, data.x_name),
+                None => format!("{} vs {}", data.y_name, data.x_name),
+            }
+        } else {
+            t.to_string()
+        }
+    };
+
+    let chart_area = layout.draw_title(&plot_area, &caption)?;
+    let y_title = layout.fit_y_title(&y_title, &chart_area);
+    let mut chart = layout
+        .chart_builder(&chart_area)
+        .build_cartesian_2d(x_lo..x_hi, y_lo..y_hi)
+        .map_err(|e| anyhow!("{:?}", e))?;
+
+    let x_tick_fmt = |v: &f64| tick_label(*v, x_abbreviate);
+    let y_tick_fmt = |v: &f64| tick_label(*v, y_abbreviate);
+    chart
+        .configure_mesh()
+        .x_desc(x_title)
+        .y_desc(y_title)
+        .x_labels(10)
+        .y_labels(10)
+        .x_label_formatter(&x_tick_fmt)
+        .y_label_formatter(&y_tick_fmt)
+        .label_style((family, fonts.tick_px))
+        .axis_desc_style((family, fonts.axis_title_px))
+        .draw()
+        .map_err(|e| anyhow!("{:?}", e))?;
+
+    let inside_mode = matches!(options.legend, LegendMode::Inside);
+    let mut legend_items: Vec<(String, RGBAColor)> = Vec::new();
+
+    for (idx, s) in data.series.iter().enumerate() {
+        let color = office_color(idx);
+        let coords: Vec<(f64, f64)> = s
+            .obs
+            .iter()
+            .map(|(_, x, y, _)| (*x / xscale, *y / yscale))
+            .collect();
+
+        if data.year.is_none() && coords.len() > 1 {
+            chart
+                .draw_series(LineSeries::new(
+                    coords.clone(),
+                    color.mix(0.6).stroke_width(1),
+                ))
+                .map_err(|e| anyhow!("{:?}", e))?;
+        }
+
+        // Trails fade older years; the latest observation is fully opaque.
+        let last = s.obs.len().saturating_sub(1);
+        let elem = chart
+            .draw_series(s.obs.iter().enumerate().map(|(i, (_, x, y, size))| {
+                let alpha = if data.year.is_none() && i < last {
+                    0.45
+                } else {
+                    0.85
+                };
+                Circle::new(
+                    (*x / xscale, *y / yscale),
+                    data.radius(*size),
+                    color.mix(alpha).filled(),
+                )
+            }))
+            .map_err(|e| anyhow!("{:?}", e))?;
+
+        if inside_mode {
+            let legend_color = color;
+            elem.label(s.label.clone())
+                .legend(move |(x, y)| Circle::new((x + 8, y), 4, legend_color.filled()));
+        } else {
+            legend_items.push((s.label.clone(), color));
+        }
+    }
+
+    if inside_mode {
+        chart
+            .configure_series_labels()
+            .border_style(BLACK)
+            .position(SeriesLabelPosition::UpperLeft)
+            .background_style(WHITE.mix(0.85))
+            .label_font((family, fonts.legend_px))
+            .draw()
+            .map_err(|e| anyhow!("{:?}", e))?;
+    } else if let Some(ref legend_area) = legend_area_opt {
+        draw_legend_panel(
+            legend_area,
+            &legend_items,
+            "",
+            options.legend.panel(),
+            axis_x_start_px,
+            fonts,
+        )?;
+    }
+
+    Ok(LegendEntries {
+        items: legend_items,
+        patterns: Vec::new(),
+    })
+}
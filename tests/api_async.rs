@@ -0,0 +1,112 @@
+mod common;
+
+use wbi_rs::{ApiError, AsyncClient, DateSpec};
+
+fn block_on<F: std::future::Future>(f: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(f)
+}
+
+#[test]
+fn async_fetch_paginates_and_splits_indicators() {
+    let (url, hits) = common::serve_pages(2);
+    let mut client = AsyncClient::default();
+    client.base_url = url;
+
+    let rows = block_on(client.fetch(
+        &["DEU".into()],
+        &["A.B".into(), "C.D".into()],
+        Some(DateSpec::Range {
+            start: 2001,
+            end: 2002,
+        }),
+        None,
+    ))
+    .unwrap();
+
+    let got: Vec<(&str, i32)> = rows
+        .iter()
+        .map(|p| (p.indicator_id.as_str(), p.year))
+        .collect();
+    assert_eq!(
+        got,
+        [("A.B", 2001), ("A.B", 2002), ("C.D", 2001), ("C.D", 2002)]
+    );
+    assert_eq!(rows[0].unit.as_deref(), Some("people"));
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 4);
+}
+
+#[test]
+fn async_fetch_surfaces_api_errors() {
+    let (url, _) = common::serve(|_| {
+        r#"[{"message":[{"id":"120","key":"Invalid value","value":"bad"}]}]"#.to_string()
+    });
+    let mut client = AsyncClient::default();
+    client.base_url = url;
+
+    let err = block_on(client.fetch(&["XXX".into()], &["A.B".into()], None, None)).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ApiError>(),
+        Some(ApiError::Api(_))
+    ));
+    assert!(block_on(client.fetch(&[], &["A.B".into()], None, None)).is_err());
+}
+
+#[test]
+fn async_fetch_honours_the_row_limit() {
+    let (url, hits) = common::serve_pages(2);
+    let mut client = AsyncClient::default();
+    client.base_url = url;
+    let countries: Vec<String> = (0..10).map(|i| format!("C{i}")).collect();
+    let date = Some(DateSpec::Range {
+        start: 2001,
+        end: 2010,
+    });
+
+    // 10 countries × 1 indicator × 10 years: refused before any request.
+    let capped = client.clone().with_max_rows(50);
+    let err = block_on(capped.fetch(&countries, &["A.B".into()], date, None)).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ApiError>(),
+        Some(ApiError::TooManyRows { rows: 100, .. })
+    ));
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+    // The totals the API reports count too.
+    let err = block_on(client.with_max_rows(1).fetch(
+        &["DEU".into()],
+        &["A.B".into()],
+        Some(DateSpec::Year(2001)),
+        None,
+    ))
+    .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ApiError>(),
+        Some(ApiError::TooManyRows { rows: 2, limit: 1 })
+    ));
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[test]
+fn async_series_country_metadata_matches_the_blocking_client() {
+    let (url, _) = common::serve(|path| {
+        if path.starts_with("/sources/2/series/A.B/metadata") {
+            r#"{"source":[{"concept":[{"variable":[{"metatype":[{"id":"Statisticalconceptandmethodology","value":"Counted."}]}]}]}]}"#.to_string()
+        } else if path.starts_with("/sources/2/country/DEU/series/A.B/metadata") {
+            r#"{"source":{"concept":[{"variable":[{"metatype":[{"id":"Country-Series","value":" Census. "}]}]}]}}"#.to_string()
+        } else {
+            r#"[{"message":[{"id":"120","key":"Invalid value","value":"bad"}]}]"#.to_string()
+        }
+    });
+    let mut client = AsyncClient::default();
+    client.base_url = url;
+
+    let deu = block_on(client.fetch_series_country_metadata("A.B", "DEU")).unwrap();
+    assert_eq!(deu.methodology.as_deref(), Some("Counted."));
+    assert_eq!(deu.notes, ["Census."]);
+    let fra = block_on(client.fetch_series_country_metadata("A.B", "FRA")).unwrap();
+    assert!(fra.notes.is_empty());
+}
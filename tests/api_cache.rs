@@ -0,0 +1,120 @@
+mod common;
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use wbi_rs::cache::{HttpCache, MetadataCache};
+use wbi_rs::{Client, DateSpec};
+
+#[test]
+fn cached_responses_are_served_until_they_expire() {
+    let (base_url, hits) = common::serve_pages(2);
+    let dir = tempfile::tempdir().unwrap();
+    let fetch = |cache: HttpCache| {
+        let mut client = Client::default().with_cache(cache);
+        client.base_url = base_url.clone();
+        client
+            .fetch(
+                &["DEU".into()],
+                &["A.B".into()],
+                Some(DateSpec::Year(2001)),
+                None,
+            )
+            .unwrap()
+    };
+
+    let first = fetch(HttpCache::new(dir.path()));
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    let second = fetch(HttpCache::new(dir.path()));
+    assert_eq!(second, first);
+    assert_eq!(
+        hits.load(Ordering::SeqCst),
+        2,
+        "second fetch should not hit the network"
+    );
+
+    fetch(HttpCache::new(dir.path()).with_ttl(Duration::ZERO));
+    assert_eq!(
+        hits.load(Ordering::SeqCst),
+        4,
+        "expired entries are refetched"
+    );
+
+    let cache = HttpCache::new(dir.path());
+    let info = cache.info().unwrap();
+    assert_eq!(info.entries, 2);
+    assert!(info.bytes > 0 && info.oldest <= info.newest);
+    assert_eq!(cache.prune(Duration::from_secs(3600)).unwrap(), 0);
+    assert_eq!(cache.clear().unwrap(), 2);
+    assert_eq!(cache.info().unwrap().entries, 0);
+}
+
+#[test]
+fn indicator_metadata_is_requested_once_and_survives_outages() {
+    let paths = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    let seen = paths.clone();
+    let (base_url, hits) = common::serve(move |path| {
+        seen.lock().unwrap().push(path.to_string());
+        let ids = path.split('/').nth(2).unwrap().split('?').next().unwrap();
+        let records: Vec<String> = ids
+            .split(';')
+            .map(|id| {
+                format!(
+                    r#"{{"id":"{id}","name":"Name of {id}","unit":"unit of {id}","sourceNote":"About {id}."}}"#
+                )
+            })
+            .collect();
+        format!(
+            r#"[{{"page":1,"pages":1,"per_page":"1000","total":{}}},[{}]]"#,
+            records.len(),
+            records.join(",")
+        )
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("indicators.json");
+    let client = |base_url: &str, cache: MetadataCache| {
+        let mut client = Client::default().with_metadata_cache(cache);
+        client.base_url = base_url.to_string();
+        client
+    };
+
+    let units = client(&base_url, MetadataCache::new(&path))
+        .fetch_indicator_units(&["A.B".into(), "C.D".into()])
+        .unwrap();
+    assert_eq!(units["C.D"], "unit of C.D");
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // A later run asks only for the indicator it has not seen.
+    let units = client(&base_url, MetadataCache::new(&path))
+        .fetch_indicator_units(&["A.B".into(), "E.F".into()])
+        .unwrap();
+    assert_eq!(units.len(), 2);
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    assert!(paths.lock().unwrap()[1].starts_with("/indicator/E.F?"));
+
+    let meta = client(&base_url, MetadataCache::new(&path))
+        .lookup_indicator("C.D")
+        .unwrap()
+        .unwrap();
+    assert_eq!(meta.source_note.as_deref(), Some("About C.D."));
+    assert_eq!(hits.load(Ordering::SeqCst), 2, "lookup served from cache");
+
+    // Expired entries are still used when the API cannot be reached.
+    let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let offline = format!("http://{}", closed.local_addr().unwrap());
+    drop(closed);
+    let stale = MetadataCache::new(&path).with_ttl(Duration::ZERO);
+    let units = client(&offline, stale.clone())
+        .fetch_indicator_units(&["A.B".into()])
+        .unwrap();
+    assert_eq!(units["A.B"], "unit of A.B");
+    assert!(
+        client(&offline, stale)
+            .fetch_indicator_units(&["X.Y".into()])
+            .is_err()
+    );
+
+    let cache = MetadataCache::new(&path);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.clear().unwrap(), 3);
+    assert!(cache.is_empty());
+}
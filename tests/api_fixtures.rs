@@ -0,0 +1,73 @@
+mod common;
+
+use wbi_rs::fixtures::{FixtureMode, Fixtures};
+use wbi_rs::{Client, DateSpec};
+
+#[test]
+fn recorded_fixtures_replay_without_the_network() {
+    let (base_url, hits) = common::serve(|path| {
+        if path.starts_with("/indicator/") {
+            return r#"[{"page":1,"pages":1,"per_page":"1000","total":1,"lastupdated":"2024-06-28"},
+                [{"id":"A.B","name":"Test","unit":"people"}]]"#
+                .to_string();
+        }
+        r#"[{"page":1,"pages":1,"per_page":"1000","total":1,"lastupdated":"2024-06-28"},[{
+            "indicator":{"id":"A.B","value":"Test"},
+            "country":{"id":"DE","value":"Germany"},
+            "countryiso3code":"DEU","date":"2020","value":1.5,
+            "unit":"","obs_status":null,"decimal":0}]]"#
+            .to_string()
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let fetch = |client: &Client| {
+        client.fetch(
+            &["DEU".into()],
+            &["A.B".into()],
+            Some(DateSpec::Year(2020)),
+            None,
+        )
+    };
+
+    let mut recorder =
+        Client::default().with_fixtures(Fixtures::new(dir.path()), FixtureMode::Record);
+    recorder.base_url = base_url;
+    let recorded = fetch(&recorder).unwrap();
+    assert_eq!(recorded[0].unit.as_deref(), Some("people"));
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    let mut names: Vec<String> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "country_DEU_indicator_A.B_format_json_per_page_1000_date_2020_page_1.json",
+            "indicator_A.B_format_json_per_page_1000.json",
+        ]
+    );
+    let text = std::fs::read_to_string(dir.path().join(&names[0])).unwrap();
+    assert!(!text.contains("lastupdated"));
+    assert!(text.contains(r#""url": "/country/DEU/indicator/A.B?"#));
+
+    // Replay against another host: no request leaves the process.
+    let mut replay =
+        Client::default().with_fixtures(Fixtures::new(dir.path()), FixtureMode::Replay);
+    replay.base_url = "http://replay.invalid".into();
+    assert_eq!(fetch(&replay).unwrap(), recorded);
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    let err = replay
+        .fetch(
+            &["FRA".into()],
+            &["A.B".into()],
+            Some(DateSpec::Year(2020)),
+            None,
+        )
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("no fixture for /country/FRA/"),
+        "{err:#}"
+    );
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "metrics")]
+
+mod common;
+
+use metrics_util::MetricKind;
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use wbi_rs::cache::HttpCache;
+use wbi_rs::{Client, DateSpec, telemetry};
+
+#[test]
+fn fetches_record_requests_cache_and_rows() {
+    let (base_url, _) = common::serve_pages(2);
+    let dir = tempfile::tempdir().unwrap();
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+
+    metrics::with_local_recorder(&recorder, || {
+        for _ in 0..2 {
+            let mut client = Client::default().with_cache(HttpCache::new(dir.path()));
+            client.base_url = base_url.clone();
+            client
+                .fetch(
+                    &["DEU".into()],
+                    &["A.B".into()],
+                    Some(DateSpec::Year(2001)),
+                    None,
+                )
+                .unwrap();
+        }
+    });
+
+    let snapshot = snapshotter.snapshot().into_vec();
+    let counter = |name: &str, labels: &[(&str, &str)]| {
+        snapshot
+            .iter()
+            .find(|(key, _, _, _)| {
+                key.kind() == MetricKind::Counter
+                    && key.key().name() == name
+                    && key
+                        .key()
+                        .labels()
+                        .map(|l| (l.key(), l.value()))
+                        .eq(labels.iter().copied())
+            })
+            .map(|(_, _, _, value)| match value {
+                DebugValue::Counter(n) => *n,
+                other => panic!("{name} is not a counter: {other:?}"),
+            })
+    };
+    // Two pages fetched once, then served from the cache.
+    assert_eq!(counter(telemetry::REQUESTS, &[("status", "200")]), Some(2));
+    assert_eq!(counter(telemetry::CACHE_MISSES, &[]), Some(2));
+    assert_eq!(counter(telemetry::CACHE_HITS, &[]), Some(2));
+    assert_eq!(counter(telemetry::ROWS, &[]), Some(4));
+    assert_eq!(counter(telemetry::RETRIES, &[]), None);
+    let timings = snapshot
+        .iter()
+        .find(|(key, _, _, _)| key.key().name() == telemetry::REQUEST_SECONDS)
+        .map(|(_, _, _, value)| match value {
+            DebugValue::Histogram(v) => v.len(),
+            other => panic!("not a histogram: {other:?}"),
+        });
+    assert_eq!(timings, Some(2));
+}
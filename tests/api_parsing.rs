@@ -45,3 +45,53 @@ fn parse_sample_json() {
     assert_eq!(points[0].year, 2019);
     assert_eq!(points[0].value, Some(83_000_000.0));
 }
+
+#[test]
+fn numbers_encoded_as_strings_or_nulls_are_accepted() {
+    let entry = |value: &str, decimal: &str| -> serde_json::Result<Entry> {
+        serde_json::from_str(&format!(
+            r#"{{"indicator":{{"id":"NY.GDP.MKTP.CD","value":"GDP"}},
+                "country":{{"id":"DE","value":"Germany"}},
+                "countryiso3code":"DEU","date":"2020",
+                "unit":"","obs_status":"","value":{value},"decimal":{decimal}}}"#
+        ))
+    };
+    let parsed = |value: &str, decimal: &str| {
+        let e = entry(value, decimal).unwrap();
+        (e.value, e.decimal)
+    };
+    assert_eq!(parsed("3.8e12", "0"), (Some(3.8e12), Some(0)));
+    assert_eq!(parsed(r#""3.8E+12""#, r#""1""#), (Some(3.8e12), Some(1)));
+    assert_eq!(parsed(r#"" 83000000 ""#, "1.0"), (Some(83e6), Some(1)));
+    assert_eq!(parsed(r#""-1.5""#, "null"), (Some(-1.5), None));
+    for missing in ["null", r#""""#, r#""..""#, r#""null""#] {
+        assert_eq!(parsed(missing, r#""""#), (None, None), "{missing}");
+    }
+    assert!(entry(r#""n/a""#, "0").is_err());
+    assert!(entry("1", "1.5").is_err());
+
+    // Fields may be left out entirely.
+    let e: Entry = serde_json::from_str(
+        r#"{"indicator":{"id":"X","value":"X"},"country":{"id":"DE","value":"Germany"},
+            "countryiso3code":"DEU","date":"2020","unit":null,"obs_status":null}"#,
+    )
+    .unwrap();
+    assert_eq!((e.value, e.decimal), (None, None));
+
+    // Saved rows from other tools load the same way, from JSON and CSV.
+    let p: DataPoint = serde_json::from_str(
+        r#"{"indicator_id":"X","indicator_name":"X","country_id":"DE","country_name":"Germany",
+            "country_iso3":"DEU","year":2020,"value":"1.25e3","unit":null,"obs_status":null,
+            "decimal":"2"}"#,
+    )
+    .unwrap();
+    assert_eq!((p.value, p.decimal), (Some(1250.0), Some(2)));
+    let csv = "indicator_id,indicator_name,country_id,country_name,country_iso3,year,value,unit,obs_status,decimal\n\
+               X,X,DE,Germany,DEU,2020,1.25E+03,,,\n\
+               X,X,DE,Germany,DEU,2021,,,,1\n";
+    let rows: Vec<DataPoint> = wbi_rs::storage::read_csv(csv.as_bytes())
+        .collect::<anyhow::Result<_>>()
+        .unwrap();
+    assert_eq!((rows[0].value, rows[0].decimal), (Some(1250.0), None));
+    assert_eq!((rows[1].value, rows[1].decimal), (None, Some(1)));
+}
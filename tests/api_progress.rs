@@ -0,0 +1,166 @@
+mod common;
+
+use wbi_rs::{Client, DateSpec, FetchProgress};
+
+#[test]
+fn progress_is_reported_per_page_and_request() {
+    let mut client = Client::default();
+    client.base_url = common::serve_pages(2).0;
+
+    let mut seen: Vec<FetchProgress> = Vec::new();
+    let rows = client
+        .fetch_with_progress(
+            &["DEU".into()],
+            &["A.B".into(), "C.D".into()],
+            Some(DateSpec::Range {
+                start: 2001,
+                end: 2002,
+            }),
+            None,
+            |p| seen.push(p),
+        )
+        .unwrap();
+
+    assert_eq!(rows.len(), 4);
+    let steps: Vec<(usize, usize, u32, u32, usize)> = seen
+        .iter()
+        .map(|p| (p.request, p.requests, p.page, p.pages, p.rows))
+        .collect();
+    assert_eq!(
+        steps,
+        [
+            (1, 2, 1, 2, 1),
+            (1, 2, 2, 2, 2),
+            (2, 2, 1, 2, 3),
+            (2, 2, 2, 2, 4)
+        ]
+    );
+}
+
+#[test]
+fn large_queries_are_split_and_capped() {
+    let paths = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    let seen = paths.clone();
+    let (base_url, hits) = common::serve(move |path| {
+        seen.lock().unwrap().push(path.to_string());
+        r#"[{"page":1,"pages":1,"per_page":"1000","total":2},[]]"#.to_string()
+    });
+    let mut client = Client::default();
+    client.base_url = base_url;
+
+    // 45 countries × 10 indicators × 100 years: 1,000 rows per country, 20 per request.
+    let countries: Vec<String> = (0..45).map(|i| format!("C{i:02}")).collect();
+    let indicators: Vec<String> = (0..10).map(|i| format!("I.{i}")).collect();
+    let date = Some(DateSpec::Range {
+        start: 1921,
+        end: 2020,
+    });
+    assert_eq!(
+        wbi_rs::api::estimate_rows(&countries, &indicators, date),
+        45_000
+    );
+    let mut requests = 0;
+    client
+        .fetch_with_progress(&countries, &indicators, date, Some(2), |p| {
+            requests = p.requests
+        })
+        .unwrap();
+    assert_eq!(requests, 3);
+    let sizes: Vec<usize> = paths
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|p| p.starts_with("/country/"))
+        .map(|p| p.split('/').nth(2).unwrap().split(';').count())
+        .collect();
+    assert_eq!(sizes, [20, 20, 5]);
+
+    // Over the cap: nothing is fetched.
+    let before = hits.load(std::sync::atomic::Ordering::SeqCst);
+    let capped = client.clone().with_max_rows(10_000);
+    let err = capped
+        .fetch(&countries, &indicators, date, Some(2))
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("about 45000 rows, more than the limit of 10000"),
+        "{err}"
+    );
+    assert!(matches!(
+        err.downcast_ref::<wbi_rs::ApiError>(),
+        Some(wbi_rs::ApiError::TooManyRows { .. })
+    ));
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), before);
+
+    // The totals the API reports count too, e.g. when `all` holds more than estimated.
+    let err = client
+        .with_max_rows(1)
+        .fetch(
+            &["DEU".into()],
+            &["A.B".into()],
+            Some(DateSpec::Year(2001)),
+            None,
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("about 2 rows"), "{err}");
+}
+
+#[test]
+fn oversized_country_groups_are_split_by_indicator_and_year() {
+    let paths = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    let seen = paths.clone();
+    let (base_url, _) = common::serve(move |path| {
+        seen.lock().unwrap().push(path.to_string());
+        r#"[{"page":1,"pages":1,"per_page":"1000","total":0},[]]"#.to_string()
+    });
+    let mut client = Client::default();
+    client.base_url = base_url;
+    let requests = |countries: &[String], indicators: &[String], date| {
+        paths.lock().unwrap().clear();
+        client.fetch(countries, indicators, date, Some(2)).unwrap();
+        let mut got: Vec<(String, String)> = paths
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.starts_with("/country/"))
+            .map(|p| {
+                let indicators = p.split('/').nth(4).unwrap().split('?').next().unwrap();
+                let date = p.split("date=").nth(1).unwrap().split('&').next().unwrap();
+                (indicators.to_string(), date.to_string())
+            })
+            .collect();
+        got.sort();
+        got
+    };
+
+    // `all` × 3 indicators × 60 years is about 48,000 rows; one indicator is about 16,000.
+    let all = ["all".to_string()];
+    let indicators: Vec<String> = ["A.B", "C.D", "E.F"].map(String::from).to_vec();
+    let sixty = Some(DateSpec::Range {
+        start: 1961,
+        end: 2020,
+    });
+    assert_eq!(wbi_rs::api::estimate_rows(&all, &indicators, sixty), 47_880);
+    assert_eq!(
+        requests(&all, &indicators, sixty),
+        [
+            ("A.B".to_string(), "1961:2020".to_string()),
+            ("C.D".to_string(), "1961:2020".to_string()),
+            ("E.F".to_string(), "1961:2020".to_string()),
+        ]
+    );
+
+    // One indicator over 100 years is still too much for `all`: split into year ranges of
+    // at most 75 years (75 × 266 rows).
+    let century = Some(DateSpec::Range {
+        start: 1921,
+        end: 2020,
+    });
+    assert_eq!(
+        requests(&all, &indicators[..1], century),
+        [
+            ("A.B".to_string(), "1921:1995".to_string()),
+            ("A.B".to_string(), "1996:2020".to_string()),
+        ]
+    );
+}
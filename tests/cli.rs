@@ -11,6 +11,998 @@ fn cli_shows_help() {
         .stdout(predicate::str::contains("wbi"));
 }
 
+#[test]
+fn plot_subcommand_renders_saved_csv() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = dir.path().join("saved.csv");
+    let rows: Vec<wbi_rs::models::DataPoint> = (2010..2015)
+        .map(|year| wbi_rs::models::DataPoint {
+            indicator_id: "SP.POP.TOTL".into(),
+            indicator_name: "Population, total".into(),
+            country_id: "DE".into(),
+            country_name: "Germany".into(),
+            country_iso3: "DEU".into(),
+            year,
+            value: Some(80.0e6 + year as f64),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect();
+    wbi_rs::storage::save_csv(&rows, &csv_path).unwrap();
+    wbi_rs::storage::save_provenance(
+        &csv_path,
+        &wbi_rs::storage::Provenance::now(vec!["DEU".into()], vec!["SP.POP.TOTL".into()]),
+    )
+    .unwrap();
+    let svg_path = dir.path().join("chart.svg");
+
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.arg("plot")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(&svg_path)
+        .args(["--plot-kind", "line-points", "--title", "Offline"]);
+    cmd.assert().success().stderr(
+        predicate::str::contains("saved.csv: fetched ")
+            .and(predicate::str::contains("(DEU · SP.POP.TOTL)")),
+    );
+    let svg = std::fs::read_to_string(&svg_path).unwrap();
+    assert!(svg.contains("\nOffline\n"));
+
+    Command::cargo_bin("wbi")
+        .unwrap()
+        .arg("plot")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(&svg_path)
+        .args(["--loess-span", "0.5"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "--loess-span has no effect on --plot-kind line",
+        ));
+    Command::cargo_bin("wbi")
+        .unwrap()
+        .arg("plot")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(&svg_path)
+        .args(["--error-bars", "decimal", "--error-bar-opacity", "2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("opacity must be in [0, 1]"));
+
+    // The default build has no clipboard backend; the flag says how to get one.
+    #[cfg(not(feature = "clipboard"))]
+    Command::cargo_bin("wbi")
+        .unwrap()
+        .arg("plot")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(&svg_path)
+        .args(["--copy", "chart"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--features clipboard"));
+
+    // A ranking year without data selects nothing rather than the first countries by code.
+    Command::cargo_bin("wbi")
+        .unwrap()
+        .arg("plot")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(&svg_path)
+        .args(["--top", "1", "--by", "year=1990"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no country has a value of SP.POP.TOTL in 1990",
+        ));
+}
+
+#[test]
+fn embedded_chart_data_replots_without_the_data_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let json_path = dir.path().join("saved.json");
+    let rows: Vec<wbi_rs::models::DataPoint> = (2010..2015)
+        .map(|year| wbi_rs::models::DataPoint {
+            indicator_id: "SP.POP.TOTL".into(),
+            indicator_name: "Population, total".into(),
+            country_id: "DE".into(),
+            country_name: "Germany".into(),
+            country_iso3: "DEU".into(),
+            year,
+            value: Some(80.0e6 + year as f64),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect();
+    wbi_rs::storage::save_json(&rows, &json_path).unwrap();
+    let svg_path = dir.path().join("chart.svg");
+    Command::cargo_bin("wbi")
+        .unwrap()
+        .arg("plot")
+        .arg("--in")
+        .arg(&json_path)
+        .arg("--out")
+        .arg(&svg_path)
+        .arg("--plot-data-embed")
+        .assert()
+        .success();
+    std::fs::remove_file(&json_path).unwrap();
+
+    let png_path = dir.path().join("again.png");
+    Command::cargo_bin("wbi")
+        .unwrap()
+        .arg("plot")
+        .arg("--in")
+        .arg(&svg_path)
+        .arg("--out")
+        .arg(&png_path)
+        .assert()
+        .success();
+    assert!(std::fs::metadata(&png_path).unwrap().len() > 0);
+}
+
+#[test]
+fn watermark_and_logo_flags_brand_the_chart() {
+    let dir = tempfile::tempdir().unwrap();
+    let json_path = dir.path().join("saved.json");
+    let rows: Vec<wbi_rs::models::DataPoint> = (2010..2015)
+        .map(|year| wbi_rs::models::DataPoint {
+            indicator_id: "SP.POP.TOTL".into(),
+            indicator_name: "Population, total".into(),
+            country_id: "DE".into(),
+            country_name: "Germany".into(),
+            country_iso3: "DEU".into(),
+            year,
+            value: Some(80.0e6 + year as f64),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect();
+    wbi_rs::storage::save_json(&rows, &json_path).unwrap();
+    let logo = dir.path().join("logo.svg");
+    std::fs::write(
+        &logo,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="10"/>"#,
+    )
+    .unwrap();
+    let plot = |out: &str| {
+        let mut cmd = Command::cargo_bin("wbi").unwrap();
+        cmd.arg("plot")
+            .arg("--in")
+            .arg(&json_path)
+            .arg("--out")
+            .arg(dir.path().join(out))
+            .arg("--logo")
+            .arg(&logo)
+            .args(["--watermark", "Example Institute"])
+            .args(["--brand-corner", "top-left", "--brand-opacity", "0.4"]);
+        cmd.assert()
+    };
+    plot("chart.svg").success();
+    let svg = std::fs::read_to_string(dir.path().join("chart.svg")).unwrap();
+    assert!(svg.contains(r#"<g opacity="0.4"><svg x="10" y="10" width="96" height="32""#));
+    assert!(svg.contains("Example Institute"));
+    plot("chart.png")
+        .failure()
+        .stderr(predicate::str::contains("SVG logos"));
+}
+
+#[test]
+fn pareto_flags_pick_the_year_and_reference_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let json_path = dir.path().join("saved.json");
+    let rows: Vec<wbi_rs::models::DataPoint> = [("DEU", "Germany", 3.0), ("FRA", "France", 5.0)]
+        .into_iter()
+        .flat_map(|(iso3, name, base)| {
+            (2018..2021).map(move |year| wbi_rs::models::DataPoint {
+                indicator_id: "EN.GHG".into(),
+                indicator_name: "Emissions".into(),
+                country_id: iso3[..2].into(),
+                country_name: name.into(),
+                country_iso3: iso3.into(),
+                year,
+                value: Some(base + (year - 2018) as f64),
+                unit: None,
+                obs_status: None,
+                decimal: None,
+            })
+        })
+        .collect();
+    wbi_rs::storage::save_json(&rows, &json_path).unwrap();
+    let out = dir.path().join("pareto.svg");
+    let plot = |reference: &str| {
+        let mut cmd = Command::cargo_bin("wbi").unwrap();
+        cmd.arg("plot")
+            .arg("--in")
+            .arg(&json_path)
+            .arg("--out")
+            .arg(&out)
+            .args(["--plot-kind", "pareto", "--pareto-year", "2019"])
+            .args(["--pareto-reference", reference]);
+        cmd.assert()
+    };
+    plot("none").success();
+    let svg = std::fs::read_to_string(&out).unwrap();
+    assert!(svg.contains("Country (2019)"));
+    assert!(svg.find("\nFrance\n").unwrap() < svg.find("\nGermany\n").unwrap());
+    plot("50%").success();
+    plot("100")
+        .failure()
+        .stderr(predicate::str::contains("between 0 and 100"));
+}
+
+#[test]
+fn plot_spec_saves_and_reuses_chart_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = dir.path().join("saved.csv");
+    let rows: Vec<wbi_rs::models::DataPoint> = (2010..2015)
+        .map(|year| wbi_rs::models::DataPoint {
+            indicator_id: "SP.POP.TOTL".into(),
+            indicator_name: "Population, total".into(),
+            country_id: "DE".into(),
+            country_name: "Germany".into(),
+            country_iso3: "DEU".into(),
+            year,
+            value: Some(1.0e6 * (year - 2000) as f64),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect();
+    wbi_rs::storage::save_csv(&rows, &csv_path).unwrap();
+    let spec_path = dir.path().join("spec.json");
+    let svg_path = dir.path().join("chart.svg");
+    let plot = || {
+        let mut cmd = Command::cargo_bin("wbi").unwrap();
+        cmd.arg("plot")
+            .arg("--in")
+            .arg(&csv_path)
+            .arg("--out")
+            .arg(&svg_path);
+        cmd
+    };
+
+    plot()
+        .args([
+            "--plot-kind",
+            "area",
+            "--title",
+            "From spec",
+            "--width",
+            "700",
+        ])
+        .arg("--save-plot-spec")
+        .arg(&spec_path)
+        .assert()
+        .success();
+    let spec = wbi_rs::viz::PlotOptions::load(&spec_path).unwrap();
+    assert_eq!(spec.kind, wbi_rs::viz::PlotKind::Area);
+
+    plot()
+        .arg("--plot-spec")
+        .arg(&spec_path)
+        .args(["--height", "300"])
+        .assert()
+        .success();
+    let svg = std::fs::read_to_string(&svg_path).unwrap();
+    assert!(svg.contains("\nFrom spec\n"), "title comes from the spec");
+    assert!(
+        svg.contains(r#"width="700" height="300""#),
+        "width from the spec, height from the flag"
+    );
+}
+
+#[test]
+fn plot_out_template_writes_one_chart_per_country() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = dir.path().join("saved.csv");
+    let rows: Vec<wbi_rs::models::DataPoint> = ["DEU", "FRA"]
+        .iter()
+        .flat_map(|iso3| {
+            (2010..2013).map(move |year| wbi_rs::models::DataPoint {
+                indicator_id: "SP.POP.TOTL".into(),
+                indicator_name: "Population, total".into(),
+                country_id: iso3[..2].into(),
+                country_name: iso3.to_string(),
+                country_iso3: iso3.to_string(),
+                year,
+                value: Some(year as f64),
+                unit: None,
+                obs_status: None,
+                decimal: None,
+            })
+        })
+        .collect();
+    wbi_rs::storage::save_csv(&rows, &csv_path).unwrap();
+
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.arg("plot")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(dir.path().join("{country}_{date}.svg"));
+    cmd.assert().success();
+    for iso3 in ["DEU", "FRA"] {
+        let svg =
+            std::fs::read_to_string(dir.path().join(format!("{iso3}_2010-2012.svg"))).unwrap();
+        assert!(
+            svg.contains(&format!("\n{iso3}")),
+            "{iso3} chart should name its country"
+        );
+    }
+
+    let mut per = Command::cargo_bin("wbi").unwrap();
+    per.arg("plot")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(dir.path().join("chart.svg"))
+        .args(["--plot-per", "indicator"]);
+    per.assert().success();
+    assert!(dir.path().join("chart_SP.POP.TOTL.svg").exists());
+
+    let mut shared = Command::cargo_bin("wbi").unwrap();
+    shared
+        .arg("plot")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(dir.path().join("{country}.svg"))
+        .arg("--legend-out")
+        .arg(dir.path().join("legend.svg"))
+        .args(["--style-mode", "country"]);
+    shared.assert().success();
+    let legend = std::fs::read_to_string(dir.path().join("legend.svg")).unwrap();
+    assert!(legend.contains("\nDEU") && legend.contains("\nFRA"));
+    let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).unwrap();
+    assert!(
+        read("DEU.svg").matches("DEU").count() < read("DEU_2010-2012.svg").matches("DEU").count(),
+        "the chart drops its own legend"
+    );
+}
+
+#[test]
+fn stats_subcommand_summarizes_saved_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let json_path = dir.path().join("saved.json");
+    let rows: Vec<wbi_rs::models::DataPoint> = ["DEU", "FRA"]
+        .iter()
+        .enumerate()
+        .map(|(i, iso3)| wbi_rs::models::DataPoint {
+            indicator_id: "SP.POP.TOTL".into(),
+            indicator_name: "Population, total".into(),
+            country_id: iso3[..2].into(),
+            country_name: iso3.to_string(),
+            country_iso3: iso3.to_string(),
+            year: 2020,
+            value: Some(1000.0 * (i + 1) as f64),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect();
+    wbi_rs::storage::save_json(&rows, &json_path).unwrap();
+
+    let out = Command::cargo_bin("wbi")
+        .unwrap()
+        .arg("stats")
+        .arg("--in")
+        .arg(&json_path)
+        .args(["--stats-format", "json", "--group-by", "indicator"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let summaries: Vec<wbi_rs::stats::Summary> = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].count, 2);
+    assert_eq!(summaries[0].mean, Some(1500.0));
+
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.arg("stats").arg("--in").arg(&json_path).args([
+        "--stats-format",
+        "markdown",
+        "--locale",
+        "de",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("| country | indicator |"))
+        .stdout(predicate::str::contains(
+            "| FRA | SP.POP.TOTL | 1 | 0 | 2.000 |",
+        ));
+
+    let mut csv = Command::cargo_bin("wbi").unwrap();
+    csv.arg("stats")
+        .arg("--in")
+        .arg(&json_path)
+        .args(["--stats-format", "csv", "--locale", "de"]);
+    csv.assert().success().stdout(predicate::str::starts_with(
+        "country,indicator,count,missing,min,p25,median,p75,max,mean,std,cagr\n\
+         DEU,SP.POP.TOTL,1,0,1000,1000,1000,1000,1000,1000,,\n",
+    ));
+
+    let mut table = Command::cargo_bin("wbi").unwrap();
+    table.arg("stats").arg("--in").arg(&json_path);
+    table
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("┌─────────┬─────────────┬"))
+        .stdout(predicate::str::contains("│ FRA     │ SP.POP.TOTL │"));
+
+    let mut quality = Command::cargo_bin("wbi").unwrap();
+    quality
+        .arg("stats")
+        .arg("--in")
+        .arg(&json_path)
+        .args(["--quality", "--stats-format", "csv"]);
+    quality
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "country,indicator,complete,last,lag,estimates,revisions,score
+\
+         DEU,SP.POP.TOTL,1,2020,0,0,,1\n",
+        ));
+}
+
+#[test]
+fn projection_overlays_the_plot_and_reports_divergence() {
+    let dir = tempfile::tempdir().unwrap();
+    let row = |year: i32, v: f64| wbi_rs::models::DataPoint {
+        indicator_id: "SP.POP.TOTL".into(),
+        indicator_name: "Population, total".into(),
+        country_id: "DE".into(),
+        country_name: "Germany".into(),
+        country_iso3: "DEU".into(),
+        year,
+        value: Some(v),
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    };
+    let actual = dir.path().join("actual.csv");
+    let projected = dir.path().join("projected.csv");
+    wbi_rs::storage::save_csv(&[row(2020, 100.0), row(2021, 110.0)], &actual).unwrap();
+    wbi_rs::storage::save_csv(
+        &[row(2020, 104.0), row(2021, 108.0), row(2022, 115.0)],
+        &projected,
+    )
+    .unwrap();
+
+    let mut stats = Command::cargo_bin("wbi").unwrap();
+    stats
+        .arg("stats")
+        .arg("--in")
+        .arg(&actual)
+        .arg("--projection")
+        .arg(&projected)
+        .args(["--stats-format", "csv"]);
+    stats.assert().success().stdout(predicate::str::diff(
+        "country,indicator,n,bias,mae,rmse,mape %,max error,year\n\
+         DEU,SP.POP.TOTL,2,1,3,3.1622776601683795,2.909090909090909,4,2020\n",
+    ));
+
+    let svg = dir.path().join("chart.svg");
+    let mut plot = Command::cargo_bin("wbi").unwrap();
+    plot.arg("plot")
+        .arg("--in")
+        .arg(&actual)
+        .arg("--out")
+        .arg(&svg)
+        .arg("--projection")
+        .arg(&projected);
+    plot.assert().success();
+    assert!(
+        std::fs::read_to_string(&svg)
+            .unwrap()
+            .contains("Germany — Population, total (projection)")
+    );
+}
+
+#[test]
+fn rank_subcommand_shows_rank_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = dir.path().join("saved.csv");
+    let row = |iso3: &str, year: i32, v: f64| wbi_rs::models::DataPoint {
+        indicator_id: "NY.GDP.PCAP.CD".into(),
+        indicator_name: "GDP per capita (current US$)".into(),
+        country_id: iso3[..2].into(),
+        country_name: iso3.to_string(),
+        country_iso3: iso3.to_string(),
+        year,
+        value: Some(v),
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    };
+    let rows = [
+        row("DEU", 2010, 3.0),
+        row("FRA", 2010, 1.0),
+        row("ITA", 2010, 2.0),
+        row("DEU", 2020, 1500.0),
+        row("FRA", 2020, 2000.0),
+        row("ITA", 2020, 1000.0),
+    ];
+    wbi_rs::storage::save_csv(&rows, &csv_path).unwrap();
+    let rank = |extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("wbi").unwrap();
+        cmd.arg("rank")
+            .arg("--in")
+            .arg(&csv_path)
+            .args(["--indicator", "NY.GDP.PCAP.CD", "--year", "2020"])
+            .args(extra);
+        cmd.assert().success()
+    };
+
+    rank(&["--baseline", "2010", "--format", "csv"]).stdout(predicate::str::diff(
+        "rank,country,name,value,rank 2010,value 2010,change\n\
+         1,FRA,FRA,2000,3,1,2\n\
+         2,DEU,DEU,1500,1,3,-1\n\
+         3,ITA,ITA,1000,2,2,-1\n",
+    ));
+    rank(&["--baseline", "2010", "--format", "markdown", "--top", "2"])
+        .stdout(predicate::str::contains(
+            "| 1 | FRA | FRA | 2,000 | 3 | 1 | ↑2 |",
+        ))
+        .stdout(predicate::str::contains(
+            "| 2 | DEU | DEU | 1,500 | 1 | 3 | ↓1 |",
+        ))
+        .stdout(predicate::str::contains("ITA").not());
+    rank(&[]).stdout(predicate::str::contains("change").not());
+}
+
+#[test]
+fn convert_subcommand_round_trips_csv_to_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = dir.path().join("saved.csv");
+    let rows = vec![wbi_rs::models::DataPoint {
+        indicator_id: "SP.POP.TOTL".into(),
+        indicator_name: "Population, total".into(),
+        country_id: "DE".into(),
+        country_name: "Germany".into(),
+        country_iso3: "DEU".into(),
+        year: 2020,
+        value: Some(83.0e6),
+        unit: None,
+        obs_status: None,
+        decimal: Some(0),
+    }];
+    wbi_rs::storage::save_csv(&rows, &csv_path).unwrap();
+    let json_path = dir.path().join("converted.json");
+
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.arg("convert")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(&json_path);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Wrote 1 rows"));
+    assert_eq!(wbi_rs::storage::load_json(&json_path).unwrap(), rows);
+
+    let mut quiet = Command::cargo_bin("wbi").unwrap();
+    quiet
+        .args(["convert", "--quiet", "--in"])
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(&json_path);
+    quiet.assert().success().stderr(predicate::str::is_empty());
+
+    let localized = dir.path().join("localized.csv");
+    Command::cargo_bin("wbi")
+        .unwrap()
+        .arg("convert")
+        .arg("--in")
+        .arg(&csv_path)
+        .arg("--out")
+        .arg(&localized)
+        .args(["--csv-locale", "de", "--csv-grouping"])
+        .assert()
+        .success();
+    let txt = std::fs::read_to_string(&localized).unwrap();
+    assert!(txt.contains(";DEU;2020;83.000.000;"), "{txt}");
+}
+
+#[test]
+fn config_file_supplies_defaults_that_flags_override() {
+    let dir = tempfile::tempdir().unwrap();
+    let json_path = dir.path().join("saved.json");
+    let rows = vec![wbi_rs::models::DataPoint {
+        indicator_id: "SP.POP.TOTL".into(),
+        indicator_name: "Population, total".into(),
+        country_id: "DE".into(),
+        country_name: "Germany".into(),
+        country_iso3: "DEU".into(),
+        year: 2020,
+        value: Some(1234.5),
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    }];
+    wbi_rs::storage::save_json(&rows, &json_path).unwrap();
+    let config_dir = dir.path().join("xdg").join("wbi");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "locale = \"de\"\n").unwrap();
+
+    let stats = |extra: &[&str]| {
+        let out = Command::cargo_bin("wbi")
+            .unwrap()
+            .env("XDG_CONFIG_HOME", dir.path().join("xdg"))
+            .arg("stats")
+            .arg("--in")
+            .arg(&json_path)
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "{:?}", out);
+        String::from_utf8(out.stdout).unwrap()
+    };
+    assert!(stats(&[]).contains("1.234,5"));
+    assert!(stats(&["--locale", "en"]).contains("1,234.5"));
+
+    let other = dir.path().join("other.toml");
+    std::fs::write(&other, "locale = \"fr\"\ntheme = \"dark\"\n").unwrap();
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.arg("stats")
+        .arg("--in")
+        .arg(&json_path)
+        .arg("--config")
+        .arg(&other);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown field `theme`"));
+}
+
+#[test]
+fn batch_reports_each_failed_job() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = dir.path().join("jobs.toml");
+    std::fs::write(
+        &manifest,
+        r#"
+[[jobs]]
+name = "bad-date"
+countries = "DEU"
+indicators = "SP.POP.TOTL"
+date = "last decade"
+
+[[jobs]]
+countries = "DEU"
+indicators = "SP.POP.TOTL"
+plot-colour = "red"
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.arg("batch").arg(&manifest).args(["--jobs", "2"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("FAILED  bad-date: invalid --date"))
+        .stderr(predicate::str::contains("FAILED  job 2:"))
+        .stderr(predicate::str::contains("0 succeeded, 2 failed"));
+}
+
+#[test]
+fn diff_exit_code_signals_differences() {
+    let dir = tempfile::tempdir().unwrap();
+    let row = |value: f64| wbi_rs::models::DataPoint {
+        indicator_id: "SP.POP.TOTL".into(),
+        indicator_name: "Population, total".into(),
+        country_id: "DE".into(),
+        country_name: "Germany".into(),
+        country_iso3: "DEU".into(),
+        year: 2020,
+        value: Some(value),
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    };
+    let old = dir.path().join("old.csv");
+    let new = dir.path().join("new.json");
+    wbi_rs::storage::save_csv(&[row(1.0)], &old).unwrap();
+    wbi_rs::storage::save_json(&[row(1.5)], &new).unwrap();
+
+    let mut same = Command::cargo_bin("wbi").unwrap();
+    same.arg("diff").arg(&old).arg(&old);
+    same.assert()
+        .code(0)
+        .stdout(predicate::str::contains("0 added, 0 removed, 0 changed"));
+
+    let mut changed = Command::cargo_bin("wbi").unwrap();
+    changed.arg("diff").arg(&old).arg(&new);
+    changed
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("~ DEU SP.POP.TOTL 2020: 1 -> 1.5"));
+
+    // --locale is global: accepted before the subcommand and applied to the text output.
+    let mut german = Command::cargo_bin("wbi").unwrap();
+    german.args(["--locale", "de", "diff"]).arg(&old).arg(&new);
+    german
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("~ DEU SP.POP.TOTL 2020: 1 -> 1,5"));
+
+    let mut missing = Command::cargo_bin("wbi").unwrap();
+    missing
+        .arg("diff")
+        .arg(&old)
+        .arg(dir.path().join("nope.csv"));
+    // Errors keep their own codes, so 1 always means "differences".
+    missing.assert().code(6);
+    let bad = dir.path().join("bad.json");
+    std::fs::write(&bad, "not json").unwrap();
+    let mut unreadable = Command::cargo_bin("wbi").unwrap();
+    unreadable.arg("diff").arg(&old).arg(&bad).assert().code(7);
+
+    // A year left out of one snapshot and empty in the other only differs without alignment.
+    let france_2019 = wbi_rs::models::DataPoint {
+        country_id: "FR".into(),
+        country_name: "France".into(),
+        country_iso3: "FRA".into(),
+        year: 2019,
+        ..row(2.0)
+    };
+    let empty_2019 = wbi_rs::models::DataPoint {
+        year: 2019,
+        value: None,
+        ..row(1.0)
+    };
+    let (without_gap, with_gap) = (dir.path().join("a.csv"), dir.path().join("b.csv"));
+    wbi_rs::storage::save_csv(&[france_2019.clone(), row(1.0)], &without_gap).unwrap();
+    wbi_rs::storage::save_csv(&[france_2019, empty_2019, row(1.0)], &with_gap).unwrap();
+    let mut plain = Command::cargo_bin("wbi").unwrap();
+    plain
+        .arg("diff")
+        .arg(&without_gap)
+        .arg(&with_gap)
+        .assert()
+        .code(1);
+    let mut aligned = Command::cargo_bin("wbi").unwrap();
+    aligned
+        .arg("diff")
+        .arg(&without_gap)
+        .arg(&with_gap)
+        .arg("--align-years")
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn chart_diff_scores_charts_and_writes_a_diff_image() {
+    let dir = tempfile::tempdir().unwrap();
+    let svg = |title: &str| {
+        format!(
+            "<svg width=\"10\" height=\"10\">\n<line x1=\"0\"/>\n<text x=\"5\">\n{title}\n</text>\n</svg>\n"
+        )
+    };
+    let (a, b) = (dir.path().join("a.svg"), dir.path().join("b.svg"));
+    std::fs::write(&a, svg("Before")).unwrap();
+    std::fs::write(&b, svg("After")).unwrap();
+    let out = dir.path().join("diff.svg");
+
+    let chart_diff = || {
+        let mut cmd = Command::cargo_bin("wbi").unwrap();
+        cmd.arg("chart-diff").arg(&a).arg(&b);
+        cmd
+    };
+    chart_diff()
+        .arg("--out")
+        .arg(&out)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "similarity 0.6667: 2 of 6 elements differ",
+        ));
+    assert!(
+        std::fs::read_to_string(&out)
+            .unwrap()
+            .contains(r#"<text data-diff="added" x="5">"#)
+    );
+    chart_diff()
+        .args(["--min-similarity", "0.5"])
+        .assert()
+        .code(0);
+    Command::cargo_bin("wbi")
+        .unwrap()
+        .arg("chart-diff")
+        .arg(&a)
+        .arg(dir.path().join("missing.svg"))
+        .assert()
+        .code(6);
+}
+
+#[test]
+fn cache_info_and_clear_on_a_cache_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = wbi_rs::cache::HttpCache::new(dir.path());
+    cache
+        .put("http://example.test/a", &serde_json::json!([1, 2, 3]))
+        .unwrap();
+
+    let mut info = Command::cargo_bin("wbi").unwrap();
+    info.args(["cache", "--cache-dir"])
+        .arg(dir.path())
+        .arg("info");
+    info.assert()
+        .success()
+        .stdout(predicate::str::contains("entries    1"));
+
+    let mut prune = Command::cargo_bin("wbi").unwrap();
+    prune
+        .args(["cache", "--cache-dir"])
+        .arg(dir.path())
+        .args(["prune", "--older-than", "30d"]);
+    prune
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Removed 0 cached responses"));
+
+    let mut clear = Command::cargo_bin("wbi").unwrap();
+    clear
+        .args(["cache", "--cache-dir"])
+        .arg(dir.path())
+        .arg("clear");
+    clear
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Removed 1 cached responses"));
+}
+
+#[test]
+fn refresh_once_if_stale_skips_a_fresh_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("pop.csv");
+    std::fs::write(&out, "indicator_id\n").unwrap();
+
+    // A fresh file means no fetch at all, so this works offline.
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.env("XDG_STATE_HOME", dir.path())
+        .args(["get", "-c", "DEU", "-i", "SP.POP.TOTL", "--out"])
+        .arg(&out)
+        .args(["--refresh", "1d", "--once-if-stale"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("is up to date"));
+
+    let mut template = Command::cargo_bin("wbi").unwrap();
+    template
+        .args(["get", "-c", "DEU", "-i", "SP.POP.TOTL", "--out"])
+        .arg(dir.path().join("{country}.csv"))
+        .args(["--refresh", "1d"]);
+    template
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("single --out file"));
+}
+
+#[test]
+fn errors_map_to_exit_codes_and_json() {
+    let mut usage = Command::cargo_bin("wbi").unwrap();
+    usage.args(["get", "--bogus"]);
+    usage.assert().code(2);
+
+    // Values rejected after parsing are usage errors too; nothing is fetched.
+    let mut bad_date = Command::cargo_bin("wbi").unwrap();
+    bad_date.args([
+        "--error-format",
+        "json",
+        "get",
+        "-c",
+        "DEU",
+        "-i",
+        "SP.POP.TOTL",
+        "--date",
+        "2020:2010",
+    ]);
+    let out = bad_date.output().unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    let err: serde_json::Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["kind"], "usage");
+    assert!(
+        err["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("invalid --date")
+    );
+
+    let mut io = Command::cargo_bin("wbi").unwrap();
+    io.args([
+        "--error-format",
+        "json",
+        "stats",
+        "--in",
+        "/nonexistent/saved.json",
+    ]);
+    let out = io.output().unwrap();
+    assert_eq!(out.status.code(), Some(6));
+    let err: serde_json::Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["kind"], "io");
+    assert_eq!(err["error"]["exit_code"], 6);
+    assert!(
+        err["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("saved.json")
+    );
+}
+
+#[test]
+fn validate_flags_typos_offline() {
+    let mut ok = Command::cargo_bin("wbi").unwrap();
+    ok.args([
+        "validate",
+        "--offline",
+        "-c",
+        "DEU,EUU",
+        "-i",
+        "SP.POP.TOTL",
+    ]);
+    ok.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "EUU          ok         European Union",
+        ))
+        .stdout(predicate::str::contains("unchecked  offline"));
+
+    let mut typo = Command::cargo_bin("wbi").unwrap();
+    typo.args(["validate", "--offline", "-c", "DEU;DUE"]);
+    typo.assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "DUE  unknown    did you mean DEU (Germany)?",
+        ))
+        .stderr(predicate::str::contains("1 of 2 codes are unknown"));
+}
+
+#[test]
+fn custom_aggregates_from_the_config_count_as_known_codes() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        r#"
+[aggregates.NRD]
+name = "Nordics"
+members = ["DNK", "FIN", "ISL", "NOR", "SWE"]
+"#,
+    )
+    .unwrap();
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.arg("--config")
+        .arg(&config)
+        .args(["validate", "--offline", "-c", "nrd,DEU"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "custom aggregate of DNK, FIN, ISL, NOR, SWE",
+    ));
+
+    std::fs::write(
+        &config,
+        "[aggregates.NRD]\nmembers = [\"DNK\"]\nmethod = \"median\"\n",
+    )
+    .unwrap();
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.arg("--config")
+        .arg(&config)
+        .args(["validate", "--offline", "-c", "NRD"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown variant `median`"));
+}
+
 // Live test (opt-in): cargo test --features online -- --ignored
 #[cfg(feature = "online")]
 #[test]
@@ -30,3 +1022,172 @@ fn fetch_online_population() {
     ]);
     cmd.assert().success();
 }
+
+#[test]
+fn presets_run_as_get_and_land_in_history() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("pop.csv");
+    std::fs::write(&out, "indicator_id\n").unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        format!(
+            "[presets.pop]\ncountries = [\"DEU\"]\nindicators = \"SP.POP.TOTL\"\n\
+             out = {:?}\nrefresh = \"1d\"\n",
+            out.display().to_string()
+        ),
+    )
+    .unwrap();
+    let wbi = || {
+        let mut cmd = Command::cargo_bin("wbi").unwrap();
+        cmd.env("XDG_STATE_HOME", dir.path().join("state"))
+            .arg("--config")
+            .arg(&config);
+        cmd
+    };
+
+    wbi()
+        .arg("preset")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pop "))
+        .stdout(predicate::str::contains("--refresh 1d"));
+    // Extra flags are appended; a repeated flag replaces the preset's value.
+    wbi()
+        .args(["preset", "pop", "--once-if-stale", "--refresh", "2d"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("is up to date"));
+    wbi()
+        .args(["preset", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("available: pop"));
+
+    wbi()
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1  wbi --config"))
+        .stdout(predicate::str::contains(
+            "preset pop --once-if-stale --refresh 2d",
+        ))
+        .stdout(predicate::str::contains("nope").not());
+}
+
+#[test]
+fn get_emit_prints_the_run_without_fetching() {
+    let run = |form: &str| {
+        let out = Command::cargo_bin("wbi")
+            .unwrap()
+            .args([
+                "--locale",
+                "de",
+                "get",
+                "-c",
+                "DEU;FRA",
+                "-i",
+                "SP.POP.TOTL",
+            ])
+            .args([
+                "--title", "It's GDP", "--width", "800", "--stats", "--emit", form,
+            ])
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        String::from_utf8(out.stdout).unwrap()
+    };
+
+    assert_eq!(
+        run("command"),
+        "wbi get --countries 'DEU;FRA' --indicators SP.POP.TOTL --stats \
+         --width 800 --title 'It'\\''s GDP' --locale de\n"
+    );
+    let job: toml::Table =
+        toml::from_str(run("manifest").strip_prefix("[[jobs]]\n").unwrap()).unwrap();
+    assert_eq!(job["countries"].as_str(), Some("DEU;FRA"));
+    assert_eq!(job["width"].as_integer(), Some(800));
+    assert_eq!(job["stats"].as_bool(), Some(true));
+    assert_eq!(job["locale"].as_str(), Some("de"));
+    assert!(!job.contains_key("emit"));
+}
+
+#[test]
+fn serve_rejects_bad_requests_without_fetching() {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = Command::cargo_bin("wbi")
+        .unwrap()
+        .args(["serve", "--port", "0", "--cache-dir"])
+        .arg(dir.path())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("Serving on") {
+        line.clear();
+        assert!(stderr.read_line(&mut line).unwrap() > 0, "server exited");
+    }
+    let addr = line
+        .split("http://")
+        .nth(1)
+        .and_then(|rest| rest.split('/').next())
+        .unwrap()
+        .to_string();
+
+    let request = |req: &str| {
+        let mut stream = std::net::TcpStream::connect(&addr).unwrap();
+        write!(stream, "{req}\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+    let nowhere = request("GET /nowhere HTTP/1.1");
+    assert!(nowhere.starts_with("HTTP/1.1 400"), "{nowhere}");
+    assert!(nowhere.contains("use /data, /stats or /chart.svg"));
+    let denied = request("GET /data?countries=DEU&indicators=X&out=%2Ftmp%2Fx.csv HTTP/1.1");
+    assert!(
+        denied.contains("'out' is not available over HTTP"),
+        "{denied}"
+    );
+    let bad_date = request("GET /chart.svg?countries=DEU&indicators=X&date=soon HTTP/1.1");
+    assert!(bad_date.starts_with("HTTP/1.1 400"), "{bad_date}");
+    assert!(bad_date.contains("invalid --date"));
+    assert!(request("POST /data HTTP/1.1").starts_with("HTTP/1.1 405"));
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn history_fetches_summarizes_the_fetch_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("fetch.log.jsonl");
+    std::fs::write(
+        &log,
+        r#"{"url":"https://api.example/a","timestamp":"2024-03-01T10:00:00Z","status":200,"rows":50,"cached":false}
+{"url":"https://api.example/b","timestamp":"2024-03-01T10:00:01Z","status":null,"rows":50,"cached":true}
+{"url":"https://api.example/c","timestamp":"2024-03-02T08:30:00Z","status":null,"rows":null,"cached":false,"error":"connection refused"}
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("wbi").unwrap();
+    cmd.env("XDG_STATE_HOME", dir.path())
+        .args(["history", "--fetches", "-n", "2", "--log"])
+        .arg(&log);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("https://api.example/a").not())
+        .stdout(predicate::str::contains(
+            "2024-03-01 10:00:01  cache      50  https://api.example/b",
+        ))
+        .stdout(predicate::str::contains(
+            "2024-03-02 08:30:00  error       -  https://api.example/c",
+        ))
+        .stdout(predicate::str::contains(
+            "3 calls from 2024-03-01 10:00:00 to 2024-03-02 08:30:00 UTC: 1 from cache, 1 failed, 100 rows",
+        ));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use wbi_rs::Client;
+use wbi_rs::codes::{
+    country_group, country_groups, find_country, is_indicator_id, suggest_countries,
+};
+
+#[test]
+fn country_codes_resolve_by_iso3_and_iso2() {
+    assert_eq!(find_country("DEU").map(|e| e.name), Some("Germany"));
+    assert_eq!(find_country(" us ").map(|e| e.iso3), Some("USA"));
+    let euu = find_country("EUU").unwrap();
+    assert!(euu.aggregate);
+    assert_eq!(find_country("XYZ"), None);
+}
+
+#[test]
+fn typos_get_suggestions() {
+    let iso3 =
+        |code: &str| -> Vec<&str> { suggest_countries(code).iter().map(|e| e.iso3).collect() };
+    assert_eq!(iso3("DUE"), ["DEU"]);
+    assert_eq!(iso3("FRAA"), ["FRA"]);
+    assert_eq!(iso3("germany"), ["DEU"]);
+    assert!(iso3("QQQQQQ").is_empty());
+}
+
+#[test]
+fn built_in_country_groups_list_known_countries_once() {
+    for name in ["G7", "G20", "EU27", "OECD", "BRICS"] {
+        assert!(country_groups().contains_key(name), "{name}");
+    }
+    assert_eq!(country_group("eu27").map(<[String]>::len), Some(27));
+    assert_eq!(country_group(" oecd ").map(<[String]>::len), Some(38));
+    assert_eq!(country_group("G8"), None);
+    for (name, members) in country_groups() {
+        let mut seen = std::collections::HashSet::new();
+        for code in members {
+            let economy = find_country(code).unwrap_or_else(|| panic!("{name}: {code}"));
+            assert!(!economy.aggregate && economy.iso3 == code, "{name}: {code}");
+            assert!(seen.insert(code), "{name}: {code} twice");
+        }
+    }
+}
+
+#[test]
+fn indicator_ids_are_checked_for_shape() {
+    assert!(is_indicator_id("SP.POP.TOTL"));
+    assert!(is_indicator_id("per_si_allsi.cov_pop_tot"));
+    assert!(!is_indicator_id(""));
+    assert!(!is_indicator_id(".SP"));
+    assert!(!is_indicator_id("SP POP"));
+}
+
+#[test]
+fn lookups_tell_known_from_invalid_codes() {
+    let mut client = Client::default();
+    client.base_url = common::serve(|path| {
+        if path.contains("/XKX?") {
+            r#"[{"page":1,"pages":1,"per_page":"50","total":1},[{"id":"XKX","iso2Code":"XK","name":"Kosovo"}]]"#.into()
+        } else if path.contains("/SP.POP.TOTL?") {
+            r#"[{"page":1,"pages":1,"per_page":"50","total":1},[{"id":"SP.POP.TOTL","name":"Population, total","unit":""}]]"#.into()
+        } else {
+            r#"[{"message":[{"id":"120","key":"Invalid value","value":"The provided parameter value is not valid"}]}]"#.into()
+        }
+    })
+    .0;
+
+    assert_eq!(
+        client.lookup_country("XKX").unwrap().as_deref(),
+        Some("Kosovo")
+    );
+    assert_eq!(client.lookup_country("XKY").unwrap(), None);
+    let meta = client.lookup_indicator("SP.POP.TOTL").unwrap().unwrap();
+    assert_eq!(meta.name, "Population, total");
+    assert!(client.lookup_indicator("SP.POP.TOTX").unwrap().is_none());
+}
@@ -0,0 +1,78 @@
+//! Golden-file checks: compare rendered output with a reference checked in under
+//! `tests/golden/`.
+//!
+//! Set `WBI_UPDATE_GOLDEN=1` to (re)write the references from the current output, then
+//! review the change with `git diff tests/golden`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Differing lines listed in a failure report before the rest is summarized.
+const MAX_REPORTED: usize = 12;
+
+/// Panic with a line diff unless `actual` matches `tests/golden/<name>`.
+pub fn assert_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("WBI_UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "cannot read {}: {e}; run with WBI_UPDATE_GOLDEN=1 to create it",
+            path.display()
+        )
+    });
+    if expected == actual {
+        return;
+    }
+    let saved = actual_path(name);
+    fs::write(&saved, actual).unwrap();
+    panic!(
+        "{name} differs from its golden file\n{}\nactual output: {}\nreference:     {}\n\
+         rerun with WBI_UPDATE_GOLDEN=1 to accept the new output",
+        diff_report(&expected, actual),
+        saved.display(),
+        path.display()
+    );
+}
+
+/// Where the output of a failed comparison is kept for inspection.
+fn actual_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("golden-actual-{name}"))
+}
+
+/// Line-by-line differences, `-` for the reference and `+` for the actual output.
+pub fn diff_report(expected: &str, actual: &str) -> String {
+    let (exp, act): (Vec<&str>, Vec<&str>) = (expected.lines().collect(), actual.lines().collect());
+    let mut out = Vec::new();
+    let mut differing = 0;
+    for i in 0..exp.len().max(act.len()) {
+        let (e, a) = (exp.get(i), act.get(i));
+        if e == a {
+            continue;
+        }
+        differing += 1;
+        if differing <= MAX_REPORTED {
+            out.push(format!("line {}:", i + 1));
+            out.extend(e.map(|l| format!("  - {l}")));
+            out.extend(a.map(|l| format!("  + {l}")));
+        }
+    }
+    if differing > MAX_REPORTED {
+        out.push(format!(
+            "… and {} more differing lines",
+            differing - MAX_REPORTED
+        ));
+    }
+    out.push(format!(
+        "{differing} of {} lines differ (reference {} lines, actual {})",
+        exp.len().max(act.len()),
+        exp.len(),
+        act.len()
+    ));
+    out.join("\n")
+}
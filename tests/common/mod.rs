@@ -0,0 +1,66 @@
+//! Helpers shared by integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+pub mod golden;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Serve `pages` pages with one observation each for any data request, on a local port.
+/// Returns the base URL and a counter of requests served.
+pub fn serve_pages(pages: u32) -> (String, Arc<AtomicUsize>) {
+    serve(move |path| {
+        let indicator = path
+            .split('/')
+            .nth(4)
+            .unwrap_or("")
+            .split('?')
+            .next()
+            .unwrap();
+        let page: u32 = path
+            .rsplit_once("page=")
+            .and_then(|(_, p)| p.parse().ok())
+            .unwrap_or(1);
+        format!(
+            r#"[{{"page":{page},"pages":{pages},"per_page":"1","total":{pages}}},[{{
+                "indicator":{{"id":"{indicator}","value":"Test"}},
+                "country":{{"id":"DE","value":"Germany"}},
+                "countryiso3code":"DEU","date":"{year}","value":1.0,
+                "unit":"people","obs_status":null,"decimal":0}}]]"#,
+            year = 2000 + page
+        )
+    })
+}
+
+/// Answer every request with the JSON body `respond(path)` returns, on a local port.
+/// Returns the base URL and a counter of requests served.
+pub fn serve(respond: impl Fn(&str) -> String + Send + 'static) -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let served = hits.clone();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            served.fetch_add(1, Ordering::SeqCst);
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let path = request_line.split_whitespace().nth(1).unwrap_or("");
+            let body = respond(path);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+    (format!("http://{addr}"), hits)
+}
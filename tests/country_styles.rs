@@ -173,4 +173,78 @@ mod tests {
             "Identical inputs should produce identical SVG output"
         );
     }
+
+    #[test]
+    fn test_country_styles_render_dashed_lines() {
+        // "Population" is assigned a dashed pattern; with styles on, its single line is
+        // cut into many short path segments.
+        let data: Vec<DataPoint> = (2000..2020)
+            .map(|year| DataPoint {
+                country_iso3: "USA".to_string(),
+                country_name: "United States".to_string(),
+                country_id: "US".to_string(),
+                indicator_id: "Population".to_string(),
+                indicator_name: "Population".to_string(),
+                year,
+                value: Some(year as f64),
+                unit: None,
+                obs_status: None,
+                decimal: None,
+            })
+            .collect();
+
+        let count_polylines = |styles: bool| {
+            let path = NamedTempFile::with_suffix(".svg").unwrap().into_temp_path();
+            wbi_rs::viz::plot_chart(
+                &data,
+                &path,
+                800,
+                600,
+                "en",
+                LegendMode::Right,
+                "Dash Test",
+                PlotKind::Line,
+                0.3,
+                Some(styles),
+            )
+            .unwrap();
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .matches("<polyline")
+                .count()
+        };
+
+        assert!(
+            count_polylines(true) > count_polylines(false) + 10,
+            "dashed series should be drawn as many short segments"
+        );
+    }
+
+    #[test]
+    fn test_indicator_style_mode_uses_assigned_shades() {
+        use wbi_rs::viz::style::{SeriesKey, assign_indicator_styles};
+        use wbi_rs::viz::{PlotOptions, StyleMode};
+
+        let data = create_test_data();
+        let path = NamedTempFile::with_suffix(".svg").unwrap().into_temp_path();
+        let opts = PlotOptions {
+            kind: PlotKind::LinePoints,
+            style_mode: StyleMode::Indicator,
+            ..Default::default()
+        };
+        wbi_rs::viz::plot_with_options(&data, &path, &opts).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap().to_uppercase();
+
+        let keys: Vec<SeriesKey> = data
+            .iter()
+            .map(|p| SeriesKey::new(p.country_iso3.clone(), p.indicator_id.clone()))
+            .collect();
+        for (key, style) in assign_indicator_styles(&keys, 255) {
+            let hex = format!(
+                "#{:02X}{:02X}{:02X}",
+                style.shade.r, style.shade.g, style.shade.b
+            );
+            assert!(svg.contains(&hex), "{key:?} should be drawn in {hex}");
+        }
+    }
 }
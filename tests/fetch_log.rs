@@ -0,0 +1,72 @@
+mod common;
+
+use wbi_rs::cache::HttpCache;
+use wbi_rs::fetch_log::FetchLog;
+use wbi_rs::{Client, DateSpec};
+
+#[test]
+fn every_call_is_logged_with_status_rows_and_cache_use() {
+    let (base_url, _) = common::serve_pages(2);
+    let dir = tempfile::tempdir().unwrap();
+    let log = FetchLog::new(dir.path().join("logs").join(FetchLog::FILE_NAME));
+    let fetch = || {
+        let mut client = Client::default()
+            .with_cache(HttpCache::new(dir.path().join("http")))
+            .with_fetch_log(log.clone());
+        client.base_url = base_url.clone();
+        client
+            .fetch(
+                &["DEU".into()],
+                &["A.B".into()],
+                Some(DateSpec::Year(2001)),
+                None,
+            )
+            .unwrap()
+    };
+
+    fetch();
+    fetch();
+    let records = log.read().unwrap();
+    assert_eq!(records.len(), 4);
+    assert!(records[0].url.starts_with(&base_url));
+    assert!(records[1].url.ends_with("page=2"));
+    for (i, record) in records.iter().enumerate() {
+        assert_eq!(record.rows, Some(1));
+        assert!(record.ok());
+        assert_eq!(
+            record.cached,
+            i >= 2,
+            "second fetch is served from the cache"
+        );
+        assert_eq!(record.status, (i < 2).then_some(200));
+    }
+    assert!(records.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+}
+
+#[test]
+fn failed_calls_are_logged_and_malformed_logs_are_reported() {
+    let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", closed.local_addr().unwrap());
+    drop(closed);
+    let dir = tempfile::tempdir().unwrap();
+    let log = FetchLog::new(dir.path().join(FetchLog::FILE_NAME));
+    let mut client = Client::default().with_fetch_log(log.clone());
+    client.base_url = base_url;
+    assert!(client.fetch_indicator_units(&["A.B".into()]).is_err());
+
+    let records = log.read().unwrap();
+    assert_eq!(records.len(), 1);
+    assert!(!records[0].ok() && !records[0].cached);
+    assert_eq!((records[0].status, records[0].rows), (None, None));
+    assert!(records[0].error.is_some());
+
+    std::fs::write(log.path(), "{not json}\n").unwrap();
+    let err = log.read().unwrap_err();
+    assert!(err.to_string().ends_with(":1: invalid record"), "{err}");
+    assert!(
+        FetchLog::new(dir.path().join("missing"))
+            .read()
+            .unwrap()
+            .is_empty()
+    );
+}
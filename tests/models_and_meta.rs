@@ -1,4 +1,4 @@
-use wbi_rs::models::{DataPoint, Entry, IndicatorMeta, Meta};
+use wbi_rs::models::{DataPoint, DateSpec, Entry, IndicatorMeta, Meta, Unit, UnitKind};
 
 #[test]
 fn meta_per_page_accepts_string_or_number() {
@@ -90,3 +90,68 @@ fn fetch_indicator_units_returns_empty_for_empty_input() {
     let result = client.fetch_indicator_units(&[]).unwrap();
     assert!(result.is_empty());
 }
+
+#[test]
+fn date_specs_parse_open_relative_and_mrv_forms() {
+    let parse = |s: &str| s.parse::<DateSpec>().unwrap();
+    assert_eq!(parse("2020"), DateSpec::Year(2020));
+    assert_eq!(
+        parse("2010:2020"),
+        DateSpec::Range {
+            start: 2010,
+            end: 2020
+        }
+    );
+    assert_eq!(parse("2015:"), DateSpec::From(2015));
+    assert_eq!(parse(":2020"), DateSpec::Until(2020));
+    assert_eq!(parse("last:10"), DateSpec::Last(10));
+    assert_eq!(parse("MRV:5"), DateSpec::MostRecent(5));
+
+    for bad in ["20x0", "2020:2010", ":", "last:0", "mrv:five"] {
+        assert!(bad.parse::<DateSpec>().is_err(), "{bad}");
+    }
+    let err = "soon".parse::<DateSpec>().unwrap_err().to_string();
+    assert!(err.contains("last:N"), "{err}");
+}
+
+#[test]
+fn relative_date_specs_resolve_against_the_current_year() {
+    let range = |start, end| DateSpec::Range { start, end };
+    assert_eq!(DateSpec::From(2015).resolve(2025), range(2015, 2025));
+    assert_eq!(DateSpec::Until(2020).resolve(2025), range(1960, 2020));
+    assert_eq!(DateSpec::Last(10).resolve(2025), range(2016, 2025));
+    assert_eq!(
+        DateSpec::MostRecent(5).resolve(2025),
+        DateSpec::MostRecent(5)
+    );
+    assert_eq!(DateSpec::MostRecent(5).query_key(), "mrv");
+    assert_eq!(DateSpec::Year(2020).query_key(), "date");
+}
+
+#[test]
+fn unit_parse_sorts_world_bank_units_into_kinds() {
+    let cases = [
+        ("current US$", UnitKind::Currency),
+        ("constant 2015 US$", UnitKind::Currency),
+        ("current LCU", UnitKind::Currency),
+        ("PPP (current international $)", UnitKind::Currency),
+        ("% of GDP", UnitKind::Percent),
+        ("annual %", UnitKind::Percent),
+        ("% of population ages 15+", UnitKind::Percent),
+        ("people", UnitKind::Count),
+        ("Number of procedures", UnitKind::Count),
+        ("2010 = 100", UnitKind::Index),
+        ("Index (0-100)", UnitKind::Index),
+        ("per 1,000 people", UnitKind::Other),
+        ("years", UnitKind::Other),
+        ("", UnitKind::Other),
+    ];
+    for (raw, kind) in cases {
+        assert_eq!(Unit::parse(raw).kind, kind, "{raw:?}");
+    }
+    let u = Unit::parse("  current US$ ");
+    assert_eq!(u.raw, "current US$");
+    assert_eq!(u.to_string(), "current US$");
+    assert!(u.is_scalable());
+    assert!(!Unit::parse("2010 = 100").is_scalable());
+}
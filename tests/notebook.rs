@@ -0,0 +1,41 @@
+#![cfg(feature = "evcxr")]
+
+use wbi_rs::models::{DataPoint, Dataset};
+use wbi_rs::notebook;
+
+fn rows(n: i32) -> Dataset {
+    (0..n)
+        .map(|i| DataPoint {
+            indicator_id: "SP.POP.TOTL".into(),
+            indicator_name: "Population, total".into(),
+            country_id: "DE".into(),
+            country_name: "Germany <DE>".into(),
+            country_iso3: "DEU".into(),
+            year: 1960 + i,
+            value: Some(1.5),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect()
+}
+
+#[test]
+fn dataset_html_escapes_and_truncates() {
+    let html = rows(3).to_html();
+    assert!(html.contains(
+        "<td>Germany &lt;DE&gt;</td><td>DEU</td><td>SP.POP.TOTL</td><td>1960</td><td>1.5</td>"
+    ));
+    assert!(!html.contains("more rows"));
+
+    let html = rows(60).to_html();
+    assert_eq!(html.matches("<tr><td>").count(), 50);
+    assert!(html.contains("… 10 more rows"));
+}
+
+#[test]
+fn chart_is_inline_svg() {
+    let chart = notebook::chart(&rows(5), &Default::default()).unwrap();
+    assert!(chart.svg().starts_with("<svg"));
+    assert!(notebook::chart(&Dataset::default(), &Default::default()).is_err());
+}
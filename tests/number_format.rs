@@ -0,0 +1,46 @@
+use wbi_rs::util::format::{
+    format_exact, format_fixed, format_float, format_opt, format_tick, map_locale,
+};
+
+#[test]
+fn float_is_grouped_and_trimmed_per_locale() {
+    let (english, en_sep) = map_locale("en");
+    let (german, de_sep) = map_locale("DE_de");
+    assert_eq!(format_float(1_234_567.25, english, en_sep), "1,234,567.25");
+    assert_eq!(format_float(1_234_567.25, german, de_sep), "1.234.567,25");
+    assert_eq!(format_float(-0.5, german, de_sep), "-0,5");
+    assert_eq!(format_float(100.0, english, en_sep), "100");
+    assert_eq!(format_float(-0.00001, english, en_sep), "0");
+    // Beyond i64: grouping must not collapse to zero.
+    assert_eq!(
+        format_float(1e19, english, en_sep),
+        "10,000,000,000,000,000,000"
+    );
+}
+
+#[test]
+fn missing_values_and_ticks() {
+    let (loc, sep) = map_locale("fr");
+    assert_eq!(format_opt(None, loc, sep), "NA");
+    assert_eq!(format_opt(Some(f64::NAN), loc, sep), "NA");
+    assert_eq!(format_fixed(2.0, 2, loc, sep), "2,00");
+    let (english, en_sep) = map_locale("unknown");
+    assert_eq!(format_tick(12_345.678, english, en_sep), "12,346");
+    assert_eq!(format_tick(12.345, english, en_sep), "12.3");
+    assert_eq!(format_tick(1.2345, english, en_sep), "1.23");
+}
+
+#[test]
+fn exact_values_keep_every_digit() {
+    let (german, de_sep) = map_locale("de");
+    assert_eq!(
+        format_exact(1234.56789, german, de_sep, false),
+        "1234,56789"
+    );
+    assert_eq!(
+        format_exact(1234.56789, german, de_sep, true),
+        "1.234,56789"
+    );
+    assert_eq!(format_exact(-1e6, german, de_sep, true), "-1.000.000");
+    assert_eq!(format_exact(f64::NAN, german, de_sep, true), "NaN");
+}
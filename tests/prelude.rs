@@ -0,0 +1,26 @@
+use wbi_rs::prelude::*;
+
+#[test]
+fn prelude_covers_fetch_stats_and_plot_types() {
+    let options = PlotOptions {
+        kind: PlotKind::Area,
+        legend: LegendMode::Right,
+        transform: PlotTransform::None,
+        ..Default::default()
+    };
+    assert!(options.validate().is_ok());
+    let _: fn(&Client) -> &str = |c| c.base_url.as_str();
+    let data = Dataset::from(Vec::<DataPoint>::new());
+    let summaries: Vec<Summary> = wbi_rs::stats::summary_by(&data, GroupBy::Country);
+    assert!(summaries.is_empty());
+    assert_eq!(DateSpec::Year(2020).to_query_param(), "2020");
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_paths_still_resolve() {
+    use wbi_rs::viz::style::DrawStyle;
+    let style: wbi_rs::viz_style::SeriesStyle = DrawStyle::for_series("DEU", "SP.POP.TOTL");
+    let color = wbi_rs::viz_plotters_adapter::rgb_color(&style);
+    assert_eq!(color, wbi_rs::viz::plotters_adapter::rgb_color(&style));
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use wbi_rs::models::{DataPoint, DateSpec};
+use wbi_rs::provider::{DataProvider, SdmxCsvProvider};
+use wbi_rs::{Client, viz};
+
+/// SDMX-CSV 2.0 with labels in the cells, as from the OECD Data Explorer.
+const OECD: &str = "\
+STRUCTURE,STRUCTURE_ID,ACTION,REF_AREA: Reference area,FREQ: Frequency,MEASURE: Measure,UNIT_MEASURE: Unit of measure,TIME_PERIOD: Time period,OBS_VALUE,UNIT_MULT: Unit multiplier,OBS_STATUS: Observation status,DECIMALS: Decimals
+dataflow,OECD.SDD.NAD:DSD_NAMAIN1@DF_QNA(1.0),I,DEU: Germany,A: Annual,B1GQ: Gross domestic product,USD_PPP: US dollars PPP,2021,4.85,12: Trillions,A: Normal value,2
+dataflow,OECD.SDD.NAD:DSD_NAMAIN1@DF_QNA(1.0),I,DEU: Germany,A: Annual,B1GQ: Gross domestic product,USD_PPP: US dollars PPP,2022,5.31,12: Trillions,E: Estimated value,2
+dataflow,OECD.SDD.NAD:DSD_NAMAIN1@DF_QNA(1.0),I,FRA: France,A: Annual,B1GQ: Gross domestic product,USD_PPP: US dollars PPP,2022,3.77,12: Trillions,A: Normal value,2
+dataflow,OECD.SDD.NAD:DSD_NAMAIN1@DF_QNA(1.0),I,FRA: France,Q: Quarterly,B1GQ: Gross domestic product,USD_PPP: US dollars PPP,2022-Q1,0.93,12: Trillions,A: Normal value,2
+";
+
+#[test]
+fn oecd_rows_become_annual_data_points_in_base_units() {
+    let oecd = SdmxCsvProvider::from_reader(OECD.as_bytes()).unwrap();
+    assert_eq!(oecd.name(), "OECD.SDD.NAD");
+    // The quarterly row is skipped.
+    assert_eq!(oecd.points().len(), 3);
+
+    let p = &oecd.points()[1];
+    assert_eq!(p.indicator_id, "DF_QNA.B1GQ.USD_PPP");
+    assert_eq!(p.indicator_name, "Gross domestic product, US dollars PPP");
+    assert_eq!(
+        (p.country_id.as_str(), p.country_iso3.as_str()),
+        ("DE", "DEU")
+    );
+    assert_eq!(p.country_name, "Germany");
+    assert_eq!(p.year, 2022);
+    assert!((p.value.unwrap() - 5.31e12).abs() < 1.0);
+    assert_eq!(p.unit.as_deref(), Some("US dollars PPP"));
+    assert_eq!(p.obs_status.as_deref(), Some("E"));
+    assert_eq!(p.decimal, Some(2));
+
+    let meta = oecd.metadata("df_qna.b1gq.usd_ppp").unwrap().unwrap();
+    assert_eq!(meta.name, "Gross domestic product, US dollars PPP");
+    assert_eq!(oecd.search("domestic GROSS").unwrap().len(), 1);
+    assert!(oecd.search("population").unwrap().is_empty());
+}
+
+#[test]
+fn fetch_filters_countries_indicators_and_dates() {
+    let oecd = SdmxCsvProvider::from_reader(OECD.as_bytes()).unwrap();
+    let id = "DF_QNA.B1GQ.USD_PPP".to_string();
+    let de = oecd
+        .fetch(&["DE".into()], std::slice::from_ref(&id), None)
+        .unwrap();
+    assert_eq!(de.len(), 2);
+    let in_2022 = oecd.fetch(&[], &[], Some(DateSpec::Year(2022))).unwrap();
+    assert_eq!(in_2022.len(), 2);
+    let latest = oecd
+        .fetch(&["DEU".into()], &[id], Some(DateSpec::MostRecent(1)))
+        .unwrap();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0].year, 2022);
+    assert!(oecd.fetch(&["USA".into()], &[], None).unwrap().is_empty());
+}
+
+#[test]
+fn label_columns_and_lowercase_eurostat_headers() {
+    // OECD "labels: both" with a label column after each code column.
+    let labelled = "\
+STRUCTURE_ID,REF_AREA,Reference area,MEASURE,Measure,TIME_PERIOD,OBS_VALUE
+IMF:WEO(1.0),XKX,Kosovo,NGDP_RPCH,Real GDP growth,2023,3.3
+";
+    let imf = SdmxCsvProvider::from_reader(labelled.as_bytes()).unwrap();
+    let p = &imf.points()[0];
+    assert_eq!(imf.name(), "IMF");
+    assert_eq!(p.indicator_id, "WEO.NGDP_RPCH");
+    assert_eq!(p.indicator_name, "Real GDP growth");
+    assert_eq!(
+        (p.country_iso3.as_str(), p.country_name.as_str()),
+        ("XKX", "Kosovo")
+    );
+
+    let eurostat = "\
+DATAFLOW,LAST UPDATE,freq,unit,na_item,geo,TIME_PERIOD,OBS_VALUE,OBS_FLAG
+ESTAT:NAMA_10_GDP(1.0),15/04/25 23:00:00,A,CP_MEUR,B1GQ,AT,2023,477000,p
+ESTAT:NAMA_10_GDP(1.0),15/04/25 23:00:00,A,CP_MEUR,B1GQ,EU27_2020,2023,,
+";
+    let estat = SdmxCsvProvider::from_reader(eurostat.as_bytes()).unwrap();
+    let at = &estat.points()[0];
+    assert_eq!(at.indicator_id, "NAMA_10_GDP.CP_MEUR.B1GQ");
+    assert_eq!(at.country_iso3, "AUT");
+    assert_eq!(at.obs_status.as_deref(), Some("p"));
+    // Unknown areas keep their code; empty values are missing.
+    let eu = &estat.points()[1];
+    assert_eq!((eu.country_iso3.as_str(), eu.value), ("EU27_2020", None));
+
+    let err = SdmxCsvProvider::from_reader("REF_AREA,OBS_VALUE\nDEU,1\n".as_bytes()).unwrap_err();
+    assert!(err.to_string().contains("TIME_PERIOD"));
+}
+
+#[test]
+fn client_is_a_data_provider() {
+    let mut client = Client::default();
+    client.base_url = common::serve(|path| {
+        if path.starts_with("/source/2/indicator") {
+            r#"[{"page":1,"pages":1,"per_page":"20000","total":3},[
+                {"id":"NY.GDP.MKTP.CD","name":"GDP (current US$)","unit":""},
+                {"id":"NY.GDP.PCAP.CD","name":"GDP per capita (current US$)","unit":""},
+                {"id":"SP.POP.TOTL","name":"Population, total","unit":""}]]"#
+                .into()
+        } else {
+            r#"[{"page":1,"pages":1,"per_page":"50","total":1},[{"id":"SP.POP.TOTL","name":"Population, total","unit":""}]]"#.into()
+        }
+    })
+    .0;
+    let provider: &dyn DataProvider = &client;
+    assert_eq!(provider.name(), "World Bank");
+    let hits: Vec<String> = provider
+        .search("gdp capita")
+        .unwrap()
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    assert_eq!(hits, ["NY.GDP.PCAP.CD"]);
+    let meta = provider.metadata("SP.POP.TOTL").unwrap().unwrap();
+    assert_eq!(meta.name, "Population, total");
+}
+
+#[test]
+fn mixed_source_rows_plot_together() {
+    let (base_url, _) = common::serve_pages(1);
+    let mut client = Client::default();
+    client.base_url = base_url;
+    let oecd = SdmxCsvProvider::from_reader(OECD.as_bytes()).unwrap();
+    let providers: [&dyn DataProvider; 2] = [&client, &oecd];
+    let indicators = [vec!["SP.POP.TOTL".to_string()], vec![]];
+    let rows: Vec<DataPoint> = providers
+        .iter()
+        .zip(indicators)
+        .flat_map(|(p, ind)| p.fetch(&["DEU".into()], &ind, None).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 3);
+    let svg = viz::render_svg_string(&rows, &viz::PlotOptions::default()).unwrap();
+    assert!(svg.contains("Gross domestic product"));
+}
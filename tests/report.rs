@@ -0,0 +1,188 @@
+mod common;
+
+use wbi_rs::Client;
+use wbi_rs::models::{DataPoint, IndicatorMeta};
+use wbi_rs::report::{ChartEmbed, ReportFormat, render_report};
+
+fn dp(iso3: &str, year: i32, v: Option<f64>) -> DataPoint {
+    DataPoint {
+        indicator_id: "SP.POP.TOTL".into(),
+        indicator_name: "Population, total".into(),
+        country_id: iso3[..2].into(),
+        country_name: iso3.into(),
+        country_iso3: iso3.into(),
+        year,
+        value: v,
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    }
+}
+
+fn rows() -> Vec<DataPoint> {
+    vec![
+        dp("DEU", 2019, Some(83.0)),
+        dp("DEU", 2020, Some(83.5)),
+        dp("FRA", 2019, None),
+        dp("FRA", 2020, Some(67.0)),
+    ]
+}
+
+#[test]
+fn markdown_report_has_chart_stats_coverage_and_source() {
+    let md = render_report(
+        &rows(),
+        "Population <2020>",
+        Some(ChartEmbed::Link("report.svg")),
+        ReportFormat::Markdown,
+        None,
+        &[],
+        &[],
+    )
+    .unwrap();
+    assert!(md.starts_with("# Population <2020>\n"));
+    assert!(md.contains("DEU, FRA · SP.POP.TOTL · 2019–2020"));
+    assert!(md.contains("![Population <2020>](report.svg)"));
+    assert!(md.contains("| DEU | SP.POP.TOTL | 2 | 0 | 83 | 83.5 | 83.25 | 83.25 |"));
+    assert!(md.contains("| FRA | SP.POP.TOTL | 1 of 2 | 2020 | 2020 |"));
+    assert!(md.contains("World Bank"));
+    assert!(md.contains("- SP.POP.TOTL: Population, total"));
+    assert!(!md.contains("{{"));
+}
+
+#[test]
+fn html_report_escapes_text_and_inlines_svg() {
+    let html = render_report(
+        &rows(),
+        "Population <2020>",
+        Some(ChartEmbed::InlineSvg("<svg id=\"c\"></svg>")),
+        ReportFormat::Html,
+        None,
+        &[],
+        &[],
+    )
+    .unwrap();
+    assert!(html.contains("<h1>Population &lt;2020&gt;</h1>"));
+    assert!(html.contains("<svg id=\"c\"></svg>"));
+    assert!(html.contains("<td class=\"num\">83.25</td>"));
+}
+
+#[test]
+fn user_templates_fill_known_placeholders_and_reject_unknown_ones() {
+    let out = render_report(
+        &rows(),
+        "T",
+        None,
+        ReportFormat::Markdown,
+        Some("{{ title }}: {{years}}"),
+        &[],
+        &[],
+    )
+    .unwrap();
+    assert_eq!(out, "T: 2019–2020");
+
+    let err = render_report(
+        &rows(),
+        "T",
+        None,
+        ReportFormat::Markdown,
+        Some("{{tittle}}"),
+        &[],
+        &[],
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("unknown placeholder '{{tittle}}'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn definitions_are_appended_when_metadata_is_given() {
+    let meta = IndicatorMeta {
+        id: "SP.POP.TOTL".into(),
+        name: "Population, total".into(),
+        unit: None,
+        source_note: Some("Total population counts all residents.".into()),
+        source_organization: Some("UN Population Division <WPP>".into()),
+    };
+    let md = render_report(
+        &rows(),
+        "T",
+        None,
+        ReportFormat::Markdown,
+        None,
+        std::slice::from_ref(&meta),
+        &[],
+    )
+    .unwrap();
+    assert!(md.contains(
+        "## Indicator definitions\n\n**Population, total** (`SP.POP.TOTL`)\n\n\
+         Total population counts all residents.\n\n_Source: UN Population Division <WPP>_\n"
+    ));
+
+    let html = render_report(&rows(), "T", None, ReportFormat::Html, None, &[meta], &[]).unwrap();
+    assert!(html.contains("<dt>Population, total (SP.POP.TOTL)</dt>"));
+    assert!(html.contains("<em>Source: UN Population Division &lt;WPP&gt;</em>"));
+
+    let plain = render_report(&rows(), "T", None, ReportFormat::Markdown, None, &[], &[]).unwrap();
+    assert!(!plain.contains("Indicator definitions"));
+    assert!(plain.ends_with("- SP.POP.TOTL: Population, total\n"));
+}
+
+#[test]
+fn series_country_metadata_fills_the_appendix() {
+    let (base_url, hits) = common::serve(|path| {
+        let metatype = |variable: &str, id: &str, text: &str| {
+            format!(
+                r#"{{"page":1,"pages":1,"per_page":"50","total":1,"source":[{{"id":"2","concept":[{{"id":"x","variable":[{{"id":"{variable}","metatype":[{{"id":"{id}","value":"{text}"}}]}}]}}]}}]}}"#
+            )
+        };
+        if path.starts_with("/sources/2/series/SP.POP.TOTL/metadata") {
+            metatype(
+                "SP.POP.TOTL",
+                "Statisticalconceptandmethodology",
+                "Counts all residents.",
+            )
+        } else if path.starts_with("/sources/2/country/DEU/series/SP.POP.TOTL/metadata") {
+            metatype(
+                "DEU~SP.POP.TOTL",
+                "Country-Series",
+                "Data sources : <Destatis>",
+            )
+        } else {
+            r#"[{"message":[{"id":"120","key":"Invalid value","value":"The provided parameter value is not valid"}]}]"#.into()
+        }
+    });
+    let mut client = Client::default();
+    client.base_url = base_url;
+    let deu = client
+        .fetch_series_country_metadata("SP.POP.TOTL", "DEU")
+        .unwrap();
+    assert_eq!(deu.methodology.as_deref(), Some("Counts all residents."));
+    assert_eq!(deu.notes, ["Data sources : <Destatis>"]);
+    let fra = client
+        .fetch_series_country_metadata("SP.POP.TOTL", "FRA")
+        .unwrap();
+    assert!(fra.notes.is_empty());
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 4);
+
+    let appendix = [deu, fra];
+    let md = render_report(
+        &rows(),
+        "T",
+        None,
+        ReportFormat::Markdown,
+        None,
+        &[],
+        &appendix,
+    )
+    .unwrap();
+    assert!(md.ends_with(
+        "## Appendix: methodology and country notes\n\n### Population, total (`SP.POP.TOTL`)\n\n\
+         Counts all residents.\n\n**DEU**\n\n- Data sources : <Destatis>\n"
+    ));
+    let html = render_report(&rows(), "T", None, ReportFormat::Html, None, &[], &appendix).unwrap();
+    assert!(html.contains("<li>Data sources : &lt;Destatis&gt;</li>"));
+    assert!(!html.contains("<strong>FRA</strong>"));
+}
@@ -0,0 +1,185 @@
+use wbi_rs::diff::{diff, merge};
+use wbi_rs::models::{ConflictPolicy, DataPoint, align_years};
+
+fn dp(c_iso3: &str, year: i32, v: Option<f64>) -> DataPoint {
+    DataPoint {
+        indicator_id: "IND".into(),
+        indicator_name: "Indicator".into(),
+        country_id: "XX".into(),
+        country_name: "Xland".into(),
+        country_iso3: c_iso3.into(),
+        year,
+        value: v,
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    }
+}
+
+#[test]
+fn diff_reports_added_removed_and_revised_values() {
+    let old = vec![
+        dp("AAA", 2019, Some(1.0)),
+        dp("AAA", 2020, Some(2.0)),
+        dp("BBB", 2020, None),
+        dp("CCC", 2020, Some(5.0)),
+    ];
+    let mut relabelled = dp("CCC", 2020, Some(5.0));
+    relabelled.country_name = "Renamed".into();
+    let new = vec![
+        dp("AAA", 2019, Some(1.0)),
+        dp("AAA", 2020, Some(2.5)),
+        dp("BBB", 2020, Some(7.0)),
+        dp("AAA", 2021, Some(3.0)),
+        relabelled,
+    ];
+
+    let d = diff(&old, &new);
+    assert!(d.removed.is_empty());
+    assert_eq!(d.added, [dp("AAA", 2021, Some(3.0))]);
+    let changed: Vec<(&str, i32, Option<f64>, Option<f64>)> = d
+        .changed
+        .iter()
+        .map(|c| {
+            (
+                c.new.country_iso3.as_str(),
+                c.new.year,
+                c.old.value,
+                c.new.value,
+            )
+        })
+        .collect();
+    assert_eq!(
+        changed,
+        [
+            ("AAA", 2020, Some(2.0), Some(2.5)),
+            ("BBB", 2020, None, Some(7.0))
+        ]
+    );
+
+    let back = diff(&new, &old);
+    assert_eq!(back.removed, [dp("AAA", 2021, Some(3.0))]);
+    assert!(diff(&old, &old).is_empty());
+}
+
+#[test]
+fn merge_overlays_recent_rows_on_a_snapshot() {
+    let saved = vec![
+        dp("AAA", 2019, Some(1.0)),
+        dp("AAA", 2020, None),
+        dp("BBB", 2020, Some(4.0)),
+    ];
+    let recent = vec![dp("AAA", 2021, Some(3.0)), dp("AAA", 2020, Some(2.0))];
+
+    let merged = merge(&saved, &recent);
+    let values: Vec<(&str, i32, Option<f64>)> = merged
+        .iter()
+        .map(|p| (p.country_iso3.as_str(), p.year, p.value))
+        .collect();
+    assert_eq!(
+        values,
+        [
+            ("AAA", 2019, Some(1.0)),
+            ("AAA", 2020, Some(2.0)),
+            ("AAA", 2021, Some(3.0)),
+            ("BBB", 2020, Some(4.0)),
+        ]
+    );
+}
+
+#[test]
+fn merge_combines_datasets_and_reports_conflicts() {
+    let run_a = [dp("AAA", 2019, Some(1.0)), dp("AAA", 2020, Some(2.0))];
+    let run_b = [
+        dp("AAA", 2020, Some(2.0)),
+        dp("AAA", 2021, None),
+        dp("BBB", 2020, Some(4.0)),
+    ];
+    let mut renamed = dp("AAA", 2021, Some(3.0));
+    renamed.country_name = "Other provider".into();
+    let run_c = [dp("AAA", 2020, Some(2.5)), renamed];
+    let inputs = [&run_a[..], &run_b[..], &run_c[..]];
+
+    let last = wbi_rs::models::merge(inputs, ConflictPolicy::KeepLast).unwrap();
+    let values: Vec<(&str, i32, Option<f64>)> = last
+        .dataset
+        .iter()
+        .map(|p| (p.country_iso3.as_str(), p.year, p.value))
+        .collect();
+    assert_eq!(
+        values,
+        [
+            ("AAA", 2019, Some(1.0)),
+            ("AAA", 2020, Some(2.5)),
+            ("AAA", 2021, Some(3.0)),
+            ("BBB", 2020, Some(4.0)),
+        ]
+    );
+    // The equal 2020 rows of runs A and B collapse into one dropped row; a value against a
+    // missing one conflicts too.
+    let conflicts: Vec<(i32, Option<f64>, Vec<Option<f64>>)> = last
+        .conflicts
+        .iter()
+        .map(|c| {
+            (
+                c.kept.year,
+                c.kept.value,
+                c.dropped.iter().map(|d| d.value).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        conflicts,
+        [
+            (2020, Some(2.5), vec![Some(2.0)]),
+            (2021, Some(3.0), vec![None])
+        ]
+    );
+    assert_eq!(last.conflicts[1].kept.country_name, "Other provider");
+
+    let first = wbi_rs::models::merge(inputs, ConflictPolicy::KeepFirst).unwrap();
+    assert_eq!(first.dataset[1].value, Some(2.0));
+    assert_eq!(first.conflicts[0].dropped.len(), 1);
+    let err = wbi_rs::models::merge(inputs, ConflictPolicy::Error).unwrap_err();
+    assert!(err.to_string().contains("IND AAA 2020: 2 and 2.5"), "{err}");
+    assert!(
+        wbi_rs::models::merge([&run_a[..], &run_a[..]], ConflictPolicy::Error)
+            .unwrap()
+            .conflicts
+            .is_empty()
+    );
+}
+
+#[test]
+fn align_years_fills_the_years_a_series_lacks() {
+    let points = vec![
+        dp("BBB", 2021, Some(4.0)),
+        dp("AAA", 2019, Some(1.0)),
+        dp("AAA", 2021, Some(3.0)),
+    ];
+    let aligned = align_years(&points);
+    let rows: Vec<(&str, i32, Option<f64>)> = aligned
+        .iter()
+        .map(|p| (p.country_iso3.as_str(), p.year, p.value))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("AAA", 2019, Some(1.0)),
+            ("AAA", 2021, Some(3.0)),
+            ("BBB", 2019, None),
+            ("BBB", 2021, Some(4.0)),
+        ]
+    );
+    assert_eq!(aligned[2].country_name, "Xland");
+
+    // A snapshot listing the missing year as an empty row now matches one leaving it out.
+    let with_empty_row = vec![
+        dp("AAA", 2019, Some(1.0)),
+        dp("AAA", 2021, Some(3.0)),
+        dp("BBB", 2019, None),
+        dp("BBB", 2021, Some(4.0)),
+    ];
+    assert!(!diff(&points, &with_empty_row).is_empty());
+    assert!(diff(&aligned, &align_years(&with_empty_row)).is_empty());
+}
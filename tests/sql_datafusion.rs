@@ -0,0 +1,98 @@
+#![cfg(feature = "datafusion")]
+
+use std::sync::Arc;
+
+use arrow_array::{Array, Float64Array, RecordBatch, StringArray};
+use datafusion::prelude::SessionContext;
+use wbi_rs::models::{DataPoint, Dataset};
+use wbi_rs::sql::WbiTable;
+
+const QUERY: &str = "SELECT country, value FROM wbi WHERE indicator='SP.POP.TOTL' AND year=2020 \
+                     ORDER BY country";
+
+fn point(indicator: &str, iso3: &str, year: i32, value: Option<f64>) -> DataPoint {
+    DataPoint {
+        indicator_id: indicator.into(),
+        indicator_name: indicator.into(),
+        country_id: iso3[..2].into(),
+        country_name: iso3.into(),
+        country_iso3: iso3.into(),
+        year,
+        value,
+        unit: None,
+        obs_status: None,
+        decimal: Some(0),
+    }
+}
+
+/// Run `sql` against `table` registered as `wbi`.
+fn query(table: WbiTable, sql: &str) -> Vec<RecordBatch> {
+    let ctx = SessionContext::new();
+    ctx.register_table("wbi", Arc::new(table)).unwrap();
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(async { ctx.sql(sql).await?.collect().await })
+        .unwrap()
+}
+
+/// (country, value) rows of a result of [`QUERY`].
+fn rows(batches: &[RecordBatch]) -> Vec<(String, Option<f64>)> {
+    let mut out = Vec::new();
+    for b in batches {
+        let country = b.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+        let value = b.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        for i in 0..b.num_rows() {
+            let v = (!value.is_null(i)).then(|| value.value(i));
+            out.push((country.value(i).to_string(), v));
+        }
+    }
+    out
+}
+
+#[test]
+fn sql_selects_from_a_dataset() {
+    let data: Dataset = vec![
+        point("SP.POP.TOTL", "DEU", 2020, Some(83.2e6)),
+        point("SP.POP.TOTL", "DEU", 2021, Some(83.1e6)),
+        point("SP.POP.TOTL", "FRA", 2020, None),
+        point("NY.GDP.MKTP.CD", "DEU", 2020, Some(3.9e12)),
+    ]
+    .into();
+    let got = rows(&query(WbiTable::from(data), QUERY));
+    assert_eq!(
+        got,
+        [("DEU".to_string(), Some(83.2e6)), ("FRA".to_string(), None)]
+    );
+
+    let schema = WbiTable::table_schema();
+    assert!(schema.field_with_name("indicator_name").is_ok());
+    assert!(schema.field_with_name("country_iso3").is_err());
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sql_selects_the_latest_values_from_the_sqlite_store() {
+    use chrono::{TimeZone, Utc};
+    let day = |d: u32| Utc.with_ymd_and_hms(2025, 3, d, 12, 0, 0).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("wbi.sqlite");
+    let first = [point("SP.POP.TOTL", "DEU", 2020, Some(83.0e6))];
+    wbi_rs::storage::save_sqlite(&first, &db, day(1)).unwrap();
+    let revised = [
+        point("SP.POP.TOTL", "DEU", 2020, Some(83.2e6)),
+        point("SP.POP.TOTL", "FRA", 2020, Some(67.4e6)),
+    ];
+    wbi_rs::storage::save_sqlite(&revised, &db, day(3)).unwrap();
+
+    let latest = rows(&query(WbiTable::sqlite(&db, None), QUERY));
+    assert_eq!(
+        latest,
+        [
+            ("DEU".to_string(), Some(83.2e6)),
+            ("FRA".to_string(), Some(67.4e6))
+        ]
+    );
+    let then = rows(&query(WbiTable::sqlite(&db, Some(day(2))), QUERY));
+    assert_eq!(then, [("DEU".to_string(), Some(83.0e6))]);
+}
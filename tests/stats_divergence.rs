@@ -0,0 +1,119 @@
+use wbi_rs::models::DataPoint;
+use wbi_rs::stats::divergence;
+use wbi_rs::viz::{self, PlotKind, PlotOptions, PlotTransform};
+
+fn dp(iso3: &str, year: i32, v: Option<f64>) -> DataPoint {
+    DataPoint {
+        indicator_id: "NY.GDP.MKTP.KD.ZG".into(),
+        indicator_name: "GDP growth (annual %)".into(),
+        country_id: iso3[..2].into(),
+        country_name: if iso3 == "DEU" { "Germany" } else { "France" }.into(),
+        country_iso3: iso3.into(),
+        year,
+        value: v,
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    }
+}
+
+fn actuals() -> Vec<DataPoint> {
+    vec![
+        dp("DEU", 2018, Some(1.0)),
+        dp("DEU", 2019, Some(2.0)),
+        dp("DEU", 2020, Some(-4.0)),
+        dp("DEU", 2021, None),
+    ]
+}
+
+#[test]
+fn divergence_compares_overlapping_years_only() {
+    let projected = vec![
+        dp("DEU", 2019, Some(1.5)),
+        dp("DEU", 2020, Some(1.0)),
+        // No actual value that year: not compared.
+        dp("DEU", 2021, Some(2.0)),
+        dp("DEU", 2025, Some(1.2)),
+        // A series without actuals still gets a row.
+        dp("FRA", 2020, Some(1.0)),
+    ];
+    let d = divergence(&actuals(), &projected);
+    assert_eq!(d.len(), 2);
+
+    let deu = &d[0];
+    assert_eq!(deu.key.country_iso3, "DEU");
+    assert_eq!(deu.count, 2);
+    // Errors: -0.5 (2019), +5.0 (2020).
+    assert_eq!(deu.mean_error, Some(2.25));
+    assert_eq!(deu.mae, Some(2.75));
+    assert!((deu.rmse.unwrap() - (12.625f64).sqrt()).abs() < 1e-12);
+    assert!((deu.mape.unwrap() - (25.0 + 125.0) / 2.0).abs() < 1e-12);
+    assert_eq!(deu.max_abs_error, Some((2020, 5.0)));
+
+    let fra = &d[1];
+    assert_eq!(fra.key.country_iso3, "FRA");
+    assert_eq!(fra.count, 0);
+    assert_eq!((fra.mae, fra.mape, fra.max_abs_error), (None, None, None));
+}
+
+#[test]
+fn mape_skips_zero_actuals() {
+    let actual = vec![dp("DEU", 2020, Some(0.0)), dp("DEU", 2021, Some(2.0))];
+    let projected = vec![dp("DEU", 2020, Some(1.0)), dp("DEU", 2021, Some(3.0))];
+    let d = divergence(&actual, &projected);
+    assert_eq!(d[0].count, 2);
+    assert_eq!(d[0].mape, Some(50.0));
+}
+
+#[test]
+fn projections_are_drawn_dashed_and_labelled() {
+    let options = PlotOptions {
+        kind: PlotKind::LinePoints,
+        projections: vec![dp("DEU", 2020, Some(0.5)), dp("DEU", 2023, Some(1.5))],
+        deterministic: true,
+        ..Default::default()
+    };
+    let svg = viz::render_svg_string(&actuals(), &options).unwrap();
+    assert!(svg.contains("\nGermany — GDP growth (annual %) (projection)\n"));
+    // The X axis runs to the last projected year.
+    assert!(svg.contains("\n2023\n"));
+
+    let plain = viz::render_svg_string(
+        &actuals(),
+        &PlotOptions {
+            projections: Vec::new(),
+            ..options.clone()
+        },
+    )
+    .unwrap();
+    assert!(!plain.contains("(projection)"));
+    // Dashes are separate path segments, so the overlay adds several lines.
+    assert!(svg.matches("<polyline").count() > plain.matches("<polyline").count() + 2);
+
+    let de = PlotOptions {
+        locale: "de".into(),
+        ..options
+    };
+    let svg = viz::render_svg_string(&actuals(), &de).unwrap();
+    assert!(svg.contains("(Projektion)"));
+}
+
+#[test]
+fn projections_reject_transforms_and_are_ignored_by_other_kinds() {
+    let options = PlotOptions {
+        projections: vec![dp("DEU", 2022, Some(1.0))],
+        transform: PlotTransform::YoYGrowth,
+        ..Default::default()
+    };
+    let err = options.validate().unwrap_err();
+    assert!(err.to_string().contains("projections"));
+
+    let bars = PlotOptions {
+        kind: PlotKind::GroupedBar,
+        transform: PlotTransform::None,
+        ..options
+    };
+    assert_eq!(bars.ignored_settings(), ["projections"]);
+    let svg = viz::render_svg_string(&actuals(), &bars).unwrap();
+    assert!(!svg.contains("(projection)"));
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "ndarray")]
+
+use wbi_rs::models::DataPoint;
+use wbi_rs::stats::{PivotKey, PivotSpec, to_ndarray};
+
+fn p(indicator: &str, iso3: &str, year: i32, value: Option<f64>) -> DataPoint {
+    DataPoint {
+        indicator_id: indicator.into(),
+        indicator_name: indicator.into(),
+        country_id: iso3[..2].into(),
+        country_name: iso3.into(),
+        country_iso3: iso3.into(),
+        year,
+        value,
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    }
+}
+
+fn panel() -> Vec<DataPoint> {
+    vec![
+        p("GDP", "FRA", 2020, Some(3.0)),
+        p("GDP", "DEU", 2020, Some(4.0)),
+        p("POP", "DEU", 2020, Some(83.0)),
+        p("POP", "DEU", 2009, None),
+        p("GDP", "DEU", 2009, Some(f64::NAN)),
+    ]
+}
+
+#[test]
+fn panel_design_matrix() {
+    let spec = PivotSpec {
+        rows: PivotKey::CountryYear,
+        cols: PivotKey::Indicator,
+    };
+    let (m, axes) = to_ndarray(&panel(), spec).unwrap();
+    assert_eq!(axes.rows, ["DEU:2009", "DEU:2020", "FRA:2020"]);
+    assert_eq!(axes.cols, ["GDP", "POP"]);
+    assert_eq!(m.shape(), &[3, 2]);
+    assert!(
+        m.row(0).iter().all(|v| v.is_nan()),
+        "missing and NaN values"
+    );
+    assert_eq!((m[[1, 0]], m[[1, 1]]), (4.0, 83.0));
+    assert_eq!(m[[2, 0]], 3.0);
+    assert!(m[[2, 1]].is_nan(), "gap");
+}
+
+#[test]
+fn series_by_year_orders_years_numerically() {
+    let spec = PivotSpec {
+        rows: PivotKey::Series,
+        cols: PivotKey::Year,
+    };
+    let (m, axes) = to_ndarray(&panel(), spec).unwrap();
+    assert_eq!(axes.rows, ["GDP:DEU", "GDP:FRA", "POP:DEU"]);
+    assert_eq!(axes.cols, ["2009", "2020"]);
+    assert_eq!(m[[2, 1]], 83.0);
+}
+
+#[test]
+fn ambiguous_spec_is_an_error() {
+    let spec = PivotSpec {
+        rows: PivotKey::Country,
+        cols: PivotKey::Year,
+    };
+    let err = to_ndarray(&panel(), spec).unwrap_err().to_string();
+    assert!(err.contains("several observations for one cell"), "{err}");
+    let (m, axes) = to_ndarray(&[], spec).unwrap();
+    assert_eq!(m.shape(), &[0, 0]);
+    assert!(axes.rows.is_empty());
+}
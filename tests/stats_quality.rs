@@ -0,0 +1,74 @@
+use wbi_rs::models::DataPoint;
+use wbi_rs::stats::{self, QUALITY_RECENCY_YEARS};
+
+fn point(iso3: &str, year: i32, value: Option<f64>, status: Option<&str>) -> DataPoint {
+    DataPoint {
+        indicator_id: "NY.GDP.MKTP.CD".into(),
+        indicator_name: "GDP (current US$)".into(),
+        country_id: iso3[..2].into(),
+        country_name: iso3.into(),
+        country_iso3: iso3.into(),
+        year,
+        value,
+        unit: None,
+        obs_status: status.map(Into::into),
+        decimal: None,
+    }
+}
+
+/// DEU: complete and current. FRA: half the years, estimates only, last value 2014.
+fn rows() -> Vec<DataPoint> {
+    (2010..2020)
+        .flat_map(|year| {
+            let fra = (year < 2015).then_some(1.0);
+            [
+                point("DEU", year, Some(year as f64), None),
+                point("FRA", year, fra, fra.map(|_| "E")),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn quality_scores_completeness_recency_and_estimates() {
+    let q = stats::quality(&rows());
+    assert_eq!(q.len(), 2);
+    let (deu, fra) = (&q[0], &q[1]);
+    assert_eq!(deu.key.country_iso3, "DEU");
+    assert_eq!(
+        (deu.completeness, deu.years_since_last, deu.estimate_share),
+        (1.0, Some(0), 0.0)
+    );
+    assert_eq!(deu.score, 1.0);
+    assert_eq!(deu.revision_volatility, None);
+
+    assert_eq!(fra.completeness, 0.5);
+    assert_eq!(fra.last_year, Some(2014));
+    assert_eq!(fra.years_since_last, Some(5));
+    assert_eq!(fra.estimate_share, 1.0);
+    let recency = 1.0 - 5.0 / QUALITY_RECENCY_YEARS as f64;
+    assert!((fra.score - (0.5 + recency) / 3.0).abs() < 1e-12);
+
+    // A series without any value scores 0.
+    let empty = stats::quality(&[point("ITA", 2020, None, None)]);
+    assert_eq!((empty[0].score, empty[0].years_since_last), (0.0, None));
+}
+
+#[test]
+fn history_adds_revision_volatility_and_filter_drops_weak_series() {
+    let history = [
+        point("DEU", 2019, Some(100.0), None),
+        point("DEU", 2019, Some(110.0), None),
+        point("DEU", 2019, Some(99.0), None),
+        point("FRA", 2014, Some(1.0), None),
+    ];
+    let q = stats::quality_with_history(&rows(), &history);
+    // (10/100 + 11/110) / 2
+    assert!((q[0].revision_volatility.unwrap() - 0.1).abs() < 1e-12);
+    assert!((q[0].score - (3.0 + 0.9) / 4.0).abs() < 1e-12);
+    assert_eq!(q[1].revision_volatility, Some(0.0));
+
+    let kept = stats::filter_quality(&rows(), 0.6);
+    assert!(!kept.is_empty());
+    assert!(kept.iter().all(|p| p.country_iso3 == "DEU"));
+}
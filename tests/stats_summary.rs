@@ -1,5 +1,5 @@
 use wbi_rs::models::{DataPoint, GroupKey};
-use wbi_rs::stats::grouped_summary;
+use wbi_rs::stats::{grouped_summary, yearly_summary};
 
 fn dp(ind_id: &str, c_iso3: &str, year: i32, v: Option<f64>) -> DataPoint {
     DataPoint {
@@ -33,13 +33,7 @@ fn grouped_stats_handle_missing_and_median_even_odd() {
     got.sort_by(|a, b| a.key.cmp(&b.key));
 
     let a = &got[0];
-    assert_eq!(
-        a.key,
-        GroupKey {
-            indicator_id: "IND1".into(),
-            country_iso3: "AAA".into()
-        }
-    );
+    assert_eq!(a.key, GroupKey::new("IND1", "AAA"));
     assert_eq!(a.count, 4);
     assert_eq!(a.missing, 0);
     assert_eq!(a.min, Some(1.0));
@@ -48,13 +42,7 @@ fn grouped_stats_handle_missing_and_median_even_odd() {
     assert!((a.median.unwrap() - 2.5).abs() < 1e-9);
 
     let b = &got[1];
-    assert_eq!(
-        b.key,
-        GroupKey {
-            indicator_id: "IND1".into(),
-            country_iso3: "BBB".into()
-        }
-    );
+    assert_eq!(b.key, GroupKey::new("IND1", "BBB"));
     assert_eq!(b.count, 2);
     assert_eq!(b.missing, 1);
     assert_eq!(b.min, Some(10.0));
@@ -62,3 +50,161 @@ fn grouped_stats_handle_missing_and_median_even_odd() {
     assert_eq!(b.mean.unwrap(), 20.0);
     assert_eq!(b.median.unwrap(), 20.0);
 }
+
+#[test]
+fn yearly_stats_span_countries() {
+    let rows = vec![
+        dp("X", "AAA", 2020, Some(1.0)),
+        dp("X", "BBB", 2020, Some(5.0)),
+        dp("X", "CCC", 2020, Some(3.0)),
+        dp("X", "AAA", 2021, None),
+        dp("Y", "AAA", 2020, Some(7.0)),
+    ];
+    let s = yearly_summary(&rows);
+    assert_eq!(s.len(), 3);
+    assert_eq!((s[0].indicator_id.as_str(), s[0].year), ("X", 2020));
+    assert_eq!(s[0].count, 3);
+    assert_eq!((s[0].min, s[0].max), (Some(1.0), Some(5.0)));
+    assert_eq!((s[0].mean, s[0].median), (Some(3.0), Some(3.0)));
+    assert_eq!((s[0].p25, s[0].p75), (Some(2.0), Some(4.0)));
+    let (p10, p90) = (s[0].p10.unwrap(), s[0].p90.unwrap());
+    assert!((p10 - 1.4).abs() < 1e-12 && (p90 - 4.6).abs() < 1e-12);
+    assert_eq!(s[1].count, 0);
+    assert_eq!(s[1].mean, None);
+    assert_eq!(s[1].p10, None);
+    assert_eq!(s[2].indicator_id, "Y");
+}
+
+#[test]
+fn summary_by_indicator_pools_countries() {
+    use wbi_rs::stats::{GroupBy, summary_by};
+    let rows = vec![
+        dp("IND1", "AAA", 2019, Some(1.0)),
+        dp("IND1", "BBB", 2019, Some(3.0)),
+        dp("IND1", "BBB", 2020, None),
+        dp("IND2", "AAA", 2019, Some(100.0)),
+    ];
+    let got = summary_by(&rows, GroupBy::Indicator);
+    assert_eq!(got.len(), 2);
+    assert_eq!(got[0].key.indicator_id, "IND1");
+    assert_eq!(got[0].key.country_iso3, "");
+    assert_eq!((got[0].count, got[0].missing), (2, 1));
+    assert_eq!(got[0].mean, Some(2.0));
+    assert_eq!(got[1].key.indicator_id, "IND2");
+    assert_eq!(summary_by(&rows, GroupBy::Country), grouped_summary(&rows));
+}
+
+#[test]
+fn summaries_include_spread_and_quartiles() {
+    // [1, 2, 3, 4, 10]: sample std = sqrt(50 / 4); quartiles at ranks 1 and 3.
+    let rows: Vec<DataPoint> = [1.0, 2.0, 3.0, 4.0, 10.0]
+        .iter()
+        .enumerate()
+        .map(|(i, v)| dp("IND1", "AAA", 2016 + i as i32, Some(*v)))
+        .chain([dp("IND1", "BBB", 2020, Some(5.0))])
+        .collect();
+    let s = grouped_summary(&rows);
+
+    assert!((s[0].std_dev.unwrap() - 12.5_f64.sqrt()).abs() < 1e-9);
+    assert_eq!((s[0].p25, s[0].p75), (Some(2.0), Some(4.0)));
+    // A single value has quartiles but no sample spread.
+    assert_eq!((s[1].p25, s[1].p75), (Some(5.0), Some(5.0)));
+    assert_eq!(s[1].std_dev, None);
+}
+
+#[test]
+fn summaries_include_cagr_between_first_and_last_value() {
+    use wbi_rs::stats::{GroupBy, summary_by};
+    // 100 -> 121 over two years is 10% a year; the gap year and unsorted input do not matter.
+    let rows = vec![
+        dp("IND1", "AAA", 2002, Some(121.0)),
+        dp("IND1", "AAA", 2000, Some(100.0)),
+        dp("IND1", "AAA", 2003, None),
+        dp("IND1", "BBB", 2000, Some(-1.0)),
+        dp("IND1", "BBB", 2001, Some(1.0)),
+        dp("IND1", "CCC", 2001, Some(5.0)),
+    ];
+    let s = grouped_summary(&rows);
+    assert!((s[0].cagr.unwrap() - 10.0).abs() < 1e-9);
+    // Sign change and a single year have no growth rate.
+    assert_eq!((s[1].cagr, s[2].cagr), (None, None));
+    assert_eq!(summary_by(&rows, GroupBy::Indicator)[0].cagr, None);
+}
+
+/// Streaming summaries of `rows` written as CSV, beside the in-memory ones.
+fn streamed(rows: &[DataPoint], group_by: wbi_rs::stats::GroupBy) -> Vec<wbi_rs::stats::Summary> {
+    let mut csv = Vec::new();
+    wbi_rs::storage::write_csv(rows, &mut csv).unwrap();
+    wbi_rs::stats::summary_by_from_reader(csv.as_slice(), group_by).unwrap()
+}
+
+fn close(a: Option<f64>, b: Option<f64>, tol: f64) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() <= tol,
+        (a, b) => a == b,
+    }
+}
+
+#[test]
+fn streaming_summary_matches_in_memory_summary() {
+    use wbi_rs::stats::{GroupBy, summary_by};
+    let rows = vec![
+        dp("IND1", "AAA", 2002, Some(121.0)),
+        dp("IND1", "AAA", 2000, Some(100.0)),
+        dp("IND1", "AAA", 2001, Some(0.1)),
+        dp("IND1", "AAA", 2003, None),
+        dp("IND1", "BBB", 2000, Some(-1.0)),
+        dp("IND1", "BBB", 2001, Some(1.0)),
+        dp("IND2", "BBB", 2001, Some(f64::NAN)),
+        dp("IND2", "CCC", 2001, Some(5.0)),
+    ];
+    for group_by in [GroupBy::Country, GroupBy::Indicator] {
+        let (exact, stream) = (summary_by(&rows, group_by), streamed(&rows, group_by));
+        assert_eq!(exact.len(), stream.len());
+        for (e, s) in exact.iter().zip(&stream) {
+            assert_eq!(
+                (
+                    &e.key, e.count, e.missing, e.min, e.max, e.median, e.p25, e.p75, e.cagr
+                ),
+                (
+                    &s.key, s.count, s.missing, s.min, s.max, s.median, s.p25, s.p75, s.cagr
+                )
+            );
+            assert!(close(e.mean, s.mean, 1e-9) && close(e.std_dev, s.std_dev, 1e-9));
+        }
+    }
+}
+
+#[test]
+fn streaming_summary_estimates_quartiles_of_large_groups() {
+    use wbi_rs::stats::{GroupBy, STREAMING_EXACT_VALUES, summary_by};
+    // A scrambled 0..10007 sequence, well past the exact buffer.
+    let n = 20 * STREAMING_EXACT_VALUES;
+    let rows: Vec<DataPoint> = (0..n)
+        .map(|i| dp("IND1", "AAA", i as i32, Some(((i * 7919) % 10_007) as f64)))
+        .chain([dp("IND1", "AAA", -1, None)])
+        .collect();
+    let exact = &summary_by(&rows, GroupBy::Country)[0];
+    let stream = &streamed(&rows, GroupBy::Country)[0];
+
+    assert_eq!(
+        (
+            stream.count,
+            stream.missing,
+            stream.min,
+            stream.max,
+            stream.cagr
+        ),
+        (exact.count, exact.missing, exact.min, exact.max, exact.cagr)
+    );
+    assert!(close(stream.mean, exact.mean, 1e-6));
+    assert!(close(stream.std_dev, exact.std_dev, 1e-6));
+    // Estimates within 1% of the range.
+    for (s, e) in [
+        (stream.p25, exact.p25),
+        (stream.median, exact.median),
+        (stream.p75, exact.p75),
+    ] {
+        assert!(close(s, e, 100.0), "{s:?} vs {e:?}");
+    }
+}
@@ -0,0 +1,452 @@
+use std::fs;
+use wbi_rs::models::DataPoint;
+use wbi_rs::stats::{
+    AggregateMethod, CountryComparison, CustomAggregate, DEFLATOR_INDICATOR, POPULATION_INDICATOR,
+    RankBy, compare_countries, custom_aggregates, deflate, drop_missing, exclude_series,
+    filter_coverage, grouped_summary, index_to_year, per_capita, rank_by_year, top_countries,
+    yoy_growth,
+};
+use wbi_rs::viz::{self, PlotOptions, PlotTransform};
+
+fn dp(ind: &str, iso3: &str, year: i32, v: Option<f64>) -> DataPoint {
+    DataPoint {
+        indicator_id: ind.into(),
+        indicator_name: if ind == POPULATION_INDICATOR {
+            "Population, total".into()
+        } else {
+            "GDP (current US$)".into()
+        },
+        country_id: iso3[..2].into(),
+        country_name: iso3.into(),
+        country_iso3: iso3.into(),
+        year,
+        value: v,
+        unit: None,
+        obs_status: None,
+        decimal: Some(0),
+    }
+}
+
+#[test]
+fn per_capita_divides_by_matching_population_and_drops_it() {
+    let rows = vec![
+        dp("GDP", "AAA", 2020, Some(1000.0)),
+        dp("GDP", "AAA", 2021, Some(1200.0)),
+        dp("GDP", "BBB", 2020, Some(500.0)),
+        dp(POPULATION_INDICATOR, "AAA", 2020, Some(10.0)),
+        dp(POPULATION_INDICATOR, "AAA", 2021, Some(0.0)),
+    ];
+    let out = per_capita(&rows);
+    assert_eq!(out.len(), 3);
+    assert!(out.iter().all(|p| p.indicator_id == "GDP"));
+    assert_eq!(out[0].value, Some(100.0));
+    assert_eq!(out[1].value, None, "zero population yields missing");
+    assert_eq!(out[2].value, None, "no population yields missing");
+    assert_eq!(out[0].unit.as_deref(), Some("current US$ per capita"));
+}
+
+#[test]
+fn index_rebases_each_series_to_base_year() {
+    let rows = vec![
+        dp("GDP", "AAA", 2019, Some(50.0)),
+        dp("GDP", "AAA", 2020, Some(200.0)),
+        dp("GDP", "AAA", 2021, Some(300.0)),
+        dp("GDP", "BBB", 2021, Some(7.0)),
+    ];
+    let out = index_to_year(&rows, 2020);
+    let vals: Vec<Option<f64>> = out.iter().map(|p| p.value).collect();
+    assert_eq!(vals, vec![Some(25.0), Some(100.0), Some(150.0), None]);
+    assert_eq!(out[0].unit.as_deref(), Some("index, 2020 = 100"));
+}
+
+#[test]
+fn yoy_growth_only_compares_consecutive_years() {
+    let rows = vec![
+        dp("GDP", "AAA", 2018, Some(100.0)),
+        dp("GDP", "AAA", 2019, Some(110.0)),
+        dp("GDP", "AAA", 2021, Some(121.0)),
+    ];
+    let out = yoy_growth(&rows);
+    assert_eq!(out[0].value, None);
+    assert!((out[1].value.unwrap() - 10.0).abs() < 1e-9);
+    assert_eq!(out[2].value, None, "gap year has no predecessor");
+    assert_eq!(out[2].unit.as_deref(), Some("annual % change"));
+}
+
+#[test]
+fn plot_transform_updates_axis_title() {
+    let rows: Vec<DataPoint> = (2015..=2020)
+        .flat_map(|y| {
+            [
+                dp("GDP", "AAA", y, Some(1.0e9 * (y - 2010) as f64)),
+                dp(POPULATION_INDICATOR, "AAA", y, Some(1.0e6)),
+            ]
+        })
+        .collect();
+    let path = std::env::temp_dir().join("wbd_transform_pc.svg");
+    let opts = PlotOptions {
+        transform: PlotTransform::PerCapita,
+        ..Default::default()
+    };
+    viz::plot_with_options(&rows, &path, &opts).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    assert!(svg.contains("current US$ per capita"));
+    assert!(
+        !svg.contains("Population"),
+        "population series must not be drawn"
+    );
+}
+
+#[test]
+fn per_capita_without_population_is_error() {
+    let rows = vec![dp("GDP", "AAA", 2020, Some(1.0))];
+    let path = std::env::temp_dir().join("wbd_transform_nopop.svg");
+    let opts = PlotOptions {
+        transform: PlotTransform::PerCapita,
+        ..Default::default()
+    };
+    let err = viz::plot_with_options(&rows, &path, &opts).unwrap_err();
+    assert!(format!("{err}").contains(POPULATION_INDICATOR));
+}
+
+#[test]
+fn deflate_converts_to_constant_prices_per_country() {
+    let deflator = |iso3: &str, year: i32, v: Option<f64>| DataPoint {
+        indicator_name: "GDP deflator (base year varies by country)".into(),
+        ..dp(DEFLATOR_INDICATOR, iso3, year, v)
+    };
+    let gdp = vec![
+        dp("GDP", "AAA", 2015, Some(100.0)),
+        dp("GDP", "AAA", 2020, Some(150.0)),
+        dp("GDP", "BBB", 2020, Some(80.0)),
+        dp("GDP", "CCC", 2020, Some(10.0)),
+    ];
+    let index = vec![
+        deflator("AAA", 2015, Some(100.0)),
+        deflator("AAA", 2020, Some(125.0)),
+        deflator("BBB", 2020, Some(80.0)),
+        deflator("CCC", 2020, Some(0.0)),
+    ];
+    let out = deflate(&gdp, &index);
+
+    let values: Vec<_> = out.iter().map(|p| p.value).collect();
+    // AAA grew 50% in current prices, 20% in real terms; a zero index is no index.
+    assert_eq!(values, [Some(100.0), Some(120.0), Some(100.0), None]);
+    assert_eq!(
+        out[0].unit.as_deref(),
+        Some("constant US$, deflator base year")
+    );
+    assert!(
+        out[0]
+            .indicator_name
+            .ends_with("deflated by GDP deflator (base year varies by country)")
+    );
+
+    // As a plot transform the deflator comes with the data and is not drawn.
+    let rows: Vec<DataPoint> = (2015..=2020)
+        .flat_map(|y| {
+            let v = (y - 2010) as f64;
+            [
+                dp("GDP", "AAA", y, Some(1.0e9 * v)),
+                deflator("AAA", y, Some(90.0 + v)),
+            ]
+        })
+        .collect();
+    let opts = PlotOptions {
+        transform: PlotTransform::Deflate,
+        ..Default::default()
+    };
+    let svg = viz::render_svg_string(&rows, &opts).unwrap();
+    assert!(svg.contains("constant US$"));
+    assert!(!svg.contains("AAA — GDP deflator"));
+    let err = viz::render_svg_string(&gdp, &opts).unwrap_err();
+    assert!(format!("{err}").contains(DEFLATOR_INDICATOR));
+}
+
+#[test]
+fn deflated_series_is_summarized_apart_from_the_current_one() {
+    let gdp = vec![
+        dp("GDP", "AAA", 2019, Some(100.0)),
+        dp("GDP", "AAA", 2020, Some(150.0)),
+    ];
+    let index = vec![
+        dp(DEFLATOR_INDICATOR, "AAA", 2019, Some(100.0)),
+        dp(DEFLATOR_INDICATOR, "AAA", 2020, Some(125.0)),
+    ];
+    let mut both = gdp.clone();
+    both.extend(deflate(&gdp, &index));
+    assert!(both[2..].iter().all(|p| p.indicator_id == "GDP~deflated"));
+
+    let summaries = grouped_summary(&both);
+    let keys: Vec<_> = summaries
+        .iter()
+        .map(|s| (s.key.indicator_id.as_str(), s.key.country_iso3.as_str()))
+        .collect();
+    assert_eq!(keys, [("GDP", "AAA"), ("GDP~deflated", "AAA")]);
+    assert_eq!(summaries[0].max, Some(150.0));
+    assert_eq!(summaries[1].max, Some(120.0));
+}
+
+#[test]
+fn top_countries_ranks_on_one_indicator_and_keeps_all_rows() {
+    let rows = vec![
+        dp("GDP", "AAA", 2019, Some(30.0)),
+        dp("GDP", "AAA", 2020, Some(10.0)),
+        dp("GDP", "BBB", 2019, Some(5.0)),
+        dp("GDP", "BBB", 2020, Some(20.0)),
+        dp("GDP", "CCC", 2020, None),
+        dp(POPULATION_INDICATOR, "CCC", 2020, Some(1e9)),
+        dp(POPULATION_INDICATOR, "BBB", 2020, Some(1.0)),
+    ];
+    let countries = |by: RankBy, n: usize| {
+        let mut c: Vec<String> = top_countries(&rows, "GDP", n, by)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.country_iso3)
+            .collect();
+        c.dedup();
+        c
+    };
+    assert_eq!(countries(RankBy::Last, 1), ["BBB"]);
+    assert_eq!(countries(RankBy::Mean, 1), ["AAA"]);
+    assert_eq!(countries(RankBy::Year(2019), 1), ["AAA"]);
+    assert_eq!(countries(RankBy::Last, 2), ["AAA", "BBB"]);
+    // CCC has no GDP value, so it is not ranked even when there is room.
+    assert_eq!(countries(RankBy::Last, 3), ["AAA", "BBB"]);
+    assert_eq!(
+        top_countries(&rows, "GDP", 1, RankBy::Last).unwrap().len(),
+        3
+    );
+}
+
+#[test]
+fn top_countries_fails_without_any_ranking_value() {
+    let rows = vec![
+        dp("GDP", "AAA", 2019, Some(30.0)),
+        dp("GDP", "BBB", 2019, Some(5.0)),
+        dp("GDP", "CCC", 2019, None),
+    ];
+    let err = top_countries(&rows, "GDP", 2, RankBy::Year(2020)).unwrap_err();
+    assert!(err.to_string().contains("GDP in 2020"), "{err}");
+    let err = top_countries(&rows, POPULATION_INDICATOR, 2, RankBy::Last).unwrap_err();
+    assert!(err.to_string().contains(POPULATION_INDICATOR), "{err}");
+}
+
+#[test]
+fn rank_by_year_shares_tied_ranks_and_tracks_movement() {
+    let rows = vec![
+        dp("GDP", "AAA", 2010, Some(1.0)),
+        dp("GDP", "BBB", 2010, Some(3.0)),
+        dp("GDP", "CCC", 2010, Some(2.0)),
+        // Only in the baseline: still pushes the others down there.
+        dp("GDP", "DDD", 2010, Some(9.0)),
+        dp("GDP", "AAA", 2020, Some(5.0)),
+        dp("GDP", "BBB", 2020, Some(4.0)),
+        dp("GDP", "CCC", 2020, Some(4.0)),
+        dp("GDP", "EEE", 2020, Some(1.0)),
+        dp("GDP", "EEE", 2010, None),
+        dp(POPULATION_INDICATOR, "EEE", 2020, Some(99.0)),
+    ];
+    let ranks = rank_by_year(&rows, "GDP", 2020, Some(2010));
+    let view: Vec<(&str, usize, Option<usize>, Option<i64>)> = ranks
+        .iter()
+        .map(|r| (r.country_iso3.as_str(), r.rank, r.baseline_rank, r.change))
+        .collect();
+    assert_eq!(
+        view,
+        [
+            ("AAA", 1, Some(4), Some(3)),
+            ("BBB", 2, Some(2), Some(0)),
+            ("CCC", 2, Some(3), Some(1)),
+            ("EEE", 4, None, None),
+        ]
+    );
+    assert_eq!(ranks[1].baseline_value, Some(3.0));
+
+    let no_baseline = rank_by_year(&rows, "GDP", 2020, None);
+    assert!(no_baseline.iter().all(|r| r.change.is_none()));
+    assert!(rank_by_year(&rows, "GDP", 2015, None).is_empty());
+}
+
+#[test]
+fn coverage_filter_drops_sparse_series_and_dropna_drops_gaps() {
+    // Four years; AAA has 3 values (75%), BBB has 1 (25%).
+    let rows = vec![
+        dp("GDP", "AAA", 2000, Some(1.0)),
+        dp("GDP", "AAA", 2001, None),
+        dp("GDP", "AAA", 2002, Some(3.0)),
+        dp("GDP", "AAA", 2003, Some(4.0)),
+        dp("GDP", "BBB", 2000, None),
+        dp("GDP", "BBB", 2001, None),
+        dp("GDP", "BBB", 2002, None),
+        dp("GDP", "BBB", 2003, Some(f64::NAN)),
+        dp("GDP", "BBB", 2003, Some(2.0)),
+    ];
+
+    let kept = filter_coverage(&rows, 0.5);
+    assert!(kept.iter().all(|p| p.country_iso3 == "AAA"));
+    assert_eq!(kept.len(), 4);
+    assert_eq!(filter_coverage(&rows, 0.0).len(), rows.len());
+
+    let dense = drop_missing(&kept);
+    let years: Vec<i32> = dense.iter().map(|p| p.year).collect();
+    assert_eq!(years, [2000, 2002, 2003]);
+}
+
+#[test]
+fn exclude_series_by_country_indicator_or_pair() {
+    let rows = vec![
+        dp("GDP", "AAA", 2000, Some(1.0)),
+        dp("POP", "AAA", 2000, Some(2.0)),
+        dp("GDP", "BBB", 2000, Some(3.0)),
+        dp("POP", "BBB", 2000, Some(4.0)),
+    ];
+    let kept = |patterns: &[&str]| -> Vec<(String, String)> {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        exclude_series(&rows, &patterns)
+            .into_iter()
+            .map(|p| (p.indicator_id, p.country_iso3))
+            .collect()
+    };
+    let pair = |i: &str, c: &str| (i.to_string(), c.to_string());
+
+    assert_eq!(kept(&[]).len(), 4);
+    assert_eq!(kept(&["aaa"]), [pair("GDP", "BBB"), pair("POP", "BBB")]);
+    assert_eq!(kept(&["POP"]), [pair("GDP", "AAA"), pair("GDP", "BBB")]);
+    assert_eq!(
+        kept(&["BBB:gdp", "AAA:POP"]),
+        [pair("GDP", "AAA"), pair("POP", "BBB")]
+    );
+}
+
+#[test]
+fn compare_countries_derives_one_labelled_series_per_indicator() {
+    let rows = vec![
+        dp("GDP", "AAA", 2019, Some(30.0)),
+        dp("GDP", "AAA", 2020, Some(40.0)),
+        dp("GDP", "BBB", 2019, Some(10.0)),
+        dp("GDP", "BBB", 2020, Some(0.0)),
+        dp("GDP", "CCC", 2020, Some(5.0)),
+    ];
+    let diff = compare_countries(&rows, "aaa", "BBB", CountryComparison::Difference);
+    let vals: Vec<Option<f64>> = diff.iter().map(|p| p.value).collect();
+    assert_eq!(vals, vec![Some(20.0), Some(40.0)]);
+    assert!(diff.iter().all(|p| p.country_iso3 == "AAA-BBB"));
+    assert_eq!(diff[0].country_name, "AAA minus BBB");
+    assert_eq!(diff[0].unit.as_deref(), Some("current US$"));
+
+    let ratio = compare_countries(&rows, "AAA", "BBB", CountryComparison::Ratio);
+    assert_eq!(ratio[0].value, Some(3.0));
+    assert_eq!(ratio[1].value, None, "division by zero yields missing");
+    assert_eq!(ratio[0].unit.as_deref(), Some("ratio, AAA / BBB"));
+}
+
+#[test]
+fn country_difference_plot_labels_axis_and_rejects_unknown_country() {
+    let rows: Vec<DataPoint> = (2015..=2020)
+        .flat_map(|y| {
+            [
+                dp("GDP", "AAA", y, Some(3.0e9)),
+                dp("GDP", "BBB", y, Some(1.0e9)),
+            ]
+        })
+        .collect();
+    let path = std::env::temp_dir().join("wbd_transform_diff.svg");
+    let mut opts = PlotOptions {
+        transform: PlotTransform::CountryDifference("AAA".into(), "BBB".into()),
+        ..Default::default()
+    };
+    viz::plot_with_options(&rows, &path, &opts).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    assert!(svg.contains("AAA minus BBB"), "legend names the comparison");
+    assert!(svg.contains("current US$"), "difference keeps the unit");
+
+    opts.transform = PlotTransform::CountryRatio("AAA".into(), "ZZZ".into());
+    let err = viz::plot_with_options(&rows, &path, &opts).unwrap_err();
+    assert!(format!("{err}").contains("ZZZ"), "{err}");
+}
+
+#[test]
+fn custom_aggregates_sum_amounts_and_weight_rates_by_population() {
+    let share = |iso3: &str, v: f64| DataPoint {
+        indicator_id: "NE.EXP.GNFS.ZS".into(),
+        indicator_name: "Exports of goods and services (% of GDP)".into(),
+        ..dp("X", iso3, 2020, Some(v))
+    };
+    let rows = vec![
+        dp("GDP", "DNK", 2020, Some(300.0)),
+        dp("GDP", "NOR", 2020, Some(400.0)),
+        dp("GDP", "DNK", 2021, Some(310.0)),
+        dp("GDP", "NOR", 2021, None),
+        dp("GDP", "FRA", 2020, Some(2000.0)),
+        dp(POPULATION_INDICATOR, "DNK", 2020, Some(6.0)),
+        dp(POPULATION_INDICATOR, "NOR", 2020, Some(4.0)),
+        share("DNK", 50.0),
+        share("NOR", 40.0),
+    ];
+    let nordics = CustomAggregate {
+        code: "NRD".into(),
+        name: "Nordics".into(),
+        members: vec!["dnk".into(), "NO".into()],
+        method: AggregateMethod::Auto,
+    };
+    let out = custom_aggregates(&rows, std::slice::from_ref(&nordics));
+    let get = |id: &str, year: i32| {
+        out.iter()
+            .find(|p| p.indicator_id == id && p.year == year)
+            .unwrap()
+    };
+
+    assert_eq!(get("GDP", 2020).value, Some(700.0));
+    assert_eq!(get("GDP", 2020).country_name, "Nordics");
+    assert_eq!(get("GDP", 2020).indicator_name, "GDP (current US$)");
+    // A member without a value leaves the year missing rather than undercounting it.
+    assert_eq!(get("GDP", 2021).value, None);
+    assert_eq!(get(POPULATION_INDICATOR, 2020).value, Some(10.0));
+    assert!((get("NE.EXP.GNFS.ZS", 2020).value.unwrap() - 46.0).abs() < 1e-9);
+    assert!(out.iter().all(|p| p.country_iso3 == "NRD"));
+
+    let mean = CustomAggregate {
+        method: AggregateMethod::Mean,
+        ..nordics
+    };
+    let out = custom_aggregates(&rows, &[mean]);
+    assert_eq!(out[0].value, Some(350.0));
+}
+
+#[test]
+fn aggregate_method_follows_the_indicator_unit() {
+    let named = |name: &str| DataPoint {
+        indicator_name: name.into(),
+        ..dp("X", "DEU", 2020, None)
+    };
+    for (name, method) in [
+        ("Population, total", AggregateMethod::Sum),
+        ("GDP (current US$)", AggregateMethod::Sum),
+        ("CO2 emissions (kt)", AggregateMethod::Sum),
+        (
+            "GDP per capita (current US$)",
+            AggregateMethod::PopulationWeighted,
+        ),
+        (
+            "Inflation, consumer prices (annual %)",
+            AggregateMethod::PopulationWeighted,
+        ),
+        (
+            "Life expectancy at birth, total (years)",
+            AggregateMethod::PopulationWeighted,
+        ),
+        (
+            "Official exchange rate (LCU per US$, period average)",
+            AggregateMethod::PopulationWeighted,
+        ),
+    ] {
+        assert_eq!(
+            AggregateMethod::for_indicator(&named(name)),
+            method,
+            "{name}"
+        );
+    }
+}
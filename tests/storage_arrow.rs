@@ -0,0 +1,50 @@
+#![cfg(feature = "arrow")]
+
+use arrow_array::{Array, Float64Array, Int32Array, StringArray};
+use wbi_rs::models::DataPoint;
+use wbi_rs::storage;
+
+fn point(year: i32, value: Option<f64>, unit: Option<&str>) -> DataPoint {
+    DataPoint {
+        indicator_id: "SP.POP.TOTL".into(),
+        indicator_name: "Population, total".into(),
+        country_id: "DE".into(),
+        country_name: "Germany".into(),
+        country_iso3: "DEU".into(),
+        year,
+        value,
+        unit: unit.map(str::to_string),
+        obs_status: None,
+        decimal: Some(0),
+    }
+}
+
+#[test]
+fn record_batch_has_one_row_per_observation() {
+    let rows = [
+        point(2019, Some(83.0e6), Some("people")),
+        point(2020, None, None),
+        point(2021, Some(f64::NAN), None),
+    ];
+    let batch = storage::to_record_batch(&rows).unwrap();
+    assert_eq!(batch.schema(), storage::record_batch_schema());
+    assert_eq!(batch.num_rows(), 3);
+
+    let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+    let years = column("year");
+    let years = years.as_any().downcast_ref::<Int32Array>().unwrap();
+    assert_eq!(years.values(), &[2019, 2020, 2021]);
+    let values = column("value");
+    let values = values.as_any().downcast_ref::<Float64Array>().unwrap();
+    assert_eq!(values.value(0), 83.0e6);
+    assert!(
+        values.is_null(1) && values.is_null(2),
+        "missing and NaN are null"
+    );
+    let units = column("unit");
+    let units = units.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(units.value(0), "people");
+    assert!(units.is_null(1));
+
+    assert_eq!(storage::to_record_batch(&[]).unwrap().num_rows(), 0);
+}
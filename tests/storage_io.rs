@@ -30,6 +30,9 @@ fn save_csv_and_json() {
     let csv_txt = fs::read_to_string(&csv_path).unwrap();
     assert!(csv_txt.starts_with("indicator_id,indicator_name,"));
     assert_eq!(csv_txt.lines().count(), 1 + rows.len());
+    let mut buf = Vec::new();
+    storage::write_csv(&rows, &mut buf).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), csv_txt);
     fs::remove_file(&csv_path).ok();
 
     let json_path: PathBuf = tmp.join("wbd_rs_test.json");
@@ -110,3 +113,118 @@ fn csv_cells_are_prefixed_to_avoid_formulas() {
     // Cleanup
     let _ = std::fs::remove_file(tmp);
 }
+
+#[test]
+fn saved_files_load_back_unchanged() {
+    let mut rows = sample(3);
+    rows[1].value = None;
+    rows[2].unit = Some("-5 is a unit".into()); // formula starter, guarded on save
+    rows[2].obs_status = Some("E".into());
+    rows[2].decimal = Some(1);
+    let dir = tempfile::tempdir().unwrap();
+
+    let csv_path = dir.path().join("rows.csv");
+    storage::save_csv(&rows, &csv_path).unwrap();
+    assert_eq!(storage::load_csv(&csv_path).unwrap(), rows);
+    assert_eq!(storage::load(&csv_path).unwrap(), rows);
+
+    let json_path = dir.path().join("rows.json");
+    storage::save_json(&rows, &json_path).unwrap();
+    assert_eq!(storage::load_json(&json_path).unwrap(), rows);
+    assert_eq!(storage::load(&json_path).unwrap(), rows);
+
+    let err = storage::load(dir.path().join("rows.txt")).unwrap_err();
+    assert!(err.to_string().contains(".csv or .json"), "{err}");
+}
+
+#[test]
+fn wide_csv_has_one_column_per_year() {
+    let mut rows = sample(3);
+    rows[1].value = None;
+    let mut fra = sample(1);
+    fra[0].country_iso3 = "FRA".into();
+    fra[0].country_name = "France".into();
+    fra[0].year = 2002;
+    rows.extend(fra);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wide.csv");
+    storage::save_csv_wide(&rows, &path).unwrap();
+    let txt = fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = txt.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "indicator_id,indicator_name,country_id,country_name,country_iso3,unit,2000,2001,2002",
+            "IND,Indicator,DE,Germany,DEU,,100,,102",
+            "IND,Indicator,DE,France,FRA,,,,100",
+        ]
+    );
+}
+
+#[test]
+fn csv_numbers_follow_the_export_locale() {
+    let mut rows = sample(2);
+    rows[0].value = Some(1234567.25);
+    rows[0].decimal = Some(2);
+    rows[1].value = Some(-0.5);
+
+    let mut buf = Vec::new();
+    let german = storage::CsvOptions {
+        locale: Some("de".into()),
+        group_thousands: true,
+        ..Default::default()
+    };
+    storage::write_csv_with_options(&rows, &mut buf, &german).unwrap();
+    let txt = String::from_utf8(buf).unwrap();
+    let lines: Vec<&str> = txt.lines().collect();
+    assert!(lines[0].starts_with("indicator_id;indicator_name;"));
+    assert_eq!(
+        lines[1],
+        "IND;Indicator;DE;Germany;DEU;2000;1.234.567,25;;;2"
+    );
+    assert_eq!(lines[2], "IND;Indicator;DE;Germany;DEU;2001;-0,5;;;");
+
+    // A comma delimiter quotes the comma decimals; no grouping keeps all digits together.
+    let mut buf = Vec::new();
+    let comma = storage::CsvOptions {
+        locale: Some("fr".into()),
+        delimiter: Some(b','),
+        ..Default::default()
+    };
+    storage::write_csv_with_options(&rows, &mut buf, &comma).unwrap();
+    assert!(
+        String::from_utf8(buf)
+            .unwrap()
+            .contains(",2000,\"1234567,25\",")
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wide.csv");
+    storage::save_csv_wide_with_options(&rows, &path, &german).unwrap();
+    let wide = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        wide.lines().nth(1),
+        Some("IND;Indicator;DE;Germany;DEU;;1.234.567,25;-0,5")
+    );
+}
+
+#[test]
+fn provenance_sidecar_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("pop.csv");
+    assert_eq!(storage::load_provenance(&data).unwrap(), None);
+
+    let provenance = storage::Provenance {
+        date: Some(wbi_rs::DateSpec::Last(5)),
+        ..storage::Provenance::now(vec!["DEU".into()], vec!["SP.POP.TOTL".into()])
+    };
+    storage::save_provenance(&data, &provenance).unwrap();
+    assert!(dir.path().join("pop.csv.meta.json").is_file());
+    let back = storage::load_provenance(&data).unwrap().unwrap();
+    assert_eq!(back, provenance);
+    assert!(back.generator.starts_with("wbi-rs "));
+
+    fs::write(dir.path().join("pop.csv.meta.json"), "{").unwrap();
+    assert!(storage::load_provenance(&data).is_err());
+}
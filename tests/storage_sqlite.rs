@@ -0,0 +1,92 @@
+#![cfg(feature = "sqlite")]
+
+use chrono::{TimeZone, Utc};
+use wbi_rs::models::DataPoint;
+use wbi_rs::storage;
+
+fn point(iso3: &str, year: i32, value: Option<f64>) -> DataPoint {
+    DataPoint {
+        indicator_id: "NY.GDP.MKTP.CD".into(),
+        indicator_name: "GDP (current US$)".into(),
+        country_id: iso3[..2].into(),
+        country_name: iso3.into(),
+        country_iso3: iso3.into(),
+        year,
+        value,
+        unit: None,
+        obs_status: None,
+        decimal: Some(0),
+    }
+}
+
+fn day(d: u32) -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, d, 12, 0, 0).unwrap()
+}
+
+#[test]
+fn only_new_and_revised_values_are_stored() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("wbi.sqlite");
+
+    let first = [point("DEU", 2020, Some(3.8e12)), point("FRA", 2020, None)];
+    assert_eq!(storage::save_sqlite(&first, &db, day(1)).unwrap(), 2);
+    // Same values again: nothing to record.
+    assert_eq!(storage::save_sqlite(&first, &db, day(2)).unwrap(), 0);
+
+    let revised = [
+        point("DEU", 2020, Some(3.9e12)),
+        point("FRA", 2020, Some(2.6e12)),
+    ];
+    assert_eq!(storage::save_sqlite(&revised, &db, day(3)).unwrap(), 2);
+
+    let history = storage::revisions(&db, "deu", "NY.GDP.MKTP.CD", 2020).unwrap();
+    let seen: Vec<_> = history.iter().map(|r| (r.retrieved_at, r.value)).collect();
+    assert_eq!(seen, [(day(1), Some(3.8e12)), (day(3), Some(3.9e12))]);
+    // ISO2 ids work as well; a missing value is a revision too.
+    let france = storage::revisions(&db, "FR", "NY.GDP.MKTP.CD", 2020).unwrap();
+    assert_eq!(france.len(), 2);
+    assert_eq!(france[0].value, None);
+
+    assert!(
+        storage::revisions(&db, "DEU", "NY.GDP.MKTP.CD", 1990)
+            .unwrap()
+            .is_empty()
+    );
+    // The full history feeds the revision volatility of the quality report.
+    let all = storage::load_sqlite_history(&db).unwrap();
+    assert_eq!(all.len(), 4);
+    let quality = wbi_rs::stats::quality_with_history(&revised, &all);
+    let deu = quality[0].revision_volatility.unwrap();
+    assert!((deu - 0.1e12 / 3.8e12).abs() < 1e-12);
+    assert_eq!(quality[1].revision_volatility, Some(0.0));
+}
+
+#[test]
+fn load_returns_latest_or_as_of_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("wbi.db");
+    storage::save_sqlite(&[point("DEU", 2020, Some(1.0))], &db, day(1)).unwrap();
+    storage::save_sqlite(
+        &[
+            point("DEU", 2020, Some(2.0)),
+            point("DEU", 2021, Some(f64::NAN)),
+        ],
+        &db,
+        day(5),
+    )
+    .unwrap();
+
+    let latest = storage::load(&db).unwrap();
+    assert_eq!(latest.len(), 2);
+    assert_eq!(
+        latest[1].value, None,
+        "non-finite values are stored as missing"
+    );
+    assert_eq!(latest[0], point("DEU", 2020, Some(2.0)));
+
+    let then = storage::load_sqlite(&db, Some(day(3))).unwrap();
+    assert_eq!(then.len(), 1);
+    assert_eq!(then[0].value, Some(1.0));
+
+    assert!(storage::load_sqlite(dir.path().join("missing.sqlite"), None).is_err());
+}
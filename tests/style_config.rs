@@ -0,0 +1,81 @@
+use std::fs;
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::style::StyleConfig;
+use wbi_rs::viz::{self, PlotKind, PlotOptions};
+
+fn points() -> Vec<DataPoint> {
+    ["DEU", "FRA"]
+        .iter()
+        .flat_map(|iso3| {
+            (2015..=2020).map(move |year| DataPoint {
+                indicator_id: "SP.POP.TOTL".into(),
+                indicator_name: "Population, total".into(),
+                country_id: iso3[..2].into(),
+                country_name: iso3.to_string(),
+                country_iso3: iso3.to_string(),
+                year,
+                value: Some(1.0e6 + year as f64),
+                unit: None,
+                obs_status: None,
+                decimal: None,
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn house_colors_and_width_end_up_in_svg() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("house.toml");
+    fs::write(
+        &config_path,
+        "line_width = 5\n[palette]\nDEU = \"#123456\"\n\"SP.POP.TOTL\" = \"#ABCDEF\"\n",
+    )
+    .unwrap();
+    let path = dir.path().join("house.svg");
+    let opts = PlotOptions {
+        kind: PlotKind::Line,
+        style_config: StyleConfig::load(&config_path).unwrap(),
+        ..Default::default()
+    };
+    viz::plot_with_options(&points(), &path, &opts).unwrap();
+    let svg = fs::read_to_string(&path).unwrap().to_uppercase();
+
+    // DEU by country key, FRA falls back to the indicator key.
+    assert!(svg.contains("STROKE=\"#123456\""));
+    assert!(svg.contains("STROKE=\"#ABCDEF\""));
+    assert!(svg.contains("STROKE-WIDTH=\"5\""));
+}
+
+#[test]
+fn invalid_style_config_names_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("broken.toml");
+    fs::write(&config_path, "[palette]\nDEU = \"blue\"\n").unwrap();
+    let err = StyleConfig::load(&config_path).unwrap_err();
+    let msg = format!("{err:#}");
+    assert!(msg.contains("broken.toml"), "{msg}");
+    assert!(msg.contains("invalid color"), "{msg}");
+}
+
+#[test]
+fn pattern_fills_add_hatching_to_bars() {
+    let dir = tempfile::tempdir().unwrap();
+    let render = |pattern_fills: bool| {
+        let path = dir.path().join(format!("bars_{pattern_fills}.svg"));
+        let opts = PlotOptions {
+            kind: PlotKind::GroupedBar,
+            pattern_fills,
+            ..Default::default()
+        };
+        viz::plot_with_options(&points(), &path, &opts).unwrap();
+        fs::read_to_string(&path).unwrap()
+    };
+    let plain = render(false);
+    let patterned = render(true);
+    // The second series (FRA) is hatched: many short strokes inside its bars.
+    assert!(
+        patterned.matches("<polyline").count() > plain.matches("<polyline").count() + 20,
+        "expected hatch lines in the patterned chart"
+    );
+}
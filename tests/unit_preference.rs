@@ -1,5 +1,6 @@
 use wbi_rs::models::DataPoint;
 use wbi_rs::viz::util::derive_axis_unit;
+use wbi_rs::viz::{self, AxisScale, PlotOptions};
 
 fn make_data_point(
     indicator_id: &str,
@@ -122,3 +123,66 @@ fn derive_axis_unit_empty_and_none_units_ignored() {
 
     assert_eq!(derive_axis_unit(&points), Some("USD".to_string()));
 }
+
+fn render_y_title(opts: &PlotOptions, name: &str) -> String {
+    let points: Vec<DataPoint> = (2015..=2020)
+        .map(|y| {
+            make_data_point(
+                "NY.GDP.MKTP.CD",
+                "GDP (current US$)",
+                "DEU",
+                y,
+                Some(3.5e12 + y as f64),
+                None,
+            )
+        })
+        .collect();
+    let path = std::env::temp_dir().join(format!("wbd_unit_{name}.svg"));
+    viz::plot_with_options(&points, &path, opts).unwrap();
+    let svg = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    svg
+}
+
+#[test]
+fn y_unit_and_scale_overrides_reach_axis_title() {
+    let auto = render_y_title(&PlotOptions::default(), "auto");
+    assert!(auto.contains("current US$ (trillions)"));
+
+    let pinned = render_y_title(
+        &PlotOptions {
+            y_unit: Some("USD".into()),
+            y_scale: AxisScale::Billions,
+            ..Default::default()
+        },
+        "pinned",
+    );
+    assert!(pinned.contains("USD (billions)"));
+    assert!(pinned.contains("\n3500\n"));
+
+    let custom = render_y_title(
+        &PlotOptions {
+            y_scale: AxisScale::Custom {
+                factor: 1e9,
+                word: "bn".into(),
+            },
+            ..Default::default()
+        },
+        "custom",
+    );
+    assert!(custom.contains("current US$ (bn)"));
+}
+
+#[test]
+fn custom_axis_scale_must_be_positive() {
+    let opts = PlotOptions {
+        y_scale: AxisScale::Custom {
+            factor: 0.0,
+            word: "x".into(),
+        },
+        ..Default::default()
+    };
+    let p = make_data_point("X", "X", "DEU", 2020, Some(1.0), None);
+    let path = std::env::temp_dir().join("wbd_unit_bad_scale.svg");
+    assert!(viz::plot_with_options(&[p], &path, &opts).is_err());
+}
@@ -0,0 +1,91 @@
+use std::fs;
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::{self, AnimationKind, AnimationOptions};
+
+fn points_two_countries() -> Vec<DataPoint> {
+    let mut out = Vec::new();
+    for (iso, name, base) in [("DEU", "Germany", 10.0), ("FRA", "France", 12.0)] {
+        for (i, year) in (2018..=2021).enumerate() {
+            out.push(DataPoint {
+                indicator_id: "X".into(),
+                indicator_name: "Demo".into(),
+                country_id: iso[..2].into(),
+                country_name: name.into(),
+                country_iso3: iso.into(),
+                year,
+                // FRA has a gap in 2020 to exercise carry-forward
+                value: if iso == "FRA" && year == 2020 {
+                    None
+                } else {
+                    Some(base + i as f64 * (if iso == "DEU" { 3.0 } else { 1.0 }))
+                },
+                unit: None,
+                obs_status: None,
+                decimal: None,
+            });
+        }
+    }
+    out
+}
+
+fn small_opts(kind: AnimationKind) -> AnimationOptions {
+    AnimationOptions {
+        kind,
+        width: 320,
+        height: 200,
+        frame_delay_ms: 100,
+        hold_last_frames: 1,
+        title: "Race".into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn ranked_bar_gif_has_gif_header() {
+    let path = std::env::temp_dir().join("wbd_anim_race.gif");
+    viz::animate(
+        &points_two_countries(),
+        &path,
+        &small_opts(AnimationKind::RankedBar),
+    )
+    .unwrap();
+    let bytes = fs::read(&path).unwrap();
+    assert!(bytes.starts_with(b"GIF89a"), "not a GIF");
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn scatter_apng_contains_animation_control_chunk() {
+    let path = std::env::temp_dir().join("wbd_anim_scatter.png");
+    viz::animate(
+        &points_two_countries(),
+        &path,
+        &small_opts(AnimationKind::Scatter),
+    )
+    .unwrap();
+    let bytes = fs::read(&path).unwrap();
+    assert!(bytes.starts_with(&[0x89, b'P', b'N', b'G']), "not a PNG");
+    assert!(
+        bytes.windows(4).any(|w| w == b"acTL"),
+        "APNG must carry an acTL chunk"
+    );
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn unsupported_extension_is_error() {
+    let path = std::env::temp_dir().join("wbd_anim.svg");
+    let err = viz::animate(
+        &points_two_countries(),
+        &path,
+        &small_opts(AnimationKind::RankedBar),
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("unsupported animation format"));
+}
+
+#[test]
+fn empty_points_is_error() {
+    let path = std::env::temp_dir().join("wbd_anim_empty.gif");
+    assert!(viz::animate(&[], &path, &AnimationOptions::default()).is_err());
+}
@@ -0,0 +1,96 @@
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::{self, AutoLayout};
+
+fn series(
+    indicator: (&str, &str),
+    iso3: &str,
+    years: std::ops::RangeInclusive<i32>,
+    scale: f64,
+) -> Vec<DataPoint> {
+    years
+        .map(|year| DataPoint {
+            indicator_id: indicator.0.into(),
+            indicator_name: indicator.1.into(),
+            country_id: iso3[..2].into(),
+            country_name: iso3.into(),
+            country_iso3: iso3.into(),
+            year,
+            value: Some(scale * (1.0 + (year - 2010) as f64 / 10.0)),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect()
+}
+
+const GDP: (&str, &str) = ("NY.GDP.MKTP.CD", "GDP (current US$)");
+const GNI: (&str, &str) = ("NY.GNP.MKTP.CD", "GNI (current US$)");
+const LIFE: (&str, &str) = ("SP.DYN.LE00.IN", "Life expectancy at birth, total (years)");
+const INFLATION: (&str, &str) = ("FP.CPI.TOTL.ZG", "Inflation, consumer prices (annual %)");
+
+#[test]
+fn layout_follows_units_and_series_counts() {
+    let mut one = series(GDP, "DEU", 2010..=2020, 3e12);
+    one.extend(series(GDP, "FRA", 2010..=2020, 2e12));
+    assert_eq!(viz::auto_layout(&one).unwrap(), AutoLayout::Single);
+
+    // GDP and GNI share a unit.
+    let mut shared = one.clone();
+    shared.extend(series(GNI, "DEU", 2010..=2020, 3e12));
+    assert_eq!(viz::auto_layout(&shared).unwrap(), AutoLayout::Single);
+
+    // Different units: few series are indexed to the first year they all cover.
+    let mut mixed = one.clone();
+    mixed.extend(series(LIFE, "DEU", 2012..=2020, 80.0));
+    assert_eq!(viz::auto_layout(&mixed).unwrap(), AutoLayout::Indexed(2012));
+
+    // Many series, or no common year, get one panel per indicator.
+    let countries = ["DEU", "FRA", "ITA", "ESP", "NLD"];
+    let mut many = Vec::new();
+    for iso3 in countries {
+        many.extend(series(GDP, iso3, 2010..=2020, 1e12));
+        many.extend(series(LIFE, iso3, 2010..=2020, 80.0));
+    }
+    assert_eq!(viz::auto_layout(&many).unwrap(), AutoLayout::Facets);
+    let mut disjoint = series(GDP, "DEU", 2000..=2005, 3e12);
+    disjoint.extend(series(LIFE, "DEU", 2010..=2015, 80.0));
+    assert_eq!(viz::auto_layout(&disjoint).unwrap(), AutoLayout::Facets);
+
+    assert!(viz::auto_layout(&[]).is_err());
+}
+
+#[test]
+fn auto_chart_draws_the_chosen_layout() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut mixed = series(GDP, "DEU", 2010..=2020, 3e12);
+    mixed.extend(series(LIFE, "DEU", 2010..=2020, 80.0));
+    let path = dir.path().join("indexed.svg");
+    assert_eq!(
+        viz::auto_chart(&mixed, &path).unwrap(),
+        AutoLayout::Indexed(2010)
+    );
+    let svg = std::fs::read_to_string(&path).unwrap();
+    assert!(svg.contains("index, 2010 = 100"));
+
+    let mut facets = Vec::new();
+    for iso3 in ["DEU", "FRA", "ITA", "ESP", "NLD"] {
+        facets.extend(series(GDP, iso3, 2010..=2020, 1e12));
+        facets.extend(series(LIFE, iso3, 2010..=2020, 80.0));
+        facets.extend(series(INFLATION, iso3, 2010..=2020, 2.0));
+    }
+    let path = dir.path().join("facets.svg");
+    assert_eq!(viz::auto_chart(&facets, &path).unwrap(), AutoLayout::Facets);
+    let svg = std::fs::read_to_string(&path).unwrap();
+    // One titled panel per indicator, each labelling its own five countries.
+    for (_, name) in [GDP, LIFE, INFLATION] {
+        let short = name.split(',').next().unwrap();
+        assert!(svg.contains(short), "missing panel {name}");
+    }
+    assert_eq!(svg.matches("\nNLD\n").count(), 3);
+
+    // Raster output takes the same layout.
+    let path = dir.path().join("facets.png");
+    viz::auto_chart(&facets, &path).unwrap();
+    assert!(std::fs::metadata(&path).unwrap().len() > 0);
+}
@@ -0,0 +1,116 @@
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::chart_diff::{self, ChartFormat};
+use wbi_rs::viz::{self, PlotOptions};
+
+fn points(last: f64) -> Vec<DataPoint> {
+    (2010..2016)
+        .map(|year| DataPoint {
+            indicator_id: "SP.POP.TOTL".into(),
+            indicator_name: "Population, total".into(),
+            country_id: "DE".into(),
+            country_name: "Germany".into(),
+            country_iso3: "DEU".into(),
+            year,
+            value: Some(if year == 2015 {
+                last
+            } else {
+                1.0e6 * (year - 2000) as f64
+            }),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect()
+}
+
+fn options(title: &str) -> PlotOptions {
+    PlotOptions {
+        title: title.into(),
+        width: 400,
+        height: 300,
+        deterministic: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn svg_diff_marks_changed_elements_only() {
+    let a = viz::render_svg_string(&points(15.0e6), &options("Before")).unwrap();
+    let same = chart_diff::diff_svg(&a, &a);
+    assert!(same.is_identical());
+    assert_eq!(same.similarity, 1.0);
+
+    let b = viz::render_svg_string(&points(15.0e6), &options("After")).unwrap();
+    let d = chart_diff::diff_svg(&a, &b);
+    assert_eq!(d.format, ChartFormat::Svg);
+    assert_eq!(d.differing, 2, "one title removed, one added");
+    assert!(d.similarity > 0.9 && d.similarity < 1.0);
+    let image = String::from_utf8(d.image).unwrap();
+    assert_eq!(image.matches(r#"<text data-diff="added""#).count(), 1);
+    let added = image.find(r#"<text data-diff="added""#).unwrap();
+    assert!(image[added..].contains("After"));
+    let removed = image.find(r#"<text data-diff="removed""#).unwrap();
+    assert!(image[removed..].contains("Before</text>"));
+    assert!(image.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+fn svg_diff_does_not_shift_after_an_inserted_element() {
+    let a = "<svg>\n<line x1=\"0\"/>\n<line x1=\"1\"/>\n<line x1=\"2\"/>\n</svg>";
+    let b =
+        "<svg>\n<line x1=\"0\"/>\n<circle r=\"3\"/>\n<line x1=\"1\"/>\n<line x1=\"2\"/>\n</svg>";
+    let d = chart_diff::diff_svg(a, b);
+    assert_eq!((d.differing, d.total), (1, 9));
+    let image = String::from_utf8(d.image).unwrap();
+    assert!(
+        image.contains(r#"<circle data-diff="added" r="3"/>"#),
+        "{image}"
+    );
+}
+
+#[test]
+fn png_diff_counts_pixels_and_compares_sizes_on_the_larger_canvas() {
+    let a = viz::render_png_bytes(&points(15.0e6), &options("Chart")).unwrap();
+    let b = viz::render_png_bytes(&points(20.0e6), &options("Chart")).unwrap();
+    assert!(chart_diff::diff_png(&a, &a, 0).unwrap().is_identical());
+
+    let d = chart_diff::diff_png(&a, &b, 0).unwrap();
+    assert_eq!((d.format, d.total), (ChartFormat::Png, 400 * 300));
+    assert!(d.differing > 0 && d.similarity < 1.0);
+    assert!(d.image.starts_with(b"\x89PNG"));
+
+    let wide = viz::render_png_bytes(
+        &points(15.0e6),
+        &PlotOptions {
+            width: 500,
+            ..options("Chart")
+        },
+    )
+    .unwrap();
+    let d = chart_diff::diff_png(&a, &wide, 0).unwrap();
+    assert_eq!(d.total, 500 * 300);
+    assert!(d.differing >= 100 * 300, "the extra columns differ");
+}
+
+#[test]
+fn files_of_different_formats_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let (png, svg) = (dir.path().join("a.png"), dir.path().join("b.svg"));
+    std::fs::write(
+        &png,
+        viz::render_png_bytes(&points(1.0), &options("x")).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(
+        &svg,
+        viz::render_svg_string(&points(1.0), &options("x")).unwrap(),
+    )
+    .unwrap();
+    let err = chart_diff::diff_files(&png, &svg, 0).unwrap_err();
+    assert!(format!("{err}").contains("both as SVG"), "{err}");
+    assert!(
+        chart_diff::diff_files(&svg, &svg, 0)
+            .unwrap()
+            .is_identical()
+    );
+}
@@ -0,0 +1,25 @@
+use wbi_rs::viz::colorscale::ColorScale;
+
+fn rgb(c: plotters::style::RGBColor) -> (u8, u8, u8) {
+    (c.0, c.1, c.2)
+}
+
+#[test]
+fn scales_hit_their_end_stops_and_clamp() {
+    assert_eq!(rgb(ColorScale::Viridis.at(0.0)), (68, 1, 84));
+    assert_eq!(rgb(ColorScale::Viridis.at(1.0)), (253, 231, 37));
+    assert_eq!(rgb(ColorScale::Viridis.at(-3.0)), (68, 1, 84));
+    assert_eq!(rgb(ColorScale::Viridis.at(7.0)), (253, 231, 37));
+    assert_eq!(rgb(ColorScale::RdBu.map(-1.0, (-1.0, 1.0))), (33, 102, 172));
+    assert_eq!(rgb(ColorScale::RdBu.map(1.0, (-1.0, 1.0))), (178, 24, 43));
+}
+
+#[test]
+fn sequential_scale_gets_lighter_along_the_domain() {
+    let luma = |t: f64| {
+        let (r, g, b) = rgb(ColorScale::Viridis.map(t, (0.0, 100.0)));
+        0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64
+    };
+    let samples: Vec<f64> = (0..=10).map(|i| luma(i as f64 * 10.0)).collect();
+    assert!(samples.windows(2).all(|w| w[1] > w[0]), "{samples:?}");
+}
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::fs;
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::{self, ErrorBarOptions, PlotKind, PlotOptions, UncertaintySource};
+
+fn points() -> Vec<DataPoint> {
+    (2015..=2020)
+        .map(|year| DataPoint {
+            indicator_id: "X".into(),
+            indicator_name: "Demo (annual %)".into(),
+            country_id: "DE".into(),
+            country_name: "Germany".into(),
+            country_iso3: "DEU".into(),
+            year,
+            value: Some(10.0 + (year - 2015) as f64 * 0.4),
+            unit: None,
+            obs_status: if year >= 2019 { Some("E".into()) } else { None },
+            decimal: Some(1),
+        })
+        .collect()
+}
+
+fn render(opts: &PlotOptions, name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("wbd_errbars_{name}.svg"));
+    viz::plot_with_options(&points(), &path, opts).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    svg
+}
+
+#[test]
+fn explicit_bounds_widen_the_value_axis() {
+    let mut table = BTreeMap::new();
+    table.insert(("DEU".to_string(), "X".to_string(), 2017), (0.0, 40.0));
+    let opts = PlotOptions {
+        kind: PlotKind::LinePoints,
+        error_bars: Some(ErrorBarOptions {
+            source: UncertaintySource::Explicit(table),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let with = render(&opts, "explicit");
+    let without = render(
+        &PlotOptions {
+            kind: PlotKind::LinePoints,
+            ..Default::default()
+        },
+        "none",
+    );
+    // The upper whisker (40) lies far above the data (max 12), so the axis must reach it.
+    assert!(with.contains("40.0"));
+    assert!(!without.contains("40.0"));
+}
+
+#[test]
+fn derived_sources_render_for_bar_and_scatter() {
+    for (i, (kind, source)) in [
+        (PlotKind::GroupedBar, UncertaintySource::FromDecimal),
+        (
+            PlotKind::Scatter,
+            UncertaintySource::FromObsStatus { relative: 0.1 },
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let opts = PlotOptions {
+            kind,
+            error_bars: Some(ErrorBarOptions {
+                source,
+                cap_width_px: 10,
+                opacity: 1.0,
+            }),
+            ..Default::default()
+        };
+        let svg = render(&opts, &format!("derived{i}"));
+        assert!(svg.starts_with("<svg"));
+    }
+}
+
+#[test]
+fn invalid_opacity_is_error() {
+    let path = std::env::temp_dir().join("wbd_errbars_bad.svg");
+    let opts = PlotOptions {
+        error_bars: Some(ErrorBarOptions {
+            opacity: 1.5,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let err = viz::plot_with_options(&points(), &path, &opts).unwrap_err();
+    assert!(format!("{err}").contains("opacity"));
+}
@@ -107,3 +107,372 @@ fn stacked_area_and_grouped_bar_and_loess() {
         .unwrap();
     });
 }
+
+#[test]
+fn envelope_has_one_legend_entry_per_indicator() {
+    let pts = points_three_series();
+    let path = std::env::temp_dir().join("wbd_viz_extra_envelope.svg");
+    viz::plot_chart(
+        &pts,
+        &path,
+        900,
+        520,
+        "en",
+        LegendMode::Bottom,
+        "Envelope",
+        PlotKind::Envelope,
+        0.3,
+        None,
+    )
+    .unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    assert!(svg.contains("range of 3 countries"));
+    assert!(!svg.contains("Germany"));
+    assert!(svg.contains("<polygon"));
+}
+
+#[test]
+fn fan_chart_draws_nested_percentile_bands_and_a_median() {
+    let opts = viz::PlotOptions {
+        kind: PlotKind::FanChart,
+        deterministic: true,
+        ..Default::default()
+    };
+    let svg = viz::render_svg_string(&points_three_series(), &opts).unwrap();
+    assert!(svg.contains("median, 25–75th and 10–90th percentiles of 3 countries"));
+    assert!(!svg.contains("Germany"));
+    assert_eq!(svg.matches("<polygon").count(), 2, "outer and inner band");
+    assert!(svg.contains("<polyline"), "median line");
+}
+
+/// `points_three_series` plus a second indicator for Germany and France, ending in 2020.
+fn points_two_indicators() -> Vec<DataPoint> {
+    let mut pts = points_three_series();
+    let second: Vec<DataPoint> = pts
+        .iter()
+        .filter(|p| p.country_iso3 != "USA" && p.year < 2021)
+        .map(|p| DataPoint {
+            indicator_id: "Y".into(),
+            indicator_name: "Other".into(),
+            value: p.value.map(|v| v * 10.0),
+            ..p.clone()
+        })
+        .collect();
+    pts.extend(second);
+    pts
+}
+
+fn bars_svg(bar_groups: viz::BarGrouping) -> String {
+    let opts = viz::PlotOptions {
+        kind: PlotKind::GroupedBar,
+        bar_groups,
+        legend: LegendMode::Right,
+        ..Default::default()
+    };
+    viz::render_svg_string(&points_two_indicators(), &opts).unwrap()
+}
+
+#[test]
+fn grouped_bar_by_country_labels_countries_on_x() {
+    let svg = bars_svg(viz::BarGrouping::Countries);
+    // One bar per series with data: 3 countries × X, 2 countries × Y.
+    for label in ["France", "Germany", "United States", "Demo", "Other"] {
+        assert!(svg.contains(&format!("\n{label}\n")), "missing {label}");
+    }
+    // Latest years differ between the indicators, so the axis names the span.
+    assert!(svg.contains("Country (2020–2021)"));
+    assert!(!svg.contains("\n2019\n"));
+}
+
+#[test]
+fn grouped_bar_by_indicator_puts_countries_in_the_legend() {
+    let svg = bars_svg(viz::BarGrouping::Indicators);
+    assert!(svg.contains("Indicator (2020–2021)"));
+    for label in ["Demo", "Other", "France", "Germany", "United States"] {
+        assert!(svg.contains(&format!("\n{label}\n")), "missing {label}");
+    }
+    // The year grouping keeps years on X and draws every observation.
+    let by_year = bars_svg(viz::BarGrouping::Years);
+    assert!(by_year.contains("\n2019\n") && by_year.contains("\nYear\n"));
+    assert!(by_year.matches("<rect").count() > svg.matches("<rect").count());
+}
+
+#[test]
+fn bar_groups_is_ignored_by_other_kinds() {
+    let opts = viz::PlotOptions {
+        bar_groups: viz::BarGrouping::Countries,
+        ..Default::default()
+    };
+    assert_eq!(opts.ignored_settings(), ["bar_groups"]);
+    let bars = viz::PlotOptions {
+        kind: PlotKind::GroupedBar,
+        ..opts
+    };
+    assert!(bars.ignored_settings().is_empty());
+}
+
+/// Tick labels of an SVG chart (text nodes that parse as numbers).
+fn numeric_labels(svg: &str) -> Vec<f64> {
+    svg.lines()
+        .filter_map(|l| l.trim().replace('−', "-").parse::<f64>().ok())
+        .collect()
+}
+
+#[test]
+fn stacked_area_axis_spans_the_stack_and_negatives_follow_the_policy() {
+    let svg_of = |pts: &[DataPoint], stack_negatives| {
+        let opts = viz::PlotOptions {
+            kind: PlotKind::StackedArea,
+            stack_negatives,
+            deterministic: true,
+            ..Default::default()
+        };
+        (
+            viz::render_svg_string(pts, &opts),
+            viz::plot_warnings(pts, &opts).unwrap(),
+        )
+    };
+
+    // 2021 stacks 3 + 3.5 + 4 = 10.5, above every single value.
+    let (svg, warnings) = svg_of(&points_three_series(), viz::StackNegatives::Clamp);
+    assert!(numeric_labels(&svg.unwrap()).contains(&10.0));
+    assert!(warnings.is_empty());
+
+    let mut pts = points_three_series();
+    let usa_2020 = pts
+        .iter_mut()
+        .find(|p| p.country_iso3 == "USA" && p.year == 2020)
+        .unwrap();
+    usa_2020.value = Some(-2.5);
+
+    let (svg, warnings) = svg_of(&pts, viz::StackNegatives::Clamp);
+    assert!(svg.is_ok());
+    assert_eq!(
+        warnings,
+        [viz::PlotWarning::NegativesClamped {
+            country_iso3: "USA".into(),
+            indicator_id: "X".into(),
+            years: vec![2020],
+        }]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "USA X: negative values in 2020 drawn as zero in the stack"
+    );
+
+    let (svg, _) = svg_of(&pts, viz::StackNegatives::Error);
+    let err = svg.unwrap_err().to_string();
+    assert!(err.contains("USA X (2020)"), "{err}");
+
+    let (svg, warnings) = svg_of(&pts, viz::StackNegatives::Mirror);
+    assert!(warnings.is_empty());
+    let labels = numeric_labels(&svg.unwrap());
+    assert!(labels.iter().any(|v| *v < 0.0), "{labels:?}");
+}
+
+/// `(x, width, height)` of the filled bars of an SVG chart, left to right.
+fn bar_rects(svg: &str) -> Vec<(i32, i32, i32)> {
+    let attr = |line: &str, name: &str| -> i32 {
+        let start = line.find(&format!(" {name}=\"")).unwrap() + name.len() + 3;
+        line[start..].split('"').next().unwrap().parse().unwrap()
+    };
+    let mut rects: Vec<_> = svg
+        .lines()
+        .filter(|l| l.starts_with("<rect") && l.contains("stroke=\"none\""))
+        .filter(|l| !l.contains("fill=\"#FFFFFF\""))
+        .map(|l| (attr(l, "x"), attr(l, "width"), attr(l, "height")))
+        .collect();
+    rects.sort();
+    rects
+}
+
+#[test]
+fn grouped_bars_can_be_ranked_spaced_outlined_and_capped() {
+    let svg_of = |bars: viz::BarOptions| {
+        let opts = viz::PlotOptions {
+            kind: PlotKind::GroupedBar,
+            bars,
+            ..Default::default()
+        };
+        viz::render_svg_string(&points_three_series(), &opts).unwrap()
+    };
+    // The middle year's group, clear of the clipped groups at the plot edges.
+    let middle = |svg: &str| -> Vec<(i32, i32, i32)> {
+        bar_rects(svg)
+            .into_iter()
+            .filter(|&(x, _, _)| (300..760).contains(&x))
+            .collect()
+    };
+
+    let plain = svg_of(Default::default());
+    assert_eq!(middle(&plain).len(), 3);
+    // France (the largest values) leads the legend, Germany (the smallest) follows.
+    let by_series: Vec<i32> = middle(&plain).iter().map(|r| r.2).collect();
+    assert!(by_series[0] > by_series[2] && by_series[2] > by_series[1]);
+    for (order, rising) in [
+        (viz::BarOrder::Ascending, true),
+        (viz::BarOrder::Descending, false),
+    ] {
+        let svg = svg_of(viz::BarOptions {
+            order,
+            ..Default::default()
+        });
+        let heights: Vec<i32> = middle(&svg).iter().map(|r| r.2).collect();
+        assert!(
+            heights.windows(2).all(|w| (w[0] < w[1]) == rising),
+            "{order:?}"
+        );
+    }
+
+    let bar_w = middle(&plain)[0].1;
+    let wide_gap = svg_of(viz::BarOptions {
+        group_gap: 0.5,
+        ..Default::default()
+    });
+    assert!(middle(&wide_gap)[0].1 < bar_w);
+    let capped = svg_of(viz::BarOptions {
+        max_bar_px: Some(20),
+        ..Default::default()
+    });
+    assert!(middle(&capped).iter().all(|r| r.1 <= 20));
+    let outlined = svg_of(viz::BarOptions {
+        borders: true,
+        ..Default::default()
+    });
+    assert!(outlined.matches("<rect").count() > plain.matches("<rect").count());
+
+    let bad = viz::PlotOptions {
+        kind: PlotKind::GroupedBar,
+        bars: viz::BarOptions {
+            group_gap: 1.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert!(bad.validate().is_err());
+    let line = viz::PlotOptions {
+        kind: PlotKind::Line,
+        ..bad
+    };
+    assert_eq!(line.ignored_settings(), ["bars"]);
+}
+
+#[test]
+fn pareto_ranks_countries_with_a_cumulative_share_axis() {
+    let opts = viz::PlotOptions {
+        kind: PlotKind::Pareto,
+        deterministic: true,
+        ..Default::default()
+    };
+    let svg = viz::render_svg_string(&points_three_series(), &opts).unwrap();
+    // Latest year by default: France 4, United States 3.5, Germany 3.
+    assert!(svg.contains("Country (2021)"));
+    let at = |label: &str| svg.find(&format!("\n{label}\n")).unwrap();
+    assert!(at("France") < at("United States") && at("United States") < at("Germany"));
+    let heights: Vec<i32> = bar_rects(&svg).iter().map(|r| r.2).collect();
+    assert_eq!(heights.len(), 3, "{heights:?}");
+    assert!(heights.windows(2).all(|w| w[0] > w[1]), "{heights:?}");
+    assert!(svg.contains("Cumulative share (%)"));
+    assert!(svg.contains("\n100%\n") && svg.contains("\n80%\n"));
+
+    let earlier = viz::PlotOptions {
+        pareto: viz::ParetoOptions {
+            year: Some(2019),
+            reference_share: None,
+        },
+        ..opts.clone()
+    };
+    let svg_2019 = viz::render_svg_string(&points_three_series(), &earlier).unwrap();
+    assert!(svg_2019.contains("Country (2019)"));
+    assert!(svg_2019.matches("<polyline").count() < svg.matches("<polyline").count());
+    assert!(earlier.ignored_settings().is_empty());
+    let line = viz::PlotOptions {
+        kind: PlotKind::Line,
+        ..earlier
+    };
+    assert_eq!(line.ignored_settings(), ["pareto"]);
+
+    let two = viz::render_svg_string(&points_two_indicators(), &opts).unwrap_err();
+    assert!(two.to_string().contains("one indicator"), "{two}");
+    let mut negative = points_three_series();
+    negative[2].value = Some(-1.0);
+    let err = viz::render_svg_string(&negative, &opts).unwrap_err();
+    assert!(err.to_string().contains("DEU"), "{err}");
+    let bad = viz::PlotOptions {
+        pareto: viz::ParetoOptions {
+            reference_share: Some(100.0),
+            ..Default::default()
+        },
+        ..opts
+    };
+    assert!(bad.validate().is_err());
+}
+
+#[test]
+fn waterfall_steps_from_one_total_to_the_next() {
+    let opts = viz::PlotOptions {
+        kind: PlotKind::Waterfall,
+        deterministic: true,
+        ..Default::default()
+    };
+    // One indicator: a step per country, each up by 2 between 2019 and 2021.
+    let svg = viz::render_svg_string(&points_three_series(), &opts).unwrap();
+    assert!(svg.contains("Country (2019–2021)"));
+    for label in [
+        "Total 2019",
+        "France",
+        "Germany",
+        "United States",
+        "Total 2021",
+    ] {
+        assert!(svg.contains(&format!("\n{label}\n")), "missing {label}");
+    }
+    assert!(svg.contains("\nIncrease\n") && !svg.contains("\nDecrease\n"));
+    assert_eq!(bar_rects(&svg).len(), 5);
+
+    // One country: a step per indicator; Germany's "Other" falls from 2019 to 2020.
+    let germany: Vec<DataPoint> = points_two_indicators()
+        .into_iter()
+        .filter(|p| p.country_iso3 == "DEU")
+        .map(|p| DataPoint {
+            value: if p.indicator_id == "Y" {
+                p.value.map(|v| 40.0 - v)
+            } else {
+                p.value
+            },
+            ..p
+        })
+        .collect();
+    let years = viz::PlotOptions {
+        waterfall: viz::WaterfallOptions {
+            from: Some(2019),
+            to: Some(2020),
+        },
+        ..opts.clone()
+    };
+    let svg = viz::render_svg_string(&germany, &years).unwrap();
+    assert!(svg.contains("Indicator (2019–2020)"));
+    assert!(svg.contains("\nDemo\n") && svg.contains("\nOther\n"));
+    assert!(svg.contains("\nIncrease\n") && svg.contains("\nDecrease\n"));
+    assert!(years.ignored_settings().is_empty());
+    let line = viz::PlotOptions {
+        kind: PlotKind::Line,
+        ..years.clone()
+    };
+    assert_eq!(line.ignored_settings(), ["waterfall"]);
+
+    let err = viz::render_svg_string(&points_two_indicators(), &opts).unwrap_err();
+    assert!(
+        err.to_string().contains("2 indicators and 3 countries"),
+        "{err}"
+    );
+    let backwards = viz::PlotOptions {
+        waterfall: viz::WaterfallOptions {
+            from: Some(2021),
+            to: Some(2019),
+        },
+        ..opts
+    };
+    assert!(backwards.validate().is_err());
+}
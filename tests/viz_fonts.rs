@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::PathBuf;
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::{self, CustomFont, FontOptions, PlotOptions};
+
+fn points() -> Vec<DataPoint> {
+    (2015..=2020)
+        .map(|year| DataPoint {
+            indicator_id: "X".into(),
+            indicator_name: "Demo (annual %)".into(),
+            country_id: "DE".into(),
+            country_name: "Germany".into(),
+            country_iso3: "DEU".into(),
+            year,
+            value: Some(10.0 + (year - 2015) as f64),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect()
+}
+
+fn bundled_font() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/DejaVuSans.ttf")
+}
+
+#[test]
+fn custom_family_and_sizes_end_up_in_svg() {
+    let path = std::env::temp_dir().join("wbd_fonts_custom.svg");
+    let opts = PlotOptions {
+        title: "Fonts".into(),
+        fonts: FontOptions {
+            custom: Some(CustomFont {
+                family: "Test Sans".into(),
+                path: bundled_font(),
+            }),
+            // The SVG backend writes `px / 1.24` as font-size.
+            title_px: 62,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    viz::plot_with_options(&points(), &path, &opts).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+
+    assert!(svg.contains(r#"font-family="Test Sans""#));
+    assert!(!svg.contains(r#"font-family="sans-serif""#));
+    assert!(svg.contains(r#"font-size="50""#));
+}
+
+#[test]
+fn invalid_font_file_fails_without_output() {
+    let path = std::env::temp_dir().join("wbd_fonts_invalid.svg");
+    fs::remove_file(&path).ok();
+    let opts = PlotOptions {
+        fonts: FontOptions {
+            custom: Some(CustomFont {
+                family: "Broken".into(),
+                path: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"),
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let err = viz::plot_with_options(&points(), &path, &opts).unwrap_err();
+    assert!(err.to_string().contains("not a valid"), "{err}");
+    assert!(!path.exists());
+}
+
+#[test]
+fn out_of_range_font_size_is_rejected() {
+    let path = std::env::temp_dir().join("wbd_fonts_size.svg");
+    let opts = PlotOptions {
+        fonts: FontOptions {
+            tick_px: 0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let err = viz::plot_with_options(&points(), &path, &opts).unwrap_err();
+    assert!(err.to_string().contains("tick font size"), "{err}");
+}
+
+#[test]
+fn family_is_trimmed_and_not_reregistered_from_another_file() {
+    let path = std::env::temp_dir().join("wbd_fonts_trimmed.svg");
+    let with_font = |family: &str, file: PathBuf| PlotOptions {
+        fonts: FontOptions {
+            custom: Some(CustomFont {
+                family: family.into(),
+                path: file,
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    viz::plot_with_options(&points(), &path, &with_font("  Trim Sans ", bundled_font())).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    assert!(svg.contains(r#"font-family="Trim Sans""#));
+
+    // Same family and file again: reuses the registration.
+    viz::plot_with_options(&points(), &path, &with_font("Trim Sans", bundled_font())).unwrap();
+    fs::remove_file(&path).ok();
+
+    let other = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let err = viz::plot_with_options(&points(), &path, &with_font("Trim Sans", other)).unwrap_err();
+    assert!(err.to_string().contains("already registered"), "{err}");
+    assert!(!path.exists());
+}
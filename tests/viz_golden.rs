@@ -0,0 +1,96 @@
+//! Rendered SVGs compared against `tests/golden/*.svg`. After an intended change to chart
+//! output, regenerate the references with `WBI_UPDATE_GOLDEN=1 cargo test --test viz_golden`.
+
+mod common;
+
+use common::golden::{assert_golden, diff_report};
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::{self, CustomFont, LegendMode, PlotKind, PlotOptions};
+
+fn points() -> Vec<DataPoint> {
+    let series = [
+        ("DEU", "DE", "Germany", 1.0),
+        ("FRA", "FR", "France", 1.4),
+        ("ITA", "IT", "Italy", 0.8),
+    ];
+    let mut out = Vec::new();
+    for (iso3, iso2, name, base) in series {
+        for year in 2010..2020 {
+            let t = (year - 2010) as f64;
+            out.push(DataPoint {
+                indicator_id: "NY.GDP.MKTP.CD".into(),
+                indicator_name: "GDP (current US$)".into(),
+                country_id: iso2.into(),
+                country_name: name.into(),
+                country_iso3: iso3.into(),
+                year,
+                value: Some(base * 1.0e12 * (1.0 + 0.03 * t) + 1.0e10 * (t * 1.7).sin()),
+                unit: None,
+                obs_status: None,
+                decimal: None,
+            });
+        }
+    }
+    out
+}
+
+fn options(kind: PlotKind) -> PlotOptions {
+    PlotOptions {
+        kind,
+        width: 800,
+        height: 480,
+        deterministic: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn line_chart_matches_golden() {
+    let svg = viz::render_svg_string(&points(), &options(PlotKind::Line)).unwrap();
+    assert_golden("line.svg", &svg);
+}
+
+#[test]
+fn grouped_bar_chart_with_patterns_matches_golden() {
+    let opts = PlotOptions {
+        pattern_fills: true,
+        legend: LegendMode::Bottom,
+        ..options(PlotKind::GroupedBar)
+    };
+    let svg = viz::render_svg_string(&points(), &opts).unwrap();
+    assert_golden("grouped_bar.svg", &svg);
+}
+
+#[test]
+fn loess_chart_with_inside_legend_matches_golden() {
+    let opts = PlotOptions {
+        legend: LegendMode::Inside,
+        locale: "de".into(),
+        ..options(PlotKind::Loess)
+    };
+    let svg = viz::render_svg_string(&points(), &opts).unwrap();
+    assert_golden("loess.svg", &svg);
+}
+
+#[test]
+fn deterministic_output_ignores_custom_fonts() {
+    let mut opts = options(PlotKind::Line);
+    opts.fonts.custom = Some(CustomFont {
+        family: "Host Font".into(),
+        path: "/nonexistent/host-font.ttf".into(),
+    });
+    let svg = viz::render_svg_string(&points(), &opts).unwrap();
+    assert!(!svg.contains("Host Font"));
+    assert_eq!(
+        svg,
+        viz::render_svg_string(&points(), &options(PlotKind::Line)).unwrap()
+    );
+}
+
+#[test]
+fn diff_report_lists_changed_lines() {
+    let report = diff_report("a\nb\nc\n", "a\nB\nc\nd\n");
+    assert!(report.contains("line 2:\n  - b\n  + B"), "{report}");
+    assert!(report.contains("line 4:\n  + d"), "{report}");
+    assert!(report.ends_with("2 of 4 lines differ (reference 3 lines, actual 4)"));
+}
@@ -0,0 +1,73 @@
+use wbi_rs::models::{DataPoint, GroupKey, Period};
+use wbi_rs::viz::group_series;
+
+fn dp(ind: &str, iso3: &str, year: i32, value: Option<f64>, unit: Option<&str>) -> DataPoint {
+    DataPoint {
+        indicator_id: ind.into(),
+        indicator_name: ind.into(),
+        country_id: iso3[..2].into(),
+        country_name: iso3.into(),
+        country_iso3: iso3.into(),
+        year,
+        value,
+        unit: unit.map(str::to_string),
+        obs_status: None,
+        decimal: None,
+    }
+}
+
+#[test]
+fn series_are_sorted_and_skip_gaps() {
+    let groups = group_series(&[
+        dp("X", "DEU", 2021, Some(2.0), None),
+        dp("X", "DEU", 2020, Some(1.0), None),
+        dp("X", "DEU", 2022, None, None),
+        dp("X", "DEU", 0, Some(9.0), None),
+        dp("X", "FRA", 2020, Some(3.0), None),
+        // Only missing values: no series at all.
+        dp("Y", "DEU", 2020, None, None),
+    ]);
+    let keys: Vec<_> = groups.keys().cloned().collect();
+    assert_eq!(
+        keys,
+        vec![GroupKey::new("X", "DEU"), GroupKey::new("X", "FRA")]
+    );
+    assert_eq!(
+        groups[&GroupKey::new("X", "DEU")],
+        vec![(Period::Year(2020), 1.0), (Period::Year(2021), 2.0)]
+    );
+}
+
+#[test]
+fn key_unit_is_the_first_non_empty_unit() {
+    let groups = group_series(&[
+        dp("X", "DEU", 2020, Some(1.0), Some(" ")),
+        dp("X", "DEU", 2021, Some(2.0), Some("current US$")),
+        dp("X", "DEU", 2022, Some(3.0), Some("other")),
+    ]);
+    let (key, series) = groups.into_iter().next().unwrap();
+    assert_eq!(key.unit.as_deref(), Some("current US$"));
+    assert_eq!(key.region, None);
+    assert_eq!(series.len(), 3);
+}
+
+#[test]
+fn periods_parse_and_order_chronologically() {
+    let mut periods: Vec<Period> = ["2021", "2020M12", "2020q4", "2020", "2020M01"]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    periods.sort();
+    let shown: Vec<String> = periods.iter().map(Period::to_string).collect();
+    assert_eq!(shown, ["2020", "2020M01", "2020Q4", "2020M12", "2021"]);
+    assert_eq!(Period::Month(2020, 7).as_f64(), 2020.5);
+
+    for bad in ["", "20x0", "2020Q5", "2020M13", "2020M", "2020W01"] {
+        assert!(bad.parse::<Period>().is_err(), "{bad:?} should not parse");
+    }
+    // Out-of-range variants built directly are clamped instead of overflowing.
+    assert_eq!(Period::Quarter(2020, 0).as_f64(), 2020.0);
+    assert_eq!(Period::Quarter(2020, 200).as_f64(), 2020.75);
+    assert!(Period::Month(2020, 0) < Period::Month(2020, 2));
+    assert!(Period::Month(2020, 255) > Period::Quarter(2020, 4));
+}
@@ -0,0 +1,64 @@
+use std::fs;
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::{self, LegendMode, MarkerDecimation, PlotKind, PlotOptions};
+
+fn dense_points() -> Vec<DataPoint> {
+    (1960..2020)
+        .map(|year| DataPoint {
+            indicator_id: "X".into(),
+            indicator_name: "Demo".into(),
+            country_id: "DE".into(),
+            country_name: "Germany".into(),
+            country_iso3: "DEU".into(),
+            year,
+            value: Some(year as f64),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect()
+}
+
+fn circles(kind: PlotKind, markers: MarkerDecimation, name: &str) -> usize {
+    let path = std::env::temp_dir().join(format!("wbd_markers_{name}.svg"));
+    let opts = PlotOptions {
+        kind,
+        markers,
+        width: 300,
+        height: 240,
+        legend: LegendMode::Inside,
+        ..Default::default()
+    };
+    viz::plot_with_options(&dense_points(), &path, &opts).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    // One extra circle is the inside legend's swatch.
+    svg.matches("<circle").count() - 1
+}
+
+#[test]
+fn off_draws_every_marker() {
+    assert_eq!(circles(PlotKind::Scatter, MarkerDecimation::Off, "off"), 60);
+}
+
+#[test]
+fn every_nth_keeps_first_and_last() {
+    // 0, 10, …, 50 and the last point (index 59).
+    assert_eq!(
+        circles(PlotKind::LinePoints, MarkerDecimation::EveryNth(10), "nth"),
+        7
+    );
+}
+
+#[test]
+fn auto_thins_overlapping_markers() {
+    let auto = circles(PlotKind::Scatter, MarkerDecimation::Auto, "auto");
+    let wide = circles(
+        PlotKind::Scatter,
+        MarkerDecimation::MinSpacingPx(40),
+        "wide",
+    );
+    assert!(auto < 60, "auto kept {auto} markers");
+    assert!(wide < auto, "40px spacing kept {wide}, auto {auto}");
+    assert!(wide >= 2);
+}
@@ -0,0 +1,475 @@
+use std::fs;
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::{self, LegendMode, PlotKind, PlotOptions};
+
+fn points() -> Vec<DataPoint> {
+    (2015..=2020)
+        .map(|year| DataPoint {
+            indicator_id: "X".into(),
+            indicator_name: "Demo (annual %)".into(),
+            country_id: "DE".into(),
+            country_name: "Germany".into(),
+            country_iso3: "DEU".into(),
+            year,
+            value: Some(10.0 + (year - 2015) as f64),
+            unit: None,
+            obs_status: None,
+            decimal: None,
+        })
+        .collect()
+}
+
+fn base() -> PlotOptions {
+    PlotOptions {
+        kind: PlotKind::LinePoints,
+        width: 320,
+        height: 200,
+        ..Default::default()
+    }
+}
+
+/// Decode a PNG into (width, height, color type, raw bytes).
+fn decode(png: &[u8]) -> (u32, u32, png::ColorType, Vec<u8>) {
+    let decoder = png::Decoder::new(png);
+    let mut reader = decoder.read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).unwrap();
+    buf.truncate(info.buffer_size());
+    (info.width, info.height, info.color_type, buf)
+}
+
+#[test]
+fn scale_multiplies_bitmap_pixels() {
+    let path = std::env::temp_dir().join("wbd_out_scale.png");
+    let opts = PlotOptions {
+        scale: 2.0,
+        ..base()
+    };
+    viz::plot_with_options(&points(), &path, &opts).unwrap();
+    let (w, h, _, _) = decode(&fs::read(&path).unwrap());
+    assert_eq!((w, h), (640, 400));
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn transparent_png_has_alpha_channel() {
+    let path = std::env::temp_dir().join("wbd_out_transparent.png");
+    let opts = PlotOptions {
+        transparent: true,
+        ..base()
+    };
+    viz::plot_with_options(&points(), &path, &opts).unwrap();
+    let (w, _, color, buf) = decode(&fs::read(&path).unwrap());
+    assert_eq!(color, png::ColorType::Rgba);
+    // Top-left corner is background: fully transparent.
+    assert_eq!(buf[3], 0);
+    // Something (text, axes, series) is fully opaque.
+    assert!(buf.chunks_exact(4).any(|px| px[3] == 255));
+    assert_eq!(buf.len(), (w * 200 * 4) as usize);
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn png_bytes_match_the_png_file() {
+    let path = std::env::temp_dir().join("wbd_out_bytes.png");
+    let opts = PlotOptions {
+        scale: 1.5,
+        ..base()
+    };
+    viz::plot_with_options(&points(), &path, &opts).unwrap();
+    let bytes = viz::render_png_bytes(&points(), &opts).unwrap();
+    let (w, h, color, pixels) = decode(&bytes);
+    assert_eq!((w, h, color), (480, 300, png::ColorType::Rgb));
+    assert_eq!(pixels, decode(&fs::read(&path).unwrap()).3);
+    fs::remove_file(&path).ok();
+
+    let transparent = PlotOptions {
+        transparent: true,
+        ..base()
+    };
+    let (_, _, color, _) = decode(&viz::render_png_bytes(&points(), &transparent).unwrap());
+    assert_eq!(color, png::ColorType::Rgba);
+    assert!(viz::render_png_bytes(&[], &opts).is_err());
+}
+
+#[test]
+fn transparent_svg_has_no_background_fill() {
+    let render = |transparent: bool| {
+        let path = std::env::temp_dir().join(format!("wbd_out_bg_{transparent}.svg"));
+        let opts = PlotOptions {
+            transparent,
+            ..base()
+        };
+        viz::plot_with_options(&points(), &path, &opts).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).ok();
+        svg
+    };
+    assert!(render(false).contains("fill=\"#FFFFFF\""));
+    assert!(!render(true).contains("fill=\"#FFFFFF\""));
+}
+
+#[test]
+fn chart_wording_follows_locale() {
+    let large: Vec<DataPoint> = points()
+        .into_iter()
+        .map(|p| DataPoint {
+            indicator_name: "Demo".into(),
+            value: p.value.map(|v| v * 1e6),
+            ..p
+        })
+        .collect();
+    let render = |locale: &str| {
+        let path = std::env::temp_dir().join(format!("wbd_out_locale_{locale}.svg"));
+        let opts = PlotOptions {
+            locale: locale.into(),
+            width: 640,
+            height: 480,
+            ..base()
+        };
+        viz::plot_with_options(&large, &path, &opts).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).ok();
+        svg
+    };
+    let english = render("en");
+    assert!(english.contains("Year") && english.contains("Value (millions)"));
+    let german = render("de");
+    assert!(german.contains("Jahr") && german.contains("Wert (Millionen)"));
+    assert!(!german.contains("Year"));
+    assert!(render("fr").contains("Année"));
+    assert!(render("es").contains("Valor (millones)"));
+}
+
+#[test]
+fn abbreviated_ticks_replace_the_scale_word() {
+    let large: Vec<DataPoint> = points()
+        .into_iter()
+        .map(|p| DataPoint {
+            indicator_name: "Demo".into(),
+            value: p.value.map(|v| v * 1e8),
+            ..p
+        })
+        .collect();
+    let render = |points: &[DataPoint], locale: &str| {
+        let opts = PlotOptions {
+            locale: locale.into(),
+            tick_abbreviation: true,
+            ..base()
+        };
+        viz::render_svg_string(points, &opts).unwrap()
+    };
+    let english = render(&large, "en");
+    assert!(english.contains("\nValue\n"), "no scale word in the title");
+    assert!(english.contains("\n1B\n") && english.contains("\n1.5B\n"));
+    let german = render(&large, "de");
+    assert!(german.contains("\n1,2 Mrd.\n"));
+
+    // Percentages keep plain ticks.
+    let plain = viz::render_svg_string(&points(), &PlotOptions { ..base() }).unwrap();
+    assert_eq!(render(&points(), "en"), plain);
+
+    let pinned = PlotOptions {
+        tick_abbreviation: true,
+        y_scale: viz::AxisScale::Millions,
+        ..base()
+    };
+    assert!(pinned.validate().is_err());
+}
+
+#[test]
+fn percent_axes_can_span_0_to_100_and_carry_a_percent_sign() {
+    use viz::PercentBounds;
+    let render = |points: &[DataPoint], percent_bounds, percent_ticks| {
+        let opts = PlotOptions {
+            percent_bounds,
+            percent_ticks,
+            ..base()
+        };
+        viz::render_svg_string(points, &opts).unwrap()
+    };
+    let tick = |svg: &str, label: &str| svg.contains(&format!("\n{label}\n"));
+
+    // Data 10–15 %: ticks follow the data unless asked for the full range.
+    let data = render(&points(), PercentBounds::Data, false);
+    assert!(tick(&data, "10.0") && !tick(&data, "100"));
+    let full = render(&points(), PercentBounds::Full, true);
+    assert!(tick(&full, "0%") && tick(&full, "20%") && tick(&full, "100%"));
+    assert!(tick(&render(&points(), PercentBounds::Data, true), "12%"));
+    // Padding widens the range, but never past 0 or 100 when the data stays inside.
+    assert_ne!(render(&points(), PercentBounds::Padded(0.1), false), data);
+    let wide: Vec<DataPoint> = points()
+        .into_iter()
+        .enumerate()
+        .map(|(i, p)| DataPoint {
+            value: Some(if i % 2 == 0 { 2.0 } else { 98.0 }),
+            ..p
+        })
+        .collect();
+    let padded = render(&wide, PercentBounds::Padded(0.5), false);
+    assert_eq!(padded, render(&wide, PercentBounds::Full, false));
+
+    // Other units keep their plain, data-driven axis.
+    let counts: Vec<DataPoint> = points()
+        .into_iter()
+        .map(|p| DataPoint {
+            indicator_name: "Demo (people)".into(),
+            ..p
+        })
+        .collect();
+    assert_eq!(
+        render(&counts, PercentBounds::Full, true),
+        render(&counts, PercentBounds::Data, false)
+    );
+    let bad = PlotOptions {
+        percent_bounds: PercentBounds::Padded(-0.1),
+        ..base()
+    };
+    assert!(bad.validate().is_err());
+}
+
+#[test]
+fn legend_renders_on_its_own_and_leaves_the_chart() {
+    let mut rows = points();
+    rows.extend(points().into_iter().map(|p| DataPoint {
+        country_id: "FR".into(),
+        country_name: "France".into(),
+        country_iso3: "FRA".into(),
+        value: p.value.map(|v| v * 2.0),
+        ..p
+    }));
+    let opts = PlotOptions {
+        legend: LegendMode::Hidden,
+        ..base()
+    };
+    let chart = viz::render_svg_string(&rows, &opts).unwrap();
+    assert!(!chart.contains("Germany") && !chart.contains("France"));
+
+    let dir = std::env::temp_dir();
+    let svg_path = dir.join("wbd_out_legend_only.svg");
+    viz::render_legend(&rows, &svg_path, &opts).unwrap();
+    let legend = fs::read_to_string(&svg_path).unwrap();
+    assert!(legend.contains("Germany") && legend.contains("France"));
+    assert!(legend.contains(r#"width="320""#));
+
+    let png_path = dir.join("wbd_out_legend_only.png");
+    let scaled = PlotOptions { scale: 2.0, ..opts };
+    viz::render_legend(&rows, &png_path, &scaled).unwrap();
+    let (w, h, _, _) = decode(&fs::read(&png_path).unwrap());
+    assert_eq!(w, 640);
+    assert!((80..400).contains(&h), "legend band is {h}px high");
+    fs::remove_file(&svg_path).ok();
+    fs::remove_file(&png_path).ok();
+}
+
+#[test]
+fn svg_string_matches_svg_file() {
+    let path = std::env::temp_dir().join("wbd_out_svg_string.svg");
+    viz::plot_with_options(&points(), &path, &base()).unwrap();
+    let file = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    assert_eq!(viz::render_svg_string(&points(), &base()).unwrap(), file);
+    assert!(viz::render_svg_string(&[], &base()).is_err());
+}
+
+#[test]
+fn embedded_data_round_trips_through_the_svg() {
+    let mut rows = points();
+    rows[0].country_name = "<Germany & co>".into();
+    let options = PlotOptions {
+        embed_data: true,
+        ..base()
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("embedded.svg");
+    viz::plot_with_options(&rows, &path, &options).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    assert!(svg.contains(r#"<script type="application/json" id="wbi-data">"#));
+    assert!(
+        !svg.contains("<Germany"),
+        "markup in values must be escaped"
+    );
+    assert_eq!(viz::embedded_data(&svg).unwrap(), rows);
+    assert_eq!(wbi_rs::storage::load(&path).unwrap(), rows);
+    // Inlined in an HTML page, as in an HTML report.
+    let html = dir.path().join("report.html");
+    fs::write(&html, format!("<html><body>{svg}</body></html>")).unwrap();
+    assert_eq!(wbi_rs::storage::load(&html).unwrap(), rows);
+
+    // Without the option, nothing is embedded.
+    let plain = viz::render_svg_string(&rows, &base()).unwrap();
+    assert!(viz::embedded_data(&plain).is_err());
+}
+
+#[test]
+fn branding_places_logo_and_watermark_in_the_corner() {
+    let dir = tempfile::tempdir().unwrap();
+    // A 4×2 solid red logo.
+    let logo = dir.path().join("logo.png");
+    let mut encoder = png::Encoder::new(fs::File::create(&logo).unwrap(), 4, 2);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&[255, 0, 0].repeat(8)).unwrap();
+    writer.finish().unwrap();
+
+    let branded = |logo: &std::path::Path| PlotOptions {
+        branding: viz::Branding {
+            logo: Some(logo.to_path_buf()),
+            logo_height: 20,
+            watermark: Some("Example Institute".into()),
+            opacity: 1.0,
+            ..Default::default()
+        },
+        ..base()
+    };
+    // Bottom right by default: the 40×20 logo ends 10 px from the edges, the text left of it.
+    let png = viz::render_png_bytes(&points(), &branded(&logo)).unwrap();
+    let (w, h, _, px) = decode(&png);
+    let at = |x: u32, y: u32| &px[((y * w + x) * 3) as usize..][..3];
+    assert_eq!(at(w - 11, h - 11), [255, 0, 0]);
+    assert_eq!(at(w - 50, h - 30), [255, 0, 0]);
+    assert_ne!(at(w - 51, h - 20), [255, 0, 0]);
+    // High-DPI output scales the logo with the chart.
+    let opts = PlotOptions {
+        scale: 2.0,
+        ..branded(&logo)
+    };
+    let (w2, h2, _, px2) = decode(&viz::render_png_bytes(&points(), &opts).unwrap());
+    assert_eq!(
+        &px2[(((h2 - 21) * w2 + w2 - 21) * 3) as usize..][..3],
+        [255, 0, 0]
+    );
+
+    let svg = viz::render_svg_string(&points(), &branded(&logo)).unwrap();
+    assert!(svg.contains(r#"<image x="270" y="170" width="40" height="20" opacity="1" href="data:image/png;base64,iVBORw0KGgo"#));
+    assert!(svg.contains("Example Institute"));
+
+    // SVG logos are nested into SVG charts only.
+    let svg_logo = dir.path().join("logo.svg");
+    fs::write(
+        &svg_logo,
+        r#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 5"><rect width="10" height="5" fill="blue"/></svg>"#,
+    )
+    .unwrap();
+    let svg = viz::render_svg_string(&points(), &branded(&svg_logo)).unwrap();
+    assert!(
+        svg.contains(
+            r#"<svg x="270" y="170" width="40" height="20" viewBox="0 0 10 5"><svg xmlns"#
+        )
+    );
+    assert!(svg.trim_end().ends_with("</svg>"));
+    let err = viz::render_png_bytes(&points(), &branded(&svg_logo)).unwrap_err();
+    assert!(err.to_string().contains("SVG logos"), "{err}");
+    assert!(viz::render_svg_string(&points(), &branded(&dir.path().join("missing.png"))).is_err());
+}
+
+#[test]
+fn transparent_requires_png() {
+    let path = std::env::temp_dir().join("wbd_out_transparent.bmp");
+    let opts = PlotOptions {
+        transparent: true,
+        ..base()
+    };
+    assert!(viz::plot_with_options(&points(), &path, &opts).is_err());
+    assert!(!path.exists());
+}
+
+#[test]
+fn validate_catches_bad_settings_without_data() {
+    assert!(base().validate().is_ok());
+    let bad = [
+        PlotOptions { width: 0, ..base() },
+        PlotOptions {
+            scale: f64::NAN,
+            ..base()
+        },
+        PlotOptions {
+            kind: PlotKind::Loess,
+            loess_span: 1.5,
+            ..base()
+        },
+        PlotOptions {
+            error_bars: Some(viz::ErrorBarOptions {
+                opacity: 2.0,
+                ..Default::default()
+            }),
+            ..base()
+        },
+        PlotOptions {
+            branding: viz::Branding {
+                opacity: -0.1,
+                ..Default::default()
+            },
+            ..base()
+        },
+    ];
+    for opts in bad {
+        assert!(opts.validate().is_err(), "{opts:?}");
+    }
+}
+
+#[test]
+fn ignored_settings_depend_on_the_kind() {
+    let opts = PlotOptions {
+        pattern_fills: true,
+        error_bars: Some(viz::ErrorBarOptions::default()),
+        ..base()
+    };
+    // base() is line-points: error bars apply, fill patterns do not.
+    assert_eq!(opts.ignored_settings(), ["pattern_fills"]);
+    let area = PlotOptions {
+        kind: PlotKind::Area,
+        ..opts
+    };
+    assert_eq!(area.ignored_settings(), ["error_bars"]);
+}
+
+#[test]
+fn y_break_cuts_the_gap_between_an_outlier_and_the_rest() {
+    let mut data = points();
+    data.extend(points().into_iter().map(|p| DataPoint {
+        country_iso3: "USA".into(),
+        country_name: "United States".into(),
+        value: p.value.map(|v| v * 20.0),
+        ..p
+    }));
+    let tick = |svg: &str, label: &str| svg.contains(&format!("\n{label}\n"));
+    let plain = viz::render_svg_string(&data, &base()).unwrap();
+    let opts = PlotOptions {
+        y_break: Some(viz::AxisBreak {
+            from: 20.0,
+            to: 190.0,
+        }),
+        ..base()
+    };
+    let broken = viz::render_svg_string(&data, &opts).unwrap();
+    assert_ne!(broken, plain);
+    // Ticks on both sides of the break, none inside it.
+    assert!(tick(&broken, "10.0") && tick(&broken, "15.0"));
+    assert!(tick(&broken, "200") && tick(&broken, "300"));
+    assert!(tick(&plain, "100") && !tick(&broken, "100"));
+
+    // A break outside the plotted range leaves the chart alone.
+    let outside = PlotOptions {
+        y_break: Some(viz::AxisBreak {
+            from: 400.0,
+            to: 500.0,
+        }),
+        ..base()
+    };
+    assert_eq!(viz::render_svg_string(&data, &outside).unwrap(), plain);
+    let backwards = PlotOptions {
+        y_break: Some(viz::AxisBreak {
+            from: 190.0,
+            to: 20.0,
+        }),
+        ..base()
+    };
+    assert!(backwards.validate().is_err());
+    let xy = PlotOptions {
+        kind: PlotKind::XYScatter,
+        ..opts
+    };
+    assert_eq!(xy.ignored_settings(), ["y_break"]);
+}
@@ -0,0 +1,90 @@
+use std::fs;
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::{self, LegendMode, PlotKind, PlotOptions};
+
+fn points() -> Vec<DataPoint> {
+    let mut out = Vec::new();
+    for (iso, name) in [("DEU", "Germany"), ("FRA", "France")] {
+        for year in 2015..=2020 {
+            out.push(DataPoint {
+                indicator_id: "X".into(),
+                indicator_name: "GDP growth (annual %)".into(),
+                country_id: iso[..2].into(),
+                country_name: name.into(),
+                country_iso3: iso.into(),
+                year,
+                value: Some(1.0 + (year - 2015) as f64),
+                unit: None,
+                obs_status: None,
+                decimal: None,
+            });
+        }
+    }
+    out
+}
+
+#[test]
+fn pdf_output_is_well_formed() {
+    let path = std::env::temp_dir().join("wbd_chart.pdf");
+    let opts = PlotOptions {
+        kind: PlotKind::LinePoints,
+        legend: LegendMode::Inside,
+        width: 800,
+        height: 500,
+        ..Default::default()
+    };
+    viz::plot_with_options(&points(), &path, &opts).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    fs::remove_file(&path).ok();
+
+    assert!(bytes.starts_with(b"%PDF-1.4"));
+    assert!(bytes.ends_with(b"%%EOF\n"));
+    let text = String::from_utf8_lossy(&bytes);
+    assert!(text.contains("/MediaBox [0 0 800 500]"));
+    // Title with escaped parentheses, drawn with the standard Helvetica font.
+    assert!(text.contains("(GDP growth \\(annual %\\)) Tj"));
+    assert!(text.contains("/BaseFont /Helvetica"));
+
+    // startxref must point at the xref table, and each entry at its object.
+    let startxref: usize = text
+        .rsplit("startxref\n")
+        .next()
+        .and_then(|s| s.lines().next())
+        .and_then(|s| s.parse().ok())
+        .unwrap();
+    assert!(bytes[startxref..].starts_with(b"xref"));
+    let table = String::from_utf8_lossy(&bytes[startxref..]);
+    for (i, line) in table.lines().skip(3).take(6).enumerate() {
+        let off: usize = line[..10].parse().unwrap();
+        let header = format!("{} 0 obj", i + 1);
+        assert!(
+            bytes[off..].starts_with(header.as_bytes()),
+            "xref entry {} is off",
+            i + 1
+        );
+    }
+}
+
+#[test]
+fn pdf_supports_all_series_kinds() {
+    for (i, kind) in [
+        PlotKind::Line,
+        PlotKind::Scatter,
+        PlotKind::Area,
+        PlotKind::StackedArea,
+        PlotKind::GroupedBar,
+        PlotKind::Loess,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let path = std::env::temp_dir().join(format!("wbd_chart_kind{i}.pdf"));
+        let opts = PlotOptions {
+            kind,
+            ..Default::default()
+        };
+        viz::plot_with_options(&points(), &path, &opts).unwrap();
+        assert!(fs::read(&path).unwrap().starts_with(b"%PDF"));
+        fs::remove_file(&path).ok();
+    }
+}
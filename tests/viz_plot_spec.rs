@@ -0,0 +1,83 @@
+use wbi_rs::viz::style::{LineDash, Rgba, StyleConfig};
+use wbi_rs::viz::{
+    AxisScale, FontOptions, LegendMode, MarkerDecimation, PlotKind, PlotOptions, PlotTransform,
+};
+
+fn spec() -> PlotOptions {
+    let mut style = StyleConfig {
+        line_width: Some(3),
+        ..Default::default()
+    };
+    style.palette.insert("DEU".into(), Rgba::new(0, 0, 0, 128));
+    style
+        .palette
+        .insert("FRA".into(), Rgba::rgb(0x1f, 0x77, 0xb4));
+    style.dashes.insert("FRA".into(), LineDash::DashDot);
+    PlotOptions {
+        kind: PlotKind::XYScatter,
+        legend: LegendMode::Right,
+        title: "Growth".into(),
+        style_config: style,
+        transform: PlotTransform::CountryRatio("DEU".into(), "FRA".into()),
+        markers: MarkerDecimation::EveryNth(3),
+        y_scale: AxisScale::Custom {
+            factor: 1e9,
+            word: "bn".into(),
+        },
+        fonts: FontOptions {
+            title_px: 30,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn chart_spec_round_trips_through_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chart.json");
+    spec().save(&path).unwrap();
+    let json = std::fs::read_to_string(&path).unwrap();
+    assert!(json.contains(r#""kind": "xy-scatter""#), "{json}");
+    assert!(json.contains(r##""DEU": "#00000080""##), "{json}");
+    assert!(
+        json.find(r#""DEU""#) < json.find(r#""FRA""#),
+        "map keys are sorted"
+    );
+    assert_eq!(PlotOptions::load(&path).unwrap(), spec());
+}
+
+#[test]
+fn partial_spec_keeps_defaults_and_rejects_unknown_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chart.json");
+    std::fs::write(&path, r#"{"kind": "fan-chart", "fonts": {"tick_px": 10}}"#).unwrap();
+    let opts = PlotOptions::load(&path).unwrap();
+    assert_eq!(opts.kind, PlotKind::FanChart);
+    assert_eq!((opts.fonts.tick_px, opts.fonts.title_px), (10, 24));
+    assert_eq!(opts.width, PlotOptions::default().width);
+
+    std::fs::write(&path, r#"{"colour": "red"}"#).unwrap();
+    let err = PlotOptions::load(&path).unwrap_err();
+    assert!(format!("{err:#}").contains("colour"), "{err:#}");
+}
+
+#[test]
+fn overlay_applies_only_changed_settings() {
+    let flags = PlotOptions {
+        width: 800,
+        transform: PlotTransform::IndexToYear(2010),
+        fonts: FontOptions {
+            tick_px: 9,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let merged = spec().overlay(&flags).unwrap();
+    assert_eq!(merged.width, 800);
+    assert_eq!(merged.kind, PlotKind::XYScatter);
+    assert_eq!(merged.transform, PlotTransform::IndexToYear(2010));
+    assert_eq!((merged.fonts.tick_px, merged.fonts.title_px), (9, 30));
+    assert_eq!(merged.style_config, spec().style_config);
+    assert_eq!(merged.markers, MarkerDecimation::EveryNth(3));
+}
@@ -86,6 +86,7 @@ fn legend_modes_produce_files() {
         LegendMode::Right,
         LegendMode::Top,
         LegendMode::Bottom,
+        LegendMode::Direct,
     ];
     for (i, mode) in modes.iter().enumerate() {
         write_and_check(
@@ -127,3 +128,117 @@ fn empty_points_is_error() {
     );
     assert!(e.is_err());
 }
+
+#[test]
+fn long_title_wraps_within_canvas() {
+    let path = std::env::temp_dir().join("wbd_long_title.svg");
+    let title = "Access to electricity, rural population living in remote areas and small \
+                 settlements, compared across several decades of household surveys";
+    let opts = viz::PlotOptions {
+        width: 500,
+        height: 400,
+        title: title.into(),
+        ..Default::default()
+    };
+    viz::plot_with_options(&sample_points(), &path, &opts).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+
+    // Two title lines, the second truncated; the full title never appears on one line.
+    assert!(svg.contains("\nAccess to electricity, rural population living\n"));
+    assert!(svg.contains("…\n"));
+    assert!(!svg.contains(title));
+}
+
+#[test]
+fn text_width_follows_glyph_metrics() {
+    use wbi_rs::viz::text::estimate_text_width_px;
+    let narrow = estimate_text_width_px("iiiiiiii", 14);
+    let wide = estimate_text_width_px("WWWWWWWW", 14);
+    assert!(narrow * 2 < wide, "narrow {narrow}px vs wide {wide}px");
+    assert!(estimate_text_width_px("Germany", 28) > estimate_text_width_px("Germany", 14));
+    assert_eq!(estimate_text_width_px("", 14), 0);
+}
+
+#[test]
+fn right_legend_keeps_long_labels_whole() {
+    let mut points = sample_points();
+    let long = "United States of America, including outlying territories";
+    for p in points.iter_mut().filter(|p| p.country_iso3 == "USA") {
+        p.country_name = long.into();
+    }
+    let opts = viz::PlotOptions {
+        legend: LegendMode::Right,
+        ..Default::default()
+    };
+    let svg = viz::render_svg_string(&points, &opts).unwrap();
+    // Wrapped onto lines inside the panel, but never cut off.
+    for word in long.split_whitespace() {
+        assert!(svg.contains(word.trim_end_matches(',')), "missing {word:?}");
+    }
+    assert!(!svg.contains('…'));
+}
+
+#[test]
+fn right_legend_is_only_as_wide_as_its_labels() {
+    let opts = viz::PlotOptions {
+        legend: LegendMode::Right,
+        ..Default::default()
+    };
+    let svg = viz::render_svg_string(&sample_points(), &opts).unwrap();
+    // Labels start 30px into the panel (swatch column); a 15% panel would start at x=850.
+    let label = svg.find("\nGermany\n").unwrap();
+    let tag = &svg[svg[..label].rfind("<text").unwrap()..label];
+    let x: i32 = tag
+        .split("x=\"")
+        .nth(1)
+        .and_then(|s| s.split('"').next())
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(
+        x - 30 > opts.width as i32 * 85 / 100,
+        "legend text at x={x}"
+    );
+}
+
+#[test]
+fn direct_labels_sit_at_the_line_ends_without_overlapping() {
+    // Both lines end close together at 3.0 and 3.05.
+    let mut points = sample_points();
+    for p in points.iter_mut().filter(|p| p.country_iso3 == "USA") {
+        p.value = p.value.map(|v| v - 0.45);
+    }
+    let opts = viz::PlotOptions {
+        legend: LegendMode::Direct,
+        ..Default::default()
+    };
+    let svg = viz::render_svg_string(&points, &opts).unwrap();
+    let label_y = |name: &str| -> i32 {
+        let at = svg.find(&format!("\n{name}\n")).unwrap();
+        let tag = &svg[svg[..at].rfind("<text").unwrap()..at];
+        tag.split(" y=\"")
+            .nth(1)
+            .unwrap()
+            .split('"')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+    let (us, de) = (label_y("United States"), label_y("Germany"));
+    assert!(de - us >= 14, "labels at y={us} and y={de}");
+    // No legend swatches on a line chart.
+    assert!(!svg.contains("<circle"));
+
+    // Kinds without line ends get a right legend instead.
+    let bars = |legend| viz::PlotOptions {
+        kind: PlotKind::GroupedBar,
+        legend,
+        ..Default::default()
+    };
+    assert_eq!(
+        viz::render_svg_string(&points, &bars(LegendMode::Direct)).unwrap(),
+        viz::render_svg_string(&points, &bars(LegendMode::Right)).unwrap()
+    );
+}
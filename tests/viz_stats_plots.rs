@@ -0,0 +1,81 @@
+use std::fs;
+use wbi_rs::models::DataPoint;
+use wbi_rs::stats::{self, CorrelationMatrix};
+use wbi_rs::viz::{self, PlotOptions};
+
+fn dp(ind: &str, iso: &str, year: i32, v: f64) -> DataPoint {
+    DataPoint {
+        indicator_id: ind.into(),
+        indicator_name: ind.into(),
+        country_id: iso[..2].into(),
+        country_name: iso.into(),
+        country_iso3: iso.into(),
+        year,
+        value: Some(v),
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    }
+}
+
+fn points() -> Vec<DataPoint> {
+    let mut out = Vec::new();
+    for (c, iso) in ["DEU", "FRA", "USA"].iter().enumerate() {
+        for year in 2010..2016 {
+            let t = (year - 2010) as f64;
+            out.push(dp("UP", iso, year, 10.0 * c as f64 + t));
+            out.push(dp("DOWN", iso, year, 100.0 - 2.0 * (10.0 * c as f64 + t)));
+        }
+    }
+    out
+}
+
+#[test]
+fn correlation_matrix_pairs_on_country_and_year() {
+    let mut pts = points();
+    pts.push(dp("SPARSE", "DEU", 2010, 1.0));
+    let m = stats::correlation_matrix(&pts);
+    assert_eq!(m.indicators, ["DOWN", "SPARSE", "UP"]);
+    assert!((m.values[0][2].unwrap() + 1.0).abs() < 1e-9);
+    assert_eq!(m.counts[0][2], 18);
+    // A single paired observation is not enough for a correlation.
+    assert_eq!(m.values[1][2], None);
+    assert_eq!(m.counts[1][2], 1);
+}
+
+#[test]
+fn plot_correlation_writes_heatmap() {
+    let path = std::env::temp_dir().join("wbd_corr.svg");
+    let m = stats::correlation_matrix(&points());
+    viz::plot_correlation(&m, &path, &PlotOptions::default()).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    assert!(svg.contains("-1.00"));
+    assert!(svg.contains("Pearson r"));
+}
+
+#[test]
+fn plot_correlation_rejects_ragged_matrix() {
+    let path = std::env::temp_dir().join("wbd_corr_bad.svg");
+    fs::remove_file(&path).ok();
+    let m = CorrelationMatrix {
+        indicators: vec!["A".into(), "B".into()],
+        values: vec![vec![Some(1.0)]],
+        counts: vec![vec![1]],
+    };
+    assert!(viz::plot_correlation(&m, &path, &PlotOptions::default()).is_err());
+    assert!(!path.exists());
+}
+
+#[test]
+fn plot_summary_draws_one_labelled_bar_per_group() {
+    let path = std::env::temp_dir().join("wbd_summary.svg");
+    let summaries = stats::grouped_summary(&points());
+    viz::plot_summary(&summaries, &path, &PlotOptions::default()).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    for iso in ["DEU", "FRA", "USA"] {
+        assert_eq!(svg.matches(&format!("\n{iso}\n")).count(), 2, "{iso}");
+    }
+    assert!(svg.contains("UP") && svg.contains("DOWN"));
+}
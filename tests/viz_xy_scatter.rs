@@ -0,0 +1,133 @@
+use std::fs;
+use wbi_rs::models::DataPoint;
+use wbi_rs::viz::{self, LegendMode, PlotKind, PlotOptions, XYScatterOptions};
+
+fn dp(ind: &str, name: &str, iso: &str, year: i32, v: f64) -> DataPoint {
+    DataPoint {
+        indicator_id: ind.into(),
+        indicator_name: name.into(),
+        country_id: iso[..2].into(),
+        country_name: format!("Country {iso}"),
+        country_iso3: iso.into(),
+        year,
+        value: Some(v),
+        unit: None,
+        obs_status: None,
+        decimal: None,
+    }
+}
+
+fn gapminder_points() -> Vec<DataPoint> {
+    let mut out = Vec::new();
+    for (i, iso) in ["AAA", "BBB", "CCC"].iter().enumerate() {
+        for year in 2000..=2004 {
+            let t = (year - 2000) as f64;
+            out.push(dp(
+                "GDPPC",
+                "GDP per capita (current US$)",
+                iso,
+                year,
+                1000.0 * (i as f64 + 1.0) + 100.0 * t,
+            ));
+            out.push(dp(
+                "LIFE",
+                "Life expectancy (years)",
+                iso,
+                year,
+                60.0 + i as f64 * 5.0 + t,
+            ));
+            out.push(dp(
+                "POP",
+                "Population, total",
+                iso,
+                year,
+                1.0e6 * (i as f64 + 1.0),
+            ));
+        }
+    }
+    out
+}
+
+fn render(opts: &PlotOptions, name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("wbd_xy_{name}.svg"));
+    viz::plot_with_options(&gapminder_points(), &path, opts).unwrap();
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    svg
+}
+
+#[test]
+fn single_year_with_size_indicator_renders_axis_titles() {
+    let opts = PlotOptions {
+        kind: PlotKind::XYScatter,
+        legend: LegendMode::Right,
+        xy: XYScatterOptions {
+            x_indicator: Some("GDPPC".into()),
+            y_indicator: Some("LIFE".into()),
+            size_indicator: Some("POP".into()),
+            year: Some(2002),
+        },
+        ..Default::default()
+    };
+    let svg = render(&opts, "year");
+    assert!(svg.contains("GDP per capita"), "x-axis title missing");
+    assert!(svg.contains("Life expectancy"), "y-axis title missing");
+    assert!(
+        svg.contains("(2002)"),
+        "derived caption should mention the year"
+    );
+}
+
+#[test]
+fn trails_without_year_render_for_all_legend_modes() {
+    for (i, legend) in [LegendMode::Inside, LegendMode::Bottom]
+        .into_iter()
+        .enumerate()
+    {
+        let opts = PlotOptions {
+            kind: PlotKind::XYScatter,
+            legend,
+            xy: XYScatterOptions {
+                x_indicator: Some("GDPPC".into()),
+                y_indicator: Some("LIFE".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let svg = render(&opts, &format!("trail{i}"));
+        assert!(svg.contains("Country BBB"));
+    }
+}
+
+#[test]
+fn single_indicator_is_error() {
+    let points: Vec<DataPoint> = gapminder_points()
+        .into_iter()
+        .filter(|p| p.indicator_id == "LIFE")
+        .collect();
+    let path = std::env::temp_dir().join("wbd_xy_single.svg");
+    let opts = PlotOptions {
+        kind: PlotKind::XYScatter,
+        ..Default::default()
+    };
+    let err = viz::plot_with_options(&points, &path, &opts).unwrap_err();
+    assert!(format!("{err}").contains("two indicators"));
+    assert!(
+        !path.exists(),
+        "no file should be written on validation error"
+    );
+}
+
+#[test]
+fn unknown_size_indicator_is_error() {
+    let path = std::env::temp_dir().join("wbd_xy_unknown.svg");
+    let opts = PlotOptions {
+        kind: PlotKind::XYScatter,
+        xy: XYScatterOptions {
+            size_indicator: Some("NOPE".into()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert!(viz::plot_with_options(&gapminder_points(), &path, &opts).is_err());
+}